@@ -1,6 +1,13 @@
-use candle_core::Tensor;
+use candle_core::{Module, Result, Tensor};
+use candle_nn::{ops, LayerNorm, LayerNormConfig};
 
+pub mod estimators;
+pub mod export;
 pub mod fou;
+pub mod hedging;
+pub mod pricing;
+pub mod simulation;
+pub mod training;
 pub mod utils;
 pub mod volatility;
 
@@ -11,3 +18,14 @@ pub struct DataSet {
   pub y_train: Tensor,
   pub y_test: Tensor,
 }
+
+/// Layer normalization by tensor ops, the fused kernel of [`LayerNorm::forward`] has no
+/// f64 version on the CPU and no backward pass
+pub fn apply_layer_norm(ln: &LayerNorm, xs: &Tensor) -> Result<Tensor> {
+  match ln.bias() {
+    Some(bias) => {
+      ops::layer_norm_slow(xs, ln.weight(), bias, LayerNormConfig::default().eps as f32)
+    }
+    None => ln.forward(xs),
+  }
+}
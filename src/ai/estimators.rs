@@ -0,0 +1,116 @@
+//! Neural estimators of the parameters of mean-reverting models from sampled paths.
+//!
+//! dX(t) = theta (mu - X(t)) dt + sigma X(t)^gamma dW^H(t)
+//!
+//! with gamma = 0 for the (fractional) Ornstein-Uhlenbeck and gamma = 1/2 for the
+//! (fractional) Cox-Ingersoll-Ross process. [`dataset::SdeDataset`] simulates labelled
+//! paths on the fly and [`SequenceEstimator`] regresses (H, theta, sigma, mu) jointly,
+//! optionally as a mixture density ([`mixture`]) to quantify the uncertainty.
+
+pub mod dataset;
+pub mod mixture;
+pub mod sequence;
+
+pub use sequence::SequenceEstimator;
+
+/// Parameters of a mean-reverting model, H = 1/2 for the Brownian models
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SdeParams {
+  /// Hurst index of the driving noise
+  pub hurst: f64,
+  /// Speed of mean reversion
+  pub theta: f64,
+  /// Volatility
+  pub sigma: f64,
+  /// Long-run mean
+  pub mu: f64,
+}
+
+impl SdeParams {
+  /// Number of estimated parameters
+  pub const LEN: usize = 4;
+
+  /// Parameters in the order (H, theta, sigma, mu)
+  pub fn to_array(self) -> [f64; Self::LEN] {
+    [self.hurst, self.theta, self.sigma, self.mu]
+  }
+
+  /// Parameters from the order (H, theta, sigma, mu)
+  pub fn from_array([hurst, theta, sigma, mu]: [f64; Self::LEN]) -> Self {
+    Self {
+      hurst,
+      theta,
+      sigma,
+      mu,
+    }
+  }
+}
+
+/// Ranges of the simulated parameters, the targets of the networks are scaled to [0, 1]
+/// with them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParamRanges {
+  pub hurst: (f64, f64),
+  pub theta: (f64, f64),
+  pub sigma: (f64, f64),
+  /// Positive for the Cox-Ingersoll-Ross models
+  pub mu: (f64, f64),
+}
+
+impl Default for ParamRanges {
+  fn default() -> Self {
+    Self {
+      hurst: (0.05, 0.95),
+      theta: (0.5, 10.0),
+      sigma: (0.1, 2.0),
+      mu: (0.5, 5.0),
+    }
+  }
+}
+
+impl ParamRanges {
+  fn bounds(&self) -> [(f64, f64); SdeParams::LEN] {
+    [self.hurst, self.theta, self.sigma, self.mu]
+  }
+
+  /// Parameters mapped linearly from the ranges to [0, 1]
+  pub fn scale(&self, params: SdeParams) -> [f64; SdeParams::LEN] {
+    let mut scaled = params.to_array();
+    for (x, (lo, hi)) in scaled.iter_mut().zip(self.bounds()) {
+      *x = (*x - lo) / (hi - lo);
+    }
+    scaled
+  }
+
+  /// Inverse of [`ParamRanges::scale`]
+  pub fn unscale(&self, mut scaled: [f64; SdeParams::LEN]) -> SdeParams {
+    for (x, (lo, hi)) in scaled.iter_mut().zip(self.bounds()) {
+      *x = lo + *x * (hi - lo);
+    }
+    SdeParams::from_array(scaled)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn param_ranges_scale_roundtrip() {
+    let ranges = ParamRanges::default();
+    let params = SdeParams {
+      hurst: 0.3,
+      theta: 2.0,
+      sigma: 0.5,
+      mu: 1.5,
+    };
+
+    let scaled = ranges.scale(params);
+    assert!(scaled.iter().all(|x| (0.0..=1.0).contains(x)));
+
+    let unscaled = ranges.unscale(scaled).to_array();
+    for (a, b) in unscaled.iter().zip(params.to_array()) {
+      assert!((a - b).abs() < 1e-12);
+    }
+  }
+}
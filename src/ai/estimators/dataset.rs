@@ -0,0 +1,241 @@
+use std::{
+  sync::mpsc::{sync_channel, Receiver},
+  thread,
+};
+
+use anyhow::{ensure, Result};
+use ndarray::{Array1, Array2, Axis};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use rayon::prelude::*;
+
+use super::{ParamRanges, SdeParams};
+use crate::stochastic::{
+  diffusion::{
+    cir::{DiscretizationScheme, CIR},
+    fcir::FCIR,
+    fou::FOU,
+    ou::OU,
+  },
+  noise::fgn::FGN,
+  Sampling,
+};
+
+/// Mean-reverting models the estimators are trained on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeanRevertingModel {
+  /// Ornstein-Uhlenbeck
+  OU,
+  /// Fractional Ornstein-Uhlenbeck
+  FOU,
+  /// Cox-Ingersoll-Ross
+  CIR,
+  /// Fractional Cox-Ingersoll-Ross
+  FCIR,
+}
+
+impl MeanRevertingModel {
+  /// Whether the model is driven by fractional Gaussian noise
+  pub fn is_fractional(self) -> bool {
+    matches!(self, MeanRevertingModel::FOU | MeanRevertingModel::FCIR)
+  }
+
+  /// Path of n points on [0, t] started at the long-run mean, simulated in single
+  /// precision, the dtype of the estimators
+  pub fn sample(self, params: SdeParams, n: usize, t: f64) -> Array1<f32> {
+    let [hurst, theta, sigma, mu] = params.to_array().map(|x| x as f32);
+    let t = t as f32;
+    let fgn = || FGN::new(hurst, n - 1, Some(t), None);
+
+    match self {
+      MeanRevertingModel::OU => {
+        OU::new(mu, sigma, theta, n, Some(mu), Some(t), None, None).sample()
+      }
+      MeanRevertingModel::FOU => {
+        FOU::new(theta, mu, sigma, n, Some(mu), Some(t), None, None, fgn()).sample()
+      }
+      MeanRevertingModel::CIR => CIR::new(
+        theta,
+        mu,
+        sigma,
+        n,
+        Some(mu),
+        Some(t),
+        None,
+        Some(DiscretizationScheme::Exact),
+        None,
+      )
+      .sample(),
+      MeanRevertingModel::FCIR => FCIR::new(
+        theta,
+        mu,
+        sigma,
+        n,
+        Some(mu),
+        Some(t),
+        None,
+        None,
+        None,
+        fgn(),
+      )
+      .sample(),
+    }
+  }
+
+  /// Random parameters in the ranges, H = 1/2 for the Brownian models and sigma capped
+  /// by the Feller condition 2 theta mu >= sigma^2 for the fractional CIR
+  fn sample_params<R: Rng>(self, ranges: &ParamRanges, rng: &mut R) -> SdeParams {
+    let mut params = SdeParams {
+      hurst: rng.gen_range(ranges.hurst.0..=ranges.hurst.1),
+      theta: rng.gen_range(ranges.theta.0..=ranges.theta.1),
+      sigma: rng.gen_range(ranges.sigma.0..=ranges.sigma.1),
+      mu: rng.gen_range(ranges.mu.0..=ranges.mu.1),
+    };
+
+    if !self.is_fractional() {
+      params.hurst = 0.5;
+    }
+    if self == MeanRevertingModel::FCIR {
+      params.sigma = params.sigma.min((2.0 * params.theta * params.mu).sqrt());
+    }
+
+    params
+  }
+}
+
+/// Labelled paths of an epoch
+#[derive(Debug, Clone)]
+pub struct Epoch {
+  /// One path per row
+  pub paths: Array2<f32>,
+  /// Parameters of the paths
+  pub params: Vec<SdeParams>,
+  /// Model of every path
+  pub models: Vec<MeanRevertingModel>,
+}
+
+/// Paths of mean-reverting models simulated on the fly
+///
+/// Like [`FouDataset`](crate::ai::fou::fou_lstm_datasets::FouDataset) a producer thread
+/// simulates the next epoch on the rayon thread pool while the current one is trained on.
+/// Every path is drawn from a model picked uniformly from `models` with parameters drawn
+/// uniformly from the ranges.
+pub struct SdeDataset {
+  receiver: Receiver<Epoch>,
+  pub models: Vec<MeanRevertingModel>,
+  pub ranges: ParamRanges,
+  /// Number of points of a path
+  pub n: usize,
+  /// Time horizon of a path
+  pub t: f64,
+}
+
+impl SdeDataset {
+  /// Start simulating epochs of `epoch_size` paths of n points on [0, t]
+  pub fn spawn(
+    models: Vec<MeanRevertingModel>,
+    ranges: ParamRanges,
+    epoch_size: usize,
+    n: usize,
+    t: f64,
+  ) -> Result<Self> {
+    ensure!(!models.is_empty(), "at least one model is needed");
+    ensure!(n >= 2, "n must be at least 2");
+    ensure!(
+      ranges.hurst.0 > 0.0 && ranges.hurst.1 < 1.0,
+      "the Hurst index must be in (0, 1)"
+    );
+    ensure!(
+      ranges.theta.0 > 0.0 && ranges.sigma.0 > 0.0,
+      "theta and sigma must be positive"
+    );
+    ensure!(
+      models
+        .iter()
+        .all(|model| matches!(model, MeanRevertingModel::OU | MeanRevertingModel::FOU))
+        || ranges.mu.0 > 0.0,
+      "mu must be positive for the Cox-Ingersoll-Ross models"
+    );
+
+    let (sender, receiver) = sync_channel(1);
+    let producer = models.clone();
+    thread::spawn(move || {
+      while sender
+        .send(Self::simulate(&producer, &ranges, epoch_size, n, t))
+        .is_ok()
+      {}
+    });
+
+    Ok(Self {
+      receiver,
+      models,
+      ranges,
+      n,
+      t,
+    })
+  }
+
+  /// Simulate an epoch in parallel on the calling thread, e.g. a fixed validation set
+  pub fn simulate(
+    models: &[MeanRevertingModel],
+    ranges: &ParamRanges,
+    epoch_size: usize,
+    n: usize,
+    t: f64,
+  ) -> Epoch {
+    let mut rng = thread_rng();
+    let (models, params): (Vec<_>, Vec<_>) = (0..epoch_size)
+      .map(|_| {
+        let model = *models.choose(&mut rng).unwrap();
+        (model, model.sample_params(ranges, &mut rng))
+      })
+      .unzip();
+
+    let mut paths = Array2::zeros((epoch_size, n));
+    paths
+      .axis_iter_mut(Axis(0))
+      .into_par_iter()
+      .zip(models.par_iter().zip(params.par_iter()))
+      .for_each(|(mut path, (model, params))| path.assign(&model.sample(*params, n, t)));
+
+    Epoch {
+      paths,
+      params,
+      models,
+    }
+  }
+
+  /// Next epoch, blocks until it is simulated
+  pub fn next_epoch(&self) -> Result<Epoch> {
+    Ok(self.receiver.recv()?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dataset_labels_in_ranges() {
+    let ranges = ParamRanges::default();
+    let models = [MeanRevertingModel::OU, MeanRevertingModel::FCIR];
+    let epoch = SdeDataset::simulate(&models, &ranges, 64, 33, 4.0);
+
+    assert_eq!(epoch.paths.dim(), (64, 33));
+    for ((params, model), path) in epoch
+      .params
+      .iter()
+      .zip(&epoch.models)
+      .zip(epoch.paths.rows())
+    {
+      assert_eq!(path[0], params.mu as f32);
+      assert!(ranges
+        .scale(*params)
+        .iter()
+        .all(|x| (0.0..=1.0).contains(x)));
+      match model {
+        MeanRevertingModel::OU => assert_eq!(params.hurst, 0.5),
+        _ => assert!(2.0 * params.theta * params.mu >= params.sigma.powi(2) - 1e-12),
+      }
+    }
+  }
+}
@@ -0,0 +1,218 @@
+//! Mixture density output of the estimators.
+//!
+//! Every parameter y_j, scaled to [0, 1], gets an independent one dimensional Gaussian
+//! mixture
+//!
+//! p(y_j | x) = sum_k pi_jk N(y_j; m_jk, s_jk^2)
+//!
+//! whose weights, means and standard deviations are the outputs of the network. The
+//! networks are trained on the negative log-likelihood of the targets, so the mixture
+//! variance estimates the posterior variance of the parameter given the path and plays the
+//! role of the squared standard error of a classical estimator. One component gives a
+//! mean and variance head.
+//!
+//! - Bishop, C. M. (1994). Mixture density networks.
+
+use candle_core::{DType, Result, Tensor, D};
+use candle_nn::ops;
+
+use super::{ParamRanges, SdeParams};
+
+/// Bounds of the log standard deviation of the components, in the scaled units
+const LOG_STD: (f64, f64) = (-7.0, 1.0);
+
+/// Output head of an estimator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EstimatorOutput {
+  /// Point estimates trained on the mean squared error
+  #[default]
+  Point,
+  /// Gaussian mixture with the given number of components per parameter, trained on the
+  /// negative log-likelihood
+  Mixture(usize),
+}
+
+impl EstimatorOutput {
+  /// Number of outputs of the network for `params` parameters
+  pub fn dim(self, params: usize) -> usize {
+    match self {
+      EstimatorOutput::Point => params,
+      EstimatorOutput::Mixture(components) => 3 * components * params,
+    }
+  }
+}
+
+/// Weights, means and standard deviations of the mixtures, each of shape
+/// (batch_size, params, components)
+pub struct MixtureParams {
+  pub weights: Tensor,
+  pub means: Tensor,
+  pub stds: Tensor,
+}
+
+impl MixtureParams {
+  /// Split the raw network output of shape (batch_size, 3 params components)
+  pub fn from_output(output: &Tensor, params: usize, components: usize) -> Result<Self> {
+    let batch_size = output.dim(0)?;
+    let output = output.reshape((batch_size, params, 3, components))?;
+    let logits = output.narrow(2, 0, 1)?.squeeze(2)?;
+    let means = ops::sigmoid(&output.narrow(2, 1, 1)?.squeeze(2)?)?;
+    let log_stds = output
+      .narrow(2, 2, 1)?
+      .squeeze(2)?
+      .clamp(LOG_STD.0, LOG_STD.1)?;
+
+    Ok(Self {
+      weights: ops::softmax_last_dim(&logits.contiguous()?)?,
+      means,
+      stds: log_stds.exp()?,
+    })
+  }
+
+  /// Mean negative log-likelihood of the targets of shape (batch_size, params)
+  pub fn nll(&self, targets: &Tensor) -> Result<Tensor> {
+    let z = self
+      .means
+      .broadcast_sub(&targets.unsqueeze(2)?)?
+      .div(&self.stds)?;
+    // ln pi_k - (y - m_k)^2 / (2 s_k^2) - ln s_k - ln(2 pi) / 2
+    let log_density = ((self.weights.log()? - (z.sqr()? * 0.5)?)? - self.stds.log()?)?
+      .affine(1.0, -0.5 * (2.0 * std::f64::consts::PI).ln())?;
+
+    log_sum_exp(&log_density)?.mean_all()?.neg()
+  }
+
+  /// Mean and variance of the mixtures, each of shape (batch_size, params)
+  pub fn moments(&self) -> Result<(Tensor, Tensor)> {
+    let mean = (&self.weights * &self.means)?.sum(D::Minus1)?;
+    let second = (&self.weights * (self.stds.sqr()? + self.means.sqr()?)?)?.sum(D::Minus1)?;
+    let variance = (second - mean.sqr()?)?.relu()?;
+
+    Ok((mean, variance))
+  }
+}
+
+/// ln sum_k exp(x_k) over the last dimension
+fn log_sum_exp(xs: &Tensor) -> Result<Tensor> {
+  let max = xs.max_keepdim(D::Minus1)?;
+  let sum = xs.broadcast_sub(&max)?.exp()?.sum_keepdim(D::Minus1)?;
+
+  (sum.log()? + max)?.squeeze(D::Minus1)
+}
+
+/// Component of the mixture of a parameter, in the units of the parameter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixtureComponent {
+  pub weight: f64,
+  pub mean: f64,
+  pub std: f64,
+}
+
+/// Estimate of the parameters of a path with its uncertainty
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamDistribution {
+  /// Mean of the mixtures
+  pub mean: SdeParams,
+  /// Standard deviation of the mixtures, the counterpart of a standard error
+  pub std: SdeParams,
+  /// Mixture of every parameter in the order (H, theta, sigma, mu)
+  pub mixtures: [Vec<MixtureComponent>; SdeParams::LEN],
+}
+
+impl ParamDistribution {
+  /// Distributions of a batch from the mixtures in the scaled units of the ranges
+  pub fn from_mixture(mixture: &MixtureParams, ranges: &ParamRanges) -> Result<Vec<Self>> {
+    // The networks run in single precision, the units are recovered in double precision
+    let (mean, variance) = mixture.moments()?;
+    let to_vec2 = |xs: Tensor| xs.to_dtype(DType::F64)?.to_vec2::<f64>();
+    let to_vec3 = |xs: &Tensor| xs.to_dtype(DType::F64)?.to_vec3::<f64>();
+    let (mean, variance) = (to_vec2(mean)?, to_vec2(variance)?);
+    let weights = to_vec3(&mixture.weights)?;
+    let means = to_vec3(&mixture.means)?;
+    let stds = to_vec3(&mixture.stds)?;
+
+    // Lower bounds and widths of the ranges, the scaling is affine
+    let lower = ranges.unscale([0.0; SdeParams::LEN]).to_array();
+    let upper = ranges.unscale([1.0; SdeParams::LEN]).to_array();
+    let width = |j: usize| upper[j] - lower[j];
+
+    Ok(
+      (0..mean.len())
+        .map(|b| Self {
+          mean: ranges.unscale(std::array::from_fn(|j| mean[b][j])),
+          std: SdeParams::from_array(std::array::from_fn(|j| variance[b][j].sqrt() * width(j))),
+          mixtures: std::array::from_fn(|j| {
+            (0..weights[b][j].len())
+              .map(|k| MixtureComponent {
+                weight: weights[b][j][k],
+                mean: lower[j] + means[b][j][k] * width(j),
+                std: stds[b][j][k] * width(j),
+              })
+              .collect()
+          }),
+        })
+        .collect(),
+    )
+  }
+
+  /// Symmetric Gaussian interval mean ± z std of the parameters, e.g. z = 1.96 for 95%
+  pub fn interval(&self, z: f64) -> (SdeParams, SdeParams) {
+    let (mean, std) = (self.mean.to_array(), self.std.to_array());
+
+    (
+      SdeParams::from_array(std::array::from_fn(|j| mean[j] - z * std[j])),
+      SdeParams::from_array(std::array::from_fn(|j| mean[j] + z * std[j])),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use candle_core::Device;
+
+  use super::*;
+
+  #[test]
+  fn mixture_moments_and_nll() -> Result<()> {
+    let device = Device::Cpu;
+    // One sample, one parameter, two components with equal weights, means 0.25 and 0.75
+    // and standard deviation e^-2
+    let logit = |m: f64| (m / (1.0 - m)).ln();
+    let output = Tensor::new(&[[0.0, 0.0, logit(0.25), logit(0.75), -2.0, -2.0]], &device)?;
+    let mixture = MixtureParams::from_output(&output, 1, 2)?;
+
+    let (mean, variance) = mixture.moments()?;
+    let s2 = (-4.0f64).exp();
+    assert!((mean.to_vec2::<f64>()?[0][0] - 0.5).abs() < 1e-12);
+    assert!((variance.to_vec2::<f64>()?[0][0] - (s2 + 0.0625)).abs() < 1e-12);
+
+    let nll = mixture
+      .nll(&Tensor::new(&[[0.25]], &device)?)?
+      .to_scalar::<f64>()?;
+    let density = |z: f64| (-0.5 * z * z).exp() / (s2 * 2.0 * std::f64::consts::PI).sqrt();
+    let expected = -(0.5 * density(0.0) + 0.5 * density(0.5 / s2.sqrt())).ln();
+    assert!((nll - expected).abs() < 1e-6);
+
+    Ok(())
+  }
+
+  #[test]
+  fn param_distribution_units() -> Result<()> {
+    let ranges = ParamRanges::default();
+    // One component per parameter at the middle of the ranges with std e^-3
+    let output = Tensor::new([0.0, 0.0, -3.0].repeat(4), &Device::Cpu)?.unsqueeze(0)?;
+    let mixture = MixtureParams::from_output(&output, SdeParams::LEN, 1)?;
+    let distribution = &ParamDistribution::from_mixture(&mixture, &ranges)?[0];
+
+    let middle = ranges.unscale([0.5; SdeParams::LEN]);
+    assert!((distribution.mean.theta - middle.theta).abs() < 1e-12);
+    let width = ranges.theta.1 - ranges.theta.0;
+    assert!((distribution.std.theta - (-3.0f64).exp() * width).abs() < 1e-9);
+    assert_eq!(distribution.mixtures[1][0].weight, 1.0);
+
+    let (lower, upper) = distribution.interval(2.0);
+    assert!(lower.hurst < middle.hurst && upper.hurst > middle.hurst);
+
+    Ok(())
+  }
+}
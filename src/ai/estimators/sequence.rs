@@ -0,0 +1,672 @@
+//! Transformer encoder regressing (H, theta, sigma, mu) from a path.
+//!
+//! The path of n points is turned into (n - 1) / patch tokens, every token holding the
+//! levels and the increments of `patch` consecutive steps. The tokens are embedded,
+//! added to a learned positional embedding and passed through pre-norm self-attention
+//! layers. The mean of the encoded tokens is mapped to the four parameters scaled to
+//! [0, 1] by the [`ParamRanges`] of the training set, either as point estimates or as
+//! Gaussian mixtures ([`EstimatorOutput`]) that carry the uncertainty of the estimate.
+//!
+//! - Vaswani, A., et al. (2017). Attention is all you need.
+//! - Nie, Y., et al. (2023). A time series is worth 64 words: Long-term forecasting with transformers.
+
+use anyhow::ensure;
+use candle_core::{DType, Device, Module, Result, Tensor};
+use candle_nn::{
+  embedding, layer_norm, linear, loss::mse, ops, Activation, AdamW, Dropout, Embedding, LayerNorm,
+  LayerNormConfig, Linear, Optimizer, ParamsAdamW, VarBuilder, VarMap,
+};
+use ndarray::{s, Array1, ArrayView2, Axis};
+
+use super::{
+  dataset::{Epoch, SdeDataset},
+  mixture::{EstimatorOutput, MixtureParams, ParamDistribution},
+  ParamRanges, SdeParams,
+};
+use crate::ai::{
+  apply_layer_norm,
+  export::{self, layer, op, Export, Json, SPEC_VERSION},
+  training::{Metrics, Trainer},
+};
+
+/// Architecture of the [`SequenceEstimator`]
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceEstimatorConfig {
+  /// Number of points of the paths
+  pub n: usize,
+  /// Number of steps per token
+  pub patch: usize,
+  /// Embedding dimension, divisible by n_head
+  pub n_embd: usize,
+  pub n_head: usize,
+  /// Number of encoder layers
+  pub n_layers: usize,
+  pub dropout_rate: f32,
+  /// Point estimates or mixture densities
+  pub output: EstimatorOutput,
+}
+
+impl Default for SequenceEstimatorConfig {
+  fn default() -> Self {
+    Self {
+      n: 1025,
+      patch: 16,
+      n_embd: 64,
+      n_head: 4,
+      n_layers: 3,
+      dropout_rate: 0.1,
+      output: EstimatorOutput::Point,
+    }
+  }
+}
+
+impl SequenceEstimatorConfig {
+  /// Number of tokens of a path, the steps of an incomplete last patch are dropped
+  pub fn n_tokens(&self) -> usize {
+    (self.n - 1) / self.patch
+  }
+}
+
+/// Multi-head self-attention without mask
+struct SelfAttention {
+  qkv: Linear,
+  out: Linear,
+  n_head: usize,
+}
+
+impl SelfAttention {
+  fn new(n_embd: usize, n_head: usize, vs: VarBuilder) -> Result<Self> {
+    Ok(Self {
+      qkv: linear(n_embd, 3 * n_embd, vs.pp("qkv"))?,
+      out: linear(n_embd, n_embd, vs.pp("out"))?,
+      n_head,
+    })
+  }
+}
+
+impl Module for SelfAttention {
+  fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+    // xs shape: (batch_size, n_tokens, n_embd)
+    let (batch_size, n_tokens, n_embd) = xs.dims3()?;
+    let head_dim = n_embd / self.n_head;
+    let qkv = self
+      .qkv
+      .forward(xs)?
+      .reshape((batch_size, n_tokens, 3, self.n_head, head_dim))?
+      .permute((2, 0, 3, 1, 4))?; // Shape: (3, batch_size, n_head, n_tokens, head_dim)
+    let q = qkv.get(0)?.contiguous()?;
+    let k = qkv.get(1)?.contiguous()?;
+    let v = qkv.get(2)?.contiguous()?;
+
+    let scores = (q.matmul(&k.t()?)? / (head_dim as f64).sqrt())?;
+    let weights = ops::softmax_last_dim(&scores)?;
+    let xs = weights
+      .matmul(&v)?
+      .transpose(1, 2)?
+      .reshape((batch_size, n_tokens, n_embd))?;
+
+    self.out.forward(&xs)
+  }
+}
+
+/// Pre-norm encoder layer, x + attention(ln(x)) followed by x + mlp(ln(x))
+struct EncoderLayer {
+  attention: SelfAttention,
+  ln1: LayerNorm,
+  ln2: LayerNorm,
+  fc1: Linear,
+  fc2: Linear,
+  dropout: Dropout,
+}
+
+impl EncoderLayer {
+  fn new(n_embd: usize, n_head: usize, dropout_rate: f32, vs: VarBuilder) -> Result<Self> {
+    Ok(Self {
+      attention: SelfAttention::new(n_embd, n_head, vs.pp("attention"))?,
+      ln1: layer_norm(n_embd, LayerNormConfig::default(), vs.pp("ln1"))?,
+      ln2: layer_norm(n_embd, LayerNormConfig::default(), vs.pp("ln2"))?,
+      fc1: linear(n_embd, 4 * n_embd, vs.pp("fc1"))?,
+      fc2: linear(4 * n_embd, n_embd, vs.pp("fc2"))?,
+      dropout: Dropout::new(dropout_rate),
+    })
+  }
+
+  fn forward(&self, xs: &Tensor, train: bool) -> Result<Tensor> {
+    let attention = self.attention.forward(&apply_layer_norm(&self.ln1, xs)?)?;
+    let xs = (xs + self.dropout.forward(&attention, train)?)?;
+    let mlp = self.fc2.forward(
+      &Activation::NewGelu.forward(&self.fc1.forward(&apply_layer_norm(&self.ln2, &xs)?)?)?,
+    )?;
+
+    &xs + self.dropout.forward(&mlp, train)?
+  }
+}
+
+/// Transformer estimator of the parameters of mean-reverting models
+pub struct SequenceEstimator {
+  pub config: SequenceEstimatorConfig,
+  /// Ranges the targets are scaled with, the ones of the training set
+  pub ranges: ParamRanges,
+  varmap: VarMap,
+  device: Device,
+  input: Linear,
+  positions: Embedding,
+  layers: Vec<EncoderLayer>,
+  ln: LayerNorm,
+  head: Linear,
+  output: Linear,
+}
+
+impl SequenceEstimator {
+  pub fn new(
+    config: SequenceEstimatorConfig,
+    ranges: ParamRanges,
+    device: &Device,
+  ) -> anyhow::Result<Self> {
+    ensure!(
+      config.patch >= 1 && config.n_tokens() >= 1,
+      "a path must hold at least one patch"
+    );
+    ensure!(
+      config.n_embd.is_multiple_of(config.n_head),
+      "n_embd must be divisible by n_head"
+    );
+    ensure!(
+      config.output != EstimatorOutput::Mixture(0),
+      "a mixture needs at least one component"
+    );
+
+    let varmap = VarMap::new();
+    let vs = VarBuilder::from_varmap(&varmap, DType::F32, device);
+    let n_embd = config.n_embd;
+
+    let input = linear(2 * config.patch, n_embd, vs.pp("input"))?;
+    let positions = embedding(config.n_tokens(), n_embd, vs.pp("positions"))?;
+    let layers = (0..config.n_layers)
+      .map(|i| {
+        EncoderLayer::new(
+          n_embd,
+          config.n_head,
+          config.dropout_rate,
+          vs.pp(format!("layers_{}", i)),
+        )
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let ln = layer_norm(n_embd, LayerNormConfig::default(), vs.pp("ln"))?;
+    let head = linear(n_embd, n_embd, vs.pp("head"))?;
+    let output = linear(n_embd, config.output.dim(SdeParams::LEN), vs.pp("output"))?;
+
+    Ok(Self {
+      config,
+      ranges,
+      varmap,
+      device: device.clone(),
+      input,
+      positions,
+      layers,
+      ln,
+      head,
+      output,
+    })
+  }
+
+  /// Variables of the network
+  pub fn varmap(&self) -> &VarMap {
+    &self.varmap
+  }
+
+  /// Tokens of shape (batch_size, n_tokens, 2 patch) of paths given as rows
+  pub fn tokens(&self, paths: ArrayView2<f32>) -> anyhow::Result<Tensor> {
+    let SequenceEstimatorConfig { n, patch, .. } = self.config;
+    ensure!(
+      paths.ncols() == n,
+      "paths must have {} points, got {}",
+      n,
+      paths.ncols()
+    );
+
+    let n_tokens = self.config.n_tokens();
+    let steps = n_tokens * patch;
+    let mut data = Vec::<f32>::with_capacity(paths.nrows() * steps * 2);
+    for path in paths.rows() {
+      let levels = path.slice(s![..steps]);
+      let increments = &path.slice(s![1..=steps]) - &levels;
+      for token in 0..n_tokens {
+        let range = s![token * patch..(token + 1) * patch];
+        data.extend(levels.slice(range));
+        data.extend(increments.slice(range));
+      }
+    }
+
+    Ok(Tensor::from_vec(
+      data,
+      (paths.nrows(), n_tokens, 2 * patch),
+      &self.device,
+    )?)
+  }
+
+  /// Output of the network for tokens of shape (batch_size, n_tokens, 2 patch), the
+  /// scaled parameters in [0, 1] for point estimates and the raw mixture parameters
+  /// otherwise, dropout is active if train is true
+  pub fn forward(&self, xs: &Tensor, train: bool) -> Result<Tensor> {
+    let mut xs = self
+      .input
+      .forward(xs)?
+      .broadcast_add(self.positions.embeddings())?;
+    for layer in &self.layers {
+      xs = layer.forward(&xs, train)?;
+    }
+    let pooled = apply_layer_norm(&self.ln, &xs)?.mean(1)?;
+    let hidden = Activation::NewGelu.forward(&self.head.forward(&pooled)?)?;
+
+    let output = self.output.forward(&hidden)?;
+
+    match self.config.output {
+      EstimatorOutput::Point => ops::sigmoid(&output),
+      EstimatorOutput::Mixture(_) => Ok(output),
+    }
+  }
+
+  /// Mixtures of the network output of a mixture estimator
+  fn mixture(&self, output: &Tensor) -> Result<MixtureParams> {
+    match self.config.output {
+      EstimatorOutput::Point => Err(candle_core::Error::Msg(
+        "point estimators have no mixture output".to_string(),
+      )),
+      EstimatorOutput::Mixture(components) => {
+        MixtureParams::from_output(output, SdeParams::LEN, components)
+      }
+    }
+  }
+
+  /// MSE of point estimates or negative log-likelihood of mixtures for scaled targets
+  pub fn loss(&self, xs: &Tensor, ys: &Tensor, train: bool) -> Result<Tensor> {
+    let output = self.forward(xs, train)?;
+
+    match self.config.output {
+      EstimatorOutput::Point => mse(&output, ys),
+      EstimatorOutput::Mixture(_) => self.mixture(&output)?.nll(ys),
+    }
+  }
+
+  /// Tokens and scaled targets of an epoch in batches, an incomplete last batch is dropped
+  pub fn batches(&self, epoch: &Epoch, batch_size: usize) -> anyhow::Result<Vec<(Tensor, Tensor)>> {
+    (0..epoch.params.len() / batch_size)
+      .map(|batch| {
+        let rows = batch * batch_size..(batch + 1) * batch_size;
+        let xs = self.tokens(epoch.paths.slice(s![rows.clone(), ..]))?;
+        let targets = epoch.params[rows]
+          .iter()
+          .flat_map(|params| self.ranges.scale(*params).map(|x| x as f32))
+          .collect::<Vec<_>>();
+        let ys = Tensor::from_vec(targets, (batch_size, SdeParams::LEN), &self.device)?;
+
+        Ok((xs, ys))
+      })
+      .collect()
+  }
+
+  /// Train with AdamW on a fresh epoch of the dataset per training epoch, the validation
+  /// loss is the mean [`SequenceEstimator::loss`] over the validation batches
+  pub fn train(
+    &self,
+    dataset: &SdeDataset,
+    batch_size: usize,
+    validation: &Epoch,
+    trainer: &mut Trainer,
+  ) -> anyhow::Result<Metrics> {
+    ensure!(
+      dataset.n == self.config.n,
+      "the dataset simulates paths of {} points, the estimator expects {}",
+      dataset.n,
+      self.config.n
+    );
+    ensure!(
+      dataset.ranges == self.ranges,
+      "the parameter ranges of the dataset and the estimator differ"
+    );
+
+    let validation = self.batches(validation, batch_size)?;
+    let mut adam = AdamW::new(
+      self.varmap.all_vars(),
+      ParamsAdamW {
+        lr: trainer.lr,
+        ..Default::default()
+      },
+    )?;
+
+    trainer.fit(
+      &mut adam,
+      |_, adam| -> anyhow::Result<Vec<f64>> {
+        let epoch = dataset.next_epoch()?;
+        let mut losses = Vec::new();
+        for (xs, ys) in self.batches(&epoch, batch_size)? {
+          let loss = self.loss(&xs, &ys, true)?;
+          adam.backward_step(&loss)?;
+          losses.push(loss.to_scalar::<f32>()? as f64);
+        }
+        Ok(losses)
+      },
+      |_| -> anyhow::Result<Option<f64>> {
+        if validation.is_empty() {
+          return Ok(None);
+        }
+
+        let mut loss = 0.0;
+        for (xs, ys) in &validation {
+          loss += self.loss(xs, ys, false)?.to_scalar::<f32>()? as f64;
+        }
+        Ok(Some(loss / validation.len() as f64))
+      },
+    )
+  }
+
+  /// Parameters of paths given as rows, the means of the mixtures for mixture estimators
+  pub fn estimate_batch(&self, paths: ArrayView2<f32>) -> anyhow::Result<Vec<SdeParams>> {
+    let output = self.forward(&self.tokens(paths)?, false)?;
+    let scaled = match self.config.output {
+      EstimatorOutput::Point => output,
+      EstimatorOutput::Mixture(_) => self.mixture(&output)?.moments()?.0,
+    }
+    .to_dtype(DType::F64)?
+    .to_vec2::<f64>()?;
+
+    Ok(
+      scaled
+        .into_iter()
+        .map(|row| self.ranges.unscale([row[0], row[1], row[2], row[3]]))
+        .collect(),
+    )
+  }
+
+  /// Parameters of a path
+  pub fn estimate(&self, path: &Array1<f32>) -> anyhow::Result<SdeParams> {
+    let paths = path.view().insert_axis(Axis(0));
+    Ok(self.estimate_batch(paths)?[0])
+  }
+
+  /// Distributions of the parameters of paths given as rows, only for mixture estimators
+  pub fn estimate_distribution_batch(
+    &self,
+    paths: ArrayView2<f32>,
+  ) -> anyhow::Result<Vec<ParamDistribution>> {
+    let output = self.forward(&self.tokens(paths)?, false)?;
+    Ok(ParamDistribution::from_mixture(
+      &self.mixture(&output)?,
+      &self.ranges,
+    )?)
+  }
+
+  /// Distribution of the parameters of a path, only for mixture estimators
+  pub fn estimate_distribution(&self, path: &Array1<f32>) -> anyhow::Result<ParamDistribution> {
+    let paths = path.view().insert_axis(Axis(0));
+    Ok(self.estimate_distribution_batch(paths)?.remove(0))
+  }
+
+  /// Save the variables as safetensors
+  pub fn save(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+    Ok(self.varmap.save(path)?)
+  }
+
+  /// Load variables saved by [`SequenceEstimator::save`] into an estimator of the same
+  /// configuration
+  pub fn load(&mut self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+    Ok(self.varmap.load(path)?)
+  }
+}
+
+impl Export for SequenceEstimator {
+  fn varmap(&self) -> &VarMap {
+    &self.varmap
+  }
+
+  /// Spec of the tokenization, the encoder and the output head, in float64
+  fn spec(&self) -> Json {
+    let SequenceEstimatorConfig {
+      n,
+      patch,
+      n_embd,
+      n_head,
+      n_layers,
+      output,
+      ..
+    } = self.config;
+    let n_tokens = self.config.n_tokens();
+    let gelu = || Json::object([("op", "gelu".into()), ("approximate", "tanh".into())]);
+
+    let mut forward = vec![
+      export::linear("input", 2 * patch, n_embd),
+      Json::object([
+        ("op", "add_positional_embedding".into()),
+        ("weight", "positions.weight".into()),
+        ("shape", vec![n_tokens, n_embd].into()),
+      ]),
+    ];
+    forward.extend((0..n_layers).map(|i| {
+      let name = |layer: &str| format!("layers_{}.{}", i, layer);
+      Json::object([
+        ("op", "encoder_layer".into()),
+        (
+          "forward",
+          Json::Array(vec![
+            export::layer_norm(&name("ln1"), n_embd),
+            Json::object([
+              ("op", "self_attention".into()),
+              ("n_head", n_head.into()),
+              (
+                "qkv",
+                export::linear(&name("attention.qkv"), n_embd, 3 * n_embd),
+              ),
+              (
+                "layout",
+                "the qkv output is reshaped to (batch, tokens, 3, n_head, head_dim), scores q k^T / sqrt(head_dim), softmax over the keys, no mask".into(),
+              ),
+              (
+                "out",
+                export::linear(&name("attention.out"), n_embd, n_embd),
+              ),
+            ]),
+            op("add_residual"),
+            export::layer_norm(&name("ln2"), n_embd),
+            export::linear(&name("fc1"), n_embd, 4 * n_embd),
+            gelu(),
+            export::linear(&name("fc2"), 4 * n_embd, n_embd),
+            op("add_residual"),
+          ]),
+        ),
+      ])
+    }));
+    forward.extend([
+      export::layer_norm("ln", n_embd),
+      Json::object([("op", "mean".into()), ("axis", 1usize.into())]),
+      export::linear("head", n_embd, n_embd),
+      gelu(),
+      layer("linear", "output", &[output.dim(SdeParams::LEN), n_embd]),
+    ]);
+
+    let head = match output {
+      EstimatorOutput::Point => Json::object([
+        ("type", "point".into()),
+        ("activation", "sigmoid".into()),
+        ("shape", vec![0, SdeParams::LEN].into()),
+      ]),
+      EstimatorOutput::Mixture(components) => Json::object([
+        ("type", "mixture".into()),
+        ("components", components.into()),
+        ("shape", vec![0, SdeParams::LEN, 3, components].into()),
+        (
+          "activation",
+          "reshape to (batch, params, 3, components), softmax of [:, :, 0] over the components are the weights, sigmoid of [:, :, 1] the means, exp of [:, :, 2] clamped to [-7, 1] the standard deviations".into(),
+        ),
+      ]),
+    };
+    let [hurst, theta, sigma, mu] = self.ranges.bounds().map(|(lo, hi)| vec![lo, hi]);
+
+    Json::object([
+      ("format", "stochastic-rs".into()),
+      ("version", SPEC_VERSION.into()),
+      ("model", "sequence_estimator".into()),
+      ("dtype", "float64".into()),
+      (
+        "config",
+        Json::object([
+          ("n", n.into()),
+          ("patch", patch.into()),
+          ("n_tokens", n_tokens.into()),
+          ("n_embd", n_embd.into()),
+          ("n_head", n_head.into()),
+          ("n_layers", n_layers.into()),
+        ]),
+      ),
+      (
+        "input",
+        Json::object([
+          ("shape", vec![0, n_tokens, 2 * patch].into()),
+          (
+            "tokens",
+            "a path x of n points gives n_tokens tokens, token t holds the levels x[t patch .. (t + 1) patch] followed by the increments x[i + 1] - x[i] of the same steps, the points after the last full patch are dropped".into(),
+          ),
+        ]),
+      ),
+      ("forward", Json::Array(forward)),
+      ("output", head),
+      (
+        "parameters",
+        Json::object([
+          ("order", vec!["hurst", "theta", "sigma", "mu"].into()),
+          ("hurst", hurst.into()),
+          ("theta", theta.into()),
+          ("sigma", sigma.into()),
+          ("mu", mu.into()),
+          (
+            "scaling",
+            "the outputs are in [0, 1], a parameter is lo + y (hi - lo) with the range [lo, hi] of the parameter, standard deviations scale with hi - lo".into(),
+          ),
+        ]),
+      ),
+    ])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ai::estimators::dataset::MeanRevertingModel;
+
+  fn config() -> SequenceEstimatorConfig {
+    SequenceEstimatorConfig {
+      n: 65,
+      patch: 8,
+      n_embd: 16,
+      n_head: 2,
+      n_layers: 1,
+      dropout_rate: 0.0,
+      output: EstimatorOutput::Point,
+    }
+  }
+
+  #[test]
+  fn sequence_estimator_estimate_in_ranges() -> anyhow::Result<()> {
+    let ranges = ParamRanges::default();
+    let estimator = SequenceEstimator::new(config(), ranges, &Device::Cpu)?;
+    let epoch = SdeDataset::simulate(&[MeanRevertingModel::FOU], &ranges, 5, 65, 1.0);
+
+    let tokens = estimator.tokens(epoch.paths.view())?;
+    assert_eq!(tokens.dims(), &[5, 8, 16]);
+
+    let estimates = estimator.estimate_batch(epoch.paths.view())?;
+    assert_eq!(estimates.len(), 5);
+    for params in estimates {
+      assert!(ranges.scale(params).iter().all(|x| (0.0..=1.0).contains(x)));
+    }
+
+    let single = estimator.estimate(&epoch.paths.row(0).to_owned())?;
+    assert!(single.theta >= ranges.theta.0 && single.theta <= ranges.theta.1);
+    assert!(estimator.estimate(&Array1::zeros(10)).is_err());
+    assert!(estimator
+      .estimate_distribution(&epoch.paths.row(0).to_owned())
+      .is_err());
+
+    Ok(())
+  }
+
+  #[test]
+  fn sequence_estimator_mixture() -> anyhow::Result<()> {
+    let ranges = ParamRanges::default();
+    let models = vec![MeanRevertingModel::FOU];
+    let config = SequenceEstimatorConfig {
+      output: EstimatorOutput::Mixture(3),
+      ..config()
+    };
+    let estimator = SequenceEstimator::new(config, ranges, &Device::Cpu)?;
+    let dataset = SdeDataset::spawn(models.clone(), ranges, 32, 65, 1.0)?;
+    let validation = SdeDataset::simulate(&models, &ranges, 16, 65, 1.0);
+
+    let mut trainer = Trainer::new(2, 1e-3).with_verbose(false);
+    let metrics = estimator.train(&dataset, 8, &validation, &mut trainer)?;
+    assert!(metrics
+      .epochs
+      .iter()
+      .all(|epoch| epoch.val_loss.unwrap().is_finite()));
+
+    let path = validation.paths.row(0).to_owned();
+    let distribution = estimator.estimate_distribution(&path)?;
+    let point = estimator.estimate(&path)?;
+    assert!((distribution.mean.hurst - point.hurst).abs() < 1e-12);
+    assert!(distribution.std.to_array().iter().all(|std| *std > 0.0));
+    for mixture in &distribution.mixtures {
+      assert_eq!(mixture.len(), 3);
+      // The softmax is taken in single precision
+      assert!((mixture.iter().map(|c| c.weight).sum::<f64>() - 1.0).abs() < 1e-6);
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn sequence_estimator_train() -> anyhow::Result<()> {
+    let ranges = ParamRanges::default();
+    let models = vec![MeanRevertingModel::OU, MeanRevertingModel::FOU];
+    let estimator = SequenceEstimator::new(config(), ranges, &Device::Cpu)?;
+    let dataset = SdeDataset::spawn(models.clone(), ranges, 32, 65, 1.0)?;
+    let validation = SdeDataset::simulate(&models, &ranges, 16, 65, 1.0);
+
+    let mut trainer = Trainer::new(2, 1e-3).with_verbose(false);
+    let metrics = estimator.train(&dataset, 8, &validation, &mut trainer)?;
+
+    assert_eq!(metrics.epochs.len(), 2);
+    assert!(metrics.epochs.iter().all(|epoch| epoch.val_loss.is_some()));
+
+    Ok(())
+  }
+
+  #[test]
+  fn sequence_estimator_export() -> anyhow::Result<()> {
+    let config = SequenceEstimatorConfig {
+      output: EstimatorOutput::Mixture(2),
+      ..config()
+    };
+    let estimator = SequenceEstimator::new(config, ParamRanges::default(), &Device::Cpu)?;
+    let dir = tempfile::tempdir()?;
+    estimator.export(dir.path().join("estimator"))?;
+
+    let weights =
+      candle_core::safetensors::load(dir.path().join("estimator.safetensors"), &Device::Cpu)?;
+    let spec = estimator.spec();
+    let tensors = spec.tensors();
+    assert!(tensors.contains(&"positions.weight".to_string()));
+    // Every variable is referenced by the spec
+    assert_eq!(tensors.len(), weights.len());
+    for name in &tensors {
+      assert!(weights.contains_key(name), "{}", name);
+    }
+    assert_eq!(weights["input.weight"].dims(), &[16, 16]);
+    assert_eq!(weights["output.weight"].dims(), &[24, 16]);
+
+    let json = std::fs::read_to_string(dir.path().join("estimator.json"))?;
+    assert_eq!(json, spec.to_string());
+    assert!(json.contains(r#""type":"mixture","components":2"#));
+
+    Ok(())
+  }
+}
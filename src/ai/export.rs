@@ -0,0 +1,244 @@
+//! Export of trained networks for inference outside of Rust.
+//!
+//! An exported model is a pair of files next to each other:
+//!
+//! - `<stem>.safetensors` with the weights under the variable names of the candle
+//!   [`VarMap`], e.g. `layers_0.attention.qkv.weight`. Linear weights are stored as
+//!   (out_features, in_features) like `torch.nn.Linear`, so they load with
+//!   `safetensors.torch.load_file` or `safetensors.numpy.load_file` as they are.
+//! - `<stem>.json` with the architecture spec: the format version, the hyperparameters,
+//!   the operations of the forward pass in order with the names and shapes of their
+//!   weights, and the pre- and post-processing of the inputs and outputs.
+//!
+//! The spec spells out every operation in terms of standard ops (linear, layer norm with
+//! eps 1e-5, ELU, tanh approximated GELU, softmax, sigmoid), so the network can be rebuilt
+//! in PyTorch or JAX, or traced from there to ONNX, without reading the Rust code.
+
+use std::{fmt, fs, path::Path};
+
+use candle_nn::VarMap;
+
+/// Version of the layout of the architecture spec
+pub const SPEC_VERSION: usize = 1;
+
+/// Minimal JSON value for the architecture specs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+  Null,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  Array(Vec<Json>),
+  Object(Vec<(String, Json)>),
+}
+
+impl Json {
+  /// Object from key-value pairs, in the given order
+  pub fn object<const N: usize>(entries: [(&str, Json); N]) -> Self {
+    Json::Object(
+      entries
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect(),
+    )
+  }
+
+  /// Value of a key of an object
+  pub fn get(&self, key: &str) -> Option<&Json> {
+    match self {
+      Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+      _ => None,
+    }
+  }
+
+  /// Names of the tensors, the values of the "weight" and "bias" keys, referenced by a
+  /// spec, in order
+  pub fn tensors(&self) -> Vec<String> {
+    match self {
+      Json::Array(values) => values.iter().flat_map(Json::tensors).collect(),
+      Json::Object(entries) => entries
+        .iter()
+        .flat_map(|(key, value)| match value {
+          Json::String(name) if key == "weight" || key == "bias" => vec![name.clone()],
+          value => value.tensors(),
+        })
+        .collect(),
+      _ => Vec::new(),
+    }
+  }
+}
+
+impl From<bool> for Json {
+  fn from(value: bool) -> Self {
+    Json::Bool(value)
+  }
+}
+
+impl From<f64> for Json {
+  fn from(value: f64) -> Self {
+    Json::Number(value)
+  }
+}
+
+impl From<usize> for Json {
+  fn from(value: usize) -> Self {
+    Json::Number(value as f64)
+  }
+}
+
+impl From<&str> for Json {
+  fn from(value: &str) -> Self {
+    Json::String(value.to_string())
+  }
+}
+
+impl From<String> for Json {
+  fn from(value: String) -> Self {
+    Json::String(value)
+  }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+  fn from(values: Vec<T>) -> Self {
+    Json::Array(values.into_iter().map(Into::into).collect())
+  }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+  write!(f, "\"")?;
+  for c in value.chars() {
+    match c {
+      '"' => write!(f, "\\\"")?,
+      '\\' => write!(f, "\\\\")?,
+      '\n' => write!(f, "\\n")?,
+      c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+      c => write!(f, "{}", c)?,
+    }
+  }
+  write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Json::Null => write!(f, "null"),
+      Json::Bool(value) => write!(f, "{}", value),
+      // JSON has no NaN or infinity
+      Json::Number(value) if !value.is_finite() => write!(f, "null"),
+      Json::Number(value) => write!(f, "{}", value),
+      Json::String(value) => write_string(f, value),
+      Json::Array(values) => {
+        write!(f, "[")?;
+        for (i, value) in values.iter().enumerate() {
+          if i > 0 {
+            write!(f, ",")?;
+          }
+          write!(f, "{}", value)?;
+        }
+        write!(f, "]")
+      }
+      Json::Object(entries) => {
+        write!(f, "{{")?;
+        for (i, (key, value)) in entries.iter().enumerate() {
+          if i > 0 {
+            write!(f, ",")?;
+          }
+          write_string(f, key)?;
+          write!(f, ":{}", value)?;
+        }
+        write!(f, "}}")
+      }
+    }
+  }
+}
+
+/// Operation of the forward pass with weights `<name>.weight` of the given shape, as
+/// stored, and `<name>.bias`
+pub(crate) fn layer(op: &str, name: &str, shape: &[usize]) -> Json {
+  Json::object([
+    ("op", op.into()),
+    ("weight", format!("{}.weight", name).into()),
+    ("bias", format!("{}.bias", name).into()),
+    ("shape", shape.to_vec().into()),
+  ])
+}
+
+/// Linear layer y = x W^T + b with W of shape (out_features, in_features)
+pub(crate) fn linear(name: &str, in_features: usize, out_features: usize) -> Json {
+  layer("linear", name, &[out_features, in_features])
+}
+
+/// Layer norm over the last dimension with eps 1e-5, the default of candle
+pub(crate) fn layer_norm(name: &str, dim: usize) -> Json {
+  let mut layer = layer("layer_norm", name, &[dim]);
+  if let Json::Object(entries) = &mut layer {
+    entries.push(("eps".to_string(), 1e-5f64.into()));
+  }
+  layer
+}
+
+/// Operation of the forward pass without weights
+pub(crate) fn op(op: &str) -> Json {
+  Json::object([("op", op.into())])
+}
+
+/// Networks that can be exported as weights and an architecture spec
+pub trait Export {
+  /// Variables of the network
+  fn varmap(&self) -> &VarMap;
+
+  /// Architecture spec of the network, see the [module documentation](self)
+  fn spec(&self) -> Json;
+
+  /// Write `<stem>.safetensors` and `<stem>.json`, any extension of the path is replaced
+  fn export(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    self.varmap().save(path.with_extension("safetensors"))?;
+    fs::write(path.with_extension("json"), self.spec().to_string())?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn json_display() {
+    let spec = Json::object([
+      ("name", "a \"quoted\"\nname".into()),
+      ("shape", vec![3usize, 4].into()),
+      ("eps", 1e-5f64.into()),
+      ("nan", f64::NAN.into()),
+      ("bias", true.into()),
+      ("empty", Json::Array(vec![])),
+    ]);
+
+    assert_eq!(
+      spec.to_string(),
+      r#"{"name":"a \"quoted\"\nname","shape":[3,4],"eps":0.00001,"nan":null,"bias":true,"empty":[]}"#
+    );
+    assert_eq!(spec.get("bias"), Some(&Json::Bool(true)));
+    assert_eq!(spec.get("missing"), None);
+  }
+
+  #[test]
+  fn spec_tensors() {
+    let spec = Json::object([
+      ("version", SPEC_VERSION.into()),
+      (
+        "forward",
+        Json::Array(vec![linear("fc", 2, 3), op("relu"), layer_norm("ln", 3)]),
+      ),
+    ]);
+
+    assert_eq!(
+      spec.tensors(),
+      ["fc.weight", "fc.bias", "ln.weight", "ln.bias"]
+    );
+    let Json::Array(forward) = spec.get("forward").unwrap() else {
+      panic!("forward is an array")
+    };
+    assert_eq!(forward[0].get("shape"), Some(&Json::from(vec![3usize, 2])));
+  }
+}
@@ -1,4 +1,8 @@
-use std::vec::IntoIter;
+use std::{
+  sync::mpsc::{sync_channel, Receiver},
+  thread,
+  vec::IntoIter,
+};
 
 use anyhow::Result;
 use candle_core::{Device, Tensor};
@@ -6,24 +10,171 @@ use candle_datasets::{batcher::IterResult2, Batcher};
 use indicatif::{ProgressBar, ProgressStyle};
 use ndarray::{s, Array1};
 use ndarray_rand::RandomExt;
+use rand::{seq::SliceRandom, thread_rng};
 use rand_distr::Uniform;
+use rayon::prelude::*;
 
-use crate::stochastic::{diffusion::fou::FOU, noise::fgn::FGN, Sampling};
+use crate::stochastic::{
+  diffusion::{fou::FOU, ou::OU},
+  noise::fgn::FGN,
+  Sampling,
+};
 
-pub fn test_vasicek_1_d(
+/// Batches of (path, theta) samples of an epoch
+pub type FouBatcher = Batcher<IterResult2<IntoIter<Result<(Tensor, Tensor), candle_core::Error>>>>;
+
+/// Input layout of the samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Features {
+  /// Normalized path of shape (n)
+  Levels,
+  /// Pairs of the normalized path and its increments, shape (n - 1, 2)
+  LevelsAndIncrements,
+}
+
+impl Features {
+  fn tensor(self, path: Array1<f32>, device: &Device) -> candle_core::Result<Tensor> {
+    match self {
+      Features::Levels => Tensor::from_iter(path, device),
+      Features::LevelsAndIncrements => {
+        let diff = &path.slice(s![1..]) - &path.slice(s![..-1]);
+        let paired = path
+          .slice(s![..-1])
+          .iter()
+          .zip(diff.iter())
+          .flat_map(|(x, dx)| [*x, *dx])
+          .collect::<Vec<_>>();
+
+        Tensor::from_vec(paired, (path.len() - 1, 2), device)
+      }
+    }
+  }
+}
+
+/// Dynamics of the simulated paths, both mean reverting to mu = 2.8 with sigma = 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dynamics {
+  /// Fractional Ornstein-Uhlenbeck with the Hurst index on the grid 0.01, 0.02, ..., 0.99
+  Fou,
+  /// Vasicek, the Brownian Ornstein-Uhlenbeck with H = 1/2
+  Vasicek,
+}
+
+/// Noise generators of the epochs, the fGn of every Hurst index on the grid is built once
+/// for the path length and shared by the paths, so the circulant embedding is not
+/// recomputed per path. The paths are simulated in single precision, the dtype of the
+/// networks.
+struct FgnCache {
+  fgns: Vec<FGN<f32>>,
+}
+
+impl FgnCache {
+  fn new(dynamics: Dynamics, n: usize) -> Self {
+    let fgns = match dynamics {
+      Dynamics::Fou => (1..100)
+        .map(|i| FGN::new(i as f32 / 100.0, n - 1, Some(1.0), None))
+        .collect(),
+      Dynamics::Vasicek => Vec::new(),
+    };
+
+    Self { fgns }
+  }
+}
+
+/// Normalized paths of an epoch with their theta and Hurst index
+struct Epoch {
+  paths: Vec<Array1<f32>>,
+  thetas: Vec<f32>,
+  hursts: Vec<f32>,
+}
+
+/// Simulate the paths of an epoch in parallel
+fn simulate_epoch(
+  dynamics: Dynamics,
+  cache: &FgnCache,
   epoch_size: usize,
-  batch_size: usize,
   n: usize,
+  progress_bar: Option<&ProgressBar>,
+) -> Epoch {
+  let mu = 2.8_f32;
+  let sigma = 1.0_f32;
+  let mut rng = thread_rng();
+  let thetas = Array1::random(epoch_size, Uniform::new(0.0_f32, 10.0)).to_vec();
+  let fgns = match dynamics {
+    Dynamics::Fou => (0..epoch_size)
+      .map(|_| cache.fgns.choose(&mut rng))
+      .collect::<Vec<_>>(),
+    Dynamics::Vasicek => vec![None; epoch_size],
+  };
+
+  let paths = thetas
+    .par_iter()
+    .zip(fgns.par_iter())
+    .map(|(&theta, fgn)| {
+      let path = match fgn {
+        Some(fgn) => FOU::new(
+          theta,
+          mu,
+          sigma,
+          n,
+          Some(0.0),
+          Some(16.0),
+          None,
+          None,
+          FGN::clone(fgn),
+        )
+        .sample(),
+        None => OU::new(mu, sigma, theta, n, Some(0.0), Some(16.0), None, None).sample(),
+      };
+      let mean = path.mean().unwrap();
+      let std = path.std(0.0);
+
+      if let Some(progress_bar) = progress_bar {
+        progress_bar.inc(1);
+      }
+      (path - mean) / std
+    })
+    .collect();
+  let hursts = fgns
+    .iter()
+    .map(|fgn| fgn.map_or(0.5, |fgn| fgn.hurst))
+    .collect();
+
+  Epoch {
+    paths,
+    thetas,
+    hursts,
+  }
+}
+
+/// Tensors of the epoch on the device, batched
+fn batch_epoch(
+  epoch: Epoch,
+  features: Features,
+  batch_size: usize,
   device: &Device,
-) -> Result<(
-  Batcher<IterResult2<IntoIter<Result<(Tensor, Tensor), candle_core::Error>>>>,
-  Vec<f64>,
-)> {
-  let mut paths = Vec::with_capacity(epoch_size);
-  let mu = 2.8;
-  let sigma = 1.0;
-  let thetas = Array1::random(epoch_size, Uniform::new(0.0, 10.0)).to_vec();
-  let hursts = Array1::random(epoch_size, Uniform::new(0.01, 0.99)).to_vec();
+) -> Result<(FouBatcher, Vec<f32>)> {
+  let samples = epoch
+    .paths
+    .into_iter()
+    .zip(epoch.thetas)
+    .map(|(path, theta)| -> candle_core::Result<(Tensor, Tensor)> {
+      Ok((
+        features.tensor(path, device)?,
+        Tensor::new(&[theta], device)?,
+      ))
+    })
+    .collect::<Vec<_>>();
+
+  let batcher = Batcher::new_r2(samples.into_iter())
+    .batch_size(batch_size)
+    .return_last_incomplete_batch(false);
+
+  Ok((batcher, epoch.hursts))
+}
+
+fn simulate_with_progress(epoch_size: usize, n: usize) -> Result<Epoch> {
+  let cache = FgnCache::new(Dynamics::Fou, n);
   let progress_bar = ProgressBar::new(epoch_size as u64);
   progress_bar.set_style(
     ProgressStyle::with_template(
@@ -31,37 +182,20 @@ pub fn test_vasicek_1_d(
     )?
     .progress_chars("#>-"),
   );
-  for idx in 0..epoch_size {
-    let hurst = hursts[idx];
-    let theta = thetas[idx];
-    let fou = FOU::new(
-      theta,
-      mu,
-      sigma,
-      n,
-      Some(0.0),
-      Some(16.0),
-      None,
-      FGN::new(hurst, n - 1, Some(1.0), None),
-    );
-    let mut path = fou.sample();
-    let mean = path.mean().unwrap();
-    let std = path.std(0.0);
-    path = (path - mean) / std;
-
-    paths.push(Ok((
-      Tensor::from_iter(path, device)?,
-      Tensor::new(&[thetas[idx]], device)?,
-    )));
-    progress_bar.inc(1);
-  }
+  let epoch = simulate_epoch(Dynamics::Fou, &cache, epoch_size, n, Some(&progress_bar));
   progress_bar.finish();
 
-  let batcher = Batcher::new_r2(paths.into_iter())
-    .batch_size(batch_size)
-    .return_last_incomplete_batch(false);
+  Ok(epoch)
+}
 
-  Ok((batcher, hursts))
+pub fn test_vasicek_1_d(
+  epoch_size: usize,
+  batch_size: usize,
+  n: usize,
+  device: &Device,
+) -> Result<(FouBatcher, Vec<f32>)> {
+  let epoch = simulate_with_progress(epoch_size, n)?;
+  batch_epoch(epoch, Features::Levels, batch_size, device)
 }
 
 pub fn test_vasicek_2_d(
@@ -69,62 +203,76 @@ pub fn test_vasicek_2_d(
   batch_size: usize,
   n: usize,
   device: &Device,
-) -> Result<(
-  Batcher<IterResult2<IntoIter<Result<(Tensor, Tensor), candle_core::Error>>>>,
-  Vec<f64>,
-)> {
-  let mut paths = Vec::with_capacity(epoch_size);
-  let mu = 2.8;
-  let sigma = 1.0;
-  let thetas = Array1::random(epoch_size, Uniform::new(0.0, 10.0)).to_vec();
-  let hursts = Array1::random(epoch_size, Uniform::new(0.01, 0.99)).to_vec();
-  let progress_bar = ProgressBar::new(epoch_size as u64);
-  progress_bar.set_style(
-    ProgressStyle::with_template(
-      "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] ({eta})",
-    )?
-    .progress_chars("#>-"),
-  );
-  for idx in 0..epoch_size {
-    let hurst = hursts[idx];
-    let theta = thetas[idx];
-    let fou = FOU::new(
-      theta,
-      mu,
-      sigma,
-      n,
-      Some(0.0),
-      Some(16.0),
-      None,
-      FGN::new(hurst, n - 1, Some(1.0), None),
-    );
-    let mut path = fou.sample();
-    let mean = path.mean().unwrap();
-    let std = path.std(0.0);
-    path = (path - mean) / std;
-
-    let diff = &path.slice(s![1..]) - &path.slice(s![..-1]);
-    let path = path.slice(s![..-1]);
-    let paired = path.iter().zip(diff.iter()).collect::<Vec<_>>();
-    let paired_tensors = paired
-      .iter()
-      .map(|pair| {
-        let (x, y) = *pair;
-        Tensor::new(&[*x, *y], device).unwrap()
-      })
-      .collect::<Vec<_>>();
-
-    paths.push(Ok((
-      Tensor::stack(&paired_tensors, 0)?,
-      Tensor::new(&[thetas[idx]], device)?,
-    )));
-    progress_bar.inc(1);
+) -> Result<(FouBatcher, Vec<f32>)> {
+  let epoch = simulate_with_progress(epoch_size, n)?;
+  batch_epoch(epoch, Features::LevelsAndIncrements, batch_size, device)
+}
+
+/// fOU or Vasicek training set simulated on the fly
+///
+/// A producer thread simulates the next epochs on the rayon thread pool while the current
+/// one is trained on. The channel holds one finished epoch, so at most two epochs are
+/// ahead of the consumer (double buffering). The tensors are created by the consumer on
+/// the device of the model. The producer stops when the dataset is dropped.
+pub struct FouDataset {
+  receiver: Receiver<Epoch>,
+  features: Features,
+}
+
+impl FouDataset {
+  /// Start simulating epochs of `epoch_size` paths of length n
+  pub fn spawn(dynamics: Dynamics, epoch_size: usize, n: usize, features: Features) -> Self {
+    let (sender, receiver) = sync_channel(1);
+    thread::spawn(move || {
+      let cache = FgnCache::new(dynamics, n);
+      while sender
+        .send(simulate_epoch(dynamics, &cache, epoch_size, n, None))
+        .is_ok()
+      {}
+    });
+
+    Self { receiver, features }
   }
-  progress_bar.finish();
 
-  let batcher = Batcher::new_r2(paths.into_iter())
-    .batch_size(batch_size)
-    .return_last_incomplete_batch(false);
+  /// Batches of the next epoch and the Hurst indices of its paths, blocks until the epoch
+  /// is simulated
+  pub fn next_epoch(&self, batch_size: usize, device: &Device) -> Result<(FouBatcher, Vec<f32>)> {
+    let epoch = self.receiver.recv()?;
+    batch_epoch(epoch, self.features, batch_size, device)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn vasicek_dataset() {
+    let dataset = FouDataset::spawn(Dynamics::Vasicek, 64, 128, Features::LevelsAndIncrements);
+    let (batcher, hursts) = dataset.next_epoch(16, &Device::Cpu).unwrap();
+    assert_eq!(hursts, vec![0.5; 64]);
 
-  Ok((batcher, hursts))
+    let batches = batcher.collect::<candle_core::Result<Vec<_>>>().unwrap();
+    assert_eq!(batches.len(), 4);
+    for (x, theta) in batches {
+      assert_eq!(x.dims(), [16, 127, 2]);
+      assert_eq!(theta.dims(), [16, 1]);
+      let theta = theta.flatten_all().unwrap().to_vec1::<f32>().unwrap();
+      assert!(theta.iter().all(|theta| (0.0..10.0).contains(theta)));
+    }
+  }
+
+  #[test]
+  fn fou_paths_share_the_cached_noise() {
+    let cache = FgnCache::new(Dynamics::Fou, 128);
+    assert_eq!(cache.fgns.len(), 99);
+
+    let epoch = simulate_epoch(Dynamics::Fou, &cache, 64, 128, None);
+    for (path, hurst) in epoch.paths.iter().zip(&epoch.hursts) {
+      assert_eq!(path.len(), 128);
+      assert!(path.mean().unwrap().abs() < 1e-5);
+      assert!((path.std(0.0) - 1.0).abs() < 1e-5);
+      assert!(cache.fgns.iter().any(|fgn| fgn.hurst == *hurst));
+    }
+  }
 }
@@ -8,7 +8,11 @@ use candle_nn::{
 };
 use polars::prelude::*;
 
-use super::fou_lstm_datasets::test_vasicek_1_d;
+use super::fou_lstm_datasets::{Dynamics, Features, FouDataset};
+use crate::ai::{
+  apply_layer_norm,
+  training::{LrSchedule, Trainer},
+};
 
 pub struct Model {
   is_train: bool,
@@ -46,7 +50,7 @@ impl Model {
           layer_idx: i,
           ..Default::default()
         },
-        vs.pp(&format!("lstm-{}", i)),
+        vs.pp(format!("lstm-{}", i)),
       )?);
     }
     let layer_n = layer_norm(hidden_dim, LayerNormConfig::default(), vs.pp("layer-norm"))?;
@@ -87,7 +91,7 @@ impl Model {
       let states = lstm.seq(&x)?;
       x = lstm.states_to_tensor(&states)?;
     }
-    x = self.layer_norm.forward(&x)?;
+    x = apply_layer_norm(&self.layer_norm, &x)?;
     if self.use_dropout {
       x = self.dropout.forward(&x, self.is_train)?;
     }
@@ -103,7 +107,7 @@ impl Model {
 pub fn test() -> anyhow::Result<()> {
   let device = Device::cuda_if_available(0).unwrap_or(Device::Cpu);
   let varmap = VarMap::new();
-  let vs = VarBuilder::from_varmap(&varmap, DType::F64, &device);
+  let vs = VarBuilder::from_varmap(&varmap, DType::F32, &device);
 
   let epochs = 50_usize;
   let epoch_size = 12_800_usize;
@@ -131,35 +135,47 @@ pub fn test() -> anyhow::Result<()> {
   let mut opt = AdamW::new(varmap.all_vars(), adamw_params)?;
 
   let n: usize = 1600_usize;
+  // Epochs are simulated in the background while the network trains
+  let dataset = FouDataset::spawn(Dynamics::Fou, epoch_size, n, Features::Levels);
+  // Fixed validation epoch, early stopping monitors its loss
+  let validation = dataset
+    .next_epoch(batch_size, &device)?
+    .0
+    .collect::<candle_core::Result<Vec<_>>>()?;
+  let mut trainer = Trainer::new(epochs, 1e-3)
+    .with_schedule(LrSchedule::Cosine { min_lr: 1e-5 })
+    .with_early_stopping(5, 1e-4)
+    .with_csv("fou_lstm_1_d_metrics.csv");
   let start = Instant::now();
 
-  for epoch in 0..epochs {
-    let (batcher, _) = test_vasicek_1_d(epoch_size, batch_size, n, &device)?;
-
-    'inner: for (batch_idx, batch) in batcher.enumerate() {
-      match batch {
-        Ok((x, target)) => {
-          let inp = net.forward(&x)?;
-          let loss = mse(&inp, &target)?;
-          opt.backward_step(&loss)?;
-          println!(
-            "Epoch: {}, Batch: {}, Loss: {:?}",
-            epoch + 1,
-            batch_idx + 1,
-            loss.to_scalar::<f64>()?
-          );
-        }
-        Err(_) => break 'inner,
+  trainer.fit(
+    &mut opt,
+    |epoch, opt| -> anyhow::Result<Vec<f64>> {
+      let (batcher, _) = dataset.next_epoch(batch_size, &device)?;
+      let mut losses = Vec::new();
+      for batch in batcher {
+        let (x, target) = batch?;
+        let loss = mse(&net.forward(&x)?, &target)?;
+        opt.backward_step(&loss)?;
+        losses.push(loss.to_scalar::<f32>()? as f64);
       }
-    }
 
-    println!("Epoch {} took {:?}", epoch + 1, start.elapsed());
-  }
+      println!("Epoch {} took {:?}", epoch + 1, start.elapsed());
+      Ok(losses)
+    },
+    |_| -> anyhow::Result<Option<f64>> {
+      let mut loss = 0.0;
+      for (x, target) in &validation {
+        loss += mse(&net.forward(x)?, target)?.to_scalar::<f32>()? as f64;
+      }
+      Ok(Some(loss / validation.len() as f64))
+    },
+  )?;
 
   net.eval();
 
   // test the model
-  let (batcher, hursts) = test_vasicek_1_d(epoch_size, batch_size, n, &device)?;
+  let (batcher, hursts) = dataset.next_epoch(batch_size, &device)?;
   let mut theta = Vec::with_capacity(epoch_size);
   let mut est_theta = Vec::with_capacity(epoch_size);
 
@@ -168,12 +184,12 @@ pub fn test() -> anyhow::Result<()> {
       Ok((x, target)) => {
         let inp = net.forward(&x)?;
         let inp_vec = inp
-          .to_vec2::<f64>()?
+          .to_vec2::<f32>()?
           .into_iter()
           .flatten()
           .collect::<Vec<_>>();
         let target_vec = target
-          .to_vec2::<f64>()?
+          .to_vec2::<f32>()?
           .into_iter()
           .flatten()
           .collect::<Vec<_>>();
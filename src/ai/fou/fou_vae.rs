@@ -2,8 +2,13 @@ use std::cell::RefCell;
 
 use candle_core::{DType, Device, Result, Tensor};
 use candle_nn::{
-  layer_norm, linear, linear_no_bias, seq, Activation, Dropout, LayerNorm, LayerNormConfig, Linear,
-  Module, Sequential, VarBuilder,
+  layer_norm, linear, linear_no_bias, loss::mse, seq, Activation, AdamW, Dropout, LayerNorm,
+  LayerNormConfig, Linear, Module, Optimizer, ParamsAdamW, Sequential, VarBuilder, VarMap,
+};
+
+use crate::ai::{
+  apply_layer_norm,
+  training::{Metrics, Trainer},
 };
 
 pub struct Time2Vec {
@@ -70,7 +75,7 @@ impl FeedForward {
       .add(linear1)
       .add(Activation::NewGelu)
       .add(linear2)
-      .add_fn(move |xs| Ok(dropout.forward(&xs, true).unwrap()));
+      .add_fn(move |xs| Ok(dropout.forward(xs, true).unwrap()));
 
     Ok(Self { net, dropout_rate })
   }
@@ -227,13 +232,13 @@ impl Block {
 
 impl Module for Block {
   fn forward(&self, xs: &Tensor) -> Result<Tensor> {
-    let x_norm = self.ln1.forward(xs)?;
+    let x_norm = apply_layer_norm(&self.ln1, xs)?;
     let attn_output = self
       .sa
       .borrow_mut()
-      .forward(&x_norm, Some(&x_norm), Some(&x_norm), false)?;
+      .forward(&x_norm, Some(&x_norm), None, true)?;
     let xs = (xs + attn_output)?;
-    let xs = (&xs + self.ffwd.forward(&self.ln2.forward(&xs)?)?)?;
+    let xs = (&xs + self.ffwd.forward(&apply_layer_norm(&self.ln2, &xs)?)?)?;
     Ok(xs)
   }
 }
@@ -267,7 +272,7 @@ impl TransformerEncoder {
         n_embd,
         n_head,
         dropout_rate,
-        vs.pp(&format!("blocks_{}", i)),
+        vs.pp(format!("blocks_{}", i)),
       )?);
     }
     let ln = layer_norm(
@@ -293,13 +298,11 @@ impl TransformerEncoder {
     // xs shape: (batch_size, seq_len, input_dim)
     let xs_input = self.input_linear.forward(xs)?;
     let t2v = self.time2vec.forward(xs)?;
-    let mut xs = (xs_input + t2v)?;
-    xs = xs.transpose(0, 1)?; // Shape: (seq_len, batch_size, n_embd)
+    let mut xs = (xs_input + t2v)?; // Shape: (batch_size, seq_len, n_embd)
     for block in &self.blocks {
       xs = block.forward(&xs)?;
     }
-    xs = self.ln.forward(&xs)?;
-    xs = xs.transpose(0, 1)?; // Shape: (batch_size, seq_len, n_embd)
+    xs = apply_layer_norm(&self.ln, &xs)?;
     let xs_pooled = xs.mean(1)?; // Average pooling over the sequence length
     let mu = self.fc_mu.forward(&xs_pooled)?;
     let log_var = self.fc_log_var.forward(&xs_pooled)?;
@@ -338,7 +341,7 @@ impl TransformerDecoder {
         n_embd,
         n_head,
         dropout_rate,
-        vs.pp(&format!("blocks_{}", i)),
+        vs.pp(format!("blocks_{}", i)),
       )?);
     }
     let ln = layer_norm(
@@ -369,12 +372,11 @@ impl TransformerDecoder {
       self.seq_len,
       z_projected.shape().dims()[1],
     ])?;
-    let mut x = z_expanded.transpose(0, 1)?; // Shape: (seq_len, batch_size, n_embd)
+    let mut x = z_expanded.contiguous()?; // Shape: (batch_size, seq_len, n_embd)
     for block in &self.blocks {
       x = block.forward(&x)?;
     }
-    x = self.ln.forward(&x)?;
-    x = x.transpose(0, 1)?; // Shape: (batch_size, seq_len, n_embd)
+    x = apply_layer_norm(&self.ln, &x)?;
     let x_reconstructed = self.output_linear.forward(&x)?;
     Ok(x_reconstructed)
   }
@@ -423,7 +425,7 @@ impl TransformerVAE {
   fn reparameterize(&self, mu: &Tensor, log_var: &Tensor) -> Result<Tensor> {
     let std = (log_var * 0.5)?.exp()?;
     let eps = Tensor::randn(0.0, 1.0, std.shape(), std.device())?;
-    Ok((mu + &(&eps * &std)?)?)
+    mu + &(&eps * &std)?
   }
 
   pub fn forward(&self, xs: &Tensor) -> Result<(Tensor, Tensor, Tensor, Tensor, Tensor)> {
@@ -432,6 +434,64 @@ impl TransformerVAE {
     let x_reconstructed = self.decoder.forward(&z)?;
     Ok((x_reconstructed, sigma_estimated, mu, log_var, z))
   }
+
+  /// Loss of a batch of paths xs with volatilities sigma: the reconstruction MSE, the KL
+  /// divergence of the latent distribution from N(0, I) weighted by beta and the MSE of the
+  /// estimated volatility
+  pub fn loss(&self, xs: &Tensor, sigma: &Tensor, beta: f64) -> Result<Tensor> {
+    let (x_reconstructed, sigma_estimated, mu, log_var, _) = self.forward(xs)?;
+    let reconstruction = mse(&x_reconstructed, xs)?;
+    // KL = -1/2 mean(1 + log_var - mu^2 - exp(log_var))
+    let kl = ((log_var.affine(1.0, 1.0)? - mu.sqr()?)? - log_var.exp()?)?
+      .mean_all()?
+      .affine(-0.5 * beta, 0.0)?;
+    let volatility = mse(&sigma_estimated, sigma)?;
+
+    (reconstruction + kl)? + volatility
+  }
+}
+
+/// Train the variational autoencoder with AdamW on batches of (paths, volatilities), the
+/// validation loss is the mean loss of the validation batches
+pub fn train(
+  model: &TransformerVAE,
+  varmap: &VarMap,
+  batches: &[(Tensor, Tensor)],
+  validation: &[(Tensor, Tensor)],
+  beta: f64,
+  trainer: &mut Trainer,
+) -> Result<Metrics> {
+  let mut adam = AdamW::new(
+    varmap.all_vars(),
+    ParamsAdamW {
+      lr: trainer.lr,
+      ..Default::default()
+    },
+  )?;
+
+  trainer.fit(
+    &mut adam,
+    |_, adam| -> Result<Vec<f64>> {
+      let mut losses = Vec::with_capacity(batches.len());
+      for (xs, sigma) in batches {
+        let loss = model.loss(xs, sigma, beta)?;
+        adam.backward_step(&loss)?;
+        losses.push(loss.to_scalar::<f64>()?);
+      }
+      Ok(losses)
+    },
+    |_| -> Result<Option<f64>> {
+      if validation.is_empty() {
+        return Ok(None);
+      }
+
+      let mut loss = 0.0;
+      for (xs, sigma) in validation {
+        loss += model.loss(xs, sigma, beta)?.to_scalar::<f64>()?;
+      }
+      Ok(Some(loss / validation.len() as f64))
+    },
+  )
 }
 
 #[cfg(test)]
@@ -484,4 +544,29 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_transformer_vae_train() -> Result<()> {
+    let (seq_len, input_dim, batch_size) = (10, 1, 8);
+    let varmap = VarMap::new();
+    let vs = VarBuilder::from_varmap(&varmap, DType::F64, &Device::Cpu);
+    let model = TransformerVAE::new(input_dim, 16, 2, 1, 1, 4, seq_len, 0.0, vs)?;
+
+    let batch = || -> Result<(Tensor, Tensor)> {
+      Ok((
+        Tensor::randn(0.0, 1.0, &[batch_size, seq_len, input_dim], &Device::Cpu)?,
+        Tensor::ones(&[batch_size, 1], DType::F64, &Device::Cpu)?,
+      ))
+    };
+    let batches = (0..4).map(|_| batch()).collect::<Result<Vec<_>>>()?;
+    let validation = vec![batch()?];
+
+    let mut trainer = Trainer::new(3, 1e-3).with_verbose(false);
+    let metrics = train(&model, &varmap, &batches, &validation, 0.1, &mut trainer)?;
+
+    assert_eq!(metrics.epochs.len(), 3);
+    assert!(metrics.epochs.iter().all(|epoch| epoch.val_loss.is_some()));
+
+    Ok(())
+  }
 }
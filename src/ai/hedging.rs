@@ -0,0 +1,519 @@
+//! Deep hedging of a European option with proportional transaction costs.
+//!
+//! A network maps the state at every rebalancing date, the log-moneyness scaled by the
+//! maturity ln(S / K) / sqrt(T), the time to maturity as a fraction of T and the previous
+//! position, to the number of shares held until the next date. The terminal P&L of a short
+//! option hedged this way is
+//!
+//! PnL = sum_k delta_k (S_(k+1) - S_k) - c sum_k |delta_k - delta_(k-1)| S_k - Z
+//!
+//! with delta_(-1) = 0, c the proportional cost and Z the payoff, the rates are zero. The
+//! network is trained on paths of a [`DifferentiableSde`] to minimize a convex risk
+//! measure rho(PnL), either the CVaR of the loss or the exponential utility. Both are cash
+//! invariant, so rho(PnL) is the price the hedger needs to charge for the option.
+//!
+//! - Buehler, H., Gonon, L., Teichmann, J., & Wood, B. (2019). Deep hedging.
+//! - Rockafellar, R. T., & Uryasev, S. (2000). Optimization of conditional value-at-risk.
+
+use std::path::Path;
+
+use anyhow::ensure;
+use candle_core::{DType, Device, IndexOp, Module, Result, Tensor};
+use candle_nn::{linear, AdamW, Init, Linear, Optimizer, ParamsAdamW, VarBuilder, VarMap};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  ai::{
+    export::{linear as linear_spec, op, Export, Json, SPEC_VERSION},
+    simulation::DifferentiableSde,
+    training::{Metrics, Trainer},
+  },
+  quant::OptionType,
+};
+
+/// Risk measure of the terminal hedging P&L minimized by the [`DeepHedger`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgingObjective {
+  /// Mean of the worst 1 - alpha fraction of the losses, in the form
+  /// min_w w + E[(-PnL - w)^+] / (1 - alpha) with a trained w
+  Cvar { alpha: f64 },
+  /// Indifference price of the exponential utility with risk aversion lambda,
+  /// ln E[e^(-lambda PnL)] / lambda
+  ExponentialUtility { lambda: f64 },
+}
+
+/// Hedged option and architecture of the [`DeepHedger`]
+#[derive(Debug, Clone, Copy)]
+pub struct DeepHedgerConfig {
+  /// Strike price
+  pub k: f64,
+  /// Time to maturity, the horizon of the simulated paths
+  pub t: f64,
+  pub option_type: OptionType,
+  /// Proportional transaction cost per traded notional
+  pub cost: f64,
+  pub objective: HedgingObjective,
+  /// Width of the two hidden layers
+  pub hidden_size: usize,
+}
+
+impl Default for DeepHedgerConfig {
+  fn default() -> Self {
+    Self {
+      k: 100.0,
+      t: 30.0 / 365.0,
+      option_type: OptionType::Call,
+      cost: 0.0,
+      objective: HedgingObjective::Cvar { alpha: 0.5 },
+      hidden_size: 32,
+    }
+  }
+}
+
+/// Summary of the terminal P&L of a hedging strategy
+#[derive(Debug, Clone, Copy)]
+pub struct HedgingStatistics {
+  pub mean: f64,
+  pub std: f64,
+  /// Value at risk of the loss -PnL at the level of the report
+  pub var: f64,
+  /// Conditional value at risk of the loss -PnL at the level of the report
+  pub cvar: f64,
+  /// Objective of the hedger, the price to charge for the option
+  pub risk: f64,
+}
+
+/// Deep hedging against Black-Scholes delta hedging on the same paths
+#[derive(Debug, Clone, Copy)]
+pub struct HedgingReport {
+  /// Level of the value at risk and the CVaR
+  pub level: f64,
+  pub deep: HedgingStatistics,
+  pub delta: HedgingStatistics,
+}
+
+/// Hedging policy network, see the [module documentation](self)
+pub struct DeepHedger {
+  pub config: DeepHedgerConfig,
+  varmap: VarMap,
+  linear1: Linear,
+  linear2: Linear,
+  output_layer: Linear,
+  /// The level w of the CVaR objective
+  cvar_level: Option<Tensor>,
+  device: Device,
+}
+
+impl DeepHedger {
+  /// Untrained hedger
+  pub fn new(config: DeepHedgerConfig, device: &Device) -> Result<Self> {
+    let varmap = VarMap::new();
+    let vs = VarBuilder::from_varmap(&varmap, DType::F32, device);
+    let hidden = config.hidden_size;
+    let linear1 = linear(3, hidden, vs.pp("linear-1"))?;
+    let linear2 = linear(hidden, hidden, vs.pp("linear-2"))?;
+    let output_layer = linear(hidden, 1, vs.pp("linear-3"))?;
+    let cvar_level = match config.objective {
+      HedgingObjective::Cvar { .. } => {
+        Some(vs.get_with_hints(1, "cvar-level", Init::Const(0.0))?)
+      }
+      HedgingObjective::ExponentialUtility { .. } => None,
+    };
+
+    Ok(Self {
+      config,
+      varmap,
+      linear1,
+      linear2,
+      output_layer,
+      cvar_level,
+      device: device.clone(),
+    })
+  }
+
+  /// Hedger with the weights of an [`export`](Export::export) of a hedger of the same
+  /// config, any extension of the path is replaced
+  pub fn load(config: DeepHedgerConfig, path: impl AsRef<Path>, device: &Device) -> Result<Self> {
+    let mut hedger = Self::new(config, device)?;
+    hedger
+      .varmap
+      .load(path.as_ref().with_extension("safetensors"))?;
+    Ok(hedger)
+  }
+
+  /// Positions of shape (m, n - 1) on price paths of shape (m, n)
+  pub fn deltas(&self, paths: &Tensor) -> Result<Tensor> {
+    let (m, n) = paths.dims2()?;
+    let dt = self.config.t / (n - 1) as f64;
+    // Features of order one, ln(S / K) moves by about sigma sqrt(T) until the maturity
+    let log_moneyness = ((paths / self.config.k)?.log()? / self.config.t.sqrt())?;
+
+    let mut previous = Tensor::zeros(m, DType::F32, &self.device)?;
+    let mut deltas = Vec::with_capacity(n - 1);
+    for i in 0..n - 1 {
+      let tau = Tensor::full(
+        (1.0 - i as f64 * dt / self.config.t) as f32,
+        m,
+        &self.device,
+      )?;
+      let xs = Tensor::stack(&[log_moneyness.i((.., i))?, tau, previous], 1)?;
+      let delta = self.forward(&xs)?.squeeze(1)?;
+      deltas.push(delta.clone());
+      previous = delta;
+    }
+
+    Tensor::stack(&deltas, 1)
+  }
+
+  /// Terminal P&L of shape (m) of the short option hedged with the positions
+  pub fn pnl(&self, paths: &Tensor, deltas: &Tensor) -> Result<Tensor> {
+    let (m, n) = paths.dims2()?;
+    let (before, after) = (paths.narrow(1, 0, n - 1)?, paths.narrow(1, 1, n - 1)?);
+    let gains = deltas.mul(&(after - &before)?)?.sum(1)?;
+
+    let previous = Tensor::cat(
+      &[
+        Tensor::zeros((m, 1), DType::F32, &self.device)?,
+        deltas.narrow(1, 0, n - 2)?,
+      ],
+      1,
+    )?;
+    let costs = ((deltas - previous)?.abs()?.mul(&before)?.sum(1)? * self.config.cost)?;
+
+    let terminal = paths.i((.., n - 1))?;
+    let payoff = match self.config.option_type {
+      OptionType::Call => (terminal - self.config.k)?.relu()?,
+      OptionType::Put => terminal.affine(-1.0, self.config.k)?.relu()?,
+    };
+
+    (gains - costs)? - payoff
+  }
+
+  /// Objective of the P&L, see [`HedgingObjective`]
+  pub fn risk(&self, pnl: &Tensor) -> Result<Tensor> {
+    match (self.config.objective, &self.cvar_level) {
+      (HedgingObjective::Cvar { alpha }, Some(w)) => {
+        let w = w.squeeze(0)?;
+        let excess = pnl.neg()?.broadcast_sub(&w)?.relu()?.mean_all()?;
+        w + (excess / (1.0 - alpha))?
+      }
+      (HedgingObjective::ExponentialUtility { lambda }, _) => {
+        // Log-mean-exp shifted by the maximum
+        let xs = (pnl * -lambda)?;
+        let max = xs.max(0)?.detach();
+        (xs.broadcast_sub(&max)?.exp()?.mean_all()?.log()? + max)? / lambda
+      }
+      (HedgingObjective::Cvar { .. }, None) => {
+        unreachable!("the CVaR level is created with the hedger")
+      }
+    }
+  }
+
+  /// Train on `batches` fresh batches of paths of the model per epoch, the validation loss
+  /// is the objective on a fixed set of `batch_size` paths
+  pub fn train<S>(
+    &self,
+    model: &S,
+    batch_size: usize,
+    batches: usize,
+    trainer: &mut Trainer,
+  ) -> anyhow::Result<Metrics>
+  where
+    S: DifferentiableSde,
+  {
+    ensure!(
+      model.n() >= 3,
+      "the paths need at least two rebalancing dates"
+    );
+    ensure!(
+      (model.t() - self.config.t).abs() < 1e-12,
+      "the horizon of the paths {} differs from the maturity {}",
+      model.t(),
+      self.config.t
+    );
+
+    let validation = model.sample(batch_size, &self.device)?.detach();
+    let mut adam = AdamW::new(
+      self.varmap.all_vars(),
+      ParamsAdamW {
+        lr: trainer.lr,
+        weight_decay: 0.0,
+        ..Default::default()
+      },
+    )?;
+
+    trainer.fit(
+      &mut adam,
+      |_, adam| -> anyhow::Result<Vec<f64>> {
+        let mut losses = Vec::with_capacity(batches);
+        for _ in 0..batches {
+          let paths = model.sample(batch_size, &self.device)?.detach();
+          let loss = self.risk(&self.pnl(&paths, &self.deltas(&paths)?)?)?;
+          adam.backward_step(&loss)?;
+          losses.push(loss.to_scalar::<f32>()? as f64);
+        }
+        Ok(losses)
+      },
+      |_| -> anyhow::Result<Option<f64>> {
+        let loss = self.risk(&self.pnl(&validation, &self.deltas(&validation)?)?)?;
+        Ok(Some(loss.to_scalar::<f32>()? as f64))
+      },
+    )
+  }
+
+  /// Compare the hedger with Black-Scholes delta hedging at the volatility `sigma` on `m`
+  /// paths of the model, the value at risk and the CVaR at the given level, e.g. 0.95
+  pub fn evaluate<S>(&self, model: &S, m: usize, sigma: f64, level: f64) -> Result<HedgingReport>
+  where
+    S: DifferentiableSde,
+  {
+    let paths = model.sample(m, &self.device)?.detach();
+    let deep = self.pnl(&paths, &self.deltas(&paths)?)?;
+    let delta = self.pnl(&paths, &self.black_scholes_deltas(&paths, sigma)?)?;
+
+    Ok(HedgingReport {
+      level,
+      deep: self.statistics(&deep, level)?,
+      delta: self.statistics(&delta, level)?,
+    })
+  }
+
+  /// Black-Scholes deltas at zero rates of shape (m, n - 1) on price paths of shape (m, n)
+  pub fn black_scholes_deltas(&self, paths: &Tensor, sigma: f64) -> Result<Tensor> {
+    let (m, n) = paths.dims2()?;
+    let dt = self.config.t / (n - 1) as f64;
+    let normal = &Normal::default();
+    let paths = paths.to_dtype(DType::F64)?.to_vec2::<f64>()?;
+
+    let deltas = paths
+      .iter()
+      .flat_map(|path| {
+        (0..n - 1).map(move |i| {
+          let tau = self.config.t - i as f64 * dt;
+          let d1 =
+            ((path[i] / self.config.k).ln() + 0.5 * sigma.powi(2) * tau) / (sigma * tau.sqrt());
+          let delta = normal.cdf(d1);
+          match self.config.option_type {
+            OptionType::Call => delta as f32,
+            OptionType::Put => (delta - 1.0) as f32,
+          }
+        })
+      })
+      .collect::<Vec<_>>();
+
+    Tensor::from_vec(deltas, (m, n - 1), &self.device)
+  }
+
+  fn statistics(&self, pnl: &Tensor, level: f64) -> Result<HedgingStatistics> {
+    let pnl = pnl.to_dtype(DType::F64)?.to_vec1::<f64>()?;
+    let count = pnl.len() as f64;
+    let mean = pnl.iter().sum::<f64>() / count;
+    let std = (pnl.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (count - 1.0)).sqrt();
+
+    // Losses in decreasing order, the tail are the worst 1 - level of them
+    let mut losses = pnl.iter().map(|x| -x).collect::<Vec<_>>();
+    losses.sort_by(|a, b| b.total_cmp(a));
+    let tail_cvar = |level: f64| {
+      let tail = (((1.0 - level) * count).ceil() as usize).clamp(1, losses.len());
+      (
+        losses[tail - 1],
+        losses[..tail].iter().sum::<f64>() / tail as f64,
+      )
+    };
+    let (var, cvar) = tail_cvar(level);
+
+    let risk = match self.config.objective {
+      HedgingObjective::Cvar { alpha } => tail_cvar(alpha).1,
+      HedgingObjective::ExponentialUtility { lambda } => {
+        let max = losses[0] * lambda;
+        (losses
+          .iter()
+          .map(|loss| (lambda * loss - max).exp())
+          .sum::<f64>()
+          / count)
+          .ln()
+          / lambda
+          + max / lambda
+      }
+    };
+
+    Ok(HedgingStatistics {
+      mean,
+      std,
+      var,
+      cvar,
+      risk,
+    })
+  }
+}
+
+impl Module for DeepHedger {
+  /// Positions of shape (batch, 1) of the features (ln(S / K) / sqrt(T), tau / T, previous
+  /// delta)
+  fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+    let xs = self.linear1.forward(xs)?.relu()?;
+    let xs = self.linear2.forward(&xs)?.relu()?;
+    self.output_layer.forward(&xs)
+  }
+}
+
+impl Export for DeepHedger {
+  fn varmap(&self) -> &VarMap {
+    &self.varmap
+  }
+
+  /// Two hidden layers with ReLU and a linear output, in float32, applied at every date
+  fn spec(&self) -> Json {
+    let hidden = self.config.hidden_size;
+    let objective = match self.config.objective {
+      HedgingObjective::Cvar { alpha } => Json::object([
+        ("type", "cvar".into()),
+        ("alpha", alpha.into()),
+        ("level", "cvar-level".into()),
+      ]),
+      HedgingObjective::ExponentialUtility { lambda } => Json::object([
+        ("type", "exponential_utility".into()),
+        ("lambda", lambda.into()),
+      ]),
+    };
+    let option_type = match self.config.option_type {
+      OptionType::Call => "call",
+      OptionType::Put => "put",
+    };
+
+    Json::object([
+      ("format", "stochastic-rs".into()),
+      ("version", SPEC_VERSION.into()),
+      ("model", "deep_hedger".into()),
+      ("dtype", "float32".into()),
+      (
+        "input",
+        Json::object([
+          ("shape", vec![0usize, 3].into()),
+          (
+            "features",
+            vec![
+              "ln(S / K) / sqrt(t)",
+              "time to maturity / t",
+              "previous delta",
+            ]
+            .into(),
+          ),
+        ]),
+      ),
+      ("output", Json::object([("shape", vec![0usize, 1].into())])),
+      (
+        "forward",
+        Json::Array(vec![
+          linear_spec("linear-1", 3, hidden),
+          op("relu"),
+          linear_spec("linear-2", hidden, hidden),
+          op("relu"),
+          linear_spec("linear-3", hidden, 1),
+        ]),
+      ),
+      (
+        "hedging",
+        Json::object([
+          ("k", self.config.k.into()),
+          ("t", self.config.t.into()),
+          ("option_type", option_type.into()),
+          ("cost", self.config.cost.into()),
+          ("objective", objective),
+        ]),
+      ),
+      (
+        "notes",
+        "The network is applied at every rebalancing date in order, the previous delta is 0 at the first date. A batch dimension of 0 is dynamic.".into(),
+      ),
+    ])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ai::{
+    simulation::{constant, TensorGbm},
+    training::LrSchedule,
+  };
+
+  fn gbm(sigma: f64, t: f64, n: usize, device: &Device) -> Result<TensorGbm> {
+    Ok(TensorGbm::new(
+      constant(100.0, device)?,
+      constant(0.0, device)?,
+      constant(sigma, device)?,
+      n,
+      t,
+    ))
+  }
+
+  #[test]
+  fn deep_hedge_beats_delta_with_costs() -> anyhow::Result<()> {
+    let device = Device::Cpu;
+    let config = DeepHedgerConfig {
+      cost: 0.005,
+      ..Default::default()
+    };
+    let model = gbm(0.2, config.t, 31, &device)?;
+    let hedger = DeepHedger::new(config, &device)?;
+
+    let metrics = hedger.train(
+      &model,
+      2048,
+      20,
+      &mut Trainer::new(40, 5e-3)
+        .with_schedule(LrSchedule::Cosine { min_lr: 1e-4 })
+        .with_verbose(false),
+    )?;
+    assert_eq!(metrics.epochs.len(), 40);
+
+    let report = hedger.evaluate(&model, 1 << 15, 0.2, 0.95)?;
+    println!("{:?}", report);
+    assert!(report.deep.risk < report.delta.risk, "{:?}", report);
+    assert!(report.deep.cvar >= report.deep.var);
+    Ok(())
+  }
+
+  #[test]
+  fn exponential_utility_is_cash_invariant() -> Result<()> {
+    let device = Device::Cpu;
+    let hedger = DeepHedger::new(
+      DeepHedgerConfig {
+        objective: HedgingObjective::ExponentialUtility { lambda: 2.0 },
+        ..Default::default()
+      },
+      &device,
+    )?;
+
+    let pnl = Tensor::new(&[1.5f32, 1.5, 1.5], &device)?;
+    let risk = hedger.risk(&pnl)?.to_scalar::<f32>()?;
+    assert!((risk + 1.5).abs() < 1e-6);
+    Ok(())
+  }
+
+  #[test]
+  fn export_and_load_hedger() -> anyhow::Result<()> {
+    let device = Device::Cpu;
+    let config = DeepHedgerConfig::default();
+    let hedger = DeepHedger::new(config, &device)?;
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("hedger");
+    hedger.export(&path)?;
+
+    let spec = std::fs::read_to_string(path.with_extension("json"))?;
+    for tensor in hedger.spec().tensors() {
+      assert!(spec.contains(&tensor));
+    }
+
+    let loaded = DeepHedger::load(config, &path, &device)?;
+    let paths = gbm(0.2, config.t, 11, &device)?.sample(16, &device)?;
+    let difference = (hedger.deltas(&paths)? - loaded.deltas(&paths)?)?
+      .abs()?
+      .flatten_all()?
+      .max(0)?
+      .to_scalar::<f32>()?;
+    assert_eq!(difference, 0.0);
+    Ok(())
+  }
+}
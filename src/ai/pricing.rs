@@ -0,0 +1,3 @@
+//! Neural network pricers of options with early exercise.
+
+pub mod optimal_stopping;
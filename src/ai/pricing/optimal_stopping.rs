@@ -0,0 +1,836 @@
+//! Deep optimal stopping of Bermudan options on simulated paths.
+//!
+//! The exercise decision at each of the dates t_1 < ... < t_{N-1} is a feed-forward
+//! network f_n in (0, 1) of the state and the payoff, at the maturity t_N the option is
+//! exercised anyway. The networks are trained backward in time: f_n maximizes the mean of
+//! g_n f_n + G_{n+1} (1 - f_n), where g_n is the discounted payoff at t_n and G_{n+1} the
+//! discounted payoff at the exercise date of the already trained policy after t_n.
+//!
+//! Exercising on fresh paths as soon as f_n >= 1/2 is a stopping time, so its mean payoff
+//! is a lower bound of the price. The continuation values of the policy, estimated by
+//! nested simulation, define a martingale M whose dual E[max_n (g_n - M_n)] is an upper
+//! bound. The gap between the bounds shows how far the learned policy is from the optimal
+//! one, which the Longstaff-Schwartz regression does not tell, and the networks scale to
+//! baskets where a polynomial basis of the state does not.
+//!
+//! - Becker, S., Cheridito, P., & Jentzen, A. (2019). Deep optimal stopping.
+//! - Becker, S., Cheridito, P., & Jentzen, A. (2020). Pricing and hedging American-style options with deep learning.
+
+use anyhow::ensure;
+use candle_core::{DType, Device, Module, Result, Tensor};
+use candle_nn::{linear, ops, AdamW, Linear, Optimizer, ParamsAdamW, VarBuilder, VarMap};
+use impl_new_derive::ImplNew;
+use ndarray::{concatenate, s, Array1, Array2, Array3, ArrayView1, ArrayView2, Axis};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  ai::training::{Metrics, Trainer},
+  quant::monte_carlo::MCEstimate,
+  stochastic::{
+    diffusion::cir::DiscretizationScheme,
+    noise::cgns::CGNS,
+    volatility::{heston::Heston, HestonPow},
+    Sampling2D,
+  },
+};
+
+/// Markov model of the underlying observed at N equidistant exercise dates after 0
+pub trait ExerciseModel: Sync {
+  /// Dimension of the state
+  fn dim(&self) -> usize;
+
+  /// Number N of exercise dates after 0, the last one is the maturity
+  fn dates(&self) -> usize;
+
+  /// Maturity
+  fn maturity(&self) -> f64;
+
+  /// Risk-free rate of the discounting
+  fn rate(&self) -> f64;
+
+  /// State at time 0
+  fn initial_state(&self) -> Array1<f64>;
+
+  /// `m` paths of the state at the dates from, ..., N started at `state` at the date
+  /// `from`, of shape (m, N - from + 1, dim)
+  fn simulate_from(&self, state: ArrayView1<f64>, from: usize, m: usize) -> Array3<f64>;
+
+  /// `m` paths from the initial state, of shape (m, N + 1, dim)
+  fn simulate(&self, m: usize) -> Array3<f64> {
+    self.simulate_from(self.initial_state().view(), 0, m)
+  }
+
+  /// Time between two exercise dates
+  fn dt(&self) -> f64 {
+    self.maturity() / self.dates() as f64
+  }
+}
+
+/// Geometric Brownian motions with a common pairwise correlation under the risk-neutral
+/// measure, the state is the vector of prices
+#[derive(ImplNew, Clone, Debug)]
+pub struct GbmBasket {
+  /// Initial prices
+  pub s0: Array1<f64>,
+  /// Volatilities
+  pub sigma: Array1<f64>,
+  /// Correlation of every pair of Brownian motions, in [0, 1)
+  pub rho: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Maturity
+  pub t: f64,
+  /// Number of exercise dates after 0
+  pub dates: usize,
+}
+
+impl ExerciseModel for GbmBasket {
+  fn dim(&self) -> usize {
+    self.s0.len()
+  }
+
+  fn dates(&self) -> usize {
+    self.dates
+  }
+
+  fn maturity(&self) -> f64 {
+    self.t
+  }
+
+  fn rate(&self) -> f64 {
+    self.r
+  }
+
+  fn initial_state(&self) -> Array1<f64> {
+    self.s0.clone()
+  }
+
+  /// Exact lognormal steps, the correlated Brownian increments share one common factor
+  fn simulate_from(&self, state: ArrayView1<f64>, from: usize, m: usize) -> Array3<f64> {
+    assert!((0.0..1.0).contains(&self.rho), "rho must be in [0, 1)");
+    let (d, steps, dt) = (self.dim(), self.dates - from, self.dt());
+    let drift = self
+      .sigma
+      .mapv(|sigma| (self.r - self.q.unwrap_or(0.0) - 0.5 * sigma.powi(2)) * dt);
+    let diffusion = self.sigma.mapv(|sigma| sigma * dt.sqrt());
+    let (common, idiosyncratic) = (self.rho.sqrt(), (1.0 - self.rho).sqrt());
+
+    let mut rng = thread_rng();
+    let mut paths = Array3::zeros((m, steps + 1, d));
+    for mut path in paths.outer_iter_mut() {
+      path.row_mut(0).assign(&state);
+      for j in 1..=steps {
+        let z0: f64 = rng.sample(StandardNormal);
+        for i in 0..d {
+          let z: f64 = rng.sample(StandardNormal);
+          let w = common * z0 + idiosyncratic * z;
+          path[[j, i]] = path[[j - 1, i]] * (drift[i] + diffusion[i] * w).exp();
+        }
+      }
+    }
+
+    paths
+  }
+}
+
+/// Heston model under the risk-neutral measure, the state is (S, v)
+///
+/// The paths are simulated with the quadratic exponential scheme on `substeps` steps
+/// between two exercise dates.
+#[derive(ImplNew, Clone, Debug)]
+pub struct HestonUnderlying {
+  /// Initial price
+  pub s0: f64,
+  /// Initial variance
+  pub v0: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of the variance
+  pub sigma: f64,
+  /// Correlation of the price and the variance
+  pub rho: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Maturity
+  pub t: f64,
+  /// Number of exercise dates after 0
+  pub dates: usize,
+  /// Simulation steps between two exercise dates, 16 if None
+  pub substeps: Option<usize>,
+}
+
+impl ExerciseModel for HestonUnderlying {
+  fn dim(&self) -> usize {
+    2
+  }
+
+  fn dates(&self) -> usize {
+    self.dates
+  }
+
+  fn maturity(&self) -> f64 {
+    self.t
+  }
+
+  fn rate(&self) -> f64 {
+    self.r
+  }
+
+  fn initial_state(&self) -> Array1<f64> {
+    Array1::from(vec![self.s0, self.v0])
+  }
+
+  fn simulate_from(&self, state: ArrayView1<f64>, from: usize, m: usize) -> Array3<f64> {
+    let substeps = self.substeps.unwrap_or(16);
+    let dates = self.dates - from;
+    let steps = dates * substeps;
+    let t = dates as f64 * self.dt();
+
+    let heston = Heston::new(
+      Some(state[0]),
+      Some(state[1]),
+      self.kappa,
+      self.theta,
+      self.sigma,
+      self.rho,
+      self.r - self.q.unwrap_or(0.0),
+      steps + 1,
+      Some(t),
+      HestonPow::Sqrt,
+      None,
+      Some(DiscretizationScheme::QuadraticExponential),
+      None,
+      CGNS::new(self.rho, steps, Some(t), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+
+    let mut paths = Array3::zeros((m, dates + 1, 2));
+    for mut path in paths.outer_iter_mut() {
+      let [s, v] = heston.sample();
+      for j in 0..=dates {
+        path[[j, 0]] = s[j * substeps];
+        path[[j, 1]] = v[j * substeps];
+      }
+    }
+
+    paths
+  }
+}
+
+/// Training setup of the [`DeepOptimalStopping`] networks
+#[derive(Debug, Clone, Copy)]
+pub struct StoppingConfig {
+  /// Width of the two hidden layers, dim + 40 if None as in Becker et al.
+  pub hidden: Option<usize>,
+  /// Number of simulated training paths, shared by all exercise dates
+  pub train_paths: usize,
+  pub batch_size: usize,
+}
+
+impl Default for StoppingConfig {
+  fn default() -> Self {
+    Self {
+      hidden: None,
+      train_paths: 1 << 15,
+      batch_size: 1024,
+    }
+  }
+}
+
+/// Exercise decision at one date, f(x) = sigmoid(a_3 o relu o a_2 o relu o a_1)(x) of the
+/// standardized state and payoff
+struct DecisionNet {
+  varmap: VarMap,
+  linear1: Linear,
+  linear2: Linear,
+  output_layer: Linear,
+  /// Mean and standard deviation of the features on the training paths
+  scaling: (Tensor, Tensor),
+}
+
+impl DecisionNet {
+  fn new(device: &Device, input_dim: usize, hidden: usize) -> Result<Self> {
+    let varmap = VarMap::new();
+    let vs = VarBuilder::from_varmap(&varmap, DType::F32, device);
+    let linear1 = linear(input_dim, hidden, vs.pp("linear-1"))?;
+    let linear2 = linear(hidden, hidden, vs.pp("linear-2"))?;
+    let output_layer = linear(hidden, 1, vs.pp("linear-3"))?;
+    let scaling = (
+      Tensor::zeros(input_dim, DType::F32, device)?,
+      Tensor::ones(input_dim, DType::F32, device)?,
+    );
+
+    Ok(Self {
+      varmap,
+      linear1,
+      linear2,
+      output_layer,
+      scaling,
+    })
+  }
+}
+
+impl Module for DecisionNet {
+  /// Probability of exercising, of shape (batch)
+  fn forward(&self, xs: &Tensor) -> Result<Tensor> {
+    let (mean, std) = &self.scaling;
+    let xs = xs.broadcast_sub(mean)?.broadcast_div(std)?;
+    let xs = self.linear1.forward(&xs)?.relu()?;
+    let xs = self.linear2.forward(&xs)?.relu()?;
+    ops::sigmoid(&self.output_layer.forward(&xs)?)?.squeeze(1)
+  }
+}
+
+/// Lower and upper bound of the price of a Bermudan option
+#[derive(Debug, Clone, Copy)]
+pub struct StoppingBounds {
+  /// Mean discounted payoff of the learned policy
+  pub lower: MCEstimate,
+  /// Dual bound of the martingale of the learned policy
+  pub upper: MCEstimate,
+}
+
+impl StoppingBounds {
+  /// Midpoint of the bounds
+  pub fn point(&self) -> f64 {
+    0.5 * (self.lower.mean + self.upper.mean)
+  }
+
+  /// Confidence interval (L - z se_L, U + z se_U) of the price with the given level, e.g.
+  /// 0.95, covering the Monte Carlo error of both bounds
+  pub fn confidence_interval(&self, level: f64) -> (f64, f64) {
+    assert!(level > 0.0 && level < 1.0, "level must be in (0, 1)");
+    let z = Normal::default().inverse_cdf(0.5 + level / 2.0);
+    (
+      self.lower.mean - z * self.lower.std_error,
+      self.upper.mean + z * self.upper.std_error,
+    )
+  }
+}
+
+/// Sample mean with its standard error and the 95% half-width
+fn estimate(samples: &Array1<f64>) -> MCEstimate {
+  let paths = samples.len();
+  let std_error = match paths {
+    0 | 1 => f64::INFINITY,
+    _ => (samples.var(1.0) / paths as f64).sqrt(),
+  };
+
+  MCEstimate {
+    paths,
+    mean: samples.mean().unwrap_or(f64::NAN),
+    std_error,
+    half_width: Normal::default().inverse_cdf(0.975) * std_error,
+  }
+}
+
+/// Exercise policy of a Bermudan option learned by deep optimal stopping, see the
+/// [module documentation](self)
+///
+/// The payoff is a function of the state at an exercise date, e.g. of the mean of the
+/// prices for a basket put or of the first coordinate for the Heston state (S, v).
+pub struct DeepOptimalStopping<M, P>
+where
+  M: ExerciseModel,
+  P: Fn(ArrayView1<f64>) -> f64 + Sync,
+{
+  pub model: M,
+  pub payoff: P,
+  pub config: StoppingConfig,
+  device: Device,
+  /// Networks of the dates 1, ..., N - 1
+  nets: Vec<DecisionNet>,
+  /// Whether the option is exercised at time 0
+  stop_at_zero: bool,
+}
+
+impl<M, P> DeepOptimalStopping<M, P>
+where
+  M: ExerciseModel,
+  P: Fn(ArrayView1<f64>) -> f64 + Sync,
+{
+  /// Untrained policy, which exercises whenever the option is in the money
+  pub fn new(model: M, payoff: P, config: StoppingConfig, device: &Device) -> Result<Self> {
+    let input_dim = model.dim() + 1;
+    let hidden = config.hidden.unwrap_or(model.dim() + 40);
+    let nets = (1..model.dates())
+      .map(|_| DecisionNet::new(device, input_dim, hidden))
+      .collect::<Result<Vec<_>>>()?;
+
+    Ok(Self {
+      model,
+      payoff,
+      config,
+      device: device.clone(),
+      nets,
+      stop_at_zero: false,
+    })
+  }
+
+  /// Train the networks backward from the last date before maturity to the first one
+  ///
+  /// Every network is fitted by a copy of the trainer on the same training paths, the
+  /// metrics are returned in the order of the dates 1, ..., N - 1. The decision at time 0
+  /// compares the payoff with the mean value of the policy on the training paths.
+  pub fn train(&mut self, trainer: &Trainer) -> anyhow::Result<Vec<Metrics>> {
+    let (dates, batch_size) = (self.model.dates(), self.config.batch_size);
+    ensure!(dates > 0, "the model needs at least one exercise date");
+    ensure!(batch_size > 0, "batch_size must be positive");
+
+    let paths = self.model.simulate(self.config.train_paths);
+    let payoffs = self.discounted_payoffs(&paths, 0);
+    let m = paths.dim().0;
+    let num_batches = m.div_ceil(batch_size);
+
+    // Discounted payoffs at the exercise dates of the trained policy
+    let mut values = payoffs.column(dates).to_owned();
+    let mut metrics = Vec::with_capacity(dates.saturating_sub(1));
+
+    for n in (1..dates).rev() {
+      let features = concatenate(
+        Axis(1),
+        &[paths.slice(s![.., n, ..]), payoffs.slice(s![.., n..n + 1])],
+      )?;
+      let mean = features.mean_axis(Axis(0)).unwrap();
+      let std = features
+        .std_axis(Axis(0), 0.0)
+        .mapv(|std| if std > 1e-12 { std } else { 1.0 });
+
+      let scaling = (self.tensor(mean.view())?, self.tensor(std.view())?);
+      self.nets[n - 1].scaling = scaling;
+      let net = &self.nets[n - 1];
+
+      let xs = self.features(paths.slice(s![.., n, ..]), payoffs.column(n))?;
+      let gs = self.tensor(payoffs.column(n))?;
+      let cs = self.tensor(values.view())?;
+
+      let mut adam = AdamW::new(
+        net.varmap.all_vars(),
+        ParamsAdamW {
+          lr: trainer.lr,
+          ..Default::default()
+        },
+      )?;
+      let mut order = (0..m as u32).collect::<Vec<_>>();
+
+      let date_metrics = trainer.clone().fit(
+        &mut adam,
+        |_, adam| -> anyhow::Result<Vec<f64>> {
+          order.shuffle(&mut thread_rng());
+          let order = Tensor::from_slice(&order, m, &self.device)?;
+          let mut losses = Vec::with_capacity(num_batches);
+
+          for batch_idx in 0..num_batches {
+            let start = batch_idx * batch_size;
+            let idx = order.narrow(0, start, batch_size.min(m - start))?;
+            let (x, g, c) = (
+              xs.index_select(&idx, 0)?,
+              gs.index_select(&idx, 0)?,
+              cs.index_select(&idx, 0)?,
+            );
+
+            // Negative mean payoff of exercising with probability f
+            let f = net.forward(&x)?;
+            let loss = (&c + f.mul(&(&g - &c)?)?)?.mean_all()?.neg()?;
+            adam.backward_step(&loss)?;
+            losses.push(loss.to_scalar::<f32>()? as f64);
+          }
+
+          Ok(losses)
+        },
+        |_| -> anyhow::Result<Option<f64>> { Ok(None) },
+      )?;
+      metrics.push(date_metrics);
+
+      let stops = self.decide(n, paths.slice(s![.., n, ..]), payoffs.column(n))?;
+      for (i, stop) in stops.into_iter().enumerate() {
+        if stop {
+          values[i] = payoffs[[i, n]];
+        }
+      }
+    }
+
+    let g0 = payoffs[[0, 0]];
+    self.stop_at_zero = g0 > 0.0 && g0 >= values.mean().unwrap_or(0.0);
+    metrics.reverse();
+
+    Ok(metrics)
+  }
+
+  /// Exercise decisions of the policy at the given date for the rows of `states`
+  pub fn exercise(&self, date: usize, states: ArrayView2<f64>) -> Result<Vec<bool>> {
+    let payoffs = self.discount(date) * states.map_axis(Axis(1), |x| (self.payoff)(x));
+    self.decide(date, states, payoffs.view())
+  }
+
+  /// Lower bound of the price, the mean discounted payoff of the policy on `m` fresh paths
+  pub fn lower_bound(&self, m: usize) -> Result<MCEstimate> {
+    if self.stop_at_zero {
+      return Ok(MCEstimate {
+        paths: m,
+        mean: (self.payoff)(self.model.initial_state().view()),
+        ..Default::default()
+      });
+    }
+
+    let paths = self.model.simulate(m);
+    Ok(estimate(&self.stopped_payoffs(&paths, 0)?))
+  }
+
+  /// Upper bound of the price by the dual of the martingale of the policy on `m` outer
+  /// paths, with the continuation values estimated by `inner` paths per outer path and
+  /// date
+  ///
+  /// The martingale has the increments M_n - M_{n-1} = f_n g_n + (1 - f_n) C_n - C_{n-1},
+  /// where f_n is the decision and C_n the discounted continuation value of the policy at
+  /// t_n. The nested simulation costs m N inner paths.
+  pub fn upper_bound(&self, m: usize, inner: usize) -> Result<MCEstimate> {
+    let dates = self.model.dates();
+    let paths = self.model.simulate(m);
+    let payoffs = self.discounted_payoffs(&paths, 0);
+
+    let model = &self.model;
+    let mut continuation = Array2::<f64>::zeros((m, dates));
+    for n in 0..dates {
+      let inner_paths = (0..m)
+        .into_par_iter()
+        .map(|i| model.simulate_from(paths.slice(s![i, n, ..]), n, inner))
+        .collect::<Vec<_>>();
+      let inner_paths = concatenate(
+        Axis(0),
+        &inner_paths.iter().map(|p| p.view()).collect::<Vec<_>>(),
+      )
+      .unwrap();
+      let values = self.stopped_payoffs(&inner_paths, n)?;
+
+      for i in 0..m {
+        continuation[[i, n]] = values.slice(s![i * inner..(i + 1) * inner]).mean().unwrap();
+      }
+    }
+
+    let mut martingale = Array1::<f64>::zeros(m);
+    let mut dual = payoffs.column(0).to_owned();
+    for n in 1..=dates {
+      let stops = match n {
+        n if n == dates => vec![true; m],
+        n => self.decide(n, paths.slice(s![.., n, ..]), payoffs.column(n))?,
+      };
+
+      for i in 0..m {
+        let value = match stops[i] {
+          true => payoffs[[i, n]],
+          false => continuation[[i, n]],
+        };
+        martingale[i] += value - continuation[[i, n - 1]];
+        dual[i] = dual[i].max(payoffs[[i, n]] - martingale[i]);
+      }
+    }
+
+    Ok(estimate(&dual))
+  }
+
+  /// Lower bound on `lower_paths` paths and upper bound on `upper_paths` outer paths with
+  /// `inner_paths` inner paths each
+  pub fn bounds(
+    &self,
+    lower_paths: usize,
+    upper_paths: usize,
+    inner_paths: usize,
+  ) -> Result<StoppingBounds> {
+    Ok(StoppingBounds {
+      lower: self.lower_bound(lower_paths)?,
+      upper: self.upper_bound(upper_paths, inner_paths)?,
+    })
+  }
+
+  /// Discount factor from the date to time 0
+  fn discount(&self, date: usize) -> f64 {
+    (-self.model.rate() * date as f64 * self.model.dt()).exp()
+  }
+
+  /// Payoffs discounted to time 0 of paths starting at the date `from`, of shape
+  /// (m, N - from + 1)
+  fn discounted_payoffs(&self, paths: &Array3<f64>, from: usize) -> Array2<f64> {
+    let (m, steps, _) = paths.dim();
+    Array2::from_shape_fn((m, steps), |(i, j)| {
+      self.discount(from + j) * (self.payoff)(paths.slice(s![i, j, ..]))
+    })
+  }
+
+  /// Discounted payoffs at the first date after `from` the policy exercises on the paths
+  /// starting at the date `from`
+  fn stopped_payoffs(&self, paths: &Array3<f64>, from: usize) -> Result<Array1<f64>> {
+    let payoffs = self.discounted_payoffs(paths, from);
+    let steps = payoffs.ncols() - 1;
+    let mut values = payoffs.column(steps).to_owned();
+
+    for j in (1..steps).rev() {
+      let stops = self.decide(from + j, paths.slice(s![.., j, ..]), payoffs.column(j))?;
+      for (i, stop) in stops.into_iter().enumerate() {
+        if stop {
+          values[i] = payoffs[[i, j]];
+        }
+      }
+    }
+
+    Ok(values)
+  }
+
+  /// Hard decisions f_n >= 1/2, the option is only exercised in the money
+  fn decide(
+    &self,
+    date: usize,
+    states: ArrayView2<f64>,
+    payoffs: ArrayView1<f64>,
+  ) -> Result<Vec<bool>> {
+    let stops = match date {
+      0 => vec![self.stop_at_zero; payoffs.len()],
+      date if date >= self.model.dates() => vec![true; payoffs.len()],
+      date => {
+        let xs = self.features(states, payoffs)?;
+        let probabilities = self.nets[date - 1].forward(&xs)?.to_vec1::<f32>()?;
+        probabilities.into_iter().map(|p| p >= 0.5).collect()
+      }
+    };
+
+    Ok(
+      stops
+        .into_iter()
+        .zip(payoffs)
+        .map(|(stop, &payoff)| stop && payoff > 0.0)
+        .collect(),
+    )
+  }
+
+  /// Network inputs of shape (m, dim + 1), the states followed by the discounted payoffs
+  fn features(&self, states: ArrayView2<f64>, payoffs: ArrayView1<f64>) -> Result<Tensor> {
+    let (m, d) = states.dim();
+    let data = states
+      .outer_iter()
+      .zip(payoffs)
+      .flat_map(|(x, &g)| {
+        x.iter()
+          .chain([g].iter())
+          .map(|&v| v as f32)
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+    Tensor::from_vec(data, (m, d + 1), &self.device)
+  }
+
+  fn tensor(&self, values: ArrayView1<f64>) -> Result<Tensor> {
+    let data = values.iter().map(|&v| v as f32).collect::<Vec<_>>();
+    Tensor::from_vec(data, values.len(), &self.device)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ai::training::LrSchedule;
+
+  /// Bermudan put on a binomial tree with the exercise dates on every `per_date` step
+  fn binomial_put(s: f64, k: f64, r: f64, sigma: f64, t: f64, dates: usize, bermudan: bool) -> f64 {
+    let per_date = 200;
+    let steps = dates * per_date;
+    let dt = t / steps as f64;
+    let u = (sigma * dt.sqrt()).exp();
+    let p = ((r * dt).exp() - 1.0 / u) / (u - 1.0 / u);
+    let discount = (-r * dt).exp();
+
+    let mut values = (0..=steps)
+      .map(|j| (k - s * u.powi(2 * j as i32 - steps as i32)).max(0.0))
+      .collect::<Vec<_>>();
+    for step in (0..steps).rev() {
+      for j in 0..=step {
+        let value = discount * (p * values[j + 1] + (1.0 - p) * values[j]);
+        let exercise = (k - s * u.powi(2 * j as i32 - step as i32)).max(0.0);
+        values[j] = match bermudan && step % per_date == 0 {
+          true => value.max(exercise),
+          false => value,
+        };
+      }
+    }
+
+    values[0]
+  }
+
+  #[test]
+  fn bermudan_put_bounds() {
+    let (s, k, r, sigma, t, dates) = (100.0, 100.0, 0.06, 0.2, 1.0, 10);
+    let model = GbmBasket::new(
+      Array1::from(vec![s]),
+      Array1::from(vec![sigma]),
+      0.0,
+      r,
+      None,
+      t,
+      dates,
+    );
+    let config = StoppingConfig {
+      train_paths: 1 << 14,
+      batch_size: 512,
+      ..Default::default()
+    };
+    let mut stopping = DeepOptimalStopping::new(
+      model,
+      move |x: ArrayView1<f64>| (k - x[0]).max(0.0),
+      config,
+      &Device::Cpu,
+    )
+    .unwrap();
+    let metrics = stopping
+      .train(&Trainer::new(20, 5e-3).with_verbose(false))
+      .unwrap();
+    assert_eq!(metrics.len(), dates - 1);
+
+    let bounds = stopping.bounds(1 << 15, 256, 256).unwrap();
+    let bermudan = binomial_put(s, k, r, sigma, t, dates, true);
+    let european = binomial_put(s, k, r, sigma, t, dates, false);
+    println!("{:?}, Bermudan {bermudan}, European {european}", bounds);
+
+    let (lower, upper) = (bounds.lower, bounds.upper);
+    assert!(lower.mean < bermudan + 4.0 * lower.std_error + 0.02);
+    assert!(upper.mean > bermudan - 4.0 * upper.std_error - 0.02);
+    assert!(lower.mean > european + 0.3);
+    assert!(upper.mean - lower.mean < 0.3);
+
+    let (low, high) = bounds.confidence_interval(0.95);
+    assert!(low < bounds.point() && bounds.point() < high);
+  }
+
+  /// Symmetric max-call on two assets of Becker et al., the price is 13.902
+  fn max_call_bounds(
+    train_paths: usize,
+    trainer: &Trainer,
+    lower_paths: usize,
+    upper_paths: usize,
+  ) -> StoppingBounds {
+    let (k, dates) = (100.0, 9);
+    let model = GbmBasket::new(
+      Array1::from(vec![100.0; 2]),
+      Array1::from(vec![0.2; 2]),
+      0.0,
+      0.05,
+      Some(0.1),
+      3.0,
+      dates,
+    );
+    assert_eq!(model.simulate(8).dim(), (8, dates + 1, 2));
+
+    let config = StoppingConfig {
+      train_paths,
+      batch_size: 512,
+      ..Default::default()
+    };
+    let mut stopping = DeepOptimalStopping::new(
+      model,
+      move |x: ArrayView1<f64>| (x.fold(f64::MIN, |a, &b| a.max(b)) - k).max(0.0),
+      config,
+      &Device::Cpu,
+    )
+    .unwrap();
+    stopping.train(trainer).unwrap();
+
+    let bounds = stopping.bounds(lower_paths, upper_paths, 128).unwrap();
+    println!("{:?}", bounds);
+    bounds
+  }
+
+  #[test]
+  fn max_call_basket() {
+    let bounds = max_call_bounds(
+      1 << 13,
+      &Trainer::new(10, 5e-3).with_verbose(false),
+      1 << 14,
+      256,
+    );
+
+    // Far above the European max-call of 11.16 already on a small training budget
+    assert!((bounds.lower.mean - 13.902).abs() < 1.0);
+    assert!((bounds.upper.mean - 13.902).abs() < 1.0);
+    assert!(bounds.lower.mean < bounds.upper.mean + 4.0 * bounds.upper.std_error);
+  }
+
+  #[test]
+  #[ignore = "Accuracy"]
+  fn max_call_basket_accuracy() {
+    let bounds = max_call_bounds(
+      1 << 17,
+      &Trainer::new(8, 5e-3)
+        .with_schedule(LrSchedule::Cosine { min_lr: 1e-4 })
+        .with_verbose(false),
+      1 << 17,
+      1 << 10,
+    );
+
+    // Becker et al. reach 13.880 and 13.910 on millions of fresh paths, on 2^17 shared
+    // paths both bounds end 0.2 to 0.3 away from the price
+    assert!((bounds.lower.mean - 13.902).abs() < 0.4);
+    assert!((bounds.upper.mean - 13.902).abs() < 0.4);
+  }
+
+  #[test]
+  fn heston_bermudan_put() {
+    let model = HestonUnderlying::new(
+      100.0,
+      0.04,
+      1.5,
+      0.04,
+      0.3,
+      -0.7,
+      0.05,
+      None,
+      0.5,
+      5,
+      Some(8),
+    );
+    let state = Array1::from(vec![90.0, 0.05]);
+    let paths = model.simulate_from(state.view(), 2, 16);
+    assert_eq!(paths.dim(), (16, 4, 2));
+    assert_eq!(paths.slice(s![0, 0, ..]), state);
+    assert!(paths.iter().all(|&x| x >= 0.0));
+
+    let config = StoppingConfig {
+      train_paths: 1 << 13,
+      batch_size: 512,
+      ..Default::default()
+    };
+    let mut stopping = DeepOptimalStopping::new(
+      model,
+      |x: ArrayView1<f64>| (100.0 - x[0]).max(0.0),
+      config,
+      &Device::Cpu,
+    )
+    .unwrap();
+    stopping
+      .train(&Trainer::new(10, 5e-3).with_verbose(false))
+      .unwrap();
+
+    // In the money early on the decision is to exercise, far out of the money never
+    let decisions = stopping
+      .exercise(
+        4,
+        Array2::from_shape_vec((2, 2), vec![80.0, 0.04, 130.0, 0.04])
+          .unwrap()
+          .view(),
+      )
+      .unwrap();
+    assert_eq!(decisions, [true, false]);
+
+    let bounds = stopping.bounds(1 << 14, 64, 128).unwrap();
+    println!("{:?}", bounds);
+    assert!(bounds.lower.mean > 0.0);
+    assert!(bounds.lower.mean < bounds.upper.mean + 4.0 * bounds.upper.std_error);
+  }
+}
@@ -0,0 +1,348 @@
+//! Training harness shared by the networks of the `ai` module.
+//!
+//! [`Trainer`] runs the epoch loop: it sets the learning rate of the optimizer from an
+//! [`LrSchedule`], collects the batch losses of every epoch into [`Metrics`] (mean and
+//! exponential moving average), evaluates the validation loss, stops early once the
+//! validation loss has not improved for a number of epochs ([`EarlyStopping`]) and can
+//! append the metrics of every epoch to a CSV file.
+
+use std::{
+  f64::consts::PI,
+  fs::File,
+  io::{BufWriter, Write},
+  path::PathBuf,
+};
+
+use candle_nn::Optimizer;
+
+/// Learning rate as a function of the epoch
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LrSchedule {
+  /// Constant learning rate
+  #[default]
+  Constant,
+  /// Learning rate multiplied by gamma every step_size epochs
+  Step { step_size: usize, gamma: f64 },
+  /// Cosine annealing from the initial learning rate to min_lr over the epochs
+  Cosine { min_lr: f64 },
+}
+
+impl LrSchedule {
+  /// Learning rate of the epoch (starting at 0) out of epochs for the initial rate lr
+  pub fn lr(&self, lr: f64, epoch: usize, epochs: usize) -> f64 {
+    match *self {
+      LrSchedule::Constant => lr,
+      LrSchedule::Step { step_size, gamma } => lr * gamma.powi((epoch / step_size.max(1)) as i32),
+      LrSchedule::Cosine { min_lr } => {
+        let progress = epoch as f64 / (epochs.max(2) - 1) as f64;
+        min_lr + 0.5 * (lr - min_lr) * (1.0 + (PI * progress.min(1.0)).cos())
+      }
+    }
+  }
+}
+
+/// Early stopping on the validation loss
+#[derive(Debug, Clone)]
+pub struct EarlyStopping {
+  /// Number of epochs without improvement before stopping
+  pub patience: usize,
+  /// Minimal decrease of the loss counted as an improvement
+  pub min_delta: f64,
+  best: f64,
+  best_epoch: usize,
+  wait: usize,
+}
+
+impl EarlyStopping {
+  pub fn new(patience: usize, min_delta: f64) -> Self {
+    Self {
+      patience,
+      min_delta,
+      best: f64::INFINITY,
+      best_epoch: 0,
+      wait: 0,
+    }
+  }
+
+  /// Record the validation loss of the epoch, true if the training should stop
+  pub fn update(&mut self, epoch: usize, loss: f64) -> bool {
+    if loss < self.best - self.min_delta {
+      self.best = loss;
+      self.best_epoch = epoch;
+      self.wait = 0;
+    } else {
+      self.wait += 1;
+    }
+
+    self.wait >= self.patience
+  }
+
+  /// Best validation loss so far
+  pub fn best(&self) -> f64 {
+    self.best
+  }
+
+  /// Epoch of the best validation loss
+  pub fn best_epoch(&self) -> usize {
+    self.best_epoch
+  }
+}
+
+/// Metrics of an epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpochMetrics {
+  pub epoch: usize,
+  /// Learning rate of the epoch
+  pub lr: f64,
+  /// Mean of the batch losses
+  pub train_loss: f64,
+  /// Exponential moving average of the batch losses at the end of the epoch
+  pub train_loss_ema: f64,
+  /// Validation loss after the epoch
+  pub val_loss: Option<f64>,
+}
+
+/// Metrics of the training
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+  /// Weight of the last batch loss in the moving average
+  pub ema: f64,
+  pub epochs: Vec<EpochMetrics>,
+  ema_loss: Option<f64>,
+}
+
+impl Metrics {
+  pub fn new(ema: f64) -> Self {
+    Self {
+      ema,
+      ..Default::default()
+    }
+  }
+
+  /// Record the batch losses of an epoch
+  pub fn record(
+    &mut self,
+    epoch: usize,
+    lr: f64,
+    batch_losses: &[f64],
+    val_loss: Option<f64>,
+  ) -> EpochMetrics {
+    for &loss in batch_losses {
+      self.ema_loss = Some(match self.ema_loss {
+        Some(ema) => self.ema * loss + (1.0 - self.ema) * ema,
+        None => loss,
+      });
+    }
+
+    let metrics = EpochMetrics {
+      epoch,
+      lr,
+      train_loss: batch_losses.iter().sum::<f64>() / batch_losses.len().max(1) as f64,
+      train_loss_ema: self.ema_loss.unwrap_or(f64::NAN),
+      val_loss,
+    };
+    self.epochs.push(metrics);
+    metrics
+  }
+
+  /// Metrics of the last epoch
+  pub fn last(&self) -> Option<&EpochMetrics> {
+    self.epochs.last()
+  }
+
+  /// Write the metrics of all epochs as CSV
+  pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, "epoch,lr,train_loss,train_loss_ema,val_loss")?;
+    for metrics in &self.epochs {
+      writeln!(
+        writer,
+        "{},{},{},{},{}",
+        metrics.epoch,
+        metrics.lr,
+        metrics.train_loss,
+        metrics.train_loss_ema,
+        metrics
+          .val_loss
+          .map(|loss| loss.to_string())
+          .unwrap_or_default()
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Epoch loop with learning rate scheduling, early stopping and metrics
+#[derive(Debug, Clone)]
+pub struct Trainer {
+  /// Maximal number of epochs
+  pub epochs: usize,
+  /// Initial learning rate
+  pub lr: f64,
+  pub schedule: LrSchedule,
+  pub early_stopping: Option<EarlyStopping>,
+  /// Weight of the last batch loss in the moving average
+  pub ema: f64,
+  /// CSV file of the metrics, rewritten after every epoch
+  pub csv: Option<PathBuf>,
+  /// Print the metrics of every epoch
+  pub verbose: bool,
+}
+
+impl Trainer {
+  /// Trainer with a constant learning rate, without early stopping and logging
+  pub fn new(epochs: usize, lr: f64) -> Self {
+    Self {
+      epochs,
+      lr,
+      schedule: LrSchedule::Constant,
+      early_stopping: None,
+      ema: 0.1,
+      csv: None,
+      verbose: true,
+    }
+  }
+
+  pub fn with_schedule(mut self, schedule: LrSchedule) -> Self {
+    self.schedule = schedule;
+    self
+  }
+
+  pub fn with_early_stopping(mut self, patience: usize, min_delta: f64) -> Self {
+    self.early_stopping = Some(EarlyStopping::new(patience, min_delta));
+    self
+  }
+
+  pub fn with_ema(mut self, ema: f64) -> Self {
+    self.ema = ema;
+    self
+  }
+
+  pub fn with_csv(mut self, path: impl Into<PathBuf>) -> Self {
+    self.csv = Some(path.into());
+    self
+  }
+
+  pub fn with_verbose(mut self, verbose: bool) -> Self {
+    self.verbose = verbose;
+    self
+  }
+
+  /// Run the epoch loop
+  ///
+  /// `train_epoch` trains one epoch (starting at 0) with the optimizer and returns the
+  /// batch losses, `validate` returns the validation loss after the epoch, if any. Without
+  /// a validation loss early stopping monitors the mean training loss. The closures can
+  /// return any error that I/O errors of the CSV log convert into, e.g. candle or anyhow
+  /// errors.
+  pub fn fit<O, E, T, V>(
+    &mut self,
+    optimizer: &mut O,
+    mut train_epoch: T,
+    mut validate: V,
+  ) -> Result<Metrics, E>
+  where
+    O: Optimizer,
+    E: From<std::io::Error>,
+    T: FnMut(usize, &mut O) -> Result<Vec<f64>, E>,
+    V: FnMut(usize) -> Result<Option<f64>, E>,
+  {
+    let mut metrics = Metrics::new(self.ema);
+    if let Some(early_stopping) = &mut self.early_stopping {
+      *early_stopping = EarlyStopping::new(early_stopping.patience, early_stopping.min_delta);
+    }
+
+    for epoch in 0..self.epochs {
+      let lr = self.schedule.lr(self.lr, epoch, self.epochs);
+      optimizer.set_learning_rate(lr);
+
+      let batch_losses = train_epoch(epoch, optimizer)?;
+      let val_loss = validate(epoch)?;
+      let last = metrics.record(epoch, lr, &batch_losses, val_loss);
+
+      if self.verbose {
+        println!(
+          "Epoch: {:4} LR: {:.2e} Train loss: {:10.6} (EMA {:10.6}) Validation loss: {}",
+          epoch + 1,
+          lr,
+          last.train_loss,
+          last.train_loss_ema,
+          val_loss.map_or("-".to_string(), |loss| format!("{loss:10.6}"))
+        );
+      }
+
+      if let Some(path) = &self.csv {
+        let mut writer = BufWriter::new(File::create(path)?);
+        metrics.write_csv(&mut writer)?;
+      }
+
+      if let Some(early_stopping) = &mut self.early_stopping {
+        if early_stopping.update(epoch, val_loss.unwrap_or(last.train_loss)) {
+          if self.verbose {
+            println!(
+              "Early stopping after epoch {}, best loss {:.6} at epoch {}",
+              epoch + 1,
+              early_stopping.best(),
+              early_stopping.best_epoch() + 1
+            );
+          }
+          break;
+        }
+      }
+    }
+
+    Ok(metrics)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lr_schedules() {
+    let step = LrSchedule::Step {
+      step_size: 10,
+      gamma: 0.5,
+    };
+    assert_eq!(step.lr(1e-2, 9, 100), 1e-2);
+    assert_eq!(step.lr(1e-2, 25, 100), 2.5e-3);
+
+    let cosine = LrSchedule::Cosine { min_lr: 1e-5 };
+    assert_eq!(cosine.lr(1e-3, 0, 11), 1e-3);
+    assert!((cosine.lr(1e-3, 5, 11) - 0.5 * (1e-3 + 1e-5)).abs() < 1e-12);
+    assert!((cosine.lr(1e-3, 10, 11) - 1e-5).abs() < 1e-12);
+  }
+
+  #[test]
+  fn early_stopping_waits_for_patience() {
+    let mut early_stopping = EarlyStopping::new(2, 1e-3);
+    let losses = [1.0, 0.5, 0.4995, 0.45, 0.46, 0.451];
+    let stopped = losses
+      .iter()
+      .enumerate()
+      .position(|(epoch, &loss)| early_stopping.update(epoch, loss));
+
+    assert_eq!(stopped, Some(5));
+    assert_eq!(early_stopping.best_epoch(), 3);
+    assert_eq!(early_stopping.best(), 0.45);
+  }
+
+  #[test]
+  fn metrics_ema_and_csv() {
+    let mut metrics = Metrics::new(0.5);
+    metrics.record(0, 1e-3, &[4.0, 2.0], Some(3.0));
+    let last = metrics.record(1, 1e-3, &[1.0], None);
+
+    assert_eq!(last.train_loss, 1.0);
+    // 4 -> 3 -> 2
+    assert_eq!(last.train_loss_ema, 2.0);
+
+    let mut csv = Vec::new();
+    metrics.write_csv(&mut csv).unwrap();
+    assert_eq!(
+      String::from_utf8(csv).unwrap(),
+      "epoch,lr,train_loss,train_loss_ema,val_loss\n0,0.001,3,3,3\n1,0.001,1,2,\n"
+    );
+  }
+}
@@ -1,8 +1,17 @@
 use candle_core::{DType, Device, Result, Tensor};
 use candle_nn::{linear, AdamW, Linear, Module, Optimizer, ParamsAdamW, VarBuilder, VarMap};
 
+use crate::ai::{
+  export::{linear as linear_spec, Export, Json, SPEC_VERSION},
+  training::{Metrics, Trainer},
+};
+
 /// Calibration model for the Heston model
 pub struct Model {
+  varmap: VarMap,
+  input_dim: usize,
+  hidden_size: usize,
+  output_dim: usize,
   linear1: Linear,
   linear2: Linear,
   linear3: Linear,
@@ -12,17 +21,23 @@ pub struct Model {
 impl Model {
   #[must_use = "new is necessary to create a new instance of Model"]
   pub fn new(
-    vs: VarBuilder,
+    varmap: &VarMap,
+    device: &Device,
     input_dim: usize,
     hidden_size: usize,
     output_dim: usize,
   ) -> Result<Self> {
+    let vs = VarBuilder::from_varmap(varmap, DType::F32, device);
     let linear1 = linear(input_dim, hidden_size, vs.pp("linear-1"))?;
     let linear2 = linear(hidden_size, hidden_size, vs.pp("linear-2"))?;
     let linear3 = linear(hidden_size, hidden_size, vs.pp("linear-3"))?;
     let output_layer = linear(hidden_size, output_dim, vs.pp("linear-4"))?;
 
     Ok(Self {
+      varmap: varmap.clone(),
+      input_dim,
+      hidden_size,
+      output_dim,
       linear1,
       linear2,
       linear3,
@@ -33,7 +48,7 @@ impl Model {
 
 impl Module for Model {
   fn forward(&self, xs: &Tensor) -> Result<Tensor> {
-    let xs = self.linear1.forward(&xs)?.elu(2.0)?;
+    let xs = self.linear1.forward(xs)?.elu(2.0)?;
     let xs = self.linear2.forward(&xs)?.elu(2.0)?;
     let xs = self.linear3.forward(&xs)?.elu(2.0)?;
     let xs = self.output_layer.forward(&xs)?;
@@ -41,6 +56,43 @@ impl Module for Model {
   }
 }
 
+impl Export for Model {
+  fn varmap(&self) -> &VarMap {
+    &self.varmap
+  }
+
+  /// Three hidden layers with ELU(alpha = 2) and a linear output, in float32
+  fn spec(&self) -> Json {
+    let (input, hidden, output) = (self.input_dim, self.hidden_size, self.output_dim);
+    let elu = Json::object([("op", "elu".into()), ("alpha", 2.0f64.into())]);
+
+    Json::object([
+      ("format", "stochastic-rs".into()),
+      ("version", SPEC_VERSION.into()),
+      ("model", "heston_surface".into()),
+      ("dtype", "float32".into()),
+      ("input", Json::object([("shape", vec![0, input].into())])),
+      ("output", Json::object([("shape", vec![0, output].into())])),
+      (
+        "forward",
+        Json::Array(vec![
+          linear_spec("linear-1", input, hidden),
+          elu.clone(),
+          linear_spec("linear-2", hidden, hidden),
+          elu.clone(),
+          linear_spec("linear-3", hidden, hidden),
+          elu,
+          linear_spec("linear-4", hidden, output),
+        ]),
+      ),
+      (
+        "notes",
+        "The inputs and outputs are in the units the network was trained on, e.g. scaled parameters and standardized implied volatilities, the scaling is not part of the model. A batch dimension of 0 is dynamic.".into(),
+      ),
+    ])
+  }
+}
+
 pub struct DataSet {
   pub x_train: Tensor,
   pub y_train: Tensor,
@@ -55,15 +107,14 @@ pub fn train(
   hidden_size: usize,
   output_dim: usize,
   batch_size: usize,
-  epochs: usize,
-) -> Result<Model> {
+  trainer: &mut Trainer,
+) -> Result<(Model, Metrics)> {
   let x_train = dataset.x_train.to_device(device)?;
   let y_train = dataset.y_train.to_device(device)?;
   let varmap = VarMap::new();
-  let vs = VarBuilder::from_varmap(&varmap, DType::F32, device);
-  let model = Model::new(vs, input_dim, hidden_size, output_dim)?;
+  let model = Model::new(&varmap, device, input_dim, hidden_size, output_dim)?;
   let optimizer_params = ParamsAdamW {
-    lr: 1e-3,
+    lr: trainer.lr,
     beta1: 0.9,
     beta2: 0.999,
     eps: 1e-7,
@@ -73,37 +124,34 @@ pub fn train(
   let x_test = dataset.x_test.to_device(device)?;
   let y_test = dataset.y_test.to_device(device)?;
 
-  let num_batches = (x_train.dim(0)? + batch_size - 1) / batch_size;
-
-  for epoch in 1..=epochs {
-    for batch_idx in 0..num_batches {
-      let start = batch_idx * batch_size;
-      let end = ((start + batch_size).min(x_train.dim(0)?)) as usize;
-      let current_batch_size = end - start;
+  let num_batches = x_train.dim(0)?.div_ceil(batch_size);
 
-      let x_batch = x_train.narrow(0, start, current_batch_size)?;
-      let y_batch = y_train.narrow(0, start, current_batch_size)?;
-
-      let logits = model.forward(&x_batch)?;
-      let loss = candle_nn::loss::mse(&logits, &y_batch)?;
-      adam.backward_step(&loss)?;
-    }
+  let metrics = trainer.fit(
+    &mut adam,
+    |_, adam| -> Result<Vec<f64>> {
+      let mut losses = Vec::with_capacity(num_batches);
+      for batch_idx in 0..num_batches {
+        let start = batch_idx * batch_size;
+        let end = (start + batch_size).min(x_train.dim(0)?);
 
-    // Compute and print the loss on the entire training and test set
-    let logits = model.forward(&x_train)?;
-    let loss = candle_nn::loss::mse(&logits, &y_train)?;
+        let x_batch = x_train.narrow(0, start, end - start)?;
+        let y_batch = y_train.narrow(0, start, end - start)?;
 
-    let test_logits = model.forward(&x_test)?;
-    let test_loss = candle_nn::loss::mse(&test_logits, &y_test)?;
-
-    println!(
-      "Epoch: {epoch:3} Train MSE: {:8.5} Test MSE: {:8.5}",
-      loss.to_scalar::<f32>()?,
-      test_loss.to_scalar::<f32>()?
-    );
-  }
-
-  Ok(model)
+        let logits = model.forward(&x_batch)?;
+        let loss = candle_nn::loss::mse(&logits, &y_batch)?;
+        adam.backward_step(&loss)?;
+        losses.push(loss.to_scalar::<f32>()? as f64);
+      }
+      Ok(losses)
+    },
+    |_| -> Result<Option<f64>> {
+      let test_logits = model.forward(&x_test)?;
+      let test_loss = candle_nn::loss::mse(&test_logits, &y_test)?;
+      Ok(Some(test_loss.to_scalar::<f32>()? as f64))
+    },
+  )?;
+
+  Ok((model, metrics))
 }
 
 #[cfg(test)]
@@ -111,15 +159,12 @@ mod tests {
   use std::fs::File;
 
   use super::*;
+  use crate::ai::training::LrSchedule;
   use candle_core::Device;
   use flate2::read::GzDecoder;
   use ndarray::{s, stack, Array1, Array2, Axis};
   use ndarray_npy::read_npy;
-  use plotly::{
-    common::{Mode, Title},
-    layout::{GridPattern, LayoutGrid},
-    Layout, Plot, Scatter,
-  };
+  use plotly::Surface;
   use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
   use tempfile::NamedTempFile;
 
@@ -179,8 +224,8 @@ mod tests {
       .expect("Failed to stack arrays")
     }
 
-    let y_train_scaled = scale_parameters(&y_train, &lb, &ub);
-    let y_test_scaled = scale_parameters(&y_test, &lb, &ub);
+    let y_train_scaled = scale_parameters(y_train, &lb, &ub);
+    let y_test_scaled = scale_parameters(y_test, &lb, &ub);
 
     // Scaling implied volatilities using StandardScaler
     #[derive(Debug)]
@@ -215,9 +260,9 @@ mod tests {
       }
     }
 
-    let scaler = StandardScaler2D::fit(&x_train);
-    let x_train_scaled = scaler.transform(&x_train);
-    let x_test_scaled = scaler.transform(&x_test);
+    let scaler = StandardScaler2D::fit(x_train);
+    let x_train_scaled = scaler.transform(x_train);
+    let x_test_scaled = scaler.transform(x_test);
 
     // Prepare the dataset
     let dataset = DataSet {
@@ -244,14 +289,17 @@ mod tests {
     };
 
     // Train the model
-    let model = train(
+    let mut trainer = Trainer::new(200, 1e-3)
+      .with_schedule(LrSchedule::Cosine { min_lr: 1e-5 })
+      .with_early_stopping(20, 1e-5);
+    let (model, _) = train(
       dataset,
       &Device::Cpu,
-      5,   // input_dim (parameters)
-      30,  // hidden_size
-      88,  // output_dim (implied volatilities)
-      32,  // batch_size
-      200, // epochs
+      5,  // input_dim (parameters)
+      30, // hidden_size
+      88, // output_dim (implied volatilities)
+      32, // batch_size
+      &mut trainer,
     )?;
 
     // Sample index for plotting
@@ -282,75 +330,50 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn export_surface_network() -> anyhow::Result<()> {
+    let varmap = VarMap::new();
+    let model = Model::new(&varmap, &Device::Cpu, 5, 30, 88)?;
+    let dir = tempfile::tempdir()?;
+    model.export(dir.path().join("heston.bin"))?;
+
+    let weights =
+      candle_core::safetensors::load(dir.path().join("heston.safetensors"), &Device::Cpu)?;
+    let tensors = model.spec().tensors();
+    assert_eq!(tensors.len(), 8);
+    for name in &tensors {
+      assert!(weights.contains_key(name), "{}", name);
+    }
+    assert_eq!(weights["linear-1.weight"].dims(), &[30, 5]);
+    assert!(dir.path().join("heston.json").exists());
+
+    Ok(())
+  }
+
   fn plot_results_with_plotly(
     strikes: &Array1<f64>,
     maturities: &Array1<f64>,
     actual: &Array1<f64>,
     prediction: &Array1<f64>,
   ) -> Result<()> {
-    let strikes_dim = strikes.len();
-    let s0 = 1.0;
-
-    let mut plot = Plot::new();
-
-    // Grid dimensions for subplots
-    let rows = 4; // Number of rows
-    let columns = 4; // Number of columns
-
-    for (i, &maturity) in maturities.iter().enumerate() {
-      let start = i * strikes_dim;
-      let end = start + strikes_dim;
-
-      let log_moneyness = strikes.iter().map(|&k| (k / s0).ln()).collect::<Vec<f64>>();
-
-      // Slicing the actual and prediction data for the current maturity
-      let actual_slice = actual.slice(s![start..end]).to_vec();
-      let prediction_slice = prediction.slice(s![start..end]).to_vec();
-
-      // Ensure dimensions match
-      assert_eq!(log_moneyness.len(), actual_slice.len());
-      assert_eq!(log_moneyness.len(), prediction_slice.len());
-
-      // Blue line for actual data
-      let trace_actual = Scatter::new(log_moneyness.clone(), actual_slice)
-        .name(format!("Actual - Maturity {:.2}", maturity))
-        .mode(Mode::Lines)
-        .line(plotly::common::Line::new().color("blue"))
-        .x_axis(format!("x{}", i + 1))
-        .y_axis(format!("y{}", i + 1))
-        .show_legend(false);
-
-      // Red dashed line for predictions
-      let trace_prediction = Scatter::new(log_moneyness.clone(), prediction_slice)
-        .name(format!("Prediction - Maturity {:.2}", maturity))
-        .mode(Mode::Lines)
-        .line(
-          plotly::common::Line::new()
-            .dash(plotly::common::DashType::Dash)
-            .color("red"),
-        )
-        .x_axis(format!("x{}", i + 1))
-        .y_axis(format!("y{}", i + 1))
-        .show_legend(false);
-
-      plot.add_trace(trace_actual);
-      plot.add_trace(trace_prediction);
-    }
-
-    // Layout settings for subplots
-    let layout = Layout::new()
-      .height(1200)
-      .grid(
-        LayoutGrid::new()
-          .rows(rows)
-          .columns(columns)
-          .pattern(GridPattern::Independent),
-      )
-      .title(Title::from("Implied Volatility Surface"))
-      .x_axis(plotly::layout::Axis::new().title("Log-moneyness"))
-      .y_axis(plotly::layout::Axis::new().title("Implied Volatility"));
-
-    plot.set_layout(layout);
+    // One row per maturity and one column per strike
+    let shape = (maturities.len(), strikes.len());
+    let actual = actual.to_shape(shape).unwrap().to_owned();
+    let prediction = prediction.to_shape(shape).unwrap();
+
+    let mut plot = crate::plot::implied_vol_surface(strikes, maturities, &actual);
+    let prediction = prediction
+      .outer_iter()
+      .map(|row| row.to_vec())
+      .collect::<Vec<_>>();
+    plot.add_trace(
+      Surface::new(prediction)
+        .x(strikes.to_vec())
+        .y(maturities.to_vec())
+        .name("Prediction")
+        .opacity(0.5)
+        .show_scale(false),
+    );
     plot.show();
 
     Ok(())
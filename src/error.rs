@@ -0,0 +1,43 @@
+use std::fmt::Display;
+
+/// Error returned by the fallible (`try_*`) APIs of the samplers and pricers
+#[derive(Debug, Clone, PartialEq)]
+pub enum StochasticError {
+  /// Parallel sampling requested without the number of samples `m`
+  MissingSampleCount,
+  /// Neither the number of steps `n` nor the horizon `t_max` is given
+  MissingHorizon,
+  /// A parameter is outside of its admissible range
+  InvalidParameter(String),
+  /// A numerical routine failed (e.g. a matrix is not positive definite)
+  Numerical(String),
+}
+
+impl Display for StochasticError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      StochasticError::MissingSampleCount => write!(f, "m must be specified for parallel sampling"),
+      StochasticError::MissingHorizon => write!(f, "n or t_max must be provided"),
+      StochasticError::InvalidParameter(msg) => write!(f, "{}", msg),
+      StochasticError::Numerical(msg) => write!(f, "{}", msg),
+    }
+  }
+}
+
+impl std::error::Error for StochasticError {}
+
+/// Result of the fallible APIs
+pub type StochasticResult<T> = Result<T, StochasticError>;
+
+/// Return an [`StochasticError::InvalidParameter`] with the given message if the condition is false
+pub(crate) fn ensure(condition: bool, msg: &str) -> StochasticResult<()> {
+  match condition {
+    true => Ok(()),
+    false => Err(StochasticError::InvalidParameter(msg.to_string())),
+  }
+}
+
+/// Panic with the message of the error, used by the infallible APIs
+pub(crate) fn or_panic<T>(result: StochasticResult<T>) -> T {
+  result.unwrap_or_else(|err| panic!("{}", err))
+}
@@ -16,8 +16,15 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
 pub mod ai;
+pub mod error;
 #[doc(hidden)]
 mod macros;
+pub mod math;
+pub mod plot;
 pub mod quant;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "sink")]
+pub mod sink;
 pub mod stats;
 pub mod stochastic;
@@ -1,6 +1,98 @@
-use stochastic_rs::stochastic::{noise::fgn::FGN, Sampling};
+use std::ops::Bound;
 
+use stochastic_rs::stochastic::{
+  catalog,
+  catalog::{Interval, ModelInfo, ParameterKind},
+  noise::fgn::FGN,
+  Sampling,
+};
+
+/// `list` prints the catalog of the models, `list <name>` the parameters and references of
+/// one model, no command runs the FGN benchmark
 fn main() {
+  let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+  match args.first().map(String::as_str) {
+    Some("list") => list(args.get(1).map(String::as_str)),
+    Some(command) => {
+      eprintln!("unknown command {command}, usage: stochastic-rs [list [name]]");
+      std::process::exit(2);
+    }
+    None => benchmark(),
+  }
+}
+
+fn list(name: Option<&str>) {
+  let models = catalog();
+
+  match name {
+    None => {
+      for model in &models {
+        println!(
+          "{:<11} {:<28} {}",
+          format!("{:?}", model.kind),
+          model.name,
+          model.title
+        );
+      }
+    }
+    Some(name) => match models
+      .iter()
+      .find(|model| model.name.eq_ignore_ascii_case(name))
+    {
+      Some(model) => describe(model),
+      None => {
+        eprintln!("no model named {name}, `list` shows the catalog");
+        std::process::exit(1);
+      }
+    },
+  }
+}
+
+fn describe(model: &ModelInfo) {
+  println!(
+    "{} ({})\n{}\n\nparameters:",
+    model.title, model.path, model.description
+  );
+  for parameter in model.parameters {
+    let kind = match parameter.kind {
+      ParameterKind::Real(interval) => format!("real in {}", range(&interval)),
+      ParameterKind::Integer(interval) => format!("integer in {}", range(&interval)),
+      ParameterKind::Choice(variants) => format!("one of {}", variants.join(", ")),
+      kind => format!("{kind:?}").to_lowercase(),
+    };
+    let optional = if parameter.optional { ", optional" } else { "" };
+    println!(
+      "  {:<12} {} ({kind}{optional})",
+      parameter.name, parameter.description
+    );
+  }
+
+  if !model.references.is_empty() {
+    println!("\nreferences:");
+    for reference in model.references {
+      println!("  {reference}");
+    }
+  }
+}
+
+/// Interval in the bracket notation, e.g. (0, inf)
+fn range(interval: &Interval) -> String {
+  let lower = match interval.lower {
+    Bound::Included(a) => format!("[{a}"),
+    Bound::Excluded(a) => format!("({a}"),
+    Bound::Unbounded => "(-inf".to_string(),
+  };
+  let upper = match interval.upper {
+    Bound::Included(b) => format!("{b}]"),
+    Bound::Excluded(b) => format!("{b})"),
+    Bound::Unbounded => "inf)".to_string(),
+  };
+
+  format!("{lower}, {upper}")
+}
+
+fn benchmark() {
   let fbm = FGN::new(0.9, 10000, None, Some(10000));
 
   let start = std::time::Instant::now();
@@ -0,0 +1,160 @@
+use nalgebra::{DMatrix, SymmetricEigen};
+use ndarray::Array2;
+
+/// Convert an ndarray matrix to a nalgebra matrix
+pub fn to_dmatrix(a: &Array2<f64>) -> DMatrix<f64> {
+  let (rows, cols) = a.dim();
+  DMatrix::from_fn(rows, cols, |i, j| a[[i, j]])
+}
+
+/// Convert a nalgebra matrix to an ndarray matrix
+pub fn from_dmatrix(a: &DMatrix<f64>) -> Array2<f64> {
+  Array2::from_shape_fn((a.nrows(), a.ncols()), |(i, j)| a[(i, j)])
+}
+
+/// Symmetrize a square matrix as (A + A^T) / 2
+pub fn symmetrize(a: &Array2<f64>) -> Array2<f64> {
+  assert!(a.is_square(), "Matrix must be square");
+  (a + &a.t()) * 0.5
+}
+
+/// Check whether a symmetric matrix is positive semi-definite up to `tol`
+pub fn is_psd(a: &Array2<f64>, tol: f64) -> bool {
+  let eigen = SymmetricEigen::new(to_dmatrix(&symmetrize(a)));
+  eigen.eigenvalues.iter().all(|&l| l >= -tol)
+}
+
+/// Replace the eigenvalues of a symmetric matrix below `eps` with `eps`
+/// and rebuild the matrix from the clipped spectrum.
+pub fn clip_eigenvalues(a: &Array2<f64>, eps: f64) -> Array2<f64> {
+  let eigen = SymmetricEigen::new(to_dmatrix(&symmetrize(a)));
+  let q = &eigen.eigenvectors;
+  let lambda = DMatrix::from_diagonal(&eigen.eigenvalues.map(|l| l.max(eps)));
+
+  symmetrize(&from_dmatrix(&(q * lambda * q.transpose())))
+}
+
+/// Principal square root of a symmetric positive semi-definite matrix.
+///
+/// Computed from the spectral decomposition A = Q diag(l) Q^T as
+/// A^{1/2} = Q diag(sqrt(max(l, 0))) Q^T, so small negative eigenvalues
+/// caused by rounding or noisy estimates are truncated to zero.
+pub fn sqrtm(a: &Array2<f64>) -> Array2<f64> {
+  let eigen = SymmetricEigen::new(to_dmatrix(&symmetrize(a)));
+  let q = &eigen.eigenvectors;
+  let lambda = DMatrix::from_diagonal(&eigen.eigenvalues.map(|l| l.max(0.0).sqrt()));
+
+  from_dmatrix(&(q * lambda * q.transpose()))
+}
+
+/// Lower triangular Cholesky factor of a symmetric positive definite matrix.
+///
+/// Returns `None` if the matrix is not positive definite.
+pub fn cholesky(a: &Array2<f64>) -> Option<Array2<f64>> {
+  assert!(a.is_square(), "Matrix must be square");
+  to_dmatrix(a).cholesky().map(|c| from_dmatrix(&c.l()))
+}
+
+/// Lower triangular Cholesky factor with positive semi-definite repair.
+///
+/// If the plain factorization fails, the input is first projected onto the
+/// nearest correlation matrix (when it has a unit diagonal) or has its
+/// spectrum clipped at `eps` (otherwise), and the factorization is retried.
+pub fn cholesky_psd(a: &Array2<f64>, eps: f64) -> Array2<f64> {
+  if let Some(l) = cholesky(a) {
+    return l;
+  }
+
+  let is_correlation = a.diag().iter().all(|&d| (d - 1.0).abs() < 1e-12);
+  let repaired = match is_correlation {
+    true => nearest_correlation_matrix(a, 100, 1e-10),
+    false => clip_eigenvalues(a, eps),
+  };
+
+  match cholesky(&repaired) {
+    Some(l) => l,
+    None => cholesky(&clip_eigenvalues(&repaired, eps.max(1e-12)))
+      .expect("Cholesky factorization failed after PSD repair"),
+  }
+}
+
+/// Nearest correlation matrix in the Frobenius norm.
+///
+/// Alternating projections method with Dykstra's correction.
+/// https://www.maths.manchester.ac.uk/~higham/narep/narep369.pdf
+///
+/// # Arguments
+/// a: &Array2<f64> - symmetric matrix, typically a noisy empirical correlation matrix
+/// max_iter: usize - maximum number of iterations
+/// tol: f64 - tolerance on the relative change between iterations
+///
+/// # Returns
+/// Array2<f64> - positive semi-definite matrix with unit diagonal
+pub fn nearest_correlation_matrix(a: &Array2<f64>, max_iter: usize, tol: f64) -> Array2<f64> {
+  assert!(a.is_square(), "Matrix must be square");
+
+  let mut y = symmetrize(a);
+  let mut ds = Array2::<f64>::zeros(a.dim());
+
+  for _ in 0..max_iter {
+    let r = &y - &ds;
+
+    // Projection onto the positive semi-definite cone
+    let x = clip_eigenvalues(&r, 0.0);
+    ds = &x - &r;
+
+    // Projection onto the set of unit diagonal matrices
+    let mut y_next = x.clone();
+    y_next.diag_mut().fill(1.0);
+
+    let diff = (&y_next - &y).mapv(|v| v.powi(2)).sum().sqrt();
+    let norm = y_next.mapv(|v| v.powi(2)).sum().sqrt();
+    y = y_next;
+
+    if diff / norm < tol {
+      break;
+    }
+  }
+
+  // The unit diagonal projection is the iterate that converges
+  y
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+
+  #[test]
+  fn sqrtm_squares_back() {
+    let a = array![[4.0, 1.0], [1.0, 3.0]];
+    let s = sqrtm(&a);
+    let a_hat = s.dot(&s);
+
+    for (x, y) in a.iter().zip(a_hat.iter()) {
+      assert!((x - y).abs() < 1e-10);
+    }
+  }
+
+  #[test]
+  fn nearest_correlation_matrix_is_psd() {
+    let a = array![[1.0, 0.9, 0.7], [0.9, 1.0, -0.4], [0.7, -0.4, 1.0]];
+    assert!(cholesky(&a).is_none());
+
+    let c = nearest_correlation_matrix(&a, 100, 1e-10);
+    assert!(is_psd(&c, 1e-10));
+    assert!(c.diag().iter().all(|&d| d == 1.0));
+  }
+
+  #[test]
+  fn cholesky_psd_repairs_indefinite_input() {
+    let a = array![[1.0, 0.9, 0.7], [0.9, 1.0, -0.4], [0.7, -0.4, 1.0]];
+    let l = cholesky_psd(&a, 1e-8);
+    let c = l.dot(&l.t());
+
+    for i in 0..3 {
+      assert!((c[[i, i]] - 1.0).abs() < 1e-6);
+    }
+  }
+}
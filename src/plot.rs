@@ -0,0 +1,246 @@
+use ndarray::{Array1, Array2, Axis};
+use plotly::{
+  common::{DashType, Line, Mode, Title},
+  histogram::HistNorm,
+  layout::{Axis as LayoutAxis, GridPattern, LayoutGrid, LayoutScene},
+  Histogram, Layout, Plot, Scatter, Surface,
+};
+
+use crate::{quant::monte_carlo::MCEstimate, stochastic::Distribution};
+
+/// Terminal values (last column) of a matrix of sampled paths
+pub fn terminal_values(paths: &Array2<f64>) -> Array1<f64> {
+  paths.index_axis(Axis(1), paths.ncols() - 1).to_owned()
+}
+
+/// Diagnostic figure comparing a sampled terminal distribution with the analytic one.
+///
+/// The left panel shows the normalized histogram of the samples together with the
+/// analytic density, the right panel is a QQ-plot of the empirical quantiles against
+/// the quantiles of the analytic distribution.
+///
+/// # Arguments
+/// samples: &Array1<f64> - sampled terminal values
+/// distribution: &D - analytic distribution implementing `pdf` and `inv_cdf`
+/// name: &str - name of the model used in the title
+/// bins: usize - number of histogram bins
+pub fn terminal_distribution<D: Distribution>(
+  samples: &Array1<f64>,
+  distribution: &D,
+  name: &str,
+  bins: usize,
+) -> Plot {
+  let mut sorted = samples.to_vec();
+  sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  let n = sorted.len();
+  let (min, max) = (sorted[0], sorted[n - 1]);
+
+  let mut plot = Plot::new();
+
+  let histogram = Histogram::new(sorted.clone())
+    .name("Sampled")
+    .hist_norm(HistNorm::ProbabilityDensity)
+    .n_bins_x(bins)
+    .opacity(0.6)
+    .x_axis("x1")
+    .y_axis("y1");
+
+  let grid = Array1::linspace(min, max, 200);
+  let density = grid.mapv(|x| distribution.pdf(x));
+  let pdf = Scatter::new(grid.to_vec(), density.to_vec())
+    .name("Analytic")
+    .mode(Mode::Lines)
+    .line(Line::new().color("orange"))
+    .x_axis("x1")
+    .y_axis("y1");
+
+  let theoretical = (0..n)
+    .map(|i| distribution.inv_cdf((i as f64 + 0.5) / n as f64))
+    .collect::<Vec<f64>>();
+  let qq = Scatter::new(theoretical.clone(), sorted)
+    .name("QQ")
+    .mode(Mode::Markers)
+    .x_axis("x2")
+    .y_axis("y2")
+    .show_legend(false);
+
+  let (lo, hi) = (theoretical[0].min(min), theoretical[n - 1].max(max));
+  let reference = Scatter::new(vec![lo, hi], vec![lo, hi])
+    .mode(Mode::Lines)
+    .line(Line::new().color("red").dash(DashType::Dash))
+    .x_axis("x2")
+    .y_axis("y2")
+    .show_legend(false);
+
+  plot.add_trace(histogram);
+  plot.add_trace(pdf);
+  plot.add_trace(qq);
+  plot.add_trace(reference);
+
+  let layout = Layout::new()
+    .grid(
+      LayoutGrid::new()
+        .rows(1)
+        .columns(2)
+        .pattern(GridPattern::Independent),
+    )
+    .title(Title::from(
+      format!("{} terminal distribution", name).as_str(),
+    ))
+    .x_axis(LayoutAxis::new().title("Value"))
+    .y_axis(LayoutAxis::new().title("Density"))
+    .x_axis2(LayoutAxis::new().title("Analytic quantiles"))
+    .y_axis2(LayoutAxis::new().title("Sampled quantiles"));
+  plot.set_layout(layout);
+
+  plot
+}
+
+/// 3D surface plot of `z` over the grid spanned by `x` (columns) and `y` (rows).
+///
+/// # Arguments
+/// x: &Array1<f64> - grid along the columns of `z`
+/// y: &Array1<f64> - grid along the rows of `z`
+/// z: &Array2<f64> - surface values with shape (y.len(), x.len())
+/// title: &str - title of the figure
+/// labels: [&str; 3] - axis titles for x, y and z
+pub fn surface(
+  x: &Array1<f64>,
+  y: &Array1<f64>,
+  z: &Array2<f64>,
+  title: &str,
+  labels: [&str; 3],
+) -> Plot {
+  assert_eq!(
+    z.dim(),
+    (y.len(), x.len()),
+    "Surface shape must be (y.len(), x.len())"
+  );
+
+  let mut plot = Plot::new();
+  let z = z.outer_iter().map(|row| row.to_vec()).collect::<Vec<_>>();
+  let trace = Surface::new(z).x(x.to_vec()).y(y.to_vec()).name(title);
+  plot.add_trace(trace);
+
+  let layout = Layout::new().title(Title::from(title)).height(800).scene(
+    LayoutScene::new()
+      .x_axis(LayoutAxis::new().title(labels[0]))
+      .y_axis(LayoutAxis::new().title(labels[1]))
+      .z_axis(LayoutAxis::new().title(labels[2])),
+  );
+  plot.set_layout(layout);
+
+  plot
+}
+
+/// Implied volatility surface over a strike x maturity grid.
+///
+/// `vols` has one row per maturity and one column per strike.
+pub fn implied_vol_surface(
+  strikes: &Array1<f64>,
+  maturities: &Array1<f64>,
+  vols: &Array2<f64>,
+) -> Plot {
+  surface(
+    strikes,
+    maturities,
+    vols,
+    "Implied Volatility Surface",
+    ["Strike", "Maturity", "Implied Volatility"],
+  )
+}
+
+/// Finite difference solution grid (option value vs underlying price vs time to maturity).
+///
+/// `grid` has one row per time layer and one column per price node.
+pub fn finite_difference_grid(
+  s_values: &Array1<f64>,
+  tau_values: &Array1<f64>,
+  grid: &Array2<f64>,
+) -> Plot {
+  surface(
+    s_values,
+    tau_values,
+    grid,
+    "Finite Difference Solution",
+    ["Underlying price", "Time to maturity", "Option value"],
+  )
+}
+
+/// Convergence plot of a Monte Carlo estimate with its confidence band.
+///
+/// # Arguments
+/// history: &[MCEstimate] - running estimates, e.g. `MCResult::history`
+/// reference: Option<f64> - known value (e.g. analytic price) drawn as a horizontal line
+pub fn mc_convergence(history: &[MCEstimate], reference: Option<f64>) -> Plot {
+  let paths = history.iter().map(|e| e.paths).collect::<Vec<_>>();
+  let mut plot = Plot::new();
+
+  plot.add_trace(
+    Scatter::new(
+      paths.clone(),
+      history.iter().map(|e| e.mean + e.half_width).collect(),
+    )
+    .name("Upper bound")
+    .mode(Mode::Lines)
+    .line(Line::new().color("gray").dash(DashType::Dash)),
+  );
+  plot.add_trace(
+    Scatter::new(paths.clone(), history.iter().map(|e| e.mean).collect())
+      .name("Estimate")
+      .mode(Mode::LinesMarkers),
+  );
+  plot.add_trace(
+    Scatter::new(
+      paths.clone(),
+      history.iter().map(|e| e.mean - e.half_width).collect(),
+    )
+    .name("Lower bound")
+    .mode(Mode::Lines)
+    .line(Line::new().color("gray").dash(DashType::Dash)),
+  );
+
+  if let (Some(reference), Some(first), Some(last)) = (reference, paths.first(), paths.last()) {
+    plot.add_trace(
+      Scatter::new(vec![*first, *last], vec![reference, reference])
+        .name("Reference")
+        .mode(Mode::Lines)
+        .line(Line::new().color("red")),
+    );
+  }
+
+  let layout = Layout::new()
+    .title(Title::from("Monte Carlo convergence"))
+    .x_axis(LayoutAxis::new().title("Paths"))
+    .y_axis(LayoutAxis::new().title("Estimate"));
+  plot.set_layout(layout);
+
+  plot
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::stochastic::{diffusion::gbm::GBM, Sampling, N, S0};
+
+  use super::*;
+
+  #[test]
+  fn gbm_terminal_distribution_plot() {
+    let mut gbm = GBM::new(
+      0.05,
+      0.2,
+      N,
+      Some(S0),
+      Some(1.0),
+      None,
+      Some(5000),
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    gbm.distribution();
+
+    let samples = terminal_values(&gbm.sample_par());
+    terminal_distribution(&samples, &gbm, "Geometric Brownian Motion (GBM)", 50).show();
+  }
+}
@@ -1,9 +1,20 @@
 use std::fmt::Display;
 
+pub mod bonds;
 pub mod calibration;
+pub mod curves;
+pub mod greeks;
+pub mod greeks_validation;
+pub mod monte_carlo;
+pub mod nested;
 pub mod pricing;
+pub mod rates;
+pub mod rough;
+pub mod schedule;
 pub mod strategies;
+pub mod synthetic;
 pub mod r#trait;
+pub mod volatility;
 #[cfg(feature = "yahoo")]
 pub mod yahoo;
 
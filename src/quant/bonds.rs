@@ -1,3 +1,81 @@
+//! Zero-coupon and coupon bonds under the short rate models.
+//!
+//! The zero-coupon bond to the expiration is the price of the [`Pricer`]. A coupon bond is
+//! given by its [`Schedule`], its cashflows paid after the evaluation date are discounted
+//! by the zero-coupon bonds of the model at their Act/365 times.
+
+use chrono::NaiveDate;
+
+use crate::quant::{
+  r#trait::Pricer,
+  schedule::{DayCount, Schedule},
+};
+
 pub mod cir;
 pub mod hull_white;
 pub mod vasicek;
+
+/// Short rate model with zero-coupon bonds in closed form
+pub trait ZeroCouponBond: Pricer {
+  /// Price of the zero-coupon bond paying 1 after tau years
+  fn zero_coupon(&self, tau: f64) -> f64;
+
+  /// Price of the fixed coupon bond of the schedule at the evaluation date
+  fn coupon_bond(&self, schedule: &Schedule, coupon: f64, notional: f64) -> f64 {
+    let eval: NaiveDate = self.eval();
+
+    schedule
+      .bond_cashflows(coupon, notional)
+      .iter()
+      .filter(|c| c.date > eval)
+      .map(|c| c.amount * self.zero_coupon(DayCount::Act365Fixed.year_fraction(eval, c.date)))
+      .sum()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{cir::CIR, hull_white::HullWhite, vasicek::Vasicek, *};
+  use crate::{quant::schedule::Frequency, stochastic::diffusion::cir};
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+  }
+
+  #[test]
+  fn cir_matches_the_process_bonds() {
+    let bond = CIR::new(0.03, 0.8, 0.05, 0.1, Some(5.0), None, None);
+    let process = cir::CIR::new(0.8, 0.05, 0.1, 2, None, None, None, None, None);
+
+    assert!((bond.calculate_price() - process.bond_price(0.03, 5.0)).abs() < 1e-12);
+  }
+
+  #[test]
+  fn hull_white_with_constant_theta_is_vasicek() {
+    let vasicek = Vasicek::new(0.03, 0.5, 0.05, 0.01, Some(7.0), None, None);
+    let hull_white = HullWhite::new(0.03, |_| 0.5 * 0.05, 0.5, 0.01, Some(7.0), None, None);
+
+    assert!((hull_white.calculate_price() - vasicek.calculate_price()).abs() < 1e-10);
+  }
+
+  #[test]
+  fn coupon_bond_discounts_the_remaining_cashflows() {
+    let eval = date(2024, 8, 1);
+    let expiration = date(2027, 1, 15);
+    let bond = Vasicek::new(0.03, 0.5, 0.05, 0.01, None, Some(eval), Some(expiration));
+    let schedule = Schedule::new(date(2024, 1, 15), expiration, Frequency::SemiAnnual).unwrap();
+
+    // Without coupons only the notional at the expiration is paid
+    assert!((bond.coupon_bond(&schedule, 0.0, 1.0) - bond.calculate_price()).abs() < 1e-12);
+
+    // The coupon of 15 July 2024 is paid before the evaluation date
+    let price = bond.coupon_bond(&schedule, 0.04, 100.0);
+    let expected = schedule
+      .bond_cashflows(0.04, 100.0)
+      .iter()
+      .skip(1)
+      .map(|c| c.amount * bond.zero_coupon(DayCount::Act365Fixed.year_fraction(eval, c.date)))
+      .sum::<f64>();
+    assert!((price - expected).abs() < 1e-12);
+  }
+}
@@ -1,20 +1,23 @@
-use crate::quant::r#trait::Pricer;
+use impl_new_derive::ImplNew;
+
+use super::ZeroCouponBond;
+use crate::quant::r#trait::{Pricer, Time};
 
 /// CIR model for zero-coupon bond pricing
 /// dR(t) = theta(mu - R(t))dt + sigma * sqrt(R(t))dW(t)
 /// where R(t) is the short rate.
-#[derive(Default, Debug)]
+#[derive(ImplNew, Default, Debug, Clone)]
 pub struct CIR {
   /// Short rate
   pub r_t: f64,
-  /// Long-term mean of the short rate
-  pub theta: f64,
   /// Mean reversion speed
+  pub theta: f64,
+  /// Long-term mean of the short rate
   pub mu: f64,
   /// Volatility
   pub sigma: f64,
-  /// Maturity of the bond in days
-  pub tau: f64,
+  /// Maturity of the bond in years
+  pub tau: Option<f64>,
   /// Evaluation date
   pub eval: Option<chrono::NaiveDate>,
   /// Expiration date
@@ -23,27 +26,33 @@ pub struct CIR {
 
 impl Pricer for CIR {
   fn calculate_price(&self) -> f64 {
-    let tau = self.calculate_tau_in_days();
+    self.zero_coupon(self.tau.unwrap_or_else(|| self.calculate_tau_in_years()))
+  }
+}
 
+impl ZeroCouponBond for CIR {
+  fn zero_coupon(&self, tau: f64) -> f64 {
     let h = (self.theta.powi(2) + 2.0 * self.sigma.powi(2)).sqrt();
-    let A = ((2.0 * h * ((self.theta + h) * (tau / 2.0)).exp())
+    let a = ((2.0 * h * ((self.theta + h) * (tau / 2.0)).exp())
       / (2.0 * h + (self.theta + h) * ((h * tau).exp() - 1.0)))
       .powf((2.0 * self.theta * self.mu) / (self.sigma.powi(2)));
-    let B =
+    let b =
       (2.0 * ((h * tau).exp() - 1.0)) / (2.0 * h + (self.theta + h) * ((h * tau).exp() - 1.0));
 
-    A * (self.r_t * B).exp()
+    a * (-b * self.r_t).exp()
   }
+}
 
+impl Time for CIR {
   fn tau(&self) -> Option<f64> {
-    Some(self.tau)
+    self.tau
   }
 
-  fn eval(&self) -> Option<chrono::NaiveDate> {
-    self.eval
+  fn eval(&self) -> chrono::NaiveDate {
+    self.eval.unwrap()
   }
 
-  fn expiration(&self) -> Option<chrono::NaiveDate> {
-    self.expiration
+  fn expiration(&self) -> chrono::NaiveDate {
+    self.expiration.unwrap()
   }
 }
@@ -1,22 +1,24 @@
-use chrono::{Datelike, Utc};
+use gauss_quad::GaussLegendre;
+use impl_new_derive::ImplNew;
 
-use crate::quant::r#trait::Pricer;
+use super::ZeroCouponBond;
+use crate::quant::r#trait::{Pricer, Time};
 
 /// Hull-White model for zero-coupon bond pricing
-/// dR(t) = (theta(t) - aR(t))dt + sigma(t)dW(t)
+/// dR(t) = (theta(t) - aR(t))dt + sigma dW(t)
 /// where R(t) is the short rate.
-#[derive(Debug)]
+#[derive(ImplNew, Debug, Clone)]
 pub struct HullWhite {
   /// Short rate
   pub r_t: f64,
-  /// Long-term mean of the short rate
+  /// Drift of the short rate in the time from the evaluation date
   pub theta: fn(f64) -> f64,
   /// Mean reversion speed
   pub alpha: f64,
   /// Volatility
   pub sigma: f64,
-  /// Maturity of the bond in days
-  pub tau: f64,
+  /// Maturity of the bond in years
+  pub tau: Option<f64>,
   /// Evaluation date
   pub eval: Option<chrono::NaiveDate>,
   /// Expiration date
@@ -24,36 +26,38 @@ pub struct HullWhite {
 }
 
 impl Pricer for HullWhite {
-  /// Calculate the price of the zero-coupon bond (unstable)
   fn calculate_price(&self) -> f64 {
-    let tau = self.calculate_tau_in_years();
-    let today = Utc::now().year() as f64;
-    let S = self.eval.unwrap().year() as f64 - today;
-    let T = self.expiration.unwrap().year() as f64 - today;
-    let p0t = (-self.r_t * T).exp();
-    let p0s = (-self.r_t * S).exp();
-
-    let B = (1.0 - (-self.alpha * tau).exp()) / self.alpha;
-    let A = p0t / p0s
-      * (-B * self.r_t
-        - (self.sigma.powi(2)
-          * ((-self.alpha * T).exp() - (-self.alpha * S).exp()).powi(2)
-          * ((2.0 * self.alpha * S) - 1.0))
-          / (4.0 * self.alpha.powi(3)))
-      .exp();
-
-    A * (-B * self.r_t).exp()
+    self.zero_coupon(self.tau.unwrap_or_else(|| self.calculate_tau_in_years()))
   }
+}
+
+impl ZeroCouponBond for HullWhite {
+  /// ln P(tau) = -B(0, tau) r - int theta(s) B(s, tau) ds + sigma^2 / 2 int B(s, tau)^2 ds
+  /// with B(s, tau) = (1 - e^(-alpha (tau - s))) / alpha, the drift integrated by quadrature
+  fn zero_coupon(&self, tau: f64) -> f64 {
+    let a = self.alpha;
+    let b = |s: f64| (1.0 - (-a * (tau - s)).exp()) / a;
+    let drift = GaussLegendre::new(32)
+      .unwrap()
+      .integrate(0.0, tau, |s| (self.theta)(s) * b(s));
+    let variance = (tau - 2.0 * (1.0 - (-a * tau).exp()) / a
+      + (1.0 - (-2.0 * a * tau).exp()) / (2.0 * a))
+      / a.powi(2);
+
+    (-b(0.0) * self.r_t - drift + 0.5 * self.sigma.powi(2) * variance).exp()
+  }
+}
 
+impl Time for HullWhite {
   fn tau(&self) -> Option<f64> {
-    Some(self.tau)
+    self.tau
   }
 
-  fn eval(&self) -> Option<chrono::NaiveDate> {
-    self.eval
+  fn eval(&self) -> chrono::NaiveDate {
+    self.eval.unwrap()
   }
 
-  fn expiration(&self) -> Option<chrono::NaiveDate> {
-    self.expiration
+  fn expiration(&self) -> chrono::NaiveDate {
+    self.expiration.unwrap()
   }
 }
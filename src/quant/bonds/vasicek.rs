@@ -1,20 +1,23 @@
-use crate::quant::r#trait::Pricer;
+use impl_new_derive::ImplNew;
+
+use super::ZeroCouponBond;
+use crate::quant::r#trait::{Pricer, Time};
 
 /// Vasicek model for zero-coupon bond pricing
 /// dR(t) = theta(mu - R(t))dt + sigma dW(t)
 /// where R(t) is the short rate.
-#[derive(Default, Debug)]
+#[derive(ImplNew, Default, Debug, Clone)]
 pub struct Vasicek {
   /// Short rate
   pub r_t: f64,
-  /// Long-term mean of the short rate
-  pub theta: f64,
   /// Mean reversion speed
+  pub theta: f64,
+  /// Long-term mean of the short rate
   pub mu: f64,
   /// Volatility
   pub sigma: f64,
-  /// Maturity of the bond in days
-  pub tau: f64,
+  /// Maturity of the bond in years
+  pub tau: Option<f64>,
   /// Evaluation date
   pub eval: Option<chrono::NaiveDate>,
   /// Expiration date
@@ -23,24 +26,30 @@ pub struct Vasicek {
 
 impl Pricer for Vasicek {
   fn calculate_price(&self) -> f64 {
-    let tau = self.calculate_tau_in_days();
+    self.zero_coupon(self.tau.unwrap_or_else(|| self.calculate_tau_in_years()))
+  }
+}
 
-    let B = (1.0 - (-self.theta * tau).exp()) / self.theta;
-    let A = (self.mu - (self.sigma.powi(2) / (2.0 * self.theta.powi(2)))) * (B - tau)
-      - (self.sigma.powi(2) / (4.0 * self.theta)) * B.powi(2);
+impl ZeroCouponBond for Vasicek {
+  fn zero_coupon(&self, tau: f64) -> f64 {
+    let b = (1.0 - (-self.theta * tau).exp()) / self.theta;
+    let a = (self.mu - (self.sigma.powi(2) / (2.0 * self.theta.powi(2)))) * (b - tau)
+      - (self.sigma.powi(2) / (4.0 * self.theta)) * b.powi(2);
 
-    (A - B * self.r_t).exp()
+    (a - b * self.r_t).exp()
   }
+}
 
+impl Time for Vasicek {
   fn tau(&self) -> Option<f64> {
-    Some(self.tau)
+    self.tau
   }
 
-  fn eval(&self) -> Option<chrono::NaiveDate> {
-    self.eval
+  fn eval(&self) -> chrono::NaiveDate {
+    self.eval.unwrap()
   }
 
-  fn expiration(&self) -> Option<chrono::NaiveDate> {
-    self.expiration
+  fn expiration(&self) -> chrono::NaiveDate {
+    self.expiration.unwrap()
   }
 }
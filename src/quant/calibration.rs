@@ -1,2 +1,7 @@
+pub mod bates;
 pub mod bsm;
 pub mod heston;
+pub mod kou;
+pub mod sabr;
+pub mod svi;
+pub mod vgsa;
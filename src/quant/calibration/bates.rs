@@ -0,0 +1,367 @@
+use std::cell::RefCell;
+
+use impl_new_derive::ImplNew;
+use levenberg_marquardt::{LeastSquaresProblem, LevenbergMarquardt};
+use nalgebra::{DMatrix, DVector, Dyn, Owned};
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use super::heston::HestonParams;
+use crate::quant::{pricing::bates::BatesPricer, r#trait::Pricer, OptionType};
+
+/// Bates model parameters, the Heston ones and the lognormal jumps
+#[derive(Clone, Debug)]
+pub struct BatesParams {
+  pub v0: f64,
+  pub theta: f64,
+  pub rho: f64,
+  pub kappa: f64,
+  pub sigma: f64,
+  /// Jump intensity
+  pub lambda: f64,
+  /// Mean of the log jump sizes
+  pub mu_j: f64,
+  /// Standard deviation of the log jump sizes
+  pub sigma_j: f64,
+}
+
+impl BatesParams {
+  /// Heston parameters with the given jumps, e.g. to start from a Heston calibration
+  pub fn from_heston(params: HestonParams, lambda: f64, mu_j: f64, sigma_j: f64) -> Self {
+    Self {
+      v0: params.v0,
+      theta: params.theta,
+      rho: params.rho,
+      kappa: params.kappa,
+      sigma: params.sigma,
+      lambda,
+      mu_j,
+      sigma_j,
+    }
+  }
+}
+
+impl From<BatesParams> for DVector<f64> {
+  fn from(params: BatesParams) -> Self {
+    DVector::from_vec(vec![
+      params.v0,
+      params.theta,
+      params.rho,
+      params.kappa,
+      params.sigma,
+      params.lambda,
+      params.mu_j,
+      params.sigma_j,
+    ])
+  }
+}
+
+impl From<DVector<f64>> for BatesParams {
+  fn from(params: DVector<f64>) -> Self {
+    BatesParams {
+      v0: params[0],
+      theta: params[1],
+      rho: params[2],
+      kappa: params[3],
+      sigma: params[4],
+      lambda: params[5],
+      mu_j: params[6],
+      sigma_j: params[7],
+    }
+  }
+}
+
+/// Names of the Bates parameters in the order of the parameter vector
+pub const BATES_PARAMETERS: [&str; 8] = [
+  "v0", "theta", "rho", "kappa", "sigma", "lambda", "mu_j", "sigma_j",
+];
+
+/// Calibrated Bates parameters with their uncertainty, see
+/// [`HestonCalibrationResult`](super::heston::HestonCalibrationResult)
+///
+/// The jump parameters are often poorly identified by a single expiry, the intensity
+/// trades off against the size of the jumps, which shows up as a strong correlation.
+#[derive(Clone, Debug)]
+pub struct BatesCalibrationResult {
+  /// Calibrated parameters
+  pub params: BatesParams,
+  /// Covariance of the parameters in the order of [`BATES_PARAMETERS`], NaN if J^T J is
+  /// singular
+  pub covariance: DMatrix<f64>,
+  /// Standard errors of the parameters
+  pub standard_errors: BatesParams,
+  /// Residual sum of squares of the prices
+  pub rss: f64,
+  /// Degrees of freedom of the residuals, number of quotes minus 8
+  pub dof: usize,
+}
+
+impl BatesCalibrationResult {
+  /// Two sided confidence intervals (lower, upper) of the given level, e.g. 0.95, from the
+  /// Student t distribution with dof degrees of freedom
+  pub fn confidence_intervals(&self, level: f64) -> (BatesParams, BatesParams) {
+    assert!(level > 0.0 && level < 1.0, "level must be in (0, 1)");
+    let quantile = StudentsT::new(0.0, 1.0, self.dof.max(1) as f64)
+      .unwrap()
+      .inverse_cdf(0.5 + level / 2.0);
+    let params = DVector::from(self.params.clone());
+    let errors = DVector::from(self.standard_errors.clone()) * quantile;
+
+    ((&params - &errors).into(), (params + errors).into())
+  }
+
+  /// Correlation matrix of the parameters
+  pub fn correlation(&self) -> DMatrix<f64> {
+    let std = self.covariance.diagonal().map(f64::sqrt);
+    DMatrix::from_fn(8, 8, |i, j| self.covariance[(i, j)] / (std[i] * std[j]))
+  }
+}
+
+/// A calibrator.
+#[derive(ImplNew, Clone)]
+pub struct BatesCalibrator {
+  /// Params to calibrate.
+  pub params: BatesParams,
+  /// Option prices from the market.
+  pub c_market: DVector<f64>,
+  /// Asset price vector.
+  pub s: DVector<f64>,
+  /// Strike price vector.
+  pub k: DVector<f64>,
+  /// Time to maturity.
+  pub tau: f64,
+  /// Risk-free rate.
+  pub r: f64,
+  /// Dividend yield.
+  pub q: Option<f64>,
+  /// Option type
+  pub option_type: OptionType,
+  /// Derivate matrix.
+  derivates: RefCell<Vec<Vec<f64>>>,
+}
+
+impl BatesCalibrator {
+  pub fn calibrate(&self) -> BatesCalibrationResult {
+    println!("Initial guess: {:?}", self.params);
+
+    let (result, ..) = LevenbergMarquardt::new().minimize(self.clone());
+
+    println!("Market prices: {:?}", self.c_market);
+    let residuals = result.residuals().unwrap();
+    println!("Model prices: {:?}", self.c_market.clone() + residuals);
+    println!("Calibration report: {:?}", result.params);
+
+    let report = result.calibration_result();
+    println!("Standard errors: {:?}", report.standard_errors);
+    report
+  }
+
+  /// Parameter covariance and standard errors at the current parameters, see
+  /// [`BatesCalibrationResult`]
+  pub fn calibration_result(&self) -> BatesCalibrationResult {
+    let residuals = self.residuals().unwrap();
+    let jacobian = self.jacobian().unwrap();
+
+    let rss = residuals.norm_squared();
+    let dof = residuals.len().saturating_sub(8);
+    let variance = if dof > 0 { rss / dof as f64 } else { f64::NAN };
+    let covariance = (jacobian.transpose() * &jacobian)
+      .try_inverse()
+      .map(|inverse| inverse * variance)
+      .unwrap_or_else(|| DMatrix::from_element(8, 8, f64::NAN));
+    let standard_errors = covariance.diagonal().map(f64::sqrt).into();
+
+    BatesCalibrationResult {
+      params: self.params.clone(),
+      covariance,
+      standard_errors,
+      rss,
+      dof,
+    }
+  }
+}
+
+/// Project the i-th parameter to the admissible region of the pricer
+fn project(i: usize, value: f64) -> f64 {
+  match i {
+    0 | 5 | 7 => value.max(0.0),
+    2 => value.clamp(-0.999, 0.999),
+    6 => value,
+    _ => value.max(1e-4),
+  }
+}
+
+impl LeastSquaresProblem<f64, Dyn, Dyn> for BatesCalibrator {
+  type JacobianStorage = Owned<f64, Dyn, Dyn>;
+  type ParameterStorage = Owned<f64, Dyn>;
+  type ResidualStorage = Owned<f64, Dyn>;
+
+  /// Parameters projected to the admissible region, a negative intensity or jump
+  /// volatility has no meaning
+  fn set_params(&mut self, params: &DVector<f64>) {
+    let params = DVector::from_fn(params.len(), |i, _| project(i, params[i]));
+    self.params = BatesParams::from(params);
+  }
+
+  fn params(&self) -> DVector<f64> {
+    self.params.clone().into()
+  }
+
+  fn residuals(&self) -> Option<DVector<f64>> {
+    let mut c_model = DVector::zeros(self.c_market.len());
+    let mut derivates = Vec::new();
+
+    for (idx, _) in self.c_market.iter().enumerate() {
+      let pricer = BatesPricer::new(
+        self.s[idx],
+        self.params.v0,
+        self.k[idx],
+        self.r,
+        self.q,
+        self.params.rho,
+        self.params.kappa,
+        self.params.theta,
+        self.params.sigma,
+        self.params.lambda,
+        self.params.mu_j,
+        self.params.sigma_j,
+        Some(self.tau),
+        None,
+        None,
+      );
+      let (call, put) = pricer.calculate_call_put();
+
+      match self.option_type {
+        OptionType::Call => c_model[idx] = call,
+        OptionType::Put => c_model[idx] = put,
+      }
+
+      derivates.push(pricer.derivatives());
+    }
+
+    let _ = std::mem::replace(&mut *self.derivates.borrow_mut(), derivates);
+    Some(c_model - self.c_market.clone())
+  }
+
+  fn jacobian(&self) -> Option<DMatrix<f64>> {
+    let derivates = self.derivates.borrow();
+    let derivates = derivates.iter().flatten().cloned().collect::<Vec<f64>>();
+
+    // One row per quote with the derivatives of the price with respect to the Heston
+    // parameters followed by the jump intensity, mean and volatility
+    let jacobian = DMatrix::from_row_slice(derivates.len() / 8, 8, &derivates);
+
+    Some(jacobian)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn prices(params: &BatesParams, s: f64, k: &[f64], r: f64, tau: f64) -> Vec<f64> {
+    k.iter()
+      .map(|&k| {
+        BatesPricer::new(
+          s,
+          params.v0,
+          k,
+          r,
+          None,
+          params.rho,
+          params.kappa,
+          params.theta,
+          params.sigma,
+          params.lambda,
+          params.mu_j,
+          params.sigma_j,
+          Some(tau),
+          None,
+          None,
+        )
+        .calculate_call_put()
+        .0
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_bates_calibrate() {
+    let (s, r, tau) = (100.0, 0.02, 0.5);
+    let truth = BatesParams {
+      v0: 0.04,
+      theta: 0.05,
+      rho: -0.6,
+      kappa: 1.5,
+      sigma: 0.4,
+      lambda: 0.6,
+      mu_j: -0.12,
+      sigma_j: 0.15,
+    };
+    let k = (0..21).map(|i| 70.0 + 3.0 * i as f64).collect::<Vec<_>>();
+    let c_market = prices(&truth, s, &k, r, tau);
+
+    let calibrator = BatesCalibrator::new(
+      BatesParams {
+        v0: 0.05,
+        theta: 0.04,
+        rho: -0.4,
+        kappa: 2.0,
+        sigma: 0.3,
+        lambda: 0.3,
+        mu_j: -0.05,
+        sigma_j: 0.1,
+      },
+      c_market.clone().into(),
+      vec![s; k.len()].into(),
+      k.clone().into(),
+      tau,
+      r,
+      None,
+      OptionType::Call,
+    );
+    let result = calibrator.calibrate();
+
+    let rmse = (result.rss / k.len() as f64).sqrt();
+    assert!(rmse < 1e-3, "{}", rmse);
+    assert_eq!(result.dof, 13);
+    assert!(result.params.lambda >= 0.0 && result.params.sigma_j >= 0.0);
+  }
+
+  #[test]
+  fn test_bates_jacobian_includes_jumps() {
+    let (s, r, tau) = (100.0, 0.02, 0.5);
+    let truth = BatesParams::from_heston(
+      HestonParams {
+        v0: 0.04,
+        theta: 0.05,
+        rho: -0.6,
+        kappa: 1.5,
+        sigma: 0.4,
+      },
+      0.6,
+      -0.12,
+      0.15,
+    );
+    let k = [90.0, 100.0, 110.0];
+    let calibrator = BatesCalibrator::new(
+      truth.clone(),
+      prices(&truth, s, &k, r, tau).into(),
+      vec![s; k.len()].into(),
+      k.to_vec().into(),
+      tau,
+      r,
+      None,
+      OptionType::Call,
+    );
+
+    assert!(calibrator.residuals().unwrap().amax() < 1e-12);
+    let jacobian = calibrator.jacobian().unwrap();
+    assert_eq!(jacobian.shape(), (3, 8));
+
+    // More jumps with a negative mean make the out of the money puts more expensive, the
+    // calls have the same sensitivities by put-call parity
+    assert!(jacobian[(0, 5)] > 0.0);
+    assert!(jacobian[(0, 6)] < 0.0);
+    assert!(jacobian[(0, 7)] > 0.0);
+  }
+}
@@ -1,12 +1,17 @@
-use std::cell::RefCell;
-
 use impl_new_derive::ImplNew;
+use implied_vol::implied_black_volatility;
 use levenberg_marquardt::{LeastSquaresProblem, LevenbergMarquardt};
 use nalgebra::{DMatrix, DVector, Dyn, Owned};
-use ndarray::Array1;
+use ndarray::{Array1, Array2};
+use statrs::distribution::{ContinuousCDF, StudentsT};
 
+use super::svi::{heston_from_svi, SviRaw};
 use crate::{
-  quant::{pricing::heston::HestonPricer, r#trait::Pricer, OptionType},
+  quant::{
+    pricing::heston::{HestonPricer, COS},
+    r#trait::Pricer,
+    OptionType,
+  },
   stats::mle::nmle_heston,
 };
 
@@ -44,6 +49,70 @@ impl From<DVector<f64>> for HestonParams {
   }
 }
 
+/// Calibrated Heston parameters with their uncertainty
+///
+/// The covariance is the Gauss-Newton approximation s^2 (J^T J)^-1 at the optimum, where
+/// J is the Jacobian of the model prices and s^2 = RSS / (n - 5) is the residual variance.
+/// Large standard errors or strongly correlated parameters mean the quotes do not
+/// identify the parameters well.
+#[derive(Clone, Debug)]
+pub struct HestonCalibrationResult {
+  /// Calibrated parameters
+  pub params: HestonParams,
+  /// Covariance of (v0, theta, rho, kappa, sigma), NaN if J^T J is singular
+  pub covariance: DMatrix<f64>,
+  /// Standard errors of the parameters
+  pub standard_errors: HestonParams,
+  /// Residual sum of squares of the prices
+  pub rss: f64,
+  /// Degrees of freedom of the residuals, number of quotes minus 5
+  pub dof: usize,
+}
+
+impl HestonCalibrationResult {
+  /// Two sided confidence intervals (lower, upper) of the given level, e.g. 0.95, from the
+  /// Student t distribution with dof degrees of freedom
+  pub fn confidence_intervals(&self, level: f64) -> (HestonParams, HestonParams) {
+    assert!(level > 0.0 && level < 1.0, "level must be in (0, 1)");
+    let quantile = StudentsT::new(0.0, 1.0, self.dof.max(1) as f64)
+      .unwrap()
+      .inverse_cdf(0.5 + level / 2.0);
+    let params = DVector::from(self.params.clone());
+    let errors = DVector::from(self.standard_errors.clone()) * quantile;
+
+    ((&params - &errors).into(), (params + errors).into())
+  }
+
+  /// Correlation matrix of the parameters
+  pub fn correlation(&self) -> DMatrix<f64> {
+    let std = self.covariance.diagonal().map(f64::sqrt);
+    DMatrix::from_fn(5, 5, |i, j| self.covariance[(i, j)] / (std[i] * std[j]))
+  }
+}
+
+/// Names of the Heston parameters in the order of the parameter vector
+pub const HESTON_PARAMETERS: [&str; 5] = ["v0", "theta", "rho", "kappa", "sigma"];
+
+/// Profile of the calibration error along one parameter
+///
+/// The parameter is held fixed on a grid around the optimum and the other four are
+/// re-calibrated at every grid point. A profile that barely rises is a flat direction of
+/// the objective, the parameter is then not identified by the quotes and trades off against
+/// the others, e.g. kappa against sigma, which can be read off the re-calibrated parameters.
+#[derive(Clone, Debug)]
+pub struct HestonProfile {
+  /// Name of the profiled parameter
+  pub parameter: &'static str,
+  /// Values of the profiled parameter
+  pub grid: Array1<f64>,
+  /// Root mean squared price error at every grid point
+  pub rmse: Array1<f64>,
+  /// Re-calibrated (v0, theta, rho, kappa, sigma), one row per grid point
+  pub params: Array2<f64>,
+  /// The RMSE rises less than the tolerance over the grid
+  pub flat: bool,
+}
+
 /// A calibrator.
 #[derive(ImplNew, Clone)]
 pub struct HestonCalibrator {
@@ -63,12 +132,10 @@ pub struct HestonCalibrator {
   pub q: Option<f64>,
   /// Option type
   pub option_type: OptionType,
-  /// Derivate matrix.
-  derivates: RefCell<Vec<Vec<f64>>>,
 }
 
 impl HestonCalibrator {
-  pub fn calibrate(&self) {
+  pub fn calibrate(&self) -> HestonCalibrationResult {
     println!("Initial guess: {:?}", self.params);
 
     let (result, ..) = LevenbergMarquardt::new().minimize(self.clone());
@@ -83,6 +150,161 @@ impl HestonCalibrator {
 
     // Print the result of the calibration
     println!("Calibration report: {:?}", result.params);
+
+    let report = result.calibration_result();
+    println!("Standard errors: {:?}", report.standard_errors);
+    report
+  }
+
+  /// Parameter covariance and standard errors at the current parameters, see
+  /// [`HestonCalibrationResult`]
+  pub fn calibration_result(&self) -> HestonCalibrationResult {
+    let residuals = self.residuals().unwrap();
+    let jacobian = self.model_jacobian();
+
+    let rss = residuals.norm_squared();
+    let dof = residuals.len().saturating_sub(5);
+    let variance = if dof > 0 { rss / dof as f64 } else { f64::NAN };
+    let covariance = (jacobian.transpose() * &jacobian)
+      .try_inverse()
+      .map(|inverse| inverse * variance)
+      .unwrap_or_else(|| DMatrix::from_element(5, 5, f64::NAN));
+    let standard_errors = covariance.diagonal().map(f64::sqrt).into();
+
+    HestonCalibrationResult {
+      params: self.params.clone(),
+      covariance,
+      standard_errors,
+      rss,
+      dof,
+    }
+  }
+
+  /// Profile RMSE curves of all parameters around the current (calibrated) parameters
+  ///
+  /// Every parameter p is profiled on `points` values spanning p +- width max(|p|, 0.1),
+  /// restricted to the admissible region, and flagged flat if the RMSE rises by less than
+  /// `tolerance` (in price units, e.g. half the bid-ask spread) over the grid. The order of
+  /// the profiles is [`HESTON_PARAMETERS`].
+  pub fn profile(&self, width: f64, points: usize, tolerance: f64) -> Vec<HestonProfile> {
+    assert!(points >= 2, "points must be at least 2");
+    let optimum = DVector::from(self.params.clone());
+    let rmse_at = |calibrator: &Self| {
+      let residuals = calibrator.residuals().unwrap();
+      (residuals.norm_squared() / residuals.len() as f64).sqrt()
+    };
+    let rmse_optimum = rmse_at(self);
+
+    (0..5)
+      .map(|fixed| {
+        let scale = width * optimum[fixed].abs().max(0.1);
+        let grid = Array1::from_shape_fn(points, |i| {
+          let offset = (2.0 * i as f64 - (points - 1) as f64) / (points - 1) as f64;
+          project(fixed, optimum[fixed] + offset * scale)
+        });
+        let mut rmse = Array1::zeros(points);
+        let mut params = Array2::zeros((points, 5));
+
+        for (i, &value) in grid.iter().enumerate() {
+          if value == optimum[fixed] {
+            rmse[i] = rmse_optimum;
+            params
+              .row_mut(i)
+              .assign(&Array1::from_iter(optimum.iter().cloned()));
+            continue;
+          }
+
+          let mut start = optimum.clone();
+          start[fixed] = value;
+          let mut calibrator = self.clone();
+          calibrator.set_params(&start);
+
+          // The profile only needs the RMSE to a few digits
+          let (problem, ..) = LevenbergMarquardt::new()
+            .with_ftol(1e-6)
+            .with_xtol(1e-6)
+            .with_patience(10)
+            .minimize(ProfileProblem { calibrator, fixed });
+          rmse[i] = rmse_at(&problem.calibrator);
+          params.row_mut(i).assign(&Array1::from_iter(
+            DVector::from(problem.calibrator.params).iter().cloned(),
+          ));
+        }
+
+        let rise = rmse.fold(f64::NEG_INFINITY, |a: f64, &b| a.max(b)) - rmse_optimum;
+        HestonProfile {
+          parameter: HESTON_PARAMETERS[fixed],
+          grid,
+          rmse,
+          params,
+          flat: rise < tolerance,
+        }
+      })
+      .collect()
+  }
+
+  /// Adjoint gradient J^T r of half the residual sum of squares
+  ///
+  /// The residuals are pulled back to the terms of the COS expansion of the quotes, so the
+  /// gradient costs one extra pass over the expansion for the whole chain whatever the
+  /// number of strikes, without forming the Jacobian, e.g. for gradient-based optimizers
+  /// on large chains.
+  pub fn gradient(&self) -> DVector<f64> {
+    let residuals = self.residuals().unwrap();
+    let weights = residuals
+      .iter()
+      .zip(self.s.iter())
+      .map(|(r, s)| r * s)
+      .collect::<Vec<_>>();
+    let (n, l) = COS;
+
+    DVector::from_row_slice(&self.unit_pricer().cos_adjoint_gradient(
+      &self.moneyness(),
+      &weights,
+      n,
+      l,
+    ))
+  }
+
+  /// Jacobian of the model prices, the exact derivatives of their COS expansion
+  ///
+  /// The prices are homogeneous in the spot and the strike, C(S, K) = S C(1, K / S), so
+  /// all quotes share the expansion of the pricer with unit spot whatever their spots and
+  /// the characteristic function and its gradient are evaluated once for the chain.
+  fn model_jacobian(&self) -> DMatrix<f64> {
+    let (n, l) = COS;
+    let jacobian = self.unit_pricer().cos_jacobian(&self.moneyness(), n, l);
+
+    DMatrix::from_fn(self.c_market.len(), 5, |i, j| self.s[i] * jacobian[[i, j]])
+  }
+
+  /// Pricer with unit spot and strike at the current parameters
+  fn unit_pricer(&self) -> HestonPricer {
+    HestonPricer::new(
+      1.0,
+      self.params.v0,
+      1.0,
+      self.r,
+      self.q,
+      self.params.rho,
+      self.params.kappa,
+      self.params.theta,
+      self.params.sigma,
+      None,
+      Some(self.tau),
+      None,
+      None,
+    )
+  }
+
+  /// Strikes of the quotes relative to their spots, K / S
+  fn moneyness(&self) -> Vec<f64> {
+    self
+      .k
+      .iter()
+      .zip(self.s.iter())
+      .map(|(k, s)| k / s)
+      .collect()
   }
 
   /// Initial guess for the calibration
@@ -92,9 +314,80 @@ impl HestonCalibrator {
   pub fn set_initial_params(&mut self, s: Array1<f64>, v: Array1<f64>, r: f64) {
     self.params = nmle_heston(s, v, r);
   }
+
+  /// Initial guess for the calibration from the market smile
+  ///
+  /// Fits SVI to the implied volatilities of the quotes and maps the fit to Heston
+  /// parameters, see [`heston_from_svi`]. With a single expiry the variance is flat
+  /// (v0 = theta) and kappa is 1.
+  pub fn set_initial_params_svi(&mut self) {
+    let q = self.q.unwrap_or(0.0);
+    let (k, w): (Vec<_>, Vec<_>) = self
+      .c_market
+      .iter()
+      .zip(self.s.iter().zip(self.k.iter()))
+      .filter_map(|(&price, (&s, &k))| {
+        let forward = s * ((self.r - q) * self.tau).exp();
+        let vol = implied_black_volatility(
+          price * (self.r * self.tau).exp(),
+          forward,
+          k,
+          self.tau,
+          self.option_type == OptionType::Call,
+        );
+
+        (vol.is_finite() && vol > 0.0).then(|| ((k / forward).ln(), vol.powi(2) * self.tau))
+      })
+      .unzip();
+
+    self.params = heston_from_svi(&[(self.tau, SviRaw::fit(&k, &w))]);
+  }
+}
+
+/// Project the i-th parameter to the admissible region of the pricer
+fn project(i: usize, value: f64) -> f64 {
+  match i {
+    0 => value.max(0.0),
+    2 => value.clamp(-0.999, 0.999),
+    _ => value.max(1e-4),
+  }
+}
+
+/// Calibration with one parameter held fixed, used for the profiles
+struct ProfileProblem {
+  calibrator: HestonCalibrator,
+  /// Index of the fixed parameter
+  fixed: usize,
 }
 
-impl<'a> LeastSquaresProblem<f64, Dyn, Dyn> for HestonCalibrator {
+impl LeastSquaresProblem<f64, Dyn, Dyn> for ProfileProblem {
+  type JacobianStorage = Owned<f64, Dyn, Dyn>;
+  type ParameterStorage = Owned<f64, Dyn>;
+  type ResidualStorage = Owned<f64, Dyn>;
+
+  /// Free parameters, projected to the admissible region
+  fn set_params(&mut self, params: &DVector<f64>) {
+    let mut full = DVector::from(self.calibrator.params.clone());
+    for (j, &value) in (0..5).filter(|&j| j != self.fixed).zip(params.iter()) {
+      full[j] = project(j, value);
+    }
+    self.calibrator.set_params(&full);
+  }
+
+  fn params(&self) -> DVector<f64> {
+    DVector::from(self.calibrator.params.clone()).remove_row(self.fixed)
+  }
+
+  fn residuals(&self) -> Option<DVector<f64>> {
+    self.calibrator.residuals()
+  }
+
+  fn jacobian(&self) -> Option<DMatrix<f64>> {
+    Some(self.calibrator.model_jacobian().remove_column(self.fixed))
+  }
+}
+
+impl LeastSquaresProblem<f64, Dyn, Dyn> for HestonCalibrator {
   type JacobianStorage = Owned<f64, Dyn, Dyn>;
   type ParameterStorage = Owned<f64, Dyn>;
   type ResidualStorage = Owned<f64, Dyn>;
@@ -109,7 +402,6 @@ impl<'a> LeastSquaresProblem<f64, Dyn, Dyn> for HestonCalibrator {
 
   fn residuals(&self) -> Option<DVector<f64>> {
     let mut c_model = DVector::zeros(self.c_market.len());
-    let mut derivates = Vec::new();
 
     for (idx, _) in self.c_market.iter().enumerate() {
       let pricer = HestonPricer::new(
@@ -133,23 +425,15 @@ impl<'a> LeastSquaresProblem<f64, Dyn, Dyn> for HestonCalibrator {
         OptionType::Call => c_model[idx] = call,
         OptionType::Put => c_model[idx] = put,
       }
-
-      derivates.push(pricer.derivatives());
     }
 
-    let _ = std::mem::replace(&mut *self.derivates.borrow_mut(), derivates);
     Some(c_model - self.c_market.clone())
   }
 
+  /// Exact Jacobian of the prices from the COS expansion, one row per quote, the same
+  /// for calls and puts
   fn jacobian(&self) -> Option<DMatrix<f64>> {
-    let derivates = self.derivates.borrow();
-    let derivates = derivates.iter().flatten().cloned().collect::<Vec<f64>>();
-
-    // The Jacobian matrix is a matrix of partial derivatives
-    // of the residuals with respect to the parameters.
-    let jacobian = DMatrix::from_vec(derivates.len() / 5, 5, derivates);
-
-    Some(jacobian)
+    Some(self.model_jacobian())
   }
 }
 
@@ -197,4 +481,245 @@ mod tests {
       calibrator.calibrate();
     }
   }
+
+  #[test]
+  fn test_heston_svi_initial_guess() {
+    let (s, r, tau) = (100.0, 0.02, 0.25);
+    let k = (0..15).map(|i| 80.0 + 3.0 * i as f64).collect::<Vec<_>>();
+    let c_market = k
+      .iter()
+      .map(|&k| {
+        HestonPricer::new(
+          s,
+          0.04,
+          k,
+          r,
+          None,
+          -0.7,
+          2.0,
+          0.04,
+          0.5,
+          Some(0.0),
+          Some(tau),
+          None,
+          None,
+        )
+        .calculate_call_put()
+        .0
+      })
+      .collect::<Vec<_>>();
+
+    let mut calibrator = HestonCalibrator::new(
+      HestonParams {
+        v0: 0.0,
+        theta: 0.0,
+        rho: 0.0,
+        kappa: 0.0,
+        sigma: 0.0,
+      },
+      c_market.into(),
+      vec![s; k.len()].into(),
+      k.into(),
+      tau,
+      r,
+      None,
+      OptionType::Call,
+    );
+    calibrator.set_initial_params_svi();
+    println!("Initial guess: {:?}", calibrator.params);
+
+    assert!((calibrator.params.v0 - 0.04).abs() < 0.002);
+    assert!((calibrator.params.rho + 0.7).abs() < 0.1);
+    assert!((calibrator.params.sigma - 0.5).abs() < 0.1);
+  }
+
+  #[test]
+  fn test_heston_calibration_uncertainty() {
+    let (s, r, tau) = (100.0, 0.02, 0.5);
+    let truth = HestonParams {
+      v0: 0.04,
+      theta: 0.05,
+      rho: -0.6,
+      kappa: 1.5,
+      sigma: 0.4,
+    };
+    let k = (0..20).map(|i| 70.0 + 3.0 * i as f64).collect::<Vec<_>>();
+    let prices = k
+      .iter()
+      .map(|&k| {
+        HestonPricer::new(
+          s,
+          truth.v0,
+          k,
+          r,
+          None,
+          truth.rho,
+          truth.kappa,
+          truth.theta,
+          truth.sigma,
+          None,
+          Some(tau),
+          None,
+          None,
+        )
+        .calculate_call_put()
+        .0
+      })
+      .collect::<Vec<_>>();
+    // Quotes with alternating bid-ask noise of the given size
+    let calibrator = |noise: f64| {
+      let c_market = prices
+        .iter()
+        .enumerate()
+        .map(|(i, p)| p + if i % 2 == 0 { noise } else { -noise })
+        .collect::<Vec<_>>();
+      HestonCalibrator::new(
+        truth.clone(),
+        c_market.into(),
+        vec![s; k.len()].into(),
+        k.clone().into(),
+        tau,
+        r,
+        None,
+        OptionType::Call,
+      )
+    };
+
+    let result = calibrator(0.01).calibration_result();
+    println!("Standard errors: {:?}", result.standard_errors);
+    assert_eq!(result.dof, 15);
+    assert!((result.rss - 20.0 * 0.01 * 0.01).abs() < 1e-10);
+    assert!((&result.covariance - result.covariance.transpose()).amax() < 1e-12);
+    assert!(DVector::from(result.standard_errors.clone())
+      .iter()
+      .all(|se| se.is_finite() && *se > 0.0));
+
+    let (lower, upper) = result.confidence_intervals(0.95);
+    assert!(lower.rho < truth.rho && truth.rho < upper.rho);
+    assert!(lower.kappa < truth.kappa && truth.kappa < upper.kappa);
+
+    // Standard errors scale with the size of the residuals
+    let wider = calibrator(0.02).calibration_result();
+    assert!((wider.standard_errors.sigma / result.standard_errors.sigma - 2.0).abs() < 1e-8);
+  }
+
+  #[test]
+  fn test_heston_adjoint_gradient() {
+    let (r, tau) = (0.02, 0.5);
+    let k = (0..12).map(|i| 80.0 + 4.0 * i as f64).collect::<Vec<_>>();
+    let s = k
+      .iter()
+      .enumerate()
+      .map(|(i, _)| 100.0 + 0.1 * i as f64)
+      .collect::<Vec<_>>();
+    let c_market = k
+      .iter()
+      .zip(s.iter())
+      .map(|(&k, &s)| 0.15 * (s - k).max(0.0) + 2.0)
+      .collect::<Vec<_>>();
+    let calibrator = HestonCalibrator::new(
+      HestonParams {
+        v0: 0.04,
+        theta: 0.05,
+        rho: -0.6,
+        kappa: 1.5,
+        sigma: 0.4,
+      },
+      c_market.into(),
+      s.into(),
+      k.into(),
+      tau,
+      r,
+      None,
+      OptionType::Call,
+    );
+
+    // The Jacobian of the expansion against central differences of the quadrature prices
+    let jacobian = calibrator.jacobian().unwrap();
+    let params = DVector::from(calibrator.params.clone());
+    let mut bumped = calibrator.clone();
+    for j in 0..5 {
+      let h = 1e-3 * params[j].abs().max(0.1);
+      let [up, down] = [h, -h].map(|bump| {
+        let mut params = params.clone();
+        params[j] += bump;
+        bumped.set_params(&params);
+        bumped.residuals().unwrap()
+      });
+      let column = (up - down) / (2.0 * h);
+      assert!(
+        (&column - jacobian.column(j)).amax() < 1e-3,
+        "{} {} {}",
+        HESTON_PARAMETERS[j],
+        column,
+        jacobian.column(j)
+      );
+    }
+
+    // The adjoint gradient without the Jacobian
+    let gradient = calibrator.gradient();
+    let expected = jacobian.transpose() * calibrator.residuals().unwrap();
+    assert!((gradient - &expected).amax() < 1e-8 * expected.amax());
+  }
+
+  #[test]
+  fn test_heston_profile_flags_flat_directions() {
+    let (s, r, tau) = (100.0, 0.02, 0.5);
+    let truth = HestonParams {
+      v0: 0.04,
+      theta: 0.05,
+      rho: -0.6,
+      kappa: 1.5,
+      sigma: 0.4,
+    };
+    let k = (0..7).map(|i| 85.0 + 5.0 * i as f64).collect::<Vec<_>>();
+    let c_market = k
+      .iter()
+      .map(|&k| {
+        HestonPricer::new(
+          s,
+          truth.v0,
+          k,
+          r,
+          None,
+          truth.rho,
+          truth.kappa,
+          truth.theta,
+          truth.sigma,
+          None,
+          Some(tau),
+          None,
+          None,
+        )
+        .calculate_call_put()
+        .0
+      })
+      .collect::<Vec<_>>();
+    let calibrator = HestonCalibrator::new(
+      truth.clone(),
+      c_market.into(),
+      vec![s; k.len()].into(),
+      k.into(),
+      tau,
+      r,
+      None,
+      OptionType::Call,
+    );
+
+    let profiles = calibrator.profile(0.3, 3, 0.01);
+    for profile in &profiles {
+      println!(
+        "{}: grid {:?}, rmse {:?}, flat {}",
+        profile.parameter, profile.grid, profile.rmse, profile.flat
+      );
+      assert_eq!(profile.params.dim(), (3, 5));
+      // The center of the grid is the optimum
+      assert!(profile.rmse[1] < 1e-6);
+    }
+
+    // A single expiry identifies the skew and the curvature of the smile, but not the
+    // term structure of the variance
+    assert!(!profiles[2].flat);
+    assert!(profiles[3].flat);
+  }
 }
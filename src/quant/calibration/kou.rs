@@ -0,0 +1,194 @@
+use impl_new_derive::ImplNew;
+use nalgebra::DVector;
+
+use crate::quant::{pricing::kou::KouPricer, volatility::svi::minimize, OptionType};
+
+/// Kou model parameters, the diffusion and the double exponential jumps
+#[derive(Clone, Debug)]
+pub struct KouParams {
+  /// Volatility of the diffusion
+  pub v: f64,
+  /// Jump intensity
+  pub lambda: f64,
+  /// Probability of an upward jump
+  pub p: f64,
+  /// Rate of the upward jumps
+  pub eta1: f64,
+  /// Rate of the downward jumps
+  pub eta2: f64,
+}
+
+impl From<KouParams> for DVector<f64> {
+  fn from(params: KouParams) -> Self {
+    DVector::from_vec(vec![
+      params.v,
+      params.lambda,
+      params.p,
+      params.eta1,
+      params.eta2,
+    ])
+  }
+}
+
+impl From<DVector<f64>> for KouParams {
+  fn from(params: DVector<f64>) -> Self {
+    KouParams {
+      v: params[0],
+      lambda: params[1],
+      p: params[2],
+      eta1: params[3],
+      eta2: params[4],
+    }
+  }
+}
+
+/// Names of the Kou parameters in the order of the parameter vector
+pub const KOU_PARAMETERS: [&str; 5] = ["v", "lambda", "p", "eta1", "eta2"];
+
+/// Calibrated Kou parameters
+#[derive(Clone, Debug)]
+pub struct KouCalibrationResult {
+  /// Calibrated parameters
+  pub params: KouParams,
+  /// Residual sum of squares of the prices
+  pub rss: f64,
+  /// Degrees of freedom of the residuals, number of quotes minus 5
+  pub dof: usize,
+}
+
+/// Calibrator of the Kou parameters to the option prices of one expiry
+///
+/// Minimizes the squared price differences with Levenberg-Marquardt on unconstrained
+/// coordinates, the logarithms of v, lambda, eta1 - 1 and eta2 and the logit of p, so the
+/// parameters stay admissible. All strikes are priced by one COS expansion of
+/// [`KouPricer`]. Like for Bates, one expiry identifies the jumps poorly, the intensity
+/// trades off against the size of the jumps, and a poor initial guess can end with
+/// p -> 0 and eta1 -> infinity, so start from typical values such as p = 0.4, eta1 = 10
+/// and eta2 = 5.
+#[derive(ImplNew, Clone)]
+pub struct KouCalibrator {
+  /// Params to calibrate, the initial guess
+  pub params: KouParams,
+  /// Option prices from the market
+  pub c_market: DVector<f64>,
+  /// Asset price
+  pub s: f64,
+  /// Strike price vector
+  pub k: DVector<f64>,
+  /// Time to maturity
+  pub tau: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Option type
+  pub option_type: OptionType,
+}
+
+/// Unconstrained coordinates of the parameters
+fn to_coordinates(params: &KouParams) -> DVector<f64> {
+  let p = params.p.clamp(1e-6, 1.0 - 1e-6);
+  DVector::from_vec(vec![
+    params.v.ln(),
+    params.lambda.max(1e-8).ln(),
+    (p / (1.0 - p)).ln(),
+    (params.eta1 - 1.0).max(1e-8).ln(),
+    params.eta2.ln(),
+  ])
+}
+
+fn from_coordinates(x: &DVector<f64>) -> KouParams {
+  KouParams {
+    v: x[0].exp(),
+    lambda: x[1].exp(),
+    p: 1.0 / (1.0 + (-x[2]).exp()),
+    eta1: 1.0 + x[3].exp(),
+    eta2: x[4].exp(),
+  }
+}
+
+impl KouCalibrator {
+  pub fn calibrate(&self) -> KouCalibrationResult {
+    let residuals = |x: &DVector<f64>| self.residuals(&from_coordinates(x));
+    let params = from_coordinates(&minimize(to_coordinates(&self.params), &residuals));
+
+    let rss = self.residuals(&params).norm_squared();
+    println!("Kou calibration: {:?}, RSS: {:e}", params, rss);
+
+    KouCalibrationResult {
+      params,
+      rss,
+      dof: self.c_market.len().saturating_sub(5),
+    }
+  }
+
+  /// Model minus market prices
+  pub fn residuals(&self, params: &KouParams) -> DVector<f64> {
+    let pricer = KouPricer::new(
+      self.s,
+      params.v,
+      self.k[0],
+      self.r,
+      self.q,
+      params.lambda,
+      params.p,
+      params.eta1,
+      params.eta2,
+      Some(self.tau),
+      None,
+      None,
+    );
+    let (calls, puts) = pricer.calculate_calls_puts(self.k.as_slice());
+    let c_model = match self.option_type {
+      OptionType::Call => calls,
+      OptionType::Put => puts,
+    };
+
+    DVector::from_vec(c_model) - &self.c_market
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_kou_calibrate() {
+    let (s, r, tau) = (100.0, 0.02, 0.5);
+    let truth = KouParams {
+      v: 0.15,
+      lambda: 1.2,
+      p: 0.3,
+      eta1: 12.0,
+      eta2: 6.0,
+    };
+    let k = (0..21).map(|i| 70.0 + 3.0 * i as f64).collect::<Vec<_>>();
+    let calibrator = KouCalibrator::new(
+      KouParams {
+        v: 0.18,
+        lambda: 1.0,
+        p: 0.4,
+        eta1: 9.0,
+        eta2: 5.0,
+      },
+      DVector::zeros(k.len()),
+      s,
+      k.clone().into(),
+      tau,
+      r,
+      None,
+      OptionType::Put,
+    );
+    let c_market = calibrator.residuals(&truth);
+    let calibrator = KouCalibrator {
+      c_market,
+      ..calibrator
+    };
+    let result = calibrator.calibrate();
+
+    let rmse = (result.rss / k.len() as f64).sqrt();
+    assert!(rmse < 1e-4, "{}", rmse);
+    assert_eq!(result.dof, 16);
+    assert!(result.params.eta1 > 1.0 && (0.0..=1.0).contains(&result.params.p));
+  }
+}
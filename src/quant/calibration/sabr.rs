@@ -0,0 +1,234 @@
+use impl_new_derive::ImplNew;
+use levenberg_marquardt::{LeastSquaresProblem, LevenbergMarquardt};
+use nalgebra::{DMatrix, DVector, Dyn, Owned};
+
+use crate::quant::volatility::sabr::{SabrExpansion, SabrParams};
+
+impl From<SabrParams> for DVector<f64> {
+  fn from(params: SabrParams) -> Self {
+    DVector::from_vec(vec![params.alpha, params.beta, params.rho, params.nu])
+  }
+}
+
+impl From<DVector<f64>> for SabrParams {
+  fn from(params: DVector<f64>) -> Self {
+    SabrParams {
+      alpha: params[0],
+      beta: params[1],
+      rho: params[2],
+      nu: params[3],
+    }
+  }
+}
+
+/// Calibrator of the SABR parameters to the implied volatilities of one expiry
+///
+/// Minimizes the squared differences between the market volatilities and the Hagan
+/// expansion over (alpha, beta, rho, nu) with Levenberg-Marquardt on unconstrained
+/// coordinates. beta and rho both drive the skew and are hard to separate from one slice,
+/// so beta is often fixed, e.g. to 0.5 or from a historical regression of the ATM vol on
+/// the forward.
+#[derive(ImplNew, Clone)]
+pub struct SABRCalibrator {
+  /// Params to calibrate, the initial guess
+  pub params: SabrParams,
+  /// Implied volatilities from the market
+  pub vols: DVector<f64>,
+  /// Forward price
+  pub f: f64,
+  /// Strike price vector
+  pub k: DVector<f64>,
+  /// Time to maturity
+  pub tau: f64,
+  /// Expansion of the market volatilities, lognormal if None
+  pub expansion: Option<SabrExpansion>,
+  /// Keep beta at its initial value
+  pub fix_beta: bool,
+}
+
+impl SABRCalibrator {
+  pub fn calibrate(&self) -> SabrParams {
+    let (result, report) = LevenbergMarquardt::new().minimize(self.clone());
+    println!(
+      "SABR calibration: {:?}, termination: {:?}, RMSE: {:e}",
+      result.params,
+      report.termination,
+      result.rmse()
+    );
+
+    result.params
+  }
+
+  /// Root mean squared error of the volatilities at the current parameters
+  pub fn rmse(&self) -> f64 {
+    let residuals = self.vol_residuals();
+    (residuals.norm_squared() / residuals.len() as f64).sqrt()
+  }
+
+  /// Model minus market volatilities
+  fn vol_residuals(&self) -> DVector<f64> {
+    let expansion = self.expansion.unwrap_or_default();
+    DVector::from_iterator(
+      self.vols.len(),
+      self
+        .k
+        .iter()
+        .zip(self.vols.iter())
+        .map(|(&k, &vol)| self.params.vol(self.f, k, self.tau, expansion) - vol),
+    )
+  }
+
+  /// Indices of the calibrated parameters
+  fn free(&self) -> Vec<usize> {
+    (0..4).filter(|&i| !(self.fix_beta && i == 1)).collect()
+  }
+}
+
+/// Unconstrained coordinate of the i-th parameter, the logarithms of alpha and nu, the
+/// logit of beta and the inverse hyperbolic tangent of rho
+fn to_coordinate(i: usize, value: f64) -> f64 {
+  match i {
+    1 => {
+      let beta = value.clamp(1e-6, 1.0 - 1e-6);
+      (beta / (1.0 - beta)).ln()
+    }
+    2 => value.clamp(-0.999, 0.999).atanh(),
+    _ => value.max(1e-8).ln(),
+  }
+}
+
+fn from_coordinate(i: usize, x: f64) -> f64 {
+  match i {
+    1 => 1.0 / (1.0 + (-x).exp()),
+    2 => x.tanh(),
+    _ => x.exp(),
+  }
+}
+
+impl LeastSquaresProblem<f64, Dyn, Dyn> for SABRCalibrator {
+  type JacobianStorage = Owned<f64, Dyn, Dyn>;
+  type ParameterStorage = Owned<f64, Dyn>;
+  type ResidualStorage = Owned<f64, Dyn>;
+
+  /// Unconstrained coordinates of the free parameters, so the parameters stay admissible
+  fn set_params(&mut self, params: &DVector<f64>) {
+    let mut full = DVector::from(self.params);
+    for (i, &x) in self.free().into_iter().zip(params.iter()) {
+      full[i] = from_coordinate(i, x);
+    }
+    self.params = full.into();
+  }
+
+  fn params(&self) -> DVector<f64> {
+    let full = DVector::from(self.params);
+    DVector::from_iterator(
+      self.free().len(),
+      self.free().into_iter().map(|i| to_coordinate(i, full[i])),
+    )
+  }
+
+  fn residuals(&self) -> Option<DVector<f64>> {
+    Some(self.vol_residuals())
+  }
+
+  /// Jacobian in the coordinates by central differences
+  fn jacobian(&self) -> Option<DMatrix<f64>> {
+    let free = self.free();
+    let params = DVector::from(self.params);
+    let mut bumped = self.clone();
+    let mut jacobian = DMatrix::zeros(self.vols.len(), free.len());
+
+    for (column, &i) in free.iter().enumerate() {
+      let x = to_coordinate(i, params[i]);
+      let h = 1e-6 * x.abs().max(1.0);
+      let [up, down] = [h, -h].map(|bump| {
+        let mut params = params.clone();
+        params[i] = from_coordinate(i, x + bump);
+        bumped.params = params.into();
+        bumped.vol_residuals()
+      });
+      jacobian.set_column(column, &((up - down) / (2.0 * h)));
+    }
+
+    Some(jacobian)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn market(params: SabrParams, expansion: SabrExpansion) -> (DVector<f64>, DVector<f64>) {
+    let k = DVector::from_iterator(11, (0..11).map(|i| 0.03 + 0.004 * i as f64));
+    let vols = k.map(|k| params.vol(0.05, k, 1.5, expansion));
+    (k, vols)
+  }
+
+  #[test]
+  fn sabr_calibration_recovers_parameters() {
+    let truth = SabrParams {
+      alpha: 0.035,
+      beta: 0.5,
+      rho: -0.3,
+      nu: 0.45,
+    };
+
+    for expansion in [SabrExpansion::Lognormal, SabrExpansion::Normal] {
+      let (k, vols) = market(truth, expansion);
+      let calibrator = SABRCalibrator::new(
+        SabrParams {
+          alpha: 0.05,
+          beta: 0.5,
+          rho: 0.0,
+          nu: 0.3,
+        },
+        vols,
+        0.05,
+        k,
+        1.5,
+        Some(expansion),
+        true,
+      );
+      let params = calibrator.calibrate();
+
+      assert!((params.alpha - truth.alpha).abs() < 1e-5, "{:?}", params);
+      assert!((params.rho - truth.rho).abs() < 1e-3, "{:?}", params);
+      assert!((params.nu - truth.nu).abs() < 1e-3, "{:?}", params);
+    }
+  }
+
+  #[test]
+  fn sabr_calibration_with_free_beta() {
+    let truth = SabrParams {
+      alpha: 0.2,
+      beta: 0.8,
+      rho: -0.5,
+      nu: 0.7,
+    };
+    let (k, vols) = market(
+      SabrParams {
+        alpha: truth.alpha * 0.05f64.powf(0.2),
+        ..truth
+      },
+      SabrExpansion::Lognormal,
+    );
+    let mut calibrator = SABRCalibrator::new(
+      SabrParams {
+        alpha: 0.1,
+        beta: 0.5,
+        rho: 0.0,
+        nu: 0.5,
+      },
+      vols,
+      0.05,
+      k,
+      1.5,
+      None,
+      false,
+    );
+    calibrator.params = calibrator.calibrate();
+
+    // beta trades off against rho, the smile itself is matched
+    assert!(calibrator.rmse() < 1e-5, "{}", calibrator.rmse());
+  }
+}
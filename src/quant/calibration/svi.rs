@@ -0,0 +1,420 @@
+//! SVI smile fits and the SVI-JW to Heston initial guess.
+//!
+//! Every expiry is fitted with the raw SVI parametrization of the total implied variance
+//!
+//! w(k) = a + b (rho (k - m) + sqrt((k - m)^2 + sigma^2)),  k = ln(K / F)
+//!
+//! using the quasi-explicit method: for fixed (m, sigma) the total variance is linear in
+//! (a, b rho sigma, b sigma), so only the two dimensional outer problem is searched.
+//!
+//! The fitted slices are mapped to Heston parameters with known asymptotic relationships:
+//!
+//! - the fair variance swap strike of every slice, E[int v dt] / t, is exactly
+//!   theta + (v0 - theta) (1 - e^(-kappa t)) / (kappa t) in the Heston model,
+//! - the ATM skew of the Heston smile is d sigma_BS^2 / dk = rho xi / (kappa' t)
+//!   (1 - (1 - e^(-kappa' t)) / (kappa' t)) with kappa' = kappa - rho xi / 2,
+//! - for short maturities the smile is
+//!   sigma_BS(k) ≈ sigma + rho xi / (4 sigma) k + (2 - 5 rho^2) xi^2 / (48 sigma^3) k^2.
+//!
+//! - Gatheral, J. (2006). The Volatility Surface: A Practitioner's Guide.
+//! - Gatheral, J., & Jacquier, A. (2014). Arbitrage-free SVI volatility surfaces.
+//! - De Marco, S., & Martini, C. (2009). Quasi-explicit calibration of Gatheral's SVI model.
+//! - Medvedev, A., & Scaillet, O. (2007). Approximation and calibration of short-term implied volatilities under jump-diffusion stochastic volatility.
+
+use nalgebra::{Matrix3, Vector3};
+use statrs::distribution::{Continuous, Normal};
+
+use super::heston::HestonParams;
+
+/// Raw SVI parameters of one expiry
+#[derive(Clone, Copy, Debug)]
+pub struct SviRaw {
+  pub a: f64,
+  pub b: f64,
+  pub rho: f64,
+  pub m: f64,
+  pub sigma: f64,
+}
+
+/// SVI-JW (jump-wings) parameters of one expiry
+#[derive(Clone, Copy, Debug)]
+pub struct SviJw {
+  /// ATM variance
+  pub v: f64,
+  /// ATM skew, sqrt(t) d sigma_BS / dk at k = 0
+  pub psi: f64,
+  /// Slope of the left (put) wing
+  pub p: f64,
+  /// Slope of the right (call) wing
+  pub c: f64,
+  /// Minimum implied variance
+  pub v_tilde: f64,
+}
+
+impl SviRaw {
+  /// Total implied variance at log-moneyness k
+  pub fn total_variance(&self, k: f64) -> f64 {
+    let x = k - self.m;
+    self.a + self.b * (self.rho * x + (x.powi(2) + self.sigma.powi(2)).sqrt())
+  }
+
+  /// First and second derivatives of the total variance at log-moneyness k
+  fn derivatives(&self, k: f64) -> (f64, f64) {
+    let x = k - self.m;
+    let r = (x.powi(2) + self.sigma.powi(2)).sqrt();
+
+    (
+      self.b * (self.rho + x / r),
+      self.b * self.sigma.powi(2) / r.powi(3),
+    )
+  }
+
+  /// Fit to total implied variances w at log-moneyness k, at least 5 quotes are needed
+  pub fn fit(k: &[f64], w: &[f64]) -> Self {
+    assert_eq!(k.len(), w.len(), "k and w must have the same length");
+    assert!(k.len() >= 5, "at least 5 quotes are needed");
+
+    let (k_min, k_max) = k
+      .iter()
+      .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &k| {
+        (lo.min(k), hi.max(k))
+      });
+    let width = (k_max - k_min).max(1e-4);
+
+    // Coarse grid over (m, ln sigma), then a pattern search around the best point
+    let mut best = (f64::INFINITY, 0.0, 0.0);
+    for i in 0..=20 {
+      for j in 0..=20 {
+        let m = k_min + width * i as f64 / 20.0;
+        let ln_sigma = (1e-3 * width).ln() + j as f64 / 20.0 * (1e3f64).ln();
+        let (error, _) = Self::fit_linear(k, w, m, ln_sigma.exp());
+        if error < best.0 {
+          best = (error, m, ln_sigma);
+        }
+      }
+    }
+
+    let (mut error, mut m, mut ln_sigma) = best;
+    let (mut dm, mut ds) = (width / 20.0, (1e3f64).ln() / 20.0);
+    for _ in 0..100 {
+      let mut improved = false;
+      for (step_m, step_s) in [(dm, 0.0), (-dm, 0.0), (0.0, ds), (0.0, -ds)] {
+        let (e, _) = Self::fit_linear(k, w, m + step_m, (ln_sigma + step_s).exp());
+        if e < error {
+          (error, m, ln_sigma) = (e, m + step_m, ln_sigma + step_s);
+          improved = true;
+        }
+      }
+
+      if !improved {
+        dm *= 0.5;
+        ds *= 0.5;
+        if dm < 1e-10 * width && ds < 1e-10 {
+          break;
+        }
+      }
+    }
+
+    Self::fit_linear(k, w, m, ln_sigma.exp()).1
+  }
+
+  /// Least squares (a, b, rho) for fixed (m, sigma) with b >= 0 and |rho| <= 1
+  fn fit_linear(k: &[f64], w: &[f64], m: f64, sigma: f64) -> (f64, Self) {
+    // w = a + d y + c sqrt(y^2 + 1), y = (k - m) / sigma, c = b sigma, d = rho b sigma
+    let features = |k: f64| {
+      let y = (k - m) / sigma;
+      Vector3::new(1.0, y, (y.powi(2) + 1.0).sqrt())
+    };
+
+    let mut normal = Matrix3::zeros();
+    let mut rhs = Vector3::zeros();
+    for (&k, &w) in k.iter().zip(w) {
+      let x = features(k);
+      normal += x * x.transpose();
+      rhs += x * w;
+    }
+
+    let (a, d, c) = match normal.lu().solve(&rhs) {
+      Some(x) => (x[0], x[1], x[2]),
+      None => (w.iter().sum::<f64>() / w.len() as f64, 0.0, 0.0),
+    };
+
+    // Project onto the admissible set and refit the level
+    let c = c.max(0.0);
+    let d = d.clamp(-c, c);
+    let a = match c == 0.0 {
+      true => a,
+      false => {
+        k.iter()
+          .zip(w)
+          .map(|(&k, &w)| {
+            let x = features(k);
+            w - d * x[1] - c * x[2]
+          })
+          .sum::<f64>()
+          / k.len() as f64
+      }
+    };
+
+    let raw = Self {
+      a,
+      b: c / sigma,
+      rho: match c > 0.0 {
+        true => d / c,
+        false => 0.0,
+      },
+      m,
+      sigma,
+    };
+    let error = k
+      .iter()
+      .zip(w)
+      .map(|(&k, &w)| (raw.total_variance(k) - w).powi(2))
+      .sum();
+
+    (error, raw)
+  }
+
+  /// Jump-wings parameters of the slice with time to maturity t
+  pub fn to_jw(&self, t: f64) -> SviJw {
+    let w = self.total_variance(0.0);
+    let (dw, _) = self.derivatives(0.0);
+    let sqrt_w = w.sqrt();
+
+    SviJw {
+      v: w / t,
+      psi: dw / (2.0 * sqrt_w),
+      p: self.b * (1.0 - self.rho) / sqrt_w,
+      c: self.b * (1.0 + self.rho) / sqrt_w,
+      v_tilde: (self.a + self.b * self.sigma * (1.0 - self.rho.powi(2)).sqrt()) / t,
+    }
+  }
+
+  /// Fair variance swap strike in total variance, E[int v dt]
+  ///
+  /// Integrates the total variance against the standard normal density of
+  /// z = d2(k) = -k / sqrt(w(k)) - sqrt(w(k)) / 2.
+  pub fn variance_swap(&self) -> f64 {
+    let normal = Normal::new(0.0, 1.0).unwrap();
+    let w = |k: f64| self.total_variance(k).max(1e-12);
+    let d2 = |k: f64| -k / w(k).sqrt() - 0.5 * w(k).sqrt();
+
+    let steps = 320;
+    let h = 16.0 / steps as f64;
+    (0..=steps)
+      .map(|i| {
+        let z = -8.0 + i as f64 * h;
+
+        // d2 is decreasing in k for arbitrage-free slices
+        let mut bound = 1.0;
+        while (d2(-bound) < z || d2(bound) > z) && bound < 1e3 {
+          bound *= 2.0;
+        }
+        let (mut lo, mut hi) = (-bound, bound);
+        for _ in 0..100 {
+          let mid = 0.5 * (lo + hi);
+          match d2(mid) > z {
+            true => lo = mid,
+            false => hi = mid,
+          }
+        }
+
+        let weight = match i == 0 || i == steps {
+          true => 0.5,
+          false => 1.0,
+        };
+        weight * h * normal.pdf(z) * w(0.5 * (lo + hi))
+      })
+      .sum()
+  }
+
+  /// ATM implied volatility, its slope and its curvature in log-moneyness
+  fn atm_smile(&self, t: f64) -> (f64, f64, f64) {
+    let w = self.total_variance(0.0);
+    let (dw, d2w) = self.derivatives(0.0);
+    let vol = (w / t).sqrt();
+
+    (
+      vol,
+      dw / (2.0 * (w * t).sqrt()),
+      d2w / (2.0 * (w * t).sqrt()) - dw.powi(2) / (4.0 * t.sqrt() * w.powf(1.5)),
+    )
+  }
+}
+
+/// Approximate Heston parameters from SVI fits of several expiries
+///
+/// The variance swap strikes of all expiries give v0, theta and kappa, the skew and the
+/// curvature of the shortest expiry give rho and sigma (xi). With a single expiry the
+/// variance is flat, v0 = theta, and kappa is set to 1.
+pub fn heston_from_svi(slices: &[(f64, SviRaw)]) -> HestonParams {
+  assert!(!slices.is_empty(), "at least one expiry is needed");
+
+  let variance = slices
+    .iter()
+    .map(|(t, raw)| (*t, raw.variance_swap() / t))
+    .collect::<Vec<_>>();
+  let (v0, theta, kappa) = match variance.len() {
+    1 => (variance[0].1, variance[0].1, 1.0),
+    _ => fit_term_structure(&variance),
+  };
+
+  let (t, raw) = slices
+    .iter()
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .copied()
+    .unwrap();
+  let (vol, skew, curvature) = raw.atm_smile(t);
+
+  // Undo the flattening of the skew by mean reversion, fixed point in kappa'
+  let mut rho_xi = 4.0 * vol * skew;
+  for _ in 0..20 {
+    let x = (kappa - 0.5 * rho_xi) * t;
+    let decay = match x.abs() > 1e-8 {
+      true => 2.0 / x * (1.0 - (1.0 - (-x).exp()) / x),
+      false => 1.0,
+    };
+    rho_xi = 4.0 * vol * skew / decay;
+  }
+
+  // (2 - 5 rho^2) xi^2 = 24 vol^3 curvature
+  let xi = ((12.0 * vol.powi(3) * curvature + 2.5 * rho_xi.powi(2)).max(0.0))
+    .sqrt()
+    .max(rho_xi.abs())
+    .max(1e-3);
+  let rho = (rho_xi / xi).clamp(-0.99, 0.99);
+
+  HestonParams {
+    v0,
+    theta,
+    rho,
+    kappa,
+    sigma: xi,
+  }
+}
+
+/// Least squares (v0, theta, kappa) of v(t) = theta + (v0 - theta) (1 - e^(-kappa t)) / (kappa t)
+fn fit_term_structure(variance: &[(f64, f64)]) -> (f64, f64, f64) {
+  let mut best = (f64::INFINITY, variance[0].1, variance[0].1, 1.0);
+
+  // Linear in (v0, theta) for fixed kappa
+  for i in 0..=60 {
+    let kappa = 0.05 * (2.0f64).powf(i as f64 / 6.0);
+    let (mut s11, mut s12, mut s22, mut r1, mut r2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for &(t, v) in variance {
+      let g = (1.0 - (-kappa * t).exp()) / (kappa * t);
+      let (x1, x2) = (g, 1.0 - g);
+      s11 += x1 * x1;
+      s12 += x1 * x2;
+      s22 += x2 * x2;
+      r1 += x1 * v;
+      r2 += x2 * v;
+    }
+
+    let det = s11 * s22 - s12.powi(2);
+    if det.abs() < 1e-14 {
+      continue;
+    }
+
+    let v0 = ((s22 * r1 - s12 * r2) / det).max(1e-6);
+    let theta = ((s11 * r2 - s12 * r1) / det).max(1e-6);
+    let error = variance
+      .iter()
+      .map(|&(t, v)| {
+        let g = (1.0 - (-kappa * t).exp()) / (kappa * t);
+        (theta + (v0 - theta) * g - v).powi(2)
+      })
+      .sum::<f64>();
+
+    if error < best.0 {
+      best = (error, v0, theta, kappa);
+    }
+  }
+
+  (best.1, best.2, best.3)
+}
+
+#[cfg(test)]
+mod tests {
+  use implied_vol::implied_black_volatility;
+
+  use super::*;
+  use crate::quant::{pricing::heston::HestonPricer, r#trait::Pricer};
+
+  #[test]
+  fn svi_fit_recovers_raw_parameters() {
+    let raw = SviRaw {
+      a: 0.01,
+      b: 0.1,
+      rho: -0.6,
+      m: 0.05,
+      sigma: 0.2,
+    };
+    let k = (0..21).map(|i| -0.5 + 0.05 * i as f64).collect::<Vec<_>>();
+    let w = k.iter().map(|&k| raw.total_variance(k)).collect::<Vec<_>>();
+    let fit = SviRaw::fit(&k, &w);
+
+    for (&k, &w) in k.iter().zip(&w) {
+      assert!((fit.total_variance(k) - w).abs() < 1e-6);
+    }
+    assert!((fit.rho - raw.rho).abs() < 1e-2);
+
+    let jw = fit.to_jw(1.0);
+    assert!((jw.v - raw.total_variance(0.0)).abs() < 1e-6);
+    assert!(jw.psi < 0.0 && jw.p > jw.c);
+
+    // The variance swap of a flat smile is its variance
+    let flat = SviRaw {
+      a: 0.04,
+      b: 0.0,
+      ..raw
+    };
+    assert!((flat.variance_swap() - 0.04).abs() < 1e-6);
+  }
+
+  #[test]
+  fn heston_from_svi_recovers_parameters() {
+    let (v0, theta, rho, kappa, sigma, s, r) = (0.04, 0.06, -0.6, 1.5, 0.4, 100.0, 0.02);
+
+    let slices = [0.1, 0.5, 1.0, 2.0]
+      .iter()
+      .map(|&t| {
+        let forward = s * f64::exp(r * t);
+        let (k, w): (Vec<_>, Vec<_>) = (0..21)
+          .map(|i| {
+            let strike = forward * f64::exp((-0.2 + 0.02 * i as f64) * t.sqrt());
+            let pricer = HestonPricer::new(
+              s,
+              v0,
+              strike,
+              r,
+              None,
+              rho,
+              kappa,
+              theta,
+              sigma,
+              Some(0.0),
+              Some(t),
+              None,
+              None,
+            );
+            let (call, _) = pricer.calculate_call_put();
+            let vol = implied_black_volatility(call * f64::exp(r * t), forward, strike, t, true);
+            ((strike / forward).ln(), vol.powi(2) * t)
+          })
+          .unzip();
+
+        (t, SviRaw::fit(&k, &w))
+      })
+      .collect::<Vec<_>>();
+
+    let guess = heston_from_svi(&slices);
+    println!("{:?}", guess);
+
+    assert!((guess.v0 - v0).abs() < 0.002);
+    assert!((guess.theta - theta).abs() < 0.005);
+    assert!((guess.rho - rho).abs() < 0.1);
+    assert!((guess.sigma - sigma).abs() < 0.1);
+    assert!(guess.kappa > 1.0 && guess.kappa < 2.5);
+  }
+}
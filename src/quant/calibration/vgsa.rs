@@ -0,0 +1,238 @@
+use impl_new_derive::ImplNew;
+use nalgebra::DVector;
+
+use crate::{
+  quant::{
+    pricing::fft::{FourierMethod, FourierPricer},
+    volatility::svi::minimize,
+    OptionType,
+  },
+  stochastic::jump::vgsa::VGSA,
+};
+
+/// COS expansion of the prices of every expiry
+const COS: FourierMethod = FourierMethod::Cos { n: 256, l: 12.0 };
+
+/// VGSA model parameters, the variance gamma process and its CIR clock started at y0 = 1
+#[derive(Clone, Debug)]
+pub struct VGSAParams {
+  /// Volatility of the subordinated Brownian motion
+  pub sigma: f64,
+  /// Variance rate of the gamma subordinator
+  pub nu: f64,
+  /// Drift of the subordinated Brownian motion, the skew
+  pub mu: f64,
+  /// Mean reversion rate of the clock
+  pub kappa: f64,
+  /// Long-run activity rate of the clock
+  pub eta: f64,
+  /// Volatility of the activity rate
+  pub lambda: f64,
+}
+
+impl VGSAParams {
+  /// Process with these parameters, used as the characteristic function of the prices
+  pub fn process(&self) -> VGSA {
+    VGSA::new(
+      self.mu,
+      self.sigma,
+      self.nu,
+      self.kappa,
+      self.eta,
+      self.lambda,
+      None,
+      2,
+      None,
+      None,
+      None,
+    )
+  }
+}
+
+impl From<VGSAParams> for DVector<f64> {
+  fn from(params: VGSAParams) -> Self {
+    DVector::from_vec(vec![
+      params.sigma,
+      params.nu,
+      params.mu,
+      params.kappa,
+      params.eta,
+      params.lambda,
+    ])
+  }
+}
+
+impl From<DVector<f64>> for VGSAParams {
+  fn from(params: DVector<f64>) -> Self {
+    VGSAParams {
+      sigma: params[0],
+      nu: params[1],
+      mu: params[2],
+      kappa: params[3],
+      eta: params[4],
+      lambda: params[5],
+    }
+  }
+}
+
+/// Names of the VGSA parameters in the order of the parameter vector
+pub const VGSA_PARAMETERS: [&str; 6] = ["sigma", "nu", "mu", "kappa", "eta", "lambda"];
+
+/// Calibrated VGSA parameters
+#[derive(Clone, Debug)]
+pub struct VGSACalibrationResult {
+  /// Calibrated parameters
+  pub params: VGSAParams,
+  /// Residual sum of squares of the prices
+  pub rss: f64,
+  /// Degrees of freedom of the residuals, number of quotes minus 6
+  pub dof: usize,
+}
+
+/// Calibrator of the VGSA parameters to an option surface
+///
+/// Unlike the constant-parameter VG, the stochastic clock lets the skew decay with the
+/// maturity, so the quotes of several expiries are fitted together. Minimizes the squared
+/// price differences with Levenberg-Marquardt on the logarithms of the positive parameters
+/// and mu itself. The strikes of every expiry are priced by one COS expansion of
+/// [`FourierPricer`].
+#[derive(ImplNew, Clone)]
+pub struct VGSACalibrator {
+  /// Params to calibrate, the initial guess
+  pub params: VGSAParams,
+  /// Option prices from the market
+  pub c_market: DVector<f64>,
+  /// Asset price
+  pub s: f64,
+  /// Strike price of every quote
+  pub k: DVector<f64>,
+  /// Time to maturity of every quote
+  pub tau: DVector<f64>,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Option type
+  pub option_type: OptionType,
+}
+
+/// Unconstrained coordinates of the parameters
+fn to_coordinates(params: &VGSAParams) -> DVector<f64> {
+  DVector::from_vec(vec![
+    params.sigma.ln(),
+    params.nu.ln(),
+    params.mu,
+    params.kappa.ln(),
+    params.eta.ln(),
+    params.lambda.ln(),
+  ])
+}
+
+fn from_coordinates(x: &DVector<f64>) -> VGSAParams {
+  VGSAParams {
+    sigma: x[0].exp(),
+    nu: x[1].exp(),
+    mu: x[2],
+    kappa: x[3].exp(),
+    eta: x[4].exp(),
+    lambda: x[5].exp(),
+  }
+}
+
+impl VGSACalibrator {
+  pub fn calibrate(&self) -> VGSACalibrationResult {
+    assert_eq!(
+      self.k.len(),
+      self.tau.len(),
+      "every quote needs a strike and a maturity"
+    );
+    let residuals = |x: &DVector<f64>| self.residuals(&from_coordinates(x));
+    let params = from_coordinates(&minimize(to_coordinates(&self.params), &residuals));
+
+    let rss = self.residuals(&params).norm_squared();
+    println!("VGSA calibration: {:?}, RSS: {:e}", params, rss);
+
+    VGSACalibrationResult {
+      params,
+      rss,
+      dof: self.c_market.len().saturating_sub(6),
+    }
+  }
+
+  /// Model minus market prices
+  pub fn residuals(&self, params: &VGSAParams) -> DVector<f64> {
+    let mut c_model = DVector::zeros(self.k.len());
+    let mut expiries = self.tau.iter().copied().collect::<Vec<_>>();
+    expiries.sort_by(f64::total_cmp);
+    expiries.dedup();
+
+    for tau in expiries {
+      let quotes = (0..self.k.len())
+        .filter(|&i| self.tau[i] == tau)
+        .collect::<Vec<_>>();
+      let strikes = quotes.iter().map(|&i| self.k[i]).collect::<Vec<_>>();
+      let (calls, puts) =
+        FourierPricer::new(params.process(), self.s, self.r, self.q, tau, Some(COS))
+          .calculate_calls_puts(&strikes);
+      let prices = match self.option_type {
+        OptionType::Call => calls,
+        OptionType::Put => puts,
+      };
+
+      for (&i, price) in quotes.iter().zip(prices) {
+        c_model[i] = price;
+      }
+    }
+
+    c_model - &self.c_market
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_vgsa_calibrate_surface() {
+    let (s, r) = (100.0, 0.02);
+    let truth = VGSAParams {
+      sigma: 0.18,
+      nu: 0.25,
+      mu: -0.2,
+      kappa: 1.5,
+      eta: 1.2,
+      lambda: 0.9,
+    };
+    let (k, tau): (Vec<f64>, Vec<f64>) = [0.25, 0.5, 1.0]
+      .iter()
+      .flat_map(|&tau| (0..11).map(move |i| (80.0 + 4.0 * i as f64, tau)))
+      .unzip();
+    let calibrator = VGSACalibrator::new(
+      VGSAParams {
+        sigma: 0.2,
+        nu: 0.3,
+        mu: -0.15,
+        kappa: 1.2,
+        eta: 1.0,
+        lambda: 0.7,
+      },
+      DVector::zeros(k.len()),
+      s,
+      k.clone().into(),
+      tau.into(),
+      r,
+      None,
+      OptionType::Put,
+    );
+    let c_market = calibrator.residuals(&truth);
+    let calibrator = VGSACalibrator {
+      c_market,
+      ..calibrator
+    };
+    let result = calibrator.calibrate();
+
+    let rmse = (result.rss / k.len() as f64).sqrt();
+    assert!(rmse < 1e-3, "{}", rmse);
+    assert_eq!(result.dof, 27);
+  }
+}
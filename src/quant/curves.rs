@@ -0,0 +1,707 @@
+//! Zero rate curves of the interest rates and the dividend yields.
+//!
+//! A [`ZeroCurve`] holds continuously compounded zero rates r(t) at its nodes, so the
+//! discount factor is D(t) = e^(-r(t) t), and is interpolated either with a piecewise
+//! constant instantaneous forward rate (linear in ln D) or linearly in the zero rate.
+//! The same type is the [`YieldCurve`] of the discounting and the [`DividendCurve`] of a
+//! continuous dividend yield, the forward price is F(t) = S D_q(t) / D_r(t).
+//!
+//! European prices only depend on the curves through the discount factors to the
+//! maturity, so a pricer with flat rates is exact with the zero rates to the maturity,
+//! which is what [`TermStructure::with_curves`] sets. It is implemented by the European
+//! pricers of [`pricing`](super::pricing) and the [`SABRPricer`], the American pricers
+//! ([`BjerksundStenslandPricer`], [`FiniteDifferencePricer`]) take the zero rates to the
+//! maturity as a flat approximation and the finite difference grid has no dividend yield.
+//! The path dependent [`AsianPricer`] needs the whole curve and has no implementation.
+//!
+//! An [`EquityForwardCurve`] adds repo spreads and discrete cash dividends to the spot and
+//! the curves, [`TermStructure::with_forward_curve`] sets the spot, the zero rate and the
+//! dividend yield implied by its forward to the maturity, so the pricers reproduce the
+//! market forward before any volatility is fitted.
+//!
+//! [`AsianPricer`]: super::pricing::asian::AsianPricer
+
+use ndarray::Array1;
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    pricing::{
+      bates::BatesPricer,
+      bjerksund_stensland::BjerksundStenslandPricer,
+      bsm::{BSMCoc, BSMPricer},
+      fft::FourierPricer,
+      finitie_difference::FiniteDifferencePricer,
+      gram_charlier::GramCharlierPricer,
+      heston::HestonPricer,
+      kou::KouPricer,
+      merton_jump::Merton1976Pricer,
+      rough_heston::RoughHestonPricer,
+      saddlepoint::SaddlepointPricer,
+    },
+    r#trait::{CharacteristicFn, Time},
+    volatility::sabr::SABRPricer,
+  },
+  stochastic::Distribution,
+};
+
+/// Interpolation of a [`ZeroCurve`] between its nodes
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveInterpolation {
+  /// Piecewise constant instantaneous forward rate, the first one from t = 0 and the last
+  /// one beyond the last node
+  #[default]
+  FlatForward,
+  /// Linear in the zero rate, flat outside of the nodes
+  LinearZero,
+}
+
+/// Curve of continuously compounded zero rates
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeroCurve {
+  /// Increasing positive times of the nodes
+  pub times: Array1<f64>,
+  /// Zero rates of the nodes
+  pub rates: Array1<f64>,
+  /// Interpolation between the nodes
+  pub interpolation: CurveInterpolation,
+}
+
+/// Curve of the risk-free rates
+pub type YieldCurve = ZeroCurve;
+
+/// Curve of the continuous dividend yields
+pub type DividendCurve = ZeroCurve;
+
+impl ZeroCurve {
+  /// Curve of the zero rates at the times
+  pub fn new(
+    times: Array1<f64>,
+    rates: Array1<f64>,
+    interpolation: CurveInterpolation,
+  ) -> StochasticResult<Self> {
+    ensure(!times.is_empty(), "at least 1 node is needed")?;
+    ensure(
+      times.len() == rates.len(),
+      "times and rates must have the same length",
+    )?;
+    ensure(
+      times[0] > 0.0 && times.windows(2).into_iter().all(|t| t[1] > t[0]),
+      "times must be positive and increasing",
+    )?;
+
+    Ok(Self {
+      times,
+      rates,
+      interpolation,
+    })
+  }
+
+  /// Flat curve of the rate
+  pub fn flat(rate: f64) -> Self {
+    Self {
+      times: Array1::from_elem(1, 1.0),
+      rates: Array1::from_elem(1, rate),
+      interpolation: CurveInterpolation::FlatForward,
+    }
+  }
+
+  /// Curve of the discount factors at the times
+  pub fn from_discount_factors(
+    times: Array1<f64>,
+    discount_factors: Array1<f64>,
+    interpolation: CurveInterpolation,
+  ) -> StochasticResult<Self> {
+    ensure(
+      discount_factors.iter().all(|&d| d > 0.0),
+      "discount factors must be positive",
+    )?;
+    let rates = Array1::from_shape_fn(times.len(), |i| -discount_factors[i].ln() / times[i]);
+
+    Self::new(times, rates, interpolation)
+  }
+
+  /// Zero rate r(t), the short rate at t = 0
+  pub fn zero_rate(&self, t: f64) -> f64 {
+    let (times, rates) = (&self.times, &self.rates);
+    let last = times.len() - 1;
+    if t <= times[0] || last == 0 {
+      return rates[0];
+    }
+
+    match self.interpolation {
+      CurveInterpolation::LinearZero => {
+        if t >= times[last] {
+          return rates[last];
+        }
+        let i = times.iter().position(|&node| node > t).unwrap();
+        let w = (t - times[i - 1]) / (times[i] - times[i - 1]);
+        (1.0 - w) * rates[i - 1] + w * rates[i]
+      }
+      CurveInterpolation::FlatForward => {
+        // The forward of the segment ending at i, the last segment beyond the nodes
+        let i = times
+          .iter()
+          .position(|&node| node > t)
+          .unwrap_or(last)
+          .max(1);
+        let forward =
+          (rates[i] * times[i] - rates[i - 1] * times[i - 1]) / (times[i] - times[i - 1]);
+        (rates[i - 1] * times[i - 1] + forward * (t - times[i - 1])) / t
+      }
+    }
+  }
+
+  /// Discount factor D(t) = e^(-r(t) t)
+  pub fn discount(&self, t: f64) -> f64 {
+    (-self.zero_rate(t) * t).exp()
+  }
+
+  /// Simple continuously compounded forward rate ln(D(t1) / D(t2)) / (t2 - t1)
+  pub fn forward_rate(&self, t1: f64, t2: f64) -> f64 {
+    assert!(t2 > t1, "t2 must be after t1");
+    (self.zero_rate(t2) * t2 - self.zero_rate(t1) * t1) / (t2 - t1)
+  }
+
+  /// Instantaneous forward rate f(t), the right limit at the nodes of the flat forward
+  /// interpolation
+  pub fn instantaneous_forward(&self, t: f64) -> f64 {
+    let t = t.max(0.0);
+    self.forward_rate(t, t + 1e-7)
+  }
+}
+
+/// Forward price S D_q(t) / D_r(t) of the spot s
+pub fn forward(
+  s: f64,
+  t: f64,
+  yield_curve: &YieldCurve,
+  dividend_curve: Option<&DividendCurve>,
+) -> f64 {
+  let dividends = dividend_curve.map_or(1.0, |curve| curve.discount(t));
+  s * dividends / yield_curve.discount(t)
+}
+
+/// Forward curve of an equity with continuous dividend yields, repo spreads and discrete
+/// cash dividends
+///
+/// The stock grows at the carry r(t) - q(t) - b(t) with the repo spread b, i.e. by
+/// G(t) = D_q(t) D_b(t) / D_r(t), and drops by the cash dividends, so
+///
+/// F(t) = (S - sum_(t_i <= t) d_i / G(t_i)) G(t)
+#[derive(Clone, Debug, PartialEq)]
+pub struct EquityForwardCurve {
+  /// Spot price
+  pub spot: f64,
+  /// Curve of the risk-free rates, discounts the payoffs
+  pub yield_curve: YieldCurve,
+  /// Curve of the continuous dividend yields
+  pub dividend_curve: Option<DividendCurve>,
+  /// Curve of the repo spreads, the cost of borrowing the stock
+  pub repo_curve: Option<ZeroCurve>,
+  /// Cash dividends (ex-dividend time, amount), ordered by time
+  pub dividends: Vec<(f64, f64)>,
+}
+
+impl EquityForwardCurve {
+  /// Forward curve of the spot without dividends and repo spreads
+  pub fn new(spot: f64, yield_curve: YieldCurve) -> StochasticResult<Self> {
+    ensure(spot > 0.0, "spot must be positive")?;
+
+    Ok(Self {
+      spot,
+      yield_curve,
+      dividend_curve: None,
+      repo_curve: None,
+      dividends: Vec::new(),
+    })
+  }
+
+  /// Add the continuous dividend yields
+  pub fn with_dividend_curve(mut self, dividend_curve: DividendCurve) -> Self {
+    self.dividend_curve = Some(dividend_curve);
+    self
+  }
+
+  /// Add the repo spreads
+  pub fn with_repo_curve(mut self, repo_curve: ZeroCurve) -> Self {
+    self.repo_curve = Some(repo_curve);
+    self
+  }
+
+  /// Add the cash dividends (ex-dividend time, amount)
+  pub fn with_dividends(mut self, dividends: &[(f64, f64)]) -> StochasticResult<Self> {
+    ensure(
+      dividends.iter().all(|&(t, d)| t > 0.0 && d >= 0.0),
+      "dividends must have positive times and non-negative amounts",
+    )?;
+    self.dividends.extend_from_slice(dividends);
+    self.dividends.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let last = self.dividends.last().map_or(0.0, |&(t, _)| t);
+    ensure(
+      self.dividends_value(last) < self.spot,
+      "the value of the dividends must be below the spot",
+    )?;
+
+    Ok(self)
+  }
+
+  /// Growth G(t) = D_q(t) D_b(t) / D_r(t) of the stock without cash dividends
+  pub fn growth(&self, t: f64) -> f64 {
+    let dividends = self
+      .dividend_curve
+      .as_ref()
+      .map_or(1.0, |curve| curve.discount(t));
+    let repo = self
+      .repo_curve
+      .as_ref()
+      .map_or(1.0, |curve| curve.discount(t));
+    dividends * repo / self.yield_curve.discount(t)
+  }
+
+  /// Value at 0 of the cash dividends up to t, sum d_i / G(t_i)
+  pub fn dividends_value(&self, t: f64) -> f64 {
+    self
+      .dividends
+      .iter()
+      .take_while(|&&(ti, _)| ti <= t)
+      .map(|&(ti, d)| d / self.growth(ti))
+      .sum()
+  }
+
+  /// Forward price F(t)
+  pub fn forward(&self, t: f64) -> f64 {
+    (self.spot - self.dividends_value(t)) * self.growth(t)
+  }
+
+  /// Discount factor of the payoffs D_r(t)
+  pub fn discount(&self, t: f64) -> f64 {
+    self.yield_curve.discount(t)
+  }
+
+  /// Continuous dividend yield q with F(t) = S e^((r(t) - q) t), it includes the repo spread
+  /// and the cash dividends, at t = 0 the dividend yield plus the repo spread
+  pub fn implied_dividend_yield(&self, t: f64) -> f64 {
+    if t <= 0.0 {
+      return self
+        .dividend_curve
+        .as_ref()
+        .map_or(0.0, |curve| curve.zero_rate(0.0))
+        + self
+          .repo_curve
+          .as_ref()
+          .map_or(0.0, |curve| curve.zero_rate(0.0));
+    }
+    self.yield_curve.zero_rate(t) - (self.forward(t) / self.spot).ln() / t
+  }
+}
+
+/// Pricers of European options with flat rates, priced with term structures through the
+/// zero rates to the maturity
+pub trait TermStructure: Sized {
+  /// Time to maturity of the priced option
+  fn maturity(&self) -> f64;
+
+  /// Set the flat risk-free rate and dividend yield, the dividend yield is kept if None
+  fn set_rates(&mut self, r: f64, q: Option<f64>);
+
+  /// Set the spot price
+  fn set_spot(&mut self, s: f64);
+
+  /// Pricer with the spot of the forward curve, the zero rate to the maturity and the
+  /// dividend yield of the forward to the maturity
+  ///
+  /// The forward of the pricer S e^((r - q) tau) is the one of the curve, European prices are
+  /// exact for the dividends and the repo spreads up to the volatility dynamics around the
+  /// cash dividends. The futures cost of carry of [`BSMCoc::BLACK1976`] and [`BSMCoc::ASAY1982`]
+  /// ignores the dividend yield, their spot is the forward itself.
+  fn with_forward_curve(mut self, curve: &EquityForwardCurve) -> Self {
+    let t = self.maturity();
+    self.set_spot(curve.spot);
+    self.set_rates(
+      curve.yield_curve.zero_rate(t),
+      Some(curve.implied_dividend_yield(t)),
+    );
+    self
+  }
+
+  /// Pricer with the zero rates of the curves to the maturity
+  fn with_curves(
+    mut self,
+    yield_curve: &YieldCurve,
+    dividend_curve: Option<&DividendCurve>,
+  ) -> Self {
+    let t = self.maturity();
+    self.set_rates(
+      yield_curve.zero_rate(t),
+      dividend_curve.map(|curve| curve.zero_rate(t)),
+    );
+    self
+  }
+}
+
+/// Maturity of the pricers implementing [`Time`], from the dates if tau is None
+fn time_to_maturity<P: Time>(pricer: &P) -> f64 {
+  pricer
+    .tau()
+    .unwrap_or_else(|| pricer.calculate_tau_in_years())
+}
+
+macro_rules! impl_term_structure {
+  ($($pricer:ty),* $(,)?) => {
+    $(
+      impl TermStructure for $pricer {
+        fn maturity(&self) -> f64 {
+          time_to_maturity(self)
+        }
+
+        fn set_rates(&mut self, r: f64, q: Option<f64>) {
+          self.r = r;
+          self.q = q.or(self.q);
+        }
+
+        fn set_spot(&mut self, s: f64) {
+          self.s = s;
+        }
+      }
+    )*
+  };
+}
+
+impl_term_structure!(
+  HestonPricer,
+  BatesPricer,
+  RoughHestonPricer,
+  Merton1976Pricer,
+  GramCharlierPricer,
+  KouPricer,
+  BjerksundStenslandPricer,
+  SABRPricer,
+);
+
+impl TermStructure for FiniteDifferencePricer {
+  fn maturity(&self) -> f64 {
+    time_to_maturity(self)
+  }
+
+  /// The finite difference grid has no dividend yield, q is ignored
+  fn set_rates(&mut self, r: f64, _q: Option<f64>) {
+    self.r = r;
+  }
+
+  fn set_spot(&mut self, s: f64) {
+    self.s = s;
+  }
+}
+
+impl TermStructure for BSMPricer {
+  fn maturity(&self) -> f64 {
+    time_to_maturity(self)
+  }
+
+  /// The cost of carry follows the rates: a dividend yield turns BSM1973 into MERTON1973
+  /// and GARMAN1983 takes r and q as the domestic and the foreign rate
+  fn set_rates(&mut self, r: f64, q: Option<f64>) {
+    self.r = r;
+    match self.b {
+      BSMCoc::GARMAN1983 => {
+        self.r_d = Some(r);
+        self.r_f = q.or(self.r_f);
+      }
+      BSMCoc::BSM1973 if q.is_some() => {
+        self.q = q;
+        self.b = BSMCoc::MERTON1973;
+      }
+      _ => self.q = q.or(self.q),
+    }
+  }
+
+  fn set_spot(&mut self, s: f64) {
+    self.s = s;
+  }
+}
+
+impl<D: Distribution> TermStructure for SaddlepointPricer<D> {
+  fn maturity(&self) -> f64 {
+    time_to_maturity(self)
+  }
+
+  fn set_rates(&mut self, r: f64, q: Option<f64>) {
+    self.r = r;
+    self.q = q.or(self.q);
+  }
+
+  fn set_spot(&mut self, s: f64) {
+    self.s = s;
+  }
+}
+
+impl<M: CharacteristicFn> TermStructure for FourierPricer<M> {
+  fn maturity(&self) -> f64 {
+    self.tau
+  }
+
+  fn set_rates(&mut self, r: f64, q: Option<f64>) {
+    self.r = r;
+    self.q = q.or(self.q);
+  }
+
+  fn set_spot(&mut self, s: f64) {
+    self.s = s;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::quant::{r#trait::Pricer, OptionType};
+
+  fn curve(interpolation: CurveInterpolation) -> ZeroCurve {
+    ZeroCurve::new(
+      array![0.5, 1.0, 2.0, 5.0],
+      array![0.02, 0.025, 0.03, 0.035],
+      interpolation,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn curve_interpolation_and_discounting() {
+    for interpolation in [
+      CurveInterpolation::FlatForward,
+      CurveInterpolation::LinearZero,
+    ] {
+      let curve = curve(interpolation);
+      for (&t, &r) in curve.times.iter().zip(&curve.rates) {
+        assert!((curve.zero_rate(t) - r).abs() < 1e-12);
+        assert!((curve.discount(t) - (-r * t).exp()).abs() < 1e-12);
+      }
+      assert_eq!(curve.zero_rate(0.1), 0.02);
+    }
+
+    // Flat forward: constant forward rate on the segments and beyond the last node
+    let curve = curve(CurveInterpolation::FlatForward);
+    let forward = (0.03 * 2.0 - 0.025) / 1.0;
+    assert!((curve.forward_rate(1.2, 1.8) - forward).abs() < 1e-12);
+    let last = (0.035 * 5.0 - 0.03 * 2.0) / 3.0;
+    assert!((curve.forward_rate(6.0, 8.0) - last).abs() < 1e-12);
+
+    let from_discounts = ZeroCurve::from_discount_factors(
+      curve.times.clone(),
+      curve.times.mapv(|t| curve.discount(t)),
+      CurveInterpolation::FlatForward,
+    )
+    .unwrap();
+    assert!((from_discounts.zero_rate(3.0) - curve.zero_rate(3.0)).abs() < 1e-12);
+    assert!(ZeroCurve::new(array![1.0, 0.5], array![0.01, 0.02], Default::default()).is_err());
+  }
+
+  #[test]
+  fn pricers_with_curves() {
+    let yield_curve = curve(CurveInterpolation::FlatForward);
+    let dividend_curve = ZeroCurve::new(
+      array![1.0, 3.0],
+      array![0.01, 0.015],
+      CurveInterpolation::LinearZero,
+    )
+    .unwrap();
+    let tau = 3.0;
+
+    // Put-call parity with the forward and the discount factor of the curves
+    let bsm = BSMPricer::new(
+      100.0,
+      0.2,
+      105.0,
+      0.0,
+      None,
+      None,
+      None,
+      Some(tau),
+      None,
+      None,
+      OptionType::Call,
+      BSMCoc::BSM1973,
+    )
+    .with_curves(&yield_curve, Some(&dividend_curve));
+    let (call, put) = bsm.calculate_call_put();
+    let discount = yield_curve.discount(tau);
+    let forward = forward(100.0, tau, &yield_curve, Some(&dividend_curve));
+    assert!((call - put - discount * (forward - 105.0)).abs() < 1e-10);
+    assert!(matches!(bsm.b, BSMCoc::MERTON1973));
+
+    let heston = HestonPricer::new(
+      100.0,
+      0.04,
+      105.0,
+      0.0,
+      None,
+      -0.7,
+      2.0,
+      0.04,
+      0.3,
+      Some(0.0),
+      Some(tau),
+      None,
+      None,
+    )
+    .with_curves(&yield_curve, Some(&dividend_curve));
+    assert_eq!(heston.r, yield_curve.zero_rate(tau));
+    let (call, put) = heston.calculate_call_put();
+    assert!((call - put - discount * (forward - 105.0)).abs() < 1e-6);
+
+    // A flat curve is the flat rate
+    // Forward curve with repo spreads and cash dividends, the one after tau is ignored
+    let forward_curve = EquityForwardCurve::new(100.0, ZeroCurve::flat(0.03))
+      .unwrap()
+      .with_dividend_curve(ZeroCurve::flat(0.01))
+      .with_repo_curve(ZeroCurve::flat(0.005))
+      .with_dividends(&[(1.5, 2.0), (0.5, 2.0), (4.0, 2.0)])
+      .unwrap();
+    let expected = (100.0 - 2.0 * (-0.0075f64).exp() - 2.0 * (-0.0225f64).exp()) * 0.045f64.exp();
+    assert!((forward_curve.forward(tau) - expected).abs() < 1e-10);
+    assert!((forward_curve.forward(0.4) - 100.0 * 0.006f64.exp()).abs() < 1e-10);
+    let discount = forward_curve.discount(tau);
+    let forward = forward_curve.forward(tau);
+
+    let bsm = BSMPricer::new(
+      1.0,
+      0.2,
+      105.0,
+      0.0,
+      None,
+      None,
+      None,
+      Some(tau),
+      None,
+      None,
+      OptionType::Call,
+      BSMCoc::BSM1973,
+    )
+    .with_forward_curve(&forward_curve);
+    assert_eq!(bsm.s, 100.0);
+    let (call, put) = bsm.calculate_call_put();
+    assert!((call - put - discount * (forward - 105.0)).abs() < 1e-10);
+
+    let heston = HestonPricer::new(
+      1.0,
+      0.04,
+      105.0,
+      0.0,
+      None,
+      -0.7,
+      2.0,
+      0.04,
+      0.3,
+      Some(0.0),
+      Some(tau),
+      None,
+      None,
+    )
+    .with_forward_curve(&forward_curve);
+    let (call, put) = heston.calculate_call_put();
+    assert!((call - put - discount * (forward - 105.0)).abs() < 1e-6);
+    assert!(EquityForwardCurve::new(100.0, ZeroCurve::flat(0.03))
+      .unwrap()
+      .with_dividends(&[(1.0, 60.0), (2.0, 60.0)])
+      .is_err());
+
+    let flat = BSMPricer::new(
+      100.0,
+      0.2,
+      105.0,
+      0.0,
+      None,
+      None,
+      None,
+      Some(tau),
+      None,
+      None,
+      OptionType::Call,
+      BSMCoc::BSM1973,
+    )
+    .with_curves(&ZeroCurve::flat(0.03), None);
+    assert!((flat.r - 0.03).abs() < 1e-15);
+  }
+
+  #[test]
+  fn jump_sabr_and_american_pricers_with_curves() {
+    let yield_curve = curve(CurveInterpolation::FlatForward);
+    let dividend_curve = ZeroCurve::flat(0.01);
+    let tau = 3.0;
+    let discount = yield_curve.discount(tau);
+    let forward = forward(100.0, tau, &yield_curve, Some(&dividend_curve));
+
+    let kou = KouPricer::new(
+      100.0,
+      0.2,
+      105.0,
+      0.0,
+      None,
+      1.0,
+      0.4,
+      10.0,
+      5.0,
+      Some(tau),
+      None,
+      None,
+    )
+    .with_curves(&yield_curve, Some(&dividend_curve));
+    let (call, put) = kou.calculate_call_put();
+    assert!((call - put - discount * (forward - 105.0)).abs() < 1e-6);
+
+    let sabr = SABRPricer::new(
+      1.0,
+      105.0,
+      0.0,
+      None,
+      crate::quant::volatility::sabr::SabrParams {
+        alpha: 0.3,
+        beta: 0.7,
+        rho: -0.4,
+        nu: 0.6,
+      },
+      None,
+      Some(tau),
+      None,
+      None,
+    )
+    .with_curves(&yield_curve, Some(&dividend_curve));
+    assert!((sabr.forward() - 1.0 / 100.0 * forward).abs() < 1e-12);
+
+    let mut american = BjerksundStenslandPricer::new(
+      100.0,
+      0.2,
+      105.0,
+      0.0,
+      None,
+      Some(tau),
+      None,
+      None,
+      OptionType::Put,
+    )
+    .with_curves(&yield_curve, Some(&dividend_curve));
+    assert_eq!(american.r, yield_curve.zero_rate(tau));
+    assert_eq!(american.q, Some(0.01));
+    american.set_spot(90.0);
+    assert_eq!(american.s, 90.0);
+
+    let fd = FiniteDifferencePricer::new(
+      100.0,
+      0.2,
+      105.0,
+      0.0,
+      50,
+      50,
+      Some(tau),
+      None,
+      None,
+      crate::quant::OptionStyle::American,
+      OptionType::Put,
+      Default::default(),
+    )
+    .with_curves(&yield_curve, Some(&dividend_curve));
+    assert_eq!(fd.r, yield_curve.zero_rate(tau));
+  }
+}
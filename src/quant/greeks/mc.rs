@@ -0,0 +1,418 @@
+//! Monte Carlo delta, gamma and vega of European payoffs.
+//!
+//! Two unbiased estimators of dE[f(S(T))] / dp are available:
+//!
+//! - pathwise: the derivative is taken inside the expectation, E[f'(S(T)) dS(T) / dp]. It
+//!   has the lowest variance but needs a payoff that is Lipschitz in S(T), it is 0 for
+//!   digitals.
+//! - likelihood ratio: the derivative falls on the density of S(T), E[f(S(T)) w] with a
+//!   weight w that does not depend on the payoff, so any payoff works at the price of a
+//!   higher variance.
+//!
+//! Under the GBM the terminal price is simulated exactly and the weights are those of the
+//! log-normal density. Under the Heston model the density of S(T) is not known, the
+//! likelihood ratio is taken conditionally on the variance path. These are the Malliavin
+//! weights, cross-validated in [`greeks_validation`](crate::quant::greeks_validation),
+//! integrating by parts in the Brownian motion independent of the variance. The gamma of the pathwise method
+//! differentiates the pathwise delta with the likelihood ratio, so it also applies to
+//! calls and puts.
+//!
+//! - Broadie, M., & Glasserman, P. (1996). Estimating security price derivatives using
+//!   simulation.
+//! - Glasserman, P. (2003). Monte Carlo methods in financial engineering, chapter 7.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+use crate::quant::{greeks_validation::GreekEstimate, OptionType};
+
+/// European payoff f(S(T)) with its derivative for the pathwise method
+pub trait EuropeanPayoff: Sync {
+  /// Payoff at the terminal price
+  fn payoff(&self, s: f64) -> f64;
+
+  /// Derivative of the payoff, defined almost everywhere
+  fn derivative(&self, s: f64) -> f64;
+}
+
+/// Call or put
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct Vanilla {
+  /// Strike price
+  pub k: f64,
+  pub option_type: OptionType,
+}
+
+impl EuropeanPayoff for Vanilla {
+  fn payoff(&self, s: f64) -> f64 {
+    match self.option_type {
+      OptionType::Call => (s - self.k).max(0.0),
+      OptionType::Put => (self.k - s).max(0.0),
+    }
+  }
+
+  fn derivative(&self, s: f64) -> f64 {
+    match self.option_type {
+      OptionType::Call if s > self.k => 1.0,
+      OptionType::Put if s < self.k => -1.0,
+      _ => 0.0,
+    }
+  }
+}
+
+/// Cash-or-nothing digital paying 1, the pathwise method does not apply to it
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct Digital {
+  /// Strike price
+  pub k: f64,
+  pub option_type: OptionType,
+}
+
+impl EuropeanPayoff for Digital {
+  fn payoff(&self, s: f64) -> f64 {
+    match self.option_type {
+      OptionType::Call => f64::from(s > self.k),
+      OptionType::Put => f64::from(s < self.k),
+    }
+  }
+
+  fn derivative(&self, _s: f64) -> f64 {
+    0.0
+  }
+}
+
+/// Estimator of the sensitivities
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GreekMethod {
+  #[default]
+  Pathwise,
+  LikelihoodRatio,
+}
+
+/// Monte Carlo price and Greeks with their standard errors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MCGreeks {
+  pub price: GreekEstimate,
+  /// dV/dS
+  pub delta: GreekEstimate,
+  /// d^2V/dS^2
+  pub gamma: GreekEstimate,
+  /// dV/dsigma under the GBM, dV/dv0 under the Heston model
+  pub vega: GreekEstimate,
+}
+
+impl MCGreeks {
+  /// Estimates from samples of (price, delta, gamma, vega)
+  fn from_samples(samples: &[[f64; 4]]) -> Self {
+    let estimate =
+      |i: usize| GreekEstimate::from_samples(&samples.iter().map(|x| x[i]).collect::<Vec<_>>());
+
+    Self {
+      price: estimate(0),
+      delta: estimate(1),
+      gamma: estimate(2),
+      vega: estimate(3),
+    }
+  }
+}
+
+/// Greeks under the GBM (Black-Scholes model)
+#[derive(ImplNew)]
+pub struct GbmGreeks {
+  /// Underlying price
+  pub s: f64,
+  /// Volatility
+  pub sigma: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Time to maturity in years
+  pub tau: f64,
+  /// Number of Monte Carlo paths
+  pub paths: usize,
+}
+
+impl GbmGreeks {
+  /// Price and Greeks of a payoff at maturity
+  pub fn compute<P: EuropeanPayoff>(&self, payoff: &P, method: GreekMethod) -> MCGreeks {
+    let (s0, sigma, tau) = (self.s, self.sigma, self.tau);
+    let sqrt_tau = tau.sqrt();
+    let drift = (self.r - self.q.unwrap_or(0.0) - 0.5 * sigma.powi(2)) * tau;
+    let discount = (-self.r * tau).exp();
+
+    let samples = Array1::<f64>::random(self.paths, StandardNormal)
+      .par_iter()
+      .map(|&z| {
+        let s = s0 * (drift + sigma * sqrt_tau * z).exp();
+        let price = discount * payoff.payoff(s);
+
+        match method {
+          GreekMethod::Pathwise => {
+            let derivative = discount * payoff.derivative(s);
+            [
+              price,
+              derivative * s / s0,
+              derivative * s / s0.powi(2) * (z / (sigma * sqrt_tau) - 1.0),
+              derivative * s * (sqrt_tau * z - sigma * tau),
+            ]
+          }
+          GreekMethod::LikelihoodRatio => [
+            price,
+            price * z / (s0 * sigma * sqrt_tau),
+            price * (z * z - 1.0 - z * sigma * sqrt_tau) / (s0 * sigma).powi(2) / tau,
+            price * ((z * z - 1.0) / sigma - z * sqrt_tau),
+          ],
+        }
+      })
+      .collect::<Vec<_>>();
+
+    MCGreeks::from_samples(&samples)
+  }
+}
+
+/// Greeks under the Heston model, simulated with the log-Euler scheme for the price and
+/// the full truncation Euler scheme for the variance
+#[derive(ImplNew)]
+pub struct HestonGreeks {
+  /// Underlying price
+  pub s: f64,
+  /// Initial variance
+  pub v0: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Correlation between the price and the variance, |rho| < 1
+  pub rho: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of the variance
+  pub sigma: f64,
+  /// Time to maturity in years
+  pub tau: f64,
+  /// Number of time steps of the paths
+  pub n: usize,
+  /// Number of Monte Carlo paths
+  pub paths: usize,
+}
+
+impl HestonGreeks {
+  /// Price and Greeks of a payoff at maturity
+  pub fn compute<P: EuropeanPayoff>(&self, payoff: &P, method: GreekMethod) -> MCGreeks {
+    assert!(self.rho.abs() < 1.0, "rho must be in (-1, 1)");
+    let discount = (-self.r * self.tau).exp();
+    let s0 = self.s;
+
+    let samples = (0..self.paths)
+      .into_par_iter()
+      .map(|_| {
+        let z_s = Array1::<f64>::random(self.n, StandardNormal);
+        let z_v = Array1::<f64>::random(self.n, StandardNormal);
+        let path = self.simulate(s0, self.v0, &z_s, &z_v);
+        let price = discount * payoff.payoff(path.s);
+
+        match method {
+          GreekMethod::Pathwise => {
+            let derivative = discount * payoff.derivative(path.s);
+            [
+              price,
+              derivative * path.s / s0,
+              derivative * path.s / s0.powi(2) * (path.pi_perp - 1.0),
+              derivative * path.s * path.dlog_s_dv0,
+            ]
+          }
+          GreekMethod::LikelihoodRatio => [
+            price,
+            price * path.delta_weight,
+            price * path.gamma_weight,
+            price * path.vega_weight,
+          ],
+        }
+      })
+      .collect::<Vec<_>>();
+
+    MCGreeks::from_samples(&samples)
+  }
+
+  /// Terminal price, its derivative and the Malliavin weights of a path driven by the
+  /// standard normals z_s (price) and z_v (independent part of the variance)
+  pub(crate) fn simulate(
+    &self,
+    s0: f64,
+    v0: f64,
+    z_s: &Array1<f64>,
+    z_v: &Array1<f64>,
+  ) -> MalliavinPath {
+    let dt = self.tau / self.n as f64;
+    let sqrt_dt = dt.sqrt();
+    let rho_bar = (1.0 - self.rho.powi(2)).sqrt();
+    let drift = self.r - self.q.unwrap_or(0.0);
+
+    let (mut log_s, mut v, mut y) = (s0.ln(), v0, 1.0);
+    // d ln S(T) / dv0, integrated variance, sum alpha_i dZ⊥_i numerator and sum Y_i dt
+    let (mut g, mut variance, mut weighted_noise, mut y_sum) = (0.0, 0.0, 0.0, 0.0);
+
+    for i in 0..self.n {
+      let v_plus = v.max(0.0);
+      let sqrt_v = v_plus.sqrt();
+      let dw_s = sqrt_dt * z_s[i];
+      let dw_v = sqrt_dt * (self.rho * z_s[i] + rho_bar * z_v[i]);
+      // W⊥ = (W^S - rho W^v) / sqrt(1 - rho^2)
+      let z_perp = rho_bar * z_s[i] - self.rho * z_v[i];
+
+      log_s += (drift - 0.5 * v_plus) * dt + sqrt_v * dw_s;
+      variance += v_plus * dt;
+      weighted_noise += sqrt_v * sqrt_dt * z_perp;
+
+      if v > 0.0 {
+        g += -0.5 * y * dt + 0.5 * y / sqrt_v * dw_s;
+        y_sum += y * dt;
+        y *= 1.0 - self.kappa * dt + 0.5 * self.sigma * dw_v / sqrt_v;
+      }
+
+      v += self.kappa * (self.theta - v_plus) * dt + self.sigma * sqrt_v * dw_v;
+    }
+
+    let s = log_s.exp();
+    let pi_perp = weighted_noise / (rho_bar * variance);
+    // Given the variance, ln S(T) is normal with variance (1 - rho^2) V in the direction
+    // of W⊥, the Skorohod correction of the second weight is 1 / ((1 - rho^2) V)
+    let correction = 1.0 / (rho_bar.powi(2) * variance);
+
+    MalliavinPath {
+      s,
+      dlog_s_dv0: g,
+      pi_perp,
+      delta_weight: pi_perp / s0,
+      gamma_weight: (pi_perp.powi(2) - pi_perp - correction) / s0.powi(2),
+      vega_weight: g * pi_perp - y_sum / (2.0 * variance),
+    }
+  }
+}
+
+/// Terminal price of a Heston path with its sensitivities
+pub(crate) struct MalliavinPath {
+  pub s: f64,
+  /// d ln S(T) / dv0
+  pub dlog_s_dv0: f64,
+  /// Malliavin weight of the derivative with respect to ln S(0)
+  pub pi_perp: f64,
+  pub delta_weight: f64,
+  pub gamma_weight: f64,
+  pub vega_weight: f64,
+}
+
+#[cfg(test)]
+mod tests {
+  use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+  use super::*;
+  use crate::quant::{
+    pricing::{bsm::BSMPricer, heston::HestonPricer},
+    r#trait::Pricer,
+  };
+
+  fn assert_estimate(estimate: GreekEstimate, expected: f64, bias: f64) {
+    assert!(
+      (estimate.value - expected).abs() < 4.0 * estimate.std_error + bias,
+      "{:?} {}",
+      estimate,
+      expected
+    );
+  }
+
+  #[test]
+  fn gbm_greeks_match_black_scholes() {
+    let (s, k, r, sigma, tau) = (100.0, 105.0, 0.03, 0.25, 0.75);
+    let greeks = GbmGreeks::new(s, sigma, r, None, tau, 200_000);
+
+    for option_type in [OptionType::Call, OptionType::Put] {
+      let bsm = BSMPricer::new(
+        s,
+        sigma,
+        k,
+        r,
+        None,
+        None,
+        None,
+        Some(tau),
+        None,
+        None,
+        option_type,
+        Default::default(),
+      );
+      let payoff = Vanilla::new(k, option_type);
+      let pathwise = greeks.compute(&payoff, GreekMethod::Pathwise);
+      let likelihood = greeks.compute(&payoff, GreekMethod::LikelihoodRatio);
+
+      for estimates in [pathwise, likelihood] {
+        assert_estimate(estimates.delta, bsm.delta(), 0.0);
+        assert_estimate(estimates.gamma, bsm.gamma(), 0.0);
+        assert_estimate(estimates.vega, bsm.vega(), 0.0);
+      }
+      // The pathwise estimator has the lower variance
+      assert!(pathwise.delta.std_error < likelihood.delta.std_error);
+      assert!(pathwise.vega.std_error < likelihood.vega.std_error);
+    }
+  }
+
+  #[test]
+  fn likelihood_ratio_prices_digital_delta() {
+    let (s, k, r, sigma, tau) = (100.0, 100.0, 0.02, 0.2, 1.0);
+    let greeks = GbmGreeks::new(s, sigma, r, None, tau, 200_000);
+    let digital = Digital::new(k, OptionType::Call);
+
+    let d2 = ((s / k).ln() + (r - 0.5 * sigma * sigma) * tau) / (sigma * tau.sqrt());
+    let normal = Normal::default();
+    let price = (-r * tau).exp() * normal.cdf(d2);
+    let delta = (-r * tau).exp() * normal.pdf(d2) / (s * sigma * tau.sqrt());
+
+    let likelihood = greeks.compute(&digital, GreekMethod::LikelihoodRatio);
+    assert_estimate(likelihood.price, price, 0.0);
+    assert_estimate(likelihood.delta, delta, 0.0);
+    // The derivative of the payoff vanishes almost everywhere
+    let pathwise = greeks.compute(&digital, GreekMethod::Pathwise);
+    assert_eq!(pathwise.delta.value, 0.0);
+  }
+
+  #[test]
+  fn heston_greeks_match_semi_analytic() {
+    let (s, v0, k, r) = (100.0, 0.04, 100.0, 0.03);
+    let pricer = |s: f64| {
+      HestonPricer::new(
+        s,
+        v0,
+        k,
+        r,
+        None,
+        -0.7,
+        2.0,
+        0.04,
+        0.3,
+        Some(0.0),
+        Some(1.0),
+        None,
+        None,
+      )
+    };
+    let analytic = pricer(s);
+    let gamma = (pricer(s + 0.1).delta() - pricer(s - 0.1).delta()) / 0.2;
+
+    let greeks = HestonGreeks::new(s, v0, r, None, -0.7, 2.0, 0.04, 0.3, 1.0, 100, 40_000);
+    let payoff = Vanilla::new(k, OptionType::Call);
+    for method in [GreekMethod::Pathwise, GreekMethod::LikelihoodRatio] {
+      let estimates = greeks.compute(&payoff, method);
+      // The bias covers the discretization of the variance
+      assert_estimate(estimates.price, analytic.calculate_call_put().0, 0.05);
+      assert_estimate(estimates.delta, analytic.delta(), 0.01);
+      assert_estimate(estimates.gamma, gamma, 0.002);
+      assert_estimate(estimates.vega, analytic.vega_v0(), 2.0);
+    }
+  }
+}
@@ -0,0 +1,319 @@
+//! Cross-validation of the Heston call delta and v0 sensitivity.
+//!
+//! The Greeks are computed three ways:
+//!
+//! - semi-analytically by differentiating the characteristic functions ([`HestonPricer`]),
+//! - by central finite differences on Monte Carlo prices with common random numbers,
+//! - by Monte Carlo with the Malliavin weights of
+//!   [`heston_weights`](crate::stochastic::malliavin::malliavin_2d::heston_weights), built
+//!   from the Malliavin derivatives of the [`Heston`] process, which needs no bumping and no
+//!   payoff derivative.
+//!
+//! Both Monte Carlo estimators sample the [`Heston`] process with the Euler scheme from the
+//! same standard normal increments. The comparison needs the `malliavin` feature.
+//!
+//! - Fournié, E., Lasry, J. M., Lebuchoux, J., Lions, P. L., & Touzi, N. (1999). Applications of
+//!   Malliavin calculus to Monte Carlo methods in finance.
+//! - Benhamou, E. (2003). Optimal Malliavin weighting function for the computation of the Greeks.
+
+#[cfg(feature = "malliavin")]
+use std::fmt::Display;
+
+#[cfg(feature = "malliavin")]
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+#[cfg(feature = "malliavin")]
+use ndarray::Array2;
+#[cfg(feature = "malliavin")]
+use ndarray_rand::RandomExt;
+#[cfg(feature = "malliavin")]
+use rand_distr::StandardNormal;
+#[cfg(feature = "malliavin")]
+use rayon::prelude::*;
+
+#[cfg(feature = "malliavin")]
+use crate::{
+  quant::pricing::heston::HestonPricer,
+  stochastic::{
+    malliavin::malliavin_2d::heston_weights,
+    noise::cgns::CGNS,
+    variance_reduction::GaussianDriven,
+    volatility::{heston::Heston, HestonPow},
+    Sampling2D,
+  },
+};
+
+/// Monte Carlo estimate of a Greek
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreekEstimate {
+  /// Sample mean
+  pub value: f64,
+  /// Standard error of the mean
+  pub std_error: f64,
+}
+
+impl GreekEstimate {
+  pub(crate) fn from_samples(samples: &[f64]) -> Self {
+    let samples = Array1::from_vec(samples.to_vec());
+    let n = samples.len() as f64;
+
+    Self {
+      value: samples.mean().unwrap(),
+      std_error: (samples.var(1.0) / n).sqrt(),
+    }
+  }
+}
+
+#[cfg(feature = "malliavin")]
+/// A Greek computed with the three methods
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreekComparison {
+  /// Semi-analytical value
+  pub analytic: f64,
+  /// Finite differences on Monte Carlo prices
+  pub finite_difference: GreekEstimate,
+  /// Monte Carlo with Malliavin weights
+  pub malliavin: GreekEstimate,
+}
+
+#[cfg(feature = "malliavin")]
+impl GreekComparison {
+  /// Deviations of the Monte Carlo estimates from the analytic value in standard errors
+  pub fn z_scores(&self) -> (f64, f64) {
+    let z = |estimate: &GreekEstimate| (estimate.value - self.analytic) / estimate.std_error;
+    (z(&self.finite_difference), z(&self.malliavin))
+  }
+
+  /// Whether both estimates are within z standard errors plus the absolute tolerance
+  /// (covering the discretization bias) of the analytic value
+  pub fn agrees(&self, z: f64, tolerance: f64) -> bool {
+    [self.finite_difference, self.malliavin]
+      .iter()
+      .all(|estimate| (estimate.value - self.analytic).abs() <= z * estimate.std_error + tolerance)
+  }
+}
+
+#[cfg(feature = "malliavin")]
+/// Result of the cross-validation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HestonGreeksReport {
+  /// dC/dS
+  pub delta: GreekComparison,
+  /// dC/dv0
+  pub vega: GreekComparison,
+}
+
+#[cfg(feature = "malliavin")]
+impl Display for HestonGreeksReport {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for (name, greek) in [("delta", &self.delta), ("vega", &self.vega)] {
+      let (z_fd, z_malliavin) = greek.z_scores();
+      writeln!(
+        f,
+        "{:<6} analytic {:.6} | finite difference {:.6} ± {:.6} (z = {:.2}) | malliavin {:.6} ± {:.6} (z = {:.2})",
+        name,
+        greek.analytic,
+        greek.finite_difference.value,
+        greek.finite_difference.std_error,
+        z_fd,
+        greek.malliavin.value,
+        greek.malliavin.std_error,
+        z_malliavin
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(feature = "malliavin")]
+/// Cross-validation of the Heston call delta and v0 sensitivity
+#[derive(ImplNew)]
+pub struct HestonGreeksValidation {
+  /// Underlying price
+  pub s: f64,
+  /// Initial variance
+  pub v0: f64,
+  /// Strike price
+  pub k: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Correlation between the price and the variance, |rho| < 1
+  pub rho: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of the variance
+  pub sigma: f64,
+  /// Time to maturity in years
+  pub tau: f64,
+  /// Number of time steps of the Monte Carlo paths
+  pub n: usize,
+  /// Number of Monte Carlo paths
+  pub paths: usize,
+  /// Relative bump of the finite differences, 1% if None
+  pub bump: Option<f64>,
+}
+
+#[cfg(feature = "malliavin")]
+impl HestonGreeksValidation {
+  /// Compute the Greeks with the three methods
+  pub fn run(&self) -> HestonGreeksReport {
+    assert!(self.rho.abs() < 1.0, "rho must be in (-1, 1)");
+
+    let pricer = HestonPricer::new(
+      self.s,
+      self.v0,
+      self.k,
+      self.r,
+      self.q,
+      self.rho,
+      self.kappa,
+      self.theta,
+      self.sigma,
+      Some(0.0),
+      Some(self.tau),
+      None,
+      None,
+    );
+
+    let bump = self.bump.unwrap_or(0.01);
+    let (ds, dv) = (bump * self.s, bump * self.v0);
+    let discount = (-self.r * self.tau).exp();
+    let payoff = |s: &Array1<f64>| discount * (s[self.n] - self.k).max(0.0);
+    let dt = self.tau / self.n as f64;
+    let rho_bar = (1.0 - self.rho.powi(2)).sqrt();
+
+    let heston = |s: f64, v0: f64, malliavin: bool| {
+      Heston::new(
+        Some(s),
+        Some(v0),
+        self.kappa,
+        self.theta,
+        self.sigma,
+        self.rho,
+        self.r - self.q.unwrap_or(0.0),
+        self.n + 1,
+        Some(self.tau),
+        HestonPow::Sqrt,
+        None,
+        None,
+        None,
+        CGNS::new(self.rho, self.n, Some(self.tau), None),
+        Some(malliavin),
+      )
+    };
+    let models = [
+      heston(self.s + ds, self.v0, false),
+      heston(self.s - ds, self.v0, false),
+      heston(self.s, self.v0 + dv, false),
+      heston(self.s, self.v0 - dv, false),
+    ];
+    let model = heston(self.s, self.v0, true);
+
+    let samples = (0..self.paths)
+      .into_par_iter()
+      .map(|_| {
+        let z = Array2::<f64>::random((2, self.n), StandardNormal);
+        let [s_up, s_down, v_up, v_down] = models
+          .each_ref()
+          .map(|m| payoff(&m.sample_from_increments(z.view())[0]));
+
+        // The Malliavin derivatives are stored on the process, so each path gets its own copy
+        let model = model.clone();
+        let [s, _] = model.sample_from_increments(z.view());
+        let dw1 = z.row(0).mapv(|z| z * dt.sqrt());
+        // W⊥ = (W1 - rho W2) / sqrt(1 - rho^2)
+        let dw_perp = (&z.row(0) * rho_bar - &z.row(1) * self.rho) * dt.sqrt();
+        let [delta_weight, vega_weight] = heston_weights(
+          &s,
+          &model.malliavin(),
+          dw1.view(),
+          dw_perp.view(),
+          self.rho,
+          dt,
+        );
+        let price = payoff(&s);
+
+        [
+          (s_up - s_down) / (2.0 * ds),
+          (v_up - v_down) / (2.0 * dv),
+          price * delta_weight,
+          price * vega_weight,
+        ]
+      })
+      .collect::<Vec<_>>();
+
+    let estimate =
+      |i: usize| GreekEstimate::from_samples(&samples.iter().map(|x| x[i]).collect::<Vec<_>>());
+
+    HestonGreeksReport {
+      delta: GreekComparison {
+        analytic: pricer.delta(),
+        finite_difference: estimate(0),
+        malliavin: estimate(2),
+      },
+      vega: GreekComparison {
+        analytic: pricer.vega_v0(),
+        finite_difference: estimate(1),
+        malliavin: estimate(3),
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(feature = "malliavin")]
+  use super::*;
+  use crate::quant::pricing::heston::HestonPricer;
+
+  #[test]
+  #[cfg(feature = "malliavin")]
+  fn heston_greeks_agree() {
+    let validation = HestonGreeksValidation::new(
+      100.0, 0.04, 100.0, 0.03, None, -0.7, 2.0, 0.04, 0.3, 1.0, 100, 40_000, None,
+    );
+    let report = validation.run();
+    println!("{}", report);
+
+    assert!(report.delta.agrees(4.0, 0.01));
+    assert!(report.vega.agrees(4.0, 2.0));
+  }
+
+  #[test]
+  fn heston_analytic_greeks_match_bumped_prices() {
+    use crate::quant::r#trait::Pricer;
+
+    let pricer = |s: f64, v0: f64| {
+      HestonPricer::new(
+        s,
+        v0,
+        100.0,
+        0.03,
+        Some(0.01),
+        -0.7,
+        2.0,
+        0.04,
+        0.3,
+        Some(0.0),
+        Some(1.0),
+        None,
+        None,
+      )
+    };
+    let base = pricer(100.0, 0.04);
+    let delta = (pricer(100.01, 0.04).calculate_call_put().0
+      - pricer(99.99, 0.04).calculate_call_put().0)
+      / 0.02;
+    let vega = (pricer(100.0, 0.0401).calculate_call_put().0
+      - pricer(100.0, 0.0399).calculate_call_put().0)
+      / 0.0002;
+
+    assert!((base.delta() - delta).abs() < 1e-5);
+    assert!((base.vega_v0() - vega).abs() < 1e-3 * vega.abs());
+  }
+}
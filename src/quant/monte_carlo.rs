@@ -0,0 +1,245 @@
+use ndarray::Array1;
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::stochastic::{variance_reduction::GaussianDriven, Sampling};
+
+/// Running estimate of a Monte Carlo simulation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MCEstimate {
+  /// Number of simulated paths so far
+  pub paths: usize,
+  /// Sample mean
+  pub mean: f64,
+  /// Standard error of the mean
+  pub std_error: f64,
+  /// Half-width of the confidence interval
+  pub half_width: f64,
+}
+
+/// Result of a sequential Monte Carlo run
+#[derive(Debug, Clone)]
+pub struct MCResult {
+  /// Final estimate
+  pub estimate: MCEstimate,
+  /// Estimate after every batch
+  pub history: Vec<MCEstimate>,
+  /// Whether the tolerance was reached before `max_paths`
+  pub converged: bool,
+}
+
+/// Monte Carlo engine with sequential stopping.
+///
+/// Paths are simulated in parallel batches of `batch_size`. After each batch the
+/// running mean and the half-width z * s / sqrt(n) of the confidence interval are
+/// updated, and the simulation stops as soon as the half-width drops below
+/// `tolerance` (but not before `min_paths`) or `max_paths` is reached.
+pub struct MonteCarlo {
+  /// Absolute tolerance on the confidence interval half-width
+  pub tolerance: f64,
+  /// Number of paths simulated between two checks
+  pub batch_size: usize,
+  /// Hard limit of the number of paths
+  pub max_paths: usize,
+  /// Minimum number of paths before stopping, batch_size if None
+  pub min_paths: Option<usize>,
+  /// Confidence level of the interval, 0.95 if None
+  pub confidence: Option<f64>,
+}
+
+impl MonteCarlo {
+  #[must_use]
+  pub fn new(tolerance: f64, batch_size: usize, max_paths: usize) -> Self {
+    Self {
+      tolerance,
+      batch_size,
+      max_paths,
+      min_paths: None,
+      confidence: None,
+    }
+  }
+
+  /// Estimate E[X] where every call of `sample` returns an independent draw of X
+  pub fn run<F>(&self, sample: F) -> MCResult
+  where
+    F: Fn() -> f64 + Sync,
+  {
+    self.run_with_callback(sample, |_| true)
+  }
+
+  /// Estimate E[X] calling `callback` with the running estimate after every batch.
+  ///
+  /// The callback can be used to report progress or update a live convergence plot,
+  /// returning false stops the simulation.
+  pub fn run_with_callback<F, C>(&self, sample: F, mut callback: C) -> MCResult
+  where
+    F: Fn() -> f64 + Sync,
+    C: FnMut(&MCEstimate) -> bool,
+  {
+    assert!(self.batch_size > 0, "batch_size must be positive");
+
+    let z = Normal::default().inverse_cdf(0.5 + 0.5 * self.confidence.unwrap_or(0.95));
+    let min_paths = self.min_paths.unwrap_or(self.batch_size).max(2);
+
+    let mut n = 0;
+    let mut mean = 0.0;
+    let mut m2 = 0.0;
+    let mut history = Vec::new();
+    let mut converged = false;
+
+    while n < self.max_paths {
+      let size = self.batch_size.min(self.max_paths - n);
+      let batch = (0..size)
+        .into_par_iter()
+        .map(|_| sample())
+        .collect::<Vec<_>>();
+
+      // Chan et al. parallel update of the mean and the sum of squared deviations
+      let batch_mean = batch.iter().sum::<f64>() / size as f64;
+      let batch_m2 = batch.iter().map(|x| (x - batch_mean).powi(2)).sum::<f64>();
+      let total = n + size;
+      let delta = batch_mean - mean;
+      mean += delta * size as f64 / total as f64;
+      m2 += batch_m2 + delta.powi(2) * (n * size) as f64 / total as f64;
+      n = total;
+
+      let std_error = match n {
+        1 => f64::INFINITY,
+        _ => (m2 / (n - 1) as f64 / n as f64).sqrt(),
+      };
+      let estimate = MCEstimate {
+        paths: n,
+        mean,
+        std_error,
+        half_width: z * std_error,
+      };
+      history.push(estimate);
+
+      if !callback(&estimate) {
+        break;
+      }
+
+      if n >= min_paths && estimate.half_width <= self.tolerance {
+        converged = true;
+        break;
+      }
+    }
+
+    MCResult {
+      estimate: history.last().copied().unwrap_or_default(),
+      history,
+      converged,
+    }
+  }
+
+  /// Estimate E[payoff(X)] over the paths of a sampler
+  pub fn run_sampler<S, P>(&self, sampler: &S, payoff: P) -> MCResult
+  where
+    S: Sampling<f64>,
+    P: Fn(&Array1<f64>) -> f64 + Sync,
+  {
+    self.run(|| payoff(&sampler.sample()))
+  }
+
+  /// Estimate E[payoff(X)] with antithetic variates.
+  ///
+  /// Every draw is the average payoff of a path pair driven by Z and -Z, so `paths`
+  /// of the estimate counts pairs.
+  pub fn run_antithetic<S, P>(&self, sampler: &S, payoff: P) -> MCResult
+  where
+    S: GaussianDriven<f64, Path = Array1<f64>>,
+    P: Fn(&Array1<f64>) -> f64 + Sync,
+  {
+    self.run(|| {
+      let (path, antithetic) = sampler.sample_antithetic();
+      0.5 * (payoff(&path) + payoff(&antithetic))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand_distr::{Distribution, Normal as RandNormal};
+
+  use crate::{
+    plot::mc_convergence,
+    stochastic::{diffusion::gbm::GBM, S0},
+  };
+
+  use super::*;
+
+  #[test]
+  fn mc_stops_at_tolerance() {
+    let normal = RandNormal::new(1.0, 2.0).unwrap();
+    let mc = MonteCarlo::new(0.05, 1000, 1_000_000);
+    let result = mc.run(|| normal.sample(&mut rand::thread_rng()));
+
+    assert!(result.converged);
+    assert!(result.estimate.half_width <= 0.05);
+    // z^2 sigma^2 / tol^2 ~ 6147 paths
+    assert!(result.estimate.paths < 10_000);
+    assert!((result.estimate.mean - 1.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn mc_callback_can_stop() {
+    let mc = MonteCarlo::new(0.0, 100, 10_000);
+    let mut calls = 0;
+    let result = mc.run_with_callback(rand::random::<f64>, |_| {
+      calls += 1;
+      calls < 3
+    });
+
+    assert!(!result.converged);
+    assert_eq!(result.estimate.paths, 300);
+    assert_eq!(result.history.len(), 3);
+  }
+
+  #[test]
+  fn mc_gbm_call_convergence_plot() {
+    let gbm = GBM::new(
+      0.05,
+      0.2,
+      100,
+      Some(S0),
+      Some(1.0),
+      None,
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let mc = MonteCarlo::new(0.1, 500, 200_000);
+    let result = mc.run_sampler(&gbm, |path| {
+      (-0.05_f64).exp() * (path.last().unwrap() - 100.0).max(0.0)
+    });
+
+    println!("{:?}", result.estimate);
+    mc_convergence(&result.history, Some(10.4506)).show();
+  }
+
+  #[test]
+  fn mc_antithetic_needs_fewer_paths() {
+    let gbm = GBM::new(
+      0.05,
+      0.2,
+      100,
+      Some(S0),
+      Some(1.0),
+      None,
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let mc = MonteCarlo::new(0.1, 500, 200_000);
+    let payoff = |path: &Array1<f64>| (-0.05_f64).exp() * (path.last().unwrap() - 100.0).max(0.0);
+
+    let plain = mc.run_sampler(&gbm, payoff);
+    let antithetic = mc.run_antithetic(&gbm, payoff);
+
+    assert!(antithetic.converged);
+    assert!(2 * antithetic.estimate.paths < plain.estimate.paths);
+    assert!((antithetic.estimate.mean - 10.4506).abs() < 0.3);
+  }
+}
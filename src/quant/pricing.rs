@@ -1,5 +1,15 @@
 pub mod asian;
+pub mod autocallable;
+pub mod bates;
+pub mod bjerksund_stensland;
 pub mod bsm;
+pub mod exotic;
+pub mod fft;
 pub mod finitie_difference;
+pub mod gram_charlier;
 pub mod heston;
+pub mod kou;
+pub mod lsm;
 pub mod merton_jump;
+pub mod rough_heston;
+pub mod saddlepoint;
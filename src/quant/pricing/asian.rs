@@ -1,7 +1,10 @@
 use impl_new_derive::ImplNew;
 use statrs::distribution::{ContinuousCDF, Normal};
 
-use crate::quant::r#trait::{Pricer, Time};
+use crate::{
+  quant::r#trait::{Pricer, Time},
+  stochastic::catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+};
 
 /// Asian option pricer
 #[derive(ImplNew)]
@@ -56,3 +59,26 @@ impl Time for AsianPricer {
     self.expiration.unwrap()
   }
 }
+
+impl ProcessInfo for AsianPricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "AsianPricer",
+    title: "Geometric average Asian option pricer",
+    path: "quant::pricing::asian::AsianPricer",
+    kind: ModelKind::Pricer,
+    description: "Closed form price of the geometric average Asian option under Black-Scholes",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::V,
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::Q,
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::EXPIRATION,
+    ],
+    references: &[
+      "Kemna, A. G. Z., & Vorst, A. C. F. (1990). A pricing method for options based on average asset values.",
+    ],
+  };
+}
@@ -0,0 +1,291 @@
+//! Monte Carlo prices and Greeks of autocallable (snowball) notes.
+//!
+//! The note observes the underlying at discrete dates t_1 < ... < t_k = T. At the first
+//! observation with S(t_j) >= autocall barrier it is redeemed early and pays the notional
+//! plus the coupon accrued to t_j. Without early redemption it pays the notional plus the
+//! coupon to T, unless the price fell to the knock-in barrier at any point of the path,
+//! then the holder is short a put struck at the reference price and receives
+//! notional min(S(T) / S_ref, 1). The barriers are fractions of the reference price S_ref,
+//! the initial fixing.
+//!
+//! The knock-in barrier is monitored at every path point, so the grid of the simulation is
+//! the monitoring schedule, e.g. daily. The payoff is discontinuous at the barriers, so the
+//! Greeks are central differences on the same paths (common random numbers), which removes
+//! most of the noise of the difference of two independent prices.
+//!
+//! - Glasserman, P. (2003). Monte Carlo methods in financial engineering, section 7.1.
+//! - Bouzoubaa, M., & Osseiran, A. (2010). Exotic Options and Hybrids, chapter 10.
+
+use impl_new_derive::ImplNew;
+use ndarray::{Array1, ArrayView1};
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  quant::{greeks_validation::GreekEstimate, monte_carlo::MCEstimate},
+  stochastic::variance_reduction::GaussianDriven,
+};
+
+/// Autocallable note with a coupon accruing linearly until the redemption
+#[derive(ImplNew, Debug, Clone)]
+pub struct Autocallable {
+  pub notional: f64,
+  /// Reference price S_ref of the barriers and the put, the initial fixing
+  pub reference: f64,
+  /// Increasing observation times of the early redemption, the last one is the maturity,
+  /// e.g. the payment times of a [`Schedule`](crate::quant::schedule::Schedule)
+  pub observation_times: Vec<f64>,
+  /// Early redemption barrier as a fraction of the reference price, e.g. 1.0
+  pub autocall_barrier: f64,
+  /// Knock-in barrier as a fraction of the reference price, e.g. 0.7
+  pub knock_in_barrier: f64,
+  /// Annual coupon rate, the redemption at t pays notional (1 + coupon t)
+  pub coupon: f64,
+}
+
+impl Autocallable {
+  /// Maturity, the last observation time
+  pub fn maturity(&self) -> f64 {
+    *self
+      .observation_times
+      .last()
+      .expect("at least 1 observation time is needed")
+  }
+
+  /// Payment time and amount of the note on a price path with the time step dt
+  pub fn cash_flow(&self, path: ArrayView1<f64>, dt: f64) -> (f64, f64) {
+    let last = path.len() - 1;
+    let index = |t: f64| ((t / dt).round() as usize).min(last);
+
+    for &t in &self.observation_times {
+      if path[index(t)] >= self.autocall_barrier * self.reference {
+        return (t, self.notional * (1.0 + self.coupon * t));
+      }
+    }
+
+    let maturity = self.maturity();
+    let terminal = path[index(maturity)];
+    let knocked_in = path
+      .iter()
+      .take(index(maturity) + 1)
+      .any(|&s| s <= self.knock_in_barrier * self.reference);
+
+    match knocked_in {
+      true => (
+        maturity,
+        self.notional * (terminal / self.reference).min(1.0),
+      ),
+      false => (maturity, self.notional * (1.0 + self.coupon * maturity)),
+    }
+  }
+
+  /// Payment discounted at the flat rate r
+  pub fn present_value(&self, path: ArrayView1<f64>, dt: f64, r: f64) -> f64 {
+    let (t, amount) = self.cash_flow(path, dt);
+    amount * (-r * t).exp()
+  }
+}
+
+/// Price, delta and gamma of an autocallable
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutocallableGreeks {
+  pub price: GreekEstimate,
+  /// dV/dS
+  pub delta: GreekEstimate,
+  /// d^2V/dS^2
+  pub gamma: GreekEstimate,
+}
+
+/// Monte Carlo pricer of autocallables under the paths of a risk-neutral price process
+/// driven by Brownian motions
+#[derive(ImplNew)]
+pub struct AutocallablePricer<S> {
+  /// Risk-neutral price process, its time horizon covers the maturity of the note
+  pub sampler: S,
+  /// Current price, the initial point of the paths
+  pub s: f64,
+  /// Time horizon of the paths
+  pub t: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Number of paths
+  pub m: usize,
+}
+
+impl<S: GaussianDriven<f64, Path = Array1<f64>>> AutocallablePricer<S> {
+  /// Price of the note
+  pub fn price(&self, note: &Autocallable) -> MCEstimate {
+    let values = (0..self.m)
+      .into_par_iter()
+      .map(|_| self.present_value(note, &self.sampler.sample_with_noise().0, 1.0))
+      .collect::<Vec<_>>();
+
+    let estimate = GreekEstimate::from_samples(&values);
+    MCEstimate {
+      paths: self.m,
+      mean: estimate.value,
+      std_error: estimate.std_error,
+      half_width: Normal::default().inverse_cdf(0.975) * estimate.std_error,
+    }
+  }
+
+  /// Price, delta and gamma with the relative spot bump h
+  ///
+  /// The bumped paths are the simulated ones scaled by 1 +- h, which is re-simulating them
+  /// with the bumped spot and the same random numbers for the processes whose returns do
+  /// not depend on the price level, e.g. GBM or Heston, not for a local volatility.
+  pub fn greeks(&self, note: &Autocallable, h: f64) -> AutocallableGreeks {
+    let bump = h * self.s;
+    let samples = (0..self.m)
+      .into_par_iter()
+      .map(|_| {
+        let path = self.sampler.sample_with_noise().0;
+        let [down, base, up] =
+          [1.0 - h, 1.0, 1.0 + h].map(|scale| self.present_value(note, &path, scale));
+        [
+          base,
+          (up - down) / (2.0 * bump),
+          (up - 2.0 * base + down) / bump.powi(2),
+        ]
+      })
+      .collect::<Vec<_>>();
+
+    let estimate =
+      |i: usize| GreekEstimate::from_samples(&samples.iter().map(|x| x[i]).collect::<Vec<_>>());
+    AutocallableGreeks {
+      price: estimate(0),
+      delta: estimate(1),
+      gamma: estimate(2),
+    }
+  }
+
+  /// Central difference (V(up) - V(down)) / (2 bump) with respect to any parameter of the
+  /// process, e.g. the volatility, with the same random numbers for the bumped processes
+  pub fn sensitivity(&self, note: &Autocallable, up: &S, down: &S, bump: f64) -> GreekEstimate {
+    let samples = (0..self.m)
+      .into_par_iter()
+      .map(|_| {
+        let (_, z) = self.sampler.sample_with_noise();
+        let [up, down] = [up, down]
+          .map(|process| self.present_value(note, &process.sample_from_increments(z.view()), 1.0));
+        (up - down) / (2.0 * bump)
+      })
+      .collect::<Vec<_>>();
+
+    GreekEstimate::from_samples(&samples)
+  }
+
+  /// Discounted payment on the path scaled by the factor
+  fn present_value(&self, note: &Autocallable, path: &Array1<f64>, scale: f64) -> f64 {
+    let dt = self.t / (path.len() - 1) as f64;
+    note.present_value((path * scale).view(), dt, self.r)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::{
+    quant::{pricing::bsm::BSMPricer, r#trait::Pricer, OptionType},
+    stochastic::diffusion::{gbm::GBM, scheme::Scheme},
+  };
+
+  const S: f64 = 100.0;
+  const R: f64 = 0.03;
+  const SIGMA: f64 = 0.25;
+  const T: f64 = 1.0;
+
+  fn gbm(sigma: f64) -> GBM {
+    GBM::new(
+      R,
+      sigma,
+      253,
+      Some(S),
+      Some(T),
+      Some(Scheme::Milstein),
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    )
+  }
+
+  fn quarterly(autocall: f64, knock_in: f64) -> Autocallable {
+    Autocallable::new(1.0, S, vec![0.25, 0.5, 0.75, 1.0], autocall, knock_in, 0.12)
+  }
+
+  #[test]
+  fn autocallable_cash_flows() {
+    let note = quarterly(1.0, 0.7);
+    let dt = 0.25;
+    // Redeemed at the second observation
+    assert_eq!(
+      note.cash_flow(array![100.0, 95.0, 101.0, 60.0, 50.0].view(), dt),
+      (0.5, 1.06)
+    );
+    // Knocked in and short the put at maturity
+    assert_eq!(
+      note.cash_flow(array![100.0, 95.0, 65.0, 90.0, 80.0].view(), dt),
+      (1.0, 0.8)
+    );
+    // Neither redeemed nor knocked in, the full coupon
+    assert_eq!(
+      note.cash_flow(array![100.0, 95.0, 75.0, 90.0, 80.0].view(), dt),
+      (1.0, 1.12)
+    );
+  }
+
+  #[test]
+  fn always_knocked_in_note_is_a_bond_short_a_put() {
+    // Never redeemed early and knocked in at the start: e^(-rT) - P(S, S_ref) / S_ref
+    let note = quarterly(10.0, 10.0);
+    let pricer = AutocallablePricer::new(gbm(SIGMA), S, T, R, 20_000);
+    let bsm = |s: f64| {
+      BSMPricer::new(
+        s,
+        SIGMA,
+        S,
+        R,
+        None,
+        None,
+        None,
+        Some(T),
+        None,
+        None,
+        OptionType::Put,
+        Default::default(),
+      )
+    };
+    let (_, put) = bsm(S).calculate_call_put();
+    let expected = (-R * T).exp() - put / S;
+
+    let price = pricer.price(&note);
+    assert!((price.mean - expected).abs() < 4.0 * price.std_error + 2e-3);
+
+    // The CRN delta of the terminal price, N(-d1) / S_ref for the short put
+    let greeks = pricer.greeks(&note, 0.01);
+    let d1 = ((R + 0.5 * SIGMA.powi(2)) * T) / (SIGMA * T.sqrt());
+    let delta = Normal::default().cdf(-d1) / S;
+    assert!((greeks.delta.value - delta).abs() < 4.0 * greeks.delta.std_error + 1e-4);
+    assert!(greeks.delta.std_error < 0.05 * delta);
+  }
+
+  #[test]
+  fn snowball_greeks() {
+    let note = quarterly(1.0, 0.7);
+    let pricer = AutocallablePricer::new(gbm(SIGMA), S, T, R, 20_000);
+    let greeks = pricer.greeks(&note, 0.01);
+
+    // Below the full coupon and above the worst case of the put
+    assert!(greeks.price.value < 1.12 * (-R * 0.25).exp());
+    assert!(greeks.price.value > 0.7);
+    // Long the underlying through the short put
+    assert!(greeks.delta.value > 0.0);
+
+    // The knock-in put makes the note short volatility
+    let vega = pricer.sensitivity(&note, &gbm(SIGMA + 0.01), &gbm(SIGMA - 0.01), 0.01);
+    assert!(vega.value < -4.0 * vega.std_error, "{:?}", vega);
+  }
+}
@@ -0,0 +1,443 @@
+use std::f64::consts::FRAC_1_PI;
+
+use impl_new_derive::ImplNew;
+use implied_vol::implied_black_volatility;
+use num_complex::Complex64;
+use quadrature::double_exponential;
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    pricing::heston::heston_exponent,
+    r#trait::{CharacteristicFn, Pricer, Time},
+    OptionType,
+  },
+  stochastic::catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+};
+
+/// European options under the Bates (1996) stochastic volatility jump diffusion of
+/// [`Bates`](crate::stochastic::jump::bates::Bates)
+///
+/// dS(t) = (r - q - lambda k) S(t) dt + sqrt(v(t)) S(t) dW_1(t) + (e^J - 1) S(t) dN(t)
+/// dv(t) = kappa (theta - v(t)) dt + sigma sqrt(v(t)) dW_2(t)
+///
+/// with lognormal jumps J ~ N(mu_j, sigma_j^2) arriving with intensity lambda and
+/// k = e^(mu_j + sigma_j^2 / 2) - 1. The characteristic function of
+/// X = ln(S(tau) / S) - (r - q) tau is the Heston one, in the form of Albrecher et al. (2007)
+/// which has no branch cut, times the one of the compensated jumps
+///
+/// exp(lambda tau (e^(iu mu_j - u^2 sigma_j^2 / 2) - 1) - iu lambda tau k)
+///
+/// and the prices follow from the inversion formula of Lewis (2001).
+#[derive(ImplNew, Clone)]
+pub struct BatesPricer {
+  /// Stock price
+  pub s: f64,
+  /// Initial variance
+  pub v0: f64,
+  /// Strike price
+  pub k: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Correlation between the stock price and its variance
+  pub rho: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of the variance
+  pub sigma: f64,
+  /// Jump intensity
+  pub lambda: f64,
+  /// Mean of the log jump sizes
+  pub mu_j: f64,
+  /// Standard deviation of the log jump sizes
+  pub sigma_j: f64,
+  /// Time to maturity
+  pub tau: Option<f64>,
+  /// Evaluation date
+  pub eval: Option<chrono::NaiveDate>,
+  /// Expiration date
+  pub expiry: Option<chrono::NaiveDate>,
+}
+
+impl Pricer for BatesPricer {
+  /// Calculate the price of a European call and put option
+  ///
+  /// C = S e^(-q tau) - sqrt(S K) e^(-(r + q) tau / 2) / pi int_0^inf Re(e^(iux) phi(u - i/2)) / (u^2 + 1/4) du
+  /// with x = ln(S / K) + (r - q) tau
+  fn calculate_call_put(&self) -> (f64, f64) {
+    let tau = self.tau().unwrap_or(1.0);
+    let q = self.q.unwrap_or(0.0);
+
+    let call = self.s * (-q * tau).exp() - self.lewis(tau, |_| Complex64::new(1.0, 0.0));
+    let put = call + self.k * (-self.r * tau).exp() - self.s * (-q * tau).exp();
+
+    (call, put)
+  }
+
+  /// Derivatives of the call with respect to
+  /// (v0, theta, rho, kappa, sigma, lambda, mu_j, sigma_j), the put has the same ones
+  ///
+  /// The characteristic function is differentiated under the integral, analytically in
+  /// v0 and the jump parameters and by central differences of its exponent in the others.
+  fn derivatives(&self) -> Vec<f64> {
+    let tau = self.tau().unwrap_or(1.0);
+    let bumped = |j: usize, w: Complex64| {
+      let h = 1e-5 * self.param(j).abs().max(1e-2);
+      let [up, down] = [h, -h].map(|bump| {
+        let mut pricer = self.clone();
+        pricer.set_param(j, self.param(j) + bump);
+        let (c, d) = pricer.heston_exponent(w, tau);
+        c + d * self.v0
+      });
+      (up - down) / (2.0 * h)
+    };
+
+    (0..8)
+      .map(|j| {
+        -self.lewis(tau, |w| match j {
+          0 => self.heston_exponent(w, tau).1,
+          1..=4 => bumped(j, w),
+          _ => self.jump_exponent_derivative(j, w, tau),
+        })
+      })
+      .collect()
+  }
+
+  /// Check the model parameters
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.s > 0.0 && self.k > 0.0, "s and k must be positive")?;
+    ensure(self.v0 >= 0.0, "v0 must be non-negative")?;
+    ensure(self.sigma > 0.0, "sigma must be positive")?;
+    ensure(self.lambda >= 0.0, "lambda must be non-negative")?;
+    ensure(self.sigma_j >= 0.0, "sigma_j must be non-negative")?;
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
+  }
+
+  fn implied_volatility(&self, c_price: f64, option_type: OptionType) -> f64 {
+    let tau = self.tau().unwrap_or(1.0);
+    let forward = self.s * ((self.r - self.q.unwrap_or(0.0)) * tau).exp();
+    implied_black_volatility(
+      c_price * (self.r * tau).exp(),
+      forward,
+      self.k,
+      tau,
+      option_type == OptionType::Call,
+    )
+  }
+}
+
+impl Time for BatesPricer {
+  fn tau(&self) -> Option<f64> {
+    self.tau
+  }
+
+  fn eval(&self) -> chrono::NaiveDate {
+    self.eval.unwrap()
+  }
+
+  fn expiration(&self) -> chrono::NaiveDate {
+    self.expiry.unwrap()
+  }
+}
+
+impl BatesPricer {
+  /// Characteristic function of ln(S(tau) / S) under the risk-neutral measure
+  pub fn log_return_cf(&self, u: Complex64, tau: f64) -> Complex64 {
+    let drift = Complex64::i() * u * (self.r - self.q.unwrap_or(0.0)) * tau;
+
+    (drift + self.exponent(u, tau)).exp()
+  }
+
+  /// sqrt(S K) e^(-(r + q) tau / 2) / pi int_0^inf Re(e^(iux) phi(w) g(w)) / (u^2 + 1/4) du
+  /// with w = u - i/2, the call is S e^(-q tau) minus the integral with g = 1
+  fn lewis(&self, tau: f64, g: impl Fn(Complex64) -> Complex64) -> f64 {
+    let q = self.q.unwrap_or(0.0);
+    let x = (self.s / self.k).ln() + (self.r - q) * tau;
+
+    let integrand = |u: f64| {
+      let w = Complex64::new(u, -0.5);
+      ((Complex64::i() * u * x + self.exponent(w, tau)).exp() * g(w)).re / (u * u + 0.25)
+    };
+    let integral = double_exponential::integrate(integrand, 0.0, 200.0, 1e-8).integral;
+
+    (self.s * self.k).sqrt() * (-0.5 * (self.r + q) * tau).exp() * FRAC_1_PI * integral
+  }
+
+  /// Exponent of the characteristic function of ln(S(tau) / S) - (r - q) tau
+  fn exponent(&self, u: Complex64, tau: f64) -> Complex64 {
+    let (c, d) = self.heston_exponent(u, tau);
+    c + d * self.v0 + self.jump_exponent(u, tau)
+  }
+
+  /// C and D of the Heston characteristic function exp(C + D v0)
+  fn heston_exponent(&self, u: Complex64, tau: f64) -> (Complex64, Complex64) {
+    heston_exponent(u, tau, self.kappa, self.theta, self.rho, self.sigma)
+  }
+
+  /// Mean relative jump size e^(mu_j + sigma_j^2 / 2) - 1
+  fn mean_jump(&self) -> f64 {
+    (self.mu_j + 0.5 * self.sigma_j.powi(2)).exp() - 1.0
+  }
+
+  /// lambda tau (e^(iu mu_j - u^2 sigma_j^2 / 2) - 1) - iu lambda tau k
+  fn jump_exponent(&self, u: Complex64, tau: f64) -> Complex64 {
+    self.lambda * tau * self.jump_terms(u).0
+  }
+
+  /// e^(iu mu_j - u^2 sigma_j^2 / 2) - 1 - iu k and the jump characteristic function
+  fn jump_terms(&self, u: Complex64) -> (Complex64, Complex64) {
+    let i = Complex64::i();
+    let jump_cf = (i * u * self.mu_j - 0.5 * u * u * self.sigma_j.powi(2)).exp();
+    (jump_cf - 1.0 - i * u * self.mean_jump(), jump_cf)
+  }
+
+  /// Derivative of the jump exponent with respect to lambda (5), mu_j (6) or sigma_j (7)
+  fn jump_exponent_derivative(&self, j: usize, u: Complex64, tau: f64) -> Complex64 {
+    let i = Complex64::i();
+    let (terms, jump_cf) = self.jump_terms(u);
+    let k1 = self.mean_jump() + 1.0;
+
+    match j {
+      5 => tau * terms,
+      6 => self.lambda * tau * i * u * (jump_cf - k1),
+      _ => self.lambda * tau * self.sigma_j * (-u * u * jump_cf - i * u * k1),
+    }
+  }
+
+  /// Parameter j in the order of [`BatesPricer::derivatives`]
+  fn param(&self, j: usize) -> f64 {
+    [
+      self.v0,
+      self.theta,
+      self.rho,
+      self.kappa,
+      self.sigma,
+      self.lambda,
+      self.mu_j,
+      self.sigma_j,
+    ][j]
+  }
+
+  fn set_param(&mut self, j: usize, value: f64) {
+    let param = match j {
+      0 => &mut self.v0,
+      1 => &mut self.theta,
+      2 => &mut self.rho,
+      3 => &mut self.kappa,
+      4 => &mut self.sigma,
+      5 => &mut self.lambda,
+      6 => &mut self.mu_j,
+      _ => &mut self.sigma_j,
+    };
+    *param = value;
+  }
+}
+
+impl CharacteristicFn for BatesPricer {
+  /// Characteristic function of ln(S(t) / S) - (r - q) t
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    self.exponent(u, t).exp()
+  }
+}
+
+impl ProcessInfo for BatesPricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "BatesPricer",
+    title: "Bates semi-analytical pricer",
+    path: "quant::pricing::bates::BatesPricer",
+    kind: ModelKind::Pricer,
+    description: "European options under the Heston model with lognormal jumps by Fourier inversion of the characteristic function",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::real("v0", "Initial variance", Interval::NON_NEGATIVE, 0.04),
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::Q,
+      ParameterInfo::RHO.example(-0.7),
+      ParameterInfo::real("kappa", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("theta", "Long-run variance", Interval::POSITIVE, 0.04),
+      ParameterInfo::real("sigma", "Volatility of the variance", Interval::POSITIVE, 0.3),
+      ParameterInfo::real("lambda", "Jump intensity", Interval::NON_NEGATIVE, 0.5),
+      ParameterInfo::real("mu_j", "Mean of the log jump sizes", Interval::REAL, -0.1),
+      ParameterInfo::real("sigma_j", "Standard deviation of the log jump sizes", Interval::NON_NEGATIVE, 0.15),
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::component("expiry", "Expiration date").optional(),
+    ],
+    references: &[
+      "Bates, D. S. (1996). Jumps and stochastic volatility: Exchange rate processes implicit in Deutsche Mark options.",
+      "Albrecher, H., Mayer, P., Schoutens, W., & Tistaert, J. (2007). The little Heston trap.",
+      "Lewis, A. L. (2001). A simple option formula for general jump-diffusion and other exponential Lévy processes.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use statrs::distribution::{ContinuousCDF, Normal};
+
+  use super::*;
+  use crate::quant::pricing::{
+    bsm::{BSMCoc, BSMPricer},
+    heston::HestonPricer,
+  };
+
+  fn pricer(k: f64, lambda: f64) -> BatesPricer {
+    BatesPricer::new(
+      100.0,
+      0.04,
+      k,
+      0.03,
+      Some(0.01),
+      -0.7,
+      1.5,
+      0.05,
+      0.4,
+      lambda,
+      -0.1,
+      0.15,
+      Some(0.5),
+      None,
+      None,
+    )
+  }
+
+  #[test]
+  fn bates_without_jumps_is_heston() {
+    for k in [80.0, 100.0, 120.0] {
+      let (call, put) = pricer(k, 0.0).calculate_call_put();
+      let (heston_call, heston_put) = HestonPricer::new(
+        100.0,
+        0.04,
+        k,
+        0.03,
+        Some(0.01),
+        -0.7,
+        1.5,
+        0.05,
+        0.4,
+        Some(0.0),
+        Some(0.5),
+        None,
+        None,
+      )
+      .calculate_call_put();
+
+      assert!(
+        (call - heston_call).abs() < 1e-6,
+        "{} {}",
+        call,
+        heston_call
+      );
+      assert!((put - heston_put).abs() < 1e-6, "{} {}", put, heston_put);
+    }
+  }
+
+  #[test]
+  fn bates_cf_is_a_martingale() {
+    // E[S(tau)] = S e^((r - q) tau), phi(-i) = e^((r - q) tau)
+    let cf = pricer(100.0, 0.8).log_return_cf(-Complex64::i(), 0.5);
+    assert!((cf.re - (0.02f64 * 0.5).exp()).abs() < 1e-10 && cf.im.abs() < 1e-10);
+  }
+
+  #[test]
+  fn bates_with_constant_variance_is_merton() {
+    // With v0 = theta and a vanishing volatility of the variance the model is the Merton
+    // jump diffusion, a Poisson mixture of Black-Scholes prices
+    let (s, k, r, tau, v): (f64, f64, f64, f64, f64) = (100.0, 105.0, 0.03, 0.5, 0.04);
+    let (lambda, mu_j, sigma_j): (f64, f64, f64) = (0.8, -0.1, 0.15);
+    let bates = BatesPricer::new(
+      s,
+      v,
+      k,
+      r,
+      None,
+      0.0,
+      1.0,
+      v,
+      1e-3,
+      lambda,
+      mu_j,
+      sigma_j,
+      Some(tau),
+      None,
+      None,
+    );
+
+    let normal = Normal::default();
+    let mean_jump = (mu_j + 0.5 * sigma_j * sigma_j).exp() - 1.0;
+    let lambda_tau = lambda * (1.0 + mean_jump) * tau;
+    let mut weight = (-lambda_tau).exp();
+    let mut merton = 0.0;
+    for n in 0..50 {
+      if n > 0 {
+        weight *= lambda_tau / n as f64;
+      }
+      let vol = (v + n as f64 * sigma_j * sigma_j / tau).sqrt();
+      let r_n = r - lambda * mean_jump + n as f64 * (mean_jump + 1.0).ln() / tau;
+      let d1 = ((s / k).ln() + (r_n + 0.5 * vol * vol) * tau) / (vol * tau.sqrt());
+      let d2 = d1 - vol * tau.sqrt();
+      merton += weight * (s * normal.cdf(d1) - k * (-r_n * tau).exp() * normal.cdf(d2));
+    }
+
+    let (call, _) = bates.calculate_call_put();
+    assert!((call - merton).abs() < 1e-4, "{} {}", call, merton);
+  }
+
+  #[test]
+  fn bates_derivatives_match_bumped_prices() {
+    let bates = pricer(95.0, 0.8);
+    let derivatives = bates.derivatives();
+    assert_eq!(derivatives.len(), 8);
+
+    for (j, derivative) in derivatives.iter().enumerate() {
+      let h = 1e-4 * bates.param(j).abs().max(1e-2);
+      let [up, down] = [h, -h].map(|bump| {
+        let mut bumped = bates.clone();
+        bumped.set_param(j, bates.param(j) + bump);
+        bumped.calculate_call_put().0
+      });
+      let bumped = (up - down) / (2.0 * h);
+
+      assert!(
+        (derivative - bumped).abs() < 1e-4 * bumped.abs().max(1.0),
+        "{} {} {}",
+        j,
+        derivative,
+        bumped
+      );
+    }
+  }
+
+  #[test]
+  fn bates_implied_volatility_inverts_black_scholes() {
+    for k in [80.0, 100.0, 120.0] {
+      let pricer = pricer(k, 0.8);
+      let (call, put) = BSMPricer::new(
+        pricer.s,
+        0.25,
+        k,
+        pricer.r,
+        None,
+        None,
+        pricer.q,
+        pricer.tau,
+        None,
+        None,
+        OptionType::Call,
+        BSMCoc::MERTON1973,
+      )
+      .calculate_call_put();
+
+      assert!((pricer.implied_volatility(call, OptionType::Call) - 0.25).abs() < 1e-8);
+      assert!((pricer.implied_volatility(put, OptionType::Put) - 0.25).abs() < 1e-8);
+    }
+  }
+}
@@ -0,0 +1,222 @@
+//! Bjerksund-Stensland approximation of American options.
+//!
+//! The early exercise boundary is approximated by a flat trigger price I, so the call is
+//! a European up-and-out call with rebate I - K at the trigger plus the exercise value
+//! above it, which has the closed form
+//!
+//! C = alpha S^beta - alpha phi(beta, I) + phi(1, I) - phi(1, K) - K phi(0, I) + K phi(0, K)
+//!
+//! where phi(gamma, H) is the value of S^gamma paid at maturity if S stays below I and ends
+//! below H. The approximation is a lower bound of the American price and the put follows
+//! from the put-call transformation P(S, K, r, b) = C(K, S, r - b, -b).
+//!
+//! - Bjerksund, P., & Stensland, G. (1993). Closed-form approximation of American options.
+
+use impl_new_derive::ImplNew;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  quant::{
+    r#trait::{Pricer, Time},
+    volatility::american::american_implied_volatility,
+    OptionType,
+  },
+  stochastic::catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+};
+
+/// Bjerksund-Stensland (1993) pricer of American options
+#[derive(ImplNew, Clone)]
+pub struct BjerksundStenslandPricer {
+  /// Underlying price
+  pub s: f64,
+  /// Volatility
+  pub v: f64,
+  /// Strike price
+  pub k: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Time to maturity in years
+  pub tau: Option<f64>,
+  /// Evaluation date
+  pub eval: Option<chrono::NaiveDate>,
+  /// Expiration date
+  pub expiration: Option<chrono::NaiveDate>,
+  /// Option type
+  pub option_type: OptionType,
+}
+
+impl Pricer for BjerksundStenslandPricer {
+  /// Calculate the option price
+  fn calculate_price(&self) -> f64 {
+    let tau = self.tau.unwrap_or_else(|| self.calculate_tau_in_years());
+    let b = self.r - self.q.unwrap_or(0.0);
+
+    match self.option_type {
+      OptionType::Call => call(self.s, self.k, tau, self.r, b, self.v),
+      OptionType::Put => call(self.k, self.s, tau, self.r - b, -b, self.v),
+    }
+  }
+
+  /// Implied volatility of the American option by Brent's method
+  fn implied_volatility(&self, c_price: f64, option_type: OptionType) -> f64 {
+    american_implied_volatility(c_price, |v| {
+      BjerksundStenslandPricer {
+        v,
+        option_type,
+        ..self.clone()
+      }
+      .calculate_price()
+    })
+    .unwrap_or(f64::NAN)
+  }
+}
+
+impl Time for BjerksundStenslandPricer {
+  fn tau(&self) -> Option<f64> {
+    self.tau
+  }
+
+  fn eval(&self) -> chrono::NaiveDate {
+    self.eval.unwrap()
+  }
+
+  fn expiration(&self) -> chrono::NaiveDate {
+    self.expiration.unwrap()
+  }
+}
+
+/// American call with the cost of carry b
+fn call(s: f64, k: f64, t: f64, r: f64, b: f64, v: f64) -> f64 {
+  let n = Normal::new(0.0, 1.0).unwrap();
+  let v2 = v * v;
+
+  // Never exercised early, the European call
+  if b >= r {
+    let d1 = ((s / k).ln() + (b + 0.5 * v2) * t) / (v * t.sqrt());
+    let d2 = d1 - v * t.sqrt();
+    return s * ((b - r) * t).exp() * n.cdf(d1) - k * (-r * t).exp() * n.cdf(d2);
+  }
+
+  let beta = (0.5 - b / v2) + ((b / v2 - 0.5).powi(2) + 2.0 * r / v2).sqrt();
+  let b_inf = beta / (beta - 1.0) * k;
+  let b_0 = k.max(r / (r - b) * k);
+  let h = -(b * t + 2.0 * v * t.sqrt()) * b_0 / (b_inf - b_0);
+  let trigger = b_0 + (b_inf - b_0) * (1.0 - h.exp());
+
+  if s >= trigger {
+    return s - k;
+  }
+
+  let alpha = (trigger - k) * trigger.powf(-beta);
+  let phi = |gamma: f64, h: f64| {
+    let lambda = (-r + gamma * b + 0.5 * gamma * (gamma - 1.0) * v2) * t;
+    let d = -((s / h).ln() + (b + (gamma - 0.5) * v2) * t) / (v * t.sqrt());
+    let kappa = 2.0 * b / v2 + 2.0 * gamma - 1.0;
+    // (I / S)^kappa N(x) in logs, the power overflows at small volatilities where N(x)
+    // vanishes
+    let reflected = match n.cdf(d - 2.0 * (trigger / s).ln() / (v * t.sqrt())) {
+      cdf if cdf > 0.0 => (kappa * (trigger / s).ln() + cdf.ln()).exp(),
+      _ => 0.0,
+    };
+    lambda.exp() * s.powf(gamma) * (n.cdf(d) - reflected)
+  };
+
+  alpha * s.powf(beta) - alpha * phi(beta, trigger) + phi(1.0, trigger)
+    - phi(1.0, k)
+    - k * phi(0.0, trigger)
+    + k * phi(0.0, k)
+}
+
+impl ProcessInfo for BjerksundStenslandPricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "BjerksundStenslandPricer",
+    title: "Bjerksund-Stensland pricer",
+    path: "quant::pricing::bjerksund_stensland::BjerksundStenslandPricer",
+    kind: ModelKind::Pricer,
+    description: "Closed-form approximation of American options with a flat exercise boundary",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::V,
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::Q,
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::EXPIRATION,
+      ParameterInfo::choice("option_type", "Option type", &["Call", "Put"]),
+    ],
+    references: &[
+      "Bjerksund, P., & Stensland, G. (1993). Closed-form approximation of American options.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::{
+    pricing::{
+      bsm::{BSMCoc, BSMPricer},
+      finitie_difference::{FiniteDifferenceMethod, FiniteDifferencePricer},
+    },
+    OptionStyle,
+  };
+
+  fn pricer(k: f64, q: Option<f64>, option_type: OptionType) -> BjerksundStenslandPricer {
+    BjerksundStenslandPricer::new(100.0, 0.25, k, 0.05, q, Some(1.0), None, None, option_type)
+  }
+
+  #[test]
+  fn american_bounds_and_finite_differences() {
+    for k in [80.0, 100.0, 120.0] {
+      let european = BSMPricer::new(
+        100.0,
+        0.25,
+        k,
+        0.05,
+        None,
+        None,
+        None,
+        Some(1.0),
+        None,
+        None,
+        OptionType::Call,
+        BSMCoc::BSM1973,
+      )
+      .calculate_call_put();
+
+      // Without dividends the call is never exercised early
+      let call = pricer(k, None, OptionType::Call).calculate_price();
+      assert!((call - european.0).abs() < 1e-10);
+
+      let put = pricer(k, None, OptionType::Put).calculate_price();
+      assert!(put >= european.1 && put >= k - 100.0);
+
+      let fd = FiniteDifferencePricer::new(
+        100.0,
+        0.25,
+        k,
+        0.05,
+        2000,
+        300,
+        Some(1.0),
+        None,
+        None,
+        OptionStyle::American,
+        OptionType::Put,
+        FiniteDifferenceMethod::CrankNicolson,
+      )
+      .calculate_price();
+      // The flat boundary is a lower bound, within about 15 cents at these strikes
+      assert!((put - fd).abs() < 0.15, "{} {} {}", k, put, fd);
+    }
+
+    // Deep in the money puts are exercised
+    assert_eq!(
+      pricer(200.0, None, OptionType::Put).calculate_price(),
+      100.0
+    );
+  }
+}
@@ -1,10 +1,16 @@
 use impl_new_derive::ImplNew;
 use implied_vol::implied_black_volatility;
+use rayon::prelude::*;
 use statrs::distribution::{Continuous, ContinuousCDF, Normal};
 
-use crate::quant::{
-  r#trait::{Pricer, Time},
-  OptionType,
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    greeks::Greeks,
+    r#trait::{Pricer, Time},
+    OptionType,
+  },
+  stochastic::catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
 };
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -56,9 +62,95 @@ pub struct BSMPricer {
   pub b: BSMCoc,
 }
 
+/// Minimal number of quotes of a rayon task in the batch APIs
+const BATCH_MIN_LEN: usize = 1024;
+
+/// Inputs of one vanilla of [`BSMPricer::price_batch`]
+#[derive(Clone, Copy, Debug)]
+pub struct BSMQuoteInput {
+  /// Underlying price
+  pub s: f64,
+  /// Volatility
+  pub v: f64,
+  /// Strike price
+  pub k: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Cost of carry, e.g. r - q for a stock with dividend yield q or 0 for a future
+  pub b: f64,
+  /// Time to maturity in years
+  pub tau: f64,
+  /// Option type of the Greeks
+  pub option_type: OptionType,
+}
+
+impl From<&BSMPricer> for BSMQuoteInput {
+  fn from(pricer: &BSMPricer) -> Self {
+    Self {
+      s: pricer.s,
+      v: pricer.v,
+      k: pricer.k,
+      r: pricer.r,
+      b: pricer.b(),
+      tau: pricer.tau().unwrap(),
+      option_type: pricer.option_type,
+    }
+  }
+}
+
+impl BSMQuoteInput {
+  fn d1_d2(&self) -> (f64, f64) {
+    let vol = self.v * self.tau.sqrt();
+    let d1 = ((self.s / self.k).ln() + (self.b + 0.5 * self.v.powi(2)) * self.tau) / vol;
+
+    (d1, d1 - vol)
+  }
+
+  fn call_put(&self, n: &Normal) -> (f64, f64) {
+    let (d1, d2) = self.d1_d2();
+    let forward = self.s * ((self.b - self.r) * self.tau).exp();
+    let strike = self.k * (-self.r * self.tau).exp();
+
+    (
+      forward * n.cdf(d1) - strike * n.cdf(d2),
+      strike * n.cdf(-d2) - forward * n.cdf(-d1),
+    )
+  }
+
+  /// Greeks with the rho of a cost of carry moving with the rate, like BSM1973
+  fn greeks(&self, n: &Normal) -> Greeks {
+    let (d1, d2) = self.d1_d2();
+    let sqrt_tau = self.tau.sqrt();
+    let exp_bt = ((self.b - self.r) * self.tau).exp();
+    let exp_rt = (-self.r * self.tau).exp();
+    let pdf_d1 = n.pdf(d1);
+    let sign = match self.option_type {
+      OptionType::Call => 1.0,
+      OptionType::Put => -1.0,
+    };
+    let (cdf_d1, cdf_d2) = (n.cdf(sign * d1), n.cdf(sign * d2));
+
+    let vega = self.s * exp_bt * pdf_d1 * sqrt_tau;
+
+    Greeks {
+      delta: sign * exp_bt * cdf_d1,
+      gamma: exp_bt * pdf_d1 / (self.s * self.v * sqrt_tau),
+      vega,
+      theta: -self.s * exp_bt * pdf_d1 * self.v / (2.0 * sqrt_tau)
+        - sign * (self.b - self.r) * self.s * exp_bt * cdf_d1
+        - sign * self.r * self.k * exp_rt * cdf_d2,
+      rho: sign * self.k * self.tau * exp_rt * cdf_d2,
+      vanna: -exp_bt * pdf_d1 * d2 / self.v,
+      vomma: vega * d1 * d2 / self.v,
+      charm: -exp_bt
+        * (pdf_d1 * (self.b / (self.v * sqrt_tau) - d2 / (2.0 * self.tau))
+          + sign * (self.b - self.r) * cdf_d1),
+    }
+  }
+}
+
 impl Pricer for BSMPricer {
   /// Calculate the option price
-  #[must_use]
   fn calculate_call_put(&self) -> (f64, f64) {
     let (d1, d2) = self.d1_d2();
     let n = Normal::default();
@@ -72,6 +164,22 @@ impl Pricer for BSMPricer {
     (call, put)
   }
 
+  /// Check the parameters and the inputs required by the cost of carry
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.s > 0.0 && self.k > 0.0, "s and k must be positive")?;
+    ensure(self.v > 0.0, "v must be positive")?;
+    ensure(self.tau.is_some(), "tau must be provided")?;
+
+    match self.b {
+      BSMCoc::MERTON1973 => ensure(self.q.is_some(), "q must be provided for MERTON1973"),
+      BSMCoc::GARMAN1983 => ensure(
+        self.r_d.is_some() && self.r_f.is_some(),
+        "r_d and r_f must be provided for GARMAN1983",
+      ),
+      _ => Ok(()),
+    }
+  }
+
   /// Calculate the implied volatility
   fn implied_volatility(&self, c_price: f64, option_type: OptionType) -> f64 {
     implied_black_volatility(
@@ -83,6 +191,7 @@ impl Pricer for BSMPricer {
     )
   }
 
+  /// [delta, gamma, theta, vega, rho], the first entries of [`Pricer::greeks`]
   fn derivatives(&self) -> Vec<f64> {
     vec![
       self.delta(),
@@ -92,6 +201,26 @@ impl Pricer for BSMPricer {
       self.rho(),
     ]
   }
+
+  /// Closed form Greeks. The rate enters the cost of carry of BSM1973 and MERTON1973 only,
+  /// for the other costs of carry the rho is -tau V.
+  fn greeks(&self) -> Option<Greeks> {
+    let greeks = BSMQuoteInput::from(self).greeks(&Normal::default());
+    let rho = match self.b {
+      BSMCoc::BSM1973 | BSMCoc::MERTON1973 => greeks.rho,
+      _ => {
+        let (call, put) = self.calculate_call_put();
+        let price = match self.option_type {
+          OptionType::Call => call,
+          OptionType::Put => put,
+        };
+
+        -self.tau().unwrap() * price
+      }
+    };
+
+    Some(Greeks { rho, ..greeks })
+  }
 }
 
 impl Time for BSMPricer {
@@ -109,6 +238,26 @@ impl Time for BSMPricer {
 }
 
 impl BSMPricer {
+  /// (call, put) of every quote, in parallel with one normal distribution for the batch
+  pub fn price_batch(inputs: &[BSMQuoteInput]) -> Vec<(f64, f64)> {
+    let n = Normal::default();
+    inputs
+      .par_iter()
+      .with_min_len(BATCH_MIN_LEN)
+      .map(|input| input.call_put(&n))
+      .collect()
+  }
+
+  /// Greeks of every quote for its option type, in parallel like [`BSMPricer::price_batch`]
+  pub fn greeks_batch(inputs: &[BSMQuoteInput]) -> Vec<Greeks> {
+    let n = Normal::default();
+    inputs
+      .par_iter()
+      .with_min_len(BATCH_MIN_LEN)
+      .map(|input| input.greeks(&n))
+      .collect()
+  }
+
   /// Calculate d1
   fn d1_d2(&self) -> (f64, f64) {
     let d1 = (1.0 / (self.v * self.tau().unwrap().sqrt()))
@@ -174,7 +323,7 @@ impl BSMPricer {
       first_term + second_term + third_term
     } else {
       let second_term = (self.b() - self.r) * self.s * exp_bt * n.cdf(-d1);
-      let third_term = -self.r * self.k * exp_rt * n.cdf(-d2);
+      let third_term = self.r * self.k * exp_rt * n.cdf(-d2);
       first_term + second_term + third_term
     }
   }
@@ -211,7 +360,7 @@ impl BSMPricer {
     self.vega() * d1 * d2 / self.v
   }
 
-  /// Calculate the charm
+  /// Calculate the charm dDelta/dtau
   pub fn charm(&self) -> f64 {
     let v = self.v;
     let r = self.r;
@@ -343,10 +492,72 @@ impl BSMPricer {
   }
 }
 
+impl ProcessInfo for BSMPricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "BSMPricer",
+    title: "Generalized Black-Scholes-Merton pricer",
+    path: "quant::pricing::bsm::BSMPricer",
+    kind: ModelKind::Pricer,
+    description: "Black-Scholes-Merton prices and Greeks with cost of carry b",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::V,
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::real("r_d", "Domestic risk-free rate", Interval::REAL, 0.05).optional(),
+      ParameterInfo::real("r_f", "Foreign risk-free rate", Interval::REAL, 0.0).optional(),
+      ParameterInfo::Q,
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::EXPIRATION,
+      ParameterInfo::choice("option_type", "Option type", &["Call", "Put"]),
+      ParameterInfo::choice(
+        "b",
+        "Cost of carry",
+        &[
+          "BSM1973",
+          "MERTON1973",
+          "BLACK1976",
+          "ASAY1982",
+          "GARMAN1983",
+        ],
+      ),
+    ],
+    references: &[
+      "Black, F., & Scholes, M. (1973). The pricing of options and corporate liabilities.",
+      "Haug, E. G. (2007). The Complete Guide to Option Pricing Formulas.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn bsm_put_theta_is_minus_the_maturity_derivative() {
+    let price = |tau: f64| {
+      BSMPricer::new(
+        100.0,
+        0.2,
+        95.0,
+        0.05,
+        None,
+        None,
+        None,
+        Some(tau),
+        None,
+        None,
+        OptionType::Put,
+        BSMCoc::BSM1973,
+      )
+    };
+    let h = 1e-5;
+    let bumped =
+      -(price(1.0 + h).calculate_call_put().1 - price(1.0 - h).calculate_call_put().1) / (2.0 * h);
+    assert!((price(1.0).theta() - bumped).abs() < 1e-6);
+  }
+
   #[test]
   fn bsm_price() {
     let bsm = BSMPricer::new(
@@ -388,4 +599,59 @@ mod tests {
     let iv = bsm.implied_volatility(call, OptionType::Call);
     println!("Implied Volatility: {}", iv);
   }
+
+  #[test]
+  fn bsm_batch_matches_single_pricer() {
+    let pricers = (0..20_000)
+      .map(|i| {
+        let x = i as f64 / 20_000.0;
+        BSMPricer::new(
+          100.0,
+          0.1 + 0.4 * x,
+          60.0 + 80.0 * ((7.0 * x).fract()),
+          0.03,
+          Some(0.05),
+          Some(0.01),
+          Some(0.02),
+          Some(0.05 + 2.0 * ((13.0 * x).fract())),
+          None,
+          None,
+          if i % 2 == 0 {
+            OptionType::Call
+          } else {
+            OptionType::Put
+          },
+          [
+            BSMCoc::BSM1973,
+            BSMCoc::MERTON1973,
+            BSMCoc::BLACK1976,
+            BSMCoc::GARMAN1983,
+          ][i % 4],
+        )
+      })
+      .collect::<Vec<_>>();
+    let inputs = pricers.iter().map(BSMQuoteInput::from).collect::<Vec<_>>();
+
+    let start = std::time::Instant::now();
+    let prices = BSMPricer::price_batch(&inputs);
+    let greeks = BSMPricer::greeks_batch(&inputs);
+    println!(
+      "{} quotes priced with Greeks in {:?}",
+      inputs.len(),
+      start.elapsed()
+    );
+
+    for (pricer, ((call, put), greeks)) in pricers.iter().zip(prices.iter().zip(&greeks)) {
+      let (c, p) = pricer.calculate_call_put();
+      assert!((call - c).abs() < 1e-10 && (put - p).abs() < 1e-10);
+      assert!((greeks.delta - pricer.delta()).abs() < 1e-10);
+      assert!((greeks.gamma - pricer.gamma()).abs() < 1e-10);
+      assert!((greeks.theta - pricer.theta()).abs() < 1e-10);
+      assert!((greeks.vega - pricer.vega()).abs() < 1e-10);
+      assert!((greeks.rho - pricer.rho()).abs() < 1e-10);
+      assert!((greeks.vanna - pricer.vanna()).abs() < 1e-10);
+      assert!((greeks.vomma - pricer.vomma()).abs() < 1e-10);
+      assert!((greeks.charm + pricer.charm()).abs() < 1e-10);
+    }
+  }
 }
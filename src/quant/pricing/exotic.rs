@@ -0,0 +1,674 @@
+//! Monte Carlo prices of path dependent options.
+//!
+//! A [`Payoff`] maps a simulated price path on the uniform grid of [0, t] to the payoff
+//! at t, and [`ExoticMCPricer`] averages the discounted payoffs over the paths of any
+//! [`Sampling`] process. Several payoffs can be priced on the same paths, which keeps
+//! the relations between them, e.g. the in-out parity of barriers, free of noise.
+//!
+//! Barriers are monitored at the path points, or continuously with the Brownian bridge
+//! correction: between two points of a geometric Brownian motion the barrier is crossed
+//! with probability exp(-2 ln(S_i / B) ln(S_(i+1) / B) / (sigma^2 dt)), and the payoff
+//! is weighted by the probability that no crossing happens.
+//!
+//! Range accruals are fixed at every path point after the initial one, so the path grid is
+//! the fixing schedule, e.g. 253 points for the daily fixings of a year, and the price
+//! depends on the resolution of the grid like the discretely monitored barriers.
+//!
+//! A [`MultiAssetPayoff`] maps the paths of several prices, one per row, e.g. of a
+//! [`MultiGBM`](crate::stochastic::diffusion::multi_gbm::MultiGBM), to the payoff. The
+//! worst-of and best-of (rainbow) options pay on the minimum or the maximum of the
+//! performances S_i(t) / S_i(0), which is the Stulz (1982) option on the minimum or the
+//! maximum of two prices with equal initial prices.
+//!
+//! - Glasserman, P. (2003). Monte Carlo methods in financial engineering, section 6.4.
+//! - Beaglehole, D. R., Dybvig, P. H., & Zhou, G. (1997). Going to extremes: correcting
+//!   simulation bias in exotic option valuation.
+//! - Stulz, R. M. (1982). Options on the minimum or the maximum of two risky assets.
+
+use impl_new_derive::ImplNew;
+use ndarray::{s, Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  quant::{monte_carlo::MCEstimate, OptionType},
+  stochastic::{Sampling, SamplingVector},
+};
+
+/// Payoff of a path dependent option
+pub trait Payoff: Sync {
+  /// Payoff at maturity of the price path with the time step dt between its points
+  fn payoff(&self, path: ArrayView1<f64>, dt: f64) -> f64;
+}
+
+/// Payoff of an option on several underlyings
+pub trait MultiAssetPayoff: Sync {
+  /// Payoff at maturity of the price paths, one asset per row, with the time step dt
+  /// between their points
+  fn payoff(&self, paths: ArrayView2<f64>, dt: f64) -> f64;
+}
+
+impl<F> Payoff for F
+where
+  F: Fn(ArrayView1<f64>) -> f64 + Sync,
+{
+  fn payoff(&self, path: ArrayView1<f64>, _dt: f64) -> f64 {
+    self(path)
+  }
+}
+
+/// Payoff of a vanilla option at the price s
+fn intrinsic(option_type: OptionType, s: f64, k: f64) -> f64 {
+  match option_type {
+    OptionType::Call => (s - k).max(0.0),
+    OptionType::Put => (k - s).max(0.0),
+  }
+}
+
+/// Barrier type
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierKind {
+  /// Void once the price rises to the barrier
+  #[default]
+  UpAndOut,
+  /// Activated once the price rises to the barrier
+  UpAndIn,
+  /// Void once the price falls to the barrier
+  DownAndOut,
+  /// Activated once the price falls to the barrier
+  DownAndIn,
+}
+
+impl BarrierKind {
+  fn is_up(self) -> bool {
+    matches!(self, Self::UpAndOut | Self::UpAndIn)
+  }
+
+  fn is_in(self) -> bool {
+    matches!(self, Self::UpAndIn | Self::DownAndIn)
+  }
+}
+
+/// Knock-in or knock-out barrier option on a vanilla payoff
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct BarrierOption {
+  pub kind: BarrierKind,
+  /// Barrier level
+  pub barrier: f64,
+  /// Strike price
+  pub k: f64,
+  pub option_type: OptionType,
+  /// Volatility of the Brownian bridge correction for continuous monitoring, the barrier
+  /// is only monitored at the path points if None
+  pub sigma: Option<f64>,
+}
+
+impl BarrierOption {
+  /// Probability that the price does not reach the barrier given the path points
+  pub fn survival(&self, path: ArrayView1<f64>, dt: f64) -> f64 {
+    // Log-distance to the barrier, non-positive once it is reached
+    let distance = |s: f64| match self.kind.is_up() {
+      true => (self.barrier / s).ln(),
+      false => (s / self.barrier).ln(),
+    };
+
+    let mut survival = 1.0;
+    for window in path.windows(2) {
+      let (a, b) = (distance(window[0]), distance(window[1]));
+      if a <= 0.0 || b <= 0.0 {
+        return 0.0;
+      }
+      if let Some(sigma) = self.sigma {
+        survival *= 1.0 - (-2.0 * a * b / (sigma.powi(2) * dt)).exp();
+      }
+    }
+    survival
+  }
+}
+
+impl Payoff for BarrierOption {
+  fn payoff(&self, path: ArrayView1<f64>, dt: f64) -> f64 {
+    let vanilla = intrinsic(self.option_type, path[path.len() - 1], self.k);
+    if vanilla == 0.0 {
+      return 0.0;
+    }
+
+    let survival = self.survival(path, dt);
+    match self.kind.is_in() {
+      true => vanilla * (1.0 - survival),
+      false => vanilla * survival,
+    }
+  }
+}
+
+/// Average of an Asian option
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Averaging {
+  #[default]
+  Arithmetic,
+  Geometric,
+}
+
+/// Fixed strike Asian option on the average of the path points after the initial one
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct AsianOption {
+  pub averaging: Averaging,
+  /// Strike price
+  pub k: f64,
+  pub option_type: OptionType,
+}
+
+impl Payoff for AsianOption {
+  fn payoff(&self, path: ArrayView1<f64>, _dt: f64) -> f64 {
+    let fixings = path.slice(s![1..]);
+    let average = match self.averaging {
+      Averaging::Arithmetic => fixings.mean().unwrap(),
+      Averaging::Geometric => fixings.mapv(f64::ln).mean().unwrap().exp(),
+    };
+    intrinsic(self.option_type, average, self.k)
+  }
+}
+
+/// Lookback option on the maximum or minimum of the path points
+///
+/// With a fixed strike the call pays (max - K)+ and the put (K - min)+, with a floating
+/// strike the call pays S(t) - min and the put max - S(t).
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct LookbackOption {
+  pub option_type: OptionType,
+  /// Fixed strike price, floating strike if None
+  pub k: Option<f64>,
+}
+
+impl Payoff for LookbackOption {
+  fn payoff(&self, path: ArrayView1<f64>, _dt: f64) -> f64 {
+    let max = path.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    let min = path.fold(f64::INFINITY, |a, &b| a.min(b));
+    let last = path[path.len() - 1];
+
+    match (self.option_type, self.k) {
+      (OptionType::Call, Some(k)) => (max - k).max(0.0),
+      (OptionType::Put, Some(k)) => (k - min).max(0.0),
+      (OptionType::Call, None) => last - min,
+      (OptionType::Put, None) => max - last,
+    }
+  }
+}
+
+/// Range accrual (corridor) coupon, the share of the fixings inside the corridor
+/// lower <= S <= upper times the coupon
+///
+/// The fixings are the path points after the initial one. The price under a Black-Scholes
+/// model is the discounted coupon times the average of P(lower <= S(t_i) <= upper), which
+/// the fixings of a coarser grid only approximate.
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct RangeAccrual {
+  /// Lower bound of the corridor, unbounded below if None
+  pub lower: Option<f64>,
+  /// Upper bound of the corridor, unbounded above if None
+  pub upper: Option<f64>,
+  /// Payment if every fixing is inside the corridor, e.g. notional times the coupon rate
+  pub coupon: f64,
+}
+
+impl RangeAccrual {
+  /// Whether the price is inside the corridor
+  pub fn is_inside(&self, s: f64) -> bool {
+    self.lower.is_none_or(|lower| s >= lower) && self.upper.is_none_or(|upper| s <= upper)
+  }
+
+  /// Share of the fixings inside the corridor
+  pub fn accrual(&self, path: ArrayView1<f64>) -> f64 {
+    let fixings = path.slice(s![1..]);
+    let inside = fixings.iter().filter(|&&s| self.is_inside(s)).count();
+    inside as f64 / fixings.len() as f64
+  }
+}
+
+impl Payoff for RangeAccrual {
+  fn payoff(&self, path: ArrayView1<f64>, _dt: f64) -> f64 {
+    self.coupon * self.accrual(path)
+  }
+}
+
+/// Performance of a rainbow option
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rainbow {
+  /// Minimum of the performances
+  #[default]
+  WorstOf,
+  /// Maximum of the performances
+  BestOf,
+}
+
+/// Worst-of or best-of option on the performances S_i(t) / S_i(0)
+///
+/// The barrier is monitored at the path points on the same worst or best performance, e.g.
+/// the down-and-in worst-of put of the autocallables, which is knocked in once any of the
+/// assets falls to the barrier.
+#[derive(ImplNew, Debug, Clone, Copy)]
+pub struct RainbowOption {
+  pub rainbow: Rainbow,
+  /// Strike as a performance, e.g. 1.0 at the money
+  pub k: f64,
+  pub option_type: OptionType,
+  /// Barrier type and level as a performance, no barrier if None
+  pub barrier: Option<(BarrierKind, f64)>,
+}
+
+impl RainbowOption {
+  /// Worst or best performance at every path point
+  pub fn performance(&self, paths: ArrayView2<f64>) -> Array1<f64> {
+    let initial = paths.column(0);
+    paths
+      .axis_iter(Axis(1))
+      .map(|prices| {
+        let performances = prices.iter().zip(initial).map(|(&s, &s0)| s / s0);
+        match self.rainbow {
+          Rainbow::WorstOf => performances.fold(f64::INFINITY, f64::min),
+          Rainbow::BestOf => performances.fold(f64::NEG_INFINITY, f64::max),
+        }
+      })
+      .collect()
+  }
+}
+
+impl MultiAssetPayoff for RainbowOption {
+  fn payoff(&self, paths: ArrayView2<f64>, dt: f64) -> f64 {
+    let performance = self.performance(paths);
+    match self.barrier {
+      Some((kind, level)) => BarrierOption::new(kind, level, self.k, self.option_type, None)
+        .payoff(performance.view(), dt),
+      None => intrinsic(self.option_type, performance[performance.len() - 1], self.k),
+    }
+  }
+}
+
+/// Monte Carlo pricer of path dependent payoffs under the paths of a risk-neutral sampler
+#[derive(ImplNew)]
+pub struct ExoticMCPricer<S> {
+  /// Risk-neutral price process
+  pub sampler: S,
+  /// Risk-free rate
+  pub r: f64,
+  /// Maturity, the time horizon of the paths
+  pub t: f64,
+  /// Number of paths
+  pub m: usize,
+}
+
+impl<S: Sampling<f64>> ExoticMCPricer<S> {
+  /// Price of a payoff
+  pub fn price<P: Payoff>(&self, payoff: &P) -> MCEstimate {
+    self.price_all(&[payoff as &dyn Payoff])[0]
+  }
+
+  /// Prices of several payoffs on the same paths
+  pub fn price_all(&self, payoffs: &[&dyn Payoff]) -> Vec<MCEstimate> {
+    let values = (0..self.m)
+      .into_par_iter()
+      .map(|_| {
+        let path = self.sampler.sample();
+        let dt = self.t / (path.len() - 1) as f64;
+        payoffs
+          .iter()
+          .map(|payoff| payoff.payoff(path.view(), dt))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    let values = Array2::from_shape_fn((self.m, payoffs.len()), |(i, j)| values[i][j]);
+    self.estimates(values)
+  }
+
+  /// Price of a payoff from given price paths, one per row, e.g. the price component of
+  /// a stochastic volatility model
+  pub fn price_paths<P: Payoff + ?Sized>(&self, paths: ArrayView2<f64>, payoff: &P) -> MCEstimate {
+    let dt = self.t / (paths.ncols() - 1) as f64;
+    let values = paths
+      .axis_iter(Axis(0))
+      .into_par_iter()
+      .map(|path| payoff.payoff(path, dt))
+      .collect::<Vec<_>>();
+
+    self.estimates(Array2::from_shape_vec((values.len(), 1), values).unwrap())[0]
+  }
+}
+
+impl<S: SamplingVector<f64>> ExoticMCPricer<S> {
+  /// Prices of several payoffs on the same paths of a multi-asset sampler
+  pub fn price_multi_asset(&self, payoffs: &[&dyn MultiAssetPayoff]) -> Vec<MCEstimate> {
+    let values = (0..self.m)
+      .into_par_iter()
+      .map(|_| {
+        let paths = self.sampler.sample();
+        let dt = self.t / (paths.ncols() - 1) as f64;
+        payoffs
+          .iter()
+          .map(|payoff| payoff.payoff(paths.view(), dt))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    let values = Array2::from_shape_fn((self.m, payoffs.len()), |(i, j)| values[i][j]);
+    self.estimates(values)
+  }
+}
+
+impl<S> ExoticMCPricer<S> {
+  /// Discounted mean and standard error of every column of payoffs
+  fn estimates(&self, values: Array2<f64>) -> Vec<MCEstimate> {
+    let discount = (-self.r * self.t).exp();
+    let z = Normal::default().inverse_cdf(0.975);
+    let m = values.nrows();
+
+    values
+      .axis_iter(Axis(1))
+      .map(|column| {
+        let std_error = discount * (column.var(1.0) / m as f64).sqrt();
+        MCEstimate {
+          paths: m,
+          mean: discount * column.mean().unwrap(),
+          std_error,
+          half_width: z * std_error,
+        }
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray_rand::{rand_distr::StandardNormal, RandomExt};
+  use statrs::distribution::Continuous;
+
+  use super::*;
+  use crate::{
+    quant::{pricing::bsm::BSMPricer, r#trait::Pricer},
+    stochastic::{
+      diffusion::{gbm::GBM, multi_gbm::MultiGBM, scheme::Scheme},
+      noise::cgnsd::{CovarianceDecomposition, CGNSD},
+    },
+  };
+
+  const S: f64 = 100.0;
+  const R: f64 = 0.05;
+  const SIGMA: f64 = 0.2;
+  const T: f64 = 1.0;
+
+  fn pricer(n: usize, m: usize) -> ExoticMCPricer<GBM> {
+    ExoticMCPricer::new(
+      GBM::new(
+        R,
+        SIGMA,
+        n,
+        Some(S),
+        Some(T),
+        Some(Scheme::Milstein),
+        None,
+        None,
+        #[cfg(feature = "malliavin")]
+        None,
+      ),
+      R,
+      T,
+      m,
+    )
+  }
+
+  fn black_scholes(s: f64, k: f64, sigma: f64, r: f64, t: f64) -> (f64, f64) {
+    BSMPricer::new(
+      s,
+      sigma,
+      k,
+      r,
+      None,
+      None,
+      None,
+      Some(t),
+      None,
+      None,
+      OptionType::Call,
+      Default::default(),
+    )
+    .calculate_call_put()
+  }
+
+  fn assert_within(estimate: MCEstimate, expected: f64, bias: f64) {
+    assert!(
+      (estimate.mean - expected).abs() < 4.0 * estimate.std_error + bias,
+      "{:?} {}",
+      estimate,
+      expected
+    );
+  }
+
+  #[test]
+  fn down_and_out_call_with_bridge_correction() {
+    let (k, b) = (100.0, 90.0);
+    let out = BarrierOption::new(BarrierKind::DownAndOut, b, k, OptionType::Call, Some(SIGMA));
+    let into = BarrierOption::new(BarrierKind::DownAndIn, b, k, OptionType::Call, Some(SIGMA));
+    let discrete = BarrierOption::new(BarrierKind::DownAndOut, b, k, OptionType::Call, None);
+    let vanilla = |path: ArrayView1<f64>| (path[path.len() - 1] - k).max(0.0);
+
+    let prices = pricer(51, 100_000).price_all(&[&out, &into, &discrete, &vanilla]);
+
+    // Continuously monitored down-and-out call with B < K (Merton, 1973)
+    let lambda = (R + 0.5 * SIGMA.powi(2)) / SIGMA.powi(2);
+    let (call, _) = black_scholes(S, k, SIGMA, R, T);
+    let (mirror, _) = black_scholes(b * b / S, k, SIGMA, R, T);
+    let expected = call - (b / S).powf(2.0 * lambda - 2.0) * mirror;
+
+    assert_within(prices[0], expected, 0.05);
+    // The in-out parity holds path by path
+    assert!((prices[0].mean + prices[1].mean - prices[3].mean).abs() < 1e-9);
+    // Monitoring at 50 dates misses crossings and overprices the knock-out
+    assert!(prices[2].mean > prices[0].mean + 0.1);
+  }
+
+  #[test]
+  fn geometric_asian_matches_closed_form() {
+    let (k, n) = (100.0, 12);
+    let asian = AsianOption::new(Averaging::Geometric, k, OptionType::Call);
+    let arithmetic = AsianOption::new(Averaging::Arithmetic, k, OptionType::Call);
+    let prices = pricer(n + 1, 100_000).price_all(&[&asian, &arithmetic]);
+
+    // ln G is normal with the mean and the variance of the average of ln S(i T / n)
+    let dt = T / n as f64;
+    let mean = (R - 0.5 * SIGMA.powi(2)) * dt * (n + 1) as f64 / 2.0;
+    let variance = SIGMA.powi(2) * dt * ((n + 1) * (2 * n + 1)) as f64 / (6 * n) as f64;
+    let forward = S * (mean + 0.5 * variance).exp();
+    let (call, _) = black_scholes(forward * (-R * T).exp(), k, (variance / T).sqrt(), R, T);
+
+    assert_within(prices[0], call, 0.03);
+    // The arithmetic average dominates the geometric one
+    assert!(prices[1].mean > prices[0].mean);
+  }
+
+  #[test]
+  fn range_accrual_matches_the_fixing_probabilities() {
+    let corridor = RangeAccrual::new(Some(90.0), Some(115.0), 1.0);
+    let above = RangeAccrual::new(Some(100.0), None, 1.0);
+    let normal = Normal::default();
+    // P(S(t) <= x) under the risk-neutral Black-Scholes model
+    let below = |x: f64, t: f64| {
+      normal.cdf(((x / S).ln() - (R - 0.5 * SIGMA.powi(2)) * t) / (SIGMA * t.sqrt()))
+    };
+
+    let mut prices = Vec::new();
+    for fixings in [4, 252] {
+      let dt = T / fixings as f64;
+      let times = (1..=fixings).map(|i| i as f64 * dt).collect::<Vec<_>>();
+      let expected = |lower: f64, upper: f64| {
+        (-R * T).exp()
+          * times
+            .iter()
+            .map(|&t| below(upper, t) - below(lower, t))
+            .sum::<f64>()
+          / fixings as f64
+      };
+
+      // Exact lognormal paths, the fixing probabilities have no discretization bias
+      let z = Array2::<f64>::random((20_000, fixings), StandardNormal);
+      let mut paths = Array2::from_elem((20_000, fixings + 1), S);
+      for j in 1..=fixings {
+        let step = z
+          .column(j - 1)
+          .mapv(|z| ((R - 0.5 * SIGMA.powi(2)) * dt + SIGMA * dt.sqrt() * z).exp());
+        let next = &paths.column(j - 1) * &step;
+        paths.column_mut(j).assign(&next);
+      }
+
+      let mc = pricer(fixings + 1, 1);
+      let estimate = mc.price_paths(paths.view(), &corridor);
+      assert_within(estimate, expected(90.0, 115.0), 0.0);
+      assert_within(
+        mc.price_paths(paths.view(), &above),
+        expected(100.0, 1e9),
+        0.0,
+      );
+      prices.push(estimate.mean);
+    }
+    // The corridor is wider than the spread of the early fixings, a quarterly schedule
+    // has fewer of them and pays less than the daily one
+    assert!(prices[0] < prices[1]);
+
+    let path = ndarray::array![100.0, 95.0, 120.0, 90.0, 80.0];
+    assert_eq!(corridor.accrual(path.view()), 0.5);
+  }
+
+  #[test]
+  fn floating_lookback_call_matches_continuous_formula() {
+    let lookback = LookbackOption::new(OptionType::Call, None);
+    let n = 1001;
+    let price = pricer(n, 40_000).price(&lookback);
+
+    // Goldman, Sosin & Gatto (1979) with the running minimum at S
+    let normal = Normal::default();
+    let a1 = (R + 0.5 * SIGMA.powi(2)) * T.sqrt() / SIGMA;
+    let a2 = a1 - SIGMA * T.sqrt();
+    let expected = S * normal.cdf(a1)
+      - S * (-R * T).exp() * normal.cdf(a2)
+      - S * SIGMA.powi(2) / (2.0 * R)
+        * (normal.cdf(-a1) - (-R * T).exp() * normal.cdf(-a1 + 2.0 * R * T.sqrt() / SIGMA));
+
+    // Discrete monitoring overestimates the minimum by about 0.5826 sigma sqrt(dt) of it
+    // (Broadie, Glasserman & Kou, 1999)
+    let shift = 0.5826 * SIGMA * (T / (n - 1) as f64).sqrt() * S;
+    assert!(price.mean < expected);
+    assert_within(price, expected - shift, 0.15);
+
+    let paths = Array2::from_shape_fn((1, 3), |(_, j)| [100.0, 80.0, 110.0][j]);
+    let estimate = pricer(3, 1).price_paths(paths.view(), &lookback);
+    assert!((estimate.mean - 30.0 * (-R * T).exp()).abs() < 1e-12);
+  }
+
+  fn rainbow_pricer(sigma: [f64; 2], rho: f64, n: usize, m: usize) -> ExoticMCPricer<MultiGBM> {
+    ExoticMCPricer::new(
+      MultiGBM::new(
+        Array1::from_elem(2, R),
+        Array1::from(sigma.to_vec()),
+        Array1::from_elem(2, S),
+        n,
+        Some(T),
+        None,
+        CGNSD::new(
+          ndarray::array![[1.0, rho], [rho, 1.0]],
+          n - 1,
+          Some(T),
+          None,
+          CovarianceDecomposition::Cholesky,
+        ),
+      ),
+      R,
+      T,
+      m,
+    )
+  }
+
+  /// P(X <= a, Y <= b) of standard normals with the correlation rho, Simpson's rule on
+  /// the integral of phi(x) Phi((b - rho x) / sqrt(1 - rho^2)) over x <= a
+  fn bivariate_normal_cdf(a: f64, b: f64, rho: f64) -> f64 {
+    let normal = Normal::default();
+    let (lower, steps) = (-10.0, 4000);
+    if a <= lower {
+      return 0.0;
+    }
+    let h = (a - lower) / steps as f64;
+    let f = |x: f64| normal.pdf(x) * normal.cdf((b - rho * x) / (1.0 - rho * rho).sqrt());
+    let inner = (1..steps)
+      .map(|i| match i % 2 {
+        1 => 4.0 * f(lower + i as f64 * h),
+        _ => 2.0 * f(lower + i as f64 * h),
+      })
+      .sum::<f64>();
+    (f(lower) + inner + f(a)) * h / 3.0
+  }
+
+  #[test]
+  fn rainbow_options_match_stulz() {
+    let (k, sigma, rho) = (100.0, [0.2, 0.3], 0.5);
+    let options = [
+      (Rainbow::WorstOf, OptionType::Call),
+      (Rainbow::BestOf, OptionType::Call),
+      (Rainbow::WorstOf, OptionType::Put),
+      (Rainbow::BestOf, OptionType::Put),
+    ]
+    .map(|(rainbow, option_type)| RainbowOption::new(rainbow, k / S, option_type, None));
+    let payoffs = options
+      .iter()
+      .map(|option| option as &dyn MultiAssetPayoff)
+      .collect::<Vec<_>>();
+    let prices = rainbow_pricer(sigma, rho, 2, 100_000).price_multi_asset(&payoffs);
+
+    // Stulz (1982) with S_1 = S_2 = S, the puts by the parity with min and max of the prices
+    let [s1, s2] = sigma;
+    let m = bivariate_normal_cdf;
+    let vol = (s1 * s1 + s2 * s2 - 2.0 * rho * s1 * s2).sqrt();
+    let (rho1, rho2) = ((s1 - rho * s2) / vol, (s2 - rho * s1) / vol);
+    let d = 0.5 * vol * T.sqrt();
+    let y = |sigma: f64| ((S / k).ln() + (R + 0.5 * sigma * sigma) * T) / (sigma * T.sqrt());
+    let (y1, y2) = (y(s1), y(s2));
+    let discount = k * (-R * T).exp();
+
+    let min_call = S * m(y1, -d, -rho1) + S * m(y2, d - vol * T.sqrt(), -rho2)
+      - discount * m(y1 - s1 * T.sqrt(), y2 - s2 * T.sqrt(), rho);
+    let max_call = S * m(y1, d, rho1) + S * m(y2, -d + vol * T.sqrt(), rho2)
+      - discount * (1.0 - m(-y1 + s1 * T.sqrt(), -y2 + s2 * T.sqrt(), rho));
+    let normal = Normal::default();
+    let min = S * normal.cdf(-d) + S * normal.cdf(d - vol * T.sqrt());
+    let max = 2.0 * S - min;
+    let expected = [
+      min_call,
+      max_call,
+      discount - min + min_call,
+      discount - max + max_call,
+    ];
+
+    for (price, expected) in prices.into_iter().zip(expected) {
+      assert_within(price, expected / S, 1e-4);
+    }
+  }
+
+  #[test]
+  fn worst_of_barrier_in_out_parity() {
+    let option = |barrier| RainbowOption::new(Rainbow::WorstOf, 1.0, OptionType::Put, barrier);
+    let vanilla = option(None);
+    let into = option(Some((BarrierKind::DownAndIn, 0.7)));
+    let out = option(Some((BarrierKind::DownAndOut, 0.7)));
+    let prices =
+      rainbow_pricer([0.2, 0.3], 0.5, 53, 10_000).price_multi_asset(&[&vanilla, &into, &out]);
+
+    assert!((prices[1].mean + prices[2].mean - prices[0].mean).abs() < 1e-9);
+    assert!(prices[1].mean > 0.0 && prices[1].mean < prices[0].mean);
+
+    // Knocked in by the worst performance of the second asset
+    let paths = ndarray::array![[100.0, 105.0, 110.0], [50.0, 30.0, 40.0]];
+    assert_eq!(
+      into.performance(paths.view()),
+      ndarray::array![1.0, 0.6, 0.8]
+    );
+    assert!((into.payoff(paths.view(), 0.5) - 0.2).abs() < 1e-12);
+    assert_eq!(out.payoff(paths.view(), 0.5), 0.0);
+  }
+}
@@ -1,17 +1,42 @@
 use impl_new_derive::ImplNew;
-use ndarray::{s, Array1};
-
-use crate::quant::{
-  r#trait::{Pricer, Time},
-  OptionStyle, OptionType,
+use ndarray::{Array1, Array2};
+use plotly::Plot;
+use rayon::prelude::*;
+
+use crate::{
+  quant::{
+    greeks::{Bump, Greeks},
+    r#trait::{Pricer, Time},
+    volatility::american::american_implied_volatility,
+    OptionStyle, OptionType,
+  },
+  stochastic::catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
 };
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub enum FiniteDifferenceMethod {
   Explicit,
   Implicit,
   #[default]
   CrankNicolson,
+  /// Crank-Nicolson after [`RANNACHER_STEPS`] steps made of two implicit half steps,
+  /// which damp the oscillations of the non-smooth payoff in the Greeks (Rannacher, 1984)
+  Rannacher,
+}
+
+/// Crank-Nicolson steps replaced by implicit half steps in the Rannacher scheme
+pub const RANNACHER_STEPS: usize = 2;
+
+/// Price and Greeks read off the solution grid of the finite difference pricer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiniteDifferenceGreeks {
+  pub price: f64,
+  /// dV / dS by central differences of the last layer
+  pub delta: f64,
+  /// d^2V / dS^2 by central differences of the last layer
+  pub gamma: f64,
+  /// dV / dt per year, the difference of the last two layers
+  pub theta: f64,
 }
 
 #[derive(ImplNew)]
@@ -44,13 +69,46 @@ pub struct FiniteDifferencePricer {
 
 impl Pricer for FiniteDifferencePricer {
   /// Calculate the option price
-  #[must_use]
   fn calculate_price(&self) -> f64 {
-    match self.method {
-      FiniteDifferenceMethod::Explicit => self.explicit(),
-      FiniteDifferenceMethod::Implicit => self.implicit(),
-      FiniteDifferenceMethod::CrankNicolson => self.crank_nicolson(),
-    }
+    let (s_values, _, grid) = self.solution_grid();
+    let option_values = grid.row(grid.nrows() - 1).to_owned();
+    self.interpolate(&s_values, &option_values, self.s)
+  }
+
+  /// Greeks by repricing, the delta and the gamma from the grid
+  /// ([`FiniteDifferencePricer::price_and_greeks`]), which unlike the interpolated price
+  /// are smooth in the spot
+  fn greeks(&self) -> Option<Greeks> {
+    let tau = self.tau.unwrap_or(1.0);
+    let bumped = |h: Bump| FiniteDifferencePricer {
+      s: self.s + h.s,
+      v: self.v + h.v,
+      r: self.r + h.r,
+      tau: Some(tau + h.tau),
+      ..*self
+    };
+
+    Some(Greeks::bumped_with_spot(
+      Bump::steps(self.s, self.v, tau),
+      |h| bumped(h).calculate_price(),
+      |h| {
+        let greeks = bumped(h).price_and_greeks();
+        (greeks.delta, greeks.gamma)
+      },
+    ))
+  }
+
+  /// Implied volatility of the option of the style of the pricer by Brent's method
+  fn implied_volatility(&self, c_price: f64, option_type: OptionType) -> f64 {
+    american_implied_volatility(c_price, |v| {
+      FiniteDifferencePricer {
+        v,
+        option_type,
+        ..*self
+      }
+      .calculate_price()
+    })
+    .unwrap_or(f64::NAN)
   }
 }
 
@@ -69,15 +127,41 @@ impl Time for FiniteDifferencePricer {
 }
 
 impl FiniteDifferencePricer {
-  fn explicit(&self) -> f64 {
+  /// Full finite difference solution.
+  ///
+  /// Returns the price nodes, the time to maturity of each layer and the grid of
+  /// option values with one row per time layer (starting from the payoff at maturity).
+  pub fn solution_grid(&self) -> (Array1<f64>, Array1<f64>, Array2<f64>) {
+    let (dt, _, s_values, time_steps) = self.calculate_grid();
+    let tau_values = Array1::linspace(0.0, dt * time_steps as f64, time_steps + 1);
+
+    let grid = match self.method {
+      FiniteDifferenceMethod::Explicit => self.explicit(),
+      FiniteDifferenceMethod::Implicit => self.theta_scheme(1.0, 0),
+      FiniteDifferenceMethod::CrankNicolson => self.theta_scheme(0.5, 0),
+      FiniteDifferenceMethod::Rannacher => self.theta_scheme(0.5, RANNACHER_STEPS),
+    };
+
+    (s_values, tau_values, grid)
+  }
+
+  /// 3D surface plot of the solution grid
+  pub fn plot_grid(&self) -> Plot {
+    let (s_values, tau_values, grid) = self.solution_grid();
+    crate::plot::finite_difference_grid(&s_values, &tau_values, &grid)
+  }
+
+  fn explicit(&self) -> Array2<f64> {
     let (dt, ds, s_values, time_steps) = self.calculate_grid();
+    let mut grid = Array2::<f64>::zeros((time_steps + 1, self.s_n + 1));
     let mut option_values = Array1::<f64>::zeros(self.s_n + 1);
 
     for (i, &s_i) in s_values.iter().enumerate() {
       option_values[i] = self.payoff(s_i);
     }
+    grid.row_mut(0).assign(&option_values);
 
-    for _ in 0..time_steps {
+    for step in 0..time_steps {
       let mut new_option_values = option_values.clone();
 
       for i in 1..self.s_n {
@@ -98,114 +182,86 @@ impl FiniteDifferencePricer {
         }
       }
 
-      new_option_values[0] = self.boundary_condition(s_values[0], 0.0);
-      new_option_values[self.s_n] = self.boundary_condition(s_values[self.s_n], 0.0);
+      let elapsed = (step + 1) as f64 * dt;
+      new_option_values[0] = self.boundary_condition(s_values[0], elapsed);
+      new_option_values[self.s_n] = self.boundary_condition(s_values[self.s_n], elapsed);
 
       option_values = new_option_values;
+      grid.row_mut(step + 1).assign(&option_values);
     }
 
-    self.interpolate(&s_values, &option_values, self.s)
+    grid
   }
 
-  fn implicit(&self) -> f64 {
+  /// Implicit (theta = 1) and Crank-Nicolson (theta = 0.5) steps after the implicit
+  /// start-up steps
+  fn theta_scheme(&self, theta: f64, rannacher: usize) -> Array2<f64> {
     let (dt, ds, s_values, time_steps) = self.calculate_grid();
-
-    let mut a = Array1::<f64>::zeros(self.s_n - 1);
-    let mut b = Array1::<f64>::zeros(self.s_n - 1);
-    let mut c = Array1::<f64>::zeros(self.s_n - 1);
-
-    let mut option_values = Array1::<f64>::zeros(self.s_n + 1);
-    for (i, &s_i) in s_values.iter().enumerate() {
-      option_values[i] = self.payoff(s_i);
-    }
-
-    for _ in 0..time_steps {
-      for i in 1..self.s_n {
-        let s_i = s_values[i];
-        let sigma_sq = self.v.powi(2);
-
-        a[i - 1] = -0.5 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) - self.r * s_i / ds);
-        b[i - 1] = 1.0 + dt * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r);
-        c[i - 1] = -0.5 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r * s_i / ds);
-      }
-
-      let mut d = option_values.slice(s![1..self.s_n]).to_owned();
-
-      d[0] -= a[0] * self.boundary_condition(0.0, dt);
-      d[self.s_n - 2] -= c[self.s_n - 2] * self.boundary_condition(s_values[self.s_n], dt);
-
-      let new_option_values_inner = self.solve_tridiagonal(&a, &b, &c, &d);
-
-      for i in 1..self.s_n {
-        option_values[i] = new_option_values_inner[i - 1];
-
-        if let OptionStyle::American = self.option_style {
-          let intrinsic_value = self.payoff(s_values[i]);
-          option_values[i] = option_values[i].max(intrinsic_value);
-        }
-      }
-
-      option_values[0] = self.boundary_condition(0.0, dt);
-      option_values[self.s_n] = self.boundary_condition(s_values[self.s_n], dt);
+    let mut grid = Array2::<f64>::zeros((time_steps + 1, self.s_n + 1));
+    let sigma_sq = self.v.powi(2);
+
+    // Coefficients of V(i - 1), V(i) and V(i + 1) in dV / dtau
+    let lower = Array1::from_shape_fn(self.s_n - 1, |i| {
+      let s_i = s_values[i + 1];
+      0.5 * (sigma_sq * s_i.powi(2) / ds.powi(2) - self.r * s_i / ds)
+    });
+    let diagonal = Array1::from_shape_fn(self.s_n - 1, |i| {
+      -(sigma_sq * s_values[i + 1].powi(2) / ds.powi(2) + self.r)
+    });
+    let upper = Array1::from_shape_fn(self.s_n - 1, |i| {
+      let s_i = s_values[i + 1];
+      0.5 * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r * s_i / ds)
+    });
+    let scheme = ThetaScheme::new(lower, diagonal, upper, theta, dt, rannacher);
+
+    let intrinsic = s_values.mapv(|s_i| self.payoff(s_i));
+    let obstacle = match self.option_style {
+      OptionStyle::American => Some(&intrinsic),
+      OptionStyle::European => None,
+    };
+    let mut option_values = intrinsic.clone();
+    grid.row_mut(0).assign(&option_values);
+
+    for step in 0..time_steps {
+      let elapsed = (step + 1) as f64 * dt;
+      let boundary = (
+        self.boundary_condition(0.0, elapsed),
+        self.boundary_condition(s_values[self.s_n], elapsed),
+      );
+      scheme.advance(&mut option_values, step, boundary, obstacle);
+      grid.row_mut(step + 1).assign(&option_values);
     }
 
-    self.interpolate(&s_values, &option_values, self.s)
+    grid
   }
 
-  fn crank_nicolson(&self) -> f64 {
-    let (dt, ds, s_values, time_steps) = self.calculate_grid();
-
-    let mut a = Array1::<f64>::zeros(self.s_n - 1);
-    let mut b = Array1::<f64>::zeros(self.s_n - 1);
-    let mut c = Array1::<f64>::zeros(self.s_n - 1);
-
-    let mut option_values = Array1::<f64>::zeros(self.s_n + 1);
-    for (i, &s_i) in s_values.iter().enumerate() {
-      option_values[i] = self.payoff(s_i);
-    }
-
-    for _ in 0..time_steps {
-      for i in 1..self.s_n {
-        let s_i = s_values[i];
-        let sigma_sq = self.v.powi(2);
-
-        a[i - 1] = -0.25 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) - self.r * s_i / ds);
-        b[i - 1] = 1.0 + 0.5 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r);
-        c[i - 1] = -0.25 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r * s_i / ds);
-      }
-
-      let mut d = Array1::<f64>::zeros(self.s_n - 1);
-      for i in 1..self.s_n {
-        let s_i = s_values[i];
-        let sigma_sq = self.v.powi(2);
-
-        let a_past = 0.25 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) - self.r * s_i / ds);
-        let b_past = 1.0 - 0.5 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r);
-        let c_past = 0.25 * dt * (sigma_sq * s_i.powi(2) / ds.powi(2) + self.r * s_i / ds);
-
-        d[i - 1] =
-          a_past * option_values[i - 1] + b_past * option_values[i] + c_past * option_values[i + 1];
-      }
-
-      d[0] -= a[0] * self.boundary_condition(0.0, dt);
-      d[self.s_n - 2] -= c[self.s_n - 2] * self.boundary_condition(s_values[self.s_n], dt);
-
-      let new_option_values_inner = self.solve_tridiagonal(&a, &b, &c, &d);
-
-      for i in 1..self.s_n {
-        option_values[i] = new_option_values_inner[i - 1];
-
-        if let OptionStyle::American = self.option_style {
-          let intrinsic_value = self.payoff(s_values[i]);
-          option_values[i] = option_values[i].max(intrinsic_value);
-        }
-      }
-
-      option_values[0] = self.boundary_condition(0.0, dt);
-      option_values[self.s_n] = self.boundary_condition(s_values[self.s_n], dt);
+  /// Price with the delta, gamma and theta of the solution grid, without solving the PDE
+  /// again for bumped inputs
+  pub fn price_and_greeks(&self) -> FiniteDifferenceGreeks {
+    let (s_values, tau_values, grid) = self.solution_grid();
+    let last = grid.nrows() - 1;
+    let values = grid.row(last).to_owned();
+    let previous = grid.row(last - 1).to_owned();
+    let ds = s_values[1] - s_values[0];
+    let dt = tau_values[last] - tau_values[last - 1];
+
+    let n = s_values.len();
+    let delta = Array1::from_shape_fn(n, |i| {
+      let i = i.clamp(1, n - 2);
+      (values[i + 1] - values[i - 1]) / (2.0 * ds)
+    });
+    let gamma = Array1::from_shape_fn(n, |i| {
+      let i = i.clamp(1, n - 2);
+      (values[i + 1] - 2.0 * values[i] + values[i - 1]) / ds.powi(2)
+    });
+    let theta = (&previous - &values) / dt;
+
+    FiniteDifferenceGreeks {
+      price: self.interpolate(&s_values, &values, self.s),
+      delta: self.interpolate(&s_values, &delta, self.s),
+      gamma: self.interpolate(&s_values, &gamma, self.s),
+      theta: self.interpolate(&s_values, &theta, self.s),
     }
-
-    self.interpolate(&s_values, &option_values, self.s)
   }
 
   fn calculate_grid(&self) -> (f64, f64, Array1<f64>, usize) {
@@ -225,22 +281,17 @@ impl FiniteDifferencePricer {
     }
   }
 
+  /// Value at the edges of the price grid with the time to maturity tau of the layer, the
+  /// American put is exercised at S = 0
   fn boundary_condition(&self, s: f64, tau: f64) -> f64 {
+    let strike = match (self.option_style, self.option_type) {
+      (OptionStyle::American, OptionType::Put) => self.k,
+      _ => self.k * (-self.r * tau).exp(),
+    };
     match self.option_type {
-      OptionType::Call => {
-        if s == 0.0 {
-          0.0
-        } else {
-          s - self.k * (-self.r * (self.tau.unwrap_or(1.0) - tau)).exp()
-        }
-      }
-      OptionType::Put => {
-        if s == 0.0 {
-          self.k * (-self.r * (self.tau.unwrap_or(1.0) - tau)).exp()
-        } else {
-          0.0
-        }
-      }
+      OptionType::Call if s > 0.0 => s - strike,
+      OptionType::Put if s == 0.0 => strike,
+      _ => 0.0,
     }
   }
 
@@ -253,37 +304,310 @@ impl FiniteDifferencePricer {
     }
     0.0
   }
+}
+
+impl FiniteDifferencePricer {
+  /// Prices of the options on the strikes, the strike of the pricer is ignored
+  ///
+  /// The prices are homogeneous of degree 1 in (S, K), V(S, K) = K u(ln(S / K)) with u the
+  /// value of the option of unit strike in log-moneyness, so one solve of the PDE
+  ///
+  /// du / dtau = sigma^2 / 2 u_xx + (r - sigma^2 / 2) u_x - r u
+  ///
+  /// prices the whole strike grid, instead of a solve per strike like
+  /// [`Pricer::calculate_price`]. The coefficients are constant on the uniform log grid
+  /// with s_n steps, so the tridiagonal system is factorized once for all time steps.
+  pub fn prices(&self, strikes: &[f64]) -> Array1<f64> {
+    self.log_moneyness_prices(strikes, self.tau.unwrap_or(1.0))
+  }
 
-  fn solve_tridiagonal(
+  /// Prices on the grid of maturities (rows) and strikes (columns), one solve per maturity
+  /// in parallel, the strike and the maturity of the pricer are ignored
+  pub fn price_surface(&self, strikes: &Array1<f64>, taus: &Array1<f64>) -> Array2<f64> {
+    let strikes = strikes.to_vec();
+    let slices = taus
+      .to_vec()
+      .par_iter()
+      .map(|&tau| self.log_moneyness_prices(&strikes, tau))
+      .collect::<Vec<_>>();
+
+    Array2::from_shape_fn((taus.len(), strikes.len()), |(i, j)| slices[i][j])
+  }
+
+  fn log_moneyness_prices(&self, strikes: &[f64], tau: f64) -> Array1<f64> {
+    assert!(!strikes.is_empty(), "at least 1 strike is needed");
+    assert!(strikes.iter().all(|&k| k > 0.0), "strikes must be positive");
+    let (theta, rannacher) = match self.method {
+      FiniteDifferenceMethod::Explicit => (0.0, 0),
+      FiniteDifferenceMethod::Implicit => (1.0, 0),
+      FiniteDifferenceMethod::CrankNicolson => (0.5, 0),
+      FiniteDifferenceMethod::Rannacher => (0.5, RANNACHER_STEPS),
+    };
+
+    // Log-moneyness of the strikes with 5 standard deviations on both sides
+    let x = strikes
+      .iter()
+      .map(|&k| (self.s / k).ln())
+      .collect::<Vec<_>>();
+    let width = 5.0 * self.v * tau.sqrt();
+    let x_min = x.iter().copied().fold(f64::INFINITY, f64::min) - width;
+    let x_max = x.iter().copied().fold(f64::NEG_INFINITY, f64::max) + width;
+    let x_values = Array1::linspace(x_min, x_max, self.s_n + 1);
+    let dx = (x_max - x_min) / self.s_n as f64;
+    let dt = tau / self.t_n as f64;
+
+    let sigma_sq = self.v.powi(2);
+    let drift = self.r - 0.5 * sigma_sq;
+    let lower = 0.5 * sigma_sq / dx.powi(2) - 0.5 * drift / dx;
+    let diagonal = -sigma_sq / dx.powi(2) - self.r;
+    let upper = 0.5 * sigma_sq / dx.powi(2) + 0.5 * drift / dx;
+    let inner = self.s_n - 1;
+    let scheme = ThetaScheme::new(
+      Array1::from_elem(inner, lower),
+      Array1::from_elem(inner, diagonal),
+      Array1::from_elem(inner, upper),
+      theta,
+      dt,
+      rannacher,
+    );
+
+    let payoff = |x: f64| match self.option_type {
+      OptionType::Call => (x.exp() - 1.0).max(0.0),
+      OptionType::Put => (1.0 - x.exp()).max(0.0),
+    };
+    let boundary = |x: f64, elapsed: f64| {
+      let strike = match self.option_style {
+        OptionStyle::European => (-self.r * elapsed).exp(),
+        OptionStyle::American => match self.option_type {
+          OptionType::Call => (-self.r * elapsed).exp(),
+          OptionType::Put => 1.0,
+        },
+      };
+      match self.option_type {
+        OptionType::Call => (x.exp() - strike).max(0.0),
+        OptionType::Put => (strike - x.exp()).max(0.0),
+      }
+    };
+
+    let intrinsic = x_values.mapv(payoff);
+    let obstacle = match self.option_style {
+      OptionStyle::American => Some(&intrinsic),
+      OptionStyle::European => None,
+    };
+
+    let mut u = intrinsic.clone();
+    for step in 0..self.t_n {
+      let elapsed = (step + 1) as f64 * dt;
+      let boundary = (boundary(x_min, elapsed), boundary(x_max, elapsed));
+      scheme.advance(&mut u, step, boundary, obstacle);
+    }
+
+    strikes
+      .iter()
+      .zip(&x)
+      .map(|(&k, &x)| {
+        let price = k * self.interpolate(&x_values, &u, x);
+        // The linear interpolation of the concave intrinsic value in x is below it
+        match (self.option_style, self.option_type) {
+          (OptionStyle::American, OptionType::Call) => price.max(self.s - k),
+          (OptionStyle::American, OptionType::Put) => price.max(k - self.s),
+          (OptionStyle::European, _) => price,
+        }
+      })
+      .collect()
+  }
+}
+
+/// Theta-scheme of dV / dtau = l V(i - 1) + d V(i) + u V(i + 1) on the inner nodes, with
+/// the systems of the steps factorized once
+struct ThetaScheme {
+  lower: Array1<f64>,
+  diagonal: Array1<f64>,
+  upper: Array1<f64>,
+  theta: f64,
+  dt: f64,
+  /// Number of first steps made of two implicit half steps
+  rannacher: usize,
+  system: Tridiagonal,
+  start_up: Tridiagonal,
+}
+
+impl ThetaScheme {
+  fn new(
+    lower: Array1<f64>,
+    diagonal: Array1<f64>,
+    upper: Array1<f64>,
+    theta: f64,
+    dt: f64,
+    rannacher: usize,
+  ) -> Self {
+    let factorize = |theta: f64, dt: f64| {
+      Tridiagonal::new(
+        &(-theta * dt * &lower),
+        &(1.0 - theta * dt * &diagonal),
+        &(-theta * dt * &upper),
+      )
+    };
+    let system = factorize(theta, dt);
+    let start_up = factorize(1.0, 0.5 * dt);
+
+    Self {
+      lower,
+      diagonal,
+      upper,
+      theta,
+      dt,
+      rannacher,
+      system,
+      start_up,
+    }
+  }
+
+  /// Advance the values over the step, with the boundary values at the end of the step and
+  /// projected on the obstacle of American options
+  fn advance(
     &self,
-    a: &Array1<f64>,
-    b: &Array1<f64>,
-    c: &Array1<f64>,
-    d: &Array1<f64>,
-  ) -> Array1<f64> {
-    let n = d.len();
+    values: &mut Array1<f64>,
+    step: usize,
+    boundary: (f64, f64),
+    obstacle: Option<&Array1<f64>>,
+  ) {
+    match step < self.rannacher {
+      true => {
+        for _ in 0..2 {
+          self.theta_step(
+            values,
+            &self.start_up,
+            1.0,
+            0.5 * self.dt,
+            boundary,
+            obstacle,
+          );
+        }
+      }
+      false => self.theta_step(
+        values,
+        &self.system,
+        self.theta,
+        self.dt,
+        boundary,
+        obstacle,
+      ),
+    }
+  }
+
+  fn theta_step(
+    &self,
+    values: &mut Array1<f64>,
+    system: &Tridiagonal,
+    theta: f64,
+    dt: f64,
+    boundary: (f64, f64),
+    obstacle: Option<&Array1<f64>>,
+  ) {
+    let n = values.len() - 1;
+    let mut d = Array1::from_shape_fn(n - 1, |i| {
+      values[i + 1]
+        + (1.0 - theta)
+          * dt
+          * (self.lower[i] * values[i]
+            + self.diagonal[i] * values[i + 1]
+            + self.upper[i] * values[i + 2])
+    });
+    d[0] += theta * dt * self.lower[0] * boundary.0;
+    d[n - 2] += theta * dt * self.upper[n - 2] * boundary.1;
+
+    let solution = system.solve(&d);
+    for i in 1..n {
+      values[i] = match obstacle {
+        Some(obstacle) => solution[i - 1].max(obstacle[i]),
+        None => solution[i - 1],
+      };
+    }
+    values[0] = boundary.0;
+    values[n] = boundary.1;
+  }
+}
+
+/// LU factorization of a tridiagonal matrix for the Thomas algorithm
+struct Tridiagonal {
+  /// Sub-diagonal
+  a: Array1<f64>,
+  /// Pivots of the elimination
+  pivots: Array1<f64>,
+  /// Super-diagonal divided by the pivots
+  c_star: Array1<f64>,
+}
+
+impl Tridiagonal {
+  /// Factorize the matrix of the sub-diagonal a, the diagonal b and the super-diagonal c
+  fn new(a: &Array1<f64>, b: &Array1<f64>, c: &Array1<f64>) -> Self {
+    let n = b.len();
+    let mut pivots = Array1::<f64>::zeros(n);
     let mut c_star = Array1::<f64>::zeros(n);
-    let mut d_star = Array1::<f64>::zeros(n);
 
+    pivots[0] = b[0];
     c_star[0] = c[0] / b[0];
-    d_star[0] = d[0] / b[0];
-
     for i in 1..n {
-      let m = b[i] - a[i] * c_star[i - 1];
-      c_star[i] = c[i] / m;
-      d_star[i] = (d[i] - a[i] * d_star[i - 1]) / m;
+      pivots[i] = b[i] - a[i] * c_star[i - 1];
+      c_star[i] = c[i] / pivots[i];
     }
 
+    Self {
+      a: a.clone(),
+      pivots,
+      c_star,
+    }
+  }
+
+  /// Solution x of the system with the right-hand side d
+  fn solve(&self, d: &Array1<f64>) -> Array1<f64> {
+    let n = d.len();
     let mut x = Array1::<f64>::zeros(n);
-    x[n - 1] = d_star[n - 1];
+
+    x[0] = d[0] / self.pivots[0];
+    for i in 1..n {
+      x[i] = (d[i] - self.a[i] * x[i - 1]) / self.pivots[i];
+    }
     for i in (0..n - 1).rev() {
-      x[i] = d_star[i] - c_star[i] * x[i + 1];
+      x[i] -= self.c_star[i] * x[i + 1];
     }
 
     x
   }
 }
 
+impl ProcessInfo for FiniteDifferencePricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FiniteDifferencePricer",
+    title: "Finite difference pricer",
+    path: "quant::pricing::finitie_difference::FiniteDifferencePricer",
+    kind: ModelKind::Pricer,
+    description: "European and American options by finite differences of the Black-Scholes PDE",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::V,
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::integer("t_n", "Number of time steps", Interval::COUNT, 1000.0),
+      ParameterInfo::integer("s_n", "Number of price steps", Interval::STEPS, 600.0),
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::EXPIRATION,
+      ParameterInfo::choice("option_style", "Option style", &["American", "European"]),
+      ParameterInfo::choice("option_type", "Option type", &["Call", "Put"]),
+      ParameterInfo::choice(
+        "method",
+        "Time stepping",
+        &["Explicit", "Implicit", "CrankNicolson", "Rannacher"],
+      ),
+    ],
+    references: &[
+      "Wilmott, P., Howison, S., & Dewynne, J. (1995). The Mathematics of Financial Derivatives.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -292,6 +616,7 @@ mod tests {
   };
 
   use super::{FiniteDifferenceMethod, FiniteDifferencePricer};
+  use ndarray::Array1;
 
   fn atm_pricer(style: OptionStyle, r#type: OptionType, method: FiniteDifferenceMethod) -> f64 {
     let pricer = FiniteDifferencePricer::new(
@@ -431,4 +756,160 @@ mod tests {
     );
     println!("Put: {}", put);
   }
+
+  #[test]
+  fn eu_crank_nicolson_grid_plot() {
+    let pricer = FiniteDifferencePricer::new(
+      S0,
+      0.1,
+      K,
+      0.05,
+      500,
+      100,
+      Some(1.0),
+      None,
+      None,
+      OptionStyle::European,
+      OptionType::Call,
+      FiniteDifferenceMethod::CrankNicolson,
+    );
+
+    pricer.plot_grid().show();
+  }
+
+  #[test]
+  fn strike_grid_in_log_space() {
+    use ndarray::array;
+
+    use crate::quant::pricing::bsm::{BSMCoc, BSMPricer};
+
+    let strikes = [70.0, 85.0, 100.0, 115.0, 130.0];
+    let pricer = |style: OptionStyle, r#type: OptionType| {
+      FiniteDifferencePricer::new(
+        S0,
+        0.2,
+        K,
+        0.05,
+        400,
+        800,
+        Some(1.0),
+        None,
+        None,
+        style,
+        r#type,
+        FiniteDifferenceMethod::CrankNicolson,
+      )
+    };
+
+    let calls = pricer(OptionStyle::European, OptionType::Call).prices(&strikes);
+    let puts = pricer(OptionStyle::European, OptionType::Put).prices(&strikes);
+    for (j, &k) in strikes.iter().enumerate() {
+      let (call, put) = BSMPricer::new(
+        S0,
+        0.2,
+        k,
+        0.05,
+        None,
+        None,
+        None,
+        Some(1.0),
+        None,
+        None,
+        OptionType::Call,
+        BSMCoc::BSM1973,
+      )
+      .calculate_call_put();
+      assert!(
+        (calls[j] - call).abs() < 5e-3,
+        "{} {} {}",
+        k,
+        calls[j],
+        call
+      );
+      assert!((puts[j] - put).abs() < 5e-3, "{} {} {}", k, puts[j], put);
+    }
+
+    // American puts of the strike grid and of one solve per strike against a converged
+    // solve per strike
+    let american = pricer(OptionStyle::American, OptionType::Put);
+    let puts = american.prices(&strikes);
+    for (j, &k) in strikes.iter().enumerate() {
+      let single = |s_n: usize, t_n: usize| {
+        FiniteDifferencePricer {
+          k,
+          s_n,
+          t_n,
+          ..american
+        }
+        .calculate_price()
+      };
+      let (single, reference) = (single(300, 2000), single(1200, 4000));
+      assert!(puts[j] >= (k - S0).max(0.0));
+      for price in [puts[j], single] {
+        assert!(
+          (price - reference).abs() < 0.02,
+          "{} {} {}",
+          k,
+          price,
+          reference
+        );
+      }
+    }
+
+    let surface = american.price_surface(&Array1::from(strikes.to_vec()), &array![0.5, 1.0]);
+    assert_eq!(surface.row(1), puts);
+    assert!(surface
+      .row(0)
+      .iter()
+      .zip(surface.row(1))
+      .all(|(short, long)| *short <= long + 1e-6));
+  }
+
+  #[test]
+  fn rannacher_greeks_from_the_grid() {
+    use crate::quant::pricing::bsm::{BSMCoc, BSMPricer};
+
+    let bsm = BSMPricer::new(
+      S0,
+      0.2,
+      K,
+      0.05,
+      None,
+      None,
+      None,
+      Some(0.25),
+      None,
+      None,
+      OptionType::Call,
+      BSMCoc::BSM1973,
+    );
+
+    // Coarse time steps, the Crank-Nicolson oscillations of the kink spoil the gamma
+    let greeks = |method| {
+      FiniteDifferencePricer::new(
+        S0,
+        0.2,
+        K,
+        0.05,
+        10,
+        600,
+        Some(0.25),
+        None,
+        None,
+        OptionStyle::European,
+        OptionType::Call,
+        method,
+      )
+      .price_and_greeks()
+    };
+
+    let rannacher = greeks(FiniteDifferenceMethod::Rannacher);
+    assert!((rannacher.price - bsm.calculate_call_put().0).abs() < 1e-2);
+    assert!((rannacher.delta - bsm.delta()).abs() < 1e-3);
+    assert!((rannacher.gamma - bsm.gamma()).abs() < 1e-3);
+    assert!((rannacher.theta - bsm.theta()).abs() < 0.5);
+
+    let crank_nicolson = greeks(FiniteDifferenceMethod::CrankNicolson);
+    assert!((crank_nicolson.gamma - bsm.gamma()).abs() > 0.1);
+  }
 }
@@ -2,14 +2,35 @@ use std::f64::consts::FRAC_1_PI;
 
 use impl_new_derive::ImplNew;
 use implied_vol::implied_black_volatility;
+use ndarray::{Array1, Array2, ArrayView1};
 use num_complex::Complex64;
 use quadrature::double_exponential;
-
-use crate::quant::{
-  r#trait::{Pricer, Time},
-  OptionType,
+use rayon::prelude::*;
+
+use super::fft::{FourierMethod, FourierPricer};
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    greeks::{Bump, Greeks},
+    r#trait::{CharacteristicFn, Pricer, Time},
+    OptionType,
+  },
+  stochastic::catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
 };
 
+/// (n, l) of the COS expansion of [`HestonPricer::price_surface`], the number of terms and
+/// the truncation range in standard deviations
+pub(crate) const COS: (usize, f64) = (256, 10.0);
+
+/// Index j of the risk-neutral probabilities P_j in the Heston formula
+/// C = S e^{-q tau} P_1 - K e^{-r tau} P_2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Probability {
+  P1,
+  P2,
+}
+
 #[derive(ImplNew, Clone)]
 pub struct HestonPricer {
   /// Stock price
@@ -46,24 +67,30 @@ impl Pricer for HestonPricer {
   fn calculate_call_put(&self) -> (f64, f64) {
     let tau = self.tau().unwrap_or(1.0);
 
-    let call = self.s * (-self.q.unwrap_or(0.0) * tau).exp() * self.p(1, tau)
-      - self.k * (-self.r * tau).exp() * self.p(2, tau);
+    let call = self.s * (-self.q.unwrap_or(0.0) * tau).exp() * self.p(Probability::P1, tau)
+      - self.k * (-self.r * tau).exp() * self.p(Probability::P2, tau);
     let put = call + self.k * (-self.r * tau).exp() - self.s * (-self.q.unwrap_or(0.0) * tau).exp();
 
     (call, put)
   }
 
-  /// Derivatives
+  /// Derivatives of the call with respect to (v0, theta, rho, kappa, sigma), the exact
+  /// derivatives of its COS expansion, see [`HestonPricer::cos_jacobian`], the put has the
+  /// same ones
   fn derivatives(&self) -> Vec<f64> {
-    let tau = self.tau().unwrap_or(1.0);
+    let (n, l) = COS;
+    self.cos_jacobian(&[self.k], n, l).row(0).to_vec()
+  }
 
-    vec![
-      self.dC_dv0(tau),
-      self.dC_dtheta(tau),
-      self.dC_drho(tau),
-      self.dC_dkappa(tau),
-      self.dC_dsigma(tau),
-    ]
+  /// Check the model parameters
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.s > 0.0 && self.k > 0.0, "s and k must be positive")?;
+    ensure(self.v0 >= 0.0, "v0 must be non-negative")?;
+    ensure(self.sigma > 0.0, "sigma must be positive")?;
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
   }
 
   fn implied_volatility(&self, c_price: f64, option_type: OptionType) -> f64 {
@@ -75,6 +102,25 @@ impl Pricer for HestonPricer {
       option_type == OptionType::Call,
     )
   }
+
+  /// Greeks of the call by central differences of the prices, the vega and the vomma in
+  /// the spot volatility √v0
+  fn greeks(&self) -> Option<Greeks> {
+    let tau = self.tau().unwrap_or(1.0);
+    let sigma0 = self.v0.sqrt();
+
+    Some(Greeks::bumped(Bump::steps(self.s, sigma0, tau), |h| {
+      HestonPricer {
+        s: self.s + h.s,
+        v0: (sigma0 + h.v).powi(2),
+        r: self.r + h.r,
+        tau: Some(tau + h.tau),
+        ..self.clone()
+      }
+      .calculate_call_put()
+      .0
+    }))
+  }
 }
 
 impl Time for HestonPricer {
@@ -92,53 +138,54 @@ impl Time for HestonPricer {
 }
 
 impl HestonPricer {
-  pub(self) fn u(&self, j: u8) -> f64 {
+  pub(self) fn u(&self, j: Probability) -> f64 {
     match j {
-      1 => 0.5,
-      2 => -0.5,
-      _ => panic!("Invalid j"),
+      Probability::P1 => 0.5,
+      Probability::P2 => -0.5,
     }
   }
 
-  pub(self) fn b(&self, j: u8) -> f64 {
+  pub(self) fn b(&self, j: Probability) -> f64 {
     match j {
-      1 => self.kappa + self.lambda.unwrap_or(1.0) - self.rho * self.sigma,
-      2 => self.kappa + self.lambda.unwrap_or(1.0),
-      _ => panic!("Invalid j"),
+      Probability::P1 => self.kappa + self.lambda.unwrap_or(1.0) - self.rho * self.sigma,
+      Probability::P2 => self.kappa + self.lambda.unwrap_or(1.0),
     }
   }
 
-  pub(self) fn d(&self, j: u8, phi: f64) -> Complex64 {
+  pub(self) fn d(&self, j: Probability, phi: f64) -> Complex64 {
     ((self.b(j) - self.rho * self.sigma * phi * Complex64::i()).powi(2)
       - self.sigma.powi(2) * (2.0 * Complex64::i() * self.u(j) * phi - phi.powi(2)))
     .sqrt()
   }
 
-  pub(self) fn g(&self, j: u8, phi: f64) -> Complex64 {
-    (self.b(j) - self.rho * self.sigma * Complex64::i() * phi + self.d(j, phi))
-      / (self.b(j) - self.rho * self.sigma * Complex64::i() * phi - self.d(j, phi))
+  /// g of the formulation of Albrecher et al. (2007), which keeps the complex logarithm of C
+  /// on its principal branch for long maturities and a large volatility of the variance
+  pub(self) fn g(&self, j: Probability, phi: f64) -> Complex64 {
+    (self.b(j) - self.rho * self.sigma * Complex64::i() * phi - self.d(j, phi))
+      / (self.b(j) - self.rho * self.sigma * Complex64::i() * phi + self.d(j, phi))
   }
 
-  pub(self) fn C(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
+  pub(self) fn C(&self, j: Probability, phi: f64, tau: f64) -> Complex64 {
     (self.r - self.q.unwrap_or(0.0)) * Complex64::i() * phi * tau
       + (self.kappa * self.theta / self.sigma.powi(2))
-        * ((self.b(j) - self.rho * self.sigma * Complex64::i() * phi + self.d(j, phi)) * tau
+        * ((self.b(j) - self.rho * self.sigma * Complex64::i() * phi - self.d(j, phi)) * tau
           - 2.0
-            * ((1.0 - self.g(j, phi) * (self.d(j, phi) * tau).exp()) / (1.0 - self.g(j, phi))).ln())
+            * ((1.0 - self.g(j, phi) * (-self.d(j, phi) * tau).exp()) / (1.0 - self.g(j, phi)))
+              .ln())
   }
 
-  pub(self) fn D(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
-    ((self.b(j) - self.rho * self.sigma * Complex64::i() * phi + self.d(j, phi))
+  pub(self) fn D(&self, j: Probability, phi: f64, tau: f64) -> Complex64 {
+    ((self.b(j) - self.rho * self.sigma * Complex64::i() * phi - self.d(j, phi))
       / self.sigma.powi(2))
-      * ((1.0 - (self.d(j, phi) * tau).exp())
-        / (1.0 - self.g(j, phi) * (self.d(j, phi) * tau).exp()))
+      * ((1.0 - (-self.d(j, phi) * tau).exp())
+        / (1.0 - self.g(j, phi) * (-self.d(j, phi) * tau).exp()))
   }
 
-  pub(self) fn f(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
+  pub(self) fn f(&self, j: Probability, phi: f64, tau: f64) -> Complex64 {
     (self.C(j, phi, tau) + self.D(j, phi, tau) * self.v0 + Complex64::i() * phi * self.s.ln()).exp()
   }
 
-  pub(self) fn re(&self, j: u8, tau: f64) -> impl Fn(f64) -> f64 {
+  pub(self) fn re(&self, j: Probability, tau: f64) -> impl Fn(f64) -> f64 {
     let self_ = self.clone();
     move |phi: f64| -> f64 {
       (self_.f(j, phi, tau) * (-Complex64::i() * phi * self_.k.ln()).exp() / (Complex64::i() * phi))
@@ -146,92 +193,312 @@ impl HestonPricer {
     }
   }
 
-  pub(self) fn p(&self, j: u8, tau: f64) -> f64 {
-    0.5 + FRAC_1_PI * double_exponential::integrate(self.re(j, tau), 0.00001, 50.0, 10e-6).integral
+  pub(self) fn p(&self, j: Probability, tau: f64) -> f64 {
+    0.5 + FRAC_1_PI * double_exponential::integrate(self.re(j, tau), 0.0, 200.0, 1e-8).integral
   }
 
-  /// Partial derivative of the C function with respect to parameters
-  /// https://www.sciencedirect.com/science/article/abs/pii/S0377221717304460
+  /// dP_j / dv0, differentiating the characteristic function f_j = exp(C_j + D_j v0 + i phi ln S)
+  pub(self) fn dp_dv0(&self, j: Probability, tau: f64) -> f64 {
+    let integrand = |phi: f64| -> f64 {
+      (self.D(j, phi, tau) * self.f(j, phi, tau) * (-Complex64::i() * phi * self.k.ln()).exp()
+        / (Complex64::i() * phi))
+        .re
+    };
 
-  /// Partial derivative of the C function with respect to the v0 parameter
-  pub(crate) fn dC_dv0(&self, tau: f64) -> f64 {
-    (-self.A(tau) / self.v0).re
+    FRAC_1_PI * double_exponential::integrate(integrand, 0.0, 200.0, 1e-8).integral
   }
 
-  /// Partial derivative of the C function with respect to the theta parameter
-  pub(crate) fn dC_dtheta(&self, tau: f64) -> f64 {
-    ((2.0 * self.kappa / self.sigma.powi(2)) * self.D_(tau)
-      - self.kappa * self.rho * tau * Complex64::i() * self.u(1) / self.sigma)
-      .re
+  /// Delta of the call, dC/dS = e^{-q tau} P_1
+  pub fn delta(&self) -> f64 {
+    let tau = self.tau().unwrap_or(1.0);
+    (-self.q.unwrap_or(0.0) * tau).exp() * self.p(Probability::P1, tau)
   }
 
-  /// Partial derivative of the C function with respect to the rho parameter
-  pub(crate) fn dC_drho(&self, tau: f64) -> f64 {
-    (-self.kappa * self.theta * tau * Complex64::i() * self.u(1) / self.sigma).re
-  }
+  /// Sensitivity of the call to the initial variance dC/dv0, the put has the same sensitivity
+  pub fn vega_v0(&self) -> f64 {
+    let tau = self.tau().unwrap_or(1.0);
 
-  /// Partial derivative of the C function with respect to the kappa parameter
-  pub(crate) fn dC_dkappa(&self, tau: f64) -> f64 {
-    (2.0 * self.theta * self.D_(tau) / self.sigma.powi(2)
-      + ((2.0 * self.kappa * self.theta) / self.sigma.powi(2) * self.B(tau)) * self.dB_dkappa(tau)
-      - (self.theta * self.rho * tau * Complex64::i() * self.u(1) / self.sigma))
-      .re
+    self.s * (-self.q.unwrap_or(0.0) * tau).exp() * self.dp_dv0(Probability::P1, tau)
+      - self.k * (-self.r * tau).exp() * self.dp_dv0(Probability::P2, tau)
   }
 
-  /// Partial derivative of the C function with respect to the sigma parameter
-  pub(crate) fn dC_dsigma(&self, tau: f64) -> f64 {
-    ((-4.0 * self.kappa * self.theta / self.sigma.powi(3)) * self.D_(tau)
-      + ((2.0 * self.kappa * self.theta) / (self.sigma.powi(2) * self.d_())) * self.dd_dsigma()
-      + self.kappa * self.theta * self.rho * tau * Complex64::i() * self.u(1) / self.sigma.powi(2))
-    .re
+  /// Call and put by the Fourier-cosine expansion (COS) of Fang & Oosterlee (2008) with n
+  /// terms on the truncation range of l standard deviations around the mean of the
+  /// log-return, e.g. n = 256 and l = 10, instead of the quadrature of the Heston formula
+  pub fn price_cos(&self, n: usize, l: f64) -> (f64, f64) {
+    let (calls, puts) = self.prices_cos(&[self.k], n, l);
+    (calls[0], puts[0])
   }
 
-  pub(self) fn xi(&self) -> Complex64 {
-    self.kappa - self.sigma * self.rho * Complex64::i() * self.u(1)
+  /// Calls and puts at the strikes by the COS expansion, the characteristic function is
+  /// evaluated once for all strikes, e.g. for a slice of a calibration
+  pub fn prices_cos(&self, strikes: &[f64], n: usize, l: f64) -> (Array1<f64>, Array1<f64>) {
+    self.cos_slice(strikes, self.tau().unwrap_or(1.0), n, l)
   }
 
-  pub(self) fn d_(&self) -> Complex64 {
-    (self.xi().powi(2) + self.sigma.powi(2) * (self.u(1).powi(2) + Complex64::i() * self.u(1)))
-      .sqrt()
+  /// (call, put) on the grid of maturities (rows) and strikes (columns), the strike and the
+  /// maturity of the pricer are ignored
+  ///
+  /// Every maturity is priced by the COS expansion with one evaluation of the
+  /// characteristic function for all strikes, the maturities in parallel, instead of a
+  /// quadrature per strike like [`Pricer::calculate_call_put`].
+  pub fn price_surface(&self, strikes: &Array1<f64>, taus: &Array1<f64>) -> Array2<(f64, f64)> {
+    let strikes = strikes.to_vec();
+    let (n, l) = COS;
+    let slices = taus
+      .to_vec()
+      .par_iter()
+      .map(|&tau| self.cos_slice(&strikes, tau, n, l))
+      .collect::<Vec<_>>();
+
+    Array2::from_shape_fn((taus.len(), strikes.len()), |(i, j)| {
+      (slices[i].0[j], slices[i].1[j])
+    })
   }
 
-  pub(self) fn dd_dsigma(&self) -> Complex64 {
-    (self.sigma * (self.u(1) + Complex64::i() * self.u(1))) / self.d_()
+  fn cos_slice(&self, strikes: &[f64], tau: f64, n: usize, l: f64) -> (Array1<f64>, Array1<f64>) {
+    FourierPricer::new(
+      self.clone(),
+      self.s,
+      self.r,
+      self.q,
+      tau,
+      Some(FourierMethod::Cos { n, l }),
+    )
+    .calculate_calls_puts(strikes)
   }
 
-  pub(self) fn A1(&self, tau: f64) -> Complex64 {
-    (self.u(1).powi(2) + Complex64::i() * self.u(1)) * (self.d_() * tau / 2.0).sinh()
+  /// Derivatives of the COS prices at the strikes with respect to
+  /// (v0, theta, rho, kappa, sigma), one row per strike, the same for calls and puts
+  ///
+  /// The expansion is linear in the characteristic function, so its closed-form gradient
+  /// gives the exact gradient of the prices with the truncation range held fixed, at the
+  /// cost of one more pass over the n terms for all strikes.
+  pub fn cos_jacobian(&self, strikes: &[f64], n: usize, l: f64) -> Array2<f64> {
+    let (weights, gradients) = self.cos_gradient_terms(strikes, n, l);
+    weights.dot(&gradients)
   }
 
-  pub(self) fn A2(&self, tau: f64) -> Complex64 {
-    (self.d_() / self.v0) * (self.d_() * tau / 2.0).cosh()
-      + (self.xi() / self.v0) * (self.d_() * tau / 2.0).sinh()
+  /// Adjoint gradient sum_k a_k dP_k / d(v0, theta, rho, kappa, sigma) of the COS prices
+  /// P_k weighted by a, e.g. J^T r of the residuals r of a calibration
+  ///
+  /// The weights are pulled back to the terms of the expansion first, W^T a, so the
+  /// gradient never forms the Jacobian of the strikes.
+  pub fn cos_adjoint_gradient(&self, strikes: &[f64], a: &[f64], n: usize, l: f64) -> [f64; 5] {
+    assert_eq!(strikes.len(), a.len(), "one weight per strike");
+    let (weights, gradients) = self.cos_gradient_terms(strikes, n, l);
+    let adjoint = weights.t().dot(&ArrayView1::from(a));
+    let gradient = gradients.t().dot(&adjoint);
+
+    [
+      gradient[0],
+      gradient[1],
+      gradient[2],
+      gradient[3],
+      gradient[4],
+    ]
   }
 
-  pub(self) fn A(&self, tau: f64) -> Complex64 {
-    self.A1(tau) / self.A2(tau)
-  }
+  /// Payoff weights W of the COS expansion (strikes x terms) and the gradients of its
+  /// coefficients Re(phi(u_j) e^(iu_j x)) (terms x parameters)
+  fn cos_gradient_terms(&self, strikes: &[f64], n: usize, l: f64) -> (Array2<f64>, Array2<f64>) {
+    let tau = self.tau().unwrap_or(1.0);
+    let (u, phase, weights) = FourierPricer::new(
+      self.clone(),
+      self.s,
+      self.r,
+      self.q,
+      tau,
+      Some(FourierMethod::Cos { n, l }),
+    )
+    .cos_terms(strikes, n, l);
+
+    let mut gradients = Array2::zeros((n, 5));
+    for (j, mut row) in gradients.rows_mut().into_iter().enumerate() {
+      let gradient = self.cf_gradient(Complex64::new(u[j], 0.0), tau);
+      for (g, dcf) in row.iter_mut().zip(gradient) {
+        *g = (dcf * phase[j]).re;
+      }
+    }
 
-  pub(self) fn D_(&self, tau: f64) -> Complex64 {
-    (self.d_() / self.v0).ln() + (self.kappa - self.d_() / 2.0) * tau
-      - (((self.d_() + self.xi()) / (2.0 * self.v0))
-        + ((self.d_() - self.xi()) / (2.0 * self.v0)) * (-self.d_() * tau).exp())
-      .ln()
+    (weights, gradients)
   }
 
-  pub(self) fn B(&self, tau: f64) -> Complex64 {
-    (self.d_() * (self.kappa * tau / 2.0).exp()) / (self.v0 * self.A2(tau))
+  /// Gradient of the characteristic function [`CharacteristicFn::cf`] with respect to
+  /// (v0, theta, rho, kappa, sigma), through the mean reversion b = kappa + lambda and the
+  /// long-run variance kappa theta / b of the pricing measure
+  fn cf_gradient(&self, u: Complex64, t: f64) -> [Complex64; 5] {
+    let lambda = self.lambda.unwrap_or(1.0);
+    let b = self.kappa + lambda;
+    let ((c, d), gradient) =
+      heston_exponent_gradient(u, t, b, self.kappa * self.theta / b, self.rho, self.sigma);
+    let cf = (c + d * self.v0).exp();
+    let [db, dtheta, drho, dsigma] = gradient.map(|(dc, dd)| (dc + dd * self.v0) * cf);
+
+    [
+      d * cf,
+      dtheta * self.kappa / b,
+      drho,
+      db + dtheta * self.theta * lambda / b.powi(2),
+      dsigma,
+    ]
   }
+}
 
-  pub(self) fn dB_dkappa(&self, tau: f64) -> Complex64 {
-    (self.d_() * tau * (self.kappa * tau / 2.0).exp()) / (2.0 * self.v0 * self.A2(tau))
+/// C and D of the Heston characteristic function exp(C + D v0) of
+/// X = ln(S(tau) / S) - (r - q) tau, in the form of Albrecher et al. (2007) without the
+/// branch cut of the logarithm
+///
+/// - Albrecher, H., Mayer, P., Schoutens, W., & Tistaert, J. (2007). The little Heston trap.
+pub(crate) fn heston_exponent(
+  u: Complex64,
+  tau: f64,
+  kappa: f64,
+  theta: f64,
+  rho: f64,
+  sigma: f64,
+) -> (Complex64, Complex64) {
+  let i = Complex64::i();
+  let sigma2 = sigma.powi(2);
+  let xi = kappa - rho * sigma * i * u;
+  let d = (xi * xi + sigma2 * (u * u + i * u)).sqrt();
+  let g = (xi - d) / (xi + d);
+  let e = (-d * tau).exp();
+
+  let c = kappa * theta / sigma2 * ((xi - d) * tau - 2.0 * ((1.0 - g * e) / (1.0 - g)).ln());
+  let d = (xi - d) / sigma2 * (1.0 - e) / (1.0 - g * e);
+
+  (c, d)
+}
+
+/// (C, D) of [`heston_exponent`] and their derivatives with respect to
+/// (kappa, theta, rho, sigma), by the chain rule through xi, d, g and e^(-d tau)
+pub(crate) fn heston_exponent_gradient(
+  u: Complex64,
+  tau: f64,
+  kappa: f64,
+  theta: f64,
+  rho: f64,
+  sigma: f64,
+) -> ((Complex64, Complex64), [(Complex64, Complex64); 4]) {
+  let i = Complex64::i();
+  let sigma2 = sigma.powi(2);
+  let xi = kappa - rho * sigma * i * u;
+  let w = u * u + i * u;
+  let d = (xi * xi + sigma2 * w).sqrt();
+  let g = (xi - d) / (xi + d);
+  let e = (-d * tau).exp();
+  let log = ((1.0 - g * e) / (1.0 - g)).ln();
+  let h = (1.0 - e) / (1.0 - g * e);
+  let a = kappa * theta / sigma2;
+
+  let dxi = [
+    Complex64::new(1.0, 0.0),
+    Complex64::new(0.0, 0.0),
+    -sigma * i * u,
+    -rho * i * u,
+  ];
+  let da = [theta / sigma2, kappa / sigma2, 0.0, -2.0 * a / sigma];
+  let dsigma2 = [0.0, 0.0, 0.0, 2.0 * sigma];
+
+  let gradient = std::array::from_fn(|p| {
+    let dd = (xi * dxi[p] + 0.5 * dsigma2[p] * w) / d;
+    let dg = 2.0 * (d * dxi[p] - xi * dd) / (xi + d).powi(2);
+    let de = -tau * e * dd;
+    let dlog = dg / (1.0 - g) - (dg * e + g * de) / (1.0 - g * e);
+    let dh = (-de * (1.0 - g * e) + (1.0 - e) * (dg * e + g * de)) / (1.0 - g * e).powi(2);
+
+    let dc = da[p] * ((xi - d) * tau - 2.0 * log) + a * ((dxi[p] - dd) * tau - 2.0 * dlog);
+    let dd = ((dxi[p] - dd) - dsigma2[p] / sigma2 * (xi - d)) / sigma2 * h + (xi - d) / sigma2 * dh;
+    (dc, dd)
+  });
+
+  (
+    (a * ((xi - d) * tau - 2.0 * log), (xi - d) / sigma2 * h),
+    gradient,
+  )
+}
+
+impl CharacteristicFn for HestonPricer {
+  /// Characteristic function of ln(S(t) / S) - (r - q) t, the mean reversion is
+  /// kappa + lambda under the pricing measure like in the Heston formula
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    let b = self.kappa + self.lambda.unwrap_or(1.0);
+    let (c, d) = heston_exponent(u, t, b, self.kappa * self.theta / b, self.rho, self.sigma);
+
+    (c + d * self.v0).exp()
   }
 }
 
+impl ProcessInfo for HestonPricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "HestonPricer",
+    title: "Heston semi-analytical pricer",
+    path: "quant::pricing::heston::HestonPricer",
+    kind: ModelKind::Pricer,
+    description: "European options under the Heston model by Fourier inversion of the characteristic function",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::real("v0", "Initial variance", Interval::NON_NEGATIVE, 0.04),
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::Q,
+      ParameterInfo::RHO,
+      ParameterInfo::real("kappa", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("theta", "Long-run variance", Interval::POSITIVE, 0.04),
+      ParameterInfo::real("sigma", "Volatility of the variance", Interval::POSITIVE, 0.3),
+      ParameterInfo::real("lambda", "Market price of volatility risk, 1 if None", Interval::REAL, 0.0).optional(),
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::component("expiry", "Expiration date").optional(),
+    ],
+    references: &[
+      "Heston, S. L. (1993). A closed-form solution for options with stochastic volatility with applications to bond and currency options.",
+      "Fang, F., & Oosterlee, C. W. (2008). A novel pricing method for European options based on Fourier-cosine series expansions.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn heston_low_variance_is_black_scholes() {
+    // With a nearly constant variance of 0.1^2 over three months the integrands are still
+    // about e^(-3) at u = 50, the price is the Black-Scholes one
+    let heston = HestonPricer::new(
+      100.0,
+      0.01,
+      100.0,
+      0.03,
+      None,
+      0.0,
+      1.0,
+      0.01,
+      1e-4,
+      Some(0.0),
+      Some(0.25),
+      None,
+      None,
+    );
+    let bsm = crate::quant::pricing::bsm::BSMPricer::new(
+      100.0,
+      0.1,
+      100.0,
+      0.03,
+      None,
+      None,
+      None,
+      Some(0.25),
+      None,
+      None,
+      OptionType::Call,
+      crate::quant::pricing::bsm::BSMCoc::BSM1973,
+    );
+    assert!((heston.calculate_call_put().0 - bsm.calculate_call_put().0).abs() < 1e-4);
+  }
+
   #[test]
   fn heston_single_price() {
     let heston = HestonPricer::new(
@@ -276,4 +543,171 @@ mod tests {
     let iv = heston.implied_volatility(call, OptionType::Call);
     println!("Implied Volatility: {}", iv);
   }
+
+  #[test]
+  fn heston_cos_matches_quadrature() {
+    let strikes = Array1::linspace(60.0, 140.0, 41);
+    for tau in [0.1, 0.5, 2.0] {
+      let heston = |k: f64| {
+        HestonPricer::new(
+          100.0,
+          0.05,
+          k,
+          0.03,
+          Some(0.02),
+          -0.8,
+          5.0,
+          0.05,
+          0.5,
+          Some(0.0),
+          Some(tau),
+          None,
+          None,
+        )
+      };
+
+      let start = std::time::Instant::now();
+      let quadrature = strikes
+        .iter()
+        .map(|&k| heston(k).calculate_call_put())
+        .collect::<Vec<_>>();
+      let quadrature_time = start.elapsed();
+
+      let start = std::time::Instant::now();
+      let (calls, puts) = heston(100.0).prices_cos(strikes.as_slice().unwrap(), 256, 10.0);
+      let cos_time = start.elapsed();
+      println!(
+        "tau = {}: quadrature {:?}, COS {:?} for {} strikes",
+        tau,
+        quadrature_time,
+        cos_time,
+        strikes.len()
+      );
+
+      for (j, &(call, put)) in quadrature.iter().enumerate() {
+        assert!(
+          (calls[j] - call).abs() < 1e-4,
+          "{} {} {} {}",
+          tau,
+          strikes[j],
+          calls[j],
+          call
+        );
+        assert!((puts[j] - put).abs() < 1e-4);
+      }
+
+      // The single strike pricer and the truncation parameters
+      let (call, _) = heston(90.0).price_cos(256, 10.0);
+      assert!((call - heston(90.0).calculate_call_put().0).abs() < 1e-4);
+      let (coarse, _) = heston(90.0).price_cos(16, 10.0);
+      assert!((coarse - call).abs() > (call - heston(90.0).price_cos(128, 12.0).0).abs());
+    }
+  }
+
+  #[test]
+  fn heston_price_surface() {
+    let heston = |k: f64, tau: f64| {
+      HestonPricer::new(
+        100.0,
+        0.04,
+        k,
+        0.02,
+        None,
+        -0.7,
+        2.0,
+        0.06,
+        0.6,
+        Some(0.0),
+        Some(tau),
+        None,
+        None,
+      )
+    };
+    let strikes = Array1::linspace(70.0, 130.0, 25);
+    let taus = Array1::from_vec(vec![0.1, 0.25, 0.5, 1.0, 2.0, 5.0]);
+
+    let start = std::time::Instant::now();
+    let surface = heston(100.0, 1.0).price_surface(&strikes, &taus);
+    let surface_time = start.elapsed();
+
+    let start = std::time::Instant::now();
+    for (i, &tau) in taus.iter().enumerate() {
+      for (j, &k) in strikes.iter().enumerate() {
+        let (call, put) = heston(k, tau).calculate_call_put();
+        assert!((surface[[i, j]].0 - call).abs() < 1e-4, "{} {}", tau, k);
+        assert!((surface[[i, j]].1 - put).abs() < 1e-4, "{} {}", tau, k);
+      }
+    }
+    println!(
+      "{} prices: surface {:?}, quadrature per strike {:?}",
+      surface.len(),
+      surface_time,
+      start.elapsed()
+    );
+  }
+
+  #[test]
+  fn heston_cos_jacobian_matches_bumped_prices() {
+    let strikes = [70.0, 90.0, 100.0, 110.0, 140.0];
+    let weights = [0.3, -1.0, 2.0, 0.5, -0.2];
+    for lambda in [None, Some(0.0)] {
+      let heston = |params: [f64; 5]| {
+        HestonPricer::new(
+          100.0,
+          params[0],
+          100.0,
+          0.03,
+          Some(0.01),
+          params[2],
+          params[3],
+          params[1],
+          params[4],
+          lambda,
+          Some(0.75),
+          None,
+          None,
+        )
+      };
+      let params = [0.05, 0.04, -0.7, 2.0, 0.6];
+      // The bumps also move the truncation range, which the gradient holds fixed, on a
+      // wide range the prices no longer depend on it
+      let (n, l) = (256, 20.0);
+      let jacobian = heston(params).cos_jacobian(&strikes, n, l);
+
+      for p in 0..5 {
+        let h = 1e-5;
+        let [up, down] = [h, -h].map(|bump| {
+          let mut bumped = params;
+          bumped[p] += bump;
+          heston(bumped).prices_cos(&strikes, n, l).0
+        });
+        for k in 0..strikes.len() {
+          let bumped = (up[k] - down[k]) / (2.0 * h);
+          assert!(
+            (jacobian[[k, p]] - bumped).abs() < 1e-5,
+            "{:?} {} {} {} {}",
+            lambda,
+            p,
+            strikes[k],
+            jacobian[[k, p]],
+            bumped
+          );
+        }
+      }
+
+      // The adjoint gradient is the weighted sum of the rows without forming them
+      let gradient = heston(params).cos_adjoint_gradient(&strikes, &weights, n, l);
+      let expected = jacobian.t().dot(&Array1::from_vec(weights.to_vec()));
+      for p in 0..5 {
+        assert!((gradient[p] - expected[p]).abs() < 1e-10);
+      }
+
+      // The single strike derivatives of the pricer
+      let derivatives = heston(params).derivatives();
+      let jacobian = heston(params).cos_jacobian(&strikes, COS.0, COS.1);
+      for p in 0..5 {
+        assert!((derivatives[p] - jacobian[[2, p]]).abs() < 1e-12);
+      }
+    }
+  }
 }
@@ -0,0 +1,362 @@
+//! American and Bermudan options by least-squares Monte Carlo.
+//!
+//! The Longstaff-Schwartz algorithm goes backward over the exercise dates of simulated
+//! price paths. At every date the discounted realized cash flows of the in-the-money
+//! paths are regressed on basis functions of the price, and a path is exercised where
+//! the payoff exceeds the fitted continuation value. The regression only decides the
+//! exercise, the price is the mean of the realized cash flows, so it is a (slightly low
+//! biased) estimate of the price of the fitted exercise policy.
+//!
+//! Any simulator of the price works: [`Sampling`] processes such as the GBM,
+//! [`Sampling2D`] processes whose first component is the price such as the Heston and
+//! Bates models, and antithetic path pairs of the [`GaussianDriven`] ones.
+//!
+//! - Longstaff, F. A., & Schwartz, E. S. (2001). Valuing American options by simulation:
+//!   a simple least-squares approach.
+
+use impl_new_derive::ImplNew;
+use nalgebra::{DMatrix, DVector};
+use ndarray::{Array1, Array2, ArrayView2, Axis};
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  quant::monte_carlo::MCEstimate,
+  stochastic::{variance_reduction::GaussianDriven, Sampling, Sampling2D},
+};
+
+/// Polynomial basis of the regression of the continuation value on x = S / S(0)
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+  /// 1, x, x^2, ...
+  #[default]
+  Monomial,
+  /// Weighted Laguerre polynomials e^(-x / 2) L_k(x) of Longstaff & Schwartz (2001)
+  Laguerre,
+  /// Probabilists' Hermite polynomials He_k(x)
+  Hermite,
+}
+
+impl Basis {
+  /// The first degree + 1 basis functions at x
+  pub fn values(&self, x: f64, degree: usize) -> Vec<f64> {
+    let mut values = Vec::with_capacity(degree + 1);
+    values.push(1.0);
+    if degree == 0 {
+      return values;
+    }
+
+    match self {
+      Self::Monomial => {
+        for k in 1..=degree {
+          values.push(values[k - 1] * x);
+        }
+      }
+      Self::Laguerre => {
+        values.push(1.0 - x);
+        for k in 1..degree {
+          let kf = k as f64;
+          values.push(((2.0 * kf + 1.0 - x) * values[k] - kf * values[k - 1]) / (kf + 1.0));
+        }
+        let weight = (-0.5 * x).exp();
+        values.iter_mut().for_each(|value| *value *= weight);
+      }
+      Self::Hermite => {
+        values.push(x);
+        for k in 1..degree {
+          values.push(x * values[k] - k as f64 * values[k - 1]);
+        }
+      }
+    }
+
+    values
+  }
+}
+
+/// Path of a simulator carrying the price of the underlying
+pub trait PricePath {
+  /// Price component of the path
+  fn into_price(self) -> Array1<f64>;
+}
+
+impl PricePath for Array1<f64> {
+  fn into_price(self) -> Array1<f64> {
+    self
+  }
+}
+
+impl PricePath for [Array1<f64>; 2] {
+  /// The price is the first component, e.g. of the Heston and Bates models
+  fn into_price(self) -> Array1<f64> {
+    let [price, _] = self;
+    price
+  }
+}
+
+/// Least-squares Monte Carlo pricer of options with early exercise
+#[derive(ImplNew)]
+pub struct LsmPricer<P>
+where
+  P: Fn(f64) -> f64 + Sync,
+{
+  /// Payoff of the exercise at the price of the underlying
+  pub payoff: P,
+  /// Risk-free rate
+  pub r: f64,
+  /// Maturity, the time horizon of the simulated paths
+  pub t: f64,
+  /// Basis of the regression
+  pub basis: Basis,
+  /// Degree of the basis, the number of regressors is degree + 1
+  pub degree: usize,
+  /// Indices of the exercise dates on the time grid of the paths, every point after the
+  /// initial one (American) if None. The maturity is always an exercise date.
+  pub exercise: Option<Vec<usize>>,
+}
+
+impl<P> LsmPricer<P>
+where
+  P: Fn(f64) -> f64 + Sync,
+{
+  /// Price from m paths of a one-dimensional simulator of the price
+  pub fn price<S: Sampling<f64>>(&self, sampler: &S, m: usize) -> MCEstimate {
+    let paths = stack((0..m).into_par_iter().map(|_| sampler.sample()).collect());
+    self.estimate(paths.view(), false)
+  }
+
+  /// Price from m paths of a two-dimensional simulator whose first component is the price
+  pub fn price_2d<S: Sampling2D<f64>>(&self, sampler: &S, m: usize) -> MCEstimate {
+    let paths = stack(
+      (0..m)
+        .into_par_iter()
+        .map(|_| sampler.sample().into_price())
+        .collect(),
+    );
+    self.estimate(paths.view(), false)
+  }
+
+  /// Price from m / 2 antithetic path pairs driven by Z and -Z.
+  ///
+  /// Both paths of a pair enter the regression, the standard error is that of the pair
+  /// averages.
+  pub fn price_antithetic<S>(&self, sampler: &S, m: usize) -> MCEstimate
+  where
+    S: GaussianDriven<f64>,
+    S::Path: PricePath,
+  {
+    let paths = stack(
+      (0..m.div_ceil(2))
+        .into_par_iter()
+        .flat_map_iter(|_| {
+          let (path, antithetic) = sampler.sample_antithetic();
+          [path.into_price(), antithetic.into_price()]
+        })
+        .collect(),
+    );
+    self.estimate(paths.view(), true)
+  }
+
+  /// Price from given price paths, one per row, e.g. observed or bootstrapped ones
+  pub fn price_paths(&self, paths: ArrayView2<f64>) -> MCEstimate {
+    self.estimate(paths, false)
+  }
+
+  /// Sorted exercise dates within the n points of the paths, ending at the maturity
+  fn dates(&self, n: usize) -> Vec<usize> {
+    let mut dates = match &self.exercise {
+      Some(dates) => dates.iter().copied().filter(|&i| i > 0 && i < n).collect(),
+      None => (1..n).collect::<Vec<_>>(),
+    };
+    dates.push(n - 1);
+    dates.sort_unstable();
+    dates.dedup();
+    dates
+  }
+
+  /// Longstaff-Schwartz backward induction, consecutive rows are antithetic pairs if
+  /// `pairs`
+  fn estimate(&self, paths: ArrayView2<f64>, pairs: bool) -> MCEstimate {
+    let (m, n) = paths.dim();
+    assert!(n >= 2, "paths must have at least 2 points");
+    assert!(m > self.degree + 1, "more paths than regressors are needed");
+
+    let dt = self.t / (n - 1) as f64;
+    let s0 = paths[[0, 0]];
+    let dates = self.dates(n);
+
+    // Cash flow of every path discounted to the current date
+    let mut time = n - 1;
+    let mut value = paths.column(time).mapv(&self.payoff);
+
+    for &date in dates.iter().rev().skip(1) {
+      value *= (-self.r * (time - date) as f64 * dt).exp();
+      time = date;
+
+      let exercise = paths.column(date).mapv(&self.payoff);
+      let itm = (0..m).filter(|&i| exercise[i] > 0.0).collect::<Vec<_>>();
+      if itm.len() <= self.degree + 1 {
+        continue;
+      }
+
+      let regressors = itm
+        .iter()
+        .map(|&i| self.basis.values(paths[[i, date]] / s0, self.degree))
+        .collect::<Vec<_>>();
+      let x = DMatrix::from_fn(itm.len(), self.degree + 1, |row, col| regressors[row][col]);
+      let y = DVector::from_iterator(itm.len(), itm.iter().map(|&i| value[i]));
+      let beta = match x.clone().svd(true, true).solve(&y, 1e-12) {
+        Ok(beta) => beta,
+        Err(_) => continue,
+      };
+      let continuation = x * beta;
+
+      for (row, &i) in itm.iter().enumerate() {
+        if exercise[i] > continuation[row] {
+          value[i] = exercise[i];
+        }
+      }
+    }
+    value *= (-self.r * time as f64 * dt).exp();
+
+    let samples = match pairs {
+      true => value
+        .exact_chunks(2)
+        .into_iter()
+        .map(|pair| pair.mean().unwrap())
+        .collect::<Array1<f64>>(),
+      false => value,
+    };
+    let mean = samples.mean().unwrap();
+    let std_error = (samples.var(1.0) / samples.len() as f64).sqrt();
+
+    // Exercising at once is worth the payoff of the initial price
+    let immediate = (self.payoff)(s0);
+    match immediate > mean {
+      true => MCEstimate {
+        paths: m,
+        mean: immediate,
+        std_error: 0.0,
+        half_width: 0.0,
+      },
+      false => MCEstimate {
+        paths: m,
+        mean,
+        std_error,
+        half_width: Normal::default().inverse_cdf(0.975) * std_error,
+      },
+    }
+  }
+}
+
+/// Paths of equal length as the rows of a matrix
+fn stack(paths: Vec<Array1<f64>>) -> Array2<f64> {
+  let n = paths[0].len();
+  let mut xs = Array2::zeros((paths.len(), n));
+  xs.axis_iter_mut(Axis(0))
+    .into_par_iter()
+    .zip(paths.par_iter())
+    .for_each(|(mut x, path)| x.assign(path));
+  xs
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stochastic::{
+    diffusion::{gbm::GBM, scheme::Scheme},
+    noise::cgns::CGNS,
+    volatility::{heston::Heston, HestonPow},
+  };
+
+  fn gbm(s0: f64, r: f64, sigma: f64, n: usize, t: f64) -> GBM {
+    GBM::new(
+      r,
+      sigma,
+      n,
+      Some(s0),
+      Some(t),
+      Some(Scheme::Milstein),
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    )
+  }
+
+  #[test]
+  fn american_put_matches_longstaff_schwartz() {
+    // Table 1 of Longstaff & Schwartz (2001), finite difference value 4.478
+    let (k, r, t) = (40.0, 0.06, 1.0);
+    let underlying = gbm(36.0, r, 0.2, 51, t);
+
+    for basis in [Basis::Laguerre, Basis::Monomial, Basis::Hermite] {
+      let lsm = LsmPricer::new(|s: f64| (k - s).max(0.0), r, t, basis, 3, None);
+      let price = lsm.price_antithetic(&underlying, 100_000);
+      assert!((price.mean - 4.478).abs() < 0.04, "{:?} {:?}", basis, price);
+    }
+  }
+
+  #[test]
+  fn antithetic_lowers_standard_error() {
+    let (k, r, t) = (100.0, 0.05, 1.0);
+    let underlying = gbm(100.0, r, 0.3, 51, t);
+    let lsm = LsmPricer::new(|s: f64| (k - s).max(0.0), r, t, Basis::Laguerre, 3, None);
+
+    let plain = lsm.price(&underlying, 40_000);
+    let antithetic = lsm.price_antithetic(&underlying, 40_000);
+    assert!((plain.mean - antithetic.mean).abs() < 3.0 * plain.std_error);
+    assert!(antithetic.std_error < 0.8 * plain.std_error);
+  }
+
+  #[test]
+  fn heston_bermudan_put_exceeds_european() {
+    let (k, r, t, n) = (100.0, 0.05, 1.0, 101);
+    let heston = Heston::new(
+      Some(100.0),
+      Some(0.04),
+      2.0,
+      0.04,
+      0.3,
+      -0.7,
+      r,
+      n,
+      Some(t),
+      HestonPow::Sqrt,
+      None,
+      None,
+      None,
+      CGNS::new(-0.7, n - 1, Some(t), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let put = |s: f64| (k - s).max(0.0);
+    let bermudan = LsmPricer::new(put, r, t, Basis::Laguerre, 3, Some(vec![25, 50, 75]));
+    let european = LsmPricer::new(put, r, t, Basis::Laguerre, 3, Some(vec![]));
+
+    let paths = stack(
+      (0..50_000)
+        .into_par_iter()
+        .map(|_| Sampling2D::sample(&heston).into_price())
+        .collect(),
+    );
+    let (bermudan, european) = (
+      bermudan.price_paths(paths.view()),
+      european.price_paths(paths.view()),
+    );
+
+    // Same paths, so the early exercise premium is not blurred by the noise
+    let premium = bermudan.mean - european.mean;
+    assert!(
+      premium > 0.05 && premium < 1.0,
+      "{:?} {:?}",
+      bermudan,
+      european
+    );
+    assert!(
+      bermudan.mean < 2.0 * european.mean,
+      "{:?} {:?}",
+      bermudan,
+      european
+    );
+  }
+}
@@ -1,8 +1,12 @@
 use impl_new_derive::ImplNew;
+use num_complex::Complex64;
 
-use crate::quant::{
-  r#trait::{Pricer, Time},
-  OptionType,
+use crate::{
+  quant::{
+    r#trait::{CharacteristicFn, Pricer, Time},
+    OptionType,
+  },
+  stochastic::catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
 };
 
 use super::bsm::{BSMCoc, BSMPricer};
@@ -44,7 +48,6 @@ pub struct Merton1976Pricer {
 
 impl Pricer for Merton1976Pricer {
   /// Calculate the option price
-  #[must_use]
   fn calculate_call_put(&self) -> (f64, f64) {
     let mut bsm = BSMPricer::new(
       self.s,
@@ -67,17 +70,17 @@ impl Pricer for Merton1976Pricer {
     let delta = || -> f64 { (self.v.powi(2) * self.gamma / self.lambda).sqrt() };
     let z = || -> f64 { (self.v.powi(2) - self.lambda * delta().powi(2)).sqrt() };
     let sigma =
-      |i: usize, tau: f64| -> f64 { ((z().powi(2) + delta().powi(2)) * i as f64 / tau).sqrt() };
+      |i: usize, tau: f64| -> f64 { (z().powi(2) + delta().powi(2) * i as f64 / tau).sqrt() };
     let tau = self.tau.unwrap();
 
     for i in 0..self.m {
       bsm.v = sigma(i, self.tau.unwrap());
-      let f: usize = (1..=i).product();
+      let f: f64 = (1..=i).map(|j| j as f64).product();
       let num = (-self.lambda * tau).exp() * (self.lambda * tau).powi(i as i32);
 
       let (c, p) = bsm.calculate_call_put();
-      call += c * num / f as f64;
-      put += p * num / f as f64;
+      call += c * num / f;
+      put += p * num / f;
     }
 
     (call, put)
@@ -97,3 +100,101 @@ impl Time for Merton1976Pricer {
     self.expiration.unwrap()
   }
 }
+
+impl CharacteristicFn for Merton1976Pricer {
+  /// Characteristic function of ln(S(t) / S) - b t, a diffusion with variance
+  /// v^2 (1 - gamma) and lognormal jumps with E[e^J] = 1 and log-size variance
+  /// gamma v^2 / lambda like in the Poisson series of the prices
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    let convexity = Complex64::i() * u + u * u;
+    let diffusion = -0.5 * self.v.powi(2) * (1.0 - self.gamma) * t * convexity;
+    let jumps = match self.lambda > 0.0 {
+      true => {
+        let delta2 = self.v.powi(2) * self.gamma / self.lambda;
+        self.lambda * t * ((-0.5 * delta2 * convexity).exp() - 1.0)
+      }
+      false => Complex64::new(0.0, 0.0),
+    };
+
+    (diffusion + jumps).exp()
+  }
+}
+
+impl ProcessInfo for Merton1976Pricer {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Merton1976Pricer",
+    title: "Merton jump diffusion pricer",
+    path: "quant::pricing::merton_jump::Merton1976Pricer",
+    kind: ModelKind::Pricer,
+    description: "European options under the Merton jump diffusion as a Poisson weighted sum of Black-Scholes prices",
+    parameters: &[
+      ParameterInfo::S,
+      ParameterInfo::V,
+      ParameterInfo::K,
+      ParameterInfo::R,
+      ParameterInfo::real("r_d", "Domestic risk-free rate", Interval::REAL, 0.05).optional(),
+      ParameterInfo::real("r_f", "Foreign risk-free rate", Interval::REAL, 0.0).optional(),
+      ParameterInfo::Q,
+      ParameterInfo::real("lambda", "Expected number of jumps", Interval::NON_NEGATIVE, 1.0),
+      ParameterInfo::real("gamma", "Percentage of the volatility due to jumps", Interval::closed(0.0, 1.0), 0.25),
+      ParameterInfo::integer("m", "Iteration limit of the series", Interval::COUNT, 50.0),
+      ParameterInfo::TAU,
+      ParameterInfo::EVAL,
+      ParameterInfo::EXPIRATION,
+      ParameterInfo::choice("option_type", "Option type", &["Call", "Put"]),
+      ParameterInfo::choice("b", "Cost of carry", &["BSM1973", "MERTON1973", "BLACK1976", "ASAY1982", "GARMAN1983"]),
+    ],
+    references: &[
+      "Merton, R. C. (1976). Option pricing when underlying stock returns are discontinuous.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::pricing::fft::{FourierMethod, FourierPricer};
+
+  #[test]
+  fn merton_series_matches_fourier() {
+    let (s, r, tau) = (100.0, 0.05, 0.75);
+    let strikes = [80.0, 100.0, 120.0];
+    let merton = |k: f64| {
+      Merton1976Pricer::new(
+        s,
+        0.25,
+        k,
+        r,
+        None,
+        None,
+        None,
+        1.5,
+        0.4,
+        50,
+        Some(tau),
+        None,
+        None,
+        OptionType::Call,
+        BSMCoc::BSM1973,
+      )
+    };
+    let fourier = FourierPricer::new(
+      merton(100.0),
+      s,
+      r,
+      None,
+      tau,
+      Some(FourierMethod::Cos { n: 256, l: 12.0 }),
+    );
+    let (calls, puts) = fourier.calculate_calls_puts(&strikes);
+
+    for (i, &k) in strikes.iter().enumerate() {
+      let (call, put) = merton(k).calculate_call_put();
+      assert!((calls[i] - call).abs() < 1e-4, "{} {}", calls[i], call);
+      assert!((puts[i] - put).abs() < 1e-4, "{} {}", puts[i], put);
+    }
+
+    // E[e^X] = 1, X is already the discounted log-return
+    assert!((merton(100.0).cf(-Complex64::i(), tau) - 1.0).norm() < 1e-12);
+  }
+}
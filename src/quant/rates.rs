@@ -0,0 +1,5 @@
+pub mod bootstrap;
+pub mod hull_white;
+pub mod lmm;
+pub mod multi_curve;
+pub mod nelson_siegel;
@@ -0,0 +1,634 @@
+//! Bootstrapping of discount curves from market quotes.
+//!
+//! [`DiscountCurve::bootstrap`] builds the discount factors at the maturities of deposits,
+//! FRAs, futures and par swaps, one maturity after the other, so that every instrument is
+//! repriced exactly. All quotes are simply compounded rates with year fractions equal to
+//! the times in years, and the swaps are discounted and projected on the same curve, the
+//! single curve setup, see [`multi_curve`](super::multi_curve) for an OIS discount curve
+//! with separate projection curves.
+//!
+//! Between the nodes the curve is
+//!
+//! - log-linear in the discount factors, a piecewise constant forward rate,
+//! - monotone convex (Hagan & West, 2006), a continuous forward curve which keeps the
+//!   discrete forwards of the nodes and does not overshoot between them.
+//!
+//! The monotone convex forwards of a segment depend on the neighbouring nodes, so the
+//! nodes are bootstrapped again until the discount factors do not change any more.
+//!
+//! The forward curve is the input of the short rate models of
+//! [`interest`](crate::stochastic::interest), [`DiscountCurve::hull_white_theta`] is the
+//! drift of the Hull-White and Ho-Lee models fitted to the curve.
+//!
+//! - Hagan, P. S., & West, G. (2006). Interpolation methods for curve construction.
+//! - Hull, J., & White, A. (1990). Pricing interest-rate-derivative securities.
+
+use chrono::NaiveDate;
+use ndarray::{s, Array1};
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    curves::{CurveInterpolation, ZeroCurve},
+    schedule::{Cashflow, DayCount, Schedule},
+    volatility::american::brent,
+  },
+};
+
+/// Quoted instruments of the bootstrapping, the rates are simply compounded
+#[derive(Clone, Debug, PartialEq)]
+pub enum RateInstrument {
+  /// Deposit from today to the maturity
+  Deposit { maturity: f64, rate: f64 },
+  /// Forward rate agreement from start to end
+  Fra { start: f64, end: f64, rate: f64 },
+  /// Interest rate future on the period from start to end, the forward rate is
+  /// 1 - price / 100 minus the convexity adjustment
+  Future {
+    start: f64,
+    end: f64,
+    price: f64,
+    convexity: Option<f64>,
+  },
+  /// Par swap with fixed payments of the given frequency per year, a short first period
+  /// if the maturity is not a multiple of the period
+  Swap {
+    maturity: f64,
+    rate: f64,
+    frequency: usize,
+  },
+}
+
+impl RateInstrument {
+  /// Last time the instrument depends on
+  pub fn maturity(&self) -> f64 {
+    match *self {
+      RateInstrument::Deposit { maturity, .. } | RateInstrument::Swap { maturity, .. } => maturity,
+      RateInstrument::Fra { end, .. } | RateInstrument::Future { end, .. } => end,
+    }
+  }
+
+  /// Quoted rate of the instrument
+  pub fn quote(&self) -> f64 {
+    match *self {
+      RateInstrument::Deposit { rate, .. }
+      | RateInstrument::Fra { rate, .. }
+      | RateInstrument::Swap { rate, .. } => rate,
+      RateInstrument::Future {
+        price, convexity, ..
+      } => 1.0 - price / 100.0 - convexity.unwrap_or(0.0),
+    }
+  }
+
+  /// Rate of the instrument implied by the curve
+  pub fn implied_rate(&self, curve: &DiscountCurve) -> f64 {
+    match *self {
+      RateInstrument::Deposit { maturity, .. } => curve.simple_forward(0.0, maturity),
+      RateInstrument::Fra { start, end, .. } | RateInstrument::Future { start, end, .. } => {
+        curve.simple_forward(start, end)
+      }
+      RateInstrument::Swap {
+        maturity,
+        frequency,
+        ..
+      } => curve.par_swap_rate(maturity, frequency),
+    }
+  }
+
+  fn validate(&self) -> StochasticResult<()> {
+    match *self {
+      RateInstrument::Deposit { maturity, .. } => {
+        ensure(maturity > 0.0, "maturity must be positive")
+      }
+      RateInstrument::Fra { start, end, .. } | RateInstrument::Future { start, end, .. } => ensure(
+        start >= 0.0 && end > start,
+        "end must be after a non-negative start",
+      ),
+      RateInstrument::Swap {
+        maturity,
+        frequency,
+        ..
+      } => ensure(
+        maturity > 0.0 && frequency > 0,
+        "maturity and frequency must be positive",
+      ),
+    }
+  }
+}
+
+/// Interpolation of a [`DiscountCurve`] between its nodes
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscountInterpolation {
+  /// Linear in the log discount factors
+  #[default]
+  LogLinear,
+  /// Monotone convex interpolation of the forward rates
+  MonotoneConvex,
+}
+
+/// Discount curve with the nodes of the bootstrapped instruments
+#[derive(Clone, Debug)]
+pub struct DiscountCurve {
+  /// Times of the nodes, starting at 0
+  pub times: Array1<f64>,
+  /// Discount factors of the nodes, 1 at 0
+  pub discount_factors: Array1<f64>,
+  /// Interpolation between the nodes
+  pub interpolation: DiscountInterpolation,
+  /// Instantaneous forwards of the nodes of the monotone convex interpolation
+  forwards: Array1<f64>,
+}
+
+impl DiscountCurve {
+  /// Curve of the discount factors at the increasing positive times
+  pub fn new(
+    times: Array1<f64>,
+    discount_factors: Array1<f64>,
+    interpolation: DiscountInterpolation,
+  ) -> StochasticResult<Self> {
+    ensure(!times.is_empty(), "at least 1 node is needed")?;
+    ensure(
+      times.len() == discount_factors.len(),
+      "times and discount factors must have the same length",
+    )?;
+    ensure(
+      times[0] > 0.0 && times.windows(2).into_iter().all(|t| t[1] > t[0]),
+      "times must be positive and increasing",
+    )?;
+    ensure(
+      discount_factors.iter().all(|&d| d > 0.0),
+      "discount factors must be positive",
+    )?;
+
+    let times = std::iter::once(0.0).chain(times).collect::<Array1<f64>>();
+    let discount_factors = std::iter::once(1.0)
+      .chain(discount_factors)
+      .collect::<Array1<f64>>();
+    let forwards = node_forwards(&times, &discount_factors);
+
+    Ok(Self {
+      times,
+      discount_factors,
+      interpolation,
+      forwards,
+    })
+  }
+
+  /// Curve repricing the instruments, one node at the maturity of every instrument
+  pub fn bootstrap(
+    instruments: &[RateInstrument],
+    interpolation: DiscountInterpolation,
+  ) -> StochasticResult<Self> {
+    ensure(!instruments.is_empty(), "at least 1 instrument is needed")?;
+    for instrument in instruments {
+      instrument.validate()?;
+    }
+    let mut instruments = instruments.to_vec();
+    instruments.sort_by(|a, b| a.maturity().total_cmp(&b.maturity()));
+    ensure(
+      instruments
+        .windows(2)
+        .all(|w| w[1].maturity() > w[0].maturity()),
+      "maturities of the instruments must be distinct",
+    )?;
+
+    let times = instruments
+      .iter()
+      .map(RateInstrument::maturity)
+      .collect::<Vec<_>>();
+
+    Self::bootstrap_nodes(&times, interpolation, &|i, curve| {
+      instruments[i].implied_rate(curve) - instruments[i].quote()
+    })
+  }
+
+  /// Curve with nodes at the increasing times such that the pricing error of the i-th
+  /// instrument vanishes on the curve of the first i + 1 nodes, e.g. for the projection
+  /// curves of [`MultiCurve`](super::multi_curve::MultiCurve)
+  pub(crate) fn bootstrap_nodes(
+    times: &[f64],
+    interpolation: DiscountInterpolation,
+    error: &dyn Fn(usize, &DiscountCurve) -> f64,
+  ) -> StochasticResult<Self> {
+    let mut discount_factors = Vec::with_capacity(times.len());
+
+    // Node i repricing instrument i with the other nodes fixed, on a bracket of the
+    // average forward rates since the previous node
+    let solve = |i: usize, discount_factors: &[f64]| -> StochasticResult<f64> {
+      let (previous_t, previous_df) = match i {
+        0 => (0.0, 1.0),
+        _ => (times[i - 1], discount_factors[i - 1]),
+      };
+      let dt = times[i] - previous_t;
+      let node_error = |z: f64| {
+        let mut dfs = discount_factors.to_vec();
+        dfs[i] = previous_df * (-z * dt).exp();
+        let n = dfs.len();
+        let curve = Self::new(
+          Array1::from_vec(times[..n].to_vec()),
+          Array1::from_vec(dfs),
+          interpolation,
+        )
+        .unwrap();
+        error(i, &curve)
+      };
+      let z = brent(node_error, -0.5, 2.0, 1e-14, 200)?;
+      Ok(previous_df * (-z * dt).exp())
+    };
+
+    for i in 0..times.len() {
+      discount_factors.push(discount_factors.last().copied().unwrap_or(1.0));
+      discount_factors[i] = solve(i, &discount_factors)?;
+    }
+
+    if interpolation == DiscountInterpolation::MonotoneConvex {
+      for _ in 0..100 {
+        let mut change = 0.0_f64;
+        for i in 0..times.len() {
+          let df = solve(i, &discount_factors)?;
+          change = change.max((df - discount_factors[i]).abs());
+          discount_factors[i] = df;
+        }
+        if change < 1e-14 {
+          break;
+        }
+      }
+    }
+
+    Self::new(
+      Array1::from_vec(times.to_vec()),
+      Array1::from_vec(discount_factors),
+      interpolation,
+    )
+  }
+
+  /// -ln D(t), the integral of the instantaneous forwards up to t
+  fn log_discount(&self, t: f64) -> f64 {
+    if t <= 0.0 {
+      return 0.0;
+    }
+    let y = |i: usize| -self.discount_factors[i].ln();
+    let last = self.times.len() - 1;
+    if t >= self.times[last] {
+      return y(last) + self.instantaneous_forward(self.times[last]) * (t - self.times[last]);
+    }
+
+    let i = self.times.iter().position(|&node| node >= t).unwrap();
+    let h = self.times[i] - self.times[i - 1];
+    let x = (t - self.times[i - 1]) / h;
+    let discrete = (y(i) - y(i - 1)) / h;
+
+    match self.interpolation {
+      DiscountInterpolation::LogLinear => y(i - 1) + discrete * (t - self.times[i - 1]),
+      DiscountInterpolation::MonotoneConvex => {
+        let (g0, g1) = (self.forwards[i - 1] - discrete, self.forwards[i] - discrete);
+        y(i - 1) + discrete * (t - self.times[i - 1]) + h * monotone_convex(g0, g1, x).1
+      }
+    }
+  }
+
+  /// Discount factor D(t)
+  pub fn df(&self, t: f64) -> f64 {
+    (-self.log_discount(t)).exp()
+  }
+
+  /// Continuously compounded zero rate r(t), the instantaneous forward at t = 0
+  pub fn zero_rate(&self, t: f64) -> f64 {
+    match t > 0.0 {
+      true => self.log_discount(t) / t,
+      false => self.instantaneous_forward(0.0),
+    }
+  }
+
+  /// Continuously compounded forward rate ln(D(t1) / D(t2)) / (t2 - t1)
+  pub fn fwd(&self, t1: f64, t2: f64) -> f64 {
+    assert!(t2 > t1, "t2 must be after t1");
+    (self.log_discount(t2) - self.log_discount(t1)) / (t2 - t1)
+  }
+
+  /// Simply compounded forward rate (D(t1) / D(t2) - 1) / (t2 - t1)
+  pub fn simple_forward(&self, t1: f64, t2: f64) -> f64 {
+    assert!(t2 > t1, "t2 must be after t1");
+    ((self.log_discount(t2) - self.log_discount(t1)).exp() - 1.0) / (t2 - t1)
+  }
+
+  /// Instantaneous forward rate f(t), the last one beyond the last node
+  pub fn instantaneous_forward(&self, t: f64) -> f64 {
+    let last = self.times.len() - 1;
+    let t = t.clamp(0.0, self.times[last]);
+    let i = self
+      .times
+      .iter()
+      .position(|&node| node > t)
+      .unwrap_or(last)
+      .max(1);
+    let h = self.times[i] - self.times[i - 1];
+    let discrete = (self.discount_factors[i - 1] / self.discount_factors[i]).ln() / h;
+
+    match self.interpolation {
+      DiscountInterpolation::LogLinear => discrete,
+      DiscountInterpolation::MonotoneConvex => {
+        let (g0, g1) = (self.forwards[i - 1] - discrete, self.forwards[i] - discrete);
+        discrete + monotone_convex(g0, g1, (t - self.times[i - 1]) / h).0
+      }
+    }
+  }
+
+  /// Par rate of a swap with fixed payments of the given frequency per year
+  pub fn par_swap_rate(&self, maturity: f64, frequency: usize) -> f64 {
+    let period = 1.0 / frequency as f64;
+    let mut annuity = 0.0;
+    let mut end = maturity;
+    while end > 1e-9 {
+      let start = (end - period).max(0.0);
+      annuity += (end - start) * self.df(end);
+      end -= period;
+    }
+
+    (1.0 - self.df(maturity)) / annuity
+  }
+
+  /// Sum of the accruals times the discount factors of the payments of a schedule, the
+  /// times are the Act/365 year fractions from the valuation date
+  pub fn annuity(&self, schedule: &Schedule, valuation: NaiveDate) -> f64 {
+    schedule
+      .periods()
+      .iter()
+      .map(|p| p.accrual * self.df(DayCount::Act365Fixed.year_fraction(valuation, p.payment)))
+      .sum()
+  }
+
+  /// Par rate of a swap with the fixed leg on the schedule, the floating leg is worth
+  /// D(start) - D(end) on the single curve
+  pub fn par_rate(&self, schedule: &Schedule, valuation: NaiveDate) -> f64 {
+    let periods = schedule.periods();
+    let time = |date: NaiveDate| DayCount::Act365Fixed.year_fraction(valuation, date);
+    let floating = self.df(time(periods[0].start)) - self.df(time(periods[periods.len() - 1].end));
+
+    floating / self.annuity(schedule, valuation)
+  }
+
+  /// Present value of the cashflows paid after the valuation date
+  pub fn present_value(&self, cashflows: &[Cashflow], valuation: NaiveDate) -> f64 {
+    cashflows
+      .iter()
+      .filter(|c| c.date > valuation)
+      .map(|c| c.amount * self.df(DayCount::Act365Fixed.year_fraction(valuation, c.date)))
+      .sum()
+  }
+
+  /// Drift theta(t) of the Hull-White short rate dr = (theta(t) - alpha r) dt + sigma dW
+  /// fitting the curve, f'(t) + alpha f(t) + sigma^2 (1 - e^(-2 alpha t)) / (2 alpha), and
+  /// of the Ho-Lee model for alpha = 0
+  pub fn hull_white_theta(&self, t: f64, alpha: f64, sigma: f64) -> f64 {
+    let h = 1e-5;
+    let (t1, t2) = ((t - h).max(0.0), t + h);
+    let slope = (self.instantaneous_forward(t2) - self.instantaneous_forward(t1)) / (t2 - t1);
+    let convexity = match alpha.abs() > 1e-12 {
+      true => sigma.powi(2) * (1.0 - (-2.0 * alpha * t).exp()) / (2.0 * alpha),
+      false => sigma.powi(2) * t,
+    };
+
+    slope + alpha * self.instantaneous_forward(t) + convexity
+  }
+
+  /// Zero curve of the nodes, the same curve for the log-linear interpolation
+  pub fn to_zero_curve(&self) -> ZeroCurve {
+    let times = self.times.slice(s![1..]).to_owned();
+    let rates = Array1::from_shape_fn(times.len(), |i| self.zero_rate(times[i]));
+
+    ZeroCurve::new(times, rates, CurveInterpolation::FlatForward).unwrap()
+  }
+}
+
+/// Instantaneous forwards of the nodes of the monotone convex interpolation
+fn node_forwards(times: &Array1<f64>, discount_factors: &Array1<f64>) -> Array1<f64> {
+  let n = times.len() - 1;
+  let discrete = Array1::from_shape_fn(n + 1, |i| match i {
+    0 => 0.0,
+    _ => (discount_factors[i - 1] / discount_factors[i]).ln() / (times[i] - times[i - 1]),
+  });
+  if n == 1 {
+    return Array1::from_elem(2, discrete[1]);
+  }
+
+  let mut forwards = Array1::zeros(n + 1);
+  for i in 1..n {
+    forwards[i] = ((times[i] - times[i - 1]) * discrete[i + 1]
+      + (times[i + 1] - times[i]) * discrete[i])
+      / (times[i + 1] - times[i - 1]);
+  }
+  forwards[0] = discrete[1] - 0.5 * (forwards[1] - discrete[1]);
+  forwards[n] = discrete[n] - 0.5 * (forwards[n - 1] - discrete[n]);
+
+  forwards
+}
+
+/// Deviation g(x) of the forward from the discrete forward of a segment at x in [0, 1],
+/// g(0) = g0 and g(1) = g1, and its integral G(x), G(1) = 0
+fn monotone_convex(g0: f64, g1: f64, x: f64) -> (f64, f64) {
+  if g0 == 0.0 && g1 == 0.0 {
+    return (0.0, 0.0);
+  }
+
+  if (g0 < 0.0 && -0.5 * g0 <= g1 && g1 <= -2.0 * g0)
+    || (g0 > 0.0 && -0.5 * g0 >= g1 && g1 >= -2.0 * g0)
+  {
+    // (i) quadratic
+    let g = g0 * (1.0 - 4.0 * x + 3.0 * x * x) + g1 * (-2.0 * x + 3.0 * x * x);
+    let big_g = g0 * (x - 2.0 * x * x + x.powi(3)) + g1 * (-x * x + x.powi(3));
+    (g, big_g)
+  } else if (g0 < 0.0 && g1 > -2.0 * g0) || (g0 > 0.0 && g1 < -2.0 * g0) {
+    // (ii) flat then quadratic
+    let eta = (g1 + 2.0 * g0) / (g1 - g0);
+    match x <= eta {
+      true => (g0, g0 * x),
+      false => (
+        g0 + (g1 - g0) * ((x - eta) / (1.0 - eta)).powi(2),
+        g0 * x + (g1 - g0) * (x - eta).powi(3) / (3.0 * (1.0 - eta).powi(2)),
+      ),
+    }
+  } else if (g0 > 0.0 && 0.0 > g1 && g1 > -0.5 * g0) || (g0 < 0.0 && 0.0 < g1 && g1 < -0.5 * g0) {
+    // (iii) quadratic then flat
+    let eta = 3.0 * g1 / (g1 - g0);
+    match x < eta {
+      true => (
+        g1 + (g0 - g1) * ((eta - x) / eta).powi(2),
+        g1 * x + (g0 - g1) * eta / 3.0 * (1.0 - ((eta - x) / eta).powi(3)),
+      ),
+      false => (g1, g1 * x + (g0 - g1) * eta / 3.0),
+    }
+  } else {
+    // (iv) two quadratics meeting at the extremum A
+    let eta = g1 / (g1 + g0);
+    let a = -g0 * g1 / (g0 + g1);
+    match x < eta {
+      true => (
+        a + (g0 - a) * ((eta - x) / eta).powi(2),
+        a * x + (g0 - a) * eta / 3.0 * (1.0 - ((eta - x) / eta).powi(3)),
+      ),
+      false => (
+        a + (g1 - a) * ((x - eta) / (1.0 - eta)).powi(2),
+        a * x + (g0 - a) * eta / 3.0 + (g1 - a) * (x - eta).powi(3) / (3.0 * (1.0 - eta).powi(2)),
+      ),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::schedule::Frequency;
+
+  fn instruments() -> Vec<RateInstrument> {
+    vec![
+      RateInstrument::Deposit {
+        maturity: 0.25,
+        rate: 0.031,
+      },
+      RateInstrument::Fra {
+        start: 0.25,
+        end: 0.5,
+        rate: 0.033,
+      },
+      RateInstrument::Future {
+        start: 0.5,
+        end: 0.75,
+        price: 96.5,
+        convexity: Some(0.0002),
+      },
+      RateInstrument::Swap {
+        maturity: 2.0,
+        rate: 0.036,
+        frequency: 2,
+      },
+      RateInstrument::Swap {
+        maturity: 5.0,
+        rate: 0.038,
+        frequency: 1,
+      },
+      RateInstrument::Swap {
+        maturity: 10.0,
+        rate: 0.037,
+        frequency: 1,
+      },
+    ]
+  }
+
+  #[test]
+  fn bootstrapped_curves_reprice_the_instruments() {
+    for interpolation in [
+      DiscountInterpolation::LogLinear,
+      DiscountInterpolation::MonotoneConvex,
+    ] {
+      let curve = DiscountCurve::bootstrap(&instruments(), interpolation).unwrap();
+      for instrument in instruments() {
+        let error = instrument.implied_rate(&curve) - instrument.quote();
+        assert!(error.abs() < 1e-10, "{:?} {:?}", interpolation, instrument);
+      }
+
+      assert_eq!(curve.df(0.0), 1.0);
+      for t in [0.1, 1.0, 3.0, 7.5, 12.0] {
+        assert!((curve.df(t) - (-curve.zero_rate(t) * t).exp()).abs() < 1e-12);
+        let forward = curve.fwd(t, t + 1.0);
+        assert!((curve.df(t + 1.0) - curve.df(t) * (-forward).exp()).abs() < 1e-12);
+      }
+    }
+
+    // The monotone convex forward curve is continuous
+    let curve =
+      DiscountCurve::bootstrap(&instruments(), DiscountInterpolation::MonotoneConvex).unwrap();
+    for &t in curve.times.iter().skip(1) {
+      let jump = curve.instantaneous_forward(t + 1e-9) - curve.instantaneous_forward(t - 1e-9);
+      assert!(jump.abs() < 1e-6);
+    }
+
+    // The zero curve of the log-linear nodes is the same curve
+    let curve = DiscountCurve::bootstrap(&instruments(), DiscountInterpolation::LogLinear).unwrap();
+    let zero = curve.to_zero_curve();
+    for t in [0.3, 1.5, 4.0, 9.0] {
+      assert!((zero.discount(t) - curve.df(t)).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn flat_curve_and_hull_white_drift() {
+    // Quotes of a flat continuously compounded curve of 3%
+    let r = 0.03_f64;
+    let simple = |t1: f64, t2: f64| ((r * (t2 - t1)).exp() - 1.0) / (t2 - t1);
+    let par = |maturity: f64| {
+      let annuity = (1..=maturity as usize)
+        .map(|t| (-r * t as f64).exp())
+        .sum::<f64>();
+      (1.0 - (-r * maturity).exp()) / annuity
+    };
+    let instruments = vec![
+      RateInstrument::Deposit {
+        maturity: 0.5,
+        rate: simple(0.0, 0.5),
+      },
+      RateInstrument::Fra {
+        start: 0.5,
+        end: 1.0,
+        rate: simple(0.5, 1.0),
+      },
+      RateInstrument::Swap {
+        maturity: 3.0,
+        rate: par(3.0),
+        frequency: 1,
+      },
+      RateInstrument::Swap {
+        maturity: 7.0,
+        rate: par(7.0),
+        frequency: 1,
+      },
+    ];
+
+    for interpolation in [
+      DiscountInterpolation::LogLinear,
+      DiscountInterpolation::MonotoneConvex,
+    ] {
+      let curve = DiscountCurve::bootstrap(&instruments, interpolation).unwrap();
+      for t in [0.2, 0.7, 2.0, 5.0, 10.0] {
+        assert!((curve.zero_rate(t) - r).abs() < 1e-10);
+        assert!((curve.instantaneous_forward(t) - r).abs() < 1e-8);
+      }
+
+      let (alpha, sigma, t): (f64, f64, f64) = (0.1, 0.01, 2.0);
+      let theta = alpha * r + sigma.powi(2) * (1.0 - (-2.0 * alpha * t).exp()) / (2.0 * alpha);
+      assert!((curve.hull_white_theta(t, alpha, sigma) - theta).abs() < 1e-6);
+    }
+
+    assert!(DiscountCurve::bootstrap(&[], DiscountInterpolation::LogLinear).is_err());
+  }
+
+  #[test]
+  fn par_bond_on_a_schedule() {
+    let date = |year: i32, month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let curve = DiscountCurve::new(
+      Array1::from_vec(vec![1.0, 2.0, 5.0, 10.0]),
+      Array1::from_vec(vec![0.97, 0.935, 0.84, 0.7]),
+      DiscountInterpolation::MonotoneConvex,
+    )
+    .unwrap();
+    let schedule = Schedule::new(date(2024, 1, 15), date(2031, 1, 15), Frequency::SemiAnnual)
+      .unwrap()
+      .with_day_count(DayCount::Thirty360)
+      .with_holidays(&[date(2027, 1, 15)]);
+    let valuation = date(2024, 1, 15);
+
+    // A bond paying the par rate is worth par, the fixed leg prices the floating leg
+    let par = curve.par_rate(&schedule, valuation);
+    let bond = curve.present_value(&schedule.bond_cashflows(par, 100.0), valuation);
+    assert!((bond - 100.0).abs() < 1e-10);
+    assert!(par > 0.0 && par < 0.05);
+
+    // Coupons paid before the valuation date are not counted
+    let later = date(2025, 1, 16);
+    let remaining = schedule.bond_cashflows(par, 100.0)[2..].to_vec();
+    assert_eq!(
+      curve.present_value(&schedule.bond_cashflows(par, 100.0), later),
+      curve.present_value(&remaining, later)
+    );
+  }
+}
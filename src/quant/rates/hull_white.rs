@@ -0,0 +1,414 @@
+//! Hull-White one-factor model fitted to a discount curve.
+//!
+//! dr(t) = (theta(t) - alpha r(t)) dt + sigma dW(t)
+//!
+//! With theta(t) fitted to the initial curve the short rate is r(t) = x(t) + phi(t), x an
+//! Ornstein-Uhlenbeck process from 0 and
+//!
+//! phi(t) = f(0, t) + sigma^2 / (2 alpha^2) (1 - e^(-alpha t))^2
+//!
+//! so the zero-coupon bonds P(t, T) = A(t, T) e^(-B(t, T) r(t)) reprice the curve and the
+//! options on them are Black-like formulas of the lognormal bond prices. Caplets are puts
+//! on zero-coupon bonds and swaptions are options on coupon bonds, priced as portfolios of
+//! zero-coupon bond options with Jamshidian's decomposition.
+//!
+//! [`HullWhiteModel::calibrate`] fits alpha and sigma to the Black volatilities of caplets
+//! and swaptions, [`HullWhiteFitted`] simulates the short rate exactly.
+//!
+//! - Hull, J., & White, A. (1990). Pricing interest-rate-derivative securities.
+//! - Jamshidian, F. (1989). An exact bond option formula.
+//! - Brigo, D., & Mercurio, F. (2006). Interest Rate Models - Theory and Practice, ch. 3.3.
+
+use impl_new_derive::ImplNew;
+use nalgebra::DVector;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    rates::bootstrap::DiscountCurve,
+    volatility::{american::brent, svi::minimize},
+    OptionType,
+  },
+  stochastic::interest::hull_white_fitted::HullWhiteFitted,
+};
+
+/// Hull-White model with theta(t) fitted to the curve
+#[derive(ImplNew, Clone, Debug)]
+pub struct HullWhiteModel {
+  /// Initial discount curve
+  pub curve: DiscountCurve,
+  /// Mean reversion speed
+  pub alpha: f64,
+  /// Volatility of the short rate
+  pub sigma: f64,
+}
+
+/// Market quotes of the calibration with their Black volatilities
+#[derive(Clone, Debug, PartialEq)]
+pub enum HullWhiteQuote {
+  /// Caplet on the simple rate of [start, end], paid at end
+  Caplet {
+    start: f64,
+    end: f64,
+    strike: f64,
+    vol: f64,
+  },
+  /// Payer swaption into a swap of the tenor with fixed payments of the given frequency
+  Swaption {
+    expiry: f64,
+    tenor: f64,
+    frequency: usize,
+    strike: f64,
+    vol: f64,
+  },
+}
+
+impl HullWhiteQuote {
+  /// Price of the quote with the Black formula on the forward rate
+  pub fn black_price(&self, curve: &DiscountCurve) -> f64 {
+    match *self {
+      HullWhiteQuote::Caplet {
+        start,
+        end,
+        strike,
+        vol,
+      } => {
+        let forward = curve.simple_forward(start, end);
+        (end - start) * curve.df(end) * black_call(forward, strike, vol, start)
+      }
+      HullWhiteQuote::Swaption {
+        expiry,
+        tenor,
+        frequency,
+        strike,
+        vol,
+      } => {
+        let (annuity, rate) = swap_annuity_and_rate(curve, expiry, tenor, frequency);
+        annuity * black_call(rate, strike, vol, expiry)
+      }
+    }
+  }
+
+  /// Price of the quote in the Hull-White model
+  pub fn model_price(&self, model: &HullWhiteModel) -> f64 {
+    match *self {
+      HullWhiteQuote::Caplet {
+        start, end, strike, ..
+      } => model.caplet(start, end, strike),
+      HullWhiteQuote::Swaption {
+        expiry,
+        tenor,
+        frequency,
+        strike,
+        ..
+      } => model.swaption(expiry, tenor, frequency, strike, OptionType::Call),
+    }
+  }
+}
+
+/// Undiscounted Black call on the forward
+pub(crate) fn black_call(forward: f64, strike: f64, vol: f64, t: f64) -> f64 {
+  let n = Normal::new(0.0, 1.0).unwrap();
+  let sd = vol * t.sqrt();
+  let d1 = (forward / strike).ln() / sd + 0.5 * sd;
+  forward * n.cdf(d1) - strike * n.cdf(d1 - sd)
+}
+
+/// Payment times of the fixed leg of a swap starting at the expiry
+fn payment_times(expiry: f64, tenor: f64, frequency: usize) -> Vec<f64> {
+  let payments = (tenor * frequency as f64).round() as usize;
+  (1..=payments)
+    .map(|j| expiry + j as f64 / frequency as f64)
+    .collect()
+}
+
+/// Annuity and par rate of a forward starting swap
+fn swap_annuity_and_rate(
+  curve: &DiscountCurve,
+  expiry: f64,
+  tenor: f64,
+  frequency: usize,
+) -> (f64, f64) {
+  let times = payment_times(expiry, tenor, frequency);
+  let annuity = times.iter().map(|&t| curve.df(t)).sum::<f64>() / frequency as f64;
+  let rate = (curve.df(expiry) - curve.df(*times.last().unwrap())) / annuity;
+  (annuity, rate)
+}
+
+impl HullWhiteModel {
+  /// B(t, T) = (1 - e^(-alpha (T - t))) / alpha
+  pub fn b(&self, t: f64, maturity: f64) -> f64 {
+    (1.0 - (-self.alpha * (maturity - t)).exp()) / self.alpha
+  }
+
+  /// Deterministic shift phi(t) of the short rate r(t) = x(t) + phi(t)
+  pub fn shift(&self, t: f64) -> f64 {
+    self.curve.instantaneous_forward(t)
+      + self.sigma.powi(2) / (2.0 * self.alpha.powi(2)) * (1.0 - (-self.alpha * t).exp()).powi(2)
+  }
+
+  /// Drift theta(t) fitting the curve
+  pub fn theta(&self, t: f64) -> f64 {
+    self.curve.hull_white_theta(t, self.alpha, self.sigma)
+  }
+
+  /// Price at t of the zero-coupon bond maturing at T given the short rate r(t)
+  pub fn bond_price(&self, t: f64, maturity: f64, r: f64) -> f64 {
+    let b = self.b(t, maturity);
+    let ln_a = (self.curve.df(maturity) / self.curve.df(t)).ln()
+      + b * self.curve.instantaneous_forward(t)
+      - self.sigma.powi(2) / (4.0 * self.alpha) * (1.0 - (-2.0 * self.alpha * t).exp()) * b * b;
+
+    (ln_a - b * r).exp()
+  }
+
+  /// Price of the option expiring at T on the zero-coupon bond maturing at S
+  pub fn bond_option(
+    &self,
+    expiry: f64,
+    maturity: f64,
+    strike: f64,
+    option_type: OptionType,
+  ) -> f64 {
+    assert!(maturity > expiry, "the bond must mature after the expiry");
+    let n = Normal::new(0.0, 1.0).unwrap();
+    let (p_t, p_s) = (self.curve.df(expiry), self.curve.df(maturity));
+    let sigma_p = self.sigma
+      * ((1.0 - (-2.0 * self.alpha * expiry).exp()) / (2.0 * self.alpha)).sqrt()
+      * self.b(expiry, maturity);
+    let h = (p_s / (p_t * strike)).ln() / sigma_p + 0.5 * sigma_p;
+
+    match option_type {
+      OptionType::Call => p_s * n.cdf(h) - strike * p_t * n.cdf(h - sigma_p),
+      OptionType::Put => strike * p_t * n.cdf(sigma_p - h) - p_s * n.cdf(-h),
+    }
+  }
+
+  /// Price of the caplet on the simple rate of [start, end] with unit notional, a put on the
+  /// zero-coupon bond
+  pub fn caplet(&self, start: f64, end: f64, strike: f64) -> f64 {
+    let growth = 1.0 + strike * (end - start);
+    growth * self.bond_option(start, end, 1.0 / growth, OptionType::Put)
+  }
+
+  /// Price of the swaption with Jamshidian's decomposition, the call is the payer swaption
+  /// (a call on the swap rate) and the put the receiver one
+  pub fn swaption(
+    &self,
+    expiry: f64,
+    tenor: f64,
+    frequency: usize,
+    strike: f64,
+    option_type: OptionType,
+  ) -> f64 {
+    let times = payment_times(expiry, tenor, frequency);
+    let coupons = (0..times.len())
+      .map(|j| match j + 1 == times.len() {
+        true => 1.0 + strike / frequency as f64,
+        false => strike / frequency as f64,
+      })
+      .collect::<Vec<_>>();
+    let coupon_bond = |r: f64| {
+      times
+        .iter()
+        .zip(&coupons)
+        .map(|(&t, &c)| c * self.bond_price(expiry, t, r))
+        .sum::<f64>()
+    };
+    // The coupon bond decreases from infinity to 0 in r, widen the bracket until it holds r*
+    let (mut low, mut high) = (-2.0, 2.0);
+    for _ in 0..64 {
+      match (coupon_bond(low) > 1.0, coupon_bond(high) < 1.0) {
+        (true, true) => break,
+        (below, above) => {
+          low *= if below { 1.0 } else { 2.0 };
+          high *= if above { 1.0 } else { 2.0 };
+        }
+      }
+    }
+    let r_star = brent(|r| coupon_bond(r) - 1.0, low, high, 1e-14, 200)
+      .expect("the coupon bond must cross 1 for a strike above -frequency");
+
+    // A payer swaption is a put on the coupon bond with strike 1
+    let bond_option_type = match option_type {
+      OptionType::Call => OptionType::Put,
+      OptionType::Put => OptionType::Call,
+    };
+    times
+      .iter()
+      .zip(&coupons)
+      .map(|(&t, &c)| {
+        c * self.bond_option(
+          expiry,
+          t,
+          self.bond_price(expiry, t, r_star),
+          bond_option_type,
+        )
+      })
+      .sum()
+  }
+
+  /// Exact simulation of the short rate on n points of [0, t]
+  pub fn process(&self, n: usize, t: Option<f64>, m: Option<usize>) -> HullWhiteFitted {
+    HullWhiteFitted::new(self.clone(), n, t, m)
+  }
+
+  /// Fit alpha and sigma to the Black volatilities of caplets and swaptions, from
+  /// the initial guess (alpha, sigma) or (0.1, 0.01)
+  pub fn calibrate(
+    curve: DiscountCurve,
+    quotes: &[HullWhiteQuote],
+    initial: Option<(f64, f64)>,
+  ) -> StochasticResult<Self> {
+    ensure(quotes.len() >= 2, "at least 2 quotes are needed")?;
+    let (alpha, sigma) = initial.unwrap_or((0.1, 0.01));
+    ensure(
+      alpha > 0.0 && sigma > 0.0,
+      "initial alpha and sigma must be positive",
+    )?;
+
+    let market = quotes
+      .iter()
+      .map(|quote| quote.black_price(&curve))
+      .collect::<Vec<_>>();
+    let build = |x: &DVector<f64>| Self::new(curve.clone(), x[0].exp(), x[1].exp());
+
+    // Relative price errors, alpha and sigma are positive through their logarithms
+    let residuals = |x: &DVector<f64>| {
+      let model = build(x);
+      DVector::from_iterator(
+        quotes.len(),
+        quotes
+          .iter()
+          .zip(&market)
+          .map(|(quote, &price)| quote.model_price(&model) / price - 1.0),
+      )
+    };
+    let x = minimize(DVector::from_vec(vec![alpha.ln(), sigma.ln()]), &residuals);
+
+    Ok(build(&x))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use implied_vol::implied_black_volatility;
+  use ndarray::array;
+
+  use super::*;
+  use crate::{quant::rates::bootstrap::DiscountInterpolation, stochastic::Sampling};
+
+  fn curve() -> DiscountCurve {
+    let times = array![0.5, 1.0, 2.0, 5.0, 10.0, 20.0];
+    let rates = array![0.030, 0.032, 0.035, 0.038, 0.040, 0.041];
+    DiscountCurve::new(
+      times.clone(),
+      (&rates * &times).mapv(|x: f64| (-x).exp()),
+      DiscountInterpolation::MonotoneConvex,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn bonds_and_options_reprice_the_curve() {
+    let model = HullWhiteModel::new(curve(), 0.08, 0.012);
+    let r0 = model.shift(0.0);
+    for maturity in [0.5, 3.0, 12.0] {
+      assert!((model.bond_price(0.0, maturity, r0) - model.curve.df(maturity)).abs() < 1e-12);
+    }
+
+    // Put-call parity of the bond options and of the swaptions
+    let (call, put) = (
+      model.bond_option(2.0, 5.0, 0.9, OptionType::Call),
+      model.bond_option(2.0, 5.0, 0.9, OptionType::Put),
+    );
+    assert!((call - put - (model.curve.df(5.0) - 0.9 * model.curve.df(2.0))).abs() < 1e-12);
+
+    let (payer, receiver) = (
+      model.swaption(2.0, 5.0, 1, 0.04, OptionType::Call),
+      model.swaption(2.0, 5.0, 1, 0.04, OptionType::Put),
+    );
+    let (annuity, rate) = swap_annuity_and_rate(&model.curve, 2.0, 5.0, 1);
+    assert!((payer - receiver - annuity * (rate - 0.04)).abs() < 1e-10);
+
+    // Caplets decrease with the strike
+    let caplets = [0.02, 0.035, 0.05].map(|strike| model.caplet(1.0, 1.5, strike));
+    assert!(caplets[0] > caplets[1] && caplets[1] > caplets[2] && caplets[2] > 0.0);
+  }
+
+  #[test]
+  fn swaption_with_the_critical_rate_outside_the_unit_bracket() {
+    let model = HullWhiteModel::new(curve(), 0.08, 0.012);
+    // A one year annual coupon of 1200% needs r* of about 2.7
+    for (tenor, strike) in [(1.0, 12.0), (2.0, 20.0)] {
+      let (payer, receiver) = (
+        model.swaption(1.0, tenor, 1, strike, OptionType::Call),
+        model.swaption(1.0, tenor, 1, strike, OptionType::Put),
+      );
+      let (annuity, rate) = swap_annuity_and_rate(&model.curve, 1.0, tenor, 1);
+      assert!(payer >= 0.0 && receiver >= 0.0);
+      assert!((payer - receiver - annuity * (rate - strike)).abs() < 1e-10);
+    }
+  }
+
+  #[test]
+  fn exact_simulation_reprices_the_bonds() {
+    let model = HullWhiteModel::new(curve(), 0.1, 0.01);
+    let (n, t) = (201, 5.0);
+    let paths = model.process(n, Some(t), Some(40_000)).sample_par();
+    let dt = t / (n - 1) as f64;
+
+    let discount = paths
+      .rows()
+      .into_iter()
+      .map(|r| {
+        let integral = (r.sum() - 0.5 * (r[0] + r[n - 1])) * dt;
+        (-integral).exp()
+      })
+      .sum::<f64>()
+      / paths.nrows() as f64;
+    assert!((discount - model.curve.df(t)).abs() < 2e-3);
+  }
+
+  #[test]
+  fn calibration_recovers_the_parameters() {
+    let truth = HullWhiteModel::new(curve(), 0.05, 0.009);
+    let curve = curve();
+
+    let mut quotes = Vec::new();
+    for (start, strike) in [(1.0, 0.035), (3.0, 0.04), (7.0, 0.042)] {
+      let end = start + 0.5;
+      let price = truth.caplet(start, end, strike);
+      let forward = curve.simple_forward(start, end);
+      let vol =
+        implied_black_volatility(price / (0.5 * curve.df(end)), forward, strike, start, true);
+      quotes.push(HullWhiteQuote::Caplet {
+        start,
+        end,
+        strike,
+        vol,
+      });
+    }
+    for (expiry, tenor) in [(1.0, 5.0), (5.0, 5.0), (2.0, 10.0)] {
+      let (annuity, rate) = swap_annuity_and_rate(&curve, expiry, tenor, 1);
+      let price = truth.swaption(expiry, tenor, 1, rate, OptionType::Call);
+      let vol = implied_black_volatility(price / annuity, rate, rate, expiry, true);
+      quotes.push(HullWhiteQuote::Swaption {
+        expiry,
+        tenor,
+        frequency: 1,
+        strike: rate,
+        vol,
+      });
+    }
+
+    let model = HullWhiteModel::calibrate(curve, &quotes, None).unwrap();
+    assert!((model.alpha - 0.05).abs() < 1e-3, "{}", model.alpha);
+    assert!((model.sigma - 0.009).abs() < 1e-4, "{}", model.sigma);
+    for quote in &quotes {
+      let price = quote.black_price(&model.curve);
+      assert!((quote.model_price(&model) / price - 1.0).abs() < 1e-4);
+    }
+  }
+}
@@ -0,0 +1,384 @@
+//! Multi-curve framework of the discounting and the projection of the forward rates.
+//!
+//! Since 2008 the forward rates of the IBOR indices are not implied by the discount curve
+//! any more, a 6M deposit carries more credit and liquidity risk than two 3M ones and both
+//! more than the overnight rate. The cash flows of collateralized trades are discounted on
+//! the OIS curve D(t) and every index of tenor delta has its own projection curve P_delta,
+//! whose simple forwards
+//!
+//! F_delta(t) = (P_delta(t) / P_delta(t + delta) - 1) / delta
+//!
+//! are the expected fixings. A swap paying the index at its periods has the par rate
+//!
+//! S = sum_j tau_j F_delta(T_(j-1)) D(T_j) / sum_i tau_i D(T_i)
+//!
+//! which is the single curve rate (1 - D(T)) / annuity only if P_delta = D. The basis
+//! spread is the difference of the projected forward and the OIS forward of a period.
+//! [`MultiCurve::bootstrap_projection`] builds a projection curve from the quotes of the
+//! index, deposits, FRAs, par swaps and basis swaps against the OIS rate, on a given OIS
+//! curve, e.g. bootstrapped from OIS swaps with
+//! [`DiscountCurve::bootstrap`].
+//!
+//! - Ametrano, F. M., & Bianchetti, M. (2013). Everything you always wanted to know about multiple interest rate curve bootstrapping but were afraid to ask.
+//! - Henrard, M. (2014). Interest Rate Modelling in the Multi-Curve Framework.
+
+use ndarray::{s, Array1};
+
+use super::{
+  bootstrap::{DiscountCurve, DiscountInterpolation},
+  hull_white::black_call,
+};
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::schedule::{Schedule, StubRule},
+};
+
+/// Quoted instruments of a projection curve, the rates are simply compounded
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProjectionInstrument {
+  /// Fixing or deposit of the index from today to the maturity
+  Deposit { maturity: f64, rate: f64 },
+  /// Forward rate agreement on the index from start to end
+  Fra { start: f64, end: f64, rate: f64 },
+  /// Par swap with fixed payments of the given frequency per year against the index paid
+  /// at its tenor
+  Swap {
+    maturity: f64,
+    rate: f64,
+    frequency: usize,
+  },
+  /// Basis swap of the index against the OIS rate compounded over the same periods plus
+  /// the spread
+  BasisSwap { maturity: f64, spread: f64 },
+}
+
+impl ProjectionInstrument {
+  /// Last time the instrument depends on
+  pub fn maturity(&self) -> f64 {
+    match *self {
+      ProjectionInstrument::Deposit { maturity, .. }
+      | ProjectionInstrument::Swap { maturity, .. }
+      | ProjectionInstrument::BasisSwap { maturity, .. } => maturity,
+      ProjectionInstrument::Fra { end, .. } => end,
+    }
+  }
+
+  /// Quoted rate or spread of the instrument
+  pub fn quote(&self) -> f64 {
+    match *self {
+      ProjectionInstrument::Deposit { rate, .. }
+      | ProjectionInstrument::Fra { rate, .. }
+      | ProjectionInstrument::Swap { rate, .. } => rate,
+      ProjectionInstrument::BasisSwap { spread, .. } => spread,
+    }
+  }
+
+  /// Rate or spread of the instrument implied by the projection curve of the index of the
+  /// tenor and the discount curve
+  pub fn implied_rate(
+    &self,
+    projection: &DiscountCurve,
+    discount: &DiscountCurve,
+    tenor: f64,
+  ) -> f64 {
+    match *self {
+      ProjectionInstrument::Deposit { maturity, .. } => projection.simple_forward(0.0, maturity),
+      ProjectionInstrument::Fra { start, end, .. } => projection.simple_forward(start, end),
+      ProjectionInstrument::Swap {
+        maturity,
+        frequency,
+        ..
+      } => {
+        floating_leg(projection, discount, 0.0, maturity, tenor)
+          / annuity(discount, 0.0, maturity, 1.0 / frequency as f64)
+      }
+      ProjectionInstrument::BasisSwap { maturity, .. } => {
+        let ois = discount.df(0.0) - discount.df(maturity);
+        (floating_leg(projection, discount, 0.0, maturity, tenor) - ois)
+          / annuity(discount, 0.0, maturity, tenor)
+      }
+    }
+  }
+
+  fn validate(&self) -> StochasticResult<()> {
+    match *self {
+      ProjectionInstrument::Deposit { maturity, .. }
+      | ProjectionInstrument::BasisSwap { maturity, .. } => {
+        ensure(maturity > 0.0, "maturity must be positive")
+      }
+      ProjectionInstrument::Fra { start, end, .. } => ensure(
+        start >= 0.0 && end > start,
+        "end must be after a non-negative start",
+      ),
+      ProjectionInstrument::Swap {
+        maturity,
+        frequency,
+        ..
+      } => ensure(
+        maturity > 0.0 && frequency > 0,
+        "maturity and frequency must be positive",
+      ),
+    }
+  }
+}
+
+/// sum tau_i D(T_i) of the periods rolled backward from the end with a short front stub,
+/// like a dated [`Schedule`]
+fn annuity(discount: &DiscountCurve, start: f64, end: f64, period: f64) -> f64 {
+  Schedule::year_fraction_periods(start, end, period, StubRule::ShortFront)
+    .iter()
+    .map(|&(t1, t2)| (t2 - t1) * discount.df(t2))
+    .sum()
+}
+
+/// sum tau_j F(T_(j-1), T_j) D(T_j) of the index paid at its tenor
+fn floating_leg(
+  projection: &DiscountCurve,
+  discount: &DiscountCurve,
+  start: f64,
+  end: f64,
+  tenor: f64,
+) -> f64 {
+  Schedule::year_fraction_periods(start, end, tenor, StubRule::ShortFront)
+    .iter()
+    .map(|&(t1, t2)| (t2 - t1) * projection.simple_forward(t1, t2) * discount.df(t2))
+    .sum()
+}
+
+/// OIS discount curve with the projection curves of the indices
+#[derive(Clone, Debug)]
+pub struct MultiCurve {
+  /// OIS curve discounting all the cash flows
+  pub discount: DiscountCurve,
+  /// Tenors in years of the indices, e.g. 0.25 for 3M, with their projection curves
+  pub projections: Vec<(f64, DiscountCurve)>,
+}
+
+impl MultiCurve {
+  /// Discount curve without projection curves, the single curve setup
+  pub fn new(discount: DiscountCurve) -> Self {
+    Self {
+      discount,
+      projections: Vec::new(),
+    }
+  }
+
+  /// Set the projection curve of the index of the tenor
+  pub fn with_projection(mut self, tenor: f64, curve: DiscountCurve) -> Self {
+    self
+      .projections
+      .retain(|(other, _)| (other - tenor).abs() > 1e-9);
+    self.projections.push((tenor, curve));
+    self
+  }
+
+  /// Projection curve of the discount curve with a constant continuously compounded
+  /// spread added to its forwards
+  pub fn with_spread(self, tenor: f64, spread: f64) -> Self {
+    let times = self.discount.times.slice(s![1..]).to_owned();
+    let discount_factors = Array1::from_shape_fn(times.len(), |i| {
+      self.discount.discount_factors[i + 1] * (-spread * times[i]).exp()
+    });
+    let curve = DiscountCurve::new(times, discount_factors, self.discount.interpolation).unwrap();
+
+    self.with_projection(tenor, curve)
+  }
+
+  /// Bootstrap the projection curve of the index of the tenor, one node at the maturity of
+  /// every instrument, with the cash flows discounted on the OIS curve
+  pub fn bootstrap_projection(
+    self,
+    tenor: f64,
+    instruments: &[ProjectionInstrument],
+    interpolation: DiscountInterpolation,
+  ) -> StochasticResult<Self> {
+    ensure(tenor > 0.0, "tenor must be positive")?;
+    ensure(!instruments.is_empty(), "at least 1 instrument is needed")?;
+    for instrument in instruments {
+      instrument.validate()?;
+    }
+    let mut instruments = instruments.to_vec();
+    instruments.sort_by(|a, b| a.maturity().total_cmp(&b.maturity()));
+    ensure(
+      instruments
+        .windows(2)
+        .all(|w| w[1].maturity() > w[0].maturity()),
+      "maturities of the instruments must be distinct",
+    )?;
+
+    let times = instruments
+      .iter()
+      .map(ProjectionInstrument::maturity)
+      .collect::<Vec<_>>();
+    let curve = DiscountCurve::bootstrap_nodes(&times, interpolation, &|i, projection| {
+      instruments[i].implied_rate(projection, &self.discount, tenor) - instruments[i].quote()
+    })?;
+
+    Ok(self.with_projection(tenor, curve))
+  }
+
+  /// Projection curve of the index of the tenor, the discount curve if it has none
+  pub fn projection(&self, tenor: f64) -> &DiscountCurve {
+    self
+      .projections
+      .iter()
+      .find(|(other, _)| (other - tenor).abs() < 1e-9)
+      .map_or(&self.discount, |(_, curve)| curve)
+  }
+
+  /// Forward F(t) of the index of the tenor fixing at t
+  pub fn forward(&self, tenor: f64, t: f64) -> f64 {
+    self.projection(tenor).simple_forward(t, t + tenor)
+  }
+
+  /// Basis spread of the forward of the index over the simple OIS forward of its period
+  pub fn basis_spread(&self, tenor: f64, t: f64) -> f64 {
+    self.forward(tenor, t) - self.discount.simple_forward(t, t + tenor)
+  }
+
+  /// Par rate of the swap from start to end with fixed payments of the given frequency
+  /// against the index of the tenor
+  pub fn par_swap_rate(&self, tenor: f64, start: f64, end: f64, frequency: usize) -> f64 {
+    floating_leg(self.projection(tenor), &self.discount, start, end, tenor)
+      / annuity(&self.discount, start, end, 1.0 / frequency as f64)
+  }
+
+  /// Black price of the caplet on the index of the tenor fixing at t and paid at
+  /// t + tenor, projected on its curve and discounted on the OIS curve
+  pub fn caplet(&self, tenor: f64, t: f64, strike: f64, vol: f64) -> f64 {
+    tenor * self.discount.df(t + tenor) * black_call(self.forward(tenor, t), strike, vol, t)
+  }
+
+  /// Black price of the payer swaption expiring at `expiry` into the swap of the length
+  /// against the index of the tenor, the OIS annuity times a call on the par rate
+  pub fn swaption(
+    &self,
+    tenor: f64,
+    expiry: f64,
+    length: f64,
+    frequency: usize,
+    strike: f64,
+    vol: f64,
+  ) -> f64 {
+    let end = expiry + length;
+    let rate = self.par_swap_rate(tenor, expiry, end, frequency);
+
+    annuity(&self.discount, expiry, end, 1.0 / frequency as f64)
+      * black_call(rate, strike, vol, expiry)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::rates::bootstrap::RateInstrument;
+
+  fn ois() -> DiscountCurve {
+    DiscountCurve::bootstrap(
+      &[
+        RateInstrument::Deposit {
+          maturity: 0.25,
+          rate: 0.02,
+        },
+        RateInstrument::Swap {
+          maturity: 1.0,
+          rate: 0.021,
+          frequency: 1,
+        },
+        RateInstrument::Swap {
+          maturity: 5.0,
+          rate: 0.024,
+          frequency: 1,
+        },
+        RateInstrument::Swap {
+          maturity: 10.0,
+          rate: 0.026,
+          frequency: 1,
+        },
+      ],
+      DiscountInterpolation::LogLinear,
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn single_curve_is_the_limit() {
+    let curves = MultiCurve::new(ois());
+    for maturity in [1.0, 5.0, 7.5] {
+      let rate = curves.par_swap_rate(0.25, 0.0, maturity, 1);
+      assert!((rate - curves.discount.par_swap_rate(maturity, 1)).abs() < 1e-12);
+    }
+    assert!(curves.basis_spread(0.5, 2.0).abs() < 1e-15);
+  }
+
+  #[test]
+  fn projection_curve_reprices_its_quotes() {
+    let instruments = [
+      ProjectionInstrument::Deposit {
+        maturity: 0.25,
+        rate: 0.023,
+      },
+      ProjectionInstrument::Fra {
+        start: 0.25,
+        end: 0.5,
+        rate: 0.024,
+      },
+      ProjectionInstrument::Swap {
+        maturity: 2.0,
+        rate: 0.027,
+        frequency: 1,
+      },
+      ProjectionInstrument::Swap {
+        maturity: 5.0,
+        rate: 0.029,
+        frequency: 1,
+      },
+      ProjectionInstrument::BasisSwap {
+        maturity: 10.0,
+        spread: 0.004,
+      },
+    ];
+    let curves = MultiCurve::new(ois())
+      .bootstrap_projection(0.25, &instruments, DiscountInterpolation::LogLinear)
+      .unwrap();
+
+    for instrument in &instruments {
+      let implied = instrument.implied_rate(curves.projection(0.25), &curves.discount, 0.25);
+      assert!(
+        (implied - instrument.quote()).abs() < 1e-10,
+        "{:?}",
+        instrument
+      );
+    }
+
+    // The index forwards are above the OIS ones, so are the swap rates
+    for t in [0.0, 1.0, 4.0, 8.0] {
+      assert!(curves.basis_spread(0.25, t) > 0.0);
+    }
+    assert!(curves.par_swap_rate(0.25, 0.0, 5.0, 1) > curves.discount.par_swap_rate(5.0, 1));
+  }
+
+  #[test]
+  fn constant_spread_prices_the_basis_and_options() {
+    let spread = 0.003;
+    let curves = MultiCurve::new(ois()).with_spread(0.5, spread);
+    let basis = ProjectionInstrument::BasisSwap {
+      maturity: 10.0,
+      spread: 0.0,
+    };
+    let implied = basis.implied_rate(curves.projection(0.5), &curves.discount, 0.5);
+    assert!((implied - spread).abs() < 1e-4, "{}", implied);
+
+    // Deep in the money options are worth their intrinsic value on the OIS discounting
+    let (t, strike) = (2.0, 1e-4);
+    let intrinsic = 0.5 * curves.discount.df(t + 0.5) * (curves.forward(0.5, t) - strike);
+    assert!((curves.caplet(0.5, t, strike, 0.2) - intrinsic).abs() < 1e-12);
+
+    let rate = curves.par_swap_rate(0.5, t, t + 5.0, 1);
+    let annuity = (1..=5)
+      .map(|j| curves.discount.df(t + j as f64))
+      .sum::<f64>();
+    let swaption = curves.swaption(0.5, t, 5.0, 1, strike, 0.2);
+    assert!((swaption - annuity * (rate - strike)).abs() < 1e-12);
+  }
+}
@@ -0,0 +1,382 @@
+//! Nelson-Siegel and Svensson parametric yield curves.
+//!
+//! The zero rate of maturity t is
+//!
+//! z(t) = beta0 + beta1 (1 - e^(-x1)) / x1 + beta2 ((1 - e^(-x1)) / x1 - e^(-x1))
+//!   + beta3 ((1 - e^(-x2)) / x2 - e^(-x2)), x_i = t / lambda_i
+//!
+//! a long-term level beta0, a slope beta1 with the short rate beta0 + beta1 and humps at
+//! the time scales lambda_1 and lambda_2. The Nelson-Siegel curve has beta3 = 0, Svensson
+//! adds the second hump. For fixed lambdas the curve is linear in the betas, so the fit
+//! solves the least squares of the betas on a grid of lambdas and refines the best one
+//! with Levenberg-Marquardt.
+//!
+//! The smooth forward curve is the input of the short rate models fitted to the curve,
+//! [`NelsonSiegelSvensson::hull_white_theta`] is the drift of the Hull-White model and
+//! [`NelsonSiegelSvensson::to_zero_curve`] the curve of CIR++.
+//!
+//! - Nelson, C. R., & Siegel, A. F. (1987). Parsimonious modeling of yield curves.
+//! - Svensson, L. E. O. (1994). Estimating and interpreting forward interest rates: Sweden 1992-1994.
+
+use impl_new_derive::ImplNew;
+use nalgebra::{DMatrix, DVector};
+use ndarray::Array1;
+
+use super::bootstrap::{DiscountCurve, DiscountInterpolation};
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::{
+    curves::{CurveInterpolation, ZeroCurve},
+    volatility::svi::minimize,
+  },
+};
+
+/// Number of humps of the fitted curve
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NelsonSiegelFamily {
+  /// One hump, beta3 = 0
+  #[default]
+  NelsonSiegel,
+  /// Two humps
+  Svensson,
+}
+
+/// Time scales of the grid of the fit, 0.2 to 10 years
+const LAMBDA_GRID: (f64, f64, usize) = (0.2, 10.0, 12);
+
+/// Nelson-Siegel-Svensson curve of continuously compounded zero rates
+#[derive(ImplNew, Clone, Copy, Debug, PartialEq)]
+pub struct NelsonSiegelSvensson {
+  /// Long-term level
+  pub beta0: f64,
+  /// Slope, the short rate is beta0 + beta1
+  pub beta1: f64,
+  /// First hump
+  pub beta2: f64,
+  /// Second hump, 0 for Nelson-Siegel
+  pub beta3: f64,
+  /// Time scale of the first hump in years
+  pub lambda1: f64,
+  /// Time scale of the second hump in years
+  pub lambda2: f64,
+}
+
+impl NelsonSiegelSvensson {
+  /// Nelson-Siegel curve with a single hump
+  pub fn nelson_siegel(beta0: f64, beta1: f64, beta2: f64, lambda: f64) -> Self {
+    Self::new(beta0, beta1, beta2, 0.0, lambda, lambda)
+  }
+
+  /// Zero rate z(t)
+  pub fn zero_rate(&self, t: f64) -> f64 {
+    let (slope, hump1) = loadings(t, self.lambda1);
+    let (_, hump2) = loadings(t, self.lambda2);
+
+    self.beta0 + self.beta1 * slope + self.beta2 * hump1 + self.beta3 * hump2
+  }
+
+  /// Discount factor e^(-z(t) t)
+  pub fn discount(&self, t: f64) -> f64 {
+    (-self.zero_rate(t) * t).exp()
+  }
+
+  /// Instantaneous forward rate f(t) = beta0 + beta1 e^(-x1) + beta2 x1 e^(-x1) + beta3 x2 e^(-x2)
+  pub fn instantaneous_forward(&self, t: f64) -> f64 {
+    let (x1, x2) = (t / self.lambda1, t / self.lambda2);
+
+    self.beta0 + (self.beta1 + self.beta2 * x1) * (-x1).exp() + self.beta3 * x2 * (-x2).exp()
+  }
+
+  /// Continuously compounded forward rate of [t1, t2]
+  pub fn forward_rate(&self, t1: f64, t2: f64) -> f64 {
+    assert!(t2 > t1, "t2 must be after t1");
+    (self.zero_rate(t2) * t2 - self.zero_rate(t1) * t1) / (t2 - t1)
+  }
+
+  /// Par rate of a swap with fixed payments of the given frequency per year, a short
+  /// first period if the maturity is not a multiple of the period
+  pub fn par_rate(&self, maturity: f64, frequency: usize) -> f64 {
+    let period = 1.0 / frequency as f64;
+    let mut annuity = 0.0;
+    let mut end = maturity;
+    while end > 1e-9 {
+      let start = (end - period).max(0.0);
+      annuity += (end - start) * self.discount(end);
+      end -= period;
+    }
+
+    (1.0 - self.discount(maturity)) / annuity
+  }
+
+  /// Drift theta(t) of the Hull-White short rate dr = (theta(t) - alpha r) dt + sigma dW
+  /// fitting the curve, f'(t) + alpha f(t) + sigma^2 (1 - e^(-2 alpha t)) / (2 alpha), with
+  /// the analytic slope of the forward curve
+  pub fn hull_white_theta(&self, t: f64, alpha: f64, sigma: f64) -> f64 {
+    let (x1, x2) = (t / self.lambda1, t / self.lambda2);
+    let slope = (self.beta2 * (1.0 - x1) - self.beta1) / self.lambda1 * (-x1).exp()
+      + self.beta3 * (1.0 - x2) / self.lambda2 * (-x2).exp();
+    let convexity = match alpha.abs() > 1e-12 {
+      true => sigma.powi(2) * (1.0 - (-2.0 * alpha * t).exp()) / (2.0 * alpha),
+      false => sigma.powi(2) * t,
+    };
+
+    slope + alpha * self.instantaneous_forward(t) + convexity
+  }
+
+  /// Zero curve of the rates at the times, e.g. the curve of CIR++
+  pub fn to_zero_curve(&self, times: &Array1<f64>) -> StochasticResult<ZeroCurve> {
+    ZeroCurve::new(
+      times.clone(),
+      times.mapv(|t| self.zero_rate(t)),
+      CurveInterpolation::LinearZero,
+    )
+  }
+
+  /// Discount curve of the discount factors at the times, e.g. the curve of the
+  /// Hull-White model
+  pub fn to_discount_curve(
+    &self,
+    times: &Array1<f64>,
+    interpolation: DiscountInterpolation,
+  ) -> StochasticResult<DiscountCurve> {
+    DiscountCurve::new(
+      times.clone(),
+      times.mapv(|t| self.discount(t)),
+      interpolation,
+    )
+  }
+
+  /// Least squares fit to the zero rates at the times
+  pub fn fit(times: &[f64], rates: &[f64], family: NelsonSiegelFamily) -> StochasticResult<Self> {
+    check_quotes(times, rates, family)?;
+    let curve = grid_fit(times, rates, family);
+    let residuals = |x: &DVector<f64>| {
+      let curve = from_vector(x, family);
+      DVector::from_iterator(
+        times.len(),
+        times
+          .iter()
+          .zip(rates)
+          .map(|(&t, &rate)| curve.zero_rate(t) - rate),
+      )
+    };
+
+    Ok(from_vector(
+      &minimize(to_vector(&curve, family), &residuals),
+      family,
+    ))
+  }
+
+  /// Least squares fit to the par rates of swaps with fixed payments of the given
+  /// frequency, starting from the fit of the par rates as zero rates
+  pub fn fit_par(
+    maturities: &[f64],
+    rates: &[f64],
+    frequency: usize,
+    family: NelsonSiegelFamily,
+  ) -> StochasticResult<Self> {
+    ensure(frequency >= 1, "frequency must be at least 1")?;
+    let start = Self::fit(maturities, rates, family)?;
+    let residuals = |x: &DVector<f64>| {
+      let curve = from_vector(x, family);
+      DVector::from_iterator(
+        maturities.len(),
+        maturities
+          .iter()
+          .zip(rates)
+          .map(|(&t, &rate)| curve.par_rate(t, frequency) - rate),
+      )
+    };
+
+    Ok(from_vector(
+      &minimize(to_vector(&start, family), &residuals),
+      family,
+    ))
+  }
+}
+
+/// Loadings ((1 - e^(-x)) / x, (1 - e^(-x)) / x - e^(-x)) of x = t / lambda
+fn loadings(t: f64, lambda: f64) -> (f64, f64) {
+  let x = t / lambda;
+  let slope = match x > 1e-10 {
+    true => (1.0 - (-x).exp()) / x,
+    false => 1.0,
+  };
+
+  (slope, slope - (-x).exp())
+}
+
+fn check_quotes(times: &[f64], rates: &[f64], family: NelsonSiegelFamily) -> StochasticResult<()> {
+  let parameters = match family {
+    NelsonSiegelFamily::NelsonSiegel => 4,
+    NelsonSiegelFamily::Svensson => 6,
+  };
+  ensure(
+    times.len() == rates.len(),
+    "times and rates must have the same length",
+  )?;
+  ensure(
+    times.len() >= parameters,
+    "at least as many quotes as parameters are needed",
+  )?;
+  ensure(times.iter().all(|&t| t > 0.0), "times must be positive")
+}
+
+/// Best least squares betas over the grid of time scales, lambda1 < lambda2 for Svensson
+fn grid_fit(times: &[f64], rates: &[f64], family: NelsonSiegelFamily) -> NelsonSiegelSvensson {
+  let (low, high, points) = LAMBDA_GRID;
+  let grid = (0..points)
+    .map(|k| low * (high / low).powf(k as f64 / (points - 1) as f64))
+    .collect::<Vec<_>>();
+  let pairs = match family {
+    NelsonSiegelFamily::NelsonSiegel => grid.iter().map(|&l| (l, l)).collect::<Vec<_>>(),
+    NelsonSiegelFamily::Svensson => grid
+      .iter()
+      .enumerate()
+      .flat_map(|(i, &l1)| grid[i + 1..].iter().map(move |&l2| (l1, l2)))
+      .collect(),
+  };
+  let columns = match family {
+    NelsonSiegelFamily::NelsonSiegel => 3,
+    NelsonSiegelFamily::Svensson => 4,
+  };
+  let y = DVector::from_column_slice(rates);
+
+  pairs
+    .into_iter()
+    .filter_map(|(lambda1, lambda2)| {
+      let x = DMatrix::from_fn(times.len(), columns, |i, j| {
+        let (slope, hump1) = loadings(times[i], lambda1);
+        match j {
+          0 => 1.0,
+          1 => slope,
+          2 => hump1,
+          _ => loadings(times[i], lambda2).1,
+        }
+      });
+      let beta = x.clone().svd(true, true).solve(&y, 1e-12).ok()?;
+      let curve = NelsonSiegelSvensson::new(
+        beta[0],
+        beta[1],
+        beta[2],
+        if columns == 4 { beta[3] } else { 0.0 },
+        lambda1,
+        lambda2,
+      );
+
+      Some(((x * beta - &y).norm_squared(), curve))
+    })
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .map(|(_, curve)| curve)
+    .unwrap()
+}
+
+/// (betas, ln lambdas) of the family
+fn to_vector(curve: &NelsonSiegelSvensson, family: NelsonSiegelFamily) -> DVector<f64> {
+  match family {
+    NelsonSiegelFamily::NelsonSiegel => DVector::from_vec(vec![
+      curve.beta0,
+      curve.beta1,
+      curve.beta2,
+      curve.lambda1.ln(),
+    ]),
+    NelsonSiegelFamily::Svensson => DVector::from_vec(vec![
+      curve.beta0,
+      curve.beta1,
+      curve.beta2,
+      curve.beta3,
+      curve.lambda1.ln(),
+      curve.lambda2.ln(),
+    ]),
+  }
+}
+
+fn from_vector(x: &DVector<f64>, family: NelsonSiegelFamily) -> NelsonSiegelSvensson {
+  match family {
+    NelsonSiegelFamily::NelsonSiegel => {
+      NelsonSiegelSvensson::nelson_siegel(x[0], x[1], x[2], x[3].exp())
+    }
+    NelsonSiegelFamily::Svensson => {
+      NelsonSiegelSvensson::new(x[0], x[1], x[2], x[3], x[4].exp(), x[5].exp())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::rates::hull_white::HullWhiteModel;
+
+  const TIMES: [f64; 11] = [0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0];
+
+  #[test]
+  fn fits_zero_rates() {
+    let svensson = NelsonSiegelSvensson::new(0.04, -0.02, 0.01, 0.015, 1.5, 6.0);
+    let rates = TIMES.map(|t| svensson.zero_rate(t));
+    let fitted = NelsonSiegelSvensson::fit(&TIMES, &rates, NelsonSiegelFamily::Svensson).unwrap();
+    for (&t, &rate) in TIMES.iter().zip(&rates) {
+      assert!((fitted.zero_rate(t) - rate).abs() < 1e-8);
+    }
+
+    // A single hump leaves a residual on the Svensson curve
+    let nelson_siegel =
+      NelsonSiegelSvensson::fit(&TIMES, &rates, NelsonSiegelFamily::NelsonSiegel).unwrap();
+    assert_eq!(nelson_siegel.beta3, 0.0);
+    let error = TIMES
+      .iter()
+      .zip(&rates)
+      .map(|(&t, &rate)| (nelson_siegel.zero_rate(t) - rate).abs())
+      .fold(0.0, f64::max);
+    assert!(error > 1e-5 && error < 2e-3, "{}", error);
+  }
+
+  #[test]
+  fn fits_par_rates() {
+    let curve = NelsonSiegelSvensson::nelson_siegel(0.045, -0.025, 0.02, 2.0);
+    let maturities = [1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0];
+    let rates = maturities.map(|t| curve.par_rate(t, 2));
+    let fitted =
+      NelsonSiegelSvensson::fit_par(&maturities, &rates, 2, NelsonSiegelFamily::NelsonSiegel)
+        .unwrap();
+    for (&t, &rate) in maturities.iter().zip(&rates) {
+      assert!((fitted.par_rate(t, 2) - rate).abs() < 1e-9);
+    }
+    assert!((fitted.lambda1 - 2.0).abs() < 1e-4);
+  }
+
+  #[test]
+  fn forwards_and_hull_white_theta() {
+    let curve = NelsonSiegelSvensson::new(0.04, -0.02, 0.01, 0.015, 1.5, 6.0);
+    let h = 1e-5;
+    for t in [0.5, 2.0, 8.0] {
+      // f(t) = d(z(t) t) / dt
+      let forward =
+        (curve.zero_rate(t + h) * (t + h) - curve.zero_rate(t - h) * (t - h)) / (2.0 * h);
+      assert!((curve.instantaneous_forward(t) - forward).abs() < 1e-9);
+      assert!((curve.forward_rate(t, t + h) - curve.instantaneous_forward(t)).abs() < 1e-6);
+
+      let slope =
+        (curve.instantaneous_forward(t + h) - curve.instantaneous_forward(t - h)) / (2.0 * h);
+      let theta = slope
+        + 0.1 * curve.instantaneous_forward(t)
+        + 0.01f64.powi(2) * (1.0 - (-0.2 * t).exp()) / 0.2;
+      assert!((curve.hull_white_theta(t, 0.1, 0.01) - theta).abs() < 1e-9);
+    }
+
+    // The Hull-White model on the sampled curve reprices its discount factors
+    let times = Array1::linspace(0.25, 30.0, 120);
+    let model = HullWhiteModel::new(
+      curve
+        .to_discount_curve(&times, DiscountInterpolation::MonotoneConvex)
+        .unwrap(),
+      0.1,
+      0.01,
+    );
+    let r0 = model.shift(0.0);
+    for t in [1.0, 5.0, 12.0] {
+      assert!((model.bond_price(0.0, t, r0) - curve.discount(t)).abs() < 1e-10);
+    }
+    let zero = curve.to_zero_curve(&times).unwrap();
+    assert!((zero.zero_rate(5.0) - curve.zero_rate(5.0)).abs() < 1e-5);
+  }
+}
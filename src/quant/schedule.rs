@@ -0,0 +1,572 @@
+//! Cashflow schedules from market conventions.
+//!
+//! A [`Schedule`] rolls the period dates from the effective date to the termination date
+//! by the frequency, adjusts them to business days and gives every period its fixing and
+//! payment date and its accrual fraction of the day count convention. Bonds, swaps and
+//! structured products are defined by their schedules instead of hand-written date
+//! arithmetic, and the times of the curves and the pricers are the Act/365 year fractions
+//! from the valuation date, like [`Time`](super::r#trait::Time).
+//!
+//! The dates are rolled from the termination date backward with a stub at the front, the
+//! market default of swaps, or from the effective date forward with a stub at the back.
+//! The rolled dates are computed from the anchor date, so 31 January rolls to 30 April and
+//! 31 July, and with the end-of-month rule 30 April rolls to the month ends as well.
+//!
+//! - ISDA (2006). 2006 ISDA Definitions, sections 4.12 (business day conventions) and 4.16
+//!   (day count fractions).
+
+use chrono::{Datelike, Months, NaiveDate, Weekday};
+use ndarray::Array1;
+
+use crate::error::{ensure, StochasticResult};
+
+/// Number of periods per year
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+  Annual,
+  SemiAnnual,
+  #[default]
+  Quarterly,
+  Monthly,
+}
+
+impl Frequency {
+  /// Length of a period in months
+  pub fn months(self) -> u32 {
+    match self {
+      Frequency::Annual => 12,
+      Frequency::SemiAnnual => 6,
+      Frequency::Quarterly => 3,
+      Frequency::Monthly => 1,
+    }
+  }
+}
+
+/// Day count convention of the accrual fractions
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayCount {
+  /// Days / 360, money market
+  Act360,
+  /// Days / 365
+  #[default]
+  Act365Fixed,
+  /// 30/360 bond basis, the days of a month capped at 30
+  Thirty360,
+  /// Days of every calendar year divided by the length of that year
+  ActActISDA,
+}
+
+impl DayCount {
+  /// Accrual fraction of the period from start to end
+  pub fn year_fraction(self, start: NaiveDate, end: NaiveDate) -> f64 {
+    let days = |from: NaiveDate, to: NaiveDate| (to - from).num_days() as f64;
+
+    match self {
+      DayCount::Act360 => days(start, end) / 360.0,
+      DayCount::Act365Fixed => days(start, end) / 365.0,
+      DayCount::Thirty360 => {
+        let d1 = start.day().min(30);
+        let d2 = match (end.day(), d1) {
+          (31, 30) => 30,
+          (d2, _) => d2,
+        };
+        (360 * (end.year() - start.year())
+          + 30 * (end.month() as i32 - start.month() as i32)
+          + (d2 as i32 - d1 as i32)) as f64
+          / 360.0
+      }
+      DayCount::ActActISDA => {
+        if end < start {
+          return -self.year_fraction(end, start);
+        }
+        (start.year()..=end.year())
+          .map(|year| {
+            let first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+            let next = NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap();
+            days(start.max(first), end.min(next)) / days(first, next)
+          })
+          .sum()
+      }
+    }
+  }
+}
+
+/// Adjustment of the dates falling on weekends and holidays
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusinessDayConvention {
+  Unadjusted,
+  /// Next business day
+  Following,
+  /// Next business day, the previous one if the next is in the next month
+  #[default]
+  ModifiedFollowing,
+  /// Previous business day
+  Preceding,
+}
+
+/// Position and length of the irregular period if the periods do not divide the schedule
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StubRule {
+  /// Rolled backward from the termination date, a short first period
+  #[default]
+  ShortFront,
+  /// Rolled backward, the stub is merged into the first regular period
+  LongFront,
+  /// Rolled forward from the effective date, a short last period
+  ShortBack,
+  /// Rolled forward, the stub is merged into the last regular period
+  LongBack,
+}
+
+/// Accrual period of a schedule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Period {
+  /// Adjusted start of the accrual
+  pub start: NaiveDate,
+  /// Adjusted end of the accrual
+  pub end: NaiveDate,
+  /// Fixing date of a floating rate set in advance
+  pub fixing: NaiveDate,
+  /// Payment date, the adjusted end shifted by the payment lag
+  pub payment: NaiveDate,
+  /// Accrual fraction of the day count convention
+  pub accrual: f64,
+}
+
+/// Payment of a fixed amount
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cashflow {
+  /// Payment date
+  pub date: NaiveDate,
+  /// Amount paid at the date
+  pub amount: f64,
+}
+
+/// Schedule of the accrual periods between the effective and the termination date
+#[derive(Debug, Clone)]
+pub struct Schedule {
+  pub effective: NaiveDate,
+  pub termination: NaiveDate,
+  pub frequency: Frequency,
+  pub day_count: DayCount,
+  pub convention: BusinessDayConvention,
+  pub stub: StubRule,
+  /// Roll to the month ends if the anchor date is a month end
+  pub end_of_month: bool,
+  /// Business days from the end of a period to its payment
+  pub payment_lag: u32,
+  /// Business days from the fixing to the start of a period
+  pub fixing_lag: u32,
+  /// Holidays besides the weekends
+  pub holidays: Vec<NaiveDate>,
+}
+
+impl Schedule {
+  /// Schedule with Act/365 accruals, modified following adjustments, a short front stub and
+  /// no lags
+  pub fn new(
+    effective: NaiveDate,
+    termination: NaiveDate,
+    frequency: Frequency,
+  ) -> StochasticResult<Self> {
+    ensure(
+      termination > effective,
+      "termination must be after the effective date",
+    )?;
+
+    Ok(Self {
+      effective,
+      termination,
+      frequency,
+      day_count: DayCount::default(),
+      convention: BusinessDayConvention::default(),
+      stub: StubRule::default(),
+      end_of_month: false,
+      payment_lag: 0,
+      fixing_lag: 0,
+      holidays: Vec::new(),
+    })
+  }
+
+  /// Replace the day count convention
+  pub fn with_day_count(mut self, day_count: DayCount) -> Self {
+    self.day_count = day_count;
+    self
+  }
+
+  /// Replace the business day convention
+  pub fn with_convention(mut self, convention: BusinessDayConvention) -> Self {
+    self.convention = convention;
+    self
+  }
+
+  /// Replace the stub rule
+  pub fn with_stub(mut self, stub: StubRule) -> Self {
+    self.stub = stub;
+    self
+  }
+
+  /// Set the end-of-month rule
+  pub fn with_end_of_month(mut self, end_of_month: bool) -> Self {
+    self.end_of_month = end_of_month;
+    self
+  }
+
+  /// Pay the given number of business days after the end of every period
+  pub fn with_payment_lag(mut self, days: u32) -> Self {
+    self.payment_lag = days;
+    self
+  }
+
+  /// Fix the given number of business days before the start of every period
+  pub fn with_fixing_lag(mut self, days: u32) -> Self {
+    self.fixing_lag = days;
+    self
+  }
+
+  /// Add holidays to the weekends
+  pub fn with_holidays(mut self, holidays: &[NaiveDate]) -> Self {
+    self.holidays.extend_from_slice(holidays);
+    self
+  }
+
+  /// Whether the date is neither a weekend nor a holiday
+  pub fn is_business_day(&self, date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+  }
+
+  /// Date moved by the given number of business days, backward if negative
+  pub fn add_business_days(&self, mut date: NaiveDate, days: i64) -> NaiveDate {
+    let step = chrono::Duration::days(days.signum());
+    for _ in 0..days.unsigned_abs() {
+      date += step;
+      while !self.is_business_day(date) {
+        date += step;
+      }
+    }
+    date
+  }
+
+  /// Date adjusted by the business day convention
+  pub fn adjust(&self, date: NaiveDate) -> NaiveDate {
+    let roll = |step: i64| {
+      let mut date = date;
+      while !self.is_business_day(date) {
+        date += chrono::Duration::days(step);
+      }
+      date
+    };
+
+    match self.convention {
+      BusinessDayConvention::Unadjusted => date,
+      BusinessDayConvention::Following => roll(1),
+      BusinessDayConvention::Preceding => roll(-1),
+      BusinessDayConvention::ModifiedFollowing => match roll(1) {
+        following if following.month() == date.month() => following,
+        _ => roll(-1),
+      },
+    }
+  }
+
+  /// Unadjusted period dates from the effective to the termination date
+  pub fn dates(&self) -> Vec<NaiveDate> {
+    let backward = matches!(self.stub, StubRule::ShortFront | StubRule::LongFront);
+    let (anchor, stop) = match backward {
+      true => (self.termination, self.effective),
+      false => (self.effective, self.termination),
+    };
+    let end_of_month = self.end_of_month && is_month_end(anchor);
+    let roll = |k: u32| {
+      let months = Months::new(k * self.frequency.months());
+      let date = match backward {
+        true => anchor.checked_sub_months(months),
+        false => anchor.checked_add_months(months),
+      }
+      .expect("date out of range");
+      match end_of_month {
+        true => month_end(date),
+        false => date,
+      }
+    };
+
+    let mut dates = vec![anchor];
+    let mut stub = true;
+    for k in 1.. {
+      let date = roll(k);
+      if date == stop {
+        stub = false;
+      }
+      if (backward && date <= stop) || (!backward && date >= stop) {
+        break;
+      }
+      dates.push(date);
+    }
+    if stub && dates.len() > 1 && matches!(self.stub, StubRule::LongFront | StubRule::LongBack) {
+      dates.pop();
+    }
+    dates.push(stop);
+
+    if backward {
+      dates.reverse();
+    }
+    dates
+  }
+
+  /// Accrual periods with the adjusted dates
+  pub fn periods(&self) -> Vec<Period> {
+    let dates = self
+      .dates()
+      .into_iter()
+      .map(|date| self.adjust(date))
+      .collect::<Vec<_>>();
+
+    dates
+      .windows(2)
+      .map(|w| Period {
+        start: w[0],
+        end: w[1],
+        fixing: self.add_business_days(w[0], -(self.fixing_lag as i64)),
+        payment: self.add_business_days(w[1], self.payment_lag as i64),
+        accrual: self.day_count.year_fraction(w[0], w[1]),
+      })
+      .collect()
+  }
+
+  /// Accrual fractions of the periods
+  pub fn accruals(&self) -> Array1<f64> {
+    self.periods().iter().map(|p| p.accrual).collect()
+  }
+
+  /// Act/365 times of the payments from the valuation date
+  pub fn payment_times(&self, valuation: NaiveDate) -> Array1<f64> {
+    self
+      .periods()
+      .iter()
+      .map(|p| DayCount::Act365Fixed.year_fraction(valuation, p.payment))
+      .collect()
+  }
+
+  /// Payments of a fixed rate leg, notional times rate times the accrual of every period
+  pub fn fixed_leg(&self, rate: f64, notional: f64) -> Vec<Cashflow> {
+    self
+      .periods()
+      .iter()
+      .map(|p| Cashflow {
+        date: p.payment,
+        amount: notional * rate * p.accrual,
+      })
+      .collect()
+  }
+
+  /// Payments of a fixed coupon bond, the coupons and the notional at the last payment
+  pub fn bond_cashflows(&self, coupon: f64, notional: f64) -> Vec<Cashflow> {
+    let mut cashflows = self.fixed_leg(coupon, notional);
+    if let Some(last) = cashflows.last_mut() {
+      last.amount += notional;
+    }
+    cashflows
+  }
+
+  /// Periods (start, end) in year fractions rolled by the period length with the stub
+  /// rule, for instruments given by their times instead of their dates
+  pub fn year_fraction_periods(
+    start: f64,
+    end: f64,
+    period: f64,
+    stub: StubRule,
+  ) -> Vec<(f64, f64)> {
+    let backward = matches!(stub, StubRule::ShortFront | StubRule::LongFront);
+    let (anchor, stop, step) = match backward {
+      true => (end, start, -period),
+      false => (start, end, period),
+    };
+
+    let mut times = vec![anchor];
+    let mut t = anchor + step;
+    while (backward && t > stop + 1e-9) || (!backward && t < stop - 1e-9) {
+      times.push(t);
+      t += step;
+    }
+    let regular = (t - stop).abs() <= 1e-9;
+    if !regular && times.len() > 1 && matches!(stub, StubRule::LongFront | StubRule::LongBack) {
+      times.pop();
+    }
+    times.push(stop);
+
+    if backward {
+      times.reverse();
+    }
+    times.windows(2).map(|w| (w[0], w[1])).collect()
+  }
+}
+
+/// Whether the date is the last day of its month
+fn is_month_end(date: NaiveDate) -> bool {
+  month_end(date) == date
+}
+
+/// Last day of the month of the date
+fn month_end(date: NaiveDate) -> NaiveDate {
+  let first = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
+  first
+    .checked_add_months(Months::new(1))
+    .unwrap()
+    .pred_opt()
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+  }
+
+  #[test]
+  fn quarterly_schedule_with_front_stub() {
+    let schedule = Schedule::new(date(2024, 3, 20), date(2025, 3, 31), Frequency::Quarterly)
+      .unwrap()
+      .with_end_of_month(true)
+      .with_day_count(DayCount::Act360)
+      .with_payment_lag(2)
+      .with_fixing_lag(2)
+      .with_holidays(&[date(2025, 1, 1)]);
+
+    assert_eq!(
+      schedule.dates(),
+      [
+        date(2024, 3, 20),
+        date(2024, 3, 31),
+        date(2024, 6, 30),
+        date(2024, 9, 30),
+        date(2024, 12, 31),
+        date(2025, 3, 31),
+      ]
+    );
+
+    let periods = schedule.periods();
+    // Sundays at month ends are moved back by the modified following convention
+    assert_eq!(periods[0].end, date(2024, 3, 29));
+    assert_eq!(periods[1].end, date(2024, 6, 28));
+    assert_eq!(periods[0].fixing, date(2024, 3, 18));
+    assert_eq!(periods[0].payment, date(2024, 4, 2));
+    // 2 business days after 31 December skip the holiday
+    assert_eq!(periods[3].payment, date(2025, 1, 3));
+    assert_eq!(periods[0].accrual, 9.0 / 360.0);
+
+    let long = schedule.clone().with_stub(StubRule::LongFront);
+    assert_eq!(long.dates()[..2], [date(2024, 3, 20), date(2024, 6, 30)]);
+
+    let following = schedule.with_convention(BusinessDayConvention::Following);
+    assert_eq!(following.periods()[0].end, date(2024, 4, 1));
+  }
+
+  #[test]
+  fn forward_roll_with_back_stub() {
+    let schedule = Schedule::new(date(2024, 1, 31), date(2024, 12, 15), Frequency::Quarterly)
+      .unwrap()
+      .with_stub(StubRule::ShortBack)
+      .with_convention(BusinessDayConvention::Unadjusted);
+
+    // Rolled from the anchor, 31 January to 30 April and back to 31 July
+    assert_eq!(
+      schedule.dates(),
+      [
+        date(2024, 1, 31),
+        date(2024, 4, 30),
+        date(2024, 7, 31),
+        date(2024, 10, 31),
+        date(2024, 12, 15),
+      ]
+    );
+    assert_eq!(
+      schedule.clone().with_stub(StubRule::LongBack).dates(),
+      [
+        date(2024, 1, 31),
+        date(2024, 4, 30),
+        date(2024, 7, 31),
+        date(2024, 12, 15),
+      ]
+    );
+
+    let april = Schedule {
+      effective: date(2024, 4, 30),
+      ..schedule
+    };
+    assert_eq!(april.dates()[1..3], [date(2024, 7, 30), date(2024, 10, 30)]);
+    let end_of_month = april.with_end_of_month(true);
+    assert_eq!(
+      end_of_month.dates()[1..3],
+      [date(2024, 7, 31), date(2024, 10, 31)]
+    );
+  }
+
+  #[test]
+  fn day_count_fractions() {
+    let (start, end) = (date(2023, 7, 1), date(2024, 7, 1));
+    assert_eq!(DayCount::Act360.year_fraction(start, end), 366.0 / 360.0);
+    assert_eq!(
+      DayCount::Act365Fixed.year_fraction(start, end),
+      366.0 / 365.0
+    );
+    assert_eq!(DayCount::Thirty360.year_fraction(start, end), 1.0);
+    let act_act = DayCount::ActActISDA.year_fraction(start, end);
+    assert!((act_act - (184.0 / 365.0 + 182.0 / 366.0)).abs() < 1e-15);
+
+    let (start, end) = (date(2024, 1, 31), date(2024, 3, 31));
+    assert_eq!(DayCount::Thirty360.year_fraction(start, end), 60.0 / 360.0);
+    assert_eq!(
+      DayCount::Thirty360.year_fraction(date(2024, 1, 30), date(2024, 2, 29)),
+      29.0 / 360.0
+    );
+  }
+
+  #[test]
+  fn bond_cashflows_repay_the_notional() {
+    let schedule = Schedule::new(date(2024, 1, 15), date(2026, 1, 15), Frequency::SemiAnnual)
+      .unwrap()
+      .with_day_count(DayCount::Thirty360);
+    let cashflows = schedule.bond_cashflows(0.04, 100.0);
+
+    assert_eq!(cashflows.len(), 4);
+    assert!(cashflows[..3]
+      .iter()
+      .all(|c| (c.amount - 2.0).abs() < 1e-12));
+    assert!((cashflows[3].amount - 102.0).abs() < 1e-12);
+    assert_eq!(cashflows[3].date, date(2026, 1, 15));
+  }
+
+  #[test]
+  fn year_fraction_periods_place_the_stub() {
+    let close = |a: &[(f64, f64)], b: &[(f64, f64)]| {
+      a.len() == b.len()
+        && a
+          .iter()
+          .zip(b)
+          .all(|(x, y)| (x.0 - y.0).abs() < 1e-12 && (x.1 - y.1).abs() < 1e-12)
+    };
+    let periods = |stub| Schedule::year_fraction_periods(0.0, 1.25, 0.5, stub);
+
+    assert!(close(
+      &periods(StubRule::ShortFront),
+      &[(0.0, 0.25), (0.25, 0.75), (0.75, 1.25)]
+    ));
+    assert!(close(
+      &periods(StubRule::LongFront),
+      &[(0.0, 0.75), (0.75, 1.25)]
+    ));
+    assert!(close(
+      &periods(StubRule::ShortBack),
+      &[(0.0, 0.5), (0.5, 1.0), (1.0, 1.25)]
+    ));
+    assert!(close(
+      &periods(StubRule::LongBack),
+      &[(0.0, 0.5), (0.5, 1.25)]
+    ));
+    assert_eq!(
+      Schedule::year_fraction_periods(0.0, 2.0, 0.5, StubRule::LongFront).len(),
+      4
+    );
+  }
+}
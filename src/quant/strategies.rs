@@ -1 +1,2 @@
 pub mod delta_hedge;
+pub mod execution;
@@ -0,0 +1,246 @@
+//! Bid and ask prices around simulated mid prices.
+//!
+//! [`SpreadOverlay`] turns a simulated mid price path into bid and ask paths, so a strategy
+//! backtested on simulated data buys at the ask and sells at the bid. The relative spread
+//! of every step comes from a [`SpreadModel`]
+//!
+//! - constant,
+//! - linear in the realized volatility of the recent log-returns, the inventory risk of the
+//!   market maker grows with the volatility (Stoll, 1978),
+//! - linear in 1 / sqrt(volume), liquid steps are cheaper to trade (Amihud & Mendelson,
+//!   1986).
+//!
+//! A proportional fee is charged on top of the spread in [`BidAskPath::transaction_costs`].
+//!
+//! - Stoll, H. R. (1978). The supply of dealer services in securities markets.
+//! - Amihud, Y., & Mendelson, H. (1986). Asset pricing and the bid-ask spread.
+
+use impl_new_derive::ImplNew;
+use ndarray::{s, Array1, ArrayView1};
+
+/// Relative bid-ask spread (ask - bid) / mid of the steps
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpreadModel {
+  /// The same spread at every step
+  Constant { spread: f64 },
+  /// base + sensitivity * sigma, sigma the standard deviation of the last window
+  /// log-returns of the mid, the first window ones for the first steps
+  VolatilityLinked {
+    base: f64,
+    sensitivity: f64,
+    window: usize,
+  },
+  /// base + impact / sqrt(volume) with the traded volume of every step
+  VolumeLinked {
+    base: f64,
+    impact: f64,
+    volume: Array1<f64>,
+  },
+}
+
+impl SpreadModel {
+  /// Relative spreads along the mid path
+  pub fn spreads(&self, mid: ArrayView1<f64>) -> Array1<f64> {
+    let n = mid.len();
+    match self {
+      SpreadModel::Constant { spread } => Array1::from_elem(n, *spread),
+      SpreadModel::VolatilityLinked {
+        base,
+        sensitivity,
+        window,
+      } => {
+        assert!(*window >= 2, "window must be at least 2");
+        let returns = Array1::from_shape_fn(n.saturating_sub(1), |i| (mid[i + 1] / mid[i]).ln());
+        let window = (*window).min(returns.len());
+
+        Array1::from_shape_fn(n, |i| {
+          let end = i.clamp(window, returns.len());
+          let sigma = match window >= 2 {
+            true => returns.slice(s![end - window..end]).std(1.0),
+            false => 0.0,
+          };
+          base + sensitivity * sigma
+        })
+      }
+      SpreadModel::VolumeLinked {
+        base,
+        impact,
+        volume,
+      } => {
+        assert_eq!(volume.len(), n, "a volume is needed at every step");
+        volume.mapv(|v| match v > 0.0 {
+          true => base + impact / v.sqrt(),
+          false => f64::INFINITY,
+        })
+      }
+    }
+  }
+}
+
+/// Overlay of bid and ask prices on simulated mid prices
+#[derive(ImplNew, Clone, Debug)]
+pub struct SpreadOverlay {
+  /// Model of the relative spread
+  pub model: SpreadModel,
+  /// Tick size, the minimal spread and the rounding of the quotes, no rounding if None
+  pub tick: Option<f64>,
+  /// Proportional fee of the traded notional
+  pub fee: Option<f64>,
+}
+
+impl SpreadOverlay {
+  /// Bid and ask paths, mid -+ spread * mid / 2 with the bids rounded down and the asks up
+  /// to the tick
+  pub fn apply(&self, mid: ArrayView1<f64>) -> BidAskPath {
+    let spreads = self.model.spreads(mid);
+    let half_spread = |i: usize| {
+      let half = 0.5 * spreads[i] * mid[i];
+      match self.tick {
+        Some(tick) => half.max(0.5 * tick),
+        None => half,
+      }
+    };
+
+    let round = |x: f64, up: bool| match self.tick {
+      Some(tick) if up => (x / tick).ceil() * tick,
+      Some(tick) => (x / tick).floor() * tick,
+      None => x,
+    };
+
+    BidAskPath {
+      mid: mid.to_owned(),
+      bid: Array1::from_shape_fn(mid.len(), |i| round(mid[i] - half_spread(i), false)),
+      ask: Array1::from_shape_fn(mid.len(), |i| round(mid[i] + half_spread(i), true)),
+      fee: self.fee.unwrap_or(0.0),
+    }
+  }
+}
+
+/// Bid, ask and mid prices of a simulated path
+#[derive(Clone, Debug)]
+pub struct BidAskPath {
+  pub mid: Array1<f64>,
+  pub bid: Array1<f64>,
+  pub ask: Array1<f64>,
+  /// Proportional fee of the traded notional
+  pub fee: f64,
+}
+
+impl BidAskPath {
+  /// Absolute spreads ask - bid
+  pub fn spread(&self) -> Array1<f64> {
+    &self.ask - &self.bid
+  }
+
+  /// Price per unit of buying (quantity > 0) or selling (quantity < 0) at step i, the fee
+  /// included
+  pub fn execution_price(&self, i: usize, quantity: f64) -> f64 {
+    match quantity >= 0.0 {
+      true => self.ask[i] * (1.0 + self.fee),
+      false => self.bid[i] * (1.0 - self.fee),
+    }
+  }
+
+  /// Costs of trading to the positions of the steps from no position, relative to trading
+  /// at the mid
+  ///
+  /// sum_i |x(i) - x(i - 1)| |p(i) - mid(i)|, with p the execution price of the trade
+  pub fn transaction_costs(&self, positions: ArrayView1<f64>) -> f64 {
+    assert_eq!(
+      positions.len(),
+      self.mid.len(),
+      "a position is needed at every step"
+    );
+
+    let mut previous = 0.0;
+    positions
+      .iter()
+      .enumerate()
+      .map(|(i, &position)| {
+        let quantity = position - previous;
+        previous = position;
+        quantity.abs() * (self.execution_price(i, quantity) - self.mid[i]).abs()
+      })
+      .sum()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::{array, concatenate, Axis};
+
+  use super::*;
+  use crate::stochastic::{diffusion::gbm::GBM, Sampling};
+
+  fn mid(sigma: f64, n: usize) -> Array1<f64> {
+    GBM::new(
+      0.0,
+      sigma,
+      n,
+      Some(100.0),
+      Some(1.0),
+      None,
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    )
+    .sample()
+  }
+
+  #[test]
+  fn constant_spread_with_ticks_and_costs() {
+    let mid = mid(0.2, 253);
+    let path = SpreadOverlay::new(
+      SpreadModel::Constant { spread: 0.001 },
+      Some(0.01),
+      Some(0.0005),
+    )
+    .apply(mid.view());
+
+    for i in 0..mid.len() {
+      assert!(path.bid[i] < mid[i] && mid[i] < path.ask[i]);
+      assert!(path.ask[i] - path.bid[i] >= 0.001 * mid[i] - 1e-9);
+      assert!(path.ask[i] - path.bid[i] <= 0.001 * mid[i] + 0.02 + 1e-9);
+      assert!((path.bid[i] / 0.01 - (path.bid[i] / 0.01).round()).abs() < 1e-6);
+    }
+
+    // A round trip of one unit costs the spread and the fees
+    let mut positions = Array1::zeros(mid.len());
+    positions[10] = 1.0;
+    let cost = path.transaction_costs(positions.view());
+    let expected =
+      path.ask[10] * (1.0 + path.fee) - path.bid[11] * (1.0 - path.fee) - (mid[10] - mid[11]);
+    assert!((cost - expected).abs() < 1e-9);
+    assert!(cost > 0.0);
+  }
+
+  #[test]
+  fn spreads_widen_with_volatility_and_narrow_with_volume() {
+    // A calm year followed by a turbulent one
+    let calm = mid(0.1, 253);
+    let turbulent = mid(0.6, 253) * calm[252] / 100.0;
+    let mid = concatenate![Axis(0), calm, turbulent.slice(s![1..])];
+
+    let model = SpreadModel::VolatilityLinked {
+      base: 0.0005,
+      sensitivity: 1.0,
+      window: 20,
+    };
+    let spreads = model.spreads(mid.view());
+    assert!(spreads.iter().all(|&s| s >= 0.0005));
+    let calm_spread = spreads.slice(s![20..253]).mean().unwrap();
+    let turbulent_spread = spreads.slice(s![273..]).mean().unwrap();
+    assert!(turbulent_spread > 3.0 * calm_spread);
+
+    let volume = array![100.0, 10_000.0, 1_000_000.0];
+    let model = SpreadModel::VolumeLinked {
+      base: 0.0001,
+      impact: 0.01,
+      volume,
+    };
+    let spreads = model.spreads(array![100.0, 100.0, 100.0].view());
+    assert!(spreads[0] > spreads[1] && spreads[1] > spreads[2]);
+    assert!((spreads[1] - 0.0002).abs() < 1e-12);
+  }
+}
@@ -0,0 +1,337 @@
+//! Synthetic option chains with known model parameters.
+//!
+//! [`SyntheticChain`] prices a grid of maturities and strikes under the Heston or the
+//! Bates model and turns the model prices into market-like quotes: bid and ask around a
+//! noisy mid, rounded to the tick, without the quotes that have no bid and with randomly
+//! missing quotes. Calibrators can then be tested against the known parameters.
+
+use std::f64::consts::FRAC_1_PI;
+
+use impl_new_derive::ImplNew;
+use num_complex::Complex64;
+use quadrature::double_exponential;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+use crate::{
+  quant::{calibration::heston::HestonParams, OptionType},
+  stochastic::{
+    noise::cgns::CGNS,
+    volatility::{heston::Heston, HestonPow},
+  },
+};
+
+/// Model generating the prices of the chain
+#[derive(Clone, Debug)]
+pub enum ChainModel {
+  /// Heston model
+  Heston(HestonParams),
+  /// Bates model, Heston with log-normal jumps of the price
+  Bates {
+    heston: HestonParams,
+    /// Jump intensity
+    lambda: f64,
+    /// Mean of the log jump size
+    mu_j: f64,
+    /// Standard deviation of the log jump size
+    sigma_j: f64,
+  },
+}
+
+impl ChainModel {
+  /// Characteristic function of ln(S(tau) / S(0)) under the risk-neutral measure
+  fn log_return_cf(&self, w: Complex64, r: f64, q: f64, tau: f64) -> Complex64 {
+    let params = match self {
+      ChainModel::Heston(params) => params,
+      ChainModel::Bates { heston, .. } => heston,
+    };
+    let heston = Heston::new(
+      Some(1.0),
+      Some(params.v0),
+      params.kappa,
+      params.theta,
+      params.sigma,
+      params.rho,
+      r - q,
+      2,
+      Some(tau),
+      HestonPow::Sqrt,
+      None,
+      None,
+      None,
+      CGNS::new(params.rho, 1, Some(tau), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let cf = heston.log_return_cf(w);
+
+    match self {
+      ChainModel::Heston(_) => cf,
+      ChainModel::Bates {
+        lambda,
+        mu_j,
+        sigma_j,
+        ..
+      } => {
+        // Compensated compound Poisson jumps, E[e^J - 1] = e^(mu_j + sigma_j^2 / 2) - 1
+        let i = Complex64::i();
+        let mean_jump = (mu_j + 0.5 * sigma_j.powi(2)).exp() - 1.0;
+        let jump_cf = (i * w * *mu_j - 0.5 * w * w * sigma_j.powi(2)).exp();
+
+        cf * (lambda * tau * (jump_cf - 1.0 - i * w * mean_jump)).exp()
+      }
+    }
+  }
+
+  /// Call and put prices from the characteristic function (Gil-Pelaez inversion)
+  ///
+  /// C = S e^(-q tau) P1 - K e^(-r tau) P2, P2 = 1/2 + 1/pi int Re(e^(-iux) phi(u) / (iu)) du and
+  /// P1 the same with phi(u - i) / phi(-i), where x = ln(K / S)
+  pub fn price(&self, s: f64, k: f64, r: f64, q: f64, tau: f64) -> (f64, f64) {
+    let i = Complex64::i();
+    let x = (k / s).ln();
+    let forward_ratio = ((r - q) * tau).exp();
+    let integral = |shift: Complex64, norm: f64| {
+      let integrand = |u: f64| {
+        ((-i * u * x).exp() * self.log_return_cf(u + shift, r, q, tau) / (i * u * norm)).re
+      };
+      0.5 + FRAC_1_PI * double_exponential::integrate(integrand, 0.0, 200.0, 1e-8).integral
+    };
+    let p1 = integral(-i, forward_ratio);
+    let p2 = integral(Complex64::new(0.0, 0.0), 1.0);
+
+    let call = s * (-q * tau).exp() * p1 - k * (-r * tau).exp() * p2;
+    let put = call + k * (-r * tau).exp() - s * (-q * tau).exp();
+
+    (call, put)
+  }
+}
+
+/// Quote of the synthetic chain
+#[derive(Clone, Debug)]
+pub struct OptionQuote {
+  /// Time to maturity
+  pub tau: f64,
+  /// Strike price
+  pub k: f64,
+  /// Bid price
+  pub bid: f64,
+  /// Ask price
+  pub ask: f64,
+  /// Model price without noise
+  pub model: f64,
+}
+
+impl OptionQuote {
+  /// Mid price
+  pub fn mid(&self) -> f64 {
+    0.5 * (self.bid + self.ask)
+  }
+}
+
+/// Generator of a synthetic option chain
+#[derive(ImplNew)]
+pub struct SyntheticChain {
+  /// Model and its parameters, the ground truth of the chain
+  pub model: ChainModel,
+  /// Spot price
+  pub s: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: Option<f64>,
+  /// Option type of all quotes
+  pub option_type: OptionType,
+  /// Maturities in years
+  pub maturities: Vec<f64>,
+  /// Strikes relative to the spot, K / S
+  pub moneyness: Vec<f64>,
+  /// Bid-ask spread relative to the model price
+  pub spread: f64,
+  /// Tick size, the minimal spread and the rounding of the quotes
+  pub tick: f64,
+  /// Standard deviation of the mid price noise relative to the half spread
+  pub noise: f64,
+  /// Probability that a quote is missing
+  pub missing: f64,
+  /// Seed of the random generator, random if None
+  pub seed: Option<u64>,
+}
+
+impl SyntheticChain {
+  /// Quotes sorted by maturity and strike
+  ///
+  /// The mid is the model price plus Gaussian noise, the half spread is
+  /// max(spread * price, tick) / 2, bids are rounded down and asks up to the tick. Quotes
+  /// without a positive bid are dropped as well as the randomly missing ones.
+  pub fn quotes(&self) -> Vec<OptionQuote> {
+    let mut rng = match self.seed {
+      Some(seed) => StdRng::seed_from_u64(seed),
+      None => StdRng::from_entropy(),
+    };
+    let q = self.q.unwrap_or(0.0);
+    let mut quotes = Vec::new();
+
+    for &tau in &self.maturities {
+      for &moneyness in &self.moneyness {
+        let k = moneyness * self.s;
+        let (call, put) = self.model.price(self.s, k, self.r, q, tau);
+        let model = match self.option_type {
+          OptionType::Call => call,
+          OptionType::Put => put,
+        };
+
+        let half_spread = 0.5 * (self.spread * model).max(self.tick);
+        let z: f64 = rng.sample(StandardNormal);
+        let mid = model + self.noise * half_spread * z;
+        let bid = ((mid - half_spread) / self.tick).floor() * self.tick;
+        let ask = ((mid + half_spread) / self.tick).ceil() * self.tick;
+
+        if bid > 0.0 && rng.gen::<f64>() >= self.missing {
+          quotes.push(OptionQuote {
+            tau,
+            k,
+            bid,
+            ask,
+            model,
+          });
+        }
+      }
+    }
+
+    quotes
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use nalgebra::DVector;
+
+  use super::*;
+  use crate::quant::{
+    calibration::heston::HestonCalibrator, pricing::heston::HestonPricer, r#trait::Pricer,
+  };
+
+  fn params() -> HestonParams {
+    HestonParams {
+      v0: 0.04,
+      theta: 0.05,
+      rho: -0.6,
+      kappa: 1.5,
+      sigma: 0.4,
+    }
+  }
+
+  fn chain(model: ChainModel, seed: u64) -> SyntheticChain {
+    SyntheticChain::new(
+      model,
+      100.0,
+      0.02,
+      None,
+      OptionType::Call,
+      vec![0.25, 0.5, 1.0],
+      (0..13).map(|i| 0.7 + 0.05 * i as f64).collect(),
+      0.04,
+      0.01,
+      0.5,
+      0.1,
+      Some(seed),
+    )
+  }
+
+  #[test]
+  fn bates_without_jumps_is_heston() {
+    let bates = ChainModel::Bates {
+      heston: params(),
+      lambda: 0.0,
+      mu_j: -0.1,
+      sigma_j: 0.1,
+    };
+
+    for k in [80.0, 100.0, 120.0] {
+      let (call, put) = bates.price(100.0, k, 0.02, 0.01, 0.5);
+      let (heston_call, heston_put) = HestonPricer::new(
+        100.0,
+        0.04,
+        k,
+        0.02,
+        Some(0.01),
+        -0.6,
+        1.5,
+        0.05,
+        0.4,
+        Some(0.0),
+        Some(0.5),
+        None,
+        None,
+      )
+      .calculate_call_put();
+
+      assert!((call - heston_call).abs() < 1e-6);
+      assert!((put - heston_put).abs() < 1e-6);
+    }
+  }
+
+  #[test]
+  fn bates_jumps_fatten_the_left_tail() {
+    let bates = ChainModel::Bates {
+      heston: params(),
+      lambda: 0.5,
+      mu_j: -0.15,
+      sigma_j: 0.1,
+    };
+    let heston = ChainModel::Heston(params());
+
+    // Downward jumps make the out-of-the-money puts more expensive
+    let (_, bates_put) = bates.price(100.0, 75.0, 0.02, 0.0, 0.5);
+    let (_, heston_put) = heston.price(100.0, 75.0, 0.02, 0.0, 0.5);
+    assert!(bates_put > heston_put);
+  }
+
+  #[test]
+  fn synthetic_quotes_bracket_the_model() {
+    let chain = chain(ChainModel::Heston(params()), 42);
+    let quotes = chain.quotes();
+
+    assert!(!quotes.is_empty() && quotes.len() < 39);
+    for quote in &quotes {
+      assert!(quote.bid > 0.0 && quote.bid < quote.ask);
+      // Noise of half the half spread keeps the mid within a spread of the model
+      assert!((quote.mid() - quote.model).abs() < quote.ask - quote.bid);
+      assert!((quote.ask / chain.tick - (quote.ask / chain.tick).round()).abs() < 1e-6);
+    }
+
+    // The chain is reproducible for a seed
+    let again = chain.quotes();
+    assert_eq!(quotes.len(), again.len());
+    assert!(quotes.iter().zip(&again).all(|(a, b)| a.bid == b.bid));
+  }
+
+  #[test]
+  fn synthetic_chain_calibrator_residuals() {
+    let chain = chain(ChainModel::Heston(params()), 7);
+    let slice = chain
+      .quotes()
+      .into_iter()
+      .filter(|quote| quote.tau == 0.5)
+      .collect::<Vec<_>>();
+    let calibrator = HestonCalibrator::new(
+      params(),
+      DVector::from_iterator(slice.len(), slice.iter().map(OptionQuote::mid)),
+      DVector::from_element(slice.len(), chain.s),
+      DVector::from_iterator(slice.len(), slice.iter().map(|quote| quote.k)),
+      0.5,
+      chain.r,
+      None,
+      OptionType::Call,
+    );
+    let result = calibrator.calibration_result();
+
+    // At the true parameters the mids are within the quoted spreads
+    let max_spread = slice
+      .iter()
+      .map(|quote| quote.ask - quote.bid)
+      .fold(0.0, f64::max);
+    assert!((result.rss / slice.len() as f64).sqrt() < max_spread);
+  }
+}
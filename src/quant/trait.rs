@@ -1,7 +1,11 @@
-use super::OptionType;
+use num_complex::Complex64;
+
+use crate::error::StochasticResult;
+
+use super::{greeks::Greeks, OptionType};
 
 /// Pricer trait.
-pub(crate) trait Pricer: Time {
+pub trait Pricer: Time {
   /// Calculate the price of an option.
   fn calculate_call_put(&self) -> (f64, f64) {
     todo!()
@@ -12,11 +16,34 @@ pub(crate) trait Pricer: Time {
     todo!()
   }
 
-  /// Derivatives.
+  /// Check the parameters of the pricer
+  fn validate(&self) -> StochasticResult<()> {
+    Ok(())
+  }
+
+  /// Calculate the price of an option, returning an error on invalid parameters
+  fn try_calculate_call_put(&self) -> StochasticResult<(f64, f64)> {
+    self.validate()?;
+    Ok(self.calculate_call_put())
+  }
+
+  /// Calculate the price, returning an error on invalid parameters
+  fn try_calculate_price(&self) -> StochasticResult<f64> {
+    self.validate()?;
+    Ok(self.calculate_price())
+  }
+
+  /// Sensitivities in the order documented by the pricer, e.g. the gradient in the model
+  /// parameters of the calibrations. Use [`Pricer::greeks`] for the Greeks by name.
   fn derivatives(&self) -> Vec<f64> {
     todo!()
   }
 
+  /// Greeks of the option of the pricer, None if the pricer does not compute them
+  fn greeks(&self) -> Option<Greeks> {
+    None
+  }
+
   /// Calculate the implied volatility using the Newton-Raphson method.
   fn implied_volatility(&self, _c_price: f64, _option_type: OptionType) -> f64 {
     todo!()
@@ -50,3 +77,30 @@ pub trait Time {
     days as f64 / 365.0
   }
 }
+
+/// Characteristic function of the log-return driver X of a model, with X(0) = 0
+///
+/// Fourier pricing ([`FourierPricer`](super::pricing::fft::FourierPricer)), moments and
+/// density inversion only need this function, so they are written once for the affine
+/// models (Heston, Bates), the jump diffusions (Merton) and the Lévy processes (VG, NIG,
+/// CGMY).
+pub trait CharacteristicFn {
+  /// E[e^(iuX(t))] for complex u in the strip where it is finite
+  fn cf(&self, u: Complex64, t: f64) -> Complex64;
+
+  /// Mean and variance of X(t), the first two cumulants by central differences of
+  /// ln E[e^(iuX(t))] around u = 0
+  fn cumulants(&self, t: f64) -> (f64, f64) {
+    let h = 1e-3;
+    let [up, down] = [h, -h].map(|u| self.cf(Complex64::new(u, 0.0), t).ln());
+
+    ((up - down).im / (2.0 * h), -(up + down).re / (h * h))
+  }
+
+  /// Center and spread of X(t) that set the grids of the numerical inversions, the mean
+  /// and the standard deviation, a location and a scale if the variance is infinite
+  fn location_scale(&self, t: f64) -> (f64, f64) {
+    let (mean, variance) = self.cumulants(t);
+    (mean, variance.max(0.0).sqrt())
+  }
+}
@@ -0,0 +1,7 @@
+pub mod american;
+pub mod dupire;
+pub mod forward_variance;
+pub mod implied_correlation;
+pub mod sabr;
+pub mod surface;
+pub mod svi;
@@ -0,0 +1,213 @@
+//! Implied volatility of American options.
+//!
+//! The closed-form inversions of `implied_vol` are only valid for European payoffs, but
+//! most listed single-stock options, e.g. those of the Yahoo option chains, are American.
+//! [`american_implied_volatility`] inverts any American pricer, in practice
+//! [`BjerksundStenslandPricer`] or the American [`FiniteDifferencePricer`], with Brent's
+//! method on a bracket of volatilities.
+//!
+//! The price of an American option is increasing in the volatility and bounded below by
+//! the intrinsic value, so the root is unique when the price is above the price at the
+//! lower end of the bracket and below the one at the upper end.
+//!
+//! - Brent, R. P. (1973). Algorithms for Minimization without Derivatives.
+//! - Bjerksund, P., & Stensland, G. (1993). Closed-form approximation of American options.
+//!
+//! [`BjerksundStenslandPricer`]: crate::quant::pricing::bjerksund_stensland::BjerksundStenslandPricer
+//! [`FiniteDifferencePricer`]: crate::quant::pricing::finitie_difference::FiniteDifferencePricer
+
+use crate::error::{ensure, StochasticError, StochasticResult};
+
+/// Bracket of the volatilities searched by [`american_implied_volatility`]
+pub const VOLATILITY_BRACKET: (f64, f64) = (1e-4, 5.0);
+
+/// Root of f in [a, b] by Brent's method, f(a) and f(b) must have opposite signs
+pub fn brent<F>(f: F, a: f64, b: f64, tol: f64, max_iter: usize) -> StochasticResult<f64>
+where
+  F: Fn(f64) -> f64,
+{
+  let (mut a, mut b) = (a, b);
+  let (mut fa, mut fb) = (f(a), f(b));
+  ensure(
+    fa.is_finite() && fb.is_finite(),
+    "function must be finite on the bracket",
+  )?;
+  if fa == 0.0 {
+    return Ok(a);
+  }
+  if fb == 0.0 {
+    return Ok(b);
+  }
+  ensure(fa * fb < 0.0, "root is not bracketed")?;
+
+  let (mut c, mut fc) = (a, fa);
+  let mut d = b - a;
+  let mut e = d;
+
+  for _ in 0..max_iter {
+    if fb * fc > 0.0 {
+      c = a;
+      fc = fa;
+      d = b - a;
+      e = d;
+    }
+    if fc.abs() < fb.abs() {
+      a = b;
+      b = c;
+      c = a;
+      fa = fb;
+      fb = fc;
+      fc = fa;
+    }
+
+    let tol1 = 2.0 * f64::EPSILON * b.abs() + 0.5 * tol;
+    let m = 0.5 * (c - b);
+    if m.abs() <= tol1 || fb == 0.0 {
+      return Ok(b);
+    }
+
+    if e.abs() >= tol1 && fa.abs() > fb.abs() {
+      // Inverse quadratic interpolation, the secant step if a = c
+      let s = fb / fa;
+      let (mut p, mut q) = match a == c {
+        true => (2.0 * m * s, 1.0 - s),
+        false => {
+          let q = fa / fc;
+          let r = fb / fc;
+          (
+            s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)),
+            (q - 1.0) * (r - 1.0) * (s - 1.0),
+          )
+        }
+      };
+      if p > 0.0 {
+        q = -q;
+      }
+      p = p.abs();
+
+      if 2.0 * p < (3.0 * m * q - (tol1 * q).abs()).min((e * q).abs()) {
+        e = d;
+        d = p / q;
+      } else {
+        d = m;
+        e = m;
+      }
+    } else {
+      d = m;
+      e = m;
+    }
+
+    a = b;
+    fa = fb;
+    b += match d.abs() > tol1 {
+      true => d,
+      false => tol1.copysign(m),
+    };
+    fb = f(b);
+  }
+
+  Err(StochasticError::Numerical(format!(
+    "Brent's method did not converge in {} iterations",
+    max_iter
+  )))
+}
+
+/// Volatility at which the pricer, the price of the American option as a function of the
+/// volatility, matches the price
+pub fn american_implied_volatility<P>(price: f64, pricer: P) -> StochasticResult<f64>
+where
+  P: Fn(f64) -> f64,
+{
+  ensure(price > 0.0, "price must be positive")?;
+  let (low, high) = VOLATILITY_BRACKET;
+  let (price_low, price_high) = (pricer(low), pricer(high));
+  ensure(
+    price >= price_low,
+    "price is below the price of the lowest volatility",
+  )?;
+  ensure(
+    price <= price_high,
+    "price is above the price of the highest volatility",
+  )?;
+
+  brent(|v| pricer(v) - price, low, high, 1e-8, 100)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::{
+    pricing::{
+      bjerksund_stensland::BjerksundStenslandPricer,
+      bsm::{BSMCoc, BSMPricer},
+      finitie_difference::{FiniteDifferenceMethod, FiniteDifferencePricer},
+    },
+    r#trait::Pricer,
+    OptionStyle, OptionType,
+  };
+
+  #[test]
+  fn brent_roots() {
+    let root = brent(|x| x * x * x - 2.0 * x - 5.0, 2.0, 3.0, 1e-12, 100).unwrap();
+    assert!((root - 2.0945514815423265).abs() < 1e-10);
+    assert!(brent(|x| x * x + 1.0, -1.0, 1.0, 1e-12, 100).is_err());
+  }
+
+  #[test]
+  fn american_implied_volatility_round_trip() {
+    for option_type in [OptionType::Put, OptionType::Call] {
+      let pricer = BjerksundStenslandPricer::new(
+        100.0,
+        0.3,
+        110.0,
+        0.05,
+        Some(0.02),
+        Some(0.5),
+        None,
+        None,
+        option_type,
+      );
+      let price = pricer.calculate_price();
+      let vol = pricer.implied_volatility(price, option_type);
+      assert!((vol - 0.3).abs() < 1e-6, "{:?}: {}", option_type, vol);
+    }
+
+    // The finite difference pricer is deterministic, so its inversion is exact as well
+    let pricer = FiniteDifferencePricer::new(
+      100.0,
+      0.25,
+      100.0,
+      0.05,
+      500,
+      200,
+      Some(1.0),
+      None,
+      None,
+      OptionStyle::American,
+      OptionType::Put,
+      FiniteDifferenceMethod::CrankNicolson,
+    );
+    let price = pricer.calculate_price();
+    let vol = pricer.implied_volatility(price, OptionType::Put);
+    assert!((vol - 0.25).abs() < 1e-5);
+
+    // The European inversion of an American put overstates the volatility
+    let european = BSMPricer::new(
+      100.0,
+      0.25,
+      100.0,
+      0.05,
+      None,
+      None,
+      None,
+      Some(1.0),
+      None,
+      None,
+      OptionType::Put,
+      BSMCoc::BSM1973,
+    );
+    assert!(price > european.calculate_call_put().1);
+
+    assert!(american_implied_volatility(0.0, |v| v).is_err());
+  }
+}
@@ -0,0 +1,330 @@
+//! Dupire local volatility surface built from a grid of option quotes.
+//!
+//! In terms of the total implied variance w(y, T) = sigma_BS^2 T at log-moneyness
+//! y = ln(K / F(T)) the Dupire formula reads (Gatheral, 2006, 1.10)
+//!
+//! sigma_loc^2(T, K) = dw/dT / (1 - y / w dw/dy + (-1/4 - 1/w + y^2 / w^2) (dw/dy)^2 / 4 + d^2w/dy^2 / 2)
+//!
+//! The derivatives are taken with finite differences on the quote grid. Market grids are
+//! rarely free of arbitrage, so before differentiating the total variance is smoothed
+//! along the strikes and made non-decreasing in the maturity at fixed log-moneyness
+//! (no calendar arbitrage). The numerator is floored at zero, the denominator (the
+//! risk-neutral density up to a positive factor) at a small positive value and the result
+//! is clipped to [`DupireSettings::min_vol`, `DupireSettings::max_vol`].
+//!
+//! - Dupire, B. (1994). Pricing with a smile.
+//! - Gatheral, J. (2006). The Volatility Surface: A Practitioner's Guide.
+
+use ndarray::{Array1, Array2, ArrayView1};
+
+use crate::error::{ensure, StochasticError, StochasticResult};
+
+/// Safeguards of the local volatility construction
+#[derive(Clone, Copy, Debug)]
+pub struct DupireSettings {
+  /// Lower bound of the local volatility
+  pub min_vol: f64,
+  /// Upper bound of the local volatility
+  pub max_vol: f64,
+  /// Number of [1, 2, 1] / 4 smoothing passes of the total variance along the strikes
+  pub smoothing: usize,
+}
+
+impl Default for DupireSettings {
+  fn default() -> Self {
+    Self {
+      min_vol: 0.01,
+      max_vol: 3.0,
+      smoothing: 1,
+    }
+  }
+}
+
+/// Local volatility surface on a maturity x strike grid
+#[derive(Clone, Debug)]
+pub struct DupireLocalVol {
+  /// Underlying price
+  pub s: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: f64,
+  /// Increasing maturities of the grid
+  pub maturities: Array1<f64>,
+  /// Increasing strikes of the grid
+  pub strikes: Array1<f64>,
+  /// Local volatility, one row per maturity
+  pub local_vol: Array2<f64>,
+}
+
+impl DupireLocalVol {
+  /// Build the surface from Black-Scholes implied volatilities, one row per maturity
+  pub fn from_implied_vols(
+    s: f64,
+    r: f64,
+    q: f64,
+    strikes: Array1<f64>,
+    maturities: Array1<f64>,
+    vols: &Array2<f64>,
+    settings: DupireSettings,
+  ) -> StochasticResult<Self> {
+    Self::validate_grid(s, &strikes, &maturities, vols)?;
+    ensure(
+      vols.iter().all(|v| v.is_finite() && *v > 0.0),
+      "implied volatilities must be positive",
+    )?;
+    ensure(
+      settings.min_vol > 0.0 && settings.min_vol < settings.max_vol,
+      "min_vol must be positive and below max_vol",
+    )?;
+
+    let (n_t, n_k) = vols.dim();
+    let log_moneyness = Array2::from_shape_fn((n_t, n_k), |(i, j)| {
+      (strikes[j] / s).ln() - (r - q) * maturities[i]
+    });
+    let mut w = Array2::from_shape_fn((n_t, n_k), |(i, j)| vols[[i, j]].powi(2) * maturities[i]);
+
+    for _ in 0..settings.smoothing {
+      for mut row in w.rows_mut() {
+        let prev = row.to_owned();
+        for j in 1..n_k - 1 {
+          row[j] = 0.25 * (prev[j - 1] + 2.0 * prev[j] + prev[j + 1]);
+        }
+      }
+    }
+
+    // Calendar arbitrage: w(y, T) must not decrease in T at fixed log-moneyness
+    for i in 1..n_t {
+      for j in 0..n_k {
+        let earlier = interpolate(
+          log_moneyness.row(i - 1),
+          w.row(i - 1),
+          log_moneyness[[i, j]],
+        );
+        w[[i, j]] = w[[i, j]].max(earlier);
+      }
+    }
+
+    // Total variance of the slice i at the log-moneyness y, zero at T = 0
+    let slice = |i: Option<usize>, y: f64| match i {
+      Some(i) => interpolate(log_moneyness.row(i), w.row(i), y),
+      None => 0.0,
+    };
+    let maturity = |i: Option<usize>| i.map_or(0.0, |i| maturities[i]);
+
+    let mut local_vol = Array2::zeros((n_t, n_k));
+    for i in 0..n_t {
+      // Central in the maturity, backward at the last slice and from T = 0 at the first
+      let lo = i.checked_sub(1);
+      let hi = Some((i + 1).min(n_t - 1));
+
+      for j in 1..n_k - 1 {
+        let y = log_moneyness[[i, j]];
+        let w_t = (slice(hi, y) - slice(lo, y)) / (maturity(hi) - maturity(lo));
+
+        let (y0, y2) = (log_moneyness[[i, j - 1]], log_moneyness[[i, j + 1]]);
+        let (h1, h2) = (y - y0, y2 - y);
+        let (w0, w1, w2) = (w[[i, j - 1]], w[[i, j]], w[[i, j + 1]]);
+        let w_y = (h1.powi(2) * w2 - h2.powi(2) * w0 + (h2.powi(2) - h1.powi(2)) * w1)
+          / (h1 * h2 * (h1 + h2));
+        let w_yy = 2.0 * (h1 * w2 - (h1 + h2) * w1 + h2 * w0) / (h1 * h2 * (h1 + h2));
+
+        let density = 1.0 - y / w1 * w_y
+          + 0.25 * (-0.25 - 1.0 / w1 + y.powi(2) / w1.powi(2)) * w_y.powi(2)
+          + 0.5 * w_yy;
+        let variance = w_t.max(0.0) / density.max(1e-8);
+
+        local_vol[[i, j]] = variance.sqrt().clamp(settings.min_vol, settings.max_vol);
+      }
+
+      // Flat in the strike at the edges of the grid
+      local_vol[[i, 0]] = local_vol[[i, 1]];
+      local_vol[[i, n_k - 1]] = local_vol[[i, n_k - 2]];
+    }
+
+    Ok(Self {
+      s,
+      r,
+      q,
+      maturities,
+      strikes,
+      local_vol,
+    })
+  }
+
+  /// Build the surface from call prices, one row per maturity
+  ///
+  /// The prices are converted to implied volatilities first, where the smoothing and the
+  /// calendar guard act on a quantity of the same scale across strikes.
+  pub fn from_prices(
+    s: f64,
+    r: f64,
+    q: f64,
+    strikes: Array1<f64>,
+    maturities: Array1<f64>,
+    calls: &Array2<f64>,
+    settings: DupireSettings,
+  ) -> StochasticResult<Self> {
+    Self::validate_grid(s, &strikes, &maturities, calls)?;
+
+    let mut vols = Array2::zeros(calls.dim());
+    for ((i, j), vol) in vols.indexed_iter_mut() {
+      let tau = maturities[i];
+      let forward = s * ((r - q) * tau).exp();
+      let undiscounted = calls[[i, j]] * (r * tau).exp();
+      *vol = implied_vol::implied_black_volatility(undiscounted, forward, strikes[j], tau, true);
+
+      if !vol.is_finite() || *vol <= 0.0 {
+        return Err(StochasticError::Numerical(format!(
+          "no implied volatility for the call price {} at T = {}, K = {}",
+          calls[[i, j]],
+          tau,
+          strikes[j]
+        )));
+      }
+    }
+
+    Self::from_implied_vols(s, r, q, strikes, maturities, &vols, settings)
+  }
+
+  fn validate_grid(
+    s: f64,
+    strikes: &Array1<f64>,
+    maturities: &Array1<f64>,
+    quotes: &Array2<f64>,
+  ) -> StochasticResult<()> {
+    ensure(s > 0.0, "s must be positive")?;
+    ensure(strikes.len() >= 3, "at least 3 strikes are needed")?;
+    ensure(!maturities.is_empty(), "at least 1 maturity is needed")?;
+    ensure(
+      quotes.dim() == (maturities.len(), strikes.len()),
+      "quotes must have one row per maturity and one column per strike",
+    )?;
+    ensure(
+      strikes[0] > 0.0 && strikes.windows(2).into_iter().all(|k| k[1] > k[0]),
+      "strikes must be positive and increasing",
+    )?;
+    ensure(
+      maturities[0] > 0.0 && maturities.windows(2).into_iter().all(|t| t[1] > t[0]),
+      "maturities must be positive and increasing",
+    )
+  }
+
+  /// Local volatility at time t and spot s, bilinear on the grid and flat outside of it
+  pub fn sigma_loc(&self, t: f64, s: f64) -> f64 {
+    let (i0, i1, wt) = bracket(self.maturities.view(), t);
+    let (j0, j1, ws) = bracket(self.strikes.view(), s);
+    let row = |i: usize| (1.0 - ws) * self.local_vol[[i, j0]] + ws * self.local_vol[[i, j1]];
+
+    (1.0 - wt) * row(i0) + wt * row(i1)
+  }
+}
+
+/// Indices of the grid points around x and the weight of the upper one
+fn bracket(grid: ArrayView1<f64>, x: f64) -> (usize, usize, f64) {
+  let last = grid.len() - 1;
+  if x <= grid[0] {
+    return (0, 0, 0.0);
+  }
+  if x >= grid[last] {
+    return (last, last, 0.0);
+  }
+
+  let hi = grid.iter().position(|&g| g > x).unwrap();
+  let lo = hi - 1;
+  (lo, hi, (x - grid[lo]) / (grid[hi] - grid[lo]))
+}
+
+/// Linear interpolation of ys over the increasing xs, flat outside
+fn interpolate(xs: ArrayView1<f64>, ys: ArrayView1<f64>, x: f64) -> f64 {
+  let (lo, hi, weight) = bracket(xs, x);
+  (1.0 - weight) * ys[lo] + weight * ys[hi]
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::Array;
+  use statrs::distribution::{ContinuousCDF, Normal};
+
+  use super::*;
+
+  fn grid() -> (Array1<f64>, Array1<f64>) {
+    (
+      Array::linspace(60.0, 150.0, 31),
+      Array1::from_vec(vec![0.25, 0.5, 1.0, 1.5, 2.0]),
+    )
+  }
+
+  #[test]
+  fn dupire_flat_smile() {
+    let (strikes, maturities) = grid();
+    let vols = Array2::from_elem((maturities.len(), strikes.len()), 0.25);
+    let surface = DupireLocalVol::from_implied_vols(
+      100.0,
+      0.03,
+      0.01,
+      strikes,
+      maturities,
+      &vols,
+      DupireSettings::default(),
+    )
+    .unwrap();
+
+    for &(t, s) in &[(0.1, 100.0), (0.7, 80.0), (1.2, 130.0), (3.0, 50.0)] {
+      assert!((surface.sigma_loc(t, s) - 0.25).abs() < 1e-6);
+    }
+  }
+
+  #[test]
+  fn dupire_prices_match_implied_vols() {
+    let (s, r, q) = (100.0, 0.02, 0.0);
+    let (strikes, maturities) = grid();
+    let n = Normal::default();
+    // Linear skew in the log-strike, sigma_BS = 0.2 - 0.1 ln(K / S)
+    let vols = Array2::from_shape_fn((maturities.len(), strikes.len()), |(_, j)| {
+      0.2 - 0.1 * (strikes[j] / s).ln()
+    });
+    let calls = Array2::from_shape_fn(vols.dim(), |(i, j)| {
+      let (tau, k, v) = (maturities[i], strikes[j], vols[[i, j]]);
+      let d1 = ((s / k).ln() + (r - q + 0.5 * v * v) * tau) / (v * tau.sqrt());
+      let d2 = d1 - v * tau.sqrt();
+      s * (-q * tau).exp() * n.cdf(d1) - k * (-r * tau).exp() * n.cdf(d2)
+    });
+
+    let settings = DupireSettings::default();
+    let from_vols = DupireLocalVol::from_implied_vols(
+      s,
+      r,
+      q,
+      strikes.clone(),
+      maturities.clone(),
+      &vols,
+      settings,
+    )
+    .unwrap();
+    let from_prices =
+      DupireLocalVol::from_prices(s, r, q, strikes, maturities, &calls, settings).unwrap();
+
+    for (a, b) in from_vols.local_vol.iter().zip(from_prices.local_vol.iter()) {
+      assert!((a - b).abs() < 1e-6);
+    }
+    // A negative skew makes the local volatility steeper than the implied one
+    assert!(from_vols.sigma_loc(1.0, 80.0) > from_vols.sigma_loc(1.0, 120.0));
+  }
+
+  #[test]
+  fn dupire_rejects_bad_grid() {
+    let (strikes, maturities) = grid();
+    let vols = Array2::from_elem((maturities.len(), strikes.len() - 1), 0.2);
+    assert!(DupireLocalVol::from_implied_vols(
+      100.0,
+      0.0,
+      0.0,
+      strikes,
+      maturities,
+      &vols,
+      DupireSettings::default()
+    )
+    .is_err());
+  }
+}
@@ -0,0 +1,274 @@
+//! Forward variance curve from option chains.
+//!
+//! The fair strike of a variance swap on the expiry t is replicated by a static portfolio of
+//! out-of-the-money options (Demeterfi, Derman, Kamal & Zou, 1999)
+//!
+//! sigma^2(t) t = 2 / D(t) int_0^inf Q(K) / K^2 dK
+//!
+//! with the puts below and the calls above the forward. It is discretized over the quoted
+//! strikes as in the VIX white paper, so the result holds for any model without jumps and
+//! the quotes only need to cover the strikes where the options have value.
+//!
+//! The total variances w(t) = sigma^2(t) t of the expiries are interpolated linearly, so
+//! the forward variance xi0(t) = dw / dt is flat between the expiries and extrapolated
+//! flat beyond the last one. Its closure is the input curve of the
+//! [`RBergomi`](crate::stochastic::volatility::rbergomi::RBergomi) model.
+//!
+//! - Demeterfi, K., Derman, E., Kamal, M., & Zou, J. (1999). More than you ever wanted to
+//!   know about volatility swaps.
+//! - Cboe (2019). Cboe Volatility Index white paper.
+//! - Bergomi, L. (2016). Stochastic volatility modeling, chapter 7.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+
+/// Option prices of one expiry
+#[derive(ImplNew, Clone, Debug)]
+pub struct ExpirySlice {
+  /// Time to expiry in years
+  pub t: f64,
+  /// Forward price to the expiry
+  pub forward: f64,
+  /// Discount factor to the expiry
+  pub discount: f64,
+  /// Strike prices in increasing order
+  pub strikes: Array1<f64>,
+  /// Call prices of the strikes
+  pub calls: Array1<f64>,
+  /// Put prices of the strikes
+  pub puts: Array1<f64>,
+}
+
+impl ExpirySlice {
+  /// Fair variance swap strike sigma^2(t), annualized
+  ///
+  /// sigma^2 = 2 / t sum dK_i / K_i^2 Q(K_i) / D - (F / K_0 - 1)^2 / t with K_0 the
+  /// highest strike below the forward, Q the put below K_0, the call above and their
+  /// average at K_0, and dK_i half the distance of the neighbouring strikes.
+  pub fn variance_swap(&self) -> f64 {
+    let k = &self.strikes;
+    let n = k.len();
+    assert!(n >= 2, "at least 2 strikes are needed");
+    assert!(
+      self.calls.len() == n && self.puts.len() == n,
+      "calls and puts must be quoted at every strike"
+    );
+
+    let k0 = (0..n).rev().find(|&i| k[i] <= self.forward).unwrap_or(0);
+    let sum = (0..n)
+      .map(|i| {
+        let dk = match i {
+          0 => k[1] - k[0],
+          _ if i == n - 1 => k[n - 1] - k[n - 2],
+          _ => 0.5 * (k[i + 1] - k[i - 1]),
+        };
+        let q = match i.cmp(&k0) {
+          std::cmp::Ordering::Less => self.puts[i],
+          std::cmp::Ordering::Greater => self.calls[i],
+          std::cmp::Ordering::Equal => 0.5 * (self.puts[i] + self.calls[i]),
+        };
+        dk / k[i].powi(2) * q
+      })
+      .sum::<f64>();
+
+    (2.0 * sum / self.discount - (self.forward / k[k0] - 1.0).powi(2)) / self.t
+  }
+}
+
+/// Piecewise flat forward variance curve
+#[derive(Clone, Debug)]
+pub struct ForwardVarianceCurve {
+  /// Expiries in increasing order
+  pub times: Array1<f64>,
+  /// Total variances w(t) of the expiries, non-decreasing
+  pub total_variance: Array1<f64>,
+}
+
+impl ForwardVarianceCurve {
+  /// Curve of the variance swap strikes replicated from the slices of a chain
+  pub fn from_chain(slices: &[ExpirySlice]) -> Self {
+    let quotes = slices
+      .iter()
+      .map(|slice| (slice.t, slice.variance_swap()))
+      .collect::<Vec<_>>();
+    Self::from_variance_swaps(&quotes)
+  }
+
+  /// Curve of the annualized variance swap strikes (t, sigma^2(t))
+  ///
+  /// A total variance below the one of an earlier expiry is a calendar arbitrage, it is
+  /// raised to that one, i.e. the forward variance is floored at 0.
+  pub fn from_variance_swaps(quotes: &[(f64, f64)]) -> Self {
+    assert!(!quotes.is_empty(), "at least one expiry is needed");
+    let mut quotes = quotes.to_vec();
+    quotes.sort_by(|a, b| a.0.total_cmp(&b.0));
+    assert!(quotes[0].0 > 0.0, "expiries must be positive");
+
+    let mut total_variance = Vec::with_capacity(quotes.len());
+    let mut last = 0.0;
+    for &(t, variance) in &quotes {
+      last = (variance * t).max(last);
+      total_variance.push(last);
+    }
+
+    Self {
+      times: quotes.iter().map(|&(t, _)| t).collect(),
+      total_variance: Array1::from_vec(total_variance),
+    }
+  }
+
+  /// Index of the expiry ending the piece of t, the last one beyond it
+  fn piece(&self, t: f64) -> usize {
+    self
+      .times
+      .iter()
+      .position(|&expiry| t <= expiry)
+      .unwrap_or(self.times.len() - 1)
+  }
+
+  /// Forward variance xi0(t) = dw / dt
+  pub fn xi(&self, t: f64) -> f64 {
+    let i = self.piece(t);
+    let (t0, w0) = match i {
+      0 => (0.0, 0.0),
+      _ => (self.times[i - 1], self.total_variance[i - 1]),
+    };
+    (self.total_variance[i] - w0) / (self.times[i] - t0)
+  }
+
+  /// Total variance w(t), the integral of the forward variance over [0, t]
+  pub fn total_variance(&self, t: f64) -> f64 {
+    let i = self.piece(t);
+    let (t0, w0) = match i {
+      0 => (0.0, 0.0),
+      _ => (self.times[i - 1], self.total_variance[i - 1]),
+    };
+    w0 + self.xi(t) * (t - t0)
+  }
+
+  /// Annualized variance swap strike w(t) / t
+  pub fn variance_swap(&self, t: f64) -> f64 {
+    match t > 0.0 {
+      true => self.total_variance(t) / t,
+      false => self.xi(0.0),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    quant::{
+      pricing::{bsm::BSMPricer, heston::HestonPricer},
+      r#trait::Pricer,
+      OptionType,
+    },
+    stochastic::{volatility::rbergomi::RBergomi, Sampling2D},
+  };
+
+  const S: f64 = 100.0;
+  const R: f64 = 0.02;
+
+  fn strikes() -> Array1<f64> {
+    Array1::range(20.0, 400.0, 1.0)
+  }
+
+  fn slice<F: Fn(f64) -> (f64, f64)>(t: f64, prices: F) -> ExpirySlice {
+    let strikes = strikes();
+    let (calls, puts): (Vec<_>, Vec<_>) = strikes.iter().map(|&k| prices(k)).unzip();
+    ExpirySlice::new(
+      t,
+      S * (R * t).exp(),
+      (-R * t).exp(),
+      strikes,
+      Array1::from_vec(calls),
+      Array1::from_vec(puts),
+    )
+  }
+
+  fn black_scholes_slice(t: f64, sigma: f64) -> ExpirySlice {
+    slice(t, |k| {
+      BSMPricer::new(
+        S,
+        sigma,
+        k,
+        R,
+        None,
+        None,
+        None,
+        Some(t),
+        None,
+        None,
+        OptionType::Call,
+        Default::default(),
+      )
+      .calculate_call_put()
+    })
+  }
+
+  #[test]
+  fn replication_recovers_black_scholes_term_structure() {
+    let chain =
+      [(0.25, 0.2), (0.5, 0.25), (1.0, 0.22)].map(|(t, sigma)| black_scholes_slice(t, sigma));
+    for (slice, sigma) in chain.iter().zip([0.2, 0.25, 0.22]) {
+      assert!((slice.variance_swap() - sigma * sigma).abs() < 1e-4);
+    }
+
+    let curve = ForwardVarianceCurve::from_chain(&chain);
+    assert!((curve.xi(0.1) - 0.04).abs() < 1e-4);
+    assert!((curve.xi(0.3) - (0.0625 * 0.5 - 0.04 * 0.25) / 0.25).abs() < 1e-3);
+    assert!((curve.variance_swap(0.5) - 0.0625).abs() < 1e-4);
+    assert_eq!(curve.xi(2.0), curve.xi(0.75));
+
+    // The curve drives the rough Bergomi model directly
+    let model = RBergomi::new(
+      1.9,
+      0.1,
+      -0.9,
+      move |t| curve.xi(t),
+      Some(S),
+      R,
+      65,
+      Some(1.0),
+      None,
+    );
+    let [_, v] = model.sample();
+    assert!((v[0] - 0.04).abs() < 1e-4);
+  }
+
+  #[test]
+  fn heston_forward_variance() {
+    let (v0, kappa, theta) = (0.09, 1.5, 0.04);
+    let chain = [0.25, 0.5, 1.0, 2.0].map(|t| {
+      slice(t, |k| {
+        HestonPricer::new(
+          S,
+          v0,
+          k,
+          R,
+          None,
+          -0.7,
+          kappa,
+          theta,
+          0.4,
+          Some(0.0),
+          Some(t),
+          None,
+          None,
+        )
+        .calculate_call_put()
+      })
+    });
+    let curve = ForwardVarianceCurve::from_chain(&chain);
+
+    // E[v(t)] = theta + (v0 - theta) e^(-kappa t), its integral is the total variance
+    let total = |t: f64| theta * t + (v0 - theta) * (1.0 - (-kappa * t).exp()) / kappa;
+    for t in [0.25, 0.5, 1.0, 2.0] {
+      assert!((curve.total_variance(t) - total(t)).abs() < 2e-3 * t);
+    }
+    // The forward variance decays from v0 towards theta
+    assert!(curve.xi(0.1) > curve.xi(0.75) && curve.xi(0.75) > curve.xi(1.5));
+    assert!(curve.xi(1.5) > theta);
+  }
+}
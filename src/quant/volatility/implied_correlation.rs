@@ -0,0 +1,162 @@
+//! Implied correlation of an index and its constituents.
+//!
+//! The variance of an index with the weights w_i and the constituent volatilities sigma_i,
+//! under a single average correlation rho, is
+//!
+//! sigma_I^2 = sum_i w_i^2 sigma_i^2 + rho sum_(i != j) w_i w_j sigma_i sigma_j
+//!
+//! so the implied volatilities of the index and the constituents, of the same maturity and
+//! moneyness, give the average correlation priced by the market
+//!
+//! rho = (sigma_I^2 - sum_i w_i^2 sigma_i^2) / ((sum_i w_i sigma_i)^2 - sum_i w_i^2 sigma_i^2)
+//!
+//! The equicorrelation matrix of rho is the input of the correlated noise
+//! [`CGNSD`](crate::stochastic::noise::cgnsd::CGNSD) of the multi-asset simulations.
+//!
+//! - Cboe (2009). Cboe S&P 500 Implied Correlation Index white paper.
+//! - Driessen, J., Maenhout, P. J., & Vilkov, G. (2009). The price of correlation risk:
+//!   Evidence from equity options.
+
+use impl_new_derive::ImplNew;
+use ndarray::{Array1, Array2};
+
+use crate::error::{ensure, StochasticResult};
+
+/// Implied volatilities of an index and its constituents
+#[derive(ImplNew, Clone, Debug)]
+pub struct ImpliedCorrelation {
+  /// Implied volatility of the index
+  pub index_vol: f64,
+  /// Weights of the constituents in the index
+  pub weights: Array1<f64>,
+  /// Implied volatilities of the constituents
+  pub vols: Array1<f64>,
+}
+
+impl ImpliedCorrelation {
+  /// Check the inputs
+  pub fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.weights.len() == self.vols.len(),
+      "weights and vols must have the same length",
+    )?;
+    ensure(
+      self.weights.len() >= 2,
+      "at least 2 constituents are needed",
+    )?;
+    ensure(self.index_vol >= 0.0, "index_vol must be non-negative")?;
+    ensure(
+      self.vols.iter().all(|&v| v >= 0.0),
+      "vols must be non-negative",
+    )?;
+    ensure(
+      self.cross_variance() > 0.0,
+      "at least 2 constituents must have a positive weighted volatility",
+    )
+  }
+
+  /// sum_i w_i^2 sigma_i^2, the index variance of uncorrelated constituents
+  fn own_variance(&self) -> f64 {
+    (&self.weights * &self.vols).mapv(|x| x * x).sum()
+  }
+
+  /// sum_(i != j) w_i w_j sigma_i sigma_j, the index variance per unit of correlation
+  fn cross_variance(&self) -> f64 {
+    (&self.weights * &self.vols).sum().powi(2) - self.own_variance()
+  }
+
+  /// Average correlation implied by the index volatility, it is outside of [-1, 1] when
+  /// the volatilities are inconsistent
+  pub fn correlation(&self) -> f64 {
+    (self.index_vol.powi(2) - self.own_variance()) / self.cross_variance()
+  }
+
+  /// Index volatility of the average correlation rho
+  pub fn index_vol(&self, rho: f64) -> f64 {
+    (self.own_variance() + rho * self.cross_variance())
+      .max(0.0)
+      .sqrt()
+  }
+
+  /// Equicorrelation matrix of the implied correlation, clamped to [-1 / (d - 1), 1] where
+  /// the matrix is positive semi-definite
+  pub fn correlation_matrix(&self) -> Array2<f64> {
+    let d = self.vols.len();
+    let rho = self.correlation().clamp(-1.0 / (d - 1) as f64, 1.0);
+
+    Array2::from_shape_fn((d, d), |(i, j)| if i == j { 1.0 } else { rho })
+  }
+
+  /// Covariance matrix sigma_i sigma_j rho_ij of the constituent log-returns per unit of
+  /// time
+  pub fn covariance_matrix(&self) -> Array2<f64> {
+    let correlation = self.correlation_matrix();
+    Array2::from_shape_fn(correlation.dim(), |(i, j)| {
+      self.vols[i] * self.vols[j] * correlation[[i, j]]
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::stochastic::{
+    noise::cgnsd::{CovarianceDecomposition, CGNSD},
+    SamplingVector,
+  };
+
+  fn basket() -> (Array1<f64>, Array1<f64>) {
+    (array![0.4, 0.3, 0.2, 0.1], array![0.25, 0.3, 0.2, 0.4])
+  }
+
+  #[test]
+  fn recovers_the_correlation_of_the_index_variance() {
+    let (weights, vols) = basket();
+    let rho = 0.45;
+    let covariance = Array2::from_shape_fn((4, 4), |(i, j)| {
+      vols[i] * vols[j] * if i == j { 1.0 } else { rho }
+    });
+    let index_vol = weights.dot(&covariance.dot(&weights)).sqrt();
+
+    let implied = ImpliedCorrelation::new(index_vol, weights, vols);
+    implied.validate().unwrap();
+    assert!((implied.correlation() - rho).abs() < 1e-12);
+    assert!((implied.index_vol(rho) - index_vol).abs() < 1e-12);
+    assert!((&implied.covariance_matrix() - &covariance)
+      .iter()
+      .all(|x| x.abs() < 1e-12));
+
+    // Perfectly correlated constituents and the bounds of the matrix
+    let (weights, vols) = basket();
+    let upper = ImpliedCorrelation::new(weights.dot(&vols), weights.clone(), vols.clone());
+    assert!((upper.correlation() - 1.0).abs() < 1e-12);
+    let lower = ImpliedCorrelation::new(0.0, weights, vols);
+    assert!(lower.correlation() < -1.0 / 3.0);
+    assert_eq!(lower.correlation_matrix()[[0, 1]], -1.0 / 3.0);
+  }
+
+  #[test]
+  fn correlation_matrix_drives_correlated_noise() {
+    let (weights, vols) = basket();
+    let implied = ImpliedCorrelation::new(0.22, weights, vols);
+    let rho = implied.correlation();
+    assert!(rho > 0.0 && rho < 1.0);
+
+    let n = 100_000;
+    let noise = CGNSD::new(
+      implied.correlation_matrix(),
+      n,
+      Some(n as f64),
+      None,
+      CovarianceDecomposition::Cholesky,
+    );
+    let increments = noise.sample();
+    let sample = increments.row(0).dot(&increments.row(3)) / n as f64;
+    assert!((sample - rho).abs() < 0.02);
+
+    let mismatched = ImpliedCorrelation::new(0.2, array![1.0], array![0.2, 0.3]);
+    assert!(mismatched.validate().is_err());
+  }
+}
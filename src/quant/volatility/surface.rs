@@ -0,0 +1,471 @@
+//! Implied volatility surface from scattered quotes.
+//!
+//! The quotes (K, T, sigma) are grouped by maturity and every slice is interpolated in the
+//! total implied variance w(y) = sigma^2 T over the log-moneyness y = ln(K / F(T)), either
+//! with an [`SviRaw`] fit or with a natural cubic spline through the quotes, linear beyond
+//! them. Between the slices w is linear in T at fixed log-moneyness, before the first slice
+//! w(y, T) = w_1(y) T / T_1 and beyond the last one the volatility is flat.
+//!
+//! [`VolSurface::check_arbitrage`] tests the slices for butterfly arbitrage with the
+//! density condition of Gatheral & Jacquier (2014)
+//!
+//! g(y) = (1 - y w' / (2 w))^2 - w'^2 / 4 (1 / w + 1 / 4) + w'' / 2 >= 0
+//!
+//! and consecutive slices for calendar arbitrage, w(y, T_i) <= w(y, T_(i + 1)).
+//!
+//! - Gatheral, J. (2006). The Volatility Surface: A Practitioner's Guide.
+//! - Gatheral, J., & Jacquier, A. (2014). Arbitrage-free SVI volatility surfaces.
+
+use ndarray::{Array1, Array2};
+use plotly::Plot;
+use polars::prelude::*;
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::calibration::svi::SviRaw,
+};
+
+/// Seconds of a year of the Yahoo expiration timestamps
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Interpolation of the slices in the log-moneyness
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmileInterpolation {
+  /// Raw SVI fit, at least 5 quotes per maturity
+  #[default]
+  Svi,
+  /// Natural cubic spline through the quotes, at least 3 quotes per maturity
+  CubicSpline,
+}
+
+/// Natural cubic spline, linear beyond the knots
+#[derive(Clone, Debug)]
+struct CubicSpline {
+  x: Vec<f64>,
+  y: Vec<f64>,
+  /// Second derivatives at the knots
+  m: Vec<f64>,
+}
+
+impl CubicSpline {
+  /// Spline through the points with increasing x
+  fn new(x: Vec<f64>, y: Vec<f64>) -> Self {
+    let n = x.len();
+    let h = x.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+    let mut m = vec![0.0; n];
+
+    // Thomas algorithm for the interior second derivatives, m_0 = m_(n - 1) = 0
+    if n > 2 {
+      let mut diagonal = vec![0.0; n];
+      let mut rhs = vec![0.0; n];
+      for i in 1..n - 1 {
+        diagonal[i] = 2.0 * (h[i - 1] + h[i]);
+        rhs[i] = 6.0 * ((y[i + 1] - y[i]) / h[i] - (y[i] - y[i - 1]) / h[i - 1]);
+        if i > 1 {
+          let factor = h[i - 1] / diagonal[i - 1];
+          diagonal[i] -= factor * h[i - 1];
+          rhs[i] -= factor * rhs[i - 1];
+        }
+      }
+      for i in (1..n - 1).rev() {
+        m[i] = (rhs[i] - h[i] * m[i + 1]) / diagonal[i];
+      }
+    }
+
+    Self { x, y, m }
+  }
+
+  fn eval(&self, x: f64) -> f64 {
+    let (xs, ys, m) = (&self.x, &self.y, &self.m);
+    let n = xs.len();
+
+    if x <= xs[0] {
+      let h = xs[1] - xs[0];
+      let slope = (ys[1] - ys[0]) / h - h * (2.0 * m[0] + m[1]) / 6.0;
+      return ys[0] + slope * (x - xs[0]);
+    }
+    if x >= xs[n - 1] {
+      let h = xs[n - 1] - xs[n - 2];
+      let slope = (ys[n - 1] - ys[n - 2]) / h + h * (m[n - 2] + 2.0 * m[n - 1]) / 6.0;
+      return ys[n - 1] + slope * (x - xs[n - 1]);
+    }
+
+    let i = xs.partition_point(|&knot| knot <= x) - 1;
+    let h = xs[i + 1] - xs[i];
+    let (a, b) = (xs[i + 1] - x, x - xs[i]);
+
+    m[i] * a.powi(3) / (6.0 * h)
+      + m[i + 1] * b.powi(3) / (6.0 * h)
+      + (ys[i] / h - m[i] * h / 6.0) * a
+      + (ys[i + 1] / h - m[i + 1] * h / 6.0) * b
+  }
+}
+
+/// Total variance of one maturity over the log-moneyness
+#[derive(Clone, Debug)]
+enum Smile {
+  Svi(SviRaw),
+  Spline(CubicSpline),
+}
+
+impl Smile {
+  fn total_variance(&self, y: f64) -> f64 {
+    match self {
+      Smile::Svi(svi) => svi.total_variance(y),
+      Smile::Spline(spline) => spline.eval(y),
+    }
+    .max(0.0)
+  }
+
+  /// Density condition g(y) by central differences
+  fn density(&self, y: f64) -> f64 {
+    let h = 1e-4;
+    let (w, up, down) = (
+      self.total_variance(y),
+      self.total_variance(y + h),
+      self.total_variance(y - h),
+    );
+    let dw = (up - down) / (2.0 * h);
+    let d2w = (up - 2.0 * w + down) / (h * h);
+
+    (1.0 - y * dw / (2.0 * w)).powi(2) - dw.powi(2) / 4.0 * (1.0 / w + 0.25) + d2w / 2.0
+  }
+}
+
+/// Quotes of the surface violating the absence of arbitrage, as (maturity, strike)
+#[derive(Clone, Debug, Default)]
+pub struct ArbitrageReport {
+  /// Negative density of the slice or non-positive total variance
+  pub butterfly: Vec<(f64, f64)>,
+  /// Total variance below the one of the previous maturity at the same log-moneyness
+  pub calendar: Vec<(f64, f64)>,
+}
+
+impl ArbitrageReport {
+  /// No violation found
+  pub fn is_free(&self) -> bool {
+    self.butterfly.is_empty() && self.calendar.is_empty()
+  }
+}
+
+/// Implied volatility surface
+#[derive(Clone, Debug)]
+pub struct VolSurface {
+  /// Underlying price
+  pub s: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: f64,
+  /// Quotes (strike, maturity, implied volatility) sorted by maturity and strike
+  pub points: Vec<(f64, f64, f64)>,
+  /// Interpolation of the slices
+  pub interpolation: SmileInterpolation,
+  /// Maturities and smiles of the slices
+  slices: Vec<(f64, Smile)>,
+}
+
+impl VolSurface {
+  /// Surface of the quotes (strike, maturity, implied volatility), the quotes of a slice
+  /// share the same maturity
+  pub fn new(
+    s: f64,
+    r: f64,
+    q: f64,
+    points: Vec<(f64, f64, f64)>,
+    interpolation: SmileInterpolation,
+  ) -> StochasticResult<Self> {
+    ensure(s > 0.0, "s must be positive")?;
+    ensure(
+      points
+        .iter()
+        .all(|&(k, t, vol)| k > 0.0 && t > 0.0 && vol.is_finite() && vol > 0.0),
+      "strikes, maturities and implied volatilities must be positive",
+    )?;
+
+    let mut points = points;
+    points.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)));
+    let min_quotes = match interpolation {
+      SmileInterpolation::Svi => 5,
+      SmileInterpolation::CubicSpline => 3,
+    };
+
+    let mut slices = Vec::new();
+    for quotes in points.chunk_by(|a, b| a.1 == b.1) {
+      let t = quotes[0].1;
+      ensure(
+        quotes.len() >= min_quotes,
+        &format!("at least {} quotes are needed at T = {}", min_quotes, t),
+      )?;
+      ensure(
+        quotes.windows(2).all(|w| w[1].0 > w[0].0),
+        &format!("strikes must be distinct at T = {}", t),
+      )?;
+
+      let y = quotes
+        .iter()
+        .map(|&(k, ..)| log_moneyness(s, r, q, k, t))
+        .collect::<Vec<_>>();
+      let w = quotes
+        .iter()
+        .map(|&(.., vol)| vol * vol * t)
+        .collect::<Vec<_>>();
+      let smile = match interpolation {
+        SmileInterpolation::Svi => Smile::Svi(SviRaw::fit(&y, &w)),
+        SmileInterpolation::CubicSpline => Smile::Spline(CubicSpline::new(y, w)),
+      };
+      slices.push((t, smile));
+    }
+    ensure(!slices.is_empty(), "at least 1 maturity is needed")?;
+
+    Ok(Self {
+      s,
+      r,
+      q,
+      points,
+      interpolation,
+      slices,
+    })
+  }
+
+  /// Surface of an options DataFrame of [`Yahoo`](crate::quant::yahoo::Yahoo) with the
+  /// columns `strike`, `expiration` (Unix seconds) and `implied_volatility`, valued at the
+  /// Unix time `eval`
+  ///
+  /// Expired quotes and quotes without an implied volatility above 0.1% are dropped.
+  pub fn from_dataframe(
+    df: &DataFrame,
+    s: f64,
+    r: f64,
+    q: f64,
+    eval: i64,
+    interpolation: SmileInterpolation,
+  ) -> anyhow::Result<Self> {
+    let column = |name: &str| -> anyhow::Result<Vec<Option<f64>>> {
+      Ok(
+        df.column(name)?
+          .cast(&DataType::Float64)?
+          .f64()?
+          .into_iter()
+          .collect(),
+      )
+    };
+    let (strikes, expirations, vols) = (
+      column("strike")?,
+      column("expiration")?,
+      column("implied_volatility")?,
+    );
+
+    let points = strikes
+      .iter()
+      .zip(&expirations)
+      .zip(&vols)
+      .filter_map(|((&k, &expiration), &vol)| {
+        let (k, t, vol) = (k?, (expiration? - eval as f64) / SECONDS_PER_YEAR, vol?);
+        (k > 0.0 && t > 0.0 && vol > 1e-3).then_some((k, t, vol))
+      })
+      .collect();
+
+    Ok(Self::new(s, r, q, points, interpolation)?)
+  }
+
+  /// Maturities of the slices
+  pub fn maturities(&self) -> Vec<f64> {
+    self.slices.iter().map(|(t, _)| *t).collect()
+  }
+
+  /// Log-moneyness ln(K / F(t)) of the strike k
+  pub fn log_moneyness(&self, k: f64, t: f64) -> f64 {
+    log_moneyness(self.s, self.r, self.q, k, t)
+  }
+
+  /// Total implied variance sigma^2 t of the strike k and the maturity t
+  pub fn total_variance(&self, k: f64, t: f64) -> f64 {
+    let y = self.log_moneyness(k, t);
+    let n = self.slices.len();
+    let (t_first, first) = &self.slices[0];
+    let (t_last, last) = &self.slices[n - 1];
+
+    if t <= *t_first {
+      return first.total_variance(y) * t / t_first;
+    }
+    if t >= *t_last {
+      return last.total_variance(y) * t / t_last;
+    }
+
+    let i = self.slices.partition_point(|(t_i, _)| *t_i <= t) - 1;
+    let ((t0, smile0), (t1, smile1)) = (&self.slices[i], &self.slices[i + 1]);
+    let weight = (t - t0) / (t1 - t0);
+
+    (1.0 - weight) * smile0.total_variance(y) + weight * smile1.total_variance(y)
+  }
+
+  /// Implied volatility of the strike k and the maturity t
+  pub fn vol(&self, k: f64, t: f64) -> f64 {
+    (self.total_variance(k, t) / t).sqrt()
+  }
+
+  /// Implied volatilities on the grid of maturities (rows) and strikes (columns)
+  pub fn vols(&self, strikes: &Array1<f64>, maturities: &Array1<f64>) -> Array2<f64> {
+    Array2::from_shape_fn((maturities.len(), strikes.len()), |(i, j)| {
+      self.vol(strikes[j], maturities[i])
+    })
+  }
+
+  /// 3D surface plot of the implied volatilities on the grid of strikes and maturities
+  pub fn plot(&self, strikes: &Array1<f64>, maturities: &Array1<f64>) -> Plot {
+    crate::plot::implied_vol_surface(strikes, maturities, &self.vols(strikes, maturities))
+  }
+
+  /// Butterfly arbitrage of every slice and calendar arbitrage of consecutive slices at the
+  /// strikes
+  pub fn check_arbitrage(&self, strikes: &[f64]) -> ArbitrageReport {
+    let mut report = ArbitrageReport::default();
+
+    for (i, (t, smile)) in self.slices.iter().enumerate() {
+      for &k in strikes {
+        let y = self.log_moneyness(k, *t);
+        if smile.total_variance(y) <= 0.0 || smile.density(y) < -1e-8 {
+          report.butterfly.push((*t, k));
+        }
+        if i > 0 && smile.total_variance(y) < self.slices[i - 1].1.total_variance(y) - 1e-10 {
+          report.calendar.push((*t, k));
+        }
+      }
+    }
+
+    report
+  }
+}
+
+/// ln(K / F(t)) with the forward F(t) = S e^((r - q) t)
+fn log_moneyness(s: f64, r: f64, q: f64, k: f64, t: f64) -> f64 {
+  (k / s).ln() - (r - q) * t
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const S: f64 = 100.0;
+  const R: f64 = 0.03;
+  const Q: f64 = 0.01;
+
+  fn strikes() -> Vec<f64> {
+    (0..=16).map(|i| 60.0 + 5.0 * i as f64).collect()
+  }
+
+  /// Quotes of raw SVI slices free of arbitrage
+  fn svi_points() -> (Vec<(f64, SviRaw)>, Vec<(f64, f64, f64)>) {
+    let slices = vec![
+      (
+        0.5,
+        SviRaw {
+          a: 0.01,
+          b: 0.05,
+          rho: -0.4,
+          m: 0.0,
+          sigma: 0.2,
+        },
+      ),
+      (
+        1.0,
+        SviRaw {
+          a: 0.025,
+          b: 0.06,
+          rho: -0.4,
+          m: 0.0,
+          sigma: 0.25,
+        },
+      ),
+    ];
+    let points = slices
+      .iter()
+      .flat_map(|(t, svi)| {
+        strikes().into_iter().map(move |k| {
+          let y = log_moneyness(S, R, Q, k, *t);
+          (k, *t, (svi.total_variance(y) / t).sqrt())
+        })
+      })
+      .collect();
+
+    (slices, points)
+  }
+
+  #[test]
+  fn svi_surface_interpolates_in_strike_and_time() {
+    let (slices, points) = svi_points();
+    let surface = VolSurface::new(S, R, Q, points.clone(), SmileInterpolation::Svi).unwrap();
+    assert_eq!(surface.maturities(), vec![0.5, 1.0]);
+
+    for &(k, t, vol) in &points {
+      assert!((surface.vol(k, t) - vol).abs() < 1e-4, "{} {}", k, t);
+    }
+    // Between the quoted strikes
+    let y = log_moneyness(S, R, Q, 97.5, 0.5);
+    let vol = (slices[0].1.total_variance(y) / 0.5).sqrt();
+    assert!((surface.vol(97.5, 0.5) - vol).abs() < 1e-4);
+
+    // Linear total variance in time at fixed log-moneyness, flat volatility beyond
+    let t = 0.75;
+    let y = surface.log_moneyness(100.0, t);
+    let w = 0.5 * (slices[0].1.total_variance(y) + slices[1].1.total_variance(y));
+    assert!((surface.total_variance(100.0, t) - w).abs() < 1e-5);
+    let y = surface.log_moneyness(100.0, 2.0);
+    assert!((surface.vol(100.0, 2.0) - slices[1].1.total_variance(y).sqrt()).abs() < 1e-4);
+
+    assert!(surface.check_arbitrage(&strikes()).is_free());
+
+    let vols = surface.vols(&Array1::from(strikes()), &Array1::from(vec![0.5, 0.75]));
+    assert_eq!(vols.dim(), (2, 17));
+    assert_eq!(vols[[1, 8]], surface.vol(100.0, 0.75));
+  }
+
+  #[test]
+  fn arbitrage_checks_flag_violations() {
+    // The later maturity has a lower total variance at every strike
+    let points = strikes()
+      .into_iter()
+      .flat_map(|k| [(k, 0.5, 0.3), (k, 0.6, 0.2)])
+      .collect::<Vec<_>>();
+    let surface = VolSurface::new(S, R, Q, points, SmileInterpolation::CubicSpline).unwrap();
+    let report = surface.check_arbitrage(&strikes());
+    assert!(report.butterfly.is_empty());
+    assert_eq!(report.calendar.len(), strikes().len());
+    assert!(report.calendar.iter().all(|&(t, _)| t == 0.6));
+
+    // A spike in the smile has a negative density next to it
+    let points = strikes()
+      .into_iter()
+      .map(|k| (k, 1.0, if k == 100.0 { 0.4 } else { 0.2 }))
+      .collect::<Vec<_>>();
+    let surface = VolSurface::new(S, R, Q, points, SmileInterpolation::CubicSpline).unwrap();
+    assert!((surface.vol(100.0, 1.0) - 0.4).abs() < 1e-12);
+    let fine = (0..=400).map(|i| 80.0 + 0.1 * i as f64).collect::<Vec<_>>();
+    assert!(!surface.check_arbitrage(&fine).butterfly.is_empty());
+
+    let too_few = vec![(90.0, 1.0, 0.2), (100.0, 1.0, 0.2)];
+    assert!(VolSurface::new(S, R, Q, too_few, SmileInterpolation::Svi).is_err());
+  }
+
+  #[test]
+  fn surface_from_options_dataframe() {
+    let eval = 1_700_000_000_i64;
+    let (_, points) = svi_points();
+    let df = df!(
+      "strike" => points.iter().map(|p| p.0).collect::<Vec<_>>(),
+      "expiration" => points
+        .iter()
+        .map(|p| eval + (p.1 * SECONDS_PER_YEAR) as i64)
+        .collect::<Vec<_>>(),
+      "implied_volatility" => points.iter().map(|p| p.2).collect::<Vec<_>>()
+    )
+    .unwrap();
+
+    let surface = VolSurface::from_dataframe(&df, S, R, Q, eval, SmileInterpolation::Svi).unwrap();
+    assert_eq!(surface.points.len(), points.len());
+    for &(k, t, vol) in &points {
+      assert!((surface.vol(k, t) - vol).abs() < 1e-4);
+    }
+  }
+}
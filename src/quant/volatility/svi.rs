@@ -0,0 +1,445 @@
+//! Raw SVI and SSVI smiles with Levenberg-Marquardt calibration.
+//!
+//! The raw SVI slice ([`SviRaw`]) of the total implied variance is
+//!
+//! w(k) = a + b (rho (k - m) + sqrt((k - m)^2 + sigma^2)),  k = ln(K / F)
+//!
+//! [`SviRaw::calibrate`] refines the quasi-explicit fit with Levenberg-Marquardt under
+//! b >= 0, |rho| < 1, sigma > 0, a positive minimum variance a + b sigma sqrt(1 - rho^2)
+//! and the moment bound of Lee (2004) on the wings, b (1 + |rho|) <= 2. These conditions
+//! are necessary, butterfly arbitrage of a single slice is checked by
+//! [`VolSurface::check_arbitrage`].
+//!
+//! The SSVI surface ([`Ssvi`]) of Gatheral & Jacquier (2014) is
+//!
+//! w(k, t) = theta_t / 2 (1 + rho phi k + sqrt((phi k + rho)^2 + 1 - rho^2)),  phi = phi(theta_t)
+//!
+//! with the ATM total variance theta_t and the power law
+//! phi(theta) = eta / (theta^gamma (1 + theta)^(1 - gamma)). It is free of static arbitrage
+//! if theta_t is non-decreasing, 0 < gamma <= 1/2 and eta (1 + |rho|) <= 2, which
+//! [`Ssvi::calibrate`] enforces by construction. Every SSVI slice is a raw SVI slice
+//! ([`Ssvi::slice`]).
+//!
+//! - Gatheral, J., & Jacquier, A. (2014). Arbitrage-free SVI volatility surfaces.
+//! - Lee, R. W. (2004). The moment formula for implied volatility at extreme strikes.
+
+use levenberg_marquardt::{LeastSquaresProblem, LevenbergMarquardt};
+use nalgebra::{DMatrix, DVector, Dyn, Owned};
+
+pub use crate::quant::calibration::svi::SviRaw;
+use crate::{
+  error::StochasticResult,
+  quant::volatility::surface::{SmileInterpolation, VolSurface},
+};
+
+/// Least squares problem over unconstrained parameters, the constraints are in the
+/// mapping of the parameters inside the residuals
+struct Problem<'a> {
+  x: DVector<f64>,
+  residuals: &'a dyn Fn(&DVector<f64>) -> DVector<f64>,
+}
+
+impl LeastSquaresProblem<f64, Dyn, Dyn> for Problem<'_> {
+  type JacobianStorage = Owned<f64, Dyn, Dyn>;
+  type ParameterStorage = Owned<f64, Dyn>;
+  type ResidualStorage = Owned<f64, Dyn>;
+
+  fn set_params(&mut self, x: &DVector<f64>) {
+    self.x.copy_from(x);
+  }
+
+  fn params(&self) -> DVector<f64> {
+    self.x.clone()
+  }
+
+  fn residuals(&self) -> Option<DVector<f64>> {
+    Some((self.residuals)(&self.x))
+  }
+
+  /// Jacobian by central differences
+  fn jacobian(&self) -> Option<DMatrix<f64>> {
+    let residuals = (self.residuals)(&self.x);
+    let mut jacobian = DMatrix::zeros(residuals.len(), self.x.len());
+
+    for i in 0..self.x.len() {
+      let h = 1e-6 * self.x[i].abs().max(1e-2);
+      let [up, down] = [h, -h].map(|bump| {
+        let mut x = self.x.clone();
+        x[i] += bump;
+        (self.residuals)(&x)
+      });
+      jacobian.set_column(i, &((up - down) / (2.0 * h)));
+    }
+
+    Some(jacobian)
+  }
+}
+
+/// Minimize the squared residuals from x0
+pub(crate) fn minimize(
+  x0: DVector<f64>,
+  residuals: &dyn Fn(&DVector<f64>) -> DVector<f64>,
+) -> DVector<f64> {
+  let (problem, _) = LevenbergMarquardt::new().minimize(Problem { x: x0, residuals });
+  problem.x
+}
+
+fn sigmoid(x: f64) -> f64 {
+  1.0 / (1.0 + (-x).exp())
+}
+
+fn logit(p: f64) -> f64 {
+  let p = p.clamp(1e-9, 1.0 - 1e-9);
+  (p / (1.0 - p)).ln()
+}
+
+impl SviRaw {
+  /// Minimum total variance a + b sigma sqrt(1 - rho^2)
+  pub fn min_variance(&self) -> f64 {
+    self.a + self.b * self.sigma * (1.0 - self.rho.powi(2)).sqrt()
+  }
+
+  /// The parameter constraints and the moment bound of the wings hold
+  pub fn is_admissible(&self) -> bool {
+    self.b >= 0.0
+      && self.rho.abs() < 1.0
+      && self.sigma > 0.0
+      && self.min_variance() >= 0.0
+      && self.b * (1.0 + self.rho.abs()) <= 2.0 + 1e-12
+  }
+
+  /// Fit to total implied variances w at log-moneyness k under the constraints, starting
+  /// from the quasi-explicit fit [`SviRaw::fit`], at least 5 quotes are needed
+  pub fn calibrate(k: &[f64], w: &[f64]) -> Self {
+    let start = Self::fit(k, w);
+
+    // x = (ln min variance, logit of b / the wing bound, atanh rho, m, ln sigma)
+    let rho = start.rho.clamp(-0.999, 0.999);
+    let bound = 2.0 / (1.0 + rho.abs());
+    let x0 = DVector::from_vec(vec![
+      start.min_variance().max(1e-8).ln(),
+      logit(start.b / bound),
+      rho.atanh(),
+      start.m,
+      start.sigma.max(1e-6).ln(),
+    ]);
+    let params = |x: &DVector<f64>| {
+      let rho = x[2].tanh();
+      let b = 2.0 / (1.0 + rho.abs()) * sigmoid(x[1]);
+      let sigma = x[4].exp();
+      Self {
+        a: x[0].exp() - b * sigma * (1.0 - rho.powi(2)).sqrt(),
+        b,
+        rho,
+        m: x[3],
+        sigma,
+      }
+    };
+    let residuals = |x: &DVector<f64>| {
+      let svi = params(x);
+      DVector::from_iterator(
+        k.len(),
+        k.iter().zip(w).map(|(&k, &w)| svi.total_variance(k) - w),
+      )
+    };
+
+    params(&minimize(x0, &residuals))
+  }
+}
+
+/// Raw SVI slices of the maturities of the surface, calibrated to its quotes
+pub fn calibrate_slices(surface: &VolSurface) -> Vec<(f64, SviRaw)> {
+  surface
+    .points
+    .chunk_by(|a, b| a.1 == b.1)
+    .map(|quotes| {
+      let t = quotes[0].1;
+      let (k, w): (Vec<_>, Vec<_>) = quotes
+        .iter()
+        .map(|&(strike, t, vol)| (surface.log_moneyness(strike, t), vol * vol * t))
+        .unzip();
+      (t, SviRaw::calibrate(&k, &w))
+    })
+    .collect()
+}
+
+/// Surface of the raw SVI slices quoted at the strikes
+pub fn slices_to_vol_surface(
+  s: f64,
+  r: f64,
+  q: f64,
+  slices: &[(f64, SviRaw)],
+  strikes: &[f64],
+) -> StochasticResult<VolSurface> {
+  let points = slices
+    .iter()
+    .flat_map(|(t, svi)| {
+      strikes.iter().map(move |&strike| {
+        let k = (strike / s).ln() - (r - q) * t;
+        (strike, *t, (svi.total_variance(k) / t).sqrt())
+      })
+    })
+    .collect();
+
+  VolSurface::new(s, r, q, points, SmileInterpolation::Svi)
+}
+
+/// SSVI surface with the power law phi
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ssvi {
+  /// Correlation, the skew of the slices
+  pub rho: f64,
+  /// Level of the curvature
+  pub eta: f64,
+  /// Decay of the curvature with the ATM total variance
+  pub gamma: f64,
+  /// Maturities and ATM total variances theta_t, increasing
+  pub thetas: Vec<(f64, f64)>,
+}
+
+impl Ssvi {
+  /// Curvature phi(theta) = eta / (theta^gamma (1 + theta)^(1 - gamma))
+  pub fn phi(&self, theta: f64) -> f64 {
+    self.eta / (theta.powf(self.gamma) * (1.0 + theta).powf(1.0 - self.gamma))
+  }
+
+  /// ATM total variance, linear between the maturities from theta_0 = 0 and with a flat
+  /// ATM volatility beyond the last one
+  pub fn theta(&self, t: f64) -> f64 {
+    let (t_last, theta_last) = self.thetas[self.thetas.len() - 1];
+    if t >= t_last {
+      return theta_last * t / t_last;
+    }
+
+    let i = self.thetas.partition_point(|&(t_i, _)| t_i < t);
+    let (t0, theta0) = match i {
+      0 => (0.0, 0.0),
+      _ => self.thetas[i - 1],
+    };
+    let (t1, theta1) = self.thetas[i];
+    theta0 + (theta1 - theta0) * (t - t0) / (t1 - t0)
+  }
+
+  /// Total implied variance at log-moneyness k and maturity t
+  pub fn total_variance(&self, k: f64, t: f64) -> f64 {
+    let theta = self.theta(t);
+    let phi = self.phi(theta);
+    let rho = self.rho;
+
+    0.5 * theta * (1.0 + rho * phi * k + ((phi * k + rho).powi(2) + 1.0 - rho.powi(2)).sqrt())
+  }
+
+  /// Implied volatility at log-moneyness k and maturity t
+  pub fn vol(&self, k: f64, t: f64) -> f64 {
+    (self.total_variance(k, t) / t).sqrt()
+  }
+
+  /// Raw SVI parameters of the slice of maturity t
+  pub fn slice(&self, t: f64) -> SviRaw {
+    let theta = self.theta(t);
+    let phi = self.phi(theta);
+    let rho = self.rho;
+
+    SviRaw {
+      a: 0.5 * theta * (1.0 - rho.powi(2)),
+      b: 0.5 * theta * phi,
+      rho,
+      m: -rho / phi,
+      sigma: (1.0 - rho.powi(2)).sqrt() / phi,
+    }
+  }
+
+  /// The sufficient conditions of Gatheral & Jacquier for the absence of static arbitrage
+  pub fn is_arbitrage_free(&self) -> bool {
+    self.rho.abs() < 1.0
+      && self.gamma > 0.0
+      && self.gamma <= 0.5
+      && self.eta > 0.0
+      && self.eta * (1.0 + self.rho.abs()) <= 2.0 + 1e-12
+      && self.thetas[0].1 > 0.0
+      && self.thetas.windows(2).all(|w| w[1].1 >= w[0].1)
+  }
+
+  /// Fit to the quotes (log-moneyness, maturity, total variance)
+  ///
+  /// theta_t of every maturity is the total variance interpolated linearly at k = 0,
+  /// floored by the one of the previous maturity, then (rho, eta, gamma) minimize the squared implied
+  /// variance errors within the no-arbitrage region.
+  pub fn calibrate(quotes: &[(f64, f64, f64)]) -> Self {
+    let mut quotes = quotes.to_vec();
+    quotes.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)));
+
+    assert!(
+      quotes.len() > 3,
+      "more quotes than the 3 parameters are needed"
+    );
+
+    let mut thetas: Vec<(f64, f64)> = Vec::new();
+    for slice in quotes.chunk_by(|a, b| a.1 == b.1) {
+      assert!(
+        slice.len() >= 2,
+        "at least 2 quotes per maturity are needed"
+      );
+      let hi = slice
+        .partition_point(|&(k, ..)| k < 0.0)
+        .clamp(1, slice.len() - 1);
+      let ((k0, _, w0), (k1, _, w1)) = (slice[hi - 1], slice[hi]);
+      let atm = w0 + (w1 - w0) * (0.0 - k0) / (k1 - k0);
+      let floor = thetas.last().map_or(1e-8, |&(_, theta)| theta);
+      thetas.push((slice[0].1, atm.max(floor)));
+    }
+
+    // x = (atanh rho, logit of eta / its bound, logit of 2 gamma)
+    let params = |x: &DVector<f64>| {
+      let rho = x[0].tanh();
+      Self {
+        rho,
+        eta: 2.0 / (1.0 + rho.abs()) * sigmoid(x[1]),
+        gamma: 0.5 * sigmoid(x[2]),
+        thetas: thetas.clone(),
+      }
+    };
+    let residuals = |x: &DVector<f64>| {
+      let ssvi = params(x);
+      DVector::from_iterator(
+        quotes.len(),
+        quotes
+          .iter()
+          .map(|&(k, t, w)| (ssvi.total_variance(k, t) - w) / t),
+      )
+    };
+
+    params(&minimize(
+      DVector::from_vec(vec![-0.5, 0.0, 0.0]),
+      &residuals,
+    ))
+  }
+
+  /// Fit to the quotes of the surface
+  pub fn from_vol_surface(surface: &VolSurface) -> Self {
+    let quotes = surface
+      .points
+      .iter()
+      .map(|&(strike, t, vol)| (surface.log_moneyness(strike, t), t, vol * vol * t))
+      .collect::<Vec<_>>();
+
+    Self::calibrate(&quotes)
+  }
+
+  /// Surface of the SSVI slices of the maturities quoted at the strikes
+  pub fn to_vol_surface(
+    &self,
+    s: f64,
+    r: f64,
+    q: f64,
+    strikes: &[f64],
+  ) -> StochasticResult<VolSurface> {
+    let slices = self
+      .thetas
+      .iter()
+      .map(|&(t, _)| (t, self.slice(t)))
+      .collect::<Vec<_>>();
+
+    slices_to_vol_surface(s, r, q, &slices, strikes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn log_moneyness() -> Vec<f64> {
+    (0..=20).map(|i| -0.5 + 0.05 * i as f64).collect()
+  }
+
+  fn ssvi() -> Ssvi {
+    Ssvi {
+      rho: -0.6,
+      eta: 1.1,
+      gamma: 0.4,
+      thetas: vec![(0.25, 0.012), (0.5, 0.022), (1.0, 0.045), (2.0, 0.085)],
+    }
+  }
+
+  #[test]
+  fn raw_svi_calibration_with_constraints() {
+    let k = log_moneyness();
+    let truth = SviRaw {
+      a: 0.02,
+      b: 0.12,
+      rho: -0.5,
+      m: 0.05,
+      sigma: 0.15,
+    };
+    let w = k
+      .iter()
+      .map(|&k| truth.total_variance(k))
+      .collect::<Vec<_>>();
+    let svi = SviRaw::calibrate(&k, &w);
+    assert!(svi.is_admissible());
+    for (&k, &w) in k.iter().zip(&w) {
+      assert!((svi.total_variance(k) - w).abs() < 1e-6);
+    }
+
+    // Wings steeper than the moment bound are flattened to it
+    let steep = SviRaw { b: 1.8, ..truth };
+    assert!(!steep.is_admissible());
+    let w = k
+      .iter()
+      .map(|&k| steep.total_variance(k))
+      .collect::<Vec<_>>();
+    let svi = SviRaw::calibrate(&k, &w);
+    assert!(svi.is_admissible());
+    assert!(svi.b * (1.0 + svi.rho.abs()) > 1.9);
+  }
+
+  #[test]
+  fn ssvi_calibration_recovers_the_surface() {
+    let truth = ssvi();
+    assert!(truth.is_arbitrage_free());
+    let quotes = truth
+      .thetas
+      .iter()
+      .flat_map(|&(t, _)| {
+        log_moneyness()
+          .into_iter()
+          .map(move |k| (k, t, ssvi().total_variance(k, t)))
+      })
+      .collect::<Vec<_>>();
+
+    let ssvi = Ssvi::calibrate(&quotes);
+    assert!(ssvi.is_arbitrage_free());
+    assert!((ssvi.rho - truth.rho).abs() < 1e-3, "{:?}", ssvi);
+    assert!((ssvi.eta - truth.eta).abs() < 1e-3);
+    assert!((ssvi.gamma - truth.gamma).abs() < 1e-3);
+
+    // Every slice is a raw SVI slice
+    for &(t, _) in &truth.thetas {
+      let slice = truth.slice(t);
+      for k in log_moneyness() {
+        assert!((slice.total_variance(k) - truth.total_variance(k, t)).abs() < 1e-12);
+      }
+    }
+  }
+
+  #[test]
+  fn vol_surface_round_trip() {
+    let (s, r, q) = (100.0, 0.02, 0.0);
+    let strikes = (0..=16).map(|i| 60.0 + 5.0 * i as f64).collect::<Vec<_>>();
+    let surface = ssvi().to_vol_surface(s, r, q, &strikes).unwrap();
+    assert!(surface.check_arbitrage(&strikes).is_free());
+
+    // The ATM total variances are interpolated between the quoted strikes
+    let fitted = Ssvi::from_vol_surface(&surface);
+    assert!(fitted.is_arbitrage_free());
+    assert!((fitted.rho - -0.6).abs() < 0.02, "{:?}", fitted);
+
+    let slices = calibrate_slices(&surface);
+    assert_eq!(slices.len(), 4);
+    for (t, svi) in &slices {
+      assert!(svi.is_admissible());
+      let k = surface.log_moneyness(90.0, *t);
+      assert!((svi.total_variance(k) - ssvi().total_variance(k, *t)).abs() < 1e-5);
+    }
+  }
+}
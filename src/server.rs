@@ -0,0 +1,162 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+  extract::{
+    ws::{Message, WebSocket, WebSocketUpgrade},
+    Path, Query, State,
+  },
+  http::StatusCode,
+  response::{IntoResponse, Response},
+  routing::get,
+  Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::stochastic::{catalog, Sampling};
+
+/// A single simulated tick sent over the websocket
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Tick {
+  /// Index of the simulated path
+  pub path: usize,
+  /// Index of the time step within the path
+  pub step: usize,
+  /// Simulation time of the tick
+  pub t: f64,
+  /// Value of the process
+  pub value: f64,
+}
+
+/// Query parameters of a stream request
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct StreamParams {
+  /// Delay between two ticks in milliseconds
+  pub interval_ms: Option<u64>,
+  /// Number of paths to stream, unlimited if None
+  pub paths: Option<usize>,
+  /// Time horizon of a single path used to timestamp the ticks
+  pub t: Option<f64>,
+}
+
+/// Streaming simulation server.
+///
+/// Every registered sampler is exposed as `GET /stream/{name}` which upgrades to a
+/// websocket and streams JSON encoded [`Tick`]s at the requested rate. When a path
+/// is exhausted a fresh one is sampled. `GET /catalog` lists the description of every
+/// implemented model (see [`catalog`]).
+#[derive(Clone, Default)]
+pub struct SimulationServer {
+  samplers: HashMap<String, Arc<dyn Sampling<f64>>>,
+}
+
+impl SimulationServer {
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Register a sampler under the given name
+  pub fn register(mut self, name: &str, sampler: impl Sampling<f64> + 'static) -> Self {
+    self.samplers.insert(name.to_string(), Arc::new(sampler));
+    self
+  }
+
+  /// Names of the registered samplers
+  pub fn names(&self) -> Vec<String> {
+    self.samplers.keys().cloned().collect()
+  }
+
+  /// Build the axum router
+  pub fn router(self) -> Router {
+    Router::new()
+      .route("/samplers", get(list))
+      .route("/catalog", get(models))
+      .route("/stream/:name", get(stream))
+      .with_state(Arc::new(self))
+  }
+
+  /// Serve the router on the given address
+  pub async fn serve(self, addr: SocketAddr) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, self.router()).await?;
+    Ok(())
+  }
+}
+
+async fn list(State(server): State<Arc<SimulationServer>>) -> Response {
+  axum::Json(server.names()).into_response()
+}
+
+async fn models() -> Response {
+  axum::Json(catalog()).into_response()
+}
+
+async fn stream(
+  ws: WebSocketUpgrade,
+  Path(name): Path<String>,
+  Query(params): Query<StreamParams>,
+  State(server): State<Arc<SimulationServer>>,
+) -> Response {
+  match server.samplers.get(&name) {
+    Some(sampler) => {
+      let sampler = sampler.clone();
+      ws.on_upgrade(move |socket| send_ticks(socket, sampler, params))
+    }
+    None => (StatusCode::NOT_FOUND, format!("Unknown sampler: {}", name)).into_response(),
+  }
+}
+
+async fn send_ticks(mut socket: WebSocket, sampler: Arc<dyn Sampling<f64>>, params: StreamParams) {
+  let mut interval =
+    tokio::time::interval(Duration::from_millis(params.interval_ms.unwrap_or(100)));
+  let mut path = 0;
+
+  while params.paths.is_none_or(|paths| path < paths) {
+    let sampler_ = sampler.clone();
+    let Ok(values) = tokio::task::spawn_blocking(move || sampler_.sample()).await else {
+      break;
+    };
+    let dt = params.t.unwrap_or(1.0) / (values.len().max(2) - 1) as f64;
+
+    for (step, &value) in values.iter().enumerate() {
+      interval.tick().await;
+
+      let tick = Tick {
+        path,
+        step,
+        t: step as f64 * dt,
+        value,
+      };
+      let Ok(message) = serde_json::to_string(&tick) else {
+        return;
+      };
+
+      if socket.send(Message::Text(message)).await.is_err() {
+        // Client disconnected
+        return;
+      }
+    }
+
+    path += 1;
+  }
+
+  let _ = socket.send(Message::Close(None)).await;
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::stochastic::{diffusion::ou::OU, N};
+
+  use super::*;
+
+  #[test]
+  fn server_registers_samplers() {
+    let server = SimulationServer::new().register(
+      "ou",
+      OU::new(2.0, 1.0, 0.8, N, Some(0.0), Some(1.0), None, None),
+    );
+
+    assert_eq!(server.names(), vec!["ou".to_string()]);
+    let _ = server.router();
+  }
+}
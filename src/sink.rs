@@ -0,0 +1,207 @@
+#[cfg(feature = "kafka")]
+pub mod kafka;
+
+use std::sync::Mutex;
+
+use ndarray::{Array2, Axis};
+use serde::{Deserialize, Serialize};
+
+/// Avro schema of a [`PathRecord`]
+pub const PATH_RECORD_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "PathRecord",
+  "namespace": "stochastic_rs",
+  "fields": [
+    { "name": "model", "type": "string" },
+    { "name": "path", "type": "long" },
+    { "name": "kind", "type": "string" },
+    { "name": "dt", "type": "double" },
+    { "name": "values", "type": { "type": "array", "items": "double" } }
+  ]
+}"#;
+
+/// What is published for every simulated path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Payload {
+  /// The full path
+  #[default]
+  Path,
+  /// The increments of the path
+  Increments,
+}
+
+/// Wire format of the published records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+  #[default]
+  Json,
+  /// Avro binary datum without container header, see [`PATH_RECORD_SCHEMA`]
+  Avro,
+}
+
+/// A single simulated path or its increments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathRecord {
+  /// Name of the model which generated the path
+  pub model: String,
+  /// Index of the path within the batch
+  pub path: i64,
+  /// `path` or `increments`
+  pub kind: String,
+  /// Time step
+  pub dt: f64,
+  /// Values
+  pub values: Vec<f64>,
+}
+
+impl PathRecord {
+  /// Encode the record
+  pub fn encode(&self, encoding: Encoding) -> anyhow::Result<Vec<u8>> {
+    match encoding {
+      Encoding::Json => Ok(serde_json::to_vec(self)?),
+      Encoding::Avro => Ok(self.to_avro_datum()),
+    }
+  }
+
+  /// Avro binary encoding of the record
+  /// https://avro.apache.org/docs/1.11.1/specification/#binary-encoding
+  fn to_avro_datum(&self) -> Vec<u8> {
+    fn long(buf: &mut Vec<u8>, v: i64) {
+      let mut z = ((v << 1) ^ (v >> 63)) as u64;
+      while z >= 0x80 {
+        buf.push((z as u8) | 0x80);
+        z >>= 7;
+      }
+      buf.push(z as u8);
+    }
+
+    fn string(buf: &mut Vec<u8>, s: &str) {
+      long(buf, s.len() as i64);
+      buf.extend_from_slice(s.as_bytes());
+    }
+
+    let mut buf = Vec::with_capacity(32 + 8 * self.values.len());
+    string(&mut buf, &self.model);
+    long(&mut buf, self.path);
+    string(&mut buf, &self.kind);
+    buf.extend_from_slice(&self.dt.to_le_bytes());
+
+    if !self.values.is_empty() {
+      long(&mut buf, self.values.len() as i64);
+      for v in &self.values {
+        buf.extend_from_slice(&v.to_le_bytes());
+      }
+    }
+    long(&mut buf, 0);
+
+    buf
+  }
+}
+
+/// Destination of simulated paths.
+///
+/// Implementors only have to provide [`Sink::send`], batching of the
+/// output of `sample_par` is handled by [`Sink::publish`].
+pub trait Sink {
+  /// Send a single encoded record
+  fn send(&self, key: &str, payload: &[u8]) -> anyhow::Result<()>;
+
+  /// Flush buffered records
+  fn flush(&self) -> anyhow::Result<()> {
+    Ok(())
+  }
+
+  /// Publish every row of a matrix of paths (e.g. from `sample_par`)
+  ///
+  /// Returns the number of published records.
+  fn publish(
+    &self,
+    model: &str,
+    paths: &Array2<f64>,
+    dt: f64,
+    payload: Payload,
+    encoding: Encoding,
+  ) -> anyhow::Result<usize> {
+    for (idx, path) in paths.axis_iter(Axis(0)).enumerate() {
+      let (kind, values) = match payload {
+        Payload::Path => ("path", path.to_vec()),
+        Payload::Increments => (
+          "increments",
+          path.windows(2).into_iter().map(|w| w[1] - w[0]).collect(),
+        ),
+      };
+
+      let record = PathRecord {
+        model: model.to_string(),
+        path: idx as i64,
+        kind: kind.to_string(),
+        dt,
+        values,
+      };
+
+      self.send(&format!("{}-{}", model, idx), &record.encode(encoding)?)?;
+    }
+
+    self.flush()?;
+    Ok(paths.nrows())
+  }
+}
+
+/// In-memory sink, useful for testing pipelines
+#[derive(Default)]
+pub struct MemorySink {
+  pub records: Mutex<Vec<(String, Vec<u8>)>>,
+}
+
+impl Sink for MemorySink {
+  fn send(&self, key: &str, payload: &[u8]) -> anyhow::Result<()> {
+    self
+      .records
+      .lock()
+      .unwrap()
+      .push((key.to_string(), payload.to_vec()));
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+
+  #[test]
+  fn memory_sink_publishes_increments() {
+    let sink = MemorySink::default();
+    let paths = array![[0.0, 1.0, 3.0], [0.0, -1.0, -0.5]];
+    let n = sink
+      .publish("bm", &paths, 0.5, Payload::Increments, Encoding::Json)
+      .unwrap();
+    assert_eq!(n, 2);
+
+    let records = sink.records.lock().unwrap();
+    let record: PathRecord = serde_json::from_slice(&records[1].1).unwrap();
+    assert_eq!(records[1].0, "bm-1");
+    assert_eq!(record.values, vec![-1.0, 0.5]);
+  }
+
+  #[test]
+  fn avro_datum_layout() {
+    let record = PathRecord {
+      model: "a".to_string(),
+      path: -1,
+      kind: "p".to_string(),
+      dt: 1.0,
+      values: vec![2.0],
+    };
+    let datum = record.encode(Encoding::Avro).unwrap();
+
+    // "a" -> len 1 (zigzag 2), path -1 -> zigzag 1, "p", dt, 1 item, 2.0, end of array
+    let mut expected = vec![2, b'a', 1, 2, b'p'];
+    expected.extend_from_slice(&1.0_f64.to_le_bytes());
+    expected.push(2);
+    expected.extend_from_slice(&2.0_f64.to_le_bytes());
+    expected.push(0);
+    assert_eq!(datum, expected);
+  }
+}
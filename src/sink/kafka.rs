@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use rdkafka::{
+  config::ClientConfig,
+  error::{KafkaError, RDKafkaErrorCode},
+  producer::{BaseProducer, BaseRecord, Producer},
+};
+
+use super::Sink;
+
+/// Kafka sink publishing every record to a single topic
+pub struct KafkaSink {
+  pub topic: String,
+  pub timeout: Duration,
+  producer: BaseProducer,
+}
+
+impl KafkaSink {
+  /// Create a new Kafka sink
+  ///
+  /// # Arguments
+  /// brokers: &str - comma separated list of bootstrap servers
+  /// topic: &str - destination topic
+  pub fn new(brokers: &str, topic: &str) -> anyhow::Result<Self> {
+    let producer = ClientConfig::new()
+      .set("bootstrap.servers", brokers)
+      .set("queue.buffering.max.ms", "5")
+      .create::<BaseProducer>()?;
+
+    Ok(Self::from_producer(producer, topic))
+  }
+
+  /// Create a new Kafka sink from a custom configured producer
+  pub fn from_producer(producer: BaseProducer, topic: &str) -> Self {
+    Self {
+      topic: topic.to_string(),
+      timeout: Duration::from_secs(30),
+      producer,
+    }
+  }
+}
+
+impl Sink for KafkaSink {
+  fn send(&self, key: &str, payload: &[u8]) -> anyhow::Result<()> {
+    loop {
+      let record = BaseRecord::to(&self.topic).key(key).payload(payload);
+
+      match self.producer.send(record) {
+        Ok(()) => break,
+        Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), _)) => {
+          // Wait for the delivery of queued messages
+          self.producer.poll(Duration::from_millis(100));
+        }
+        Err((err, _)) => return Err(err.into()),
+      }
+    }
+
+    self.producer.poll(Duration::ZERO);
+    Ok(())
+  }
+
+  fn flush(&self) -> anyhow::Result<()> {
+    self.producer.flush(self.timeout)?;
+    Ok(())
+  }
+}
@@ -1,6 +1,14 @@
 pub mod cir;
 pub mod double_exp;
+pub mod ewma;
+pub mod exponential_functional;
 pub mod fd;
 pub mod fou_estimator;
+pub mod hawkes;
+pub mod inversion;
 pub mod mle;
 pub mod non_central_chi_squared;
+pub mod regime_switching;
+pub mod resample;
+pub mod signature;
+pub mod trend;
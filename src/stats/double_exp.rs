@@ -2,7 +2,7 @@ use impl_new_derive::ImplNew;
 use rand::Rng;
 use rand_distr::Distribution;
 
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 pub struct DoubleExp {
   pub p: Option<f64>,
   pub lambda_plus: f64,
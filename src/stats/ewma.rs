@@ -0,0 +1,187 @@
+use ndarray::{Array1, Array2, Axis};
+use polars::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// RiskMetrics decay factor for daily returns
+pub const RISKMETRICS_DAILY_LAMBDA: f64 = 0.94;
+
+/// RiskMetrics decay factor for monthly returns
+pub const RISKMETRICS_MONTHLY_LAMBDA: f64 = 0.97;
+
+/// Convert the numeric columns of a return DataFrame to a (time x asset) matrix.
+///
+/// Missing values are treated as zero returns.
+pub fn returns_from_dataframe(df: &DataFrame) -> anyhow::Result<Array2<f64>> {
+  let mut returns = Array2::<f64>::zeros((df.height(), df.width()));
+
+  for (j, column) in df.get_columns().iter().enumerate() {
+    let column = column.cast(&DataType::Float64)?;
+    for (i, value) in column.f64()?.into_iter().enumerate() {
+      returns[[i, j]] = value.unwrap_or(0.0);
+    }
+  }
+
+  Ok(returns)
+}
+
+/// EWMA (RiskMetrics) conditional variance.
+///
+/// sigma^2_t = lambda * sigma^2_{t-1} + (1 - lambda) * r^2_{t-1}
+///
+/// The recursion is seeded with the mean of the squared returns. The returned
+/// array has `returns.len() + 1` elements, the last one being the one-step-ahead forecast.
+pub fn ewma_variance(returns: &Array1<f64>, lambda: f64) -> Array1<f64> {
+  assert!((0.0..1.0).contains(&lambda), "lambda must be in [0, 1)");
+
+  let n = returns.len();
+  let mut variance = Array1::<f64>::zeros(n + 1);
+  variance[0] = returns.mapv(|r| r.powi(2)).mean().unwrap_or(0.0);
+
+  for t in 1..=n {
+    variance[t] = lambda * variance[t - 1] + (1.0 - lambda) * returns[t - 1].powi(2);
+  }
+
+  variance
+}
+
+/// EWMA (RiskMetrics) one-step-ahead covariance matrix.
+///
+/// # Arguments
+/// returns: &Array2<f64> - (time x asset) matrix of returns
+/// lambda: f64 - decay factor
+pub fn ewma_covariance(returns: &Array2<f64>, lambda: f64) -> Array2<f64> {
+  assert!((0.0..1.0).contains(&lambda), "lambda must be in [0, 1)");
+
+  let n = returns.nrows().max(1) as f64;
+  let mut covariance = returns.t().dot(returns) / n;
+
+  for r in returns.axis_iter(Axis(0)) {
+    let r = r.insert_axis(Axis(1));
+    covariance = covariance * lambda + r.dot(&r.t()) * (1.0 - lambda);
+  }
+
+  covariance
+}
+
+/// EWMA correlation matrix derived from [`ewma_covariance`]
+pub fn ewma_correlation(returns: &Array2<f64>, lambda: f64) -> Array2<f64> {
+  let covariance = ewma_covariance(returns, lambda);
+  let std = covariance.diag().mapv(f64::sqrt);
+  let d = returns.ncols();
+
+  Array2::from_shape_fn((d, d), |(i, j)| covariance[[i, j]] / (std[i] * std[j]))
+}
+
+/// Root mean squared error between the squared returns and the EWMA variance forecasts.
+///
+/// This is the loss used by RiskMetrics to choose the decay factor.
+pub fn ewma_rmse(returns: &Array1<f64>, lambda: f64) -> f64 {
+  let variance = ewma_variance(returns, lambda);
+  let n = returns.len() as f64;
+
+  (returns
+    .iter()
+    .zip(variance.iter())
+    .map(|(r, v)| (r.powi(2) - v).powi(2))
+    .sum::<f64>()
+    / n)
+    .sqrt()
+}
+
+/// Decay factor minimizing [`ewma_rmse`] on [lower, upper] by golden-section search
+pub fn optimal_lambda(returns: &Array1<f64>, lower: f64, upper: f64, tol: f64) -> f64 {
+  let inv_phi = (5.0_f64.sqrt() - 1.0) / 2.0;
+  let (mut a, mut b) = (lower, upper);
+  let mut c = b - inv_phi * (b - a);
+  let mut d = a + inv_phi * (b - a);
+
+  while (b - a).abs() > tol {
+    if ewma_rmse(returns, c) < ewma_rmse(returns, d) {
+      b = d;
+    } else {
+      a = c;
+    }
+
+    c = b - inv_phi * (b - a);
+    d = a + inv_phi * (b - a);
+  }
+
+  0.5 * (a + b)
+}
+
+/// Devolatilized returns r_t / sigma_t used by filtered historical simulation
+pub fn standardized_residuals(returns: &Array1<f64>, lambda: f64) -> Array1<f64> {
+  let variance = ewma_variance(returns, lambda);
+
+  Array1::from_shape_fn(returns.len(), |t| returns[t] / variance[t].sqrt())
+}
+
+/// Parametric (normal) one-day Value at Risk with EWMA volatility.
+///
+/// Returned as a positive loss quantile at the given confidence level (e.g. 0.99).
+pub fn ewma_var(returns: &Array1<f64>, lambda: f64, confidence: f64) -> f64 {
+  let sigma = ewma_variance(returns, lambda).last().unwrap().sqrt();
+  let z = Normal::default().inverse_cdf(confidence);
+
+  z * sigma
+}
+
+/// Filtered historical simulation one-day Value at Risk.
+///
+/// The standardized residuals are rescaled with the EWMA volatility forecast and the
+/// empirical loss quantile is returned as a positive number.
+pub fn fhs_var(returns: &Array1<f64>, lambda: f64, confidence: f64) -> f64 {
+  let sigma = ewma_variance(returns, lambda).last().unwrap().sqrt();
+  let mut scenarios = standardized_residuals(returns, lambda)
+    .mapv(|z| z * sigma)
+    .to_vec();
+  scenarios.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+  let idx =
+    (((1.0 - confidence) * scenarios.len() as f64).floor() as usize).min(scenarios.len() - 1);
+
+  -scenarios[idx]
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray_rand::RandomExt;
+  use rand_distr::Normal as RandNormal;
+
+  use super::*;
+
+  #[test]
+  fn ewma_variance_recursion() {
+    let returns = Array1::from(vec![0.01, -0.02, 0.015]);
+    let variance = ewma_variance(&returns, RISKMETRICS_DAILY_LAMBDA);
+
+    assert_eq!(variance.len(), 4);
+    let expected = 0.94 * variance[0] + 0.06 * 0.01_f64.powi(2);
+    assert!((variance[1] - expected).abs() < 1e-15);
+  }
+
+  #[test]
+  fn ewma_covariance_is_symmetric() {
+    let returns = Array2::random((500, 3), RandNormal::new(0.0, 0.01).unwrap());
+    let cov = ewma_covariance(&returns, RISKMETRICS_DAILY_LAMBDA);
+
+    for i in 0..3 {
+      for j in 0..3 {
+        assert!((cov[[i, j]] - cov[[j, i]]).abs() < 1e-15);
+      }
+    }
+  }
+
+  #[test]
+  fn dataframe_returns_var() {
+    let r = Array1::random(1000, RandNormal::new(0.0, 0.01).unwrap());
+    let df = df!("asset" => r.to_vec()).unwrap();
+    let returns = returns_from_dataframe(&df).unwrap().column(0).to_owned();
+
+    let lambda = optimal_lambda(&returns, 0.8, 0.999, 1e-4);
+    let var = ewma_var(&returns, lambda, 0.99);
+    let fhs = fhs_var(&returns, lambda, 0.99);
+    println!("lambda: {}, VaR: {}, FHS VaR: {}", lambda, var, fhs);
+    assert!(var > 0.0 && fhs > 0.0);
+  }
+}
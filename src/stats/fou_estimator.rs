@@ -421,7 +421,7 @@ mod tests {
     const X0: f64 = 0.0;
 
     let fgn = FGN::new(0.70, 4095, Some(1.0), None);
-    let fou = FOU::new(5.0, 2.8, 1.0, 4096, Some(X0), Some(16.0), None, fgn);
+    let fou = FOU::new(5.0, 2.8, 1.0, 4096, Some(X0), Some(16.0), None, None, fgn);
     let path = fou.sample();
     let mut estimator = FOUParameterEstimationV1::new(path, FilterType::Daubechies);
 
@@ -443,7 +443,7 @@ mod tests {
     let delta = 1.0 / 256.0;
 
     let fgn = FGN::new(0.70, N - 1, Some(1.0), None);
-    let fou = FOU::new(5.0, 2.8, 2.0, N, Some(X0), Some(16.0), None, fgn);
+    let fou = FOU::new(5.0, 2.8, 2.0, N, Some(X0), Some(16.0), None, None, fgn);
     let path = fou.sample();
     let mut estimator = FOUParameterEstimationV2::new(path, delta, N);
 
@@ -0,0 +1,266 @@
+//! Maximum likelihood estimation of Hawkes processes.
+//!
+//! The log-likelihood of the arrival times t_1 < ... < t_k observed on [0, T] is
+//!
+//! ln L = sum_i ln lambda(t_i) - Lambda(T)
+//!
+//! with the intensity lambda and the compensator Lambda of
+//! [`Hawkes`](crate::stochastic::process::hawkes::Hawkes). It takes O(k) operations for
+//! the exponential kernel by the recursion of Ozaki (1979) and O(k^2) for the power law
+//! kernel. The estimates maximize it by a damped Newton method on the logarithms of the
+//! parameters.
+//!
+//! - Ozaki, T. (1979). Maximum likelihood estimation of Hawkes' self-exciting point processes.
+
+use nalgebra::{DMatrix, DVector};
+use ndarray::Array1;
+
+use crate::stochastic::process::hawkes::{Hawkes, HawkesKernel};
+
+/// Log-likelihood of the arrival times on [0, t_max], a leading 0 of the paths is not an
+/// arrival
+pub fn hawkes_log_likelihood(
+  times: &Array1<f64>,
+  t_max: f64,
+  mu: f64,
+  kernel: &HawkesKernel,
+) -> f64 {
+  let times = times
+    .iter()
+    .copied()
+    .filter(|&t| t > 0.0)
+    .collect::<Vec<_>>();
+  assert!(
+    times.windows(2).all(|w| w[1] >= w[0]) && times.last().is_none_or(|&t| t <= t_max),
+    "arrival times must be increasing and not after t_max"
+  );
+
+  let log_intensities = match *kernel {
+    HawkesKernel::Exponential { alpha, beta } => {
+      // A_i = sum_(j < i) e^(-beta (t_i - t_j))
+      let mut a = 0.0;
+      times
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| {
+          if i > 0 {
+            a = (-beta * (t - times[i - 1])).exp() * (1.0 + a);
+          }
+          (mu + alpha * beta * a).ln()
+        })
+        .sum::<f64>()
+    }
+    HawkesKernel::PowerLaw { .. } => times
+      .iter()
+      .enumerate()
+      .map(|(i, &t)| {
+        let excitation = times[..i]
+          .iter()
+          .map(|&tj| kernel.value(t - tj))
+          .sum::<f64>();
+        (mu + excitation).ln()
+      })
+      .sum::<f64>(),
+  };
+  let compensator = mu * t_max
+    + times
+      .iter()
+      .map(|&t| kernel.integral(t_max - t))
+      .sum::<f64>();
+
+  log_intensities - compensator
+}
+
+/// Maximum likelihood estimate of the Hawkes process of the arrival times on [0, t_max]
+///
+/// The kernel gives the family and the initial guess of its parameters, the baseline
+/// starts from the share 1 - n of the arrivals that are immigrants. The estimated process
+/// samples the arrivals up to t_max.
+pub fn mle_hawkes(times: &Array1<f64>, t_max: f64, kernel: HawkesKernel) -> Hawkes {
+  let count = times.iter().filter(|&&t| t > 0.0).count();
+  assert!(count >= 2, "at least 2 arrivals are needed");
+  kernel.validate().expect("invalid initial kernel");
+
+  let branching = kernel.branching_ratio().clamp(0.05, 0.95);
+  let mu = (1.0 - branching) * count as f64 / t_max;
+
+  // x = ln of (mu, kernel parameters)
+  let x0 = match kernel {
+    HawkesKernel::Exponential { beta, .. } => vec![mu, branching, beta],
+    HawkesKernel::PowerLaw { c, p, .. } => vec![mu, branching, c, p],
+  };
+  let from_x = |x: &DVector<f64>| -> (f64, HawkesKernel) {
+    let x = x.map(f64::exp);
+    let kernel = match kernel {
+      HawkesKernel::Exponential { .. } => HawkesKernel::Exponential {
+        alpha: x[1],
+        beta: x[2],
+      },
+      HawkesKernel::PowerLaw { .. } => HawkesKernel::PowerLaw {
+        alpha: x[1],
+        c: x[2],
+        p: x[3],
+      },
+    };
+    (x[0], kernel)
+  };
+  let log_likelihood = |x: &DVector<f64>| {
+    let (mu, kernel) = from_x(x);
+    hawkes_log_likelihood(times, t_max, mu, &kernel)
+  };
+
+  let x = maximize(DVector::from_vec(x0).map(f64::ln), &log_likelihood);
+  let (mu, kernel) = from_x(&x);
+
+  Hawkes::new(mu, kernel, None, Some(t_max), None)
+}
+
+/// Damped Newton maximization with central difference derivatives
+///
+/// The steps solve (-H + d diag|H|) dx = g and the damping d shrinks after an increase of
+/// f and grows otherwise, like Levenberg-Marquardt for a log-likelihood.
+fn maximize(mut x: DVector<f64>, f: &dyn Fn(&DVector<f64>) -> f64) -> DVector<f64> {
+  let dim = x.len();
+  let h = 1e-4;
+  let mut fx = f(&x);
+  let mut damping = 1e-3;
+
+  let bumped = |x: &DVector<f64>, bumps: &[(usize, f64)]| {
+    let mut y = x.clone();
+    for &(i, bump) in bumps {
+      y[i] += bump;
+    }
+    f(&y)
+  };
+
+  for _ in 0..100 {
+    let gradient = DVector::from_fn(dim, |i, _| {
+      (bumped(&x, &[(i, h)]) - bumped(&x, &[(i, -h)])) / (2.0 * h)
+    });
+    let mut hessian = DMatrix::zeros(dim, dim);
+    for i in 0..dim {
+      hessian[(i, i)] = (bumped(&x, &[(i, h)]) - 2.0 * fx + bumped(&x, &[(i, -h)])) / (h * h);
+      for j in 0..i {
+        let value = (bumped(&x, &[(i, h), (j, h)])
+          - bumped(&x, &[(i, h), (j, -h)])
+          - bumped(&x, &[(i, -h), (j, h)])
+          + bumped(&x, &[(i, -h), (j, -h)]))
+          / (4.0 * h * h);
+        hessian[(i, j)] = value;
+        hessian[(j, i)] = value;
+      }
+    }
+
+    let mut step = None;
+    while damping < 1e10 {
+      let mut a = -&hessian;
+      for i in 0..dim {
+        a[(i, i)] += damping * (hessian[(i, i)].abs() + 1e-12);
+      }
+      if let Some(dx) = a.lu().solve(&gradient) {
+        let y = &x + &dx;
+        let fy = f(&y);
+        if fy > fx {
+          step = Some(dx);
+          x = y;
+          fx = fy;
+          damping = (damping / 3.0).max(1e-12);
+          break;
+        }
+      }
+      damping *= 4.0;
+    }
+
+    match step {
+      Some(dx) if dx.amax() > 1e-9 => {}
+      _ => break,
+    }
+  }
+
+  x
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stochastic::Sampling;
+
+  #[test]
+  fn mle_recovers_exponential_hawkes() {
+    let t_max = 2000.0;
+    let truth = Hawkes::new(
+      0.5,
+      HawkesKernel::Exponential {
+        alpha: 0.6,
+        beta: 2.0,
+      },
+      None,
+      Some(t_max),
+      None,
+    );
+    let times = truth.sample();
+
+    let fit = mle_hawkes(
+      &times,
+      t_max,
+      HawkesKernel::Exponential {
+        alpha: 0.5,
+        beta: 1.0,
+      },
+    );
+    let HawkesKernel::Exponential { alpha, beta } = fit.kernel else {
+      unreachable!()
+    };
+    assert!((fit.mu - 0.5).abs() < 0.1, "{}", fit.mu);
+    assert!((alpha - 0.6).abs() < 0.1, "{}", alpha);
+    assert!((beta - 2.0).abs() < 0.6, "{}", beta);
+
+    let log_likelihood =
+      |hawkes: &Hawkes| hawkes_log_likelihood(&times, t_max, hawkes.mu, &hawkes.kernel);
+    assert!(log_likelihood(&fit) >= log_likelihood(&truth));
+  }
+
+  #[test]
+  fn mle_fits_power_law_hawkes() {
+    let t_max = 400.0;
+    let truth = Hawkes::new(
+      0.5,
+      HawkesKernel::PowerLaw {
+        alpha: 0.5,
+        c: 0.5,
+        p: 1.5,
+      },
+      None,
+      Some(t_max),
+      None,
+    );
+    let times = truth.sample();
+
+    let fit = mle_hawkes(
+      &times,
+      t_max,
+      HawkesKernel::PowerLaw {
+        alpha: 0.5,
+        c: 1.0,
+        p: 1.0,
+      },
+    );
+    assert!((fit.branching_ratio() - 0.5).abs() < 0.2);
+    assert!(
+      hawkes_log_likelihood(&times, t_max, fit.mu, &fit.kernel)
+        >= hawkes_log_likelihood(&times, t_max, truth.mu, &truth.kernel)
+    );
+  }
+
+  #[test]
+  fn log_likelihood_of_poisson_arrivals() {
+    // Without excitation the arrivals are Poisson, k ln mu - mu T
+    let times = ndarray::array![0.0, 0.5, 1.5, 3.0];
+    let kernel = HawkesKernel::Exponential {
+      alpha: 0.0,
+      beta: 1.0,
+    };
+    let expected = 3.0 * 2.0f64.ln() - 2.0 * 4.0;
+    assert!((hawkes_log_likelihood(&times, 4.0, 2.0, &kernel) - expected).abs() < 1e-12);
+  }
+}
@@ -0,0 +1,313 @@
+//! EM estimation of regime-switching diffusions.
+//!
+//! The discretely observed path x_0, ..., x_N of
+//! [`RegimeSwitching`](crate::stochastic::diffusion::regime_switching::RegimeSwitching)
+//! is a hidden Markov model: the regime s_i of the step i is a Markov chain with the
+//! transition matrix P over dt and the increments are conditionally normal
+//!
+//! x_(i+1) - x_i | s_i = k ~ N((a_k + b_k x_i) dt, sigma_k^2 dt)
+//!
+//! by the Euler scheme. The EM (Baum-Welch) algorithm alternates the scaled
+//! forward-backward recursions of the regime probabilities and their weighted least
+//! squares and transition counts until the log-likelihood stops increasing. The generator
+//! is (P - I) / dt, the first order of P = e^(Q dt). The regimes of the estimate are
+//! sorted by increasing volatility.
+//!
+//! - Hamilton, J. D. (1989). A new approach to the economic analysis of nonstationary time series and the business cycle.
+//! - Rabiner, L. R. (1989). A tutorial on hidden Markov models and selected applications in speech recognition.
+
+use std::f64::consts::PI;
+
+use ndarray::{Array1, Array2};
+
+use crate::stochastic::diffusion::{gbm::GBM, ou::OU, regime_switching::RegimeSwitching};
+
+/// Estimate of the switching Euler increments
+struct Estimate {
+  /// Intercept a_k of the drift per regime
+  a: Vec<f64>,
+  /// Slope b_k of the drift per regime
+  b: Vec<f64>,
+  sigma: Vec<f64>,
+  generator: Array2<f64>,
+}
+
+/// EM estimate of the regime-switching geometric Brownian motion of the prices s with the
+/// time step dt
+///
+/// The log-prices have the switching drifts mu_k - sigma_k^2 / 2. The estimated process
+/// samples paths like the observed one.
+pub fn em_regime_switching_gbm(s: &Array1<f64>, dt: f64, regimes: usize) -> RegimeSwitching<GBM> {
+  assert!(s.iter().all(|&s| s > 0.0), "prices must be positive");
+  let estimate = em(&s.mapv(f64::ln), dt, regimes, false);
+
+  let gbms = (0..regimes)
+    .map(|k| {
+      let sigma = estimate.sigma[k];
+      GBM::new(
+        estimate.a[k] + 0.5 * sigma.powi(2),
+        sigma,
+        s.len(),
+        Some(s[0]),
+        Some(dt * (s.len() - 1) as f64),
+        None,
+        None,
+        None,
+        #[cfg(feature = "malliavin")]
+        None,
+      )
+    })
+    .collect();
+
+  RegimeSwitching::new(
+    gbms,
+    estimate.generator,
+    None,
+    s.len(),
+    Some(s[0]),
+    Some(dt * (s.len() - 1) as f64),
+    None,
+    None,
+  )
+}
+
+/// EM estimate of the regime-switching Ornstein-Uhlenbeck process of the path x with the
+/// time step dt
+///
+/// The drift theta_k (mu_k - x) is a_k + b_k x with theta_k = -b_k, the Euler likelihood
+/// biases theta by O(theta^2 dt). The estimated process samples paths like the observed one.
+pub fn em_regime_switching_ou(x: &Array1<f64>, dt: f64, regimes: usize) -> RegimeSwitching<OU> {
+  let estimate = em(x, dt, regimes, true);
+
+  let ous = (0..regimes)
+    .map(|k| {
+      let theta = -estimate.b[k];
+      OU::new(
+        estimate.a[k] / theta,
+        estimate.sigma[k],
+        theta,
+        x.len(),
+        Some(x[0]),
+        Some(dt * (x.len() - 1) as f64),
+        None,
+        None,
+      )
+    })
+    .collect();
+
+  RegimeSwitching::new(
+    ous,
+    estimate.generator,
+    None,
+    x.len(),
+    Some(x[0]),
+    Some(dt * (x.len() - 1) as f64),
+    None,
+    None,
+  )
+}
+
+/// Baum-Welch iterations, the drift is a_k + b_k x if mean_reverting and a_k otherwise
+fn em(x: &Array1<f64>, dt: f64, regimes: usize, mean_reverting: bool) -> Estimate {
+  let k = regimes;
+  let steps = x.len().saturating_sub(1);
+  assert!(k >= 1, "at least 1 regime is needed");
+  assert!(dt > 0.0, "dt must be positive");
+  assert!(
+    steps >= 2 * k,
+    "at least 2 increments per regime are needed"
+  );
+
+  let dx = (0..steps).map(|i| x[i + 1] - x[i]).collect::<Vec<_>>();
+  let mean = dx.iter().sum::<f64>() / (steps as f64 * dt);
+  let sd = (dx.iter().map(|d| (d - mean * dt).powi(2)).sum::<f64>() / (steps as f64 * dt)).sqrt();
+
+  // The initial regimes spread the volatility around the pooled one
+  let mut a = vec![mean; k];
+  let mut b = vec![0.0; k];
+  let mut sigma = (0..k)
+    .map(|j| sd.max(1e-12) * 2f64.powf(j as f64 - (k - 1) as f64 / 2.0))
+    .collect::<Vec<_>>();
+  let mut p = Array2::from_shape_fn((k, k), |(i, j)| match (i == j, k) {
+    (_, 1) => 1.0,
+    (true, _) => 0.95,
+    (false, _) => 0.05 / (k - 1) as f64,
+  });
+  let mut initial = vec![1.0 / k as f64; k];
+  let mut last = f64::NEG_INFINITY;
+
+  for _ in 0..1000 {
+    // Conditional densities of the increments
+    let density = Array2::from_shape_fn((steps, k), |(i, j)| {
+      let scale = sigma[j] * dt.sqrt();
+      let z = (dx[i] - (a[j] + b[j] * x[i]) * dt) / scale;
+      ((-0.5 * z * z).exp() / (scale * (2.0 * PI).sqrt())).max(f64::MIN_POSITIVE)
+    });
+
+    // Scaled forward recursion, the scales c_i multiply to the likelihood
+    let mut alpha = Array2::<f64>::zeros((steps, k));
+    let mut c = vec![0.0; steps];
+    for i in 0..steps {
+      for j in 0..k {
+        let prior = match i {
+          0 => initial[j],
+          _ => (0..k).map(|l| alpha[[i - 1, l]] * p[[l, j]]).sum(),
+        };
+        alpha[[i, j]] = prior * density[[i, j]];
+      }
+      c[i] = alpha.row(i).sum();
+      alpha.row_mut(i).mapv_inplace(|v| v / c[i]);
+    }
+    let log_likelihood = c.iter().map(|c| c.ln()).sum::<f64>();
+
+    let mut beta = Array2::<f64>::ones((steps, k));
+    for i in (0..steps - 1).rev() {
+      for j in 0..k {
+        beta[[i, j]] = (0..k)
+          .map(|l| p[[j, l]] * density[[i + 1, l]] * beta[[i + 1, l]])
+          .sum::<f64>()
+          / c[i + 1];
+      }
+    }
+
+    // Regime probabilities and transition counts
+    let gamma = &alpha * &beta;
+    let mut transitions = Array2::<f64>::zeros((k, k));
+    for i in 0..steps - 1 {
+      for j in 0..k {
+        for l in 0..k {
+          transitions[[j, l]] +=
+            alpha[[i, j]] * p[[j, l]] * density[[i + 1, l]] * beta[[i + 1, l]] / c[i + 1];
+        }
+      }
+    }
+
+    initial = gamma.row(0).to_vec();
+    for j in 0..k {
+      let total = transitions.row(j).sum();
+      if total > 0.0 {
+        p.row_mut(j).assign(&(&transitions.row(j) / total));
+      }
+    }
+
+    // Weighted least squares of dx / dt on x
+    for j in 0..k {
+      let w = gamma.column(j);
+      let total = w.sum().max(f64::MIN_POSITIVE);
+      let weighted =
+        |f: &dyn Fn(usize) -> f64| (0..steps).map(|i| w[i] * f(i)).sum::<f64>() / total;
+      let z_mean = weighted(&|i| dx[i] / dt);
+
+      if mean_reverting {
+        let x_mean = weighted(&|i| x[i]);
+        let variance = weighted(&|i| (x[i] - x_mean).powi(2));
+        let covariance = weighted(&|i| (x[i] - x_mean) * (dx[i] / dt - z_mean));
+        b[j] = if variance > 0.0 {
+          covariance / variance
+        } else {
+          0.0
+        };
+        a[j] = z_mean - b[j] * x_mean;
+      } else {
+        a[j] = z_mean;
+      }
+
+      let residuals = weighted(&|i| (dx[i] - (a[j] + b[j] * x[i]) * dt).powi(2));
+      sigma[j] = (residuals / dt).sqrt().max(1e-12);
+    }
+
+    if log_likelihood - last < 1e-8 {
+      break;
+    }
+    last = log_likelihood;
+  }
+
+  // Sort the regimes by volatility
+  let mut order = (0..k).collect::<Vec<_>>();
+  order.sort_by(|&i, &j| sigma[i].total_cmp(&sigma[j]));
+  let generator = Array2::from_shape_fn((k, k), |(i, j)| {
+    let (i, j) = (order[i], order[j]);
+    (p[[i, j]] - if i == j { 1.0 } else { 0.0 }) / dt
+  });
+
+  Estimate {
+    a: order.iter().map(|&j| a[j]).collect(),
+    b: order.iter().map(|&j| b[j]).collect(),
+    sigma: order.iter().map(|&j| sigma[j]).collect(),
+    generator,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::stochastic::Sampling2D;
+
+  const DT: f64 = 1.0 / 252.0;
+
+  #[test]
+  fn em_recovers_regime_switching_gbm() {
+    let n = 20_161;
+    let gbm = |mu: f64, sigma: f64| {
+      GBM::new(
+        mu,
+        sigma,
+        n,
+        None,
+        None,
+        None,
+        None,
+        None,
+        #[cfg(feature = "malliavin")]
+        None,
+      )
+    };
+    let truth = RegimeSwitching::new(
+      vec![gbm(0.1, 0.1), gbm(-0.2, 0.4)],
+      array![[-2.0, 2.0], [4.0, -4.0]],
+      Some(0),
+      n,
+      Some(100.0),
+      Some(DT * (n - 1) as f64),
+      None,
+      None,
+    );
+    let [s, _] = truth.sample();
+
+    let estimate = em_regime_switching_gbm(&s, DT, 2);
+    assert!((estimate.regimes[0].sigma - 0.1).abs() < 0.01);
+    assert!((estimate.regimes[1].sigma - 0.4).abs() < 0.04);
+    // About 100 switches of each kind, the rates are known to about 10%
+    let q = &estimate.generator;
+    assert!((q[[0, 1]] - 2.0).abs() < 1.0, "{}", q);
+    assert!((q[[1, 0]] - 4.0).abs() < 2.0, "{}", q);
+    assert!((q[[0, 0]] + q[[0, 1]]).abs() < 1e-9);
+  }
+
+  #[test]
+  fn em_recovers_regime_switching_ou() {
+    let n = 20_161;
+    let ou = |mu: f64, sigma: f64| OU::new(mu, sigma, 2.0, n, None, None, None, None);
+    let truth = RegimeSwitching::new(
+      vec![ou(0.0, 0.1), ou(0.5, 0.4)],
+      array![[-2.0, 2.0], [4.0, -4.0]],
+      None,
+      n,
+      Some(0.0),
+      Some(DT * (n - 1) as f64),
+      None,
+      None,
+    );
+    let [x, _] = truth.sample();
+
+    let estimate = em_regime_switching_ou(&x, DT, 2);
+    assert!((estimate.regimes[0].sigma - 0.1).abs() < 0.01);
+    assert!((estimate.regimes[1].sigma - 0.4).abs() < 0.04);
+    assert!(estimate
+      .regimes
+      .iter()
+      .all(|ou| (ou.theta - 2.0).abs() < 1.5));
+  }
+}
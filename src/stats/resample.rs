@@ -0,0 +1,186 @@
+//! Conversion between irregularly observed series and regular grids.
+//!
+//! [`resample`] maps observations at irregular (event) times onto a regular grid and
+//! [`thin`] goes the other way, observing a simulated regular path at random times such as
+//! the arrivals of [`poisson_times`], e.g. to test estimators on asynchronous data.
+//!
+//! The previous-tick scheme only uses the observations up to each grid point, so the
+//! resampled series is adapted and can be fed to estimators and backtests. The linear
+//! scheme uses the next observation as well, it is smoother but looks ahead and biases the
+//! realized variance downwards (Hansen & Lunde, 2006). Grid points before the first
+//! observation are NaN in both schemes, [`staleness`] gives the age of the value used at
+//! every grid point.
+//!
+//! - Hansen, P. R., & Lunde, A. (2006). Realized variance and market microstructure noise.
+//! - Hayashi, T., & Yoshida, N. (2005). On covariance estimation of non-synchronously
+//!   observed diffusion processes.
+
+use ndarray::{Array1, ArrayView1};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::Exp;
+
+/// Interpolation of the observations between the event times
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResampleScheme {
+  /// The last observation at or before the grid point
+  #[default]
+  PreviousTick,
+  /// Linear interpolation of the neighbouring observations, the last observation beyond it
+  Linear,
+}
+
+/// Index of the last observation at or before every grid point, None before the first one
+fn last_indices(times: ArrayView1<f64>, grid: ArrayView1<f64>) -> Vec<Option<usize>> {
+  assert!(
+    times.windows(2).into_iter().all(|w| w[0] <= w[1]),
+    "observation times must be non-decreasing"
+  );
+  assert!(
+    grid.windows(2).into_iter().all(|w| w[0] <= w[1]),
+    "grid must be non-decreasing"
+  );
+
+  let mut next = 0;
+  grid
+    .iter()
+    .map(|&t| {
+      while next < times.len() && times[next] <= t {
+        next += 1;
+      }
+      next.checked_sub(1)
+    })
+    .collect()
+}
+
+/// Values of the observations (times, values) on the grid
+pub fn resample(
+  times: ArrayView1<f64>,
+  values: ArrayView1<f64>,
+  grid: ArrayView1<f64>,
+  scheme: ResampleScheme,
+) -> Array1<f64> {
+  assert_eq!(times.len(), values.len(), "a value is needed at every time");
+
+  last_indices(times, grid)
+    .into_iter()
+    .zip(grid.iter())
+    .map(|(last, &t)| match (last, scheme) {
+      (None, _) => f64::NAN,
+      (Some(i), ResampleScheme::PreviousTick) => values[i],
+      (Some(i), ResampleScheme::Linear) if i + 1 < times.len() => {
+        let w = (t - times[i]) / (times[i + 1] - times[i]);
+        values[i] + w * (values[i + 1] - values[i])
+      }
+      (Some(i), ResampleScheme::Linear) => values[i],
+    })
+    .collect()
+}
+
+/// Time since the last observation at every grid point, NaN before the first one
+pub fn staleness(times: ArrayView1<f64>, grid: ArrayView1<f64>) -> Array1<f64> {
+  last_indices(times, grid)
+    .into_iter()
+    .zip(grid.iter())
+    .map(|(last, &t)| last.map_or(f64::NAN, |i| t - times[i]))
+    .collect()
+}
+
+/// Arrival times of a Poisson process of the given intensity on (0, t]
+pub fn poisson_times(t: f64, intensity: f64, seed: Option<u64>) -> Array1<f64> {
+  assert!(intensity > 0.0, "intensity must be positive");
+  let mut rng = match seed {
+    Some(seed) => StdRng::seed_from_u64(seed),
+    None => StdRng::from_entropy(),
+  };
+  let exp = Exp::new(intensity).unwrap();
+
+  let mut times = Vec::new();
+  let mut time = rng.sample(exp);
+  while time <= t {
+    times.push(time);
+    time += rng.sample(exp);
+  }
+
+  Array1::from_vec(times)
+}
+
+/// Values of a path on the regular grid of [0, t] at the observation times, the last
+/// grid value at or before every time, i.e. the value in force at the event
+pub fn thin(path: ArrayView1<f64>, t: f64, times: ArrayView1<f64>) -> Array1<f64> {
+  assert!(path.len() >= 2, "the path must have at least 2 points");
+  let grid = Array1::linspace(0.0, t, path.len());
+  // Round the grid against the observation times that fall on a grid point
+  let grid = grid.mapv(|s| s - 1e-12 * t);
+
+  resample(grid.view(), path, times, ResampleScheme::PreviousTick)
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::{array, s};
+
+  use super::*;
+  use crate::stochastic::{process::bm::BM, Sampling};
+
+  #[test]
+  fn previous_tick_and_linear_resampling() {
+    let times = array![0.5, 1.0, 2.5];
+    let values = array![1.0, 2.0, 5.0];
+    let grid = array![0.0, 1.0, 2.0, 3.0];
+
+    let previous = resample(
+      times.view(),
+      values.view(),
+      grid.view(),
+      ResampleScheme::PreviousTick,
+    );
+    assert!(previous[0].is_nan());
+    assert_eq!(previous.slice(s![1..]), array![2.0, 2.0, 5.0]);
+
+    let linear = resample(
+      times.view(),
+      values.view(),
+      grid.view(),
+      ResampleScheme::Linear,
+    );
+    assert!(linear[0].is_nan());
+    assert_eq!(linear.slice(s![1..]), array![2.0, 4.0, 5.0]);
+
+    let age = staleness(times.view(), grid.view());
+    assert!(age[0].is_nan());
+    assert_eq!(age.slice(s![1..]), array![0.0, 1.0, 0.5]);
+  }
+
+  #[test]
+  fn thinning_and_resampling_round_trip() {
+    let n = 10_001;
+    let path = BM::new(n, Some(1.0), None).sample();
+
+    let times = poisson_times(1.0, 2_000.0, Some(7));
+    assert!((times.len() as f64 - 2_000.0).abs() < 200.0);
+    assert_eq!(times, poisson_times(1.0, 2_000.0, Some(7)));
+
+    // Every thinned value is the path at the grid point before the observation
+    let observed = thin(path.view(), 1.0, times.view());
+    for (j, &time) in times.iter().enumerate() {
+      let i = (time * (n - 1) as f64).floor() as usize;
+      assert_eq!(observed[j], path[i]);
+    }
+
+    // The realized variance of the thinned path is still that of the Brownian motion
+    let grid = Array1::linspace(0.0, 1.0, 251);
+    let resampled = resample(
+      times.view(),
+      observed.view(),
+      grid.view(),
+      ResampleScheme::PreviousTick,
+    );
+    let variance = resampled
+      .slice(s![1..])
+      .windows(2)
+      .into_iter()
+      .map(|w| (w[1] - w[0]).powi(2))
+      .sum::<f64>();
+    assert!((variance - 1.0).abs() < 0.3);
+  }
+}
@@ -0,0 +1,401 @@
+//! Truncated signatures and log-signatures of paths.
+//!
+//! The signature of a path X: [0, T] -> R^d is the collection of its iterated integrals
+//!
+//! S(X)^(i_1...i_k) = int_(0 < t_1 < ... < t_k < T) dX^(i_1)(t_1) ... dX^(i_k)(t_k)
+//!
+//! It determines the path up to reparametrization and tree-like excursions, and linear
+//! functionals of it approximate continuous functions of the path, so the signature
+//! truncated at a depth is a feature map of path data for regressions, the estimators of
+//! [`ai`](crate::ai) and signature-based calibration. The log-signature carries the same
+//! information in fewer coordinates.
+//!
+//! The observed points are interpolated linearly. The signature of a segment with
+//! increment h is exp(h) = (1, h, h^(⊗2) / 2!, ...) and the segments are concatenated with
+//! Chen's identity S(X * Y) = S(X) ⊗ S(Y).
+//!
+//! - Lyons, T. (1998). Differential equations driven by rough signals.
+//! - Chevyrev, I., & Kormilitzin, A. (2016). A primer on the signature method in machine
+//!   learning.
+//! - Reizenstein, J., & Graham, B. (2020). Algorithm 1004: The iisignature library.
+
+use ndarray::{concatenate, s, Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rayon::prelude::*;
+
+/// Element of the tensor algebra over R^dim truncated at a depth
+///
+/// Level k holds the dim^k coefficients of the words i_1...i_k in lexicographic order,
+/// the row-major flattening of a k-tensor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+  /// Dimension of the path
+  pub dim: usize,
+  /// Levels 0, ..., depth
+  pub levels: Vec<Array1<f64>>,
+}
+
+impl Signature {
+  /// Unit element, the signature of a constant path
+  pub fn one(dim: usize, depth: usize) -> Self {
+    let mut signature = Self::zero(dim, depth);
+    signature.levels[0][0] = 1.0;
+    signature
+  }
+
+  /// Zero element, the log-signature of a constant path
+  pub fn zero(dim: usize, depth: usize) -> Self {
+    Self {
+      dim,
+      levels: (0..=depth)
+        .map(|k| Array1::zeros(dim.pow(k as u32)))
+        .collect(),
+    }
+  }
+
+  /// Signature of a linear segment, exp(h) of the increment h
+  pub fn segment(increment: ArrayView1<f64>, depth: usize) -> Self {
+    let increment = increment.to_owned();
+    let mut signature = Self::one(increment.len(), depth);
+    for k in 1..=depth {
+      signature.levels[k] = outer(&signature.levels[k - 1], &increment) / k as f64;
+    }
+    signature
+  }
+
+  /// Truncation depth
+  pub fn depth(&self) -> usize {
+    self.levels.len() - 1
+  }
+
+  /// Coefficients of the words of length k
+  pub fn level(&self, k: usize) -> &Array1<f64> {
+    &self.levels[k]
+  }
+
+  /// Coefficient of a word, e.g. &[0, 1] for int X^0 dX^1
+  pub fn get(&self, word: &[usize]) -> f64 {
+    let index = word.iter().fold(0, |index, &i| index * self.dim + i);
+    self.levels[word.len()][index]
+  }
+
+  /// Product of the truncated tensor algebra, the signature of the concatenated paths
+  pub fn tensor_mul(&self, other: &Self) -> Self {
+    assert_eq!(self.dim, other.dim, "dimensions must match");
+    let depth = self.depth().min(other.depth());
+    let mut product = Self::zero(self.dim, depth);
+    for k in 0..=depth {
+      for i in 0..=k {
+        product.levels[k] += &outer(&self.levels[i], &other.levels[k - i]);
+      }
+    }
+    product
+  }
+
+  /// Logarithm log(1 + x) = x - x^2 / 2 + x^3 / 3 - ..., of a signature with level 0
+  /// equal to 1
+  pub fn log(&self) -> Self {
+    assert!((self.levels[0][0] - 1.0).abs() < 1e-12, "level 0 must be 1");
+    let mut x = self.clone();
+    x.levels[0][0] = 0.0;
+    Self::series(&x, |n| (-1f64).powi(n as i32 + 1) / n as f64)
+  }
+
+  /// Exponential exp(x) = 1 + x + x^2 / 2! + ..., of a log-signature with level 0 equal
+  /// to 0
+  pub fn exp(&self) -> Self {
+    assert!(self.levels[0][0].abs() < 1e-12, "level 0 must be 0");
+    let mut exp = Self::series(self, |n| 1.0 / (1..=n).product::<usize>() as f64);
+    exp.levels[0][0] = 1.0;
+    exp
+  }
+
+  /// sum_(n = 1..depth) coefficient(n) x^n, the higher powers of x without a level 0
+  /// term vanish
+  fn series<F: Fn(usize) -> f64>(x: &Self, coefficient: F) -> Self {
+    let depth = x.depth();
+    let mut sum = Self::zero(x.dim, depth);
+    let mut power = x.clone();
+    for n in 1..=depth {
+      for k in 0..=depth {
+        sum.levels[k].scaled_add(coefficient(n), &power.levels[k]);
+      }
+      power = power.tensor_mul(x);
+    }
+    sum
+  }
+
+  /// Levels 1, ..., depth concatenated, level 0 is constant
+  pub fn features(&self) -> Array1<f64> {
+    let levels = self.levels[1..]
+      .iter()
+      .map(|level| level.view())
+      .collect::<Vec<_>>();
+    concatenate(Axis(0), &levels).unwrap()
+  }
+
+  /// Number of [`Signature::features`] of a path of dimension dim at a depth
+  pub fn feature_len(dim: usize, depth: usize) -> usize {
+    (1..=depth).map(|k| dim.pow(k as u32)).sum()
+  }
+}
+
+/// Flattened tensor product a ⊗ b
+fn outer(a: &Array1<f64>, b: &Array1<f64>) -> Array1<f64> {
+  let mut product = Array1::zeros(a.len() * b.len());
+  for (i, &x) in a.iter().enumerate() {
+    product
+      .slice_mut(s![i * b.len()..(i + 1) * b.len()])
+      .scaled_add(x, b);
+  }
+  product
+}
+
+/// Signature of the piecewise linear path through the rows of `path`, of shape
+/// (points, dimension)
+pub fn signature(path: ArrayView2<f64>, depth: usize) -> Signature {
+  let mut signature = Signature::one(path.ncols(), depth);
+  for window in path.axis_windows(Axis(0), 2) {
+    let increment = &window.row(1) - &window.row(0);
+    signature = signature.tensor_mul(&Signature::segment(increment.view(), depth));
+  }
+  signature
+}
+
+/// Log-signature of the piecewise linear path through the rows of `path` in the
+/// coordinates of the tensor algebra
+pub fn log_signature(path: ArrayView2<f64>, depth: usize) -> Signature {
+  signature(path, depth).log()
+}
+
+/// Lyndon words over the letters 0, ..., dim - 1 up to a length, ordered by length and
+/// then lexicographically (Duval's algorithm)
+///
+/// Their number at length k is given by Witt's formula, it is the dimension of level k of
+/// the free Lie algebra in which the log-signature lives.
+pub fn lyndon_words(dim: usize, depth: usize) -> Vec<Vec<usize>> {
+  let mut words = Vec::new();
+  if dim == 0 || depth == 0 {
+    return words;
+  }
+
+  let mut word = vec![0];
+  loop {
+    words.push(word.clone());
+
+    let period = word.len();
+    while word.len() < depth {
+      word.push(word[word.len() - period]);
+    }
+    while word.last() == Some(&(dim - 1)) {
+      word.pop();
+    }
+    match word.last_mut() {
+      Some(last) => *last += 1,
+      None => break,
+    }
+  }
+
+  words.sort_by_key(Vec::len);
+  words
+}
+
+/// Log-signature coefficients of the Lyndon words, the minimal number of features that
+/// determine the log-signature
+///
+/// The log-signature is a Lie polynomial, which is determined by its coefficients of the
+/// Lyndon words (Reutenauer, 1993), so they are a linear change of the
+/// coordinates in the Lyndon basis.
+pub fn log_signature_lyndon(path: ArrayView2<f64>, depth: usize) -> Array1<f64> {
+  let log = log_signature(path, depth);
+  lyndon_words(path.ncols(), depth)
+    .iter()
+    .map(|word| log.get(word))
+    .collect()
+}
+
+/// Path (t_i, x_i) of a scalar path x on the uniform grid of [0, t]
+///
+/// The signature of a scalar path only depends on its increment, adding the time makes
+/// it characterize the path.
+pub fn time_augment(path: ArrayView1<f64>, t: f64) -> Array2<f64> {
+  let n = path.len();
+  let dt = t / (n - 1).max(1) as f64;
+  Array2::from_shape_fn((n, 2), |(i, j)| match j {
+    0 => i as f64 * dt,
+    _ => path[i],
+  })
+}
+
+/// Lead-lag transform (X_lead, X_lag) of 2n - 1 points, the level 2 of its signature
+/// contains the quadratic variation of the path
+pub fn lead_lag(path: ArrayView2<f64>) -> Array2<f64> {
+  let (n, dim) = path.dim();
+  let mut transformed = Array2::zeros((2 * n - 1, 2 * dim));
+  for i in 0..2 * n - 1 {
+    let (lead, lag) = (i.div_ceil(2), i / 2);
+    transformed.slice_mut(s![i, ..dim]).assign(&path.row(lead));
+    transformed.slice_mut(s![i, dim..]).assign(&path.row(lag));
+  }
+  transformed
+}
+
+/// Signature features of the time augmented scalar paths on [0, t], one path per row
+/// as returned by [`Sampling::sample_par`](crate::stochastic::Sampling::sample_par)
+pub fn signature_features(paths: ArrayView2<f64>, t: f64, depth: usize) -> Array2<f64> {
+  path_features(paths, Signature::feature_len(2, depth), |path| {
+    signature(time_augment(path, t).view(), depth).features()
+  })
+}
+
+/// Log-signature features in the Lyndon coordinates of the time augmented scalar paths on
+/// [0, t], one path per row
+pub fn log_signature_features(paths: ArrayView2<f64>, t: f64, depth: usize) -> Array2<f64> {
+  path_features(paths, lyndon_words(2, depth).len(), |path| {
+    log_signature_lyndon(time_augment(path, t).view(), depth)
+  })
+}
+
+/// Features of every row of `paths` computed in parallel
+fn path_features<F>(paths: ArrayView2<f64>, len: usize, features: F) -> Array2<f64>
+where
+  F: Fn(ArrayView1<f64>) -> Array1<f64> + Sync,
+{
+  let mut xs = Array2::zeros((paths.nrows(), len));
+  xs.axis_iter_mut(Axis(0))
+    .into_par_iter()
+    .zip(paths.axis_iter(Axis(0)))
+    .for_each(|(mut x, path)| x.assign(&features(path)));
+  xs
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+  use rand_distr::{Distribution, StandardNormal};
+
+  use super::*;
+
+  fn random_walk(n: usize, dim: usize) -> Array2<f64> {
+    let mut rng = rand::thread_rng();
+    let increments: Array2<f64> =
+      Array2::from_shape_fn((n - 1, dim), |_| StandardNormal.sample(&mut rng));
+    let mut path = Array2::zeros((n, dim));
+    for i in 1..n {
+      let next = &path.row(i - 1) + &increments.row(i - 1);
+      path.row_mut(i).assign(&next);
+    }
+    path
+  }
+
+  fn assert_close(a: &Array1<f64>, b: &Array1<f64>, tolerance: f64) {
+    assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter().zip(b) {
+      assert!((x - y).abs() < tolerance, "{} {}", x, y);
+    }
+  }
+
+  #[test]
+  fn scalar_path_signature_is_power_of_increment() {
+    let path = array![[0.0], [1.0], [-0.5], [3.0]];
+    let signature = signature(path.view(), 5);
+    for k in 0..=5 {
+      let expected = 3f64.powi(k as i32) / (1..=k).product::<usize>() as f64;
+      assert!((signature.level(k)[0] - expected).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn levy_area_and_lyndon_coordinates() {
+    let path = array![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]];
+    let signature = signature(path.view(), 2);
+    assert!((signature.get(&[0, 1]) - 1.0).abs() < 1e-12);
+    assert!(signature.get(&[1, 0]).abs() < 1e-12);
+
+    assert_eq!(lyndon_words(2, 2), vec![vec![0], vec![1], vec![0, 1]]);
+    assert_close(
+      &log_signature_lyndon(path.view(), 2),
+      &array![1.0, 1.0, 0.5],
+      1e-12,
+    );
+  }
+
+  #[test]
+  fn chen_identity_and_reparametrization() {
+    let path = random_walk(20, 3);
+    let (head, tail) = (path.slice(s![..11, ..]), path.slice(s![10.., ..]));
+    let chen = signature(head, 4).tensor_mul(&signature(tail, 4));
+    assert_close(
+      &signature(path.view(), 4).features(),
+      &chen.features(),
+      1e-9,
+    );
+
+    // Repeated points and extra points on the segments do not change the signature
+    let mut refined = Vec::new();
+    for window in path.axis_windows(Axis(0), 2) {
+      refined.push(window.row(0).to_owned());
+      refined.push(window.row(0).to_owned());
+      refined.push(&window.row(0) * 0.3 + &window.row(1) * 0.7);
+    }
+    refined.push(path.row(19).to_owned());
+    let views = refined.iter().map(|row| row.view()).collect::<Vec<_>>();
+    let refined = ndarray::stack(Axis(0), &views).unwrap();
+    assert_close(
+      &signature(path.view(), 4).features(),
+      &signature(refined.view(), 4).features(),
+      1e-9,
+    );
+  }
+
+  #[test]
+  fn shuffle_identity() {
+    let signature = signature(random_walk(30, 2).view(), 3);
+    // S^i S^j = S^ij + S^ji and S^0 S^01 = 2 S^001 + S^010
+    for (i, j) in [(0, 0), (0, 1), (1, 1)] {
+      let product = signature.get(&[i]) * signature.get(&[j]);
+      let shuffle = signature.get(&[i, j]) + signature.get(&[j, i]);
+      assert!((product - shuffle).abs() < 1e-9);
+    }
+    let product = signature.get(&[0]) * signature.get(&[0, 1]);
+    let shuffle = 2.0 * signature.get(&[0, 0, 1]) + signature.get(&[0, 1, 0]);
+    assert!((product - shuffle).abs() < 1e-9);
+  }
+
+  #[test]
+  fn log_signature_inverts_exp() {
+    let signature = signature(random_walk(10, 2).view(), 4);
+    let log = signature.log();
+    assert_close(&log.exp().features(), &signature.features(), 1e-9);
+
+    // The log-signature of a segment is its increment
+    let segment = log_signature(array![[1.0, 2.0], [1.5, 1.0]].view(), 4);
+    let mut expected = Array1::zeros(Signature::feature_len(2, 4));
+    expected[0] = 0.5;
+    expected[1] = -1.0;
+    assert_close(&segment.features(), &expected, 1e-12);
+
+    // Witt's formula for the dimension of the free Lie algebra
+    assert_eq!(lyndon_words(2, 4).len(), 2 + 1 + 2 + 3);
+    assert_eq!(lyndon_words(3, 3).len(), 3 + 3 + 8);
+  }
+
+  #[test]
+  fn lead_lag_recovers_quadratic_variation() {
+    let path = random_walk(50, 1);
+    let quadratic_variation = path
+      .axis_windows(Axis(0), 2)
+      .into_iter()
+      .map(|window| (window[[1, 0]] - window[[0, 0]]).powi(2))
+      .sum::<f64>();
+
+    // The Lévy area of the lead and the lag is half the quadratic variation
+    let signature = signature(lead_lag(path.view()).view(), 2);
+    let area = 0.5 * (signature.get(&[0, 1]) - signature.get(&[1, 0]));
+    assert!((area - 0.5 * quadratic_variation).abs() < 1e-9);
+
+    let features = signature_features(path.t(), 1.0, 3);
+    assert_eq!(features.dim(), (1, Signature::feature_len(2, 3)));
+    let log_features = log_signature_features(path.t(), 1.0, 3);
+    assert_eq!(log_features.dim(), (1, 5));
+  }
+}
@@ -0,0 +1,166 @@
+//! Ordinary least squares calibration of deterministic trends.
+//!
+//! The observations x(t_i) = m(t_i) + e_i are regressed on the regressors of the trend,
+//! (1, t) for the linear trend, the cubic B-splines of the knots for the spline trend and
+//! (1, t) of ln x for the exponential trend, which needs positive observations. The
+//! fitted [`TrendComponent`] gives the residuals of [`detrend`] for the analysis of the
+//! stationary part, and drives [`Trend`](crate::stochastic::trend::Trend) to simulate the
+//! detrended model with its trend.
+
+use nalgebra::{DMatrix, DVector};
+use ndarray::Array1;
+
+use crate::stochastic::trend::{spline_basis, TrendComponent};
+
+/// Least squares coefficients of the regressors (rows of the observations)
+fn ols(regressors: &DMatrix<f64>, x: &DVector<f64>) -> DVector<f64> {
+  regressors
+    .clone()
+    .svd(true, true)
+    .solve(x, 1e-12)
+    .expect("least squares of the trend failed")
+}
+
+fn check(times: &Array1<f64>, x: &Array1<f64>, parameters: usize) {
+  assert_eq!(
+    times.len(),
+    x.len(),
+    "times and x must have the same length"
+  );
+  assert!(
+    times.len() >= parameters,
+    "at least as many observations as coefficients are needed"
+  );
+}
+
+/// Linear trend a + b t
+pub fn fit_linear(times: &Array1<f64>, x: &Array1<f64>) -> TrendComponent {
+  check(times, x, 2);
+  let regressors = DMatrix::from_fn(times.len(), 2, |i, j| times[i].powi(j as i32));
+  let beta = ols(
+    &regressors,
+    &DVector::from_iterator(x.len(), x.iter().copied()),
+  );
+
+  TrendComponent::Linear {
+    intercept: beta[0],
+    slope: beta[1],
+  }
+}
+
+/// Exponential trend a e^(b t) from the linear trend of ln x, x must be positive
+pub fn fit_exponential(times: &Array1<f64>, x: &Array1<f64>) -> TrendComponent {
+  assert!(
+    x.iter().all(|&x| x > 0.0),
+    "exponential trend needs positive observations"
+  );
+  match fit_linear(times, &x.mapv(f64::ln)) {
+    TrendComponent::Linear { intercept, slope } => TrendComponent::Exponential {
+      scale: intercept.exp(),
+      rate: slope,
+    },
+    _ => unreachable!(),
+  }
+}
+
+/// Cubic spline trend of the increasing knots, at least 2 of them
+///
+/// The first and the last knot are usually the first and the last time, more knots follow
+/// the trend more closely and take more of the fluctuations into the trend.
+pub fn fit_spline(times: &Array1<f64>, x: &Array1<f64>, knots: &Array1<f64>) -> TrendComponent {
+  assert!(
+    knots.len() >= 2 && knots.windows(2).into_iter().all(|k| k[1] > k[0]),
+    "spline needs at least 2 increasing knots"
+  );
+  check(times, x, knots.len() + 2);
+  let bases = times
+    .iter()
+    .map(|&t| spline_basis(knots, t))
+    .collect::<Vec<_>>();
+  let regressors = DMatrix::from_fn(times.len(), knots.len() + 2, |i, j| bases[i][j]);
+  let beta = ols(
+    &regressors,
+    &DVector::from_iterator(x.len(), x.iter().copied()),
+  );
+
+  TrendComponent::Spline {
+    knots: knots.clone(),
+    coefficients: Array1::from_iter(beta.iter().copied()),
+  }
+}
+
+/// Residuals x(t_i) - m(t_i) of the trend
+pub fn detrend(times: &Array1<f64>, x: &Array1<f64>, trend: &TrendComponent) -> Array1<f64> {
+  assert_eq!(
+    times.len(),
+    x.len(),
+    "times and x must have the same length"
+  );
+  x - &trend.values(times)
+}
+
+/// Evenly spaced knots over the observation times, e.g. for [`fit_spline`]
+pub fn uniform_knots(times: &Array1<f64>, knots: usize) -> Array1<f64> {
+  assert!(knots >= 2, "at least 2 knots are needed");
+  let (first, last) = times
+    .iter()
+    .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &t| {
+      (a.min(t), b.max(t))
+    });
+  assert!(first < last, "times must span an interval");
+
+  Array1::linspace(first, last, knots)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stochastic::{diffusion::ou::OU, trend::Trend, Sampling};
+
+  #[test]
+  fn ols_recovers_exact_trends() {
+    let times = Array1::linspace(0.0, 10.0, 101);
+
+    let linear = fit_linear(&times, &times.mapv(|t| 1.0 + 0.5 * t));
+    assert!(matches!(linear, TrendComponent::Linear { intercept, slope }
+      if (intercept - 1.0).abs() < 1e-12 && (slope - 0.5).abs() < 1e-12));
+
+    let exponential = fit_exponential(&times, &times.mapv(|t| 2.0 * (0.03 * t).exp()));
+    assert!(
+      matches!(exponential, TrendComponent::Exponential { scale, rate }
+      if (scale - 2.0).abs() < 1e-12 && (rate - 0.03).abs() < 1e-12)
+    );
+
+    // Cubic polynomials are splines of any knots
+    let cubic = |t: f64| 1.0 - 0.2 * t + 0.03 * t * t - 0.002 * t.powi(3);
+    let spline = fit_spline(&times, &times.mapv(cubic), &uniform_knots(&times, 5));
+    assert!(detrend(&times, &times.mapv(cubic), &spline)
+      .iter()
+      .all(|r| r.abs() < 1e-10));
+  }
+
+  #[test]
+  fn detrended_ou_paths_are_stationary() {
+    // OU around 0 with a linear trend, the fitted trend of the average path is the trend
+    let ou = OU::new(0.0, 0.3, 5.0, 201, Some(0.0), Some(4.0), None, Some(500));
+    let trend = Trend::new(
+      ou,
+      TrendComponent::Linear {
+        intercept: 1.0,
+        slope: 0.25,
+      },
+      Some(4.0),
+    );
+    let times = Array1::linspace(0.0, 4.0, 201);
+    let paths = trend.sample_par();
+    let mean = paths.mean_axis(ndarray::Axis(0)).unwrap();
+
+    let TrendComponent::Linear { intercept, slope } = fit_linear(&times, &mean) else {
+      unreachable!()
+    };
+    assert!((intercept - 1.0).abs() < 0.02 && (slope - 0.25).abs() < 0.01);
+
+    let residuals = detrend(&times, &paths.row(0).to_owned(), &fit_linear(&times, &mean));
+    assert!(residuals.mean().unwrap().abs() < 0.1);
+  }
+}
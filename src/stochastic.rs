@@ -6,45 +6,122 @@
 //!
 //! | Module          | Description                                                                                                                                                                       |
 //! |-----------------|-----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------|
-//! | **diffusion**    | Handles diffusion processes, such as Brownian motion and Geometric Brownian motion, commonly used in physics and finance to model random behavior over time.                                                            |
+//! | **catalog**      | Machine-readable description of every implemented process and pricer: parameters, valid ranges, example values and references.                                                                  |
+//! | **diffusion**    | Handles diffusion processes, such as Brownian motion and Geometric Brownian motion, commonly used in physics and finance to model random behavior over time, with a shared choice of Euler, Milstein, Runge-Kutta and predictor-corrector schemes. |
+//! | **donsker**      | Random walk approximations converging to Brownian motion and fractional Brownian motion (Donsker's invariance principle), with convergence in distribution diagnostics.                                       |
+//! | **initial_state** | Initial states of the samplers drawn from Dirac, normal, lognormal or empirical distributions, for predictive simulations and uncertain initial conditions.                                  |
 //! | **interest**     | Provides models for simulating stochastic interest rates, including well-known models like the Cox-Ingersoll-Ross (CIR) model used in financial mathematics.                                                             |
 //! | **jump**         | Implements jump processes, where sudden changes occur at random intervals, such as in the Poisson process or in financial models like the Bates model.                                                                  |
 //! | **malliavin**    | Tools for working with the Malliavin calculus, which is used to compute derivatives of stochastic processes for sensitivity analysis and other advanced applications.                                                     |
 //! | **noise**        | Generates various noise processes, including Gaussian and fractional Gaussian noise, which are essential for simulating random perturbations in stochastic models.                                                       |
+//! | **prelude**      | Re-exports of the most used samplers, traits and constants, with deprecated aliases of renamed types kept in their modules.                                                         |
 //! | **process**      | Provides general abstractions and implementations for creating, simulating, and sampling stochastic processes, supporting both regular and parallelized workflows.                                                       |
+//! | **trend**        | Deterministic linear, exponential and spline trends added to the paths of any sampler.                                                                                                 |
+//! | **variance_reduction** | Antithetic and moment matched Gaussian increments for processes driven by Brownian noise, lowering the variance of Monte Carlo estimators.                                                            |
 //! | **volatility**   | Focuses on modeling stochastic volatility, including processes like the Heston model, which are used to simulate changes in volatility over time in financial markets.                                                    |
 //!
 
+pub mod catalog;
 pub mod diffusion;
+pub mod donsker;
+pub mod initial_state;
 pub mod interest;
 pub mod isonormal;
 pub mod jump;
 #[cfg(feature = "malliavin")]
 pub mod malliavin;
 pub mod noise;
+pub mod prelude;
 pub mod process;
+pub mod trend;
+pub mod variance_reduction;
 pub mod volatility;
 
-use std::sync::{Arc, Mutex};
-
 use ndarray::parallel::prelude::*;
-use ndarray::{Array1, Array2, Axis};
-use ndrustfft::Zero;
+use ndarray::{Array1, Array2, Axis, NdFloat};
+use ndarray_rand::RandomExt;
+use ndrustfft::{FftNum, Zero};
 use num_complex::Complex64;
+use num_traits::FloatConst;
+use rand::distributions::uniform::SampleUniform;
+use rand_distr::{Exp1, Normal};
+
+use crate::error::{StochasticError, StochasticResult};
+
+pub use catalog::{catalog, ProcessInfo};
 
 pub const N: usize = 1000;
 pub const X0: f64 = 0.5;
 pub const S0: f64 = 100.0;
 pub const K: f64 = 100.0;
 
+/// Floating point type of the simulated paths, implemented for `f32` and `f64`.
+///
+/// Single precision paths take half the memory of the default `f64` ones and can be
+/// fed into single precision pipelines (e.g. the models in `crate::ai`) as they are.
+pub trait FloatExt: NdFloat + FftNum + FloatConst + SampleUniform + Default + Into<f64> {
+  /// Convert from f64, rounding to the nearest representable value
+  fn from_f64_(x: f64) -> Self;
+
+  /// Convert from usize
+  fn from_usize_(n: usize) -> Self {
+    Self::from_f64_(n as f64)
+  }
+
+  /// Array of n i.i.d. normal samples
+  fn normal_array(n: usize, mean: Self, std_dev: Self) -> Array1<Self>;
+
+  /// Array of n i.i.d. standard exponential samples
+  fn exp1_array(n: usize) -> Array1<Self>;
+}
+
+macro_rules! impl_float_ext {
+  ($t:ty) => {
+    impl FloatExt for $t {
+      fn from_f64_(x: f64) -> Self {
+        x as $t
+      }
+
+      fn normal_array(n: usize, mean: Self, std_dev: Self) -> Array1<Self> {
+        Array1::random(n, Normal::new(mean, std_dev).unwrap())
+      }
+
+      fn exp1_array(n: usize) -> Array1<Self> {
+        Array1::random(n, Exp1)
+      }
+    }
+  };
+}
+
+impl_float_ext!(f32);
+impl_float_ext!(f64);
+
 pub trait Sampling<T: Clone + Send + Sync + Zero>: Send + Sync {
   /// Sample the process
   fn sample(&self) -> Array1<T>;
 
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    Ok(())
+  }
+
+  /// Sample the process, returning an error instead of panicking on invalid parameters
+  fn try_sample(&self) -> StochasticResult<Array1<T>> {
+    self.validate()?;
+    Ok(self.sample())
+  }
+
+  /// Parallel sampling, returning an error instead of panicking on invalid parameters or missing `m`
+  fn try_sample_par(&self) -> StochasticResult<Array2<T>> {
+    self.validate()?;
+    self.m().ok_or(StochasticError::MissingSampleCount)?;
+    Ok(self.sample_par())
+  }
+
   /// Parallel sampling
   fn sample_par(&self) -> Array2<T> {
     if self.m().is_none() {
-      panic!("m must be specified for parallel sampling");
+      panic!("{}", StochasticError::MissingSampleCount);
     }
 
     let mut xs = Array2::zeros((self.m().unwrap(), self.n()));
@@ -76,6 +153,17 @@ pub trait SamplingVector<T: Clone + Send + Sync + Zero>: Send + Sync {
   /// Sample the vector process
   fn sample(&self) -> Array2<T>;
 
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    Ok(())
+  }
+
+  /// Sample the vector process, returning an error instead of panicking on invalid parameters
+  fn try_sample(&self) -> StochasticResult<Array2<T>> {
+    self.validate()?;
+    Ok(self.sample())
+  }
+
   /// Parallel sampling
   fn sample_par(&self) -> Array2<T> {
     unimplemented!()
@@ -98,25 +186,27 @@ pub trait Sampling2D<T: Clone + Send + Sync + Zero>: Send + Sync {
   /// Sample the process
   fn sample(&self) -> [Array1<T>; 2];
 
-  /// Parallel sampling
-  fn sample_par(&self) -> [Array2<T>; 2] {
-    if self.m().is_none() {
-      panic!("m must be specified for parallel sampling");
-    }
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    Ok(())
+  }
 
-    let m = self.m().unwrap(); // m értékét előre kinyerjük, hogy ne kelljen többször unwrap-elni
-    let xs1 = Arc::new(Mutex::new(Array2::zeros((self.m().unwrap(), self.n()))));
-    let xs2 = Arc::new(Mutex::new(Array2::zeros((self.m().unwrap(), self.n()))));
+  /// Sample the process, returning an error instead of panicking on invalid parameters
+  fn try_sample(&self) -> StochasticResult<[Array1<T>; 2]> {
+    self.validate()?;
+    Ok(self.sample())
+  }
 
-    (0..m).into_par_iter().for_each(|i| {
-      let [x1, x2] = self.sample(); // Minden szálon mintavételezünk
-      xs1.lock().unwrap().row_mut(i).assign(&x1); // Az első mintavételezés eredményét beírjuk az első mátrix i. sorába
-      xs2.lock().unwrap().row_mut(i).assign(&x2); // A második mintavételezés eredményét beírjuk a második mátrix i. sorába
-    });
+  /// Parallel sampling, returning an error instead of panicking on invalid parameters or missing `m`
+  fn try_sample_par(&self) -> StochasticResult<[Array2<T>; 2]> {
+    self.validate()?;
+    self.m().ok_or(StochasticError::MissingSampleCount)?;
+    Ok(self.sample_par())
+  }
 
-    let xs1 = xs1.lock().unwrap().clone();
-    let xs2 = xs2.lock().unwrap().clone();
-    [xs1, xs2]
+  /// Parallel sampling, both components must have n points
+  fn sample_par(&self) -> [Array2<T>; 2] {
+    sample_par_components(self.m(), self.n(), || self.sample())
   }
 
   /// Number of time steps
@@ -136,9 +226,63 @@ pub trait Sampling3D<T: Clone + Send + Sync + Zero>: Send + Sync {
   /// Sample the process
   fn sample(&self) -> [Array1<T>; 3];
 
-  /// Parallel sampling
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    Ok(())
+  }
+
+  /// Sample the process, returning an error instead of panicking on invalid parameters
+  fn try_sample(&self) -> StochasticResult<[Array1<T>; 3]> {
+    self.validate()?;
+    Ok(self.sample())
+  }
+
+  /// Parallel sampling, returning an error instead of panicking on invalid parameters or missing `m`
+  fn try_sample_par(&self) -> StochasticResult<[Array2<T>; 3]> {
+    self.validate()?;
+    self.m().ok_or(StochasticError::MissingSampleCount)?;
+    Ok(self.sample_par())
+  }
+
+  /// Parallel sampling, every component must have n points
   fn sample_par(&self) -> [Array2<T>; 3] {
-    unimplemented!()
+    sample_par_components(self.m(), self.n(), || self.sample())
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize;
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize>;
+}
+
+/// Sampling of processes with `D` components, e.g. multi-factor models or models with
+/// auxiliary states
+pub trait SamplingND<T: Clone + Send + Sync + Zero, const D: usize>: Send + Sync {
+  /// Sample the components of the process
+  fn sample(&self) -> [Array1<T>; D];
+
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    Ok(())
+  }
+
+  /// Sample the process, returning an error instead of panicking on invalid parameters
+  fn try_sample(&self) -> StochasticResult<[Array1<T>; D]> {
+    self.validate()?;
+    Ok(self.sample())
+  }
+
+  /// Parallel sampling, returning an error instead of panicking on invalid parameters or missing `m`
+  fn try_sample_par(&self) -> StochasticResult<[Array2<T>; D]> {
+    self.validate()?;
+    self.m().ok_or(StochasticError::MissingSampleCount)?;
+    Ok(self.sample_par())
+  }
+
+  /// Parallel sampling, one m x n matrix per component, every component must have n points
+  fn sample_par(&self) -> [Array2<T>; D] {
+    sample_par_components(self.m(), self.n(), || self.sample())
   }
 
   /// Number of time steps
@@ -148,6 +292,81 @@ pub trait Sampling3D<T: Clone + Send + Sync + Zero>: Send + Sync {
   fn m(&self) -> Option<usize>;
 }
 
+/// Sample m paths of a D component process in parallel without locking.
+///
+/// The rows of the pre-allocated matrices are disjoint mutable views, every task writes
+/// the components of its path directly into its own rows.
+fn sample_par_components<T, const D: usize, F>(
+  m: Option<usize>,
+  n: usize,
+  sample: F,
+) -> [Array2<T>; D]
+where
+  T: Clone + Send + Sync + Zero,
+  F: Fn() -> [Array1<T>; D] + Sync,
+{
+  let m = m.unwrap_or_else(|| panic!("{}", StochasticError::MissingSampleCount));
+  let mut xs: [Array2<T>; D] = std::array::from_fn(|_| Array2::zeros((m, n)));
+
+  let mut components = xs
+    .iter_mut()
+    .map(|x| x.axis_iter_mut(Axis(0)))
+    .collect::<Vec<_>>();
+  let rows = (0..m)
+    .map(|_| {
+      components
+        .iter_mut()
+        .map(|rows| rows.next().unwrap())
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+
+  rows.into_par_iter().for_each(|mut row| {
+    let path = sample();
+    for (x, component) in row.iter_mut().zip(path.iter()) {
+      x.assign(component);
+    }
+  });
+
+  xs
+}
+
+/// Probability measure a model is simulated under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Measure {
+  /// Real-world (physical) measure P, used for scenario generation
+  #[default]
+  RealWorld,
+  /// Risk-neutral measure Q, used for pricing
+  RiskNeutral,
+}
+
+/// Risk premia connecting the real-world and the risk-neutral dynamics of a model
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RiskPremia {
+  /// Risk-free rate
+  pub r: f64,
+  /// Dividend yield
+  pub q: f64,
+  /// Market price of the non-traded risk factor (volatility risk for Heston,
+  /// interest rate risk for OU/Vasicek short rates)
+  pub lambda: f64,
+}
+
+/// Change between the real-world and the risk-neutral measure.
+///
+/// The parameters of a model are taken to be real-world parameters, the risk-neutral
+/// version is obtained by removing the risk premia from the drift (Girsanov).
+pub trait MeasureChange {
+  /// Real-world minus risk-neutral drift of the state at x
+  fn drift_adjustment(&self, x: f64, premia: &RiskPremia) -> f64;
+
+  /// Copy of the model under the given measure
+  fn under_measure(&self, measure: Measure, premia: &RiskPremia) -> Self
+  where
+    Self: Sized;
+}
+
 pub trait Distribution {
   /// Characteristic function of the distribution
   fn characteristic_function(&self, _t: f64) -> Complex64 {
@@ -208,4 +427,9 @@ pub trait Distribution {
   fn moment_generating_function(&self, _t: f64) -> f64 {
     0.0
   }
+
+  /// Cumulant generating function ln E[e^(tX)] of the distribution
+  fn cumulant_generating_function(&self, t: f64) -> f64 {
+    self.moment_generating_function(t).ln()
+  }
 }
@@ -0,0 +1,522 @@
+//! Machine-readable catalog of the implemented processes and pricers.
+//!
+//! Every model implements [`ProcessInfo`], which describes its parameters (in the order of
+//! the `new` constructor), their valid ranges and an example value, together with the
+//! references of the model. [`catalog`] collects all of them, e.g. for model pickers of
+//! user interfaces or for listing the available models.
+
+use std::ops::Bound;
+
+use crate::{
+  quant::{
+    pricing::{
+      asian::AsianPricer, bates::BatesPricer, bjerksund_stensland::BjerksundStenslandPricer,
+      bsm::BSMPricer, finitie_difference::FiniteDifferencePricer,
+      gram_charlier::GramCharlierPricer, heston::HestonPricer, kou::KouPricer,
+      merton_jump::Merton1976Pricer, rough_heston::RoughHestonPricer,
+    },
+    volatility::sabr::SABRPricer,
+  },
+  stochastic::{
+    diffusion::{
+      cev::CEV,
+      cir::CIR,
+      custom::CustomSDE,
+      fcir::FCIR,
+      fgbm::FGBM,
+      fjacobi::FJacobi,
+      fou::FOU,
+      gbm::GBM,
+      jacobi::Jacobi,
+      levy_ou::{GammaOU, IGOU},
+      local_vol::LocalVolProcess,
+      multi_gbm::MultiGBM,
+      ou::OU,
+      regime_switching::RegimeSwitching,
+      wright_fisher::WrightFisher,
+    },
+    interest::{
+      adg::ADG, cir_2f::CIR2F, cir_plus_plus::CIRPlusPlus, duffie_kan::DuffieKan,
+      fvasicek::FVasicek, g2pp::G2PP, hjm::HJM, ho_lee::HoLee, hull_white::HullWhite,
+      hull_white_2f::HullWhite2F, hull_white_fitted::HullWhiteFitted,
+      libor_market_model::LiborMarketModel, vasicek::Vasicek,
+    },
+    jump::{
+      bates::Bates,
+      cgmy::CGMY,
+      cts::CTS,
+      ig::IG,
+      jump_fou::JumpFOU,
+      kou::KOU,
+      levy_diffusion::LevyDiffusion,
+      merton::Merton,
+      nig::NIG,
+      rdts::RDTS,
+      stable::{StableProcess, TemperedStable},
+      vg::VG,
+      vgsa::VGSA,
+    },
+    noise::{cfgns::CFGNS, cgns::CGNS, cgnsd::CGNSD, fgn::FGN, spacetime::SpaceTimeField},
+    process::{
+      bm::BM,
+      cbms::CBMS,
+      cbmsd::CBMSD,
+      ccustom::CompoundCustom,
+      cfbms::{CFBMS, MFBM},
+      cpoisson::CompoundPoisson,
+      customjt::CustomJt,
+      fbm::FBM,
+      fbm_bridge::FBMBridge,
+      hawkes::Hawkes,
+      poisson::Poisson,
+      subordinator::{GammaSubordinator, IGSubordinator},
+      time_changed_bm::TimeChangedBM,
+    },
+    volatility::{
+      bergomi::Bergomi,
+      bns::BNS,
+      heston::Heston,
+      rbergomi::{self, RBergomi},
+      rough_heston::RoughHeston,
+      sabr::SABR,
+      svcgmy::SVCGMY,
+    },
+  },
+};
+
+/// Jump size distribution used to list the models generic over it
+type Jumps = rand_distr::Normal<f64>;
+/// Coefficient type used to list the custom SDE
+type Coefficient = fn(f64, f64) -> f64;
+
+/// Family of a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "server", derive(serde::Serialize))]
+pub enum ModelKind {
+  Diffusion,
+  Interest,
+  Jump,
+  Noise,
+  Process,
+  Volatility,
+  Pricer,
+}
+
+/// Interval of the valid values of a real or integer parameter
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "server", derive(serde::Serialize))]
+pub struct Interval {
+  pub lower: Bound<f64>,
+  pub upper: Bound<f64>,
+}
+
+impl Interval {
+  /// (-inf, inf)
+  pub const REAL: Self = Self::new(Bound::Unbounded, Bound::Unbounded);
+  /// (0, inf)
+  pub const POSITIVE: Self = Self::new(Bound::Excluded(0.0), Bound::Unbounded);
+  /// [0, inf)
+  pub const NON_NEGATIVE: Self = Self::new(Bound::Included(0.0), Bound::Unbounded);
+  /// (0, 1), e.g. the Hurst index
+  pub const UNIT: Self = Self::open(0.0, 1.0);
+  /// [-1, 1]
+  pub const CORRELATION: Self = Self::closed(-1.0, 1.0);
+  /// [2, inf), number of points of a path
+  pub const STEPS: Self = Self::new(Bound::Included(2.0), Bound::Unbounded);
+  /// [1, inf)
+  pub const COUNT: Self = Self::new(Bound::Included(1.0), Bound::Unbounded);
+
+  pub const fn new(lower: Bound<f64>, upper: Bound<f64>) -> Self {
+    Self { lower, upper }
+  }
+
+  /// (a, b)
+  pub const fn open(a: f64, b: f64) -> Self {
+    Self::new(Bound::Excluded(a), Bound::Excluded(b))
+  }
+
+  /// [a, b]
+  pub const fn closed(a: f64, b: f64) -> Self {
+    Self::new(Bound::Included(a), Bound::Included(b))
+  }
+
+  /// Whether x is in the interval
+  pub fn contains(&self, x: f64) -> bool {
+    let lower = match self.lower {
+      Bound::Included(a) => x >= a,
+      Bound::Excluded(a) => x > a,
+      Bound::Unbounded => true,
+    };
+    let upper = match self.upper {
+      Bound::Included(b) => x <= b,
+      Bound::Excluded(b) => x < b,
+      Bound::Unbounded => true,
+    };
+
+    lower && upper
+  }
+}
+
+/// Type of a parameter
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "server", derive(serde::Serialize))]
+pub enum ParameterKind {
+  /// Real number in the interval
+  Real(Interval),
+  /// Integer in the interval
+  Integer(Interval),
+  Boolean,
+  /// Deterministic function of time (and maturity), e.g. a term structure
+  Function,
+  /// Vector or matrix
+  Array,
+  /// One of the variants of an enum
+  Choice(&'static [&'static str]),
+  /// Another sampler or distribution driving the model, e.g. the noise generator
+  Component,
+}
+
+/// Description of a parameter of a model
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "server", derive(serde::Serialize))]
+pub struct ParameterInfo {
+  pub name: &'static str,
+  pub description: &'static str,
+  pub kind: ParameterKind,
+  /// Whether the parameter is an `Option`
+  pub optional: bool,
+  /// Example value in the valid range, None for non-numeric parameters
+  pub example: Option<f64>,
+}
+
+impl ParameterInfo {
+  /// Number of points of a path
+  pub const N: Self = Self::integer("n", "Number of time steps", Interval::STEPS, 1000.0);
+  /// Time horizon
+  pub const T: Self =
+    Self::real("t", "Time horizon, 1 if None", Interval::POSITIVE, 1.0).optional();
+  /// Number of paths of parallel sampling
+  pub const M: Self = Self::integer(
+    "m",
+    "Number of paths for parallel sampling",
+    Interval::COUNT,
+    100.0,
+  )
+  .optional();
+  /// Initial value
+  pub const X0: Self = Self::real("x0", "Initial value, 0 if None", Interval::REAL, 0.0).optional();
+  /// Discretization scheme of a diffusion
+  pub const SCHEME: Self = Self::choice(
+    "scheme",
+    "Discretization scheme, Euler if None",
+    &[
+      "Euler",
+      "Milstein",
+      "SRK2",
+      "PredictorCorrector",
+      "DriftImplicitEuler",
+      "SplitStepBackwardEuler",
+    ],
+  )
+  .optional();
+  /// Hurst index
+  pub const HURST: Self = Self::real("hurst", "Hurst index", Interval::UNIT, 0.7);
+  /// Correlation of two drivers
+  pub const RHO: Self = Self::real("rho", "Correlation", Interval::CORRELATION, -0.5);
+  /// Underlying price of a pricer
+  pub const S: Self = Self::real("s", "Underlying price", Interval::POSITIVE, 100.0);
+  /// Volatility of a pricer
+  pub const V: Self = Self::real("v", "Volatility", Interval::POSITIVE, 0.2);
+  /// Strike of a pricer
+  pub const K: Self = Self::real("k", "Strike price", Interval::POSITIVE, 100.0);
+  /// Risk-free rate of a pricer
+  pub const R: Self = Self::real("r", "Risk-free rate", Interval::REAL, 0.05);
+  /// Dividend yield of a pricer
+  pub const Q: Self = Self::real("q", "Dividend yield", Interval::REAL, 0.0).optional();
+  /// Time to maturity of a pricer
+  pub const TAU: Self =
+    Self::real("tau", "Time to maturity in years", Interval::POSITIVE, 1.0).optional();
+  /// Evaluation date of a pricer
+  pub const EVAL: Self = Self::component("eval", "Evaluation date").optional();
+  /// Expiration date of a pricer
+  pub const EXPIRATION: Self = Self::component("expiration", "Expiration date").optional();
+
+  /// Real parameter
+  pub const fn real(
+    name: &'static str,
+    description: &'static str,
+    interval: Interval,
+    example: f64,
+  ) -> Self {
+    Self {
+      name,
+      description,
+      kind: ParameterKind::Real(interval),
+      optional: false,
+      example: Some(example),
+    }
+  }
+
+  /// Integer parameter
+  pub const fn integer(
+    name: &'static str,
+    description: &'static str,
+    interval: Interval,
+    example: f64,
+  ) -> Self {
+    Self {
+      name,
+      description,
+      kind: ParameterKind::Integer(interval),
+      optional: false,
+      example: Some(example),
+    }
+  }
+
+  /// Boolean parameter
+  pub const fn boolean(name: &'static str, description: &'static str) -> Self {
+    Self::other(name, description, ParameterKind::Boolean)
+  }
+
+  /// Function of time
+  pub const fn function(name: &'static str, description: &'static str) -> Self {
+    Self::other(name, description, ParameterKind::Function)
+  }
+
+  /// Vector or matrix parameter
+  pub const fn array(name: &'static str, description: &'static str) -> Self {
+    Self::other(name, description, ParameterKind::Array)
+  }
+
+  /// Enum parameter
+  pub const fn choice(
+    name: &'static str,
+    description: &'static str,
+    variants: &'static [&'static str],
+  ) -> Self {
+    Self::other(name, description, ParameterKind::Choice(variants))
+  }
+
+  /// Sampler or distribution driving the model
+  pub const fn component(name: &'static str, description: &'static str) -> Self {
+    Self::other(name, description, ParameterKind::Component)
+  }
+
+  const fn other(name: &'static str, description: &'static str, kind: ParameterKind) -> Self {
+    Self {
+      name,
+      description,
+      kind,
+      optional: false,
+      example: None,
+    }
+  }
+
+  /// Mark the parameter as an `Option`
+  pub const fn optional(self) -> Self {
+    Self {
+      optional: true,
+      ..self
+    }
+  }
+
+  /// Replace the example value
+  pub const fn example(self, example: f64) -> Self {
+    Self {
+      example: Some(example),
+      ..self
+    }
+  }
+
+  /// Replace the description
+  pub const fn describe(self, description: &'static str) -> Self {
+    Self {
+      description,
+      ..self
+    }
+  }
+}
+
+/// Description of a model
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "server", derive(serde::Serialize))]
+pub struct ModelInfo {
+  /// Name of the type
+  pub name: &'static str,
+  /// Full name of the model
+  pub title: &'static str,
+  /// Path of the type within the crate
+  pub path: &'static str,
+  pub kind: ModelKind,
+  /// Dynamics or formula of the model
+  pub description: &'static str,
+  /// Parameters in the order of the `new` constructor, without the fields of optional features
+  pub parameters: &'static [ParameterInfo],
+  pub references: &'static [&'static str],
+}
+
+impl ModelInfo {
+  /// Parameter with the given name
+  pub fn parameter(&self, name: &str) -> Option<&ParameterInfo> {
+    self.parameters.iter().find(|p| p.name == name)
+  }
+}
+
+/// Static description of a process or pricer
+pub trait ProcessInfo {
+  const INFO: ModelInfo;
+
+  /// Description of the model
+  fn info(&self) -> ModelInfo {
+    Self::INFO
+  }
+}
+
+/// Description of every implemented process and pricer
+#[allow(deprecated)]
+pub fn catalog() -> Vec<ModelInfo> {
+  vec![
+    // diffusion
+    CEV::<f64>::INFO,
+    CIR::<f64>::INFO,
+    CustomSDE::<Coefficient, Coefficient>::INFO,
+    FCIR::<f64>::INFO,
+    FGBM::<f64>::INFO,
+    FJacobi::<f64>::INFO,
+    FOU::<f64>::INFO,
+    GammaOU::INFO,
+    GBM::<f64>::INFO,
+    IGOU::INFO,
+    Jacobi::<f64>::INFO,
+    LocalVolProcess::INFO,
+    MultiGBM::INFO,
+    OU::<f64>::INFO,
+    RegimeSwitching::<GBM>::INFO,
+    WrightFisher::INFO,
+    // interest
+    ADG::INFO,
+    CIR2F::INFO,
+    CIRPlusPlus::INFO,
+    DuffieKan::INFO,
+    FVasicek::INFO,
+    G2PP::INFO,
+    HJM::INFO,
+    HoLee::INFO,
+    HullWhite::INFO,
+    HullWhiteFitted::INFO,
+    HullWhite2F::INFO,
+    LiborMarketModel::INFO,
+    Vasicek::INFO,
+    // jump
+    Bates::<Jumps>::INFO,
+    CGMY::<f64>::INFO,
+    CTS::<f64>::INFO,
+    IG::<f64>::INFO,
+    JumpFOU::<Jumps>::INFO,
+    KOU::<Jumps>::INFO,
+    LevyDiffusion::<Jumps>::INFO,
+    Merton::<Jumps>::INFO,
+    NIG::<f64>::INFO,
+    RDTS::<f64>::INFO,
+    StableProcess::<f64>::INFO,
+    TemperedStable::<f64>::INFO,
+    VG::<f64>::INFO,
+    VGSA::<f64>::INFO,
+    // noise
+    CFGNS::INFO,
+    CGNS::<f64>::INFO,
+    CGNSD::INFO,
+    FGN::<f64>::INFO,
+    SpaceTimeField::INFO,
+    // process
+    BM::<f64>::INFO,
+    CBMS::INFO,
+    CBMSD::INFO,
+    CompoundCustom::<Jumps, Jumps>::INFO,
+    CFBMS::INFO,
+    MFBM::INFO,
+    CompoundPoisson::<Jumps>::INFO,
+    CustomJt::<Jumps>::INFO,
+    FBM::<f64>::INFO,
+    FBMBridge::INFO,
+    Hawkes::INFO,
+    Poisson::<f64>::INFO,
+    GammaSubordinator::<f64>::INFO,
+    IGSubordinator::<f64>::INFO,
+    TimeChangedBM::<GammaSubordinator>::INFO,
+    // volatility
+    Bergomi::INFO,
+    BNS::<GammaOU>::INFO,
+    RoughHeston::INFO,
+    Heston::INFO,
+    rbergomi::RoughBergomi::INFO,
+    RBergomi::<fn(f64) -> f64>::INFO,
+    SABR::INFO,
+    SVCGMY::INFO,
+    // pricing
+    AsianPricer::INFO,
+    BatesPricer::INFO,
+    BjerksundStenslandPricer::INFO,
+    BSMPricer::INFO,
+    FiniteDifferencePricer::INFO,
+    GramCharlierPricer::INFO,
+    HestonPricer::INFO,
+    KouPricer::INFO,
+    Merton1976Pricer::INFO,
+    RoughHestonPricer::INFO,
+    SABRPricer::INFO,
+  ]
+}
+
+/// Description of the model with the given type name
+pub fn find(name: &str) -> Option<ModelInfo> {
+  catalog().into_iter().find(|info| info.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use super::*;
+
+  #[test]
+  fn catalog_is_consistent() {
+    let catalog = catalog();
+    let names = catalog.iter().map(|info| info.name).collect::<HashSet<_>>();
+    assert_eq!(names.len(), catalog.len());
+
+    for info in &catalog {
+      assert!(info.path.ends_with(info.name), "{}", info.name);
+      assert!(!info.parameters.is_empty(), "{}", info.name);
+
+      let parameters = info
+        .parameters
+        .iter()
+        .map(|p| p.name)
+        .collect::<HashSet<_>>();
+      assert_eq!(parameters.len(), info.parameters.len(), "{}", info.name);
+
+      for p in info.parameters {
+        match p.kind {
+          ParameterKind::Real(interval) | ParameterKind::Integer(interval) => {
+            let example = p.example.unwrap();
+            assert!(interval.contains(example), "{}.{}", info.name, p.name);
+          }
+          _ => assert!(p.example.is_none(), "{}.{}", info.name, p.name),
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn catalog_lookup() {
+    let gbm = find("GBM").unwrap();
+    assert_eq!(gbm.kind, ModelKind::Diffusion);
+    assert_eq!(
+      gbm.parameter("sigma").unwrap().kind,
+      ParameterKind::Real(Interval::POSITIVE)
+    );
+    assert_eq!(GBM::<f64>::INFO, gbm);
+    assert!(find("Unknown").is_none());
+  }
+}
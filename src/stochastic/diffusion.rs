@@ -1,9 +1,16 @@
 pub mod cev;
 pub mod cir;
+pub mod custom;
 pub mod fcir;
 pub mod fgbm;
 pub mod fjacobi;
 pub mod fou;
 pub mod gbm;
 pub mod jacobi;
+pub mod levy_ou;
+pub mod local_vol;
+pub mod multi_gbm;
 pub mod ou;
+pub mod regime_switching;
+pub mod scheme;
+pub mod wright_fisher;
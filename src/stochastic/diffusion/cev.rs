@@ -3,38 +3,61 @@ use std::sync::Mutex;
 
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
 
-use crate::stochastic::Sampling;
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::scheme::{Coefficients, Scheme},
+  FloatExt, Sampling,
+};
 
 #[derive(ImplNew)]
-pub struct CEV {
-  pub mu: f64,
-  pub sigma: f64,
-  pub gamma: f64,
+pub struct CEV<T: FloatExt = f64> {
+  pub mu: T,
+  pub sigma: T,
+  pub gamma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
   pub calculate_malliavin: Option<bool>,
   #[cfg(feature = "malliavin")]
-  malliavin: Mutex<Option<Array1<f64>>>,
+  malliavin: Mutex<Option<Array1<T>>>,
 }
 
-impl Sampling<f64> for CEV {
+impl<T: FloatExt> Clone for CEV<T> {
+  /// Copy of the parameters with an empty Malliavin derivative
+  fn clone(&self) -> Self {
+    Self {
+      mu: self.mu,
+      sigma: self.sigma,
+      gamma: self.gamma,
+      n: self.n,
+      x0: self.x0,
+      t: self.t,
+      scheme: self.scheme,
+      m: self.m,
+      calculate_malliavin: self.calculate_malliavin,
+      #[cfg(feature = "malliavin")]
+      malliavin: Mutex::new(None),
+    }
+  }
+}
+
+impl<T: FloatExt> Sampling<T> for CEV<T> {
   /// Sample the CEV process
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
 
-    let mut cev = Array1::<f64>::zeros(self.n);
-    cev[0] = self.x0.unwrap_or(0.0);
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut cev = Array1::<T>::zeros(self.n);
+    cev[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
-      cev[i] = cev[i - 1]
-        + self.mu * cev[i - 1] * dt
-        + self.sigma * cev[i - 1].powf(self.gamma) * gn[i - 1]
+      cev[i] = scheme.step(self, cev[i - 1], dt, gn[i - 1]);
     }
 
     #[cfg(feature = "malliavin")]
@@ -45,16 +68,20 @@ impl Sampling<f64> for CEV {
 
       for i in 0..self.n {
         det_term[i] = (self.mu
-          - (self.gamma.powi(2) * self.sigma.powi(2) * cev[i].powf(2.0 * self.gamma - 2.0) / 2.0))
+          - (self.gamma.powi(2)
+            * self.sigma.powi(2)
+            * cev[i].powf(T::from_f64_(2.0) * self.gamma - T::from_f64_(2.0))
+            / T::from_f64_(2.0)))
           * dt;
         if i > 0 {
-          stochastic_term[i] = self.sigma * self.gamma * cev[i].powf(self.gamma - 1.0) * gn[i - 1];
+          stochastic_term[i] =
+            self.sigma * self.gamma * cev[i].powf(self.gamma - T::one()) * gn[i - 1];
         }
         malliavin[i] =
           self.sigma * cev[i].powf(self.gamma) * (det_term[i] + stochastic_term[i]).exp()
       }
 
-      let _ = std::mem::replace(&mut *self.malliavin.lock().unwrap(), Some(malliavin));
+      *self.malliavin.lock().unwrap() = Some(malliavin);
     }
 
     cev
@@ -77,11 +104,53 @@ impl Sampling<f64> for CEV {
   ///
   /// The Malliavin derivative of the CEV process shows the sensitivity of the stock price with respect to the Wiener process.
   #[cfg(feature = "malliavin")]
-  fn malliavin(&self) -> Array1<f64> {
+  fn malliavin(&self) -> Array1<T> {
     self.malliavin.lock().unwrap().clone().unwrap()
   }
 }
 
+impl<T: FloatExt> Coefficients<T> for CEV<T> {
+  fn drift(&self, x: T) -> T {
+    self.mu * x
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * x.powf(self.gamma)
+  }
+
+  fn milstein(&self, x: T) -> T {
+    self.sigma.powi(2) * self.gamma * x.powf(self.gamma + self.gamma - T::one())
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for CEV<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CEV",
+    title: "Constant elasticity of variance process",
+    path: "stochastic::diffusion::cev::CEV",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = mu X(t) dt + sigma X(t)^gamma dW(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Drift", Interval::REAL, 0.05),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.2),
+      ParameterInfo::real(
+        "gamma",
+        "Elasticity of the volatility",
+        Interval::NON_NEGATIVE,
+        0.5,
+      ),
+      ParameterInfo::N,
+      ParameterInfo::X0.example(1.0),
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Cox, J. C. (1975). Notes on option pricing I: Constant elasticity of variance diffusions.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -96,19 +165,19 @@ mod tests {
 
   #[test]
   fn cev_length_equals_n() {
-    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, None);
+    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, None, None);
     assert_eq!(cev.sample().len(), N);
   }
 
   #[test]
   fn cev_starts_with_x0() {
-    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, None);
+    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, None, None);
     assert_eq!(cev.sample()[0], X0);
   }
 
   #[test]
   fn cev_plot() {
-    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, None);
+    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, None, None);
     plot_1d!(
       cev.sample(),
       "Constant Elasticity of Variance (CEV) process"
@@ -118,7 +187,17 @@ mod tests {
   #[test]
   #[cfg(feature = "malliavin")]
   fn cev_malliavin() {
-    let cev = CEV::new(0.25, 0.5, 0.3, N, Some(X0), Some(1.0), None, Some(true));
+    let cev = CEV::new(
+      0.25,
+      0.5,
+      0.3,
+      N,
+      Some(X0),
+      Some(1.0),
+      None,
+      None,
+      Some(true),
+    );
     let process = cev.sample();
     let malliavin = cev.malliavin();
     plot_2d!(
@@ -1,52 +1,397 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
+use rand::thread_rng;
+use rand_distr::{ChiSquared, Distribution, Poisson};
+use statrs::function::erf::erfc;
 
-use crate::stochastic::Sampling;
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::scheme::{Coefficients, Scheme},
+    FloatExt, Sampling,
+  },
+};
+
+/// Discretization of the square-root (CIR) diffusion
+/// dX(t) = kappa(theta - X(t))dt + sigma * sqrt(X(t))dW(t)
+///
+/// The shared [`Scheme`]s convert into it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscretizationScheme {
+  /// Euler-Maruyama with truncation max(0, X) or reflection |X| (`use_sym`).
+  /// Requires the Feller condition 2 kappa theta >= sigma^2.
+  #[default]
+  Euler,
+  /// Milstein, Euler with the correction sigma^2 (dW^2 - dt) / 4, truncated or reflected
+  /// like Euler. Requires the Feller condition 2 kappa theta >= sigma^2.
+  Milstein,
+  /// [`Scheme::SRK2`], truncated or reflected like Euler. Requires the Feller condition.
+  SRK2,
+  /// [`Scheme::PredictorCorrector`], truncated or reflected like Euler. Requires the Feller
+  /// condition.
+  PredictorCorrector,
+  /// [`Scheme::DriftImplicitEuler`], truncated or reflected like Euler, stable for a fast
+  /// mean reversion. Requires the Feller condition.
+  DriftImplicitEuler,
+  /// [`Scheme::SplitStepBackwardEuler`], truncated or reflected like Euler, stable for a
+  /// fast mean reversion. Requires the Feller condition.
+  SplitStepBackwardEuler,
+  /// Drift-implicit Euler on Y = sqrt(X) (Alfonsi, 2005).
+  ///
+  /// The implicit equation is a quadratic in Y(n+1) with a unique positive root, so the
+  /// scheme is positivity preserving without any truncation. Requires sigma^2 < 4 kappa theta.
+  Alfonsi,
+  /// Explicit scheme on the Lamperti transform Y = sqrt(X) (Alfonsi's E(0) scheme)
+  ///
+  /// dY(t) = ((4 kappa theta - sigma^2) / (8 Y(t)) - kappa Y(t) / 2)dt + sigma / 2 dW(t)
+  ///
+  /// The singular 1 / Y term is integrated in X coordinates, which gives
+  /// X(n+1) = ((1 - kappa dt / 2) sqrt(X(n)) + sigma dW / (2 (1 - kappa dt / 2)))^2 + (kappa theta - sigma^2 / 4)dt
+  /// and X is non-negative by construction. Requires sigma^2 < 4 kappa theta and kappa dt < 2.
+  Lamperti,
+  /// Exact sampling of the transition density (Broadie & Kaya, 2006)
+  ///
+  /// X(n+1) = c chi'^2_d(lambda) is non-central chi-squared with d = 4 kappa theta / sigma^2
+  /// degrees of freedom, c = sigma^2 (1 - e^(-kappa dt)) / (4 kappa) and non-centrality
+  /// lambda = X(n) e^(-kappa dt) / c, hence unbiased for any step size and without the
+  /// Feller condition. For d > 1 the Gaussian part of chi'^2 is driven by dW / sqrt(dt).
+  Exact,
+  /// Quadratic-Exponential scheme (Andersen, 2008)
+  ///
+  /// Matches the conditional mean m and variance s^2 of the exact transition. With
+  /// psi = s^2 / m^2 <= 1.5 it samples a (b + Z)^2, otherwise the mixture of an atom at zero
+  /// and an exponential tail. Non-negative for any step size and without the Feller
+  /// condition, Z = dW / sqrt(dt) and the tail is inverted at U = N(Z).
+  QuadraticExponential,
+}
+
+impl From<Scheme> for DiscretizationScheme {
+  fn from(scheme: Scheme) -> Self {
+    match scheme {
+      Scheme::Euler => Self::Euler,
+      Scheme::Milstein => Self::Milstein,
+      Scheme::SRK2 => Self::SRK2,
+      Scheme::PredictorCorrector => Self::PredictorCorrector,
+      Scheme::DriftImplicitEuler => Self::DriftImplicitEuler,
+      Scheme::SplitStepBackwardEuler => Self::SplitStepBackwardEuler,
+    }
+  }
+}
+
+/// Coefficients of the square-root diffusion for the shared schemes
+struct SqrtDiffusion<T: FloatExt> {
+  kappa: T,
+  theta: T,
+  sigma: T,
+}
+
+impl<T: FloatExt> Coefficients<T> for SqrtDiffusion<T> {
+  fn drift(&self, x: T) -> T {
+    self.kappa * (self.theta - x)
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * x.abs().sqrt()
+  }
+
+  fn milstein(&self, _x: T) -> T {
+    T::from_f64_(0.5) * self.sigma.powi(2)
+  }
+
+  fn implicit_drift(&self, x: T, dt: T) -> T {
+    (x + self.kappa * self.theta * dt) / (T::one() + self.kappa * dt)
+  }
+}
+
+/// One step of the square-root diffusion with the given scheme
+///
+/// The transitions of the exact and the QE scheme are sampled in double precision.
+pub(crate) fn sqrt_diffusion_step<T: FloatExt>(
+  scheme: DiscretizationScheme,
+  kappa: T,
+  theta: T,
+  sigma: T,
+  x: T,
+  dt: T,
+  dw: T,
+  use_sym: bool,
+) -> T {
+  let half = T::from_f64_(0.5);
+
+  match scheme {
+    DiscretizationScheme::Euler => {
+      let dx = kappa * (theta - x) * dt + sigma * x.abs().sqrt() * dw;
+
+      match use_sym {
+        true => (x + dx).abs(),
+        false => (x + dx).max(T::zero()),
+      }
+    }
+    DiscretizationScheme::Milstein => {
+      let dx = kappa * (theta - x) * dt
+        + sigma * x.abs().sqrt() * dw
+        + T::from_f64_(0.25) * sigma.powi(2) * (dw.powi(2) - dt);
+
+      match use_sym {
+        true => (x + dx).abs(),
+        false => (x + dx).max(T::zero()),
+      }
+    }
+    DiscretizationScheme::SRK2
+    | DiscretizationScheme::PredictorCorrector
+    | DiscretizationScheme::DriftImplicitEuler
+    | DiscretizationScheme::SplitStepBackwardEuler => {
+      let shared = match scheme {
+        DiscretizationScheme::SRK2 => Scheme::SRK2,
+        DiscretizationScheme::PredictorCorrector => Scheme::PredictorCorrector,
+        DiscretizationScheme::DriftImplicitEuler => Scheme::DriftImplicitEuler,
+        _ => Scheme::SplitStepBackwardEuler,
+      };
+      let next = shared.step(
+        &SqrtDiffusion {
+          kappa,
+          theta,
+          sigma,
+        },
+        x,
+        dt,
+        dw,
+      );
+
+      match use_sym {
+        true => next.abs(),
+        false => next.max(T::zero()),
+      }
+    }
+    DiscretizationScheme::Alfonsi => {
+      // (1 + kappa dt / 2) Y^2 - (Y(n) + sigma dW / 2) Y - (4 kappa theta - sigma^2) dt / 8 = 0
+      let four = T::from_f64_(4.0);
+      let a = T::one() + half * kappa * dt;
+      let b = x.sqrt() + half * sigma * dw;
+      let c = (four * kappa * theta - sigma.powi(2)) * dt / T::from_f64_(8.0);
+      let y = (b + (b.powi(2) + four * a * c).sqrt()) / (a + a);
+
+      y.powi(2)
+    }
+    DiscretizationScheme::Lamperti => {
+      let a = T::one() - half * kappa * dt;
+      let y = a * x.sqrt() + half * sigma * dw / a;
+
+      y.powi(2) + (kappa * theta - T::from_f64_(0.25) * sigma.powi(2)) * dt
+    }
+    DiscretizationScheme::Exact => {
+      let (kappa, theta, sigma, x, dt): (f64, f64, f64, f64, f64) = (
+        kappa.into(),
+        theta.into(),
+        sigma.into(),
+        x.into(),
+        dt.into(),
+      );
+      let decay = (-kappa * dt).exp();
+      let c = sigma.powi(2) * (1.0 - decay) / (4.0 * kappa);
+      let df = 4.0 * kappa * theta / sigma.powi(2);
+
+      T::from_f64_(c * noncentral_chi_squared(df, x.max(0.0) * decay / c, dw.into() / dt.sqrt()))
+    }
+    DiscretizationScheme::QuadraticExponential => {
+      let transition = QeTransition::new(
+        kappa.into(),
+        theta.into(),
+        sigma.into(),
+        x.into(),
+        dt.into(),
+      );
+      T::from_f64_(transition.sample((dw / dt.sqrt()).into()))
+    }
+  }
+}
+
+/// Switching level of psi between the quadratic and the exponential branch of the QE scheme
+const PSI_CRITICAL: f64 = 1.5;
+
+/// Moment matched transition of the square-root diffusion of the QE scheme
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum QeTransition {
+  /// a (b + Z)^2
+  Quadratic { a: f64, b: f64 },
+  /// 0 with probability p, otherwise exponential with rate beta
+  Exponential { p: f64, beta: f64 },
+}
+
+impl QeTransition {
+  /// Transition from x over dt, matching the exact conditional mean and variance
+  pub(crate) fn new(kappa: f64, theta: f64, sigma: f64, x: f64, dt: f64) -> Self {
+    let x = x.max(0.0);
+    let decay = (-kappa * dt).exp();
+    let m = theta + (x - theta) * decay;
+    let s2 = x * sigma.powi(2) * decay * (1.0 - decay) / kappa
+      + theta * sigma.powi(2) * (1.0 - decay).powi(2) / (2.0 * kappa);
+    let psi = s2 / m.powi(2);
+
+    if psi <= PSI_CRITICAL {
+      let b2 = 2.0 / psi - 1.0 + (2.0 / psi).sqrt() * (2.0 / psi - 1.0).sqrt();
+      QeTransition::Quadratic {
+        a: m / (1.0 + b2),
+        b: b2.sqrt(),
+      }
+    } else {
+      let p = (psi - 1.0) / (psi + 1.0);
+      QeTransition::Exponential {
+        p,
+        beta: (1.0 - p) / m,
+      }
+    }
+  }
+
+  /// Next value for the standard normal z
+  pub(crate) fn sample(self, z: f64) -> f64 {
+    match self {
+      QeTransition::Quadratic { a, b } => a * (b + z).powi(2),
+      QeTransition::Exponential { p, beta } => {
+        let u = 0.5 * erfc(-z / std::f64::consts::SQRT_2);
+
+        match u <= p {
+          true => 0.0,
+          false => ((1.0 - p) / (1.0 - u)).ln() / beta,
+        }
+      }
+    }
+  }
+
+  /// E[exp(u X)] of the next value, None where it does not exist
+  pub(crate) fn mgf(self, u: f64) -> Option<f64> {
+    match self {
+      QeTransition::Quadratic { a, b } => (2.0 * u * a < 1.0)
+        .then(|| (u * b.powi(2) * a / (1.0 - 2.0 * u * a)).exp() / (1.0 - 2.0 * u * a).sqrt()),
+      QeTransition::Exponential { p, beta } => {
+        (u < beta).then(|| p + beta * (1.0 - p) / (beta - u))
+      }
+    }
+  }
+}
+
+/// Non-central chi-squared variate with df degrees of freedom and non-centrality lambda
+///
+/// For df > 1 it is (z + sqrt(lambda))^2 + chi^2_(df - 1) with the given standard normal z,
+/// otherwise a chi^2_(df + 2N) with N ~ Poisson(lambda / 2).
+fn noncentral_chi_squared(df: f64, lambda: f64, z: f64) -> f64 {
+  let mut rng = thread_rng();
+
+  if df > 1.0 {
+    let central = match df - 1.0 > f64::EPSILON {
+      true => ChiSquared::new(df - 1.0).unwrap().sample(&mut rng),
+      false => 0.0,
+    };
+
+    return (z + lambda.sqrt()).powi(2) + central;
+  }
+
+  // P(N > 0) is below machine precision for tiny lambda, where the Poisson sampler of
+  // rand_distr also breaks down
+  let poisson = match lambda > f64::EPSILON {
+    true => Poisson::new(0.5 * lambda).unwrap().sample(&mut rng),
+    false => 0.0,
+  };
+
+  ChiSquared::new(df + 2.0 * poisson)
+    .unwrap()
+    .sample(&mut rng)
+}
+
+/// Check the parameter restriction of the scheme for the step size dt
+pub(crate) fn check_sqrt_diffusion(
+  scheme: DiscretizationScheme,
+  kappa: f64,
+  theta: f64,
+  sigma: f64,
+  dt: f64,
+) -> StochasticResult<()> {
+  match scheme {
+    DiscretizationScheme::Euler
+    | DiscretizationScheme::Milstein
+    | DiscretizationScheme::SRK2
+    | DiscretizationScheme::PredictorCorrector
+    | DiscretizationScheme::DriftImplicitEuler
+    | DiscretizationScheme::SplitStepBackwardEuler => ensure(
+      2.0 * kappa * theta >= sigma.powi(2),
+      "2 * theta * mu < sigma^2",
+    ),
+    DiscretizationScheme::Alfonsi => ensure(
+      4.0 * kappa * theta > sigma.powi(2),
+      "4 * theta * mu <= sigma^2",
+    ),
+    DiscretizationScheme::Lamperti => {
+      ensure(
+        4.0 * kappa * theta > sigma.powi(2),
+        "4 * theta * mu <= sigma^2",
+      )?;
+      ensure(kappa * dt < 2.0, "theta * dt >= 2")
+    }
+    DiscretizationScheme::Exact | DiscretizationScheme::QuadraticExponential => ensure(
+      kappa > 0.0 && theta > 0.0 && sigma > 0.0,
+      "theta, mu and sigma must be positive",
+    ),
+  }
+}
 
 /// Cox-Ingersoll-Ross (CIR) process.
 /// dX(t) = theta(mu - X(t))dt + sigma * sqrt(X(t))dW(t)
 /// where X(t) is the CIR process.
-#[derive(ImplNew)]
-pub struct CIR {
-  pub theta: f64,
-  pub mu: f64,
-  pub sigma: f64,
+#[derive(ImplNew, Clone)]
+pub struct CIR<T: FloatExt = f64> {
+  pub theta: T,
+  pub mu: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub use_sym: Option<bool>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<DiscretizationScheme>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for CIR {
+impl<T: FloatExt> Sampling<T> for CIR<T> {
   /// Sample the Cox-Ingersoll-Ross (CIR) process
-  fn sample(&self) -> Array1<f64> {
-    assert!(
-      2.0 * self.theta * self.mu >= self.sigma.powi(2),
-      "2 * theta * mu < sigma^2"
-    );
+  fn sample(&self) -> Array1<T> {
+    or_panic(self.validate());
+    let scheme = self.scheme.unwrap_or_default();
 
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
 
-    let mut cir = Array1::<f64>::zeros(self.n);
-    cir[0] = self.x0.unwrap_or(0.0);
+    let mut cir = Array1::<T>::zeros(self.n);
+    cir[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
-      let dcir = self.theta * (self.mu - cir[i - 1]) * dt
-        + self.sigma * (cir[i - 1]).abs().sqrt() * gn[i - 1];
-
-      cir[i] = match self.use_sym.unwrap_or(false) {
-        true => (cir[i - 1] + dcir).abs(),
-        false => (cir[i - 1] + dcir).max(0.0),
-      };
+      cir[i] = sqrt_diffusion_step(
+        scheme,
+        self.theta,
+        self.mu,
+        self.sigma,
+        cir[i - 1],
+        dt,
+        gn[i - 1],
+        self.use_sym.unwrap_or(false),
+      );
     }
 
     cir
   }
 
+  /// Check the positivity condition of the scheme
+  fn validate(&self) -> StochasticResult<()> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    check_sqrt_diffusion(
+      self.scheme.unwrap_or_default(),
+      self.theta.into(),
+      self.mu.into(),
+      self.sigma.into(),
+      dt.into(),
+    )
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -58,6 +403,63 @@ impl Sampling<f64> for CIR {
   }
 }
 
+impl CIR {
+  /// Coefficients (A, B) of the zero-coupon bond P(tau) = A(tau) e^(-B(tau) x) of the short
+  /// rate x (Cox, Ingersoll & Ross, 1985)
+  pub fn bond_coefficients(&self, tau: f64) -> (f64, f64) {
+    let h = (self.theta.powi(2) + 2.0 * self.sigma.powi(2)).sqrt();
+    let growth = (h * tau).exp() - 1.0;
+    let denominator = 2.0 * h + (self.theta + h) * growth;
+    let a = (2.0 * h * (0.5 * (self.theta + h) * tau).exp() / denominator)
+      .powf(2.0 * self.theta * self.mu / self.sigma.powi(2));
+
+    (a, 2.0 * growth / denominator)
+  }
+
+  /// Price of the zero-coupon bond with time to maturity tau when the short rate is x
+  pub fn bond_price(&self, x: f64, tau: f64) -> f64 {
+    let (a, b) = self.bond_coefficients(tau);
+    a * (-b * x).exp()
+  }
+
+  /// Instantaneous forward rate f(0, t) = -d ln P(t) / dt of the short rate x
+  pub fn forward_rate(&self, x: f64, t: f64) -> f64 {
+    let h = (self.theta.powi(2) + 2.0 * self.sigma.powi(2)).sqrt();
+    let growth = (h * t).exp() - 1.0;
+    let denominator = 2.0 * h + (self.theta + h) * growth;
+
+    2.0 * self.theta * self.mu * growth / denominator
+      + x * 4.0 * h.powi(2) * (h * t).exp() / denominator.powi(2)
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for CIR<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CIR",
+    title: "Cox-Ingersoll-Ross process",
+    path: "stochastic::diffusion::cir::CIR",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = theta (mu - X(t)) dt + sigma sqrt(X(t)) dW(t)",
+    parameters: &[
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("mu", "Long-run mean", Interval::POSITIVE, 0.04),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::X0.example(0.04),
+      ParameterInfo::T,
+      ParameterInfo::boolean("use_sym", "Reflect instead of truncate negative values").optional(),
+      ParameterInfo::choice("scheme", "Discretization scheme", &["Euler", "Milstein", "SRK2", "PredictorCorrector", "DriftImplicitEuler", "SplitStepBackwardEuler", "Alfonsi", "Lamperti", "Exact", "QuadraticExponential"]).optional(),
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Cox, J. C., Ingersoll, J. E., & Ross, S. A. (1985). A theory of the term structure of interest rates.",
+      "Alfonsi, A. (2005). On the discretization schemes for the CIR (and Bessel squared) processes.",
+      "Broadie, M., & Kaya, Ö. (2006). Exact simulation of stochastic volatility and other affine jump diffusion processes.",
+      "Andersen, L. (2008). Simple and efficient simulation of the Heston stochastic volatility model.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -69,22 +471,219 @@ mod tests {
 
   #[test]
   fn cir_length_equals_n() {
-    let cir = CIR::new(1.0, 1.2, 0.2, N, Some(X0), Some(1.0), Some(false), None);
+    let cir = CIR::new(
+      1.0,
+      1.2,
+      0.2,
+      N,
+      Some(X0),
+      Some(1.0),
+      Some(false),
+      None,
+      None,
+    );
     assert_eq!(cir.sample().len(), N);
   }
 
   #[test]
   fn cir_starts_with_x0() {
-    let cir = CIR::new(1.0, 1.2, 0.2, N, Some(X0), Some(1.0), Some(false), None);
+    let cir = CIR::new(
+      1.0,
+      1.2,
+      0.2,
+      N,
+      Some(X0),
+      Some(1.0),
+      Some(false),
+      None,
+      None,
+    );
     assert_eq!(cir.sample()[0], X0);
   }
 
   #[test]
   fn cir_plot() {
-    let cir = CIR::new(1.0, 1.2, 0.2, N, Some(X0), Some(1.0), Some(false), None);
+    let cir = CIR::new(
+      1.0,
+      1.2,
+      0.2,
+      N,
+      Some(X0),
+      Some(1.0),
+      Some(false),
+      None,
+      None,
+    );
     plot_1d!(cir.sample(), "Cox-Ingersoll-Ross (CIR) process");
   }
 
+  #[test]
+  fn cir_try_sample_rejects_feller_violation() {
+    let cir = CIR::new(1.0, 0.01, 0.5, N, Some(X0), Some(1.0), None, None, None);
+    assert!(cir.try_sample().is_err());
+    assert!(cir.try_sample_par().is_err());
+  }
+
+  #[test]
+  fn cir_lamperti_rejects_large_steps() {
+    // theta dt = 2.5 flips the sign of the Lamperti drift factor 1 - theta dt / 2
+    let cir = |n| {
+      CIR::new(
+        50.0,
+        0.04,
+        0.3,
+        n,
+        Some(0.04),
+        Some(1.0),
+        None,
+        Some(DiscretizationScheme::Lamperti),
+        None,
+      )
+    };
+    assert!(cir(21).try_sample().is_err());
+    assert!(cir(101).try_sample().is_ok());
+  }
+
+  #[test]
+  fn cir_scheme_bias() {
+    // sigma^2 close to the Feller bound, start close to zero
+    let (theta, mu, sigma, x0, t): (f64, f64, f64, f64, f64) = (2.0, 0.04, 0.39, 0.01, 1.0);
+    let paths = 20_000;
+    let mean = mu + (x0 - mu) * (-theta * t).exp();
+    let variance = x0 * sigma.powi(2) / theta * ((-theta * t).exp() - (-2.0 * theta * t).exp())
+      + mu * sigma.powi(2) / (2.0 * theta) * (1.0 - (-theta * t).exp()).powi(2);
+
+    for scheme in [
+      DiscretizationScheme::Euler,
+      DiscretizationScheme::Milstein,
+      Scheme::SRK2.into(),
+      Scheme::PredictorCorrector.into(),
+      DiscretizationScheme::Alfonsi,
+      DiscretizationScheme::Lamperti,
+      DiscretizationScheme::QuadraticExponential,
+    ] {
+      let cir = CIR::new(
+        theta,
+        mu,
+        sigma,
+        101,
+        Some(x0),
+        Some(t),
+        None,
+        Some(scheme),
+        None,
+      );
+      let terminal = Array1::from_shape_fn(paths, |_| *cir.sample().last().unwrap());
+      let bias = terminal.mean().unwrap() - mean;
+      let variance_bias = terminal.var(1.0) / variance - 1.0;
+
+      println!(
+        "{:?}: mean bias {:.6}, relative variance bias {:.4}, min {:.6}",
+        scheme,
+        bias,
+        variance_bias,
+        terminal.fold(f64::INFINITY, |a, &b| a.min(b))
+      );
+      assert!(terminal.iter().all(|x| *x >= 0.0));
+
+      if !matches!(
+        scheme,
+        DiscretizationScheme::Euler
+          | DiscretizationScheme::Milstein
+          | DiscretizationScheme::SRK2
+          | DiscretizationScheme::PredictorCorrector
+      ) {
+        assert!(bias.abs() < 4.0 * (variance / paths as f64).sqrt() + 5e-4);
+        assert!(variance_bias.abs() < 0.1);
+      }
+    }
+  }
+
+  #[test]
+  fn cir_implicit_schemes_are_stable_for_stiff_mean_reversion() {
+    // theta dt = 5, the explicit step overshoots the mean and swings between 0 and 5 mu
+    let (theta, mu, sigma): (f64, f64, f64) = (50.0, 0.04, 0.3);
+    let cir = |scheme| {
+      CIR::new(
+        theta,
+        mu,
+        sigma,
+        11,
+        Some(mu),
+        Some(1.0),
+        None,
+        Some(scheme),
+        None,
+      )
+    };
+
+    let explicit = cir(DiscretizationScheme::Euler).sample();
+    assert!(explicit.iter().any(|x| (x - mu).abs() > 2.0 * mu));
+
+    for scheme in [Scheme::DriftImplicitEuler, Scheme::SplitStepBackwardEuler] {
+      let terminal = Array1::from_shape_fn(2000, |_| *cir(scheme.into()).sample().last().unwrap());
+      assert!(
+        terminal.iter().all(|x| (0.0..3.0 * mu).contains(x)),
+        "{:?}",
+        scheme
+      );
+      assert!(
+        (terminal.mean().unwrap() / mu - 1.0).abs() < 0.05,
+        "{:?}",
+        scheme
+      );
+    }
+  }
+
+  #[test]
+  fn cir_exact_is_unbiased_without_feller() {
+    // 2 theta mu / sigma^2 = 0.16, Euler and the square-root schemes are all biased here
+    let (theta, mu, sigma, x0, t): (f64, f64, f64, f64, f64) = (1.0, 0.04, 0.5, 0.04, 1.0);
+    let paths = 20_000;
+    let mean = mu + (x0 - mu) * (-theta * t).exp();
+    let variance = x0 * sigma.powi(2) / theta * ((-theta * t).exp() - (-2.0 * theta * t).exp())
+      + mu * sigma.powi(2) / (2.0 * theta) * (1.0 - (-theta * t).exp()).powi(2);
+
+    // A single step is enough
+    let cir = CIR::new(
+      theta,
+      mu,
+      sigma,
+      2,
+      Some(x0),
+      Some(t),
+      None,
+      Some(DiscretizationScheme::Exact),
+      None,
+    );
+    let terminal = Array1::from_shape_fn(paths, |_| cir.sample()[1]);
+
+    assert!(terminal.iter().all(|x| *x >= 0.0));
+    assert!((terminal.mean().unwrap() - mean).abs() < 4.0 * (variance / paths as f64).sqrt());
+    assert!((terminal.var(1.0) / variance - 1.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn cir_f32_exact_matches_the_mean() {
+    let (theta, mu, sigma, x0) = (1.0_f32, 0.04, 0.5, 0.04);
+    let cir = CIR::<f32>::new(
+      theta,
+      mu,
+      sigma,
+      2,
+      Some(x0),
+      Some(1.0),
+      None,
+      Some(DiscretizationScheme::Exact),
+      Some(20_000),
+    );
+    let terminal = cir.sample_par().column(1).to_owned();
+
+    // Standard deviation of the terminal value about 0.05
+    assert!(terminal.iter().all(|x| *x >= 0.0));
+    assert!((terminal.mean().unwrap() - 0.04).abs() < 2e-3);
+  }
+
   #[test]
   #[ignore = "Not implemented"]
   #[cfg(feature = "malliavin")]
@@ -0,0 +1,504 @@
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand::thread_rng;
+use rand_distr::{Distribution, Poisson, StandardNormal};
+
+use crate::stochastic::{
+  catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::scheme::Scheme,
+  Sampling,
+};
+
+/// Discretization scheme of [`CustomSDE`], the shared [`Scheme`]s convert into it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SDEScheme {
+  /// Euler-Maruyama, strong order 0.5
+  #[default]
+  Euler,
+  /// Milstein, strong order 1.0
+  Milstein,
+  /// Wagner-Platen order 1.5 strong Taylor scheme
+  WagnerPlaten,
+  /// Derivative-free Runge-Kutta scheme of Platen with two stages, strong order 1.0
+  SRK2,
+  /// Euler predictor and trapezoidal corrector with the drift f - g g' / 2
+  PredictorCorrector,
+  /// Drift-implicit (backward) Euler, stable for stiff drifts
+  ///
+  /// X(n+1) = X(n) + f(t(n+1), X(n+1))dt + g(t(n), X(n))dW(n)
+  DriftImplicitEuler,
+  /// Split-step backward Euler (Higham, Mao & Stuart, 2002)
+  ///
+  /// Y = X(n) + f(t(n+1), Y)dt, X(n+1) = Y + g(t(n), Y)dW(n)
+  SplitStepBackwardEuler,
+}
+
+impl From<Scheme> for SDEScheme {
+  fn from(scheme: Scheme) -> Self {
+    match scheme {
+      Scheme::Euler => Self::Euler,
+      Scheme::Milstein => Self::Milstein,
+      Scheme::SRK2 => Self::SRK2,
+      Scheme::PredictorCorrector => Self::PredictorCorrector,
+      Scheme::DriftImplicitEuler => Self::DriftImplicitEuler,
+      Scheme::SplitStepBackwardEuler => Self::SplitStepBackwardEuler,
+    }
+  }
+}
+
+/// Scalar SDE with user defined drift, diffusion and optional jumps.
+///
+/// dX(t) = f(t, X(t))dt + g(t, X(t))dW(t) + h(t, X(t-))dN(t)
+///
+/// The derivatives needed by the higher order schemes are computed with central
+/// finite differences, so only f and g have to be provided. Compound Poisson jumps
+/// are added with [`CustomSDE::with_jumps`].
+#[derive(ImplNew)]
+pub struct CustomSDE<F, G>
+where
+  F: Fn(f64, f64) -> f64 + Send + Sync,
+  G: Fn(f64, f64) -> f64 + Send + Sync,
+{
+  pub drift: F,
+  pub diffusion: G,
+  pub n: usize,
+  pub x0: Option<f64>,
+  pub t: Option<f64>,
+  pub scheme: SDEScheme,
+  pub m: Option<usize>,
+  jumps: Option<Jumps>,
+}
+
+/// Compound Poisson jumps of [`CustomSDE`]
+struct Jumps {
+  /// Jump intensity
+  lambda: f64,
+  /// Jump size h(t, X(t-)), may draw its own random marks
+  size: Box<dyn Fn(f64, f64) -> f64 + Send + Sync>,
+}
+
+/// Value, time derivative, first and second space derivative of a coefficient
+struct Derivatives {
+  v: f64,
+  v_t: f64,
+  v_x: f64,
+  v_xx: f64,
+}
+
+impl Derivatives {
+  fn new(f: &impl Fn(f64, f64) -> f64, t: f64, x: f64) -> Self {
+    // Balances truncation and round-off error of the second derivative
+    let h = 1e-4 * x.abs().max(1.0);
+    let ht = 1e-6 * t.abs().max(1.0);
+    let (v, up, down) = (f(t, x), f(t, x + h), f(t, x - h));
+
+    Self {
+      v,
+      v_t: (f(t + ht, x) - f((t - ht).max(0.0), x)) / (t + ht - (t - ht).max(0.0)),
+      v_x: (up - down) / (2.0 * h),
+      v_xx: (up - 2.0 * v + down) / h.powi(2),
+    }
+  }
+}
+
+impl<F, G> CustomSDE<F, G>
+where
+  F: Fn(f64, f64) -> f64 + Send + Sync,
+  G: Fn(f64, f64) -> f64 + Send + Sync,
+{
+  /// Add compound Poisson jumps with intensity lambda and size h(t, X(t-)).
+  ///
+  /// The number of jumps in a step is Poisson(lambda dt) and every jump is applied at the
+  /// end of the step to the current value. Random jump sizes are drawn inside h.
+  pub fn with_jumps<H>(mut self, lambda: f64, size: H) -> Self
+  where
+    H: Fn(f64, f64) -> f64 + Send + Sync + 'static,
+  {
+    self.jumps = Some(Jumps {
+      lambda,
+      size: Box::new(size),
+    });
+    self
+  }
+
+  /// Solve the SDE for the given standard normal draws.
+  ///
+  /// dW = sqrt(dt) * u1 and dZ = dt^(3/2) / 2 * (u1 + u2 / sqrt(3)) is the double integral
+  /// int int dW ds needed by the order 1.5 scheme (u2 is ignored by the other schemes).
+  /// Useful to compare schemes on the same Brownian path. Jumps, if any, are drawn here.
+  pub fn sample_from_noise(&self, u1: &Array1<f64>, u2: &Array1<f64>) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let mut x = Array1::<f64>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    let jump_counts = self.jumps.as_ref().map(|jumps| {
+      let rate = jumps.lambda * dt;
+      assert!(rate >= 0.0, "lambda must be non-negative");
+      // Poisson of rand_distr is not reliable for tiny rates
+      (rate > f64::EPSILON).then(|| Poisson::new(rate).unwrap())
+    });
+    let mut rng = thread_rng();
+
+    for i in 1..self.n {
+      let t = (i - 1) as f64 * dt;
+      let dw = dt.sqrt() * u1[i - 1];
+      let dz = 0.5 * dt.powf(1.5) * (u1[i - 1] + u2[i - 1] / 3.0_f64.sqrt());
+      x[i] = self.step(t, x[i - 1], dt, dw, dz);
+
+      if let (Some(jumps), Some(Some(poisson))) = (&self.jumps, &jump_counts) {
+        let count: f64 = poisson.sample(&mut rng);
+        for _ in 0..count as usize {
+          x[i] += (jumps.size)(t + dt, x[i]);
+        }
+      }
+    }
+
+    x
+  }
+
+  fn step(&self, t: f64, x: f64, dt: f64, dw: f64, dz: f64) -> f64 {
+    match self.scheme {
+      SDEScheme::Euler => x + (self.drift)(t, x) * dt + (self.diffusion)(t, x) * dw,
+      SDEScheme::Milstein => {
+        let b = Derivatives::new(&self.diffusion, t, x);
+        x + (self.drift)(t, x) * dt + b.v * dw + 0.5 * b.v * b.v_x * (dw.powi(2) - dt)
+      }
+      SDEScheme::WagnerPlaten => {
+        let a = Derivatives::new(&self.drift, t, x);
+        let b = Derivatives::new(&self.diffusion, t, x);
+
+        // Kloeden & Platen (1992), Eq. 10.4.1 extended with the time derivatives
+        x + a.v * dt
+          + b.v * dw
+          + 0.5 * b.v * b.v_x * (dw.powi(2) - dt)
+          + a.v_x * b.v * dz
+          + 0.5 * (a.v_t + a.v * a.v_x + 0.5 * b.v.powi(2) * a.v_xx) * dt.powi(2)
+          + (b.v_t + a.v * b.v_x + 0.5 * b.v.powi(2) * b.v_xx) * (dw * dt - dz)
+          + 0.5 * b.v * (b.v * b.v_xx + b.v_x.powi(2)) * (dw.powi(2) / 3.0 - dt) * dw
+      }
+      SDEScheme::SRK2 => {
+        let (a, b) = ((self.drift)(t, x), (self.diffusion)(t, x));
+        let y = x + a * dt + b * dt.sqrt();
+        x + a * dt + b * dw + ((self.diffusion)(t, y) - b) * (dw.powi(2) - dt) / (2.0 * dt.sqrt())
+      }
+      SDEScheme::PredictorCorrector => {
+        let corrected = |t: f64, x: f64| {
+          let b = Derivatives::new(&self.diffusion, t, x);
+          (self.drift)(t, x) - 0.5 * b.v * b.v_x
+        };
+        let b = (self.diffusion)(t, x);
+        let y = x + (self.drift)(t, x) * dt + b * dw;
+        x + 0.5 * (corrected(t + dt, y) + corrected(t, x)) * dt
+          + 0.5 * ((self.diffusion)(t + dt, y) + b) * dw
+      }
+      SDEScheme::DriftImplicitEuler => {
+        let rhs = x + (self.diffusion)(t, x) * dw;
+        self.implicit_drift_step(t + dt, rhs, dt)
+      }
+      SDEScheme::SplitStepBackwardEuler => {
+        let y = self.implicit_drift_step(t + dt, x, dt);
+        y + (self.diffusion)(t, y) * dw
+      }
+    }
+  }
+
+  /// Solve y - f(t, y)dt = rhs with Newton's method
+  fn implicit_drift_step(&self, t: f64, rhs: f64, dt: f64) -> f64 {
+    let mut y = rhs;
+
+    for _ in 0..50 {
+      let a = Derivatives::new(&self.drift, t, y);
+      let residual = y - a.v * dt - rhs;
+      let jacobian = 1.0 - a.v_x * dt;
+
+      if jacobian.abs() < f64::EPSILON {
+        break;
+      }
+
+      let delta = residual / jacobian;
+      y -= delta;
+
+      if delta.abs() <= 1e-12 * y.abs().max(1.0) {
+        break;
+      }
+    }
+
+    y
+  }
+}
+
+impl<F, G> Sampling<f64> for CustomSDE<F, G>
+where
+  F: Fn(f64, f64) -> f64 + Send + Sync,
+  G: Fn(f64, f64) -> f64 + Send + Sync,
+{
+  fn sample(&self) -> Array1<f64> {
+    let u1 = Array1::random(self.n - 1, StandardNormal);
+    let u2 = match self.scheme {
+      SDEScheme::WagnerPlaten => Array1::random(self.n - 1, StandardNormal),
+      _ => Array1::zeros(self.n - 1),
+    };
+
+    self.sample_from_noise(&u1, &u2)
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<F, G> ProcessInfo for CustomSDE<F, G>
+where
+  F: Fn(f64, f64) -> f64 + Send + Sync,
+  G: Fn(f64, f64) -> f64 + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "CustomSDE",
+    title: "Scalar SDE with user defined coefficients",
+    path: "stochastic::diffusion::custom::CustomSDE",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = f(t, X(t)) dt + g(t, X(t)) dW(t)",
+    parameters: &[
+      ParameterInfo::function("drift", "Drift f(t, x)"),
+      ParameterInfo::function("diffusion", "Diffusion coefficient g(t, x)"),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::choice("scheme", "Discretization scheme", &["Euler", "Milstein", "WagnerPlaten", "SRK2", "PredictorCorrector", "DriftImplicitEuler", "SplitStepBackwardEuler"]),
+      ParameterInfo::M,
+      ParameterInfo::function("jumps", "Intensity lambda and size h(t, x) of compound Poisson jumps, set with with_jumps").optional(),
+    ],
+    references: &[
+      "Kloeden, P. E., & Platen, E. (1992). Numerical Solution of Stochastic Differential Equations.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::Rng;
+
+  use crate::{
+    plot_1d,
+    stochastic::{N, X0},
+  };
+
+  use super::*;
+
+  #[test]
+  fn custom_sde_length_equals_n() {
+    let sde = CustomSDE::new(
+      |_, x| 2.0 * (1.0 - x),
+      |_, _| 0.5,
+      N,
+      Some(X0),
+      Some(1.0),
+      SDEScheme::WagnerPlaten,
+      None,
+    );
+    assert_eq!(sde.sample().len(), N);
+  }
+
+  #[test]
+  fn custom_sde_strong_error() {
+    let (mu, sigma, x0, n) = (0.05, 0.8, 1.0, 17);
+    let paths = 2000;
+    let mut errors = [0.0; 3];
+
+    for _ in 0..paths {
+      let u1 = Array1::<f64>::random(n - 1, StandardNormal);
+      let u2 = Array1::<f64>::random(n - 1, StandardNormal);
+      let dt = 1.0 / (n - 1) as f64;
+      let w = u1.sum() * dt.sqrt();
+      let exact = x0 * ((mu - 0.5 * sigma * sigma) + sigma * w).exp();
+
+      for (idx, scheme) in [
+        SDEScheme::Euler,
+        SDEScheme::Milstein,
+        SDEScheme::WagnerPlaten,
+      ]
+      .into_iter()
+      .enumerate()
+      {
+        let gbm = CustomSDE::new(
+          |_, x| mu * x,
+          |_, x| sigma * x,
+          n,
+          Some(x0),
+          Some(1.0),
+          scheme,
+          None,
+        );
+        let path = gbm.sample_from_noise(&u1, &u2);
+        errors[idx] += (path[n - 1] - exact).abs() / paths as f64;
+      }
+    }
+
+    println!("Euler, Milstein, Wagner-Platen strong errors: {:?}", errors);
+    assert!(errors[2] < errors[1] && errors[1] < errors[0]);
+  }
+
+  #[test]
+  fn custom_sde_shared_schemes_match_gbm() {
+    use crate::stochastic::{diffusion::gbm::GBM, variance_reduction::GaussianDriven};
+
+    let (mu, sigma, n) = (0.5, 0.8, 65);
+    let u1 = Array1::<f64>::random(n - 1, StandardNormal);
+    let u2 = Array1::<f64>::zeros(n - 1);
+
+    for scheme in [
+      Scheme::Euler,
+      Scheme::Milstein,
+      Scheme::SRK2,
+      Scheme::PredictorCorrector,
+    ] {
+      let custom = CustomSDE::new(
+        |_, x| mu * x,
+        |_, x| sigma * x,
+        n,
+        Some(1.0),
+        Some(1.0),
+        scheme.into(),
+        None,
+      )
+      .sample_from_noise(&u1, &u2);
+      let gbm = GBM::new(
+        mu,
+        sigma,
+        n,
+        Some(1.0),
+        Some(1.0),
+        Some(scheme),
+        None,
+        None,
+        #[cfg(feature = "malliavin")]
+        None,
+      )
+      .sample_from_increments(u1.view().insert_axis(ndarray::Axis(0)));
+
+      // Up to the finite differences of g'
+      assert!(
+        custom
+          .iter()
+          .zip(&gbm)
+          .all(|(a, b)| (a - b).abs() < 1e-5 * b.abs().max(1.0)),
+        "{:?}",
+        scheme
+      );
+    }
+  }
+
+  #[test]
+  fn custom_sde_implicit_schemes_are_stable() {
+    // Stiff OU and CIR with kappa * dt = 3.125, the explicit Euler amplification factor is |1 - kappa * dt| > 1
+    let (kappa, mu, n) = (50.0, 1.0, 81);
+
+    for (name, sigma) in [("OU", 0.3), ("CIR", 0.5)] {
+      let diffusion = move |_: f64, x: f64| match name {
+        "OU" => sigma,
+        _ => sigma * x.abs().sqrt(),
+      };
+      let u1 = Array1::<f64>::random(n - 1, StandardNormal);
+      let u2 = Array1::<f64>::zeros(n - 1);
+
+      let sample = |scheme| {
+        CustomSDE::new(
+          |_, x| kappa * (mu - x),
+          diffusion,
+          n,
+          Some(2.0),
+          Some(5.0),
+          scheme,
+          None,
+        )
+        .sample_from_noise(&u1, &u2)
+      };
+
+      let explicit = sample(SDEScheme::Euler);
+      assert!(
+        explicit.iter().any(|x| !x.is_finite() || x.abs() > 1e6),
+        "{}",
+        name
+      );
+
+      for scheme in [
+        SDEScheme::DriftImplicitEuler,
+        SDEScheme::SplitStepBackwardEuler,
+      ] {
+        let implicit = sample(scheme);
+        assert!(
+          implicit.iter().all(|x| x.abs() < 10.0),
+          "{} {:?}",
+          name,
+          scheme
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn custom_sde_compound_poisson_jumps() {
+    let (lambda, t, paths) = (5.0, 2.0, 4000);
+    // Pure jump process with unit jumps is a Poisson process
+    let sde = CustomSDE::new(
+      |_, _| 0.0,
+      |_, _| 0.0,
+      1001,
+      Some(0.0),
+      Some(t),
+      SDEScheme::Euler,
+      Some(paths),
+    )
+    .with_jumps(lambda, |_, _| 1.0);
+    let terminal = sde.sample_par().column(1000).to_owned();
+
+    assert!(terminal.iter().all(|x| x.fract() == 0.0));
+    let std_error = (lambda * t / paths as f64).sqrt();
+    assert!((terminal.mean().unwrap() - lambda * t).abs() < 4.0 * std_error);
+    assert!((terminal.var(1.0) / (lambda * t) - 1.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn custom_sde_jump_diffusion_mean() {
+    // GBM with proportional jumps X(t-) J, E[J] = -0.1: E[X(T)] = x0 e^((mu + lambda E[J]) T)
+    let (mu, sigma, lambda) = (0.05, 0.2, 3.0);
+    let sde = CustomSDE::new(
+      move |_, x| mu * x,
+      move |_, x| sigma * x,
+      501,
+      Some(1.0),
+      Some(1.0),
+      SDEScheme::Milstein,
+      Some(20_000),
+    )
+    .with_jumps(lambda, |_, x| {
+      let z: f64 = thread_rng().sample(StandardNormal);
+      x * (-0.1 + 0.05 * z)
+    });
+    let terminal = sde.sample_par().column(500).to_owned();
+    let expected = (mu - 0.1 * lambda).exp();
+
+    assert!((terminal.mean().unwrap() - expected).abs() < 0.02);
+  }
+
+  #[test]
+  fn custom_sde_plot() {
+    let sde = CustomSDE::new(
+      |t, x| (2.0 * std::f64::consts::PI * t).sin() - x,
+      |_, x| 0.3 * (1.0 + x * x).sqrt(),
+      N,
+      Some(X0),
+      Some(5.0),
+      SDEScheme::WagnerPlaten,
+      None,
+    );
+    plot_1d!(sde.sample(), "Custom SDE (Wagner-Platen)");
+  }
+}
@@ -1,51 +1,68 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::scheme::{Coefficients, Scheme},
+    noise::fgn::FGN,
+    FloatExt, Sampling,
+  },
+};
 
 /// Fractional Cox-Ingersoll-Ross (FCIR) process.
 /// dX(t) = theta(mu - X(t))dt + sigma * sqrt(X(t))dW^H(t)
 /// where X(t) is the FCIR process.
-#[derive(ImplNew)]
-pub struct FCIR {
-  pub theta: f64,
-  pub mu: f64,
-  pub sigma: f64,
+#[derive(ImplNew, Clone)]
+pub struct FCIR<T: FloatExt = f64> {
+  pub theta: T,
+  pub mu: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub use_sym: Option<bool>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
-  pub fgn: FGN,
+  pub fgn: FGN<T>,
 }
 
-impl Sampling<f64> for FCIR {
+impl<T: FloatExt> Sampling<T> for FCIR<T> {
   /// Sample the Fractional Cox-Ingersoll-Ross (FCIR) process
-  fn sample(&self) -> Array1<f64> {
-    assert!(
-      2.0 * self.theta * self.mu >= self.sigma.powi(2),
-      "2 * theta * mu < sigma^2"
-    );
+  fn sample(&self) -> Array1<T> {
+    or_panic(self.validate());
 
     let fgn = self.fgn.sample();
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+
+    let mut fcir = Array1::<T>::zeros(self.n);
+    fcir[0] = self.x0.unwrap_or(T::zero());
 
-    let mut fcir = Array1::<f64>::zeros(self.n);
-    fcir[0] = self.x0.unwrap_or(0.0);
+    let scale = dt.powf(self.fgn.hurst);
+    let scheme = self.scheme.unwrap_or_default();
 
     for i in 1..self.n {
-      let dfcir = self.theta * (self.mu - fcir[i - 1]) * dt
-        + self.sigma * (fcir[i - 1]).abs().sqrt() * fgn[i - 1];
+      let next = scheme.step_pathwise(self, fcir[i - 1], dt, fgn[i - 1], scale);
 
       fcir[i] = match self.use_sym.unwrap_or(false) {
-        true => (fcir[i - 1] + dfcir).abs(),
-        false => (fcir[i - 1] + dfcir).max(0.0),
+        true => next.abs(),
+        false => next.max(T::zero()),
       };
     }
 
     fcir
   }
 
+  /// Check the Feller condition
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      T::from_f64_(2.0) * self.theta * self.mu >= self.sigma.powi(2),
+      "2 * theta * mu < sigma^2",
+    )
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -57,6 +74,45 @@ impl Sampling<f64> for FCIR {
   }
 }
 
+impl<T: FloatExt> Coefficients<T> for FCIR<T> {
+  fn drift(&self, x: T) -> T {
+    self.theta * (self.mu - x)
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * x.abs().sqrt()
+  }
+
+  fn milstein(&self, _x: T) -> T {
+    T::from_f64_(0.5) * self.sigma.powi(2)
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for FCIR<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FCIR",
+    title: "Fractional Cox-Ingersoll-Ross process",
+    path: "stochastic::diffusion::fcir::FCIR",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = theta (mu - X(t)) dt + sigma sqrt(X(t)) dW^H(t)",
+    parameters: &[
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("mu", "Long-run mean", Interval::POSITIVE, 0.04),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::X0.example(0.04),
+      ParameterInfo::T,
+      ParameterInfo::boolean("use_sym", "Reflect instead of truncate negative values").optional(),
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Mishura, Y., Piterbarg, V., Ralchenko, K., & Yurchenko-Tytarenko, A. (2018). Stochastic representation and path properties of a fractional Cox-Ingersoll-Ross process.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -76,6 +132,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       Some(false),
+      None,
       Some(1),
       FGN::new(0.7, N, Some(1.0), None),
     );
@@ -93,6 +150,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       Some(false),
+      None,
       Some(1),
       FGN::new(0.7, N, Some(1.0), None),
     );
@@ -110,6 +168,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       Some(false),
+      None,
       Some(1),
       FGN::new(0.7, N, Some(1.0), None),
     );
@@ -1,30 +1,40 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
-
-#[derive(ImplNew)]
-pub struct FGBM {
-  pub mu: f64,
-  pub sigma: f64,
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::scheme::{Coefficients, Scheme},
+  noise::fgn::FGN,
+  FloatExt, Sampling,
+};
+
+#[derive(ImplNew, Clone)]
+pub struct FGBM<T: FloatExt = f64> {
+  pub mu: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
-  pub fgn: FGN,
+  pub fgn: FGN<T>,
 }
 
-impl Sampling<f64> for FGBM {
+impl<T: FloatExt> Sampling<T> for FGBM<T> {
   /// Sample the Fractional Geometric Brownian Motion (FGBM) process
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
     let fgn = self.fgn.sample();
 
-    let mut fgbm = Array1::<f64>::zeros(self.n);
-    fgbm[0] = self.x0.unwrap_or(0.0);
+    let scale = dt.powf(self.fgn.hurst);
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut fgbm = Array1::<T>::zeros(self.n);
+    fgbm[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
-      fgbm[i] = fgbm[i - 1] + self.mu * fgbm[i - 1] * dt + self.sigma * fgbm[i - 1] * fgn[i - 1]
+      fgbm[i] = scheme.step_pathwise(self, fgbm[i - 1], dt, fgn[i - 1], scale);
     }
 
     fgbm
@@ -42,6 +52,43 @@ impl Sampling<f64> for FGBM {
   }
 }
 
+impl<T: FloatExt> Coefficients<T> for FGBM<T> {
+  fn drift(&self, x: T) -> T {
+    self.mu * x
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * x
+  }
+
+  fn milstein(&self, x: T) -> T {
+    self.sigma.powi(2) * x
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for FGBM<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FGBM",
+    title: "Fractional geometric Brownian motion",
+    path: "stochastic::diffusion::fgbm::FGBM",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = mu X(t) dt + sigma X(t) dW^H(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Drift", Interval::REAL, 0.05),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.2),
+      ParameterInfo::N,
+      ParameterInfo::X0.example(100.0),
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Mishura, Y. (2008). Stochastic Calculus for Fractional Brownian Motion and Related Processes.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -60,6 +107,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -75,6 +123,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -90,6 +139,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
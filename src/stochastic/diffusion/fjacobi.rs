@@ -1,49 +1,63 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
-
-#[derive(ImplNew)]
-pub struct FJacobi {
-  pub alpha: f64,
-  pub beta: f64,
-  pub sigma: f64,
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::scheme::{Coefficients, Scheme},
+    noise::fgn::FGN,
+    FloatExt, Sampling,
+  },
+};
+
+#[derive(ImplNew, Clone)]
+pub struct FJacobi<T: FloatExt = f64> {
+  pub alpha: T,
+  pub beta: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
-  pub fgn: FGN,
+  pub fgn: FGN<T>,
 }
 
-impl Sampling<f64> for FJacobi {
+impl<T: FloatExt> Sampling<T> for FJacobi<T> {
   /// Sample the Fractional Jacobi process
-  fn sample(&self) -> Array1<f64> {
-    assert!(self.alpha > 0.0, "alpha must be positive");
-    assert!(self.beta > 0.0, "beta must be positive");
-    assert!(self.sigma > 0.0, "sigma must be positive");
-    assert!(self.alpha < self.beta, "alpha must be less than beta");
+  fn sample(&self) -> Array1<T> {
+    or_panic(self.validate());
 
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
     let fgn = self.fgn.sample();
 
-    let mut fjacobi = Array1::<f64>::zeros(self.n);
-    fjacobi[0] = self.x0.unwrap_or(0.0);
+    let scale = dt.powf(self.fgn.hurst);
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut fjacobi = Array1::<T>::zeros(self.n);
+    fjacobi[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
       fjacobi[i] = match fjacobi[i - 1] {
-        _ if fjacobi[i - 1] <= 0.0 && i > 0 => 0.0,
-        _ if fjacobi[i - 1] >= 1.0 && i > 0 => 1.0,
-        _ => {
-          fjacobi[i - 1]
-            + (self.alpha - self.beta * fjacobi[i - 1]) * dt
-            + self.sigma * (fjacobi[i - 1] * (1.0 - fjacobi[i - 1])).sqrt() * fgn[i - 1]
-        }
+        _ if fjacobi[i - 1] <= T::zero() && i > 0 => T::zero(),
+        _ if fjacobi[i - 1] >= T::one() && i > 0 => T::one(),
+        _ => scheme.step_pathwise(self, fjacobi[i - 1], dt, fgn[i - 1], scale),
       }
     }
 
     fjacobi
   }
 
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.alpha > T::zero(), "alpha must be positive")?;
+    ensure(self.beta > T::zero(), "beta must be positive")?;
+    ensure(self.sigma > T::zero(), "sigma must be positive")?;
+    ensure(self.alpha < self.beta, "alpha must be less than beta")
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -55,6 +69,44 @@ impl Sampling<f64> for FJacobi {
   }
 }
 
+impl<T: FloatExt> Coefficients<T> for FJacobi<T> {
+  fn drift(&self, x: T) -> T {
+    self.alpha - self.beta * x
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * (x * (T::one() - x)).max(T::zero()).sqrt()
+  }
+
+  fn milstein(&self, x: T) -> T {
+    T::from_f64_(0.5) * self.sigma.powi(2) * (T::one() - x - x)
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for FJacobi<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FJacobi",
+    title: "Fractional Jacobi process",
+    path: "stochastic::diffusion::fjacobi::FJacobi",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = (alpha - beta X(t)) dt + sigma sqrt(X(t) (1 - X(t))) dW^H(t)",
+    parameters: &[
+      ParameterInfo::real("alpha", "Drift level, 0 < alpha < beta", Interval::POSITIVE, 0.5),
+      ParameterInfo::real("beta", "Mean reversion rate", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::X0.describe("Initial value in (0, 1), 0 if None").example(0.5),
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Gourieroux, C., & Jasiak, J. (2006). Multivariate Jacobi process with application to smooth transitions.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -74,6 +126,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -90,6 +143,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -106,6 +160,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -1,31 +1,41 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
-
-#[derive(ImplNew)]
-pub struct FOU {
-  pub theta: f64,
-  pub mu: f64,
-  pub sigma: f64,
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::scheme::{Coefficients, Scheme},
+  noise::fgn::FGN,
+  FloatExt, Sampling,
+};
+
+#[derive(ImplNew, Clone)]
+pub struct FOU<T: FloatExt = f64> {
+  pub theta: T,
+  pub mu: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
-  pub fgn: FGN,
+  pub fgn: FGN<T>,
 }
 
-impl Sampling<f64> for FOU {
+impl<T: FloatExt> Sampling<T> for FOU<T> {
   /// Sample the Fractional Ornstein-Uhlenbeck (FOU) process
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
     let fgn = self.fgn.sample();
 
-    let mut fou = Array1::<f64>::zeros(self.n);
-    fou[0] = self.x0.unwrap_or(0.0);
+    let scale = dt.powf(self.fgn.hurst);
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut fou = Array1::<T>::zeros(self.n);
+    fou[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
-      fou[i] = fou[i - 1] + self.theta * (self.mu - fou[i - 1]) * dt + self.sigma * fgn[i - 1]
+      fou[i] = scheme.step_pathwise(self, fou[i - 1], dt, fgn[i - 1], scale);
     }
 
     fou
@@ -42,6 +52,44 @@ impl Sampling<f64> for FOU {
   }
 }
 
+impl<T: FloatExt> Coefficients<T> for FOU<T> {
+  fn drift(&self, x: T) -> T {
+    self.theta * (self.mu - x)
+  }
+
+  fn diffusion(&self, _x: T) -> T {
+    self.sigma
+  }
+
+  fn milstein(&self, _x: T) -> T {
+    T::zero()
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for FOU<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FOU",
+    title: "Fractional Ornstein-Uhlenbeck process",
+    path: "stochastic::diffusion::fou::FOU",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = theta (mu - X(t)) dt + sigma dW^H(t)",
+    parameters: &[
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("mu", "Long-run mean", Interval::REAL, 0.0),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Cheridito, P., Kawaguchi, H., & Maejima, M. (2003). Fractional Ornstein-Uhlenbeck processes.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -61,6 +109,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -77,6 +126,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
@@ -93,6 +143,7 @@ mod tests {
       Some(X0),
       Some(1.0),
       None,
+      None,
       FGN::new(0.7, N - 1, Some(1.0), None),
     );
 
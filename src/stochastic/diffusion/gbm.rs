@@ -2,44 +2,62 @@
 use std::sync::Mutex;
 
 use impl_new_derive::ImplNew;
-use ndarray::Array1;
-use ndarray_rand::RandomExt;
+use ndarray::{Array1, ArrayView2, Axis};
 use num_complex::Complex64;
-use rand_distr::Normal;
 use statrs::{
   distribution::{Continuous, ContinuousCDF, LogNormal},
   statistics::{Distribution as StatDistribution, Median, Mode},
 };
 
-use crate::stochastic::{Distribution, Sampling};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::scheme::{Coefficients, Scheme},
+  variance_reduction::GaussianDriven,
+  Distribution, FloatExt, Measure, MeasureChange, RiskPremia, Sampling,
+};
 
 #[derive(ImplNew)]
-pub struct GBM {
-  pub mu: f64,
-  pub sigma: f64,
+pub struct GBM<T: FloatExt = f64> {
+  pub mu: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
   pub distribution: Option<LogNormal>,
   #[cfg(feature = "malliavin")]
   pub calculate_malliavin: Option<bool>,
   #[cfg(feature = "malliavin")]
-  malliavin: Mutex<Option<Array1<f64>>>,
+  malliavin: Mutex<Option<Array1<T>>>,
 }
 
-impl Sampling<f64> for GBM {
-  /// Sample the GBM process
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
-
-    let mut gbm = Array1::<f64>::zeros(self.n);
-    gbm[0] = self.x0.unwrap_or(0.0);
-
-    for i in 1..self.n {
-      gbm[i] = gbm[i - 1] + self.mu * gbm[i - 1] * dt + self.sigma * gbm[i - 1] * gn[i - 1]
+impl<T: FloatExt> Clone for GBM<T> {
+  /// Copy of the parameters with an empty Malliavin derivative
+  fn clone(&self) -> Self {
+    Self {
+      mu: self.mu,
+      sigma: self.sigma,
+      n: self.n,
+      x0: self.x0,
+      t: self.t,
+      scheme: self.scheme,
+      m: self.m,
+      distribution: self.distribution,
+      #[cfg(feature = "malliavin")]
+      calculate_malliavin: self.calculate_malliavin,
+      #[cfg(feature = "malliavin")]
+      malliavin: Mutex::new(None),
     }
+  }
+}
+
+impl<T: FloatExt> Sampling<T> for GBM<T> {
+  /// Sample the GBM process
+  fn sample(&self) -> Array1<T> {
+    let z = T::normal_array(self.n - 1, T::zero(), T::one());
+    let gbm = self.sample_from_increments(z.view().insert_axis(Axis(0)));
 
     #[cfg(feature = "malliavin")]
     if self.calculate_malliavin.is_some() && self.calculate_malliavin.unwrap() {
@@ -47,12 +65,12 @@ impl Sampling<f64> for GBM {
 
       // reverse due the option pricing
       for i in 0..self.n {
-        malliavin[i] = self.sigma * gbm.last().unwrap();
+        malliavin[i] = self.sigma * *gbm.last().unwrap();
       }
 
       // This equivalent to the following:
       // self.malliavin.lock().unwrap().replace(Some(malliavin));
-      let _ = std::mem::replace(&mut *self.malliavin.lock().unwrap(), Some(malliavin));
+      *self.malliavin.lock().unwrap() = Some(malliavin);
     }
 
     gbm
@@ -69,12 +87,14 @@ impl Sampling<f64> for GBM {
   }
 
   /// Distribution of the GBM process
+  ///
+  /// ln S_T ~ N(ln S_0 + (mu - sigma^2 / 2) T, sigma^2 T)
   fn distribution(&mut self) {
-    let mu = self.x0.unwrap() * (self.mu * self.t.unwrap()).exp();
-    let sigma = (self.x0.unwrap().powi(2)
-      * (2.0 * self.mu * self.t.unwrap()).exp()
-      * ((self.sigma.powi(2) * self.t.unwrap()).exp() - 1.0))
-      .sqrt();
+    let t = self.t.map_or(1.0, Into::into);
+    let (x0, mu, sigma): (f64, f64, f64) =
+      (self.x0.unwrap().into(), self.mu.into(), self.sigma.into());
+    let mu = x0.ln() + (mu - 0.5 * sigma.powi(2)) * t;
+    let sigma = sigma * t.sqrt();
 
     self.distribution = Some(LogNormal::new(mu, sigma).unwrap());
   }
@@ -86,11 +106,77 @@ impl Sampling<f64> for GBM {
   ///
   /// The Malliavin derivate of the GBM shows the sensitivity of the stock price with respect to the Wiener process.
   #[cfg(feature = "malliavin")]
-  fn malliavin(&self) -> Array1<f64> {
+  fn malliavin(&self) -> Array1<T> {
     self.malliavin.lock().unwrap().as_ref().unwrap().clone()
   }
 }
 
+impl<T: FloatExt> GaussianDriven<T> for GBM<T> {
+  type Path = Array1<T>;
+
+  fn increments(&self) -> usize {
+    self.n - 1
+  }
+
+  /// Discretization scheme with the Brownian increments sqrt(dt) z
+  fn sample_from_increments(&self, z: ArrayView2<T>) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let sqrt_dt = dt.sqrt();
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut gbm = Array1::<T>::zeros(self.n);
+    gbm[0] = self.x0.unwrap_or(T::zero());
+
+    for i in 1..self.n {
+      gbm[i] = scheme.step(self, gbm[i - 1], dt, sqrt_dt * z[[0, i - 1]]);
+    }
+
+    gbm
+  }
+}
+
+impl<T: FloatExt> Coefficients<T> for GBM<T> {
+  fn drift(&self, x: T) -> T {
+    self.mu * x
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * x
+  }
+
+  fn milstein(&self, x: T) -> T {
+    self.sigma * self.sigma * x
+  }
+}
+
+impl<T: FloatExt> MeasureChange for GBM<T> {
+  /// (mu - (r - q)) x, the equity risk premium
+  fn drift_adjustment(&self, x: f64, premia: &RiskPremia) -> f64 {
+    (self.mu.into() - (premia.r - premia.q)) * x
+  }
+
+  /// Under Q the drift mu is replaced by r - q
+  fn under_measure(&self, measure: Measure, premia: &RiskPremia) -> Self {
+    let mu = match measure {
+      Measure::RealWorld => self.mu,
+      Measure::RiskNeutral => T::from_f64_(premia.r - premia.q),
+    };
+
+    Self::new(
+      mu,
+      self.sigma,
+      self.n,
+      self.x0,
+      self.t,
+      self.scheme,
+      self.m,
+      self.distribution,
+      #[cfg(feature = "malliavin")]
+      self.calculate_malliavin,
+    )
+  }
+}
+
 impl Distribution for GBM {
   /// Characteristic function of the distribution
   fn characteristic_function(&self, _t: f64) -> Complex64 {
@@ -178,6 +264,33 @@ impl Distribution for GBM {
   }
 }
 
+impl<T: FloatExt> ProcessInfo for GBM<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "GBM",
+    title: "Geometric Brownian motion",
+    path: "stochastic::diffusion::gbm::GBM",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = mu X(t) dt + sigma X(t) dW(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Drift", Interval::REAL, 0.05),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.2),
+      ParameterInfo::N,
+      ParameterInfo::X0.example(100.0),
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+      ParameterInfo::component(
+        "distribution",
+        "Log-normal marginal, filled in by the sampler",
+      )
+      .optional(),
+    ],
+    references: &[
+      "Black, F., & Scholes, M. (1973). The pricing of options and corporate liabilities.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   #[cfg(feature = "malliavin")]
@@ -199,6 +312,7 @@ mod tests {
       Some(1.0),
       None,
       None,
+      None,
       #[cfg(feature = "malliavin")]
       None,
     );
@@ -215,12 +329,42 @@ mod tests {
       Some(1.0),
       None,
       None,
+      None,
       #[cfg(feature = "malliavin")]
       None,
     );
     assert_eq!(gbm.sample()[0], X0);
   }
 
+  #[test]
+  fn gbm_risk_neutral_drift() {
+    let premia = RiskPremia {
+      r: 0.03,
+      q: 0.01,
+      lambda: 0.0,
+    };
+    let gbm = GBM::new(
+      0.1,
+      0.2,
+      101,
+      Some(100.0),
+      Some(1.0),
+      None,
+      Some(1000),
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let q = gbm.under_measure(Measure::RiskNeutral, &premia);
+
+    assert!((q.mu - 0.02_f64).abs() < 1e-12);
+    assert!((gbm.drift_adjustment(100.0, &premia) - 8.0).abs() < 1e-12);
+
+    // E_Q[S_T] = S_0 e^((r - q) T)
+    let terminal = q.sample_par().column(100).mean().unwrap();
+    assert!((terminal - 100.0 * 0.02_f64.exp()).abs() < 2.0);
+  }
+
   #[test]
   fn gbm_plot() {
     let gbm = GBM::new(
@@ -231,6 +375,7 @@ mod tests {
       Some(1.0),
       None,
       None,
+      None,
       #[cfg(feature = "malliavin")]
       None,
     );
@@ -240,7 +385,17 @@ mod tests {
   #[test]
   #[cfg(feature = "malliavin")]
   fn gbm_malliavin() {
-    let gbm = GBM::new(0.25, 0.5, N, Some(X0), Some(1.0), None, None, Some(true));
+    let gbm = GBM::new(
+      0.25,
+      0.5,
+      N,
+      Some(X0),
+      Some(1.0),
+      None,
+      None,
+      None,
+      Some(true),
+    );
     let process = gbm.sample();
     let malliavin = gbm.malliavin();
     plot_2d!(
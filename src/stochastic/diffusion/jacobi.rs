@@ -1,50 +1,60 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
 
-use crate::stochastic::Sampling;
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::scheme::{Coefficients, Scheme},
+    FloatExt, Sampling,
+  },
+};
 
-#[derive(ImplNew)]
-pub struct Jacobi {
-  pub alpha: f64,
-  pub beta: f64,
-  pub sigma: f64,
+#[derive(ImplNew, Clone)]
+pub struct Jacobi<T: FloatExt = f64> {
+  pub alpha: T,
+  pub beta: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for Jacobi {
+impl<T: FloatExt> Sampling<T> for Jacobi<T> {
   /// Sample the Jacobi process
-  fn sample(&self) -> Array1<f64> {
-    assert!(self.alpha > 0.0, "alpha must be positive");
-    assert!(self.beta > 0.0, "beta must be positive");
-    assert!(self.sigma > 0.0, "sigma must be positive");
-    assert!(self.alpha < self.beta, "alpha must be less than beta");
+  fn sample(&self) -> Array1<T> {
+    or_panic(self.validate());
 
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
 
-    let mut jacobi = Array1::<f64>::zeros(self.n);
-    jacobi[0] = self.x0.unwrap_or(0.0);
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut jacobi = Array1::<T>::zeros(self.n);
+    jacobi[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
       jacobi[i] = match jacobi[i - 1] {
-        _ if jacobi[i - 1] <= 0.0 && i > 0 => 0.0,
-        _ if jacobi[i - 1] >= 1.0 && i > 0 => 1.0,
-        _ => {
-          jacobi[i - 1]
-            + (self.alpha - self.beta * jacobi[i - 1]) * dt
-            + self.sigma * (jacobi[i - 1] * (1.0 - jacobi[i - 1])).sqrt() * gn[i - 1]
-        }
+        _ if jacobi[i - 1] <= T::zero() && i > 0 => T::zero(),
+        _ if jacobi[i - 1] >= T::one() && i > 0 => T::one(),
+        _ => scheme.step(self, jacobi[i - 1], dt, gn[i - 1]),
       }
     }
 
     jacobi
   }
 
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.alpha > T::zero(), "alpha must be positive")?;
+    ensure(self.beta > T::zero(), "beta must be positive")?;
+    ensure(self.sigma > T::zero(), "sigma must be positive")?;
+    ensure(self.alpha < self.beta, "alpha must be less than beta")
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -56,6 +66,43 @@ impl Sampling<f64> for Jacobi {
   }
 }
 
+impl<T: FloatExt> Coefficients<T> for Jacobi<T> {
+  fn drift(&self, x: T) -> T {
+    self.alpha - self.beta * x
+  }
+
+  fn diffusion(&self, x: T) -> T {
+    self.sigma * (x * (T::one() - x)).max(T::zero()).sqrt()
+  }
+
+  fn milstein(&self, x: T) -> T {
+    T::from_f64_(0.5) * self.sigma.powi(2) * (T::one() - x - x)
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for Jacobi<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Jacobi",
+    title: "Jacobi process",
+    path: "stochastic::diffusion::jacobi::Jacobi",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = (alpha - beta X(t)) dt + sigma sqrt(X(t) (1 - X(t))) dW(t)",
+    parameters: &[
+      ParameterInfo::real("alpha", "Drift level, 0 < alpha < beta", Interval::POSITIVE, 0.5),
+      ParameterInfo::real("beta", "Mean reversion rate", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::X0.describe("Initial value in (0, 1), 0 if None").example(0.5),
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Gourieroux, C., & Jasiak, J. (2006). Multivariate Jacobi process with application to smooth transitions.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -67,19 +114,19 @@ mod tests {
 
   #[test]
   fn fjacobi_length_equals_n() {
-    let jacobi = Jacobi::new(0.43, 0.5, 0.8, N, Some(X0), Some(1.0), None);
+    let jacobi = Jacobi::new(0.43, 0.5, 0.8, N, Some(X0), Some(1.0), None, None);
     assert_eq!(jacobi.sample().len(), N);
   }
 
   #[test]
   fn jacobi_starts_with_x0() {
-    let jacobi = Jacobi::new(0.43, 0.5, 0.8, N, Some(X0), Some(1.0), None);
+    let jacobi = Jacobi::new(0.43, 0.5, 0.8, N, Some(X0), Some(1.0), None, None);
     assert_eq!(jacobi.sample()[0], X0);
   }
 
   #[test]
   fn jacobi_plot() {
-    let jacobi = Jacobi::new(0.43, 0.5, 0.8, N, Some(X0), Some(1.0), None);
+    let jacobi = Jacobi::new(0.43, 0.5, 0.8, N, Some(X0), Some(1.0), None, None);
     plot_1d!(jacobi.sample(), "Jacobi process");
   }
 
@@ -0,0 +1,380 @@
+//! Ornstein-Uhlenbeck processes driven by Lévy subordinators (Barndorff-Nielsen & Shephard).
+//!
+//! dY(t) = -lambda Y(t) dt + dZ(lambda t)
+//!
+//! The background driving Lévy process (BDLP) Z is increasing, so Y stays positive and
+//! jumps up, and the time change lambda t keeps the stationary distribution of Y
+//! independent of lambda. The step over dt is
+//!
+//! Y(t + dt) = e^(-lambda dt) Y(t) + int_0^(lambda dt) e^(-(lambda dt - s)) dZ(s)
+//!
+//! and the stochastic integral has the Lévy density u(x) - e^a u(x e^a), a = lambda dt,
+//! with the Lévy density u of the stationary distribution. It is sampled exactly:
+//!
+//! - gamma-OU, stationary Gamma(nu, alpha): Z is compound Poisson with the rate nu and
+//!   Exp(alpha) jumps, the integral discounts every jump from its uniform time,
+//! - IG-OU, stationary IG(delta, gamma): the integral is IG(delta (1 - e^(-a/2)), gamma) plus
+//!   Poisson(delta gamma (1 - e^(-a/2))) jumps of the density proportional to
+//!   x^(-3/2) (e^(-gamma^2 x / 2) - e^(-gamma^2 e^a x / 2)), a Gamma(1/2, r) mixture over
+//!   r in [gamma^2 / 2, gamma^2 e^a / 2] with the density proportional to r^(-1/2).
+//!
+//! - Barndorff-Nielsen, O. E., & Shephard, N. (2001). Non-Gaussian Ornstein-Uhlenbeck-based models and some of their uses in financial economics.
+//! - Zhang, S., & Zhang, X. (2008). Exact simulation of IG-OU processes.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use rand::{thread_rng, Rng};
+use rand_distr::{Distribution, Exp1, Gamma, InverseGaussian, Poisson, StandardNormal};
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    Sampling,
+  },
+};
+
+/// OU process driven by a Lévy subordinator, the variance of the Barndorff-Nielsen &
+/// Shephard model
+pub trait LevyOU: Send + Sync {
+  /// Mean reversion rate lambda
+  fn lambda(&self) -> f64;
+
+  /// Initial value, x0 or a draw of the stationary distribution if None
+  fn initial(&self) -> f64;
+
+  /// Value after dt from y and the increment of Z(lambda t) over the step
+  fn step<R: Rng>(&self, y: f64, dt: f64, rng: &mut R) -> (f64, f64);
+
+  /// Cumulant generating function ln E[e^(theta Z(1))] of the BDLP
+  fn bdlp_cumulant(&self, theta: f64) -> f64;
+}
+
+/// Poisson count, 0 for the rates below the machine epsilon
+fn poisson<R: Rng>(rate: f64, rng: &mut R) -> usize {
+  match rate > f64::EPSILON {
+    true => Poisson::new(rate).unwrap().sample(rng) as usize,
+    false => 0,
+  }
+}
+
+/// Path of a Lévy-driven OU process with the given step
+fn sample_path(
+  n: usize,
+  t: Option<f64>,
+  y0: f64,
+  mut step: impl FnMut(f64, f64) -> f64,
+) -> Array1<f64> {
+  let dt = t.unwrap_or(1.0) / (n - 1) as f64;
+  let mut y = Array1::<f64>::zeros(n);
+  y[0] = y0;
+
+  for i in 1..n {
+    y[i] = step(y[i - 1], dt);
+  }
+
+  y
+}
+
+/// Gamma-OU process with the stationary Gamma(nu, alpha) distribution
+#[derive(ImplNew, Clone)]
+pub struct GammaOU {
+  /// Mean reversion rate
+  pub lambda: f64,
+  /// Shape of the stationary distribution, the rate of the jumps of Z
+  pub nu: f64,
+  /// Rate of the stationary distribution and of the exponential jumps of Z
+  pub alpha: f64,
+  pub n: usize,
+  /// Initial value, drawn from the stationary distribution if None
+  pub x0: Option<f64>,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl LevyOU for GammaOU {
+  fn lambda(&self) -> f64 {
+    self.lambda
+  }
+
+  fn initial(&self) -> f64 {
+    self.x0.unwrap_or_else(|| {
+      Gamma::new(self.nu, 1.0 / self.alpha)
+        .unwrap()
+        .sample(&mut thread_rng())
+    })
+  }
+
+  /// Exact, the jumps of Z are discounted from their uniform times
+  fn step<R: Rng>(&self, y: f64, dt: f64, rng: &mut R) -> (f64, f64) {
+    let a = self.lambda * dt;
+    let (mut y, mut dz) = ((-a).exp() * y, 0.0);
+
+    for _ in 0..poisson(self.nu * a, rng) {
+      let jump = rng.sample::<f64, _>(Exp1) / self.alpha;
+      y += (-a * rng.gen::<f64>()).exp() * jump;
+      dz += jump;
+    }
+
+    (y, dz)
+  }
+
+  /// nu theta / (alpha - theta) for theta < alpha
+  fn bdlp_cumulant(&self, theta: f64) -> f64 {
+    self.nu * theta / (self.alpha - theta)
+  }
+}
+
+impl Sampling<f64> for GammaOU {
+  /// Sample the gamma-OU process
+  fn sample(&self) -> Array1<f64> {
+    or_panic(self.validate());
+    let mut rng = thread_rng();
+    sample_path(self.n, self.t, self.initial(), |y, dt| {
+      self.step(y, dt, &mut rng).0
+    })
+  }
+
+  /// Check the parameters
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.lambda > 0.0 && self.nu > 0.0 && self.alpha > 0.0,
+      "lambda, nu and alpha must be positive",
+    )?;
+    ensure(self.n >= 2, "n must be at least 2")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for GammaOU {
+  const INFO: ModelInfo = ModelInfo {
+    name: "GammaOU",
+    title: "Gamma Ornstein-Uhlenbeck process",
+    path: "stochastic::diffusion::levy_ou::GammaOU",
+    kind: ModelKind::Diffusion,
+    description: "dY(t) = -lambda Y(t) dt + dZ(lambda t) with the stationary Gamma(nu, alpha) distribution",
+    parameters: &[
+      ParameterInfo::real("lambda", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("nu", "Shape of the stationary distribution", Interval::POSITIVE, 1.5),
+      ParameterInfo::real("alpha", "Rate of the stationary distribution", Interval::POSITIVE, 30.0),
+      ParameterInfo::N,
+      ParameterInfo::X0.describe("Initial value, stationary if None").example(0.05),
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Barndorff-Nielsen, O. E., & Shephard, N. (2001). Non-Gaussian Ornstein-Uhlenbeck-based models and some of their uses in financial economics.",
+    ],
+  };
+}
+
+/// IG-OU process with the stationary inverse Gaussian IG(delta, gamma) distribution, of
+/// mean delta / gamma and variance delta / gamma^3
+#[derive(ImplNew, Clone)]
+pub struct IGOU {
+  /// Mean reversion rate
+  pub lambda: f64,
+  pub delta: f64,
+  pub gamma: f64,
+  pub n: usize,
+  /// Initial value, drawn from the stationary distribution if None
+  pub x0: Option<f64>,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl IGOU {
+  /// Draw of IG(delta, gamma)
+  fn inverse_gaussian<R: Rng>(delta: f64, gamma: f64, rng: &mut R) -> f64 {
+    InverseGaussian::new(delta / gamma, delta.powi(2))
+      .unwrap()
+      .sample(rng)
+  }
+
+  /// Exact step of the process
+  fn exact_step<R: Rng>(&self, y: f64, dt: f64, rng: &mut R) -> f64 {
+    let a = self.lambda * dt;
+    let delta = self.delta * (1.0 - (-0.5 * a).exp());
+    let mut y = (-a).exp() * y + Self::inverse_gaussian(delta, self.gamma, rng);
+
+    let (p, q) = (0.5 * self.gamma.powi(2), 0.5 * self.gamma.powi(2) * a.exp());
+    for _ in 0..poisson(delta * self.gamma, rng) {
+      let r = (p.sqrt() + rng.gen::<f64>() * (q.sqrt() - p.sqrt())).powi(2);
+      y += rng.sample::<f64, _>(StandardNormal).powi(2) / (2.0 * r);
+    }
+
+    y
+  }
+}
+
+impl LevyOU for IGOU {
+  fn lambda(&self) -> f64 {
+    self.lambda
+  }
+
+  fn initial(&self) -> f64 {
+    self
+      .x0
+      .unwrap_or_else(|| Self::inverse_gaussian(self.delta, self.gamma, &mut thread_rng()))
+  }
+
+  /// Z is an IG(delta / 2, gamma) process plus compound Poisson jumps of the rate
+  /// delta gamma / 2 and the size chi^2(1) / gamma^2. The jumps are discounted from their
+  /// uniform times, the increment of the IG part is discounted from one uniform time of
+  /// the step, which keeps the mean and is exact as dt -> 0.
+  fn step<R: Rng>(&self, y: f64, dt: f64, rng: &mut R) -> (f64, f64) {
+    let a = self.lambda * dt;
+    let ig = Self::inverse_gaussian(0.5 * self.delta * a, self.gamma, rng);
+    let (mut y, mut dz) = ((-a).exp() * y + (-a * rng.gen::<f64>()).exp() * ig, ig);
+
+    for _ in 0..poisson(0.5 * self.delta * self.gamma * a, rng) {
+      let jump = rng.sample::<f64, _>(StandardNormal).powi(2) / self.gamma.powi(2);
+      y += (-a * rng.gen::<f64>()).exp() * jump;
+      dz += jump;
+    }
+
+    (y, dz)
+  }
+
+  /// delta theta / sqrt(gamma^2 - 2 theta) for theta < gamma^2 / 2
+  fn bdlp_cumulant(&self, theta: f64) -> f64 {
+    self.delta * theta / (self.gamma.powi(2) - 2.0 * theta).sqrt()
+  }
+}
+
+impl Sampling<f64> for IGOU {
+  /// Sample the IG-OU process exactly
+  fn sample(&self) -> Array1<f64> {
+    or_panic(self.validate());
+    let mut rng = thread_rng();
+    sample_path(self.n, self.t, self.initial(), |y, dt| {
+      self.exact_step(y, dt, &mut rng)
+    })
+  }
+
+  /// Check the parameters
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.lambda > 0.0 && self.delta > 0.0 && self.gamma > 0.0,
+      "lambda, delta and gamma must be positive",
+    )?;
+    ensure(self.n >= 2, "n must be at least 2")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for IGOU {
+  const INFO: ModelInfo = ModelInfo {
+    name: "IGOU",
+    title: "Inverse Gaussian Ornstein-Uhlenbeck process",
+    path: "stochastic::diffusion::levy_ou::IGOU",
+    kind: ModelKind::Diffusion,
+    description: "dY(t) = -lambda Y(t) dt + dZ(lambda t) with the stationary IG(delta, gamma) distribution",
+    parameters: &[
+      ParameterInfo::real("lambda", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("delta", "Scale of the stationary distribution", Interval::POSITIVE, 0.5),
+      ParameterInfo::real("gamma", "Tail of the stationary distribution", Interval::POSITIVE, 2.5),
+      ParameterInfo::N,
+      ParameterInfo::X0.describe("Initial value, stationary if None").example(0.2),
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Barndorff-Nielsen, O. E., & Shephard, N. (2001). Non-Gaussian Ornstein-Uhlenbeck-based models and some of their uses in financial economics.",
+      "Zhang, S., & Zhang, X. (2008). Exact simulation of IG-OU processes.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Mean and variance of the last points of m paths
+  fn terminal_moments(sampler: &impl Sampling<f64>, m: usize) -> (f64, f64) {
+    let last = (0..m)
+      .map(|_| *sampler.sample().last().unwrap())
+      .collect::<Vec<_>>();
+    let mean = last.iter().sum::<f64>() / m as f64;
+    let variance = last.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / m as f64;
+    (mean, variance)
+  }
+
+  #[test]
+  fn levy_ou_paths_start_at_x0_and_stay_positive() {
+    let gamma = GammaOU::new(2.0, 1.5, 30.0, 500, Some(0.05), Some(1.0), None).sample();
+    let ig = IGOU::new(2.0, 0.5, 2.5, 500, Some(0.2), Some(1.0), None).sample();
+
+    for (path, x0) in [(gamma, 0.05), (ig, 0.2)] {
+      assert_eq!(path.len(), 500);
+      assert_eq!(path[0], x0);
+      assert!(path.iter().all(|&y| y > 0.0));
+    }
+  }
+
+  #[test]
+  fn gamma_ou_keeps_the_stationary_distribution() {
+    let (nu, alpha) = (1.5, 30.0);
+    let process = GammaOU::new(2.0, nu, alpha, 3, None, Some(1.0), None);
+    let (mean, variance) = terminal_moments(&process, 50_000);
+
+    // Gamma(nu, alpha) with the standard errors of about 1.5e-4 and 1.4e-5
+    assert!((mean - nu / alpha).abs() < 8e-4, "{}", mean);
+    assert!((variance - nu / alpha.powi(2)).abs() < 1e-4, "{}", variance);
+  }
+
+  #[test]
+  fn ig_ou_keeps_the_stationary_distribution() {
+    let (delta, gamma) = (0.5, 2.5);
+    let process = IGOU::new(2.0, delta, gamma, 3, None, Some(1.0), None);
+    let (mean, variance) = terminal_moments(&process, 50_000);
+
+    // IG(delta, gamma) with the standard errors of about 8e-4 and 1e-3
+    assert!((mean - delta / gamma).abs() < 4e-3, "{}", mean);
+    assert!(
+      (variance - delta / gamma.powi(3)).abs() < 5e-3,
+      "{}",
+      variance
+    );
+  }
+
+  #[test]
+  fn levy_ou_mean_reverts_from_x0() {
+    let (lambda, t): (f64, f64) = (3.0, 0.5);
+    let decay = (-lambda * t).exp();
+    let gamma = GammaOU::new(lambda, 1.5, 30.0, 11, Some(0.2), Some(t), None);
+    let ig = IGOU::new(lambda, 0.5, 2.5, 11, Some(0.01), Some(t), None);
+
+    // E[Y(t)] = x0 e^(-lambda t) + E[Y] (1 - e^(-lambda t))
+    let (mean, _) = terminal_moments(&gamma, 20_000);
+    assert!(
+      (mean - (0.2 * decay + 0.05 * (1.0 - decay))).abs() < 1.5e-3,
+      "{}",
+      mean
+    );
+    let (mean, _) = terminal_moments(&ig, 20_000);
+    assert!(
+      (mean - (0.01 * decay + 0.2 * (1.0 - decay))).abs() < 6e-3,
+      "{}",
+      mean
+    );
+  }
+}
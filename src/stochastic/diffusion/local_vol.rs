@@ -0,0 +1,194 @@
+use impl_new_derive::ImplNew;
+use ndarray::{Array1, ArrayView2, Axis};
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::volatility::dupire::DupireLocalVol,
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::scheme::{Coefficients, Scheme},
+    variance_reduction::GaussianDriven,
+    FloatExt, Sampling,
+  },
+};
+
+/// Spot under a fitted local volatility surface
+///
+/// dS(t) = (r - q) S(t) dt + sigma_loc(t, S(t)) S(t) dW(t)
+///
+/// The rates are the ones of the surface, so the simulated calls reprice the quotes the
+/// surface was built from up to the discretization and interpolation error.
+#[derive(ImplNew, Clone)]
+pub struct LocalVolProcess {
+  pub surface: DupireLocalVol,
+  pub n: usize,
+  /// Initial spot, the spot of the surface if None
+  pub x0: Option<f64>,
+  pub t: Option<f64>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
+  pub m: Option<usize>,
+}
+
+/// Coefficients of the local volatility diffusion frozen at the start of a step
+struct Frozen<'a> {
+  process: &'a LocalVolProcess,
+  t: f64,
+}
+
+impl Coefficients<f64> for Frozen<'_> {
+  fn drift(&self, x: f64) -> f64 {
+    let surface = &self.process.surface;
+    (surface.r - surface.q) * x
+  }
+
+  fn diffusion(&self, x: f64) -> f64 {
+    self.process.surface.sigma_loc(self.t, x) * x
+  }
+
+  /// b b' with the derivative of the interpolated surface by central differences
+  fn milstein(&self, x: f64) -> f64 {
+    let h = 1e-4 * x.abs().max(1.0);
+    let b_x = (self.diffusion(x + h) - self.diffusion(x - h)) / (2.0 * h);
+    self.diffusion(x) * b_x
+  }
+}
+
+impl Sampling<f64> for LocalVolProcess {
+  /// Sample the spot under the local volatility surface
+  fn sample(&self) -> Array1<f64> {
+    let z = f64::normal_array(self.n - 1, 0.0, 1.0);
+    self.sample_from_increments(z.view().insert_axis(Axis(0)))
+  }
+
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.n >= 2, "n must be at least 2")?;
+    ensure(
+      self.x0.unwrap_or(self.surface.s) > 0.0,
+      "x0 must be positive",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl GaussianDriven<f64> for LocalVolProcess {
+  type Path = Array1<f64>;
+
+  fn increments(&self) -> usize {
+    self.n - 1
+  }
+
+  /// Discretization scheme with the Brownian increments sqrt(dt) z, the local volatility
+  /// is taken at the start of every step
+  fn sample_from_increments(&self, z: ArrayView2<f64>) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let sqrt_dt = dt.sqrt();
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut s = Array1::<f64>::zeros(self.n);
+    s[0] = self.x0.unwrap_or(self.surface.s);
+
+    for i in 1..self.n {
+      let frozen = Frozen {
+        process: self,
+        t: (i - 1) as f64 * dt,
+      };
+      s[i] = scheme.step(&frozen, s[i - 1], dt, sqrt_dt * z[[0, i - 1]]);
+    }
+
+    s
+  }
+}
+
+impl ProcessInfo for LocalVolProcess {
+  const INFO: ModelInfo = ModelInfo {
+    name: "LocalVolProcess",
+    title: "Local volatility process",
+    path: "stochastic::diffusion::local_vol::LocalVolProcess",
+    kind: ModelKind::Diffusion,
+    description: "dS(t) = (r - q) S(t) dt + sigma_loc(t, S(t)) S(t) dW(t)",
+    parameters: &[
+      ParameterInfo::component("surface", "Dupire local volatility surface"),
+      ParameterInfo::N,
+      ParameterInfo::X0
+        .describe("Initial spot, the spot of the surface if None")
+        .example(100.0),
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Dupire, B. (1994). Pricing with a smile.",
+      "Derman, E., & Kani, I. (1994). Riding on a smile.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::{Array, Array2};
+  use statrs::distribution::{ContinuousCDF, Normal};
+
+  use super::*;
+  use crate::quant::volatility::dupire::DupireSettings;
+
+  fn surface(smile: impl Fn(f64) -> f64) -> DupireLocalVol {
+    let strikes = Array::linspace(40.0, 200.0, 41);
+    let maturities = Array1::from_vec(vec![0.25, 0.5, 0.75, 1.0]);
+    let vols = Array2::from_shape_fn((maturities.len(), strikes.len()), |(_, j)| {
+      smile(strikes[j])
+    });
+
+    DupireLocalVol::from_implied_vols(
+      100.0,
+      0.03,
+      0.0,
+      strikes,
+      maturities,
+      &vols,
+      DupireSettings::default(),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn local_vol_length_equals_n() {
+    let process = LocalVolProcess::new(surface(|_| 0.2), 100, None, Some(1.0), None, None);
+    let path = process.sample();
+
+    assert_eq!(path.len(), 100);
+    assert_eq!(path[0], 100.0);
+  }
+
+  #[test]
+  fn local_vol_reprices_flat_smile() {
+    let process = LocalVolProcess::new(
+      surface(|_| 0.2),
+      101,
+      None,
+      Some(1.0),
+      Some(Scheme::Milstein),
+      Some(20_000),
+    );
+    let terminal = process.sample_par().column(100).to_owned();
+
+    // Black-Scholes call with the same volatility
+    let (s, k, r, v): (f64, f64, f64, f64) = (100.0, 100.0, 0.03, 0.2);
+    let d1 = (s / k).ln() / v + (r / v + 0.5 * v);
+    let n = Normal::default();
+    let bs = s * n.cdf(d1) - k * (-r).exp() * n.cdf(d1 - v);
+    let mc = terminal.mapv(|x| (x - k).max(0.0)).mean().unwrap() * (-r).exp();
+
+    assert!((mc - bs).abs() < 0.35, "{} {}", mc, bs);
+  }
+}
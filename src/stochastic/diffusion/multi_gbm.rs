@@ -0,0 +1,161 @@
+use impl_new_derive::ImplNew;
+use ndarray::{Array1, Array2};
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    noise::cgnsd::CGNSD,
+    SamplingVector,
+  },
+};
+
+/// Geometric Brownian motions driven by correlated Brownian motions, e.g. the prices of
+/// basket and rainbow options.
+///
+/// dS_i(t) = mu_i S_i(t) dt + sigma_i S_i(t) dW_i(t)
+///
+/// The paths are the exact lognormal solutions on the grid. The increments of W are given
+/// by [`CGNSD`] with the correlation matrix, which should have n - 1 steps and the same t.
+#[derive(ImplNew)]
+pub struct MultiGBM {
+  /// Drift of every asset, the risk-free rate minus the dividend yield under Q
+  pub mu: Array1<f64>,
+  /// Volatility of every asset
+  pub sigma: Array1<f64>,
+  /// Initial prices
+  pub s0: Array1<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  pub cgnsd: CGNSD,
+}
+
+impl SamplingVector<f64> for MultiGBM {
+  /// Sample the price paths, one asset per row
+  fn sample(&self) -> Array2<f64> {
+    or_panic(self.validate());
+
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let increments = self.cgnsd.sample();
+    let mut paths = Array2::<f64>::zeros((self.s0.len(), self.n));
+
+    for (j, &s0) in self.s0.iter().enumerate() {
+      let drift = (self.mu[j] - 0.5 * self.sigma[j].powi(2)) * dt;
+      paths[[j, 0]] = s0;
+      for i in 1..self.n {
+        paths[[j, i]] = paths[[j, i - 1]] * (drift + self.sigma[j] * increments[[j, i - 1]]).exp();
+      }
+    }
+
+    paths
+  }
+
+  /// Check the dimensions and the prices
+  fn validate(&self) -> StochasticResult<()> {
+    let d = self.cgnsd.dim();
+    ensure(
+      self.mu.len() == d && self.sigma.len() == d && self.s0.len() == d,
+      "mu, sigma and s0 must have a component for every row of the correlation matrix",
+    )?;
+    ensure(
+      self.cgnsd.n() == self.n - 1,
+      "the noise must have n - 1 steps",
+    )?;
+    ensure(
+      self.sigma.iter().all(|&sigma| sigma >= 0.0),
+      "volatilities must be non-negative",
+    )?;
+    ensure(
+      self.s0.iter().all(|&s| s > 0.0),
+      "initial prices must be positive",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for MultiGBM {
+  const INFO: ModelInfo = ModelInfo {
+    name: "MultiGBM",
+    title: "Correlated geometric Brownian motions",
+    path: "stochastic::diffusion::multi_gbm::MultiGBM",
+    kind: ModelKind::Diffusion,
+    description: "dS_i(t) = mu_i S_i(t) dt + sigma_i S_i(t) dW_i(t) with correlated W",
+    parameters: &[
+      ParameterInfo::array("mu", "Drift of every asset"),
+      ParameterInfo::array("sigma", "Volatility of every asset"),
+      ParameterInfo::array("s0", "Initial prices"),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component(
+        "cgnsd",
+        "Correlated Gaussian noise generator with n - 1 steps",
+      ),
+    ],
+    references: &[
+      "Glasserman, P. (2003). Monte Carlo methods in financial engineering, section 3.2.3.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::stochastic::noise::cgnsd::CovarianceDecomposition;
+
+  #[test]
+  fn multi_gbm_log_returns_are_correlated() {
+    let (n, paths) = (3, 20_000);
+    let gbm = MultiGBM::new(
+      array![0.05, 0.05],
+      array![0.2, 0.3],
+      array![100.0, 50.0],
+      n,
+      Some(1.0),
+      None,
+      CGNSD::new(
+        array![[1.0, 0.6], [0.6, 1.0]],
+        n - 1,
+        Some(1.0),
+        None,
+        CovarianceDecomposition::Cholesky,
+      ),
+    );
+    assert_eq!(gbm.sample().column(0), array![100.0, 50.0]);
+
+    let returns = (0..paths)
+      .map(|_| {
+        let s = gbm.sample();
+        [(s[[0, n - 1]] / 100.0).ln(), (s[[1, n - 1]] / 50.0).ln()]
+      })
+      .collect::<Vec<_>>();
+
+    let mean = |j: usize| returns.iter().map(|x| x[j]).sum::<f64>() / paths as f64;
+    let (m0, m1) = (mean(0), mean(1));
+    let covariance = returns
+      .iter()
+      .map(|x| (x[0] - m0) * (x[1] - m1))
+      .sum::<f64>()
+      / paths as f64;
+
+    // ln S_i(1) / S_i(0) ~ N(mu_i - sigma_i^2 / 2, sigma_i^2) with the covariance rho sigma_1 sigma_2
+    assert!((m0 - 0.03).abs() < 0.01 && (m1 - 0.005).abs() < 0.015);
+    assert!(
+      (covariance - 0.6 * 0.2 * 0.3).abs() < 0.003,
+      "{}",
+      covariance
+    );
+  }
+}
@@ -1,35 +1,31 @@
 use impl_new_derive::ImplNew;
-use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
-
-use crate::stochastic::Sampling;
-
-#[derive(ImplNew)]
-pub struct OU {
-  pub mu: f64,
-  pub sigma: f64,
-  pub theta: f64,
+use ndarray::{Array1, ArrayView2, Axis};
+
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::scheme::{Coefficients, Scheme},
+  variance_reduction::GaussianDriven,
+  FloatExt, Measure, MeasureChange, RiskPremia, Sampling,
+};
+
+#[derive(ImplNew, Clone)]
+pub struct OU<T: FloatExt = f64> {
+  pub mu: T,
+  pub sigma: T,
+  pub theta: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for OU {
+impl<T: FloatExt> Sampling<T> for OU<T> {
   /// Sample the Ornstein-Uhlenbeck (OU) process
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
-
-    let mut ou = Array1::<f64>::zeros(self.n);
-    ou[0] = self.x0.unwrap_or(0.0);
-
-    for i in 1..self.n {
-      ou[i] = ou[i - 1] + self.theta * (self.mu - ou[i - 1]) * dt + self.sigma * gn[i - 1]
-    }
-
-    ou
+  fn sample(&self) -> Array1<T> {
+    let z = T::normal_array(self.n - 1, T::zero(), T::one());
+    self.sample_from_increments(z.view().insert_axis(Axis(0)))
   }
 
   /// Number of time steps
@@ -43,32 +39,156 @@ impl Sampling<f64> for OU {
   }
 }
 
+impl<T: FloatExt> GaussianDriven<T> for OU<T> {
+  type Path = Array1<T>;
+
+  fn increments(&self) -> usize {
+    self.n - 1
+  }
+
+  /// Discretization scheme with the Brownian increments sqrt(dt) z
+  fn sample_from_increments(&self, z: ArrayView2<T>) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let sqrt_dt = dt.sqrt();
+    let scheme = self.scheme.unwrap_or_default();
+
+    let mut ou = Array1::<T>::zeros(self.n);
+    ou[0] = self.x0.unwrap_or(T::zero());
+
+    for i in 1..self.n {
+      ou[i] = scheme.step(self, ou[i - 1], dt, sqrt_dt * z[[0, i - 1]]);
+    }
+
+    ou
+  }
+}
+
+impl<T: FloatExt> Coefficients<T> for OU<T> {
+  fn drift(&self, x: T) -> T {
+    self.theta * (self.mu - x)
+  }
+
+  fn diffusion(&self, _x: T) -> T {
+    self.sigma
+  }
+
+  fn milstein(&self, _x: T) -> T {
+    T::zero()
+  }
+
+  /// y = (x + theta mu dt) / (1 + theta dt), the drift is linear
+  fn implicit_drift(&self, x: T, dt: T) -> T {
+    (x + self.theta * self.mu * dt) / (T::one() + self.theta * dt)
+  }
+}
+
+impl<T: FloatExt> MeasureChange for OU<T> {
+  /// lambda sigma, the premium of a short rate following the OU (Vasicek) dynamics
+  fn drift_adjustment(&self, _x: f64, premia: &RiskPremia) -> f64 {
+    premia.lambda * self.sigma.into()
+  }
+
+  /// Under Q the long-run mean mu is replaced by mu - lambda sigma / theta
+  fn under_measure(&self, measure: Measure, premia: &RiskPremia) -> Self {
+    let mu = match measure {
+      Measure::RealWorld => self.mu,
+      Measure::RiskNeutral => self.mu - T::from_f64_(premia.lambda) * self.sigma / self.theta,
+    };
+
+    Self::new(
+      mu,
+      self.sigma,
+      self.theta,
+      self.n,
+      self.x0,
+      self.t,
+      self.scheme,
+      self.m,
+    )
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for OU<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "OU",
+    title: "Ornstein-Uhlenbeck process",
+    path: "stochastic::diffusion::ou::OU",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = theta (mu - X(t)) dt + sigma dW(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Long-run mean", Interval::REAL, 0.0),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::NON_NEGATIVE, 2.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Uhlenbeck, G. E., & Ornstein, L. S. (1930). On the theory of the Brownian motion.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
     plot_1d,
-    stochastic::{Sampling, N, X0},
+    stochastic::{FloatExt, Sampling, N, X0},
   };
 
   use super::*;
 
   #[test]
   fn ou_length_equals_n() {
-    let ou = OU::new(2.0, 1.0, 0.8, N, Some(X0), Some(1.0), None);
+    let ou = OU::new(2.0, 1.0, 0.8, N, Some(X0), Some(1.0), None, None);
 
     assert_eq!(ou.sample().len(), N);
   }
 
   #[test]
   fn ou_starts_with_x0() {
-    let ou = OU::new(2.0, 1.0, 0.8, N, Some(X0), Some(1.0), None);
+    let ou = OU::new(2.0, 1.0, 0.8, N, Some(X0), Some(1.0), None, None);
 
     assert_eq!(ou.sample()[0], X0);
   }
 
+  #[test]
+  fn ou_f32_sample_par() {
+    let ou = OU::<f32>::new(2.0, 1.0, 0.8, N, Some(0.5), Some(1.0), None, Some(10));
+    let paths = ou.sample_par();
+
+    assert_eq!(paths.dim(), (10, N));
+    assert!(paths.iter().all(|x| x.is_finite()));
+  }
+
+  #[test]
+  fn ou_implicit_schemes_are_stable_for_stiff_mean_reversion() {
+    // theta dt = 5, the explicit step multiplies the distance to mu by -4
+    let (theta, mu, n) = (50.0, 1.0, 51);
+    let z = f64::normal_array(n - 1, 0.0, 1.0);
+    let sample = |scheme| {
+      OU::new(mu, 0.5, theta, n, Some(2.0), Some(5.0), Some(scheme), None)
+        .sample_from_increments(z.view().insert_axis(Axis(0)))
+    };
+
+    assert!(sample(Scheme::Euler)
+      .iter()
+      .any(|x| !x.is_finite() || x.abs() > 1e6));
+
+    for scheme in [Scheme::DriftImplicitEuler, Scheme::SplitStepBackwardEuler] {
+      assert!(
+        sample(scheme).iter().all(|x| (x - mu).abs() < 1.5),
+        "{:?}",
+        scheme
+      );
+    }
+  }
+
   #[test]
   fn ou_plot() {
-    let ou = OU::new(2.0, 1.0, 0.8, N, Some(X0), Some(1.0), None);
+    let ou = OU::new(2.0, 1.0, 0.8, N, Some(X0), Some(1.0), None, None);
 
     plot_1d!(ou.sample(), "Fractional Ornstein-Uhlenbeck (FOU) Process");
   }
@@ -0,0 +1,274 @@
+//! Regime-switching (Markov-modulated) diffusions.
+//!
+//! dX(t) = a_(J(t))(X(t))dt + b_(J(t))(X(t))dW(t)
+//!
+//! The regime J is a continuous-time Markov chain on 0, ..., K - 1 with the generator Q,
+//! it stays in i for an exponential time of rate -q_ii and then switches to j with the
+//! probability q_ij / -q_ii. The chain is simulated exactly and the diffusion takes every
+//! step with the coefficients of the regime at the start of the step.
+//!
+//! - Hamilton, J. D. (1989). A new approach to the economic analysis of nonstationary time series and the business cycle.
+//! - Yin, G. G., & Zhu, C. (2010). Hybrid Switching Diffusions.
+
+use impl_new_derive::ImplNew;
+use nalgebra::DVector;
+use ndarray::{Array1, Array2};
+use rand::{thread_rng, Rng};
+use rand_distr::Exp1;
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  math::linalg::to_dmatrix,
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::scheme::{Coefficients, Scheme},
+    FloatExt, Sampling2D,
+  },
+};
+
+/// Diffusion whose coefficients switch with a continuous-time Markov chain
+///
+/// The samples are the path and the regime indicator J(t_i) as a float.
+#[derive(ImplNew, Clone)]
+pub struct RegimeSwitching<S: Coefficients<f64>> {
+  /// Diffusion of every regime, e.g. GBMs or OUs with different parameters, only their
+  /// coefficients are used
+  pub regimes: Vec<S>,
+  /// Generator Q of the chain, q_ij >= 0 is the rate of the switches from i to j and the
+  /// rows sum to 0
+  pub generator: Array2<f64>,
+  /// Initial regime, drawn from the stationary distribution if None
+  pub regime0: Option<usize>,
+  pub n: usize,
+  pub x0: Option<f64>,
+  pub t: Option<f64>,
+  /// Discretization scheme, Euler if None
+  pub scheme: Option<Scheme>,
+  pub m: Option<usize>,
+}
+
+impl<S: Coefficients<f64>> RegimeSwitching<S> {
+  /// Stationary distribution pi of the chain, the solution of pi Q = 0 with sum pi = 1
+  ///
+  /// Unique for an irreducible chain, the uniform distribution if the system is singular.
+  pub fn stationary_distribution(&self) -> Array1<f64> {
+    let k = self.regimes.len();
+    // Q^T pi = 0 with the last equation replaced by the normalization
+    let mut a = to_dmatrix(&self.generator).transpose();
+    a.row_mut(k - 1).fill(1.0);
+    let mut b = DVector::zeros(k);
+    b[k - 1] = 1.0;
+
+    match a.lu().solve(&b) {
+      Some(pi) if pi.iter().all(|&p| p.is_finite() && p >= -1e-12) => {
+        pi.iter().map(|&p| p.max(0.0)).collect()
+      }
+      _ => Array1::from_elem(k, 1.0 / k as f64),
+    }
+  }
+
+  /// Path of the regime at the n time points, simulated exactly
+  pub fn sample_regimes(&self) -> Array1<usize> {
+    let mut rng = thread_rng();
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let rate = |i: usize| -self.generator[[i, i]];
+    let holding = |i: usize, rng: &mut rand::rngs::ThreadRng| match rate(i) > 0.0 {
+      true => rng.sample::<f64, _>(Exp1) / rate(i),
+      false => f64::INFINITY,
+    };
+
+    let mut regime = self.regime0.unwrap_or_else(|| {
+      let u = rng.gen::<f64>();
+      let mut cumulative = 0.0;
+      self
+        .stationary_distribution()
+        .iter()
+        .position(|&p| {
+          cumulative += p;
+          u < cumulative
+        })
+        .unwrap_or(self.regimes.len() - 1)
+    });
+    let mut switch = holding(regime, &mut rng);
+
+    let mut regimes = Array1::<usize>::zeros(self.n);
+    for i in 0..self.n {
+      while switch <= i as f64 * dt {
+        // Next regime j != i with the probability q_ij / -q_ii
+        let u = rng.gen::<f64>() * rate(regime);
+        let mut cumulative = 0.0;
+        let from = regime;
+        regime = (0..self.regimes.len())
+          .filter(|&j| j != from)
+          .find(|&j| {
+            cumulative += self.generator[[from, j]];
+            u < cumulative
+          })
+          .unwrap_or_else(|| (0..self.regimes.len()).rfind(|&j| j != from).unwrap());
+        switch += holding(regime, &mut rng);
+      }
+      regimes[i] = regime;
+    }
+
+    regimes
+  }
+}
+
+impl<S: Coefficients<f64> + Send + Sync> Sampling2D<f64> for RegimeSwitching<S> {
+  /// Sample the path and the regimes
+  fn sample(&self) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let scheme = self.scheme.unwrap_or_default();
+    let regimes = self.sample_regimes();
+    let dw = f64::normal_array(self.n - 1, 0.0, dt.sqrt());
+
+    let mut x = Array1::<f64>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    for i in 1..self.n {
+      x[i] = scheme.step(&self.regimes[regimes[i - 1]], x[i - 1], dt, dw[i - 1]);
+    }
+
+    [x, regimes.mapv(|j| j as f64)]
+  }
+
+  /// Check the generator and the initial regime
+  fn validate(&self) -> StochasticResult<()> {
+    let k = self.regimes.len();
+    ensure(k > 0, "at least 1 regime is needed")?;
+    ensure(
+      self.generator.dim() == (k, k),
+      "generator must be a square matrix with a row for every regime",
+    )?;
+    ensure(
+      self
+        .generator
+        .indexed_iter()
+        .all(|((i, j), &q)| i == j || q >= 0.0),
+      "off-diagonal rates of the generator must be non-negative",
+    )?;
+    ensure(
+      self.generator.rows().into_iter().all(|row| {
+        let scale = row.iter().map(|q| q.abs()).sum::<f64>().max(1.0);
+        row.sum().abs() <= 1e-10 * scale
+      }),
+      "rows of the generator must sum to 0",
+    )?;
+    ensure(
+      self.regime0.is_none_or(|j| j < k),
+      "initial regime must be below the number of regimes",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<S: Coefficients<f64>> ProcessInfo for RegimeSwitching<S> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "RegimeSwitching",
+    title: "Regime-switching diffusion",
+    path: "stochastic::diffusion::regime_switching::RegimeSwitching",
+    kind: ModelKind::Diffusion,
+    description: "dX(t) = a_J(t)(X(t)) dt + b_J(t)(X(t)) dW(t) with a Markov chain J",
+    parameters: &[
+      ParameterInfo::component("regimes", "Diffusion of every regime"),
+      ParameterInfo::array("generator", "Generator matrix of the Markov chain"),
+      ParameterInfo::integer(
+        "regime0",
+        "Initial regime, stationary if None",
+        Interval::NON_NEGATIVE,
+        0.0,
+      )
+      .optional(),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::SCHEME,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Hamilton, J. D. (1989). A new approach to the economic analysis of nonstationary time series and the business cycle.",
+      "Yin, G. G., & Zhu, C. (2010). Hybrid Switching Diffusions.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::stochastic::{diffusion::ou::OU, N};
+
+  fn ou(mu: f64, sigma: f64) -> OU {
+    OU::new(mu, sigma, 2.0, N, None, None, None, None)
+  }
+
+  #[test]
+  fn regime_switching_without_switches_stays_in_the_initial_regime() {
+    let process = RegimeSwitching::new(
+      vec![ou(0.0, 0.1), ou(1.0, 0.1)],
+      Array2::zeros((2, 2)),
+      Some(1),
+      N,
+      Some(0.5),
+      Some(1.0),
+      None,
+      None,
+    );
+    let [x, regimes] = process.sample();
+
+    assert_eq!(x.len(), N);
+    assert_eq!(x[0], 0.5);
+    assert!(regimes.iter().all(|&j| j == 1.0));
+  }
+
+  #[test]
+  fn regime_occupation_is_stationary() {
+    let generator = array![[-1.0, 1.0], [2.0, -2.0]];
+    let process = RegimeSwitching::new(
+      vec![ou(0.0, 0.1), ou(1.0, 0.1)],
+      generator,
+      None,
+      200_001,
+      None,
+      Some(2000.0),
+      None,
+      None,
+    );
+
+    let pi = process.stationary_distribution();
+    assert!((pi[0] - 2.0 / 3.0).abs() < 1e-12 && (pi[1] - 1.0 / 3.0).abs() < 1e-12);
+
+    // The standard deviation of the occupation time share is about 0.009
+    let [_, regimes] = process.sample();
+    let share = regimes.iter().filter(|&&j| j == 0.0).count() as f64 / regimes.len() as f64;
+    assert!((share - 2.0 / 3.0).abs() < 0.04, "{}", share);
+  }
+
+  #[test]
+  fn regime_switching_rejects_invalid_generator() {
+    let process = RegimeSwitching::new(
+      vec![ou(0.0, 0.1), ou(1.0, 0.1)],
+      array![[-1.0, 1.0], [2.0, -1.0]],
+      None,
+      N,
+      None,
+      None,
+      None,
+      None,
+    );
+
+    assert!(process.try_sample().is_err());
+  }
+}
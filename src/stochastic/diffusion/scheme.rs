@@ -0,0 +1,263 @@
+//! Time discretization shared by the scalar diffusions
+//!
+//! dX(t) = a(X(t))dt + b(X(t))dW(t)
+//!
+//! | Scheme | Strong order | Weak order | Evaluations per step |
+//! |--------|--------------|------------|----------------------|
+//! | [`Scheme::Euler`] | 0.5 | 1.0 | a, b |
+//! | [`Scheme::Milstein`] | 1.0 | 1.0 | a, b, b b' |
+//! | [`Scheme::SRK2`] | 1.0 | 1.0 | a, 2 b |
+//! | [`Scheme::PredictorCorrector`] | 1.0 | 1.0 | 2 a, 2 b, 2 b b' |
+//! | [`Scheme::DriftImplicitEuler`] | 0.5 | 1.0 | implicit a, b |
+//! | [`Scheme::SplitStepBackwardEuler`] | 0.5 | 1.0 | implicit a, b |
+//!
+//! The implicit schemes stay bounded for stiff drifts, e.g. a fast mean reversion with
+//! kappa dt > 2, where the explicit ones blow up.
+//!
+//! CIR's [`DiscretizationScheme`](super::cir::DiscretizationScheme) and the
+//! [`SDEScheme`](super::custom::SDEScheme) of the custom SDEs convert from [`Scheme`].
+//!
+//! The orders hold for scalar noise. Fractional processes are driven by fGn increments
+//! and their integrals are understood pathwise (Young, H > 1/2), so the Itô correction
+//! -dt of the Milstein term is dropped for them.
+//!
+//! - Kloeden, P. E., & Platen, E. (1992). Numerical Solution of Stochastic Differential Equations.
+//! - Higham, D. J., Mao, X., & Stuart, A. M. (2002). Strong convergence of Euler-type methods
+//!   for nonlinear stochastic differential equations.
+
+use crate::stochastic::FloatExt;
+
+/// Discretization scheme of a scalar diffusion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scheme {
+  /// Euler-Maruyama
+  ///
+  /// X(n+1) = X(n) + a dt + b dW
+  #[default]
+  Euler,
+  /// Milstein, Euler with the correction b b' (dW^2 - dt) / 2
+  Milstein,
+  /// Derivative-free Runge-Kutta scheme of Platen with two stages
+  ///
+  /// Y = X(n) + a dt + b sqrt(dt), X(n+1) = X(n) + a dt + b dW + (b(Y) - b) (dW^2 - dt) / (2 sqrt(dt))
+  SRK2,
+  /// Euler predictor and trapezoidal corrector (Kloeden & Platen, 1992, 15.5)
+  ///
+  /// X(n+1) = X(n) + (a'(Y) + a'(X(n))) dt / 2 + (b(Y) + b(X(n))) dW / 2 with the Euler
+  /// predictor Y and the corrected drift a' = a - b b' / 2
+  PredictorCorrector,
+  /// Drift-implicit (backward) Euler
+  ///
+  /// X(n+1) = X(n) + a(X(n+1)) dt + b dW
+  DriftImplicitEuler,
+  /// Split-step backward Euler (Higham, Mao & Stuart, 2002)
+  ///
+  /// Y = X(n) + a(Y) dt, X(n+1) = Y + b(Y) dW
+  SplitStepBackwardEuler,
+}
+
+/// Coefficients of an autonomous scalar diffusion
+pub trait Coefficients<T: FloatExt> {
+  /// Drift a(x)
+  fn drift(&self, x: T) -> T;
+
+  /// Diffusion coefficient b(x)
+  fn diffusion(&self, x: T) -> T;
+
+  /// b(x) b'(x), the coefficient of the Milstein correction
+  fn milstein(&self, x: T) -> T;
+
+  /// Solution y of y - a(y) dt = x of the implicit drift step, by Newton's method with a
+  /// central difference of the drift
+  fn implicit_drift(&self, x: T, dt: T) -> T {
+    let mut y = x;
+
+    for _ in 0..50 {
+      let h = T::from_f64_(1e-6) * y.abs().max(T::one());
+      let slope = (self.drift(y + h) - self.drift(y - h)) / (h + h);
+      let jacobian = T::one() - slope * dt;
+
+      if jacobian.abs() < T::epsilon() {
+        break;
+      }
+
+      let delta = (y - self.drift(y) * dt - x) / jacobian;
+      y -= delta;
+
+      if delta.abs() <= T::from_f64_(1e-12) * y.abs().max(T::one()) {
+        break;
+      }
+    }
+
+    y
+  }
+}
+
+impl Scheme {
+  /// Step of a diffusion driven by the Brownian increment dw over dt
+  pub(crate) fn step<T: FloatExt>(self, sde: &impl Coefficients<T>, x: T, dt: T, dw: T) -> T {
+    self.step_with(sde, x, dt, dw, dt.sqrt(), dt)
+  }
+
+  /// Step of a diffusion driven by the fractional Gaussian increment dw of standard
+  /// deviation scale = dt^H, integrated pathwise
+  pub(crate) fn step_pathwise<T: FloatExt>(
+    self,
+    sde: &impl Coefficients<T>,
+    x: T,
+    dt: T,
+    dw: T,
+    scale: T,
+  ) -> T {
+    self.step_with(sde, x, dt, dw, scale, T::zero())
+  }
+
+  /// Step with the Itô correction of dW^2, dt for Brownian and 0 for pathwise integrals
+  fn step_with<T: FloatExt>(
+    self,
+    sde: &impl Coefficients<T>,
+    x: T,
+    dt: T,
+    dw: T,
+    scale: T,
+    correction: T,
+  ) -> T {
+    let half = T::from_f64_(0.5);
+    let (a, b) = (sde.drift(x), sde.diffusion(x));
+
+    match self {
+      Scheme::Euler => x + a * dt + b * dw,
+      Scheme::Milstein => x + a * dt + b * dw + half * sde.milstein(x) * (dw * dw - correction),
+      Scheme::SRK2 => {
+        let y = x + a * dt + b * scale;
+        x + a * dt
+          + b * dw
+          + (sde.diffusion(y) - b) * (dw * dw - correction) / (T::from_f64_(2.0) * scale)
+      }
+      Scheme::PredictorCorrector => {
+        // The Itô drift correction vanishes for pathwise integrals
+        let ito = correction / dt;
+        let corrected = |x: T| sde.drift(x) - half * ito * sde.milstein(x);
+
+        let y = x + a * dt + b * dw;
+        x + half * (corrected(y) + corrected(x)) * dt + half * (sde.diffusion(y) + b) * dw
+      }
+      Scheme::DriftImplicitEuler => sde.implicit_drift(x + b * dw, dt),
+      Scheme::SplitStepBackwardEuler => {
+        let y = sde.implicit_drift(x, dt);
+        y + sde.diffusion(y) * dw
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::{Array1, Array2, Axis};
+
+  use super::*;
+  use crate::stochastic::{diffusion::gbm::GBM, variance_reduction::GaussianDriven};
+
+  const SCHEMES: [Scheme; 4] = [
+    Scheme::Euler,
+    Scheme::Milstein,
+    Scheme::SRK2,
+    Scheme::PredictorCorrector,
+  ];
+
+  fn gbm(scheme: Scheme, n: usize, mu: f64, sigma: f64) -> GBM {
+    GBM::new(
+      mu,
+      sigma,
+      n,
+      Some(1.0),
+      Some(1.0),
+      Some(scheme),
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    )
+  }
+
+  /// Slope of log2(error) over halvings of dt
+  fn order(errors: &[f64]) -> f64 {
+    errors.windows(2).map(|e| (e[0] / e[1]).log2()).sum::<f64>() / (errors.len() - 1) as f64
+  }
+
+  #[test]
+  fn strong_order() {
+    let (mu, sigma, paths, fine) = (0.5, 0.8, 2000, 256);
+    let z = Array2::from_shape_vec(
+      (paths, fine),
+      f64::normal_array(paths * fine, 0.0, 1.0).to_vec(),
+    )
+    .unwrap();
+    // X(T) = x0 exp((mu - sigma^2 / 2) T + sigma W(T))
+    let exact = z
+      .sum_axis(Axis(1))
+      .mapv(|w| ((mu - 0.5 * sigma * sigma) + sigma * w / (fine as f64).sqrt()).exp());
+
+    for scheme in SCHEMES {
+      let errors = [16, 32, 64, 128]
+        .iter()
+        .map(|&steps| {
+          let process = gbm(scheme, steps + 1, mu, sigma);
+          let ratio = fine / steps;
+          (0..paths)
+            .map(|p| {
+              // Coarse standard normal increments from the same Brownian path
+              let coarse = Array1::from_shape_fn(steps, |i| {
+                z.row(p)
+                  .slice(ndarray::s![i * ratio..(i + 1) * ratio])
+                  .sum()
+                  / (ratio as f64).sqrt()
+              });
+              let path = process.sample_from_increments(coarse.view().insert_axis(Axis(0)));
+              (path[steps] - exact[p]).abs()
+            })
+            .sum::<f64>()
+            / paths as f64
+        })
+        .collect::<Vec<_>>();
+      let order = order(&errors);
+      println!(
+        "{:?}: errors {:?}, strong order {:.2}",
+        scheme, errors, order
+      );
+
+      match scheme {
+        Scheme::Euler => assert!((0.3..0.75).contains(&order)),
+        _ => assert!(order > 0.8),
+      }
+    }
+  }
+
+  #[test]
+  fn weak_order() {
+    let (mu, sigma, paths) = (1.0, 0.5, 200_000);
+
+    for scheme in SCHEMES {
+      let errors = [2, 4, 8]
+        .iter()
+        .map(|&steps| {
+          let process = gbm(scheme, steps + 1, mu, sigma);
+          let z = f64::normal_array(paths * steps, 0.0, 1.0);
+          let mean = z
+            .exact_chunks(steps)
+            .into_iter()
+            .map(|z| process.sample_from_increments(z.insert_axis(Axis(0)))[steps])
+            .sum::<f64>()
+            / paths as f64;
+
+          // E[X(T)] = x0 e^(mu T)
+          (mean - mu.exp()).abs()
+        })
+        .collect::<Vec<_>>();
+      let order = order(&errors);
+      println!("{:?}: errors {:?}, weak order {:.2}", scheme, errors, order);
+
+      assert!(order > 0.7 && order < 1.5);
+    }
+  }
+}
@@ -0,0 +1,251 @@
+//! Multi-allele Wright-Fisher diffusion, the multivariate Jacobi process on the simplex.
+//!
+//! dX_i(t) = sigma^2 / 2 (theta_i - |theta| X_i(t)) dt + sigma sum_j B_ij(X(t)) dW_j(t)
+//!
+//! with B B^T = diag(x) - x x^T and |theta| = sum_i theta_i. The proportions X_i stay
+//! non-negative and sum to 1, theta_i is the immigration (mutation) rate towards the type
+//! i, and the stationary distribution is Dirichlet(theta). For 2 types X_1 is the
+//! [`Jacobi`](super::jacobi::Jacobi) process with alpha = sigma^2 theta_1 / 2 and
+//! beta = sigma^2 |theta| / 2.
+//!
+//! The factor B = diag(sqrt(x)) - x sqrt(x)^T costs O(d) per step. The Euler steps are
+//! projected back onto the simplex, and the stationary draws use the stick-breaking
+//! construction of the Dirichlet distribution.
+//!
+//! - Ethier, S. N., & Kurtz, T. G. (1986). Markov Processes: Characterization and Convergence, chapter 10.
+//! - Gourieroux, C., & Jasiak, J. (2006). Multivariate Jacobi process with application to smooth transitions.
+
+use impl_new_derive::ImplNew;
+use ndarray::{s, Array1, Array2};
+use ndarray_rand::RandomExt;
+use rand::thread_rng;
+use rand_distr::{Beta, Distribution, StandardNormal};
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    SamplingVector,
+  },
+};
+
+/// Wright-Fisher diffusion of d proportions with the immigration rates theta
+#[derive(ImplNew, Clone)]
+pub struct WrightFisher {
+  /// Immigration (mutation) rate towards every type, the parameters of the stationary
+  /// Dirichlet distribution
+  pub theta: Array1<f64>,
+  /// Scale of the time, the genetic drift is sigma^2 (diag(x) - x x^T)
+  pub sigma: f64,
+  /// Initial proportions on the simplex, drawn from the stationary distribution if None
+  pub x0: Option<Array1<f64>>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl WrightFisher {
+  /// Draw of the stationary Dirichlet(theta) distribution by stick-breaking
+  ///
+  /// X_k = V_k prod_(j < k) (1 - V_j) with V_k ~ Beta(theta_k, sum_(j > k) theta_j).
+  pub fn stationary_sample(&self) -> Array1<f64> {
+    let mut rng = thread_rng();
+    let d = self.theta.len();
+    let mut x = Array1::<f64>::zeros(d);
+    let mut rest = 1.0;
+
+    for k in 0..d - 1 {
+      let tail = self.theta.slice(s![k + 1..]).sum();
+      let v = Beta::new(self.theta[k], tail).unwrap().sample(&mut rng);
+      x[k] = rest * v;
+      rest -= x[k];
+    }
+    x[d - 1] = rest.max(0.0);
+
+    x
+  }
+
+  /// Stationary mean theta / |theta|
+  pub fn stationary_mean(&self) -> Array1<f64> {
+    &self.theta / self.theta.sum()
+  }
+}
+
+/// Projection onto the simplex by truncating the negative proportions
+fn project(x: &mut Array1<f64>) {
+  x.mapv_inplace(|x| x.max(0.0));
+  let total = x.sum();
+  x.mapv_inplace(|x| x / total);
+}
+
+impl SamplingVector<f64> for WrightFisher {
+  /// Sample the proportions, one type per row
+  fn sample(&self) -> Array2<f64> {
+    or_panic(self.validate());
+
+    let d = self.theta.len();
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let (sigma2, total) = (self.sigma.powi(2), self.theta.sum());
+    let z = Array2::<f64>::random((self.n - 1, d), StandardNormal);
+
+    let mut paths = Array2::<f64>::zeros((d, self.n));
+    let mut x = self.x0.clone().unwrap_or_else(|| self.stationary_sample());
+    paths.column_mut(0).assign(&x);
+
+    for i in 1..self.n {
+      let root = x.mapv(f64::sqrt);
+      let z = z.row(i - 1);
+      let common = root.dot(&z);
+
+      for k in 0..d {
+        let drift = 0.5 * sigma2 * (self.theta[k] - total * x[k]);
+        let noise = self.sigma * (root[k] * z[k] - x[k] * common);
+        x[k] += drift * dt + noise * dt.sqrt();
+      }
+      project(&mut x);
+      paths.column_mut(i).assign(&x);
+    }
+
+    paths
+  }
+
+  /// Check the rates and the initial proportions
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.theta.len() >= 2, "at least 2 types are needed")?;
+    ensure(
+      self.theta.iter().all(|&theta| theta >= 0.0),
+      "theta must be non-negative",
+    )?;
+    ensure(self.sigma > 0.0, "sigma must be positive")?;
+    ensure(self.n >= 2, "n must be at least 2")?;
+
+    match &self.x0 {
+      Some(x0) => {
+        ensure(
+          x0.len() == self.theta.len(),
+          "x0 must have a proportion for every type",
+        )?;
+        ensure(
+          x0.iter().all(|&x| x >= 0.0) && (x0.sum() - 1.0).abs() < 1e-10,
+          "x0 must be on the simplex",
+        )
+      }
+      None => ensure(
+        self.theta.iter().all(|&theta| theta > 0.0),
+        "theta must be positive to draw x0 from the stationary distribution",
+      ),
+    }
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for WrightFisher {
+  const INFO: ModelInfo = ModelInfo {
+    name: "WrightFisher",
+    title: "Multi-allele Wright-Fisher diffusion",
+    path: "stochastic::diffusion::wright_fisher::WrightFisher",
+    kind: ModelKind::Diffusion,
+    description: "dX_i(t) = sigma^2 / 2 (theta_i - |theta| X_i(t)) dt + sigma sum_j B_ij(X(t)) dW_j(t) with B B^T = diag(x) - x x^T",
+    parameters: &[
+      ParameterInfo::array("theta", "Immigration rate towards every type"),
+      ParameterInfo::real("sigma", "Scale of the genetic drift", Interval::POSITIVE, 1.0),
+      ParameterInfo::array("x0", "Initial proportions, stationary if None").optional(),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Ethier, S. N., & Kurtz, T. G. (1986). Markov Processes: Characterization and Convergence, chapter 10.",
+      "Gourieroux, C., & Jasiak, J. (2006). Multivariate Jacobi process with application to smooth transitions.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+
+  #[test]
+  fn wright_fisher_stays_on_the_simplex() {
+    let process = WrightFisher::new(
+      array![0.2, 0.5, 1.0, 0.3],
+      1.0,
+      Some(array![0.7, 0.1, 0.1, 0.1]),
+      501,
+      Some(2.0),
+      None,
+    );
+    let paths = process.sample();
+
+    assert_eq!(paths.dim(), (4, 501));
+    assert!(paths.iter().all(|&x| x >= 0.0));
+    assert!(paths
+      .columns()
+      .into_iter()
+      .all(|x| (x.sum() - 1.0).abs() < 1e-12));
+  }
+
+  #[test]
+  fn wright_fisher_stationary_sample_is_dirichlet() {
+    let process = WrightFisher::new(array![1.0, 2.0, 3.0], 1.0, None, 2, None, None);
+    let draws = 20_000;
+    let samples = (0..draws)
+      .map(|_| process.stationary_sample())
+      .collect::<Vec<_>>();
+
+    let mean = samples
+      .iter()
+      .fold(Array1::<f64>::zeros(3), |acc, x| acc + x)
+      / draws as f64;
+    let variance = samples
+      .iter()
+      .map(|x| (x[0] - mean[0]).powi(2))
+      .sum::<f64>()
+      / draws as f64;
+
+    // Dirichlet(1, 2, 3) has the means 1/6, 1/3, 1/2 and Var X_1 = 5 / 252
+    assert!((&mean - &process.stationary_mean())
+      .iter()
+      .all(|x| x.abs() < 0.005));
+    assert!((variance - 5.0 / 252.0).abs() < 0.001, "{}", variance);
+  }
+
+  #[test]
+  fn wright_fisher_mean_reverts_to_the_stationary_mean() {
+    let (t, paths) = (1.0, 2_000);
+    let x0 = array![0.6, 0.3, 0.1];
+    let process = WrightFisher::new(
+      array![1.0, 2.0, 3.0],
+      1.0,
+      Some(x0.clone()),
+      201,
+      Some(t),
+      None,
+    );
+
+    let terminal = (0..paths)
+      .map(|_| process.sample().column(200).to_owned())
+      .fold(Array1::<f64>::zeros(3), |acc, x| acc + x)
+      / paths as f64;
+
+    // E[X(t)] = pi + (x0 - pi) e^(-sigma^2 |theta| t / 2), the standard errors are below 0.004
+    let pi = process.stationary_mean();
+    let expected = &pi + &((&x0 - &pi) * (-3.0 * t).exp());
+    assert!(
+      (&terminal - &expected).iter().all(|x| x.abs() < 0.015),
+      "{}",
+      terminal
+    );
+  }
+}
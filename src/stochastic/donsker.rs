@@ -0,0 +1,252 @@
+//! Random walk approximations of Brownian motion and fractional Brownian motion.
+//!
+//! Donsker's invariance principle states that the rescaled partial sums of i.i.d. zero
+//! mean unit variance increments converge in distribution to Brownian motion, whatever
+//! the distribution of the increments. The walks here can be used to see the
+//! convergence in action and to validate CLT-based approximations.
+
+use impl_new_derive::ImplNew;
+use ndarray::{s, Array1};
+use ndarray_rand::RandomExt;
+use rand_distr::{Exp1, StandardNormal, StudentT, Uniform};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::stochastic::Sampling;
+
+/// Distribution of the random walk increments, standardized to zero mean and unit variance
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Increment {
+  #[default]
+  Gaussian,
+  /// +1 or -1 with equal probability (simple random walk)
+  Rademacher,
+  /// Uniform on [-sqrt(3), sqrt(3)]
+  Uniform,
+  /// Centered exponential, skewed
+  Exponential,
+  /// Student-t with nu > 2 degrees of freedom, heavy tailed
+  StudentT(f64),
+}
+
+impl Increment {
+  /// Draw n standardized increments
+  pub fn sample(&self, n: usize) -> Array1<f64> {
+    match *self {
+      Increment::Gaussian => Array1::random(n, StandardNormal),
+      Increment::Rademacher => {
+        Array1::random(n, Uniform::new(0.0, 1.0)).mapv(|u: f64| if u < 0.5 { -1.0 } else { 1.0 })
+      }
+      Increment::Uniform => {
+        let a = 3.0_f64.sqrt();
+        Array1::random(n, Uniform::new(-a, a))
+      }
+      Increment::Exponential => Array1::random(n, Exp1).mapv(|x: f64| x - 1.0),
+      Increment::StudentT(nu) => {
+        assert!(
+          nu > 2.0,
+          "Student-t increments need nu > 2 for finite variance"
+        );
+        let scale = ((nu - 2.0) / nu).sqrt();
+        Array1::random(n, StudentT::new(nu).unwrap()) * scale
+      }
+    }
+  }
+}
+
+/// Rescaled random walk W_n(t_k) = sqrt(dt) * (xi_1 + ... + xi_k) converging to Brownian motion
+#[derive(ImplNew)]
+pub struct RandomWalk {
+  pub increment: Increment,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl Sampling<f64> for RandomWalk {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let xi = self.increment.sample(self.n - 1) * dt.sqrt();
+
+    let mut walk = Array1::<f64>::zeros(self.n);
+    for i in 1..self.n {
+      walk[i] = walk[i - 1] + xi[i - 1];
+    }
+
+    walk
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Fractional random walk converging to fractional Brownian motion.
+///
+/// Discretized Mandelbrot-van Ness moving average of i.i.d. standardized increments
+///
+/// B_n(k) = sum_{j < k} [(k - j - 1/2)^(H - 1/2) - (-j - 1/2)_+^(H - 1/2)] xi_j
+///
+/// where the infinite past is truncated to `burn_in` increments (n by default).
+/// The walk is normalized so that Var B_n(t) = t^(2H) at the end of the horizon.
+/// The cost is O(n (n + burn_in)), so it is meant for demonstrations, use
+/// [`crate::stochastic::process::fbm::FBM`] for simulation.
+#[derive(ImplNew)]
+pub struct FractionalRandomWalk {
+  pub hurst: f64,
+  pub increment: Increment,
+  pub n: usize,
+  pub burn_in: Option<usize>,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl FractionalRandomWalk {
+  fn weight(&self, k: usize, j: isize) -> f64 {
+    let d = self.hurst - 0.5;
+    let future = (k as f64 - j as f64 - 0.5).powf(d);
+    let past = if j < 0 {
+      (-(j as f64) - 0.5).powf(d)
+    } else {
+      0.0
+    };
+
+    future - past
+  }
+}
+
+impl Sampling<f64> for FractionalRandomWalk {
+  fn sample(&self) -> Array1<f64> {
+    assert!(
+      self.hurst > 0.0 && self.hurst < 1.0,
+      "Hurst parameter must be in (0, 1)"
+    );
+
+    let steps = self.n - 1;
+    let burn_in = self.burn_in.unwrap_or(self.n);
+    let xi = self.increment.sample(burn_in + steps);
+
+    let mut walk = Array1::<f64>::zeros(self.n);
+    let mut variance = 0.0;
+    for k in 1..=steps {
+      for (idx, x) in xi.slice(s![..burn_in + k]).iter().enumerate() {
+        let w = self.weight(k, idx as isize - burn_in as isize);
+        walk[k] += w * x;
+
+        if k == steps {
+          variance += w.powi(2);
+        }
+      }
+    }
+
+    walk * self.t.unwrap_or(1.0).powf(self.hurst) / variance.sqrt()
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Convergence in distribution diagnostics of the terminal value of a walk
+#[derive(Debug, Clone, Copy)]
+pub struct ConvergenceReport {
+  /// Number of time steps of the walk
+  pub n: usize,
+  /// Kolmogorov-Smirnov distance to the limiting normal distribution
+  pub ks: f64,
+  /// Sample mean (limit 0)
+  pub mean: f64,
+  /// Sample variance divided by the limiting variance (limit 1)
+  pub variance_ratio: f64,
+  /// Sample excess kurtosis (limit 0)
+  pub excess_kurtosis: f64,
+}
+
+/// Kolmogorov-Smirnov distance between the empirical distribution of the samples and a cdf
+pub fn ks_distance(samples: &Array1<f64>, cdf: impl Fn(f64) -> f64) -> f64 {
+  let mut sorted = samples.to_vec();
+  sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  let n = sorted.len() as f64;
+
+  sorted
+    .iter()
+    .enumerate()
+    .map(|(i, &x)| {
+      let f = cdf(x);
+      (f - i as f64 / n).abs().max(((i + 1) as f64 / n - f).abs())
+    })
+    .fold(0.0, f64::max)
+}
+
+/// Compare the terminal values of `paths` sampled walks with the N(0, t^(2H)) limit.
+///
+/// Use hurst = 0.5 for walks converging to Brownian motion.
+pub fn terminal_convergence(
+  walk: &impl Sampling<f64>,
+  hurst: f64,
+  t: f64,
+  paths: usize,
+) -> ConvergenceReport {
+  let terminal = Array1::from_shape_fn(paths, |_| *walk.sample().last().unwrap());
+  let limit = Normal::new(0.0, t.powf(hurst)).unwrap();
+
+  let mean = terminal.mean().unwrap();
+  let variance = terminal.var(1.0);
+  let kurtosis = terminal.mapv(|x| (x - mean).powi(4)).mean().unwrap() / variance.powi(2) - 3.0;
+
+  ConvergenceReport {
+    n: walk.n(),
+    ks: ks_distance(&terminal, |x| limit.cdf(x)),
+    mean,
+    variance_ratio: variance / t.powf(2.0 * hurst),
+    excess_kurtosis: kurtosis,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn random_walk_converges_to_bm() {
+    let coarse = terminal_convergence(
+      &RandomWalk::new(Increment::Exponential, 3, Some(1.0), None),
+      0.5,
+      1.0,
+      20_000,
+    );
+    let fine = terminal_convergence(
+      &RandomWalk::new(Increment::Exponential, 401, Some(1.0), None),
+      0.5,
+      1.0,
+      20_000,
+    );
+
+    println!("{:?}\n{:?}", coarse, fine);
+    assert!(fine.ks < coarse.ks);
+    assert!(fine.excess_kurtosis.abs() < coarse.excess_kurtosis.abs());
+    assert!((fine.variance_ratio - 1.0).abs() < 0.05);
+  }
+
+  #[test]
+  fn fractional_random_walk_variance() {
+    let walk = FractionalRandomWalk::new(0.7, Increment::Rademacher, 101, None, Some(2.0), None);
+    let report = terminal_convergence(&walk, 0.7, 2.0, 5_000);
+
+    println!("{:?}", report);
+    assert_eq!(walk.sample().len(), 101);
+    assert!((report.variance_ratio - 1.0).abs() < 0.1);
+    assert!(report.ks < 0.05);
+  }
+}
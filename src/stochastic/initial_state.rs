@@ -0,0 +1,366 @@
+//! Random initial states of the samplers.
+//!
+//! The processes start from the fixed values of their x0, s0 or v0 fields. [`RandomInitial`]
+//! wraps a process implementing [`InitialState`] and starts every path from a draw of an
+//! [`InitialDistribution`] instead, e.g. a prior on an unobserved variance or the posterior
+//! draws of a filtered state for predictive simulations. The state variables are drawn
+//! independently, in the order of [`InitialState::STATE`].
+//!
+//! Every path samples a copy of the process with the drawn state, the copies share the
+//! precomputed spectra of the fractional noises. [`CustomSDE`](super::diffusion::custom::CustomSDE)
+//! holds boxed jump sizes and cannot be copied, and the Vasicek models start from the state
+//! of their inner OU process, which can be wrapped instead.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use ndrustfft::Zero;
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal, Normal};
+
+use super::{
+  diffusion::{
+    cev::CEV, cir::CIR, fcir::FCIR, fgbm::FGBM, fjacobi::FJacobi, fou::FOU, gbm::GBM,
+    jacobi::Jacobi, local_vol::LocalVolProcess, ou::OU,
+  },
+  interest::{duffie_kan::DuffieKan, hull_white::HullWhite, hull_white_2f::HullWhite2F},
+  jump::{
+    bates::Bates, cgmy::CGMY, cts::CTS, ig::IG, jump_fou::JumpFOU, kou::KOU,
+    levy_diffusion::LevyDiffusion, merton::Merton, nig::NIG, rdts::RDTS, vg::VG,
+  },
+  volatility::{
+    bergomi::Bergomi, heston::Heston, rbergomi::RBergomi, rough_heston::RoughHeston, sabr::SABR,
+    svcgmy::SVCGMY,
+  },
+  FloatExt, Sampling, Sampling2D,
+};
+use crate::error::{ensure, StochasticResult};
+
+/// Distribution of a state variable at time 0
+#[derive(Debug, Clone, PartialEq)]
+pub enum InitialDistribution {
+  /// Fixed value, the same as setting the field of the process
+  Dirac(f64),
+  /// Normal with the mean and standard deviation
+  Normal { mean: f64, std_dev: f64 },
+  /// e^X with X normal with the mean mu and standard deviation sigma
+  LogNormal { mu: f64, sigma: f64 },
+  /// Uniform draw of the samples, e.g. the draws of a posterior
+  Empirical(Array1<f64>),
+}
+
+impl InitialDistribution {
+  /// Draw of the state variable
+  pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    match self {
+      Self::Dirac(x) => *x,
+      Self::Normal { mean, std_dev } => Normal::new(*mean, *std_dev).unwrap().sample(rng),
+      Self::LogNormal { mu, sigma } => LogNormal::new(*mu, *sigma).unwrap().sample(rng),
+      Self::Empirical(samples) => samples[rng.gen_range(0..samples.len())],
+    }
+  }
+
+  /// Check the parameters of the distribution
+  pub fn validate(&self) -> StochasticResult<()> {
+    match self {
+      Self::Dirac(x) => ensure(x.is_finite(), "initial state must be finite"),
+      Self::Normal { mean, std_dev } => ensure(
+        mean.is_finite() && std_dev.is_finite() && *std_dev >= 0.0,
+        "normal initial state needs a finite mean and a non-negative std_dev",
+      ),
+      Self::LogNormal { mu, sigma } => ensure(
+        mu.is_finite() && sigma.is_finite() && *sigma >= 0.0,
+        "lognormal initial state needs a finite mu and a non-negative sigma",
+      ),
+      Self::Empirical(samples) => ensure(
+        !samples.is_empty() && samples.iter().all(|x| x.is_finite()),
+        "empirical initial state needs finite samples",
+      ),
+    }
+  }
+}
+
+/// Processes whose initial state can be set
+pub trait InitialState: Clone {
+  /// Names of the state variables, e.g. `["s0", "v0"]` for stochastic volatility models
+  const STATE: &'static [&'static str];
+
+  /// Set the initial state, one value for every variable of [`Self::STATE`]
+  fn set_initial_state(&mut self, state: &[f64]);
+
+  /// Copy of the process starting from the state
+  fn with_initial_state(&self, state: &[f64]) -> Self {
+    let mut process = self.clone();
+    process.set_initial_state(state);
+    process
+  }
+}
+
+macro_rules! impl_initial_state {
+  ($([$($generics:tt)*] $process:ty => $($field:ident),+;)*) => {
+    $(
+      impl<$($generics)*> InitialState for $process {
+        const STATE: &'static [&'static str] = &[$(stringify!($field)),+];
+
+        fn set_initial_state(&mut self, state: &[f64]) {
+          let mut state = state.iter();
+          $(self.$field = state.next().map(|&x| FloatExt::from_f64_(x));)+
+        }
+      }
+    )*
+  };
+}
+
+impl_initial_state! {
+  [] CIR => x0;
+  [] FCIR => x0;
+  [] CEV => x0;
+  [] Jacobi => x0;
+  [] FJacobi => x0;
+  [] FGBM => x0;
+  [] LocalVolProcess => x0;
+  [T: FloatExt] GBM<T> => x0;
+  [T: FloatExt] OU<T> => x0;
+  [T: FloatExt] FOU<T> => x0;
+  [] HullWhite => x0;
+  [] HullWhite2F => x0;
+  [] DuffieKan => x0;
+  [] VG => x0;
+  [] IG => x0;
+  [] NIG => x0;
+  [] CTS => x0;
+  [] RDTS => x0;
+  [] CGMY => x0;
+  [D: Distribution<f64> + Clone + Send + Sync] Merton<D> => x0;
+  [D: Distribution<f64> + Clone + Send + Sync] KOU<D> => x0;
+  [D: Distribution<f64> + Clone + Send + Sync] LevyDiffusion<D> => x0;
+  [D: Distribution<f64> + Clone + Send + Sync] JumpFOU<D> => x0;
+  [D: Distribution<f64> + Clone + Send + Sync] Bates<D> => s0, v0;
+  [] Heston => s0, v0;
+  [] SABR => f0, v0;
+  [] SVCGMY => x0, v0;
+  [] Bergomi => s0, v0;
+  [X: Clone] RBergomi<X> => s0;
+}
+
+impl InitialState for RoughHeston {
+  const STATE: &'static [&'static str] = &["s0", "v0"];
+
+  /// The initial variance is a required parameter of the Volterra equation
+  fn set_initial_state(&mut self, state: &[f64]) {
+    self.s0 = Some(state[0]);
+    self.v0 = state[1];
+  }
+}
+
+#[allow(deprecated)]
+impl InitialState for super::volatility::rbergomi::RoughBergomi {
+  const STATE: &'static [&'static str] = &["s0", "v0"];
+
+  fn set_initial_state(&mut self, state: &[f64]) {
+    let mut state = state.iter();
+    self.s0 = state.next().copied();
+    self.v0 = state.next().copied();
+  }
+}
+
+/// Process started from a random initial state
+#[derive(ImplNew)]
+pub struct RandomInitial<P> {
+  pub process: P,
+  /// Distributions of the state variables of [`InitialState::STATE`]
+  pub initial: Vec<InitialDistribution>,
+  /// Number of paths for parallel sampling
+  pub m: Option<usize>,
+}
+
+impl<P: InitialState> RandomInitial<P> {
+  /// Draw of the initial state
+  pub fn sample_state(&self) -> Vec<f64> {
+    let mut rng = rand::thread_rng();
+    self
+      .initial
+      .iter()
+      .map(|distribution| distribution.sample(&mut rng))
+      .collect()
+  }
+
+  /// Copy of the process starting from a draw of the initial state
+  pub fn draw(&self) -> P {
+    self.process.with_initial_state(&self.sample_state())
+  }
+
+  fn validate_initial(&self) -> StochasticResult<()> {
+    ensure(
+      self.initial.len() == P::STATE.len(),
+      "one initial distribution is needed for every state variable",
+    )?;
+    self
+      .initial
+      .iter()
+      .try_for_each(InitialDistribution::validate)
+  }
+}
+
+impl<T, P> Sampling<T> for RandomInitial<P>
+where
+  T: Clone + Send + Sync + Zero,
+  P: InitialState + Sampling<T>,
+{
+  /// Sample the process from a draw of the initial state
+  fn sample(&self) -> Array1<T> {
+    self.draw().sample()
+  }
+
+  /// Check the initial distributions and the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    self.validate_initial()?;
+    Sampling::<T>::validate(&self.process)
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    Sampling::<T>::n(&self.process)
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<T, P> Sampling2D<T> for RandomInitial<P>
+where
+  T: Clone + Send + Sync + Zero,
+  P: InitialState + Sampling2D<T>,
+{
+  /// Sample the process from a draw of the initial state
+  fn sample(&self) -> [Array1<T>; 2] {
+    self.draw().sample()
+  }
+
+  /// Check the initial distributions and the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    self.validate_initial()?;
+    Sampling2D::<T>::validate(&self.process)
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    Sampling2D::<T>::n(&self.process)
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::stochastic::{noise::cgns::CGNS, volatility::HestonPow};
+
+  fn mean_and_variance(x: &[f64]) -> (f64, f64) {
+    let mean = x.iter().sum::<f64>() / x.len() as f64;
+    let variance = x.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (x.len() - 1) as f64;
+    (mean, variance)
+  }
+
+  #[test]
+  fn dirac_starts_from_the_fixed_state() {
+    let gbm = GBM::new(
+      0.05,
+      0.2,
+      100,
+      None,
+      Some(1.0),
+      None,
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let random = RandomInitial::new(gbm, vec![InitialDistribution::Dirac(100.0)], Some(8));
+    assert!(Sampling::<f64>::validate(&random).is_ok());
+    assert!(Sampling::<f64>::sample_par(&random)
+      .column(0)
+      .iter()
+      .all(|&x| x == 100.0));
+  }
+
+  #[test]
+  fn normal_initial_state_of_ou() {
+    // x(1) = x0 e^(-theta) + sigma int_0^1 e^(-theta (1 - s)) dW(s) with x0 ~ N(1, 0.3^2)
+    let ou = OU::new(0.0, 0.5, 1.0, 501, None, Some(1.0), None, None);
+    let random = RandomInitial::new(
+      ou,
+      vec![InitialDistribution::Normal {
+        mean: 1.0,
+        std_dev: 0.3,
+      }],
+      Some(20_000),
+    );
+    let paths = Sampling::<f64>::sample_par(&random);
+
+    let (mean, variance) = mean_and_variance(&paths.column(0).to_vec());
+    assert!((mean - 1.0).abs() < 0.01 && (variance.sqrt() - 0.3).abs() < 0.01);
+
+    let decay = (-1.0f64).exp();
+    let (mean, variance) = mean_and_variance(&paths.column(500).to_vec());
+    let exact = decay.powi(2) * 0.09 + 0.25 * (1.0 - decay.powi(2)) / 2.0;
+    assert!((mean - decay).abs() < 0.01, "{} {}", mean, decay);
+    assert!((variance - exact).abs() < 0.006, "{} {}", variance, exact);
+  }
+
+  #[test]
+  fn empirical_and_lognormal_states_of_heston() {
+    let heston = Heston::new(
+      None,
+      None,
+      2.0,
+      0.04,
+      0.3,
+      -0.7,
+      0.0,
+      100,
+      Some(1.0),
+      HestonPow::Sqrt,
+      None,
+      None,
+      None,
+      CGNS::new(-0.7, 99, Some(1.0), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let spots = array![95.0, 100.0, 105.0];
+    let mut random = RandomInitial::new(
+      heston,
+      vec![
+        InitialDistribution::Empirical(spots.clone()),
+        InitialDistribution::LogNormal {
+          mu: 0.04f64.ln(),
+          sigma: 0.2,
+        },
+      ],
+      None,
+    );
+    assert_eq!(Heston::STATE, ["s0", "v0"]);
+    assert!(Sampling2D::<f64>::validate(&random).is_ok());
+
+    for _ in 0..20 {
+      let [s, v] = Sampling2D::<f64>::sample(&random);
+      assert!(spots.iter().any(|&spot| spot == s[0]));
+      assert!(v[0] > 0.0);
+    }
+
+    random.initial.pop();
+    assert!(Sampling2D::<f64>::validate(&random).is_err());
+    random.initial.push(InitialDistribution::Normal {
+      mean: 0.04,
+      std_dev: -0.01,
+    });
+    assert!(Sampling2D::<f64>::validate(&random).is_err());
+  }
+}
@@ -2,12 +2,16 @@ pub mod adg;
 // pub mod bgm;
 pub mod cir;
 pub mod cir_2f;
+pub mod cir_plus_plus;
 pub mod duffie_kan;
 pub mod fvasicek;
+pub mod g2pp;
 pub mod hjm;
 pub mod ho_lee;
 pub mod hull_white;
 pub mod hull_white_2f;
+pub mod hull_white_fitted;
+pub mod libor_market_model;
 // pub mod mod_duffie_kan;
 pub mod vasicek;
 // pub mod wu_zhang;
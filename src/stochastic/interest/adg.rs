@@ -3,7 +3,10 @@ use ndarray::{Array1, Array2};
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
-use crate::stochastic::SamplingVector;
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  SamplingVector,
+};
 
 /// Ahn-Dittmar-Gallant (ADG) model
 ///
@@ -66,3 +69,29 @@ impl SamplingVector<f64> for ADG {
     self.m
   }
 }
+
+impl ProcessInfo for ADG {
+  const INFO: ModelInfo = ModelInfo {
+    name: "ADG",
+    title: "Ahn-Dittmar-Gallant quadratic term structure model",
+    path: "stochastic::interest::adg::ADG",
+    kind: ModelKind::Interest,
+    description: "dX_i(t) = (k(t) - theta(t) X_i(t)) dt + sigma_i dW_i(t), r(t) = phi(t) + b(t) X(t) + c(t) X(t)^2",
+    parameters: &[
+      ParameterInfo::function("k", "Drift level k(t)"),
+      ParameterInfo::function("theta", "Mean reversion rate theta(t)"),
+      ParameterInfo::array("sigma", "Volatility of every factor"),
+      ParameterInfo::function("phi", "Deterministic shift of the rate phi(t)"),
+      ParameterInfo::function("b", "Linear loading b(t)"),
+      ParameterInfo::function("c", "Quadratic loading c(t)"),
+      ParameterInfo::N,
+      ParameterInfo::integer("xn", "Number of factors", Interval::COUNT, 2.0),
+      ParameterInfo::array("x0", "Initial value of every factor"),
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Ahn, D. H., Dittmar, R. F., & Gallant, A. R. (2002). Quadratic term structure models: Theory and evidence.",
+    ],
+  };
+}
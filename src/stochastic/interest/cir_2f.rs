@@ -1,7 +1,10 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::Sampling;
+use crate::stochastic::{
+  catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  Sampling,
+};
 
 use super::cir::CIR;
 
@@ -31,3 +34,22 @@ impl Sampling<f64> for CIR2F {
     self.x.m()
   }
 }
+
+impl ProcessInfo for CIR2F {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CIR2F",
+    title: "Two-factor Cox-Ingersoll-Ross model",
+    path: "stochastic::interest::cir_2f::CIR2F",
+    kind: ModelKind::Interest,
+    description: "r(t) = x(t) + y(t) + phi(t) with independent CIR factors x and y",
+    parameters: &[
+      ParameterInfo::component("x", "First CIR factor"),
+      ParameterInfo::component("y", "Second CIR factor"),
+      ParameterInfo::function(
+        "phi",
+        "Deterministic shift fitting the initial term structure",
+      ),
+    ],
+    references: &["Brigo, D., & Mercurio, F. (2006). Interest Rate Models - Theory and Practice."],
+  };
+}
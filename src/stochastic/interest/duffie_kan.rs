@@ -1,9 +1,13 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::cgns::CGNS,
+  Sampling2D,
+};
 
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 
 pub struct DuffieKan {
   pub alpha: f64,
@@ -60,3 +64,36 @@ impl Sampling2D<f64> for DuffieKan {
     self.m
   }
 }
+
+impl ProcessInfo for DuffieKan {
+  const INFO: ModelInfo = ModelInfo {
+    name: "DuffieKan",
+    title: "Duffie-Kan two-factor affine model",
+    path: "stochastic::interest::duffie_kan::DuffieKan",
+    kind: ModelKind::Interest,
+    description: "dr(t) = (a1 r + b1 x + c1) dt + sigma1 (alpha r + beta x + gamma) dW1(t), dx(t) = (a2 r + b2 x + c2) dt + sigma2 (alpha r + beta x + gamma) dW2(t)",
+    parameters: &[
+      ParameterInfo::real("alpha", "Loading of r in the volatility", Interval::REAL, 0.1),
+      ParameterInfo::real("beta", "Loading of x in the volatility", Interval::REAL, 0.1),
+      ParameterInfo::real("gamma", "Constant of the volatility", Interval::REAL, 0.1),
+      ParameterInfo::RHO,
+      ParameterInfo::real("a1", "Loading of r in the drift of r", Interval::REAL, -0.5),
+      ParameterInfo::real("b1", "Loading of x in the drift of r", Interval::REAL, 0.1),
+      ParameterInfo::real("c1", "Constant drift of r", Interval::REAL, 0.02),
+      ParameterInfo::real("sigma1", "Volatility of r", Interval::POSITIVE, 0.1),
+      ParameterInfo::real("a2", "Loading of r in the drift of x", Interval::REAL, 0.1),
+      ParameterInfo::real("b2", "Loading of x in the drift of x", Interval::REAL, -0.5),
+      ParameterInfo::real("c2", "Constant drift of x", Interval::REAL, 0.02),
+      ParameterInfo::real("sigma2", "Volatility of x", Interval::POSITIVE, 0.1),
+      ParameterInfo::N,
+      ParameterInfo::real("r0", "Initial rate, 0 if None", Interval::REAL, 0.03).optional(),
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+    ],
+    references: &[
+      "Duffie, D., & Kan, R. (1996). A yield-factor model of interest rates.",
+    ],
+  };
+}
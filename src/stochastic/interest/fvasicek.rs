@@ -1,7 +1,11 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{diffusion::fou::FOU, Sampling};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::fou::FOU,
+  Sampling,
+};
 
 #[derive(ImplNew)]
 pub struct FVasicek {
@@ -32,3 +36,25 @@ impl Sampling<f64> for FVasicek {
     self.m
   }
 }
+
+impl ProcessInfo for FVasicek {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FVasicek",
+    title: "Fractional Vasicek model",
+    path: "stochastic::interest::fvasicek::FVasicek",
+    kind: ModelKind::Interest,
+    description: "dr(t) = theta (mu - r(t)) dt + sigma dW^H(t)",
+    parameters: &[
+      ParameterInfo::HURST,
+      ParameterInfo::real("mu", "Long-run mean", Interval::REAL, 0.03),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.02),
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("fou", "Underlying fractional Ornstein-Uhlenbeck process"),
+    ],
+    references: &["Vasicek, O. (1977). An equilibrium characterization of the term structure."],
+  };
+}
@@ -3,7 +3,10 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
-use crate::stochastic::Sampling3D;
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  Sampling3D,
+};
 
 #[derive(ImplNew)]
 pub struct HJM {
@@ -54,3 +57,31 @@ impl Sampling3D<f64> for HJM {
     self.m
   }
 }
+
+impl ProcessInfo for HJM {
+  const INFO: ModelInfo = ModelInfo {
+    name: "HJM",
+    title: "Heath-Jarrow-Morton framework",
+    path: "stochastic::interest::hjm::HJM",
+    kind: ModelKind::Interest,
+    description: "dr(t) = a(t) dt + b(t) dW1(t), dP(t, T) = p(t, T) (q(t, T) dt + v(t, T) dW2(t)), df(t, T) = alpha(t, T) dt + sigma(t, T) dW3(t)",
+    parameters: &[
+      ParameterInfo::function("a", "Drift of the short rate a(t)"),
+      ParameterInfo::function("b", "Volatility of the short rate b(t)"),
+      ParameterInfo::function("p", "Scale of the bond price dynamics p(t, T)"),
+      ParameterInfo::function("q", "Drift of the bond price q(t, T)"),
+      ParameterInfo::function("v", "Volatility of the bond price v(t, T)"),
+      ParameterInfo::function("alpha", "Drift of the forward rate alpha(t, T)"),
+      ParameterInfo::function("sigma", "Volatility of the forward rate sigma(t, T)"),
+      ParameterInfo::N,
+      ParameterInfo::real("r0", "Initial short rate", Interval::REAL, 0.03).optional(),
+      ParameterInfo::real("p0", "Initial bond price", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::real("f0", "Initial forward rate", Interval::REAL, 0.03).optional(),
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Heath, D., Jarrow, R., & Morton, A. (1992). Bond pricing and the term structure of interest rates: A new methodology for contingent claims valuation.",
+    ],
+  };
+}
@@ -5,7 +5,10 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
-use crate::stochastic::Sampling;
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  Sampling,
+};
 
 #[allow(non_snake_case)]
 #[derive(ImplNew)]
@@ -52,3 +55,24 @@ impl Sampling<f64> for HoLee {
     self.m
   }
 }
+
+impl ProcessInfo for HoLee {
+  const INFO: ModelInfo = ModelInfo {
+    name: "HoLee",
+    title: "Ho-Lee model",
+    path: "stochastic::interest::ho_lee::HoLee",
+    kind: ModelKind::Interest,
+    description: "dr(t) = (theta(t) + sigma^2) dt + sigma dW(t)",
+    parameters: &[
+      ParameterInfo::function("f_T", "Time dependent drift, theta is used if None").optional(),
+      ParameterInfo::real("theta", "Constant drift", Interval::REAL, 0.01).optional(),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.01),
+      ParameterInfo::N,
+      ParameterInfo::real("t", "Time horizon", Interval::POSITIVE, 1.0),
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Ho, T. S., & Lee, S. B. (1986). Term structure movements and pricing interest rate contingent claims.",
+    ],
+  };
+}
@@ -3,12 +3,15 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
-use crate::stochastic::Sampling;
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  Sampling,
+};
 
 /// Hull-White process.
 /// dX(t) = theta(t)dt - alpha * X(t)dt + sigma * dW(t)
 /// where X(t) is the Hull-White process.
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 pub struct HullWhite {
   pub theta: fn(f64) -> f64,
   pub alpha: f64,
@@ -46,3 +49,23 @@ impl Sampling<f64> for HullWhite {
     self.m
   }
 }
+
+impl ProcessInfo for HullWhite {
+  const INFO: ModelInfo = ModelInfo {
+    name: "HullWhite",
+    title: "Hull-White model",
+    path: "stochastic::interest::hull_white::HullWhite",
+    kind: ModelKind::Interest,
+    description: "dr(t) = (theta(t) - alpha r(t)) dt + sigma dW(t)",
+    parameters: &[
+      ParameterInfo::function("theta", "Time dependent drift theta(t)"),
+      ParameterInfo::real("alpha", "Mean reversion rate", Interval::POSITIVE, 0.1),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.01),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &["Hull, J., & White, A. (1990). Pricing interest-rate-derivative securities."],
+  };
+}
@@ -1,12 +1,16 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::cgns::CGNS,
+  Sampling2D,
+};
 
 /// Hull-White 2-factor model
 /// dX(t) = (k(t) + U(t) - theta * X(t)) dt + sigma_1 dW1(t) x(0) = x0
 /// dU(t) = b * U(t) dt + sigma_2 dW2(t) u(0) = 0
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 pub struct HullWhite2F {
   pub k: fn(f64) -> f64,
   pub theta: f64,
@@ -52,3 +56,29 @@ impl Sampling2D<f64> for HullWhite2F {
     self.m
   }
 }
+
+impl ProcessInfo for HullWhite2F {
+  const INFO: ModelInfo = ModelInfo {
+    name: "HullWhite2F",
+    title: "Hull-White two-factor model",
+    path: "stochastic::interest::hull_white_2f::HullWhite2F",
+    kind: ModelKind::Interest,
+    description: "dX(t) = (k(t) + U(t) - theta X(t)) dt + sigma_1 dW1(t), dU(t) = b U(t) dt + sigma_2 dW2(t)",
+    parameters: &[
+      ParameterInfo::function("k", "Time dependent drift k(t)"),
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 0.1),
+      ParameterInfo::real("sigma1", "Volatility of X", Interval::POSITIVE, 0.01),
+      ParameterInfo::real("sigma2", "Volatility of U", Interval::POSITIVE, 0.01),
+      ParameterInfo::RHO,
+      ParameterInfo::real("b", "Mean reversion of U", Interval::REAL, -0.1),
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::N,
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+    ],
+    references: &[
+      "Hull, J., & White, A. (1994). Numerical procedures for implementing term structure models II: Two-factor models.",
+    ],
+  };
+}
@@ -0,0 +1,85 @@
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::rates::hull_white::HullWhiteModel,
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, Sampling,
+  },
+};
+
+/// Hull-White short rate fitted to a discount curve, simulated exactly
+///
+/// r(t) = x(t) + phi(t), dx(t) = -alpha x(t) dt + sigma dW(t), x(0) = 0
+///
+/// x is Gaussian with the transition of the Ornstein-Uhlenbeck process, so the grid has no
+/// discretization error, and phi(t) is the shift of the
+/// [`HullWhiteModel`] fitting the curve.
+#[derive(ImplNew)]
+pub struct HullWhiteFitted {
+  pub model: HullWhiteModel,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl Sampling<f64> for HullWhiteFitted {
+  fn sample(&self) -> Array1<f64> {
+    let (alpha, sigma) = (self.model.alpha, self.model.sigma);
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let decay = (-alpha * dt).exp();
+    let sd = sigma * ((1.0 - decay * decay) / (2.0 * alpha)).sqrt();
+    let z = f64::normal_array(self.n - 1, 0.0, 1.0);
+
+    let mut r = Array1::<f64>::zeros(self.n);
+    let mut x = 0.0;
+    r[0] = self.model.shift(0.0);
+    for i in 1..self.n {
+      x = x * decay + sd * z[i - 1];
+      r[i] = x + self.model.shift(i as f64 * dt);
+    }
+
+    r
+  }
+
+  /// Check the parameters of the process
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.n >= 2, "n must be at least 2")?;
+    ensure(
+      self.model.alpha > 0.0 && self.model.sigma > 0.0,
+      "alpha and sigma must be positive",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for HullWhiteFitted {
+  const INFO: ModelInfo = ModelInfo {
+    name: "HullWhiteFitted",
+    title: "Hull-White model fitted to a curve",
+    path: "stochastic::interest::hull_white_fitted::HullWhiteFitted",
+    kind: ModelKind::Interest,
+    description: "r(t) = x(t) + phi(t), dx(t) = -alpha x(t) dt + sigma dW(t), exact simulation",
+    parameters: &[
+      ParameterInfo::component("model", "Hull-White model with its discount curve"),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Hull, J., & White, A. (1990). Pricing interest-rate-derivative securities.",
+      "Brigo, D., & Mercurio, F. (2006). Interest Rate Models - Theory and Practice.",
+    ],
+  };
+}
@@ -0,0 +1,294 @@
+use impl_new_derive::ImplNew;
+use ndarray::{s, Array1, Array2};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  error::{ensure, StochasticResult},
+  math::linalg::cholesky,
+  quant::rates::lmm::{AbcdVolatility, ForwardCorrelation},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, SamplingVector,
+  },
+};
+
+/// Numeraire of the [`LiborMarketModel`] simulation
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LmmMeasure {
+  /// Bank account rolled over at the tenor dates, the spot LIBOR measure
+  #[default]
+  Spot,
+  /// Zero-coupon bond maturing at the last tenor date, the last forward is a martingale
+  Terminal,
+}
+
+/// LIBOR market model of the forwards L_i of the accrual periods [T_i, T_(i+1)]
+///
+/// d(L_i + delta) = (L_i + delta) (mu_i dt + sigma_i(t) dW_i), dW_i dW_j = rho_ij dt
+///
+/// shifted lognormal with the displacement delta, lognormal for delta = 0. Under the spot
+/// measure mu_i = sigma_i sum_(j = q(t))^i tau_j rho_ij sigma_j (L_j + delta) / (1 + tau_j L_j),
+/// q(t) the first forward not fixed yet, and under the terminal measure
+/// mu_i = -sigma_i sum_(j > i) of the same terms. The forwards are stepped by log-Euler on n
+/// points of [0, T_(N-1)] up to their fixing and frozen afterwards, so the last column
+/// holds the fixings L_i(T_i).
+#[derive(ImplNew)]
+pub struct LiborMarketModel {
+  /// Tenor dates 0 <= T_0 < ... < T_N
+  pub tenors: Array1<f64>,
+  /// Initial forwards L_i(0) of the N accrual periods
+  pub forwards: Array1<f64>,
+  /// Volatility shape sigma_i(t) = k_i vol(T_i - t)
+  pub volatility: AbcdVolatility,
+  /// Scales k_i of the volatility of the forwards
+  pub scales: Array1<f64>,
+  /// Displacement delta of the shifted lognormal dynamics
+  pub displacement: f64,
+  /// Correlation of the forwards
+  pub correlation: ForwardCorrelation,
+  /// Numeraire of the simulation
+  pub measure: LmmMeasure,
+  pub n: usize,
+  pub m: Option<usize>,
+}
+
+impl LiborMarketModel {
+  /// Volatility sigma_i(t) of the i-th forward
+  pub fn sigma(&self, i: usize, t: f64) -> f64 {
+    self.scales[i] * self.volatility.vol(self.tenors[i] - t)
+  }
+
+  /// Time grid of the paths, n points of [0, T_(N-1)]
+  pub fn times(&self) -> Array1<f64> {
+    Array1::linspace(0.0, self.tenors[self.forwards.len() - 1], self.n)
+  }
+
+  /// Discount factors P(T_0, T_k), k = 0, ..., N, of the initial forwards
+  pub fn discount_factors(&self) -> Array1<f64> {
+    let mut discount = Array1::ones(self.tenors.len());
+    for i in 0..self.forwards.len() {
+      let accrual = self.tenors[i + 1] - self.tenors[i];
+      discount[i + 1] = discount[i] / (1.0 + accrual * self.forwards[i]);
+    }
+
+    discount
+  }
+
+  /// Price of the caplet on the i-th forward with unit notional, paid at T_(i+1)
+  ///
+  /// The shifted Black formula of L_i + delta with the caplet volatility of sigma_i, the
+  /// same under every numeraire.
+  pub fn caplet(&self, i: usize, strike: f64) -> f64 {
+    let fixing = self.tenors[i];
+    let accrual = self.tenors[i + 1] - fixing;
+    let (forward, strike) = (
+      self.forwards[i] + self.displacement,
+      strike + self.displacement,
+    );
+    let sd = self.scales[i]
+      * self
+        .volatility
+        .integrated_variance(fixing, 0.0, fixing)
+        .sqrt();
+    let n = Normal::new(0.0, 1.0).unwrap();
+    let d1 = (forward / strike).ln() / sd + 0.5 * sd;
+
+    self.discount_factors()[i + 1] * accrual * (forward * n.cdf(d1) - strike * n.cdf(d1 - sd))
+  }
+}
+
+impl SamplingVector<f64> for LiborMarketModel {
+  /// Forwards (rows) on the time grid (columns), see [`LiborMarketModel::times`]
+  fn sample(&self) -> Array2<f64> {
+    let forwards = self.forwards.len();
+    let fixings = self.tenors.slice(s![..forwards]).to_vec();
+    let accruals = Array1::from_shape_fn(forwards, |j| self.tenors[j + 1] - self.tenors[j]);
+    let rho = self.correlation.matrix(&fixings);
+    let factor = cholesky(&rho).expect("correlation matrix must be positive definite");
+    let times = self.times();
+
+    let mut l = Array2::<f64>::zeros((forwards, self.n));
+    l.column_mut(0).assign(&self.forwards);
+
+    for k in 1..self.n {
+      let t = times[k - 1];
+      let dt = times[k] - t;
+      let w = factor.dot(&f64::normal_array(forwards, 0.0, 1.0));
+      let previous = l.column(k - 1).to_owned();
+      let sigma = Array1::from_shape_fn(forwards, |j| self.sigma(j, t));
+      // tau_j sigma_j (L_j + delta) / (1 + tau_j L_j)
+      let terms = Array1::from_shape_fn(forwards, |j| {
+        accruals[j] * sigma[j] * (previous[j] + self.displacement)
+          / (1.0 + accruals[j] * previous[j])
+      });
+      let first = fixings
+        .iter()
+        .position(|&fixing| fixing > t)
+        .unwrap_or(forwards);
+
+      for i in 0..forwards {
+        if i < first {
+          l[[i, k]] = previous[i];
+          continue;
+        }
+
+        let drift = match self.measure {
+          LmmMeasure::Spot => (first..=i).map(|j| rho[[i, j]] * terms[j]).sum::<f64>(),
+          LmmMeasure::Terminal => -(i + 1..forwards)
+            .map(|j| rho[[i, j]] * terms[j])
+            .sum::<f64>(),
+        } * sigma[i];
+        // The last step of a forward ends at its fixing
+        let h = dt.min(fixings[i] - t);
+        l[[i, k]] = (previous[i] + self.displacement)
+          * ((drift - 0.5 * sigma[i].powi(2)) * h + sigma[i] * h.sqrt() * w[i]).exp()
+          - self.displacement;
+      }
+    }
+
+    l
+  }
+
+  /// Check the tenor structure, the forwards and the volatilities
+  fn validate(&self) -> StochasticResult<()> {
+    let forwards = self.forwards.len();
+    ensure(forwards >= 1, "at least 1 forward is needed")?;
+    ensure(
+      self.tenors.len() == forwards + 1 && self.scales.len() == forwards,
+      "tenors must have one more date than forwards and scales",
+    )?;
+    ensure(
+      self.tenors[0] >= 0.0 && self.tenors.windows(2).into_iter().all(|t| t[1] > t[0]),
+      "tenors must be non-negative and increasing",
+    )?;
+    ensure(
+      self.forwards.iter().all(|&l| l + self.displacement > 0.0),
+      "shifted forwards must be positive",
+    )?;
+    ensure(self.volatility.c > 0.0, "c must be positive")?;
+    ensure(self.n >= 2, "n must be at least 2")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for LiborMarketModel {
+  const INFO: ModelInfo = ModelInfo {
+    name: "LiborMarketModel",
+    title: "LIBOR market model",
+    path: "stochastic::interest::libor_market_model::LiborMarketModel",
+    kind: ModelKind::Interest,
+    description: "d(L_i + delta) = (L_i + delta) (mu_i dt + sigma_i(t) dW_i) with correlated forwards under the spot or terminal measure",
+    parameters: &[
+      ParameterInfo::array("tenors", "Tenor dates T_0 < ... < T_N"),
+      ParameterInfo::array("forwards", "Initial forwards of the accrual periods"),
+      ParameterInfo::component("volatility", "abcd volatility of the time to fixing"),
+      ParameterInfo::array("scales", "Scales of the volatility of the forwards"),
+      ParameterInfo::real("displacement", "Shift of the lognormal dynamics", Interval::NON_NEGATIVE, 0.0),
+      ParameterInfo::component("correlation", "Parametric correlation of the forwards"),
+      ParameterInfo::choice("measure", "Numeraire", &["Spot", "Terminal"]),
+      ParameterInfo::N,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Brace, A., Gatarek, D., & Musiela, M. (1997). The market model of interest rate dynamics.",
+      "Rebonato, R. (2004). Volatility and Correlation: The Perfect Hedger and the Fox.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lmm(measure: LmmMeasure) -> LiborMarketModel {
+    let tenors = Array1::from_shape_fn(11, |i| 0.5 * i as f64);
+    LiborMarketModel::new(
+      tenors,
+      Array1::from_shape_fn(10, |i| 0.03 + 0.002 * i as f64),
+      AbcdVolatility::new(0.05, 0.4, 1.5, 0.12),
+      Array1::ones(10),
+      0.01,
+      ForwardCorrelation::Rebonato {
+        long_term: 0.4,
+        beta: 0.3,
+      },
+      measure,
+      91,
+      None,
+    )
+  }
+
+  /// Mean of f over m paths
+  fn mean(model: &LiborMarketModel, m: usize, f: impl Fn(&Array2<f64>) -> f64) -> f64 {
+    (0..m).map(|_| f(&model.sample())).sum::<f64>() / m as f64
+  }
+
+  #[test]
+  fn spot_measure_reprices_bonds_and_caplets() {
+    let model = lmm(LmmMeasure::Spot);
+    assert!(model.validate().is_ok());
+    let discount = model.discount_factors();
+    let accrual = 0.5;
+    // Bank account at T_(i+1) from the fixings in the last column
+    let bank = |l: &Array2<f64>, i: usize| {
+      (0..=i)
+        .map(|j| 1.0 + accrual * l[[j, model.n - 1]])
+        .product::<f64>()
+    };
+
+    let m = 20_000;
+    let strike = model.forwards[5];
+    let (bond, caplet) = (0..m)
+      .map(|_| {
+        let l = model.sample();
+        (
+          1.0 / bank(&l, 9),
+          accrual * (l[[5, model.n - 1]] - strike).max(0.0) / bank(&l, 5),
+        )
+      })
+      .fold((0.0, 0.0), |a, b| {
+        (a.0 + b.0 / m as f64, a.1 + b.1 / m as f64)
+      });
+
+    assert!(
+      (bond - discount[10]).abs() < 2e-3,
+      "{} {}",
+      bond,
+      discount[10]
+    );
+    let exact = model.caplet(5, strike);
+    assert!((caplet / exact - 1.0).abs() < 0.03, "{} {}", caplet, exact);
+  }
+
+  #[test]
+  fn terminal_measure_keeps_the_last_forward_a_martingale() {
+    let model = lmm(LmmMeasure::Terminal);
+    let discount = model.discount_factors();
+    let last = model.n - 1;
+
+    let forward = mean(&model, 20_000, |l| l[[9, last]]);
+    assert!((forward - model.forwards[9]).abs() < 1e-3);
+
+    // P(0, T_8) / P(0, T_10) = E[1 / P(T_8, T_10)] from the forwards at T_8 = 4.0
+    let column = 80;
+    assert!((model.times()[column] - 4.0).abs() < 1e-12);
+    let ratio = mean(&model, 20_000, |l| {
+      (8..10).map(|j| 1.0 + 0.5 * l[[j, column]]).product::<f64>()
+    });
+    assert!((ratio - discount[8] / discount[10]).abs() < 1e-3);
+
+    let strike = model.forwards[9];
+    let caplet = discount[10] * mean(&model, 20_000, |l| 0.5 * (l[[9, last]] - strike).max(0.0));
+    let exact = model.caplet(9, strike);
+    assert!((caplet / exact - 1.0).abs() < 0.03, "{} {}", caplet, exact);
+  }
+}
@@ -1,7 +1,11 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{diffusion::ou::OU, Sampling};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  diffusion::ou::OU,
+  Sampling,
+};
 
 #[derive(ImplNew)]
 pub struct Vasicek {
@@ -30,3 +34,24 @@ impl Sampling<f64> for Vasicek {
     self.m
   }
 }
+
+impl ProcessInfo for Vasicek {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Vasicek",
+    title: "Vasicek model",
+    path: "stochastic::interest::vasicek::Vasicek",
+    kind: ModelKind::Interest,
+    description: "dr(t) = theta (mu - r(t)) dt + sigma dW(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Long-run mean", Interval::REAL, 0.03),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.02),
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("ou", "Underlying Ornstein-Uhlenbeck process"),
+    ],
+    references: &["Vasicek, O. (1977). An equilibrium characterization of the term structure."],
+  };
+}
@@ -68,15 +68,13 @@ where
   fn set_covariance_matrix_sqrt(&mut self) {
     let inner_product_structure_embedding =
       |inner_product_structure: &Array1<f64>| -> Array1<Complex64> {
-        let fft = FftHandler::new(inner_product_structure.len() * 2 - 2);
+        let n = inner_product_structure.len();
+        let fft = FftHandler::new(n * 2 - 2);
         let input = concatenate(
           Axis(0),
           &[
             inner_product_structure.view(),
-            inner_product_structure
-              .slice(s![..;-1])
-              .slice(s![1..-1])
-              .view(),
+            inner_product_structure.slice(s![1..n - 1;-1]),
           ],
         )
         .unwrap();
@@ -85,14 +83,12 @@ where
         let mut embedded_inner_product_structure =
           Array1::<Complex64>::zeros(inner_product_structure.len() * 2 - 2);
         ndfft(&input, &mut embedded_inner_product_structure, &fft, 0);
-        let embedded_inner_product_structure = embedded_inner_product_structure.mapv(|x| {
+        embedded_inner_product_structure.mapv(|x| {
           Complex64::new(
             (x.re / (2.0 * (inner_product_structure.len() - 1) as f64)).sqrt(),
             x.im,
           )
-        });
-
-        embedded_inner_product_structure
+        })
       };
 
     let embedded_inner_product_matrix =
@@ -111,7 +107,7 @@ where
     );
     let mut path = Array1::<Complex64>::zeros(self.covariance_matrix_sqrt.as_ref().unwrap().len());
     ndfft(
-      &(&*self.covariance_matrix_sqrt.as_ref().unwrap() * &normal),
+      &(self.covariance_matrix_sqrt.as_ref().unwrap() * &normal),
       &mut path,
       &fft,
       0,
@@ -162,9 +158,7 @@ where
 
   // Use quad to perform the integration between 0 and 1
   let quad = GaussLegendre::new(5).unwrap();
-  let integral = quad.integrate(0.0, 1.0, integrand);
-
-  integral
+  quad.integrate(0.0, 1.0, integrand)
 }
 
 // Fractional Lévy Ornstein-Uhlenbeck inner product function (Unstable)
@@ -187,9 +181,7 @@ fn cov_ld(t: f64, s: f64, d: f64, e_l1_squared: f64) -> f64 {
   let s_term = s.abs().powf(2.0 * d + 1.0);
   let ts_term = (t - s).abs().powf(2.0 * d + 1.0);
 
-  let covariance = (e_l1_squared / denominator) * (t_term + s_term - ts_term);
-
-  covariance
+  (e_l1_squared / denominator) * (t_term + s_term - ts_term)
 }
 
 #[cfg(test)]
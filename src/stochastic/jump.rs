@@ -8,4 +8,6 @@ pub mod levy_diffusion;
 pub mod merton;
 pub mod nig;
 pub mod rdts;
+pub mod stable;
 pub mod vg;
+pub mod vgsa;
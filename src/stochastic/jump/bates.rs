@@ -3,47 +3,54 @@ use ndarray::Array1;
 use rand_distr::Distribution;
 
 use crate::stochastic::{
-  noise::cgns::CGNS, process::cpoisson::CompoundPoisson, Sampling2D, Sampling3D,
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::cgns::CGNS,
+  process::cpoisson::CompoundPoisson,
+  FloatExt, Sampling2D, Sampling3D,
 };
 
-#[derive(ImplNew)]
-pub struct Bates1996<D>
+#[derive(ImplNew, Clone)]
+pub struct Bates<D, T: FloatExt = f64>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  pub mu: Option<f64>,
-  pub b: Option<f64>,
-  pub r: Option<f64>,
-  pub r_f: Option<f64>,
-  pub lambda: f64,
-  pub k: f64,
-  pub alpha: f64,
-  pub beta: f64,
-  pub sigma: f64,
-  pub rho: f64,
+  pub mu: Option<T>,
+  pub b: Option<T>,
+  pub r: Option<T>,
+  pub r_f: Option<T>,
+  pub lambda: T,
+  pub k: T,
+  pub alpha: T,
+  pub beta: T,
+  pub sigma: T,
+  pub rho: T,
   pub n: usize,
-  pub s0: Option<f64>,
-  pub v0: Option<f64>,
-  pub t: Option<f64>,
+  pub s0: Option<T>,
+  pub v0: Option<T>,
+  pub t: Option<T>,
   pub use_sym: Option<bool>,
   pub m: Option<usize>,
-  pub cgns: CGNS,
-  pub cpoisson: CompoundPoisson<D>,
+  pub cgns: CGNS<T>,
+  pub cpoisson: CompoundPoisson<D, T>,
 }
 
-impl<D> Sampling2D<f64> for Bates1996<D>
+/// Former name of [`Bates`]
+#[deprecated(note = "renamed to `Bates`")]
+pub type Bates1996<D> = Bates<D>;
+
+impl<D, T: FloatExt> Sampling2D<T> for Bates<D, T>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  fn sample(&self) -> [Array1<f64>; 2] {
+  fn sample(&self) -> [Array1<T>; 2] {
     let [cgn1, cgn2] = self.cgns.sample();
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
 
-    let mut s = Array1::<f64>::zeros(self.n);
-    let mut v = Array1::<f64>::zeros(self.n);
+    let mut s = Array1::<T>::zeros(self.n);
+    let mut v = Array1::<T>::zeros(self.n);
 
-    s[0] = self.s0.unwrap_or(0.0);
-    v[0] = self.v0.unwrap_or(0.0);
+    s[0] = self.s0.unwrap_or(T::zero());
+    v[0] = self.v0.unwrap_or(T::zero());
 
     let drift = match (self.mu, self.b, self.r, self.r_f) {
       (Some(r), Some(r_f), ..) => r - r_f,
@@ -63,7 +70,7 @@ where
 
       v[i] = match self.use_sym.unwrap_or(false) {
         true => (v[i - 1] + dv).abs(),
-        false => (v[i - 1] + dv).max(0.0),
+        false => (v[i - 1] + dv).max(T::zero()),
       }
     }
 
@@ -81,6 +88,42 @@ where
   }
 }
 
+impl<D, T: FloatExt> ProcessInfo for Bates<D, T>
+where
+  D: Distribution<T> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "Bates",
+    title: "Bates stochastic volatility jump diffusion",
+    path: "stochastic::jump::bates::Bates",
+    kind: ModelKind::Jump,
+    description: "dS(t) = (b - lambda k) S(t) dt + sqrt(v(t)) S(t) dW1(t) + S(t) dJ(t), dv(t) = (alpha - beta v(t)) dt + sigma sqrt(v(t)) dW2(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Drift of the price", Interval::REAL, 0.05).optional(),
+      ParameterInfo::real("b", "Cost of carry", Interval::REAL, 0.05).optional(),
+      ParameterInfo::real("r", "Domestic risk-free rate", Interval::REAL, 0.05).optional(),
+      ParameterInfo::real("r_f", "Foreign risk-free rate", Interval::REAL, 0.0).optional(),
+      ParameterInfo::real("lambda", "Jump intensity", Interval::NON_NEGATIVE, 1.0),
+      ParameterInfo::real("k", "Expected relative jump size", Interval::REAL, -0.05),
+      ParameterInfo::real("alpha", "Drift level of the variance", Interval::POSITIVE, 0.08),
+      ParameterInfo::real("beta", "Mean reversion rate of the variance", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("sigma", "Volatility of the variance", Interval::POSITIVE, 0.3),
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("v0", "Initial variance", Interval::NON_NEGATIVE, 0.04).optional(),
+      ParameterInfo::T,
+      ParameterInfo::boolean("use_sym", "Reflect instead of truncate negative variance").optional(),
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+      ParameterInfo::component("cpoisson", "Compound Poisson process of the jumps"),
+    ],
+    references: &[
+      "Bates, D. S. (1996). Jumps and stochastic volatility: Exchange rate processes implicit in Deutsche Mark options.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use rand_distr::Normal;
@@ -94,7 +137,7 @@ mod tests {
 
   #[test]
   fn bates1996__length_equals_n() {
-    let bates1996 = Bates1996::new(
+    let bates1996 = Bates::new(
       Some(3.0),
       None,
       None,
@@ -125,7 +168,7 @@ mod tests {
 
   #[test]
   fn bates1996__starts_with_x0() {
-    let bates1996 = Bates1996::new(
+    let bates1996 = Bates::new(
       Some(3.0),
       None,
       None,
@@ -156,7 +199,7 @@ mod tests {
 
   #[test]
   fn bates1996__plot() {
-    let bates1996 = Bates1996::new(
+    let bates1996 = Bates::new(
       Some(3.0),
       None,
       None,
@@ -182,7 +225,7 @@ mod tests {
     );
 
     let [s, v] = bates1996.sample();
-    plot_2d!(s, "Bates1996 process (s)", v, "Bates1996 process (v)");
+    plot_2d!(s, "Bates process (s)", v, "Bates process (v)");
   }
 
   #[test]
@@ -1,11 +1,21 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 use ndarray_rand::RandomExt;
+use num_complex::Complex64;
 use rand::Rng;
 use rand_distr::{Exp, Uniform};
-use scilib::math::basic::gamma;
+use statrs::function::gamma::gamma;
 
-use crate::stochastic::{process::poisson::Poisson, Sampling};
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::r#trait::CharacteristicFn,
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    jump::stable::sample_tempered_stable,
+    process::poisson::Poisson,
+    FloatExt, Sampling,
+  },
+};
 
 /// CGMY process
 ///
@@ -29,49 +39,115 @@ use crate::stochastic::{process::poisson::Poisson, Sampling};
 /// This implementation simulates the CGMY process using a discrete approximation over a grid of time points.
 /// At each time step, we generate a Poisson random number of jumps, and for each jump, we generate the jump size
 /// according to the CGMY process. The process also includes a drift component computed from the parameters.
+/// The series is truncated after `j` jumps, alternatively [`CGMYMethod::StableRejection`] samples
+/// the increments directly.
 ///
 /// # References
 ///
 /// - Cont, R., & Tankov, P. (2004). *Financial Modelling with Jump Processes*. Chapman and Hall/CRC.
 /// - Madan, D. B., Carr, P., & Chang, E. C. (1998). The Variance Gamma Process and Option Pricing. *European Finance Review*, 2(1), 79-105.
-/// https://www.econstor.eu/bitstream/10419/239493/1/175133161X.pdf
+///   https://www.econstor.eu/bitstream/10419/239493/1/175133161X.pdf
+/// - Baeumer, B., & Meerschaert, M. M. (2010). Tempered stable Lévy motion and transient super-diffusion. *Journal of Computational and Applied Mathematics*, 233(10), 2438-2448.
 ///
-#[derive(ImplNew)]
-pub struct CGMY {
+#[derive(ImplNew, Clone)]
+pub struct CGMY<T: FloatExt = f64> {
   /// Positive jump rate lambda_plus (corresponds to G)
-  pub lambda_plus: f64, // G
+  pub lambda_plus: T, // G
   /// Negative jump rate lambda_minus (corresponds to M)
-  pub lambda_minus: f64, // M
+  pub lambda_minus: T, // M
   /// Jump activity parameter alpha (corresponds to Y), with 0 < alpha < 2
-  pub alpha: f64,
+  pub alpha: T,
   /// Number of time steps
   pub n: usize,
-  /// Jumps
+  /// Jumps of the series representation
   pub j: usize,
+  /// Simulation method, the series representation if None
+  pub method: Option<CGMYMethod>,
   /// Initial value
-  pub x0: Option<f64>,
+  pub x0: Option<T>,
   /// Total time horizon
-  pub t: Option<f64>,
+  pub t: Option<T>,
   /// Number of samples for parallel sampling (not used in this implementation)
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for CGMY {
-  fn sample(&self) -> Array1<f64> {
+/// Simulation method of the CGMY process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CGMYMethod {
+  /// Shot noise series representation truncated after `j` jumps.
+  ///
+  /// Slow for many jumps, and the truncation neglects the small jumps, which carry much
+  /// of the variation for large alpha.
+  #[default]
+  Series,
+  /// Increments as the difference of two one-sided tempered stable variables, each drawn
+  /// from a stable variable accepted with probability e^(-lambda x).
+  ///
+  /// Exact for alpha < 1. For alpha > 1 the stable variable is spectrally positive but not
+  /// bounded below, it is shifted by 4 scales beyond its location, so the values further in
+  /// the left tail are accepted without tempering, an error of order 1e-5 in probability.
+  /// The expected number of trials per increment grows with the tempering times the scale
+  /// (dt^(1 / alpha)), so it suits fine grids. Requires alpha != 1.
+  StableRejection,
+}
+
+impl<T: FloatExt> CGMY<T> {
+  /// G, M and Y in double precision, the jumps are drawn in f64
+  fn params(&self) -> (f64, f64, f64) {
+    (
+      self.lambda_plus.into(),
+      self.lambda_minus.into(),
+      self.alpha.into(),
+    )
+  }
+
+  /// C normalizing the variance of X(1) to 1
+  fn levy_c(&self) -> f64 {
+    let (lambda_plus, lambda_minus, alpha) = self.params();
+    (gamma(2.0 - alpha) * (lambda_plus.powf(alpha - 2.0) + lambda_minus.powf(alpha - 2.0))).powi(-1)
+  }
+
+  /// Drift centering the process
+  fn drift(&self) -> f64 {
+    let (lambda_plus, lambda_minus, alpha) = self.params();
+    -self.levy_c()
+      * gamma(1.0 - alpha)
+      * (lambda_plus.powf(alpha - 1.0) - lambda_minus.powf(alpha - 1.0))
+  }
+
+  fn sample_stable_rejection(&self) -> Array1<T> {
     let mut rng = rand::thread_rng();
 
-    let t_max = self.t.unwrap_or(1.0);
-    let dt = t_max / (self.n - 1) as f64;
-    let mut x = Array1::<f64>::zeros(self.n);
-    x[0] = self.x0.unwrap_or(0.0);
+    let (lambda_plus, lambda_minus, alpha) = self.params();
+    let dt = self.t.map_or(1.0, Into::into) / (self.n - 1) as f64;
+    let c = self.levy_c();
+    let drift = self.drift() * dt;
+    let mut x = Array1::<T>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(T::zero());
 
-    let C = (gamma(2.0 - self.alpha)
-      * (self.lambda_plus.powf(self.alpha - 2.0) + self.lambda_minus.powf(self.alpha - 2.0)))
-    .powi(-1);
+    for i in 1..self.n {
+      x[i] = x[i - 1]
+        + T::from_f64_(
+          sample_tempered_stable(alpha, c, lambda_plus, dt, &mut rng)
+            - sample_tempered_stable(alpha, c, lambda_minus, dt, &mut rng)
+            + drift,
+        );
+    }
 
-    let b_t = -C
-      * gamma(1.0 - self.alpha)
-      * (self.lambda_plus.powf(self.alpha - 1.0) - self.lambda_minus.powf(self.alpha - 1.0));
+    x
+  }
+
+  fn sample_series(&self) -> Array1<T> {
+    let mut rng = rand::thread_rng();
+
+    let (lambda_plus, lambda_minus, alpha) = self.params();
+    let t_max = self.t.map_or(1.0, Into::into);
+    let dt = t_max / (self.n - 1) as f64;
+    let mut x = Array1::<T>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(T::zero());
+
+    let C = self.levy_c();
+    let b_t = self.drift();
 
     let U = Array1::<f64>::random(self.j, Uniform::new(0.0, 1.0));
     let E = Array1::<f64>::random(self.j, Exp::new(1.0).unwrap());
@@ -87,24 +163,49 @@ impl Sampling<f64> for CGMY {
       for j in 1..self.j {
         if tau[j] > t_1 && tau[j] <= t {
           let v_j = if rng.gen_bool(0.5) {
-            self.lambda_plus
+            lambda_plus
           } else {
-            -self.lambda_minus
+            -lambda_minus
           };
 
-          let term1 = (self.alpha * P[j] / (2.0 * C * t_max)).powf(-1.0 / self.alpha);
-          let term2 = E[j] * U[j].powf(1.0 / self.alpha) / v_j.abs();
+          let term1 = (alpha * P[j] / (2.0 * C * t_max)).powf(-1.0 / alpha);
+          let term2 = E[j] * U[j].powf(1.0 / alpha) / v_j.abs();
           let jump_size = term1.min(term2) * (v_j / v_j.abs());
 
           jump_component += jump_size;
         }
       }
 
-      x[i] = x[i - 1] + jump_component + b_t * dt;
+      x[i] = x[i - 1] + T::from_f64_(jump_component + b_t * dt);
     }
 
     x
   }
+}
+
+impl<T: FloatExt> Sampling<T> for CGMY<T> {
+  fn sample(&self) -> Array1<T> {
+    match self.method.unwrap_or_default() {
+      CGMYMethod::Series => self.sample_series(),
+      CGMYMethod::StableRejection => self.sample_stable_rejection(),
+    }
+  }
+
+  /// Check the tempering and the stability index
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.lambda_plus > T::zero() && self.lambda_minus > T::zero(),
+      "lambda_plus and lambda_minus must be positive",
+    )?;
+    ensure(
+      self.alpha > T::zero() && self.alpha < T::from_f64_(2.0),
+      "alpha must be in (0, 2)",
+    )?;
+    ensure(
+      self.method != Some(CGMYMethod::StableRejection) || self.alpha != T::one(),
+      "the stable rejection method requires alpha != 1",
+    )
+  }
 
   /// Number of time steps
   fn n(&self) -> usize {
@@ -117,6 +218,48 @@ impl Sampling<f64> for CGMY {
   }
 }
 
+impl<T: FloatExt> CharacteristicFn for CGMY<T> {
+  /// exp(t C Gamma(-Y) ((M - iu)^Y - M^Y + (G + iu)^Y - G^Y) + iu b t) with the positive
+  /// jumps tempered by M = lambda_plus and the negative ones by G = lambda_minus, C and
+  /// the drift b as in the simulation, which centers the process, Y != 1
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    let (m, g, y) = self.params();
+    let (c, b) = (self.levy_c(), self.drift());
+    // Gamma(-Y) = Gamma(2 - Y) / (Y (Y - 1))
+    let gamma_y = gamma(2.0 - y) / (y * (y - 1.0));
+    let i = Complex64::i();
+
+    (t * c * gamma_y * ((m - i * u).powf(y) - m.powf(y) + (g + i * u).powf(y) - g.powf(y))
+      + i * u * b * t)
+      .exp()
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for CGMY<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CGMY",
+    title: "CGMY process",
+    path: "stochastic::jump::cgmy::CGMY",
+    kind: ModelKind::Jump,
+    description: "Pure jump Levy process with Levy density C e^(-G |x|) / |x|^(1 + Y) for x < 0 and C e^(-M x) / x^(1 + Y) for x > 0",
+    parameters: &[
+      ParameterInfo::real("lambda_plus", "Tempering of the negative jumps (G)", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("lambda_minus", "Tempering of the positive jumps (M)", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("alpha", "Stability index (Y)", Interval::open(0.0, 2.0), 0.7),
+      ParameterInfo::N,
+      ParameterInfo::integer("j", "Number of terms of the series representation", Interval::COUNT, 1000.0),
+      ParameterInfo::choice("method", "Simulation method", &["Series", "StableRejection"]).optional(),
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Carr, P., Geman, H., Madan, D. B., & Yor, M. (2002). The fine structure of asset returns: An empirical investigation.",
+      "Cont, R., & Tankov, P. (2004). Financial Modelling with Jump Processes.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use ndarray::Axis;
@@ -126,25 +269,90 @@ mod tests {
 
   #[test]
   fn cgmy_length_equals_n() {
-    let cgmy = CGMY::new(5.0, 5.0, 0.7, N, 1000, Some(0.0), Some(1.0), None);
+    let cgmy = CGMY::new(5.0, 5.0, 0.7, N, 1000, None, Some(0.0), Some(1.0), None);
     assert_eq!(cgmy.sample().len(), N);
   }
 
   #[test]
   fn cgmy_starts_with_x0() {
-    let cgmy = CGMY::new(5.0, 5.0, 0.7, N, 1000, Some(0.0), Some(1.0), None);
+    let cgmy = CGMY::new(5.0, 5.0, 0.7, N, 1000, None, Some(0.0), Some(1.0), None);
     assert_eq!(cgmy.sample()[0], 0.0);
   }
 
+  #[test]
+  fn cgmy_f32_series_starts_with_x0() {
+    let cgmy = CGMY::<f32>::new(5.0, 5.0, 0.7, N, 1000, None, Some(1.0), Some(1.0), None);
+    let x = cgmy.sample();
+    assert_eq!(x.len(), N);
+    assert_eq!(x[0], 1.0);
+    assert!(x.iter().all(|x| x.is_finite()));
+  }
+
+  #[test]
+  fn cgmy_stable_rejection_matches_the_cumulants() {
+    for alpha in [0.5, 1.5] {
+      let cgmy = CGMY::new(
+        5.0,
+        8.0,
+        alpha,
+        51,
+        0,
+        Some(CGMYMethod::StableRejection),
+        Some(0.0),
+        Some(1.0),
+        Some(4000),
+      );
+      assert!(cgmy.validate().is_ok());
+      let (mean, variance) = cgmy.cumulants(1.0);
+      // The drift centers the process and C normalizes the variance
+      assert!(mean.abs() < 1e-4 && (variance - 1.0).abs() < 1e-4);
+
+      let ends = cgmy.sample_par().column(50).to_owned();
+      let sample_mean = ends.mean().unwrap();
+      assert!(
+        (sample_mean - mean).abs() < 0.07,
+        "{} {}",
+        alpha,
+        sample_mean
+      );
+      assert!(
+        (ends.var(1.0) - variance).abs() < 0.12,
+        "{} {}",
+        alpha,
+        ends.var(1.0)
+      );
+    }
+  }
+
   #[test]
   fn cgmy_plot() {
-    let cgmy = CGMY::new(25.46, 4.604, 0.52, 1000, 1024, Some(2.0), Some(1.0), None);
+    let cgmy = CGMY::new(
+      25.46,
+      4.604,
+      0.52,
+      1000,
+      1024,
+      None,
+      Some(2.0),
+      Some(1.0),
+      None,
+    );
     plot_1d!(cgmy.sample(), "CGMY Process");
   }
 
   #[test]
   fn cgmy_plot_multi() {
-    let cgmy = CGMY::new(25.46, 4.604, 0.52, N, 10000, Some(2.0), Some(1.0), Some(10));
+    let cgmy = CGMY::new(
+      25.46,
+      4.604,
+      0.52,
+      N,
+      10000,
+      None,
+      Some(2.0),
+      Some(1.0),
+      Some(10),
+    );
     plot_nd!(cgmy.sample_par(), "CGMY Process");
   }
 }
@@ -5,47 +5,55 @@ use rand::Rng;
 use rand_distr::{Exp, Uniform};
 use scilib::math::basic::gamma;
 
-use crate::stochastic::{process::poisson::Poisson, Sampling};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  process::poisson::Poisson,
+  FloatExt, Sampling,
+};
 
 /// CTS process (Classical Tempered Stable process)
 /// https://sci-hub.se/https://doi.org/10.1016/j.jbankfin.2010.01.015
 ///
-#[derive(ImplNew)]
-pub struct CTS {
+#[derive(ImplNew, Clone)]
+pub struct CTS<T: FloatExt = f64> {
   /// Positive jump rate lambda_plus (corresponds to G)
-  pub lambda_plus: f64, // G
+  pub lambda_plus: T, // G
   /// Negative jump rate lambda_minus (corresponds to M)
-  pub lambda_minus: f64, // M
+  pub lambda_minus: T, // M
   /// Jump activity parameter alpha (corresponds to Y), with 0 < alpha < 2
-  pub alpha: f64,
+  pub alpha: T,
   /// Number of time steps
   pub n: usize,
   /// Jumps
   pub j: usize,
   /// Initial value
-  pub x0: Option<f64>,
+  pub x0: Option<T>,
   /// Total time horizon
-  pub t: Option<f64>,
+  pub t: Option<T>,
   /// Number of samples for parallel sampling (not used in this implementation)
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for CTS {
-  fn sample(&self) -> Array1<f64> {
+impl<T: FloatExt> Sampling<T> for CTS<T> {
+  /// The jumps of the series are drawn in double precision
+  fn sample(&self) -> Array1<T> {
     let mut rng = rand::thread_rng();
 
-    let t_max = self.t.unwrap_or(1.0);
+    let (lambda_plus, lambda_minus, alpha): (f64, f64, f64) = (
+      self.lambda_plus.into(),
+      self.lambda_minus.into(),
+      self.alpha.into(),
+    );
+    let t_max = self.t.map_or(1.0, Into::into);
     let dt = t_max / (self.n - 1) as f64;
-    let mut x = Array1::<f64>::zeros(self.n);
-    x[0] = self.x0.unwrap_or(0.0);
+    let mut x = Array1::<T>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(T::zero());
 
-    let C = (gamma(2.0 - self.alpha)
-      * (self.lambda_plus.powf(self.alpha - 2.0) + self.lambda_minus.powf(self.alpha - 2.0)))
-    .powi(-1);
+    let C = (gamma(2.0 - alpha) * (lambda_plus.powf(alpha - 2.0) + lambda_minus.powf(alpha - 2.0)))
+      .powi(-1);
 
-    let b_t = -C
-      * gamma(1.0 - self.alpha)
-      * (self.lambda_plus.powf(self.alpha - 1.0) - self.lambda_minus.powf(self.alpha - 1.0));
+    let b_t =
+      -C * gamma(1.0 - alpha) * (lambda_plus.powf(alpha - 1.0) - lambda_minus.powf(alpha - 1.0));
 
     let U = Array1::<f64>::random(self.j, Uniform::new(0.0, 1.0));
     let E = Array1::<f64>::random(self.j, Exp::new(1.0).unwrap());
@@ -61,20 +69,20 @@ impl Sampling<f64> for CTS {
       for j in 1..self.j {
         if tau[j] > t_1 && tau[j] <= t {
           let v_j = if rng.gen_bool(0.5) {
-            self.lambda_plus
+            lambda_plus
           } else {
-            -self.lambda_minus
+            -lambda_minus
           };
 
-          let term1 = (self.alpha * poisson[j] / C).powf(-1.0 / self.alpha);
-          let term2 = E[j] * U[j].powf(1.0 / self.alpha) / v_j.abs();
+          let term1 = (alpha * poisson[j] / C).powf(-1.0 / alpha);
+          let term2 = E[j] * U[j].powf(1.0 / alpha) / v_j.abs();
           let jump_size = term1.min(term2) * (v_j / v_j.abs());
 
           jump_component += jump_size;
         }
       }
 
-      x[i] = x[i - 1] + jump_component + b_t * dt;
+      x[i] = x[i - 1] + T::from_f64_(jump_component + b_t * dt);
     }
 
     x
@@ -91,6 +99,29 @@ impl Sampling<f64> for CTS {
   }
 }
 
+impl<T: FloatExt> ProcessInfo for CTS<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CTS",
+    title: "Classical tempered stable process",
+    path: "stochastic::jump::cts::CTS",
+    kind: ModelKind::Jump,
+    description: "Tempered stable Levy process sampled with the series representation of Rosinski",
+    parameters: &[
+      ParameterInfo::real("lambda_plus", "Tempering of the negative jumps (G)", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("lambda_minus", "Tempering of the positive jumps (M)", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("alpha", "Stability index (Y)", Interval::open(0.0, 2.0), 0.7),
+      ParameterInfo::N,
+      ParameterInfo::integer("j", "Number of terms of the series representation", Interval::COUNT, 1000.0),
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Kim, Y. S., Rachev, S. T., Bianchi, M. L., & Fabozzi, F. J. (2010). Tempered stable and tempered infinitely divisible GARCH models.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use ndarray::Axis;
@@ -1,26 +1,27 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
 
-use crate::stochastic::Sampling;
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  FloatExt, Sampling,
+};
 
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 
-pub struct IG {
-  pub gamma: f64,
+pub struct IG<T: FloatExt = f64> {
+  pub gamma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for IG {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+impl<T: FloatExt> Sampling<T> for IG<T> {
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
     let mut ig = Array1::zeros(self.n);
-    ig[0] = self.x0.unwrap_or(0.0);
+    ig[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
       ig[i] = ig[i - 1] + self.gamma * dt + gn[i - 1]
@@ -40,6 +41,26 @@ impl Sampling<f64> for IG {
   }
 }
 
+impl<T: FloatExt> ProcessInfo for IG<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "IG",
+    title: "Inverse Gaussian process",
+    path: "stochastic::jump::ig::IG",
+    kind: ModelKind::Jump,
+    description: "First passage time of a Brownian motion with drift gamma",
+    parameters: &[
+      ParameterInfo::real("gamma", "Drift", Interval::POSITIVE, 1.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Barndorff-Nielsen, O. E. (1997). Normal inverse Gaussian distributions and stochastic volatility modelling.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -3,34 +3,37 @@ use ndarray::{s, Array1};
 use rand_distr::Distribution;
 
 use crate::stochastic::{
-  noise::fgn::FGN, process::cpoisson::CompoundPoisson, Sampling, Sampling3D,
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::fgn::FGN,
+  process::cpoisson::CompoundPoisson,
+  FloatExt, Sampling, Sampling3D,
 };
 
-#[derive(ImplNew)]
-pub struct JumpFOU<D>
+#[derive(ImplNew, Clone)]
+pub struct JumpFOU<D, T: FloatExt = f64>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  pub mu: f64,
-  pub sigma: f64,
-  pub theta: f64,
+  pub mu: T,
+  pub sigma: T,
+  pub theta: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
-  pub fgn: FGN,
-  pub cpoisson: CompoundPoisson<D>,
+  pub fgn: FGN<T>,
+  pub cpoisson: CompoundPoisson<D, T>,
 }
 
-impl<D> Sampling<f64> for JumpFOU<D>
+impl<D, T: FloatExt> Sampling<T> for JumpFOU<D, T>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
     let fgn = self.fgn.sample();
-    let mut jump_fou = Array1::<f64>::zeros(self.n);
-    jump_fou[0] = self.x0.unwrap_or(0.0);
+    let mut jump_fou = Array1::<T>::zeros(self.n);
+    jump_fou[0] = self.x0.unwrap_or(T::zero());
 
     for i in 1..self.n {
       let [.., jumps] = self.cpoisson.sample();
@@ -55,6 +58,33 @@ where
   }
 }
 
+impl<D, T: FloatExt> ProcessInfo for JumpFOU<D, T>
+where
+  D: Distribution<T> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "JumpFOU",
+    title: "Fractional Ornstein-Uhlenbeck process with jumps",
+    path: "stochastic::jump::jump_fou::JumpFOU",
+    kind: ModelKind::Jump,
+    description: "dX(t) = theta (mu - X(t)) dt + sigma dW^H(t) + dJ(t)",
+    parameters: &[
+      ParameterInfo::real("mu", "Long-run mean", Interval::REAL, 0.0),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.3),
+      ParameterInfo::real("theta", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+      ParameterInfo::component("cpoisson", "Compound Poisson process of the jumps"),
+    ],
+    references: &[
+      "Cheridito, P., Kawaguchi, H., & Maejima, M. (2003). Fractional Ornstein-Uhlenbeck processes.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use rand_distr::Normal;
@@ -1,50 +1,61 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::{Distribution, Normal};
-
-use crate::stochastic::{process::cpoisson::CompoundPoisson, Sampling, Sampling3D};
+use num_complex::Complex64;
+use rand_distr::Distribution;
+
+use crate::{
+  quant::r#trait::CharacteristicFn,
+  stats::double_exp::DoubleExp,
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    process::cpoisson::CompoundPoisson,
+    FloatExt, Sampling, Sampling3D,
+  },
+};
 
 /// Kou process
 ///
 /// https://www.columbia.edu/~sk75/MagSci02.pdf
 ///
-#[derive(ImplNew)]
-pub struct KOU<D>
+/// With [`DoubleExp`] jump sizes and theta = [`KOU::compensator`], e^X(t) grows at the
+/// rate alpha, European options are priced with
+/// [`KouPricer`](crate::quant::pricing::kou::KouPricer).
+#[derive(ImplNew, Clone)]
+pub struct KOU<D, T: FloatExt = f64>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  pub alpha: f64,
-  pub sigma: f64,
-  pub lambda: f64,
-  pub theta: f64,
+  pub alpha: T,
+  pub sigma: T,
+  pub lambda: T,
+  pub theta: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
-  pub cpoisson: CompoundPoisson<D>,
+  pub cpoisson: CompoundPoisson<D, T>,
 }
 
-impl<D> Sampling<f64> for KOU<D>
+impl<D, T: FloatExt> Sampling<T> for KOU<D, T>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let mut merton = Array1::<f64>::zeros(self.n);
-    merton[0] = self.x0.unwrap_or(0.0);
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let mut kou = Array1::<T>::zeros(self.n);
+    kou[0] = self.x0.unwrap_or(T::zero());
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
 
     for i in 1..self.n {
       let [.., jumps] = self.cpoisson.sample();
 
-      merton[i] = merton[i - 1]
-        + (self.alpha * self.sigma.powf(2.0) / 2.0 - self.lambda * self.theta) * dt
+      kou[i] = kou[i - 1]
+        + (self.alpha - self.sigma.powi(2) / T::from_f64_(2.0) - self.lambda * self.theta) * dt
         + self.sigma * gn[i - 1]
         + jumps.sum();
     }
 
-    merton
+    kou
   }
 
   /// Number of time steps
@@ -58,11 +69,71 @@ where
   }
 }
 
+impl KOU<DoubleExp> {
+  /// E[e^J] - 1 = p eta1 / (eta1 - 1) + (1 - p) eta2 / (eta2 + 1) - 1 of the jump sizes,
+  /// the theta of the martingale compensation, finite for eta1 > 1
+  pub fn compensator(&self) -> f64 {
+    let jumps = &self.cpoisson.distribution;
+    let p = jumps.p.unwrap_or(0.5);
+
+    p * jumps.lambda_plus / (jumps.lambda_plus - 1.0)
+      + (1.0 - p) * jumps.lambda_minus / (jumps.lambda_minus + 1.0)
+      - 1.0
+  }
+}
+
+impl CharacteristicFn for KOU<DoubleExp> {
+  /// Characteristic function of X(t) - x0 in closed form, the jumps arrive at the
+  /// intensity lambda_J = 1 / lambda of the Poisson process of cpoisson, whose lambda is
+  /// the mean interarrival time
+  ///
+  /// E[e^(iuX(t))] = exp(t (iu (alpha - sigma^2 / 2 - lambda theta) - sigma^2 u^2 / 2
+  /// + lambda_J (p eta1 / (eta1 - iu) + (1 - p) eta2 / (eta2 + iu) - 1)))
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    let i = Complex64::i();
+    let jumps = &self.cpoisson.distribution;
+    let p = jumps.p.unwrap_or(0.5);
+    let (eta1, eta2) = (jumps.lambda_plus, jumps.lambda_minus);
+
+    let drift = self.alpha - 0.5 * self.sigma.powi(2) - self.lambda * self.theta;
+    let jump = p * eta1 / (eta1 - i * u) + (1.0 - p) * eta2 / (eta2 + i * u) - 1.0;
+    let intensity = 1.0 / self.cpoisson.poisson.lambda;
+
+    (t * (i * u * drift - 0.5 * self.sigma.powi(2) * u * u + intensity * jump)).exp()
+  }
+}
+
+impl<D, T: FloatExt> ProcessInfo for KOU<D, T>
+where
+  D: Distribution<T> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "KOU",
+    title: "Kou double exponential jump diffusion",
+    path: "stochastic::jump::kou::KOU",
+    kind: ModelKind::Jump,
+    description: "dX(t) = (alpha - sigma^2 / 2 - lambda theta) dt + sigma dW(t) + dJ(t) with double exponential jump sizes",
+    parameters: &[
+      ParameterInfo::real("alpha", "Drift", Interval::REAL, 0.05),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.2),
+      ParameterInfo::real("lambda", "Jump intensity", Interval::NON_NEGATIVE, 1.0),
+      ParameterInfo::real("theta", "Jump compensator", Interval::REAL, 0.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cpoisson", "Compound Poisson process of the jumps"),
+    ],
+    references: &[
+      "Kou, S. G. (2002). A jump-diffusion model for option pricing.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
     plot_1d,
-    stats::double_exp::DoubleExp,
     stochastic::{process::poisson::Poisson, N, S0, X0},
   };
 
@@ -110,6 +181,35 @@ mod tests {
     assert_eq!(kou.sample()[0], X0);
   }
 
+  #[test]
+  fn kou_moments_match_characteristic_function() {
+    let (n, t) = (51, 1.0);
+    let jumps = CompoundPoisson::new(
+      None,
+      DoubleExp::new(Some(0.4), 10.0, 5.0),
+      Poisson::new(1.0 / 3.0, None, Some(t / (n - 1) as f64), None),
+    );
+    let mut kou = KOU::new(0.1, 0.2, 3.0, 0.0, n, Some(0.0), Some(t), Some(4000), jumps);
+    kou.theta = kou.compensator();
+
+    let (mean, variance) = kou.cumulants(t);
+    // alpha - sigma^2 / 2 - lambda theta + lambda (p / eta1 - (1 - p) / eta2)
+    let expected = 0.1 - 0.02 - 3.0 * kou.theta + 3.0 * (0.04 - 0.12);
+    assert!((mean - expected).abs() < 1e-6, "{} {}", mean, expected);
+    assert!((variance - (0.04 + 3.0 * (0.008 + 0.048))).abs() < 1e-5);
+    // e^X(t) grows at the rate alpha
+    assert!((kou.cf(-Complex64::i(), t).re - 0.1f64.exp()).abs() < 1e-12);
+
+    let ends = kou.sample_par().column(n - 1).to_owned();
+    let sample_mean = ends.mean().unwrap();
+    assert!(
+      (sample_mean - mean).abs() < 0.04,
+      "{} {}",
+      sample_mean,
+      mean
+    );
+  }
+
   #[test]
   fn kou_plot() {
     let kou = KOU::new(
@@ -1,33 +1,36 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::{Distribution, Normal};
+use rand_distr::Distribution;
 
-use crate::stochastic::{process::cpoisson::CompoundPoisson, Sampling, Sampling3D};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  process::cpoisson::CompoundPoisson,
+  FloatExt, Sampling, Sampling3D,
+};
 
-#[derive(ImplNew)]
-pub struct LevyDiffusion<D>
+#[derive(ImplNew, Clone)]
+pub struct LevyDiffusion<D, T: FloatExt = f64>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  pub gamma: f64,
-  pub sigma: f64,
+  pub gamma: T,
+  pub sigma: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
-  pub cpoisson: CompoundPoisson<D>,
+  pub cpoisson: CompoundPoisson<D, T>,
 }
 
-impl<D> Sampling<f64> for LevyDiffusion<D>
+impl<D, T: FloatExt> Sampling<T> for LevyDiffusion<D, T>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let mut levy = Array1::<f64>::zeros(self.n);
-    levy[0] = self.x0.unwrap_or(0.0);
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let mut levy = Array1::<T>::zeros(self.n);
+    levy[0] = self.x0.unwrap_or(T::zero());
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
 
     for i in 1..self.n {
       let [.., jumps] = self.cpoisson.sample();
@@ -48,8 +51,33 @@ where
   }
 }
 
+impl<D, T: FloatExt> ProcessInfo for LevyDiffusion<D, T>
+where
+  D: Distribution<T> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "LevyDiffusion",
+    title: "Levy jump diffusion",
+    path: "stochastic::jump::levy_diffusion::LevyDiffusion",
+    kind: ModelKind::Jump,
+    description: "dX(t) = gamma dt + sigma dW(t) + dJ(t)",
+    parameters: &[
+      ParameterInfo::real("gamma", "Drift", Interval::REAL, 0.05),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.2),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cpoisson", "Compound Poisson process of the jumps"),
+    ],
+    references: &["Cont, R., & Tankov, P. (2004). Financial Modelling with Jump Processes."],
+  };
+}
+
 #[cfg(test)]
 mod tests {
+  use rand_distr::Normal;
+
   use crate::{
     plot_1d,
     stochastic::{process::poisson::Poisson, N, X0},
@@ -1,41 +1,44 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand_distr::{Distribution, Normal};
+use rand_distr::Distribution;
 
-use crate::stochastic::{process::cpoisson::CompoundPoisson, Sampling, Sampling3D};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  process::cpoisson::CompoundPoisson,
+  FloatExt, Sampling, Sampling3D,
+};
 
-#[derive(ImplNew)]
-pub struct Merton<D>
+#[derive(ImplNew, Clone)]
+pub struct Merton<D, T: FloatExt = f64>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  pub alpha: f64,
-  pub sigma: f64,
-  pub lambda: f64,
-  pub theta: f64,
+  pub alpha: T,
+  pub sigma: T,
+  pub lambda: T,
+  pub theta: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
-  pub cpoisson: CompoundPoisson<D>,
+  pub cpoisson: CompoundPoisson<D, T>,
 }
 
-impl<D> Sampling<f64> for Merton<D>
+impl<D, T: FloatExt> Sampling<T> for Merton<D, T>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let mut merton = Array1::<f64>::zeros(self.n);
-    merton[0] = self.x0.unwrap_or(0.0);
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let mut merton = Array1::<T>::zeros(self.n);
+    merton[0] = self.x0.unwrap_or(T::zero());
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
 
     for i in 1..self.n {
       let [.., jumps] = self.cpoisson.sample();
 
       merton[i] = merton[i - 1]
-        + (self.alpha * self.sigma.powf(2.0) / 2.0 - self.lambda * self.theta) * dt
+        + (self.alpha * self.sigma.powi(2) / T::from_f64_(2.0) - self.lambda * self.theta) * dt
         + self.sigma * gn[i - 1]
         + jumps.sum();
     }
@@ -54,8 +57,37 @@ where
   }
 }
 
+impl<D, T: FloatExt> ProcessInfo for Merton<D, T>
+where
+  D: Distribution<T> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "Merton",
+    title: "Merton jump diffusion",
+    path: "stochastic::jump::merton::Merton",
+    kind: ModelKind::Jump,
+    description: "dX(t) = (alpha - sigma^2 / 2 - lambda theta) dt + sigma dW(t) + dJ(t)",
+    parameters: &[
+      ParameterInfo::real("alpha", "Drift", Interval::REAL, 0.05),
+      ParameterInfo::real("sigma", "Volatility", Interval::POSITIVE, 0.2),
+      ParameterInfo::real("lambda", "Jump intensity", Interval::NON_NEGATIVE, 1.0),
+      ParameterInfo::real("theta", "Jump compensator", Interval::REAL, 0.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cpoisson", "Compound Poisson process of the jumps"),
+    ],
+    references: &[
+      "Merton, R. C. (1976). Option pricing when underlying stock returns are discontinuous.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
+  use rand_distr::Normal;
+
   use crate::{
     plot_1d,
     stochastic::{process::poisson::Poisson, N, S0, X0},
@@ -105,6 +137,30 @@ mod tests {
     assert_eq!(merton.sample()[0], X0);
   }
 
+  #[test]
+  fn merton_f32_starts_with_x0() {
+    let merton = Merton::new(
+      2.25_f32,
+      2.5,
+      1.0,
+      1.0,
+      N,
+      Some(1.0),
+      Some(1.0),
+      None,
+      CompoundPoisson::new(
+        None,
+        Normal::new(0.0_f32, 2.0).unwrap(),
+        Poisson::new(1.0, None, Some(1.0 / N as f32), None),
+      ),
+    );
+    let x = merton.sample();
+
+    assert_eq!(x.len(), N);
+    assert_eq!(x[0], 1.0);
+    assert!(x.iter().all(|x| x.is_finite()));
+  }
+
   #[test]
   fn merton_plot() {
     let merton = Merton::new(
@@ -1,37 +1,46 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::{rand_distr::InverseGaussian, RandomExt};
-use rand_distr::Normal;
+use num_complex::Complex64;
 
-use crate::stochastic::Sampling;
+use crate::{
+  quant::r#trait::CharacteristicFn,
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    process::{subordinator::IGSubordinator, time_changed_bm::TimeChangedBM},
+    FloatExt, Sampling,
+  },
+};
 
-#[derive(ImplNew)]
-
-pub struct NIG {
-  pub theta: f64,
-  pub sigma: f64,
-  pub kappa: f64,
+/// Normal inverse Gaussian process, a Brownian motion with drift theta and volatility
+/// sigma time-changed by the inverse Gaussian subordinator of variance rate kappa, see
+/// [`NIG::time_changed_bm`]
+#[derive(ImplNew, Clone)]
+pub struct NIG<T: FloatExt = f64> {
+  pub theta: T,
+  pub sigma: T,
+  pub kappa: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for NIG {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let scale = dt.powf(2.0) / self.kappa;
-    let mean = dt / scale;
-    let ig = Array1::random(self.n - 1, InverseGaussian::new(mean, scale).unwrap());
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
-    let mut nig = Array1::zeros(self.n);
-    nig[0] = self.x0.unwrap_or(0.0);
-
-    for i in 1..self.n {
-      nig[i] = nig[i - 1] + self.theta * ig[i - 1] + self.sigma * ig[i - 1].sqrt() * gn[i - 1]
-    }
-
-    nig
+impl<T: FloatExt> NIG<T> {
+  /// theta I(t) + sigma W(I(t)) with the inverse Gaussian subordinator I
+  pub fn time_changed_bm(&self) -> TimeChangedBM<IGSubordinator<T>, T> {
+    TimeChangedBM::new(
+      self.theta,
+      self.sigma,
+      IGSubordinator::new(self.kappa, self.n, self.t, None),
+      self.x0,
+      self.m,
+    )
+  }
+}
+
+impl<T: FloatExt> Sampling<T> for NIG<T> {
+  fn sample(&self) -> Array1<T> {
+    self.time_changed_bm().sample()
   }
 
   /// Number of time steps
@@ -45,6 +54,36 @@ impl Sampling<f64> for NIG {
   }
 }
 
+impl<T: FloatExt> CharacteristicFn for NIG<T> {
+  /// exp(t / kappa (1 - sqrt(1 - 2iu theta kappa + u^2 sigma^2 kappa))), the subordinator
+  /// has mean t and variance kappa t
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    self.time_changed_bm().cf(u, t)
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for NIG<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "NIG",
+    title: "Normal inverse Gaussian process",
+    path: "stochastic::jump::nig::NIG",
+    kind: ModelKind::Jump,
+    description: "X(t) = theta I(t) + sigma W(I(t)) with an inverse Gaussian subordinator I",
+    parameters: &[
+      ParameterInfo::real("theta", "Drift of the subordinated Brownian motion", Interval::REAL, -0.1),
+      ParameterInfo::real("sigma", "Volatility of the subordinated Brownian motion", Interval::POSITIVE, 0.2),
+      ParameterInfo::real("kappa", "Variance rate of the subordinator", Interval::POSITIVE, 0.2),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Barndorff-Nielsen, O. E. (1997). Normal inverse Gaussian distributions and stochastic volatility modelling.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -66,6 +105,22 @@ mod tests {
     assert_eq!(nig.sample()[0], X0);
   }
 
+  #[test]
+  fn nig_moments_match_the_cf() {
+    let nig = NIG::new(-0.1, 0.2, 0.2, 51, Some(0.0), Some(1.0), Some(4000));
+    let (mean, variance) = nig.cumulants(1.0);
+    // exp(t / kappa (1 - sqrt(1 - 2iu theta kappa + u^2 sigma^2 kappa)))
+    let i = Complex64::i();
+    let u = Complex64::new(1.5, 0.0);
+    let closed =
+      (1.0 / 0.2 * (1.0 - (1.0 - 2.0 * i * u * -0.1 * 0.2 + u * u * 0.04 * 0.2).sqrt())).exp();
+    assert!((nig.cf(u, 1.0) - closed).norm() < 1e-12);
+
+    let ends = nig.sample_par().column(50).to_owned();
+    assert!((ends.mean().unwrap() - mean).abs() < 0.015);
+    assert!((ends.var(1.0) - variance).abs() < 0.006);
+  }
+
   #[test]
   fn nig_plot() {
     let nig = NIG::new(2.25, 2.5, 1.0, N, Some(X0), Some(100.0), None);
@@ -0,0 +1,506 @@
+//! Alpha-stable and tempered stable Lévy processes.
+//!
+//! The increments of the alpha-stable process over dt are S_alpha(sigma dt^(1 / alpha),
+//! beta, mu dt) in the parametrization of Samorodnitsky and Taqqu,
+//!
+//! ln E[e^(iuX(t))] = t (-sigma^alpha |u|^alpha (1 - i beta sign(u) tan(pi alpha / 2)) + i mu u)
+//!
+//! for alpha != 1 and t (-sigma |u| (1 + i beta 2 / pi sign(u) ln|u|) + i mu u) for alpha = 1,
+//! and are drawn by the method of Chambers, Mallows and Stuck. The tails decay like
+//! |x|^(-alpha), so the variance is infinite for alpha < 2 and the mean for alpha <= 1.
+//!
+//! Tempering the Lévy density by e^(-lambda |x|) restores all moments, the tempered stable
+//! increments are stable variables accepted with probability e^(-lambda x). The density,
+//! the distribution function and the quantiles of both come from the characteristic
+//! function by [`stats::inversion`](crate::stats::inversion).
+//!
+//! - Chambers, J. M., Mallows, C. L., & Stuck, B. W. (1976). A method for simulating stable random variables.
+//! - Samorodnitsky, G., & Taqqu, M. S. (1994). Stable Non-Gaussian Random Processes.
+//! - Baeumer, B., & Meerschaert, M. M. (2010). Tempered stable Lévy motion and transient super-diffusion.
+
+use std::{
+  f64::consts::{FRAC_PI_2, PI},
+  ops::Bound,
+};
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use num_complex::Complex64;
+use rand::Rng;
+use rand_distr::Exp1;
+use statrs::function::gamma::gamma;
+
+use crate::{
+  error::{ensure, StochasticResult},
+  quant::r#trait::CharacteristicFn,
+  stats::inversion::{cdf_from_cf, pdf_from_cf, quantile_from_cf},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    Distribution, FloatExt, Sampling,
+  },
+};
+
+/// Standard stable variable S_alpha(1, beta, 0) by Chambers-Mallows-Stuck
+pub fn sample_stable<R: Rng>(alpha: f64, beta: f64, rng: &mut R) -> f64 {
+  let v = rng.gen_range(-FRAC_PI_2..FRAC_PI_2);
+  let w: f64 = rng.sample(Exp1);
+
+  if alpha == 1.0 {
+    let b = FRAC_PI_2 + beta * v;
+    return (b * v.tan() - beta * (FRAC_PI_2 * w * v.cos() / b).ln()) / FRAC_PI_2;
+  }
+
+  let tan = beta * (FRAC_PI_2 * alpha).tan();
+  let b = tan.atan() / alpha;
+  let s = (1.0 + tan * tan).powf(0.5 / alpha);
+
+  s * (alpha * (v + b)).sin() / v.cos().powf(1.0 / alpha)
+    * ((v - alpha * (v + b)).cos() / w).powf((1.0 - alpha) / alpha)
+}
+
+/// Sum over dt of the jumps of the Lévy density c e^(-lambda x) / x^(1 + alpha) on x > 0,
+/// without compensation, E[e^(-s X)] = e^(dt c Gamma(-alpha) ((lambda + s)^alpha - lambda^alpha))
+/// and the mean dt c Gamma(1 - alpha) lambda^(alpha - 1), alpha in (0, 2) and alpha != 1
+///
+/// A totally skewed stable variable is accepted with probability e^(-lambda x), exactly for
+/// alpha < 1. For alpha > 1 it is not bounded below and the bound is shifted by 4 scales
+/// beyond its location, the values further in the left tail, of probability of order 1e-5,
+/// are accepted without tempering. The expected number of trials grows with lambda times
+/// the scale (c dt)^(1 / alpha).
+pub fn sample_tempered_stable<R: Rng>(
+  alpha: f64,
+  c: f64,
+  lambda: f64,
+  dt: f64,
+  rng: &mut R,
+) -> f64 {
+  // S_alpha(scale, 1, 0) has E[e^(-s S)] = e^(-(scale s)^alpha / cos(pi alpha / 2))
+  let scale = (-dt * c * gamma(-alpha) * (FRAC_PI_2 * alpha).cos()).powf(1.0 / alpha);
+  let shift = match alpha < 1.0 {
+    true => 0.0,
+    false => scale * (4.0 - (FRAC_PI_2 * alpha).tan()),
+  };
+
+  loop {
+    let x = scale * sample_stable(alpha, 1.0, rng);
+    if rng.gen::<f64>() <= (-lambda * (x + shift)).exp() {
+      return x;
+    }
+  }
+}
+
+/// Alpha-stable Lévy process with stability index alpha, skewness beta, scale sigma and
+/// drift mu per unit of time
+#[derive(ImplNew, Clone)]
+pub struct StableProcess<T: FloatExt = f64> {
+  /// Stability index in (0, 2], 2 is the Brownian motion with variance 2 sigma^2 t
+  pub alpha: T,
+  /// Skewness in [-1, 1]
+  pub beta: T,
+  /// Scale of X(1)
+  pub sigma: T,
+  /// Location of X(1)
+  pub mu: T,
+  pub n: usize,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  pub m: Option<usize>,
+}
+
+impl<T: FloatExt> Sampling<T> for StableProcess<T> {
+  /// The stable variables are drawn in double precision
+  fn sample(&self) -> Array1<T> {
+    let mut rng = rand::thread_rng();
+    let (alpha, beta, sigma, mu): (f64, f64, f64, f64) = (
+      self.alpha.into(),
+      self.beta.into(),
+      self.sigma.into(),
+      self.mu.into(),
+    );
+    let dt = self.t.map_or(1.0, Into::into) / (self.n - 1) as f64;
+
+    // S_alpha(scale, beta, mu dt) = scale S_alpha(1, beta, 0) + shift
+    let scale = sigma * dt.powf(1.0 / alpha);
+    let shift = match alpha == 1.0 {
+      true => mu * dt + 2.0 / PI * beta * scale * scale.ln(),
+      false => mu * dt,
+    };
+
+    let mut x = Array1::<T>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(T::zero());
+
+    for i in 1..self.n {
+      x[i] = x[i - 1] + T::from_f64_(scale * sample_stable(alpha, beta, &mut rng) + shift);
+    }
+
+    x
+  }
+
+  /// Check the stability index, the skewness and the scale
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.alpha > T::zero() && self.alpha <= T::from_f64_(2.0),
+      "alpha must be in (0, 2]",
+    )?;
+    ensure(self.beta.abs() <= T::one(), "beta must be in [-1, 1]")?;
+    ensure(self.sigma > T::zero(), "sigma must be positive")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl CharacteristicFn for StableProcess {
+  /// e^(t (-sigma^alpha |u|^alpha (1 - i beta sign(u) tan(pi alpha / 2)) + i mu u)) for real u,
+  /// the real part of u is used
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    let u = u.re;
+    if u == 0.0 {
+      return Complex64::new(1.0, 0.0);
+    }
+    let i = Complex64::i();
+    let skew = match self.alpha == 1.0 {
+      true => -self.beta * 2.0 / PI * u.signum() * u.abs().ln(),
+      false => self.beta * u.signum() * (FRAC_PI_2 * self.alpha).tan(),
+    };
+
+    (t * (-(self.sigma * u.abs()).powf(self.alpha) * (1.0 - i * skew) + i * self.mu * u)).exp()
+  }
+
+  /// The moments do not exist for alpha < 2, the location mu t and the scale
+  /// sigma t^(1 / alpha)
+  fn location_scale(&self, t: f64) -> (f64, f64) {
+    (self.mu * t, self.sigma * t.powf(1.0 / self.alpha))
+  }
+}
+
+impl Distribution for StableProcess {
+  /// Characteristic function of X(t)
+  fn characteristic_function(&self, u: f64) -> Complex64 {
+    (Complex64::i() * u * self.x0.unwrap_or(0.0)).exp()
+      * self.cf(Complex64::new(u, 0.0), self.t.unwrap_or(1.0))
+  }
+
+  /// Density of X(t) by inversion of the characteristic function
+  fn pdf(&self, x: f64) -> f64 {
+    pdf_from_cf(self, self.t.unwrap_or(1.0), x - self.x0.unwrap_or(0.0))
+  }
+
+  /// Distribution function of X(t) by inversion of the characteristic function
+  fn cdf(&self, x: f64) -> f64 {
+    cdf_from_cf(self, self.t.unwrap_or(1.0), x - self.x0.unwrap_or(0.0))
+  }
+
+  /// Quantile of X(t) by bisection of the distribution function
+  fn inv_cdf(&self, p: f64) -> f64 {
+    self.x0.unwrap_or(0.0) + quantile_from_cf(self, self.t.unwrap_or(1.0), p)
+  }
+
+  /// Mean of X(t), infinite or undefined for alpha <= 1
+  fn mean(&self) -> f64 {
+    match self.alpha > 1.0 {
+      true => self.x0.unwrap_or(0.0) + self.mu * self.t.unwrap_or(1.0),
+      false => f64::NAN,
+    }
+  }
+
+  /// Median of X(t), the location for beta = 0
+  fn median(&self) -> f64 {
+    match self.beta == 0.0 {
+      true => self.x0.unwrap_or(0.0) + self.mu * self.t.unwrap_or(1.0),
+      false => self.inv_cdf(0.5),
+    }
+  }
+
+  /// Variance of X(t), infinite for alpha < 2
+  fn variance(&self) -> f64 {
+    match self.alpha == 2.0 {
+      true => 2.0 * self.sigma.powi(2) * self.t.unwrap_or(1.0),
+      false => f64::INFINITY,
+    }
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for StableProcess<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "StableProcess",
+    title: "Alpha-stable Lévy process",
+    path: "stochastic::jump::stable::StableProcess",
+    kind: ModelKind::Jump,
+    description: "Levy process with alpha-stable increments S_alpha(sigma dt^(1 / alpha), beta, mu dt)",
+    parameters: &[
+      ParameterInfo::real("alpha", "Stability index", Interval::new(Bound::Excluded(0.0), Bound::Included(2.0)), 1.7),
+      ParameterInfo::real("beta", "Skewness", Interval::CORRELATION, 0.0),
+      ParameterInfo::real("sigma", "Scale of X(1)", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("mu", "Location of X(1)", Interval::REAL, 0.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Chambers, J. M., Mallows, C. L., & Stuck, B. W. (1976). A method for simulating stable random variables.",
+      "Samorodnitsky, G., & Taqqu, M. S. (1994). Stable Non-Gaussian Random Processes.",
+    ],
+  };
+}
+
+/// Tempered stable Lévy process with the Lévy density
+/// c_plus e^(-lambda_plus x) / x^(1 + alpha) for x > 0 and
+/// c_minus e^(-lambda_minus |x|) / |x|^(1 + alpha) for x < 0, compensated to the mean mu t
+#[derive(ImplNew, Clone)]
+pub struct TemperedStable<T: FloatExt = f64> {
+  /// Stability index in (0, 2), alpha != 1
+  pub alpha: T,
+  /// Intensity of the positive jumps
+  pub c_plus: T,
+  /// Intensity of the negative jumps
+  pub c_minus: T,
+  /// Tempering of the positive jumps
+  pub lambda_plus: T,
+  /// Tempering of the negative jumps
+  pub lambda_minus: T,
+  /// Mean of X(1)
+  pub mu: T,
+  pub n: usize,
+  pub x0: Option<T>,
+  pub t: Option<T>,
+  pub m: Option<usize>,
+}
+
+impl TemperedStable {
+  /// k-th cumulant of X(1) for k >= 2, Gamma(k - alpha) (c_plus lambda_plus^(alpha - k)
+  /// + (-1)^k c_minus lambda_minus^(alpha - k))
+  pub fn cumulant(&self, k: i32) -> f64 {
+    let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+    gamma(k as f64 - self.alpha)
+      * (self.c_plus * self.lambda_plus.powf(self.alpha - k as f64)
+        + sign * self.c_minus * self.lambda_minus.powf(self.alpha - k as f64))
+  }
+}
+
+impl<T: FloatExt> Sampling<T> for TemperedStable<T> {
+  /// The one-sided sums are drawn in double precision
+  fn sample(&self) -> Array1<T> {
+    let mut rng = rand::thread_rng();
+    let (alpha, c_plus, c_minus): (f64, f64, f64) =
+      (self.alpha.into(), self.c_plus.into(), self.c_minus.into());
+    let (lambda_plus, lambda_minus): (f64, f64) =
+      (self.lambda_plus.into(), self.lambda_minus.into());
+    let dt = self.t.map_or(1.0, Into::into) / (self.n - 1) as f64;
+
+    // The one-sided sums have the means c Gamma(1 - alpha) lambda^(alpha - 1) dt
+    let compensator = gamma(1.0 - alpha)
+      * (c_plus * lambda_plus.powf(alpha - 1.0) - c_minus * lambda_minus.powf(alpha - 1.0))
+      * dt;
+    let drift = self.mu.into() * dt - compensator;
+
+    let mut x = Array1::<T>::zeros(self.n);
+    x[0] = self.x0.unwrap_or(T::zero());
+
+    for i in 1..self.n {
+      x[i] = x[i - 1]
+        + T::from_f64_(
+          sample_tempered_stable(alpha, c_plus, lambda_plus, dt, &mut rng)
+            - sample_tempered_stable(alpha, c_minus, lambda_minus, dt, &mut rng)
+            + drift,
+        );
+    }
+
+    x
+  }
+
+  /// Check the stability index, the intensities and the tempering
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.alpha > T::zero() && self.alpha < T::from_f64_(2.0) && self.alpha != T::one(),
+      "alpha must be in (0, 2) and not 1",
+    )?;
+    ensure(
+      self.c_plus > T::zero() && self.c_minus > T::zero(),
+      "c_plus and c_minus must be positive",
+    )?;
+    ensure(
+      self.lambda_plus > T::zero() && self.lambda_minus > T::zero(),
+      "lambda_plus and lambda_minus must be positive",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl CharacteristicFn for TemperedStable {
+  /// e^(t (c_plus Gamma(-alpha) ((lambda_plus - iu)^alpha - lambda_plus^alpha + iu alpha
+  /// lambda_plus^(alpha - 1)) + c_minus Gamma(-alpha) ((lambda_minus + iu)^alpha
+  /// - lambda_minus^alpha - iu alpha lambda_minus^(alpha - 1)) + iu mu))
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    let (a, lp, lm) = (self.alpha, self.lambda_plus, self.lambda_minus);
+    let iu = Complex64::i() * u;
+    let plus = (lp - iu).powf(a) - lp.powf(a) + iu * a * lp.powf(a - 1.0);
+    let minus = (lm + iu).powf(a) - lm.powf(a) - iu * a * lm.powf(a - 1.0);
+
+    (t * (gamma(-a) * (self.c_plus * plus + self.c_minus * minus) + iu * self.mu)).exp()
+  }
+
+  /// Mean and variance of X(t) in closed form
+  fn cumulants(&self, t: f64) -> (f64, f64) {
+    (self.mu * t, self.cumulant(2) * t)
+  }
+}
+
+impl Distribution for TemperedStable {
+  /// Characteristic function of X(t)
+  fn characteristic_function(&self, u: f64) -> Complex64 {
+    (Complex64::i() * u * self.x0.unwrap_or(0.0)).exp()
+      * self.cf(Complex64::new(u, 0.0), self.t.unwrap_or(1.0))
+  }
+
+  /// Density of X(t) by inversion of the characteristic function
+  fn pdf(&self, x: f64) -> f64 {
+    pdf_from_cf(self, self.t.unwrap_or(1.0), x - self.x0.unwrap_or(0.0))
+  }
+
+  /// Distribution function of X(t) by inversion of the characteristic function
+  fn cdf(&self, x: f64) -> f64 {
+    cdf_from_cf(self, self.t.unwrap_or(1.0), x - self.x0.unwrap_or(0.0))
+  }
+
+  /// Quantile of X(t) by bisection of the distribution function
+  fn inv_cdf(&self, p: f64) -> f64 {
+    self.x0.unwrap_or(0.0) + quantile_from_cf(self, self.t.unwrap_or(1.0), p)
+  }
+
+  /// Mean of X(t)
+  fn mean(&self) -> f64 {
+    self.x0.unwrap_or(0.0) + self.mu * self.t.unwrap_or(1.0)
+  }
+
+  /// Variance of X(t)
+  fn variance(&self) -> f64 {
+    self.cumulant(2) * self.t.unwrap_or(1.0)
+  }
+
+  /// Skewness of X(t)
+  fn skewness(&self) -> f64 {
+    self.cumulant(3) / (self.cumulant(2).powf(1.5) * self.t.unwrap_or(1.0).sqrt())
+  }
+
+  /// Excess kurtosis of X(t)
+  fn kurtosis(&self) -> f64 {
+    self.cumulant(4) / (self.cumulant(2).powi(2) * self.t.unwrap_or(1.0))
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for TemperedStable<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "TemperedStable",
+    title: "Tempered stable process",
+    path: "stochastic::jump::stable::TemperedStable",
+    kind: ModelKind::Jump,
+    description: "Pure jump Levy process with Levy density c_+- e^(-lambda_+- |x|) / |x|^(1 + alpha) and mean mu t",
+    parameters: &[
+      ParameterInfo::real("alpha", "Stability index, not 1", Interval::open(0.0, 2.0), 0.7),
+      ParameterInfo::real("c_plus", "Intensity of the positive jumps", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("c_minus", "Intensity of the negative jumps", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("lambda_plus", "Tempering of the positive jumps", Interval::POSITIVE, 8.0),
+      ParameterInfo::real("lambda_minus", "Tempering of the negative jumps", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("mu", "Mean of X(1)", Interval::REAL, 0.0),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Rosiński, J. (2007). Tempering stable processes.",
+      "Baeumer, B., & Meerschaert, M. M. (2010). Tempered stable Lévy motion and transient super-diffusion.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stable_starts_with_x0() {
+    let stable = StableProcess::new(1.5, 0.5, 1.0, 0.0, 100, Some(1.0), None, None);
+    let path = stable.sample();
+    assert_eq!(path.len(), 100);
+    assert_eq!(path[0], 1.0);
+  }
+
+  #[test]
+  fn cauchy_distribution() {
+    // alpha = 1, beta = 0 is the Cauchy distribution of location mu t and scale sigma t
+    let cauchy = StableProcess::new(1.0, 0.0, 0.5, 0.2, 2, None, Some(2.0), None);
+    for x in [-3.0, -0.5, 0.4, 1.0, 6.0] {
+      let z: f64 = (x - 0.4) / 1.0;
+      assert!((cauchy.pdf(x) - 1.0 / (PI * (1.0 + z * z))).abs() < 1e-6);
+      assert!((cauchy.cdf(x) - (0.5 + z.atan() / PI)).abs() < 1e-6);
+    }
+    let q = cauchy.inv_cdf(0.9);
+    assert!((q - (0.4 + (PI * 0.4).tan())).abs() < 1e-6);
+  }
+
+  #[test]
+  fn stable_paths_match_the_cdf() {
+    for (alpha, beta) in [(1.5, 0.5), (0.8, -0.7), (1.0, 0.6), (2.0, 0.0)] {
+      let stable = StableProcess::new(alpha, beta, 0.4, 0.1, 51, None, Some(2.0), Some(4000));
+      let ends = stable.sample_par().column(50).to_owned();
+
+      // Kolmogorov-Smirnov distance at the quartiles, 5 standard errors
+      for p in [0.25, 0.5, 0.75] {
+        let q = stable.inv_cdf(p);
+        let empirical = ends.iter().filter(|&&x| x <= q).count() as f64 / ends.len() as f64;
+        assert!(
+          (empirical - p).abs() < 0.035,
+          "{} {} {} {}",
+          alpha,
+          beta,
+          p,
+          empirical
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn tempered_stable_matches_the_cumulants() {
+    for alpha in [0.6, 1.4] {
+      let tempered =
+        TemperedStable::new(alpha, 1.0, 1.5, 8.0, 5.0, 0.1, 51, None, None, Some(4000));
+      let (mean, variance) = tempered.cumulants(1.0);
+      // Central differences of the cumulant generating function
+      let cf = |u: f64| tempered.cf(Complex64::new(u, 0.0), 1.0).ln();
+      let h = 1e-3;
+      assert!(((cf(h) - cf(-h)).im / (2.0 * h) - mean).abs() < 1e-5);
+      assert!((-(cf(h) + cf(-h)).re / (h * h) - variance).abs() < 1e-5);
+
+      let ends = tempered.sample_par().column(50).to_owned();
+      let sd = variance.sqrt();
+      assert!((ends.mean().unwrap() - mean).abs() < 5.0 * sd / 4000f64.sqrt());
+      assert!(
+        (ends.var(1.0) / variance - 1.0).abs() < 0.12,
+        "{} {}",
+        alpha,
+        ends.var(1.0)
+      );
+      assert!((tempered.cdf(tempered.inv_cdf(0.3)) - 0.3).abs() < 1e-6);
+    }
+  }
+}
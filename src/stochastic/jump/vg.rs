@@ -1,40 +1,45 @@
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
-use ndarray_rand::rand_distr::Gamma;
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
-
-use crate::stochastic::Sampling;
-
-#[derive(ImplNew)]
-pub struct VG {
-  pub mu: f64,
-  pub sigma: f64,
-  pub nu: f64,
+use num_complex::Complex64;
+
+use crate::{
+  quant::r#trait::CharacteristicFn,
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    process::{subordinator::GammaSubordinator, time_changed_bm::TimeChangedBM},
+    Distribution, FloatExt, Sampling,
+  },
+};
+
+/// Variance gamma process, a Brownian motion with drift mu and volatility sigma
+/// time-changed by the gamma subordinator of variance rate nu, see [`VG::time_changed_bm`]
+#[derive(ImplNew, Clone)]
+pub struct VG<T: FloatExt = f64> {
+  pub mu: T,
+  pub sigma: T,
+  pub nu: T,
   pub n: usize,
-  pub x0: Option<f64>,
-  pub t: Option<f64>,
+  pub x0: Option<T>,
+  pub t: Option<T>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for VG {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-
-    let shape = dt / self.nu;
-    let scale = self.nu;
-
-    let mut vg = Array1::<f64>::zeros(self.n);
-    vg[0] = self.x0.unwrap_or(0.0);
-
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
-    let gammas = Array1::random(self.n - 1, Gamma::new(shape, scale).unwrap());
-
-    for i in 1..self.n {
-      vg[i] = vg[i - 1] + self.mu * gammas[i - 1] + self.sigma * gammas[i - 1].sqrt() * gn[i - 1];
-    }
+impl<T: FloatExt> VG<T> {
+  /// mu G(t) + sigma W(G(t)) with the gamma subordinator G
+  pub fn time_changed_bm(&self) -> TimeChangedBM<GammaSubordinator<T>, T> {
+    TimeChangedBM::new(
+      self.mu,
+      self.sigma,
+      GammaSubordinator::new(self.nu, self.n, self.t, None),
+      self.x0,
+      self.m,
+    )
+  }
+}
 
-    vg
+impl<T: FloatExt> Sampling<T> for VG<T> {
+  fn sample(&self) -> Array1<T> {
+    self.time_changed_bm().sample()
   }
 
   /// Number of time steps
@@ -48,6 +53,75 @@ impl Sampling<f64> for VG {
   }
 }
 
+impl Distribution for VG {
+  /// Characteristic function of X(t)
+  ///
+  /// E[e^(iuX(t))] = e^(iu x0) (1 - iu mu nu + sigma^2 nu u^2 / 2)^(-t / nu)
+  fn characteristic_function(&self, u: f64) -> Complex64 {
+    let t = self.t.unwrap_or(1.0);
+    let i = Complex64::i();
+    let base = 1.0 - i * u * self.mu * self.nu + 0.5 * self.sigma.powi(2) * self.nu * u.powi(2);
+
+    (i * u * self.x0.unwrap_or(0.0)).exp() * base.powf(-t / self.nu)
+  }
+
+  /// Moment generating function of X(t), infinite outside of its domain
+  fn moment_generating_function(&self, u: f64) -> f64 {
+    self.cumulant_generating_function(u).exp()
+  }
+
+  /// K(u) = x0 u - t / nu ln(1 - mu nu u - sigma^2 nu u^2 / 2)
+  fn cumulant_generating_function(&self, u: f64) -> f64 {
+    let t = self.t.unwrap_or(1.0);
+    let base = 1.0 - self.mu * self.nu * u - 0.5 * self.sigma.powi(2) * self.nu * u.powi(2);
+
+    match base > 0.0 {
+      true => self.x0.unwrap_or(0.0) * u - t / self.nu * base.ln(),
+      false => f64::INFINITY,
+    }
+  }
+
+  /// Mean of X(t)
+  fn mean(&self) -> f64 {
+    self.x0.unwrap_or(0.0) + self.mu * self.t.unwrap_or(1.0)
+  }
+
+  /// Variance of X(t)
+  fn variance(&self) -> f64 {
+    (self.sigma.powi(2) + self.mu.powi(2) * self.nu) * self.t.unwrap_or(1.0)
+  }
+}
+
+impl<T: FloatExt> CharacteristicFn for VG<T> {
+  /// (1 - iu mu nu + sigma^2 nu u^2 / 2)^(-t / nu) for complex u, from the Laplace exponent
+  /// of the gamma subordinator
+  fn cf(&self, u: Complex64, t: f64) -> Complex64 {
+    self.time_changed_bm().cf(u, t)
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for VG<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "VG",
+    title: "Variance gamma process",
+    path: "stochastic::jump::vg::VG",
+    kind: ModelKind::Jump,
+    description: "X(t) = mu G(t) + sigma W(G(t)) with a gamma subordinator G of variance rate nu",
+    parameters: &[
+      ParameterInfo::real("mu", "Drift of the subordinated Brownian motion", Interval::REAL, -0.1),
+      ParameterInfo::real("sigma", "Volatility of the subordinated Brownian motion", Interval::POSITIVE, 0.2),
+      ParameterInfo::real("nu", "Variance rate of the subordinator", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::X0,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Madan, D. B., Carr, P., & Chang, E. C. (1998). The variance gamma process and option pricing.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -69,6 +143,14 @@ mod tests {
     assert_eq!(vg.sample()[0], X0);
   }
 
+  #[test]
+  fn vg_cumulants_from_cf() {
+    let vg = VG::new(-0.1, 0.2, 0.3, N, None, Some(1.0), None);
+    let (mean, variance) = vg.cumulants(1.0);
+    assert!((mean - vg.mean()).abs() < 1e-6);
+    assert!((variance - vg.variance()).abs() < 1e-6);
+  }
+
   #[test]
   fn vg_plot() {
     let vg = VG::new(2.25, 2.5, 1.0, N, Some(X0), None, None);
@@ -1,4 +1,4 @@
-use ndarray::Array1;
+use ndarray::{Array1, ArrayView1};
 
 use crate::stochastic::Sampling2D;
 
@@ -64,3 +64,51 @@ impl<T: Sampling2D<f64>> Malliavin2D for T {
     self.sample()
   }
 }
+
+/// Malliavin weights of the sensitivities of E[f(S(T))] to S(0) and v(0) in the Heston model.
+///
+/// With dW1 = rho dW2 + sqrt(1 - rho^2) dW⊥, integrating by parts in the direction of W⊥
+/// with the derivatives D_r S(T) = sqrt(v(r)) S(T) and D_r v(T) = sigma sqrt(v(r)) Y(T) / Y(r)
+/// of [`Sampling2D::malliavin`], where Y = dv / dv(0), gives
+///
+/// d/dS(0) E[f(S(T))] = E[f(S(T)) pi / S(0)]
+/// d/dv(0) E[f(S(T))] = E[f(S(T)) (G pi - int Y dt / (2 V))]
+///
+/// with V = int v dt, pi = int sqrt(v) dW⊥ / (sqrt(1 - rho^2) V) and G = d ln S(T) / dv(0).
+///
+/// - `s`: price path
+/// - `malliavin`: D_r S(T) and D_r v(T) on the time grid of the path
+/// - `dw1`, `dw_perp`: increments of W1 and W⊥ over the time steps
+///
+/// Returns the delta and the v(0) sensitivity weights.
+pub fn heston_weights(
+  s: &Array1<f64>,
+  malliavin: &[Array1<f64>; 2],
+  dw1: ArrayView1<f64>,
+  dw_perp: ArrayView1<f64>,
+  rho: f64,
+  dt: f64,
+) -> [f64; 2] {
+  let (s0, s_t) = (s[0], s[s.len() - 1]);
+  let rho_bar = (1.0 - rho.powi(2)).sqrt();
+  let [d_s, d_v] = malliavin;
+
+  // d ln S(T) / dv(0), integrated variance, int sqrt(v) dW⊥ and int Y dt
+  let (mut g, mut variance, mut weighted_noise, mut y_sum) = (0.0, 0.0, 0.0, 0.0);
+
+  for i in 0..dw1.len() {
+    let sqrt_v = d_s[i] / s_t;
+    variance += sqrt_v.powi(2) * dt;
+    weighted_noise += sqrt_v * dw_perp[i];
+
+    if sqrt_v > 0.0 {
+      // Y(r) = sqrt(v(r)) D_0 v(T) / (sqrt(v(0)) D_r v(T)) as Y(0) = 1
+      let y = d_s[i] / d_s[0] * d_v[0] / d_v[i];
+      g += -0.5 * y * dt + 0.5 * y / sqrt_v * dw1[i];
+      y_sum += y * dt;
+    }
+  }
+
+  let pi = weighted_noise / (rho_bar * variance);
+  [pi / s0, g * pi - y_sum / (2.0 * variance)]
+}
@@ -1,3 +1,6 @@
 pub mod cfgns;
 pub mod cgns;
+pub mod cgnsd;
 pub mod fgn;
+pub mod qmc;
+pub mod spacetime;
@@ -1,7 +1,13 @@
 use impl_new_derive::ImplNew;
 use ndarray::{Array1, Array2};
 
-use crate::stochastic::{Sampling, Sampling2D};
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    Sampling, Sampling2D,
+  },
+};
 
 use super::fgn::FGN;
 
@@ -17,14 +23,7 @@ pub struct CFGNS {
 
 impl Sampling2D<f64> for CFGNS {
   fn sample(&self) -> [Array1<f64>; 2] {
-    assert!(
-      (0.0..=1.0).contains(&self.hurst),
-      "Hurst parameter must be in (0, 1)"
-    );
-    assert!(
-      (-1.0..=1.0).contains(&self.rho),
-      "Correlation coefficient must be in [-1, 1]"
-    );
+    or_panic(self.validate());
 
     let mut cfgns = Array2::<f64>::zeros((2, self.n));
     let fgn1 = self.fgn.sample();
@@ -38,6 +37,18 @@ impl Sampling2D<f64> for CFGNS {
     [cfgns.row(0).into_owned(), cfgns.row(1).into_owned()]
   }
 
+  /// Check the Hurst parameter and the correlation coefficient
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      (0.0..=1.0).contains(&self.hurst),
+      "Hurst parameter must be in (0, 1)",
+    )?;
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -48,3 +59,24 @@ impl Sampling2D<f64> for CFGNS {
     self.m
   }
 }
+
+impl ProcessInfo for CFGNS {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CFGNS",
+    title: "Correlated fractional Gaussian noise",
+    path: "stochastic::noise::cfgns::CFGNS",
+    kind: ModelKind::Noise,
+    description: "Two fractional Gaussian noises with correlation rho",
+    parameters: &[
+      ParameterInfo::HURST,
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator"),
+    ],
+    references: &[
+      "Mandelbrot, B. B., & Van Ness, J. W. (1968). Fractional Brownian motions, fractional noises and applications.",
+    ],
+  };
+}
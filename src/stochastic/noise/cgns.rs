@@ -1,36 +1,41 @@
 use impl_new_derive::ImplNew;
-use ndarray::{Array1, Array2};
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
+use ndarray::Array1;
 
-use crate::stochastic::Sampling2D;
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, Sampling2D,
+  },
+};
 
-#[derive(ImplNew)]
-pub struct CGNS {
-  pub rho: f64,
+#[derive(ImplNew, Clone)]
+pub struct CGNS<T: FloatExt = f64> {
+  pub rho: T,
   pub n: usize,
-  pub t: Option<f64>,
+  pub t: Option<T>,
   pub m: Option<usize>,
 }
 
-impl Sampling2D<f64> for CGNS {
-  fn sample(&self) -> [Array1<f64>; 2] {
-    assert!(
-      (-1.0..=1.0).contains(&self.rho),
-      "Correlation coefficient must be in [-1, 1]"
-    );
+impl<T: FloatExt> Sampling2D<T> for CGNS<T> {
+  /// n increments of each Brownian motion, the first one drawn like the others
+  fn sample(&self) -> [Array1<T>; 2] {
+    or_panic(self.validate());
 
-    let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let mut cgns = Array2::<f64>::zeros((2, self.n));
-    let gn1 = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
-    let gn2 = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n);
+    let gn1 = T::normal_array(self.n, T::zero(), dt.sqrt());
+    let gn2 = T::normal_array(self.n, T::zero(), dt.sqrt());
+    let cgn2 = &gn1 * self.rho + gn2 * (T::one() - self.rho.powi(2)).sqrt();
 
-    for i in 1..self.n {
-      cgns[[0, i]] = gn1[i - 1];
-      cgns[[1, i]] = self.rho * gn1[i - 1] + (1.0 - self.rho.powi(2)).sqrt() * gn2[i - 1];
-    }
+    [gn1, cgn2]
+  }
 
-    [cgns.row(0).into_owned(), cgns.row(1).into_owned()]
+  /// Check the correlation coefficient
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      (-T::one()..=T::one()).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
   }
 
   /// Number of time steps
@@ -43,3 +48,40 @@ impl Sampling2D<f64> for CGNS {
     self.m
   }
 }
+
+impl<T: FloatExt> ProcessInfo for CGNS<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CGNS",
+    title: "Correlated Gaussian noise",
+    path: "stochastic::noise::cgns::CGNS",
+    kind: ModelKind::Noise,
+    description: "Increments dW1, dW2 of two Brownian motions with correlation rho",
+    parameters: &[
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn first_increment_is_drawn() {
+    let cgns = CGNS::new(0.6, 4, Some(4.0), None);
+    let samples = (0..20_000).map(|_| cgns.sample()).collect::<Vec<_>>();
+    let moment = |f: &dyn Fn(&[Array1<f64>; 2]) -> f64| {
+      samples.iter().map(f).sum::<f64>() / samples.len() as f64
+    };
+
+    // dt = 1, so the first increments are standard normals with correlation rho
+    assert!((moment(&|s| s[0][0].powi(2)) - 1.0).abs() < 0.05);
+    assert!((moment(&|s| s[1][0].powi(2)) - 1.0).abs() < 0.05);
+    assert!((moment(&|s| s[0][0] * s[1][0]) - 0.6).abs() < 0.05);
+    assert!(samples.iter().all(|s| s[0].len() == 4 && s[1].len() == 4));
+  }
+}
@@ -0,0 +1,194 @@
+use nalgebra::SymmetricEigen;
+use ndarray::Array2;
+use ndarray_rand::RandomExt;
+use rand_distr::StandardNormal;
+
+use crate::{
+  error::{ensure, or_panic, StochasticError, StochasticResult},
+  math::linalg::{cholesky, from_dmatrix, is_psd, to_dmatrix},
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    SamplingVector,
+  },
+};
+
+/// Factorization A A^T = C of the covariance matrix used to correlate the noise
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CovarianceDecomposition {
+  /// Lower triangular Cholesky factor, requires a positive definite matrix
+  #[default]
+  Cholesky,
+  /// Eigenvectors scaled by the square root of the eigenvalues in decreasing order,
+  /// works for singular (positive semi-definite) matrices as well
+  PCA,
+}
+
+/// Correlated Gaussian noise in d dimensions.
+///
+/// Increments dW = A Z sqrt(dt) with A A^T the given d x d correlation (or covariance)
+/// matrix. The factor A is computed once at construction.
+pub struct CGNSD {
+  /// Correlation or covariance matrix of the components
+  pub cov: Array2<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  pub decomposition: CovarianceDecomposition,
+  /// Factor of the covariance matrix
+  factor: Array2<f64>,
+}
+
+impl CGNSD {
+  #[must_use]
+  pub fn new(
+    cov: Array2<f64>,
+    n: usize,
+    t: Option<f64>,
+    m: Option<usize>,
+    decomposition: CovarianceDecomposition,
+  ) -> Self {
+    or_panic(Self::try_new(cov, n, t, m, decomposition))
+  }
+
+  /// Fallible version of [`CGNSD::new`]
+  pub fn try_new(
+    cov: Array2<f64>,
+    n: usize,
+    t: Option<f64>,
+    m: Option<usize>,
+    decomposition: CovarianceDecomposition,
+  ) -> StochasticResult<Self> {
+    ensure(cov.is_square(), "Covariance matrix must be square")?;
+    ensure(
+      (&cov - &cov.t()).iter().all(|x| x.abs() < 1e-12),
+      "Covariance matrix must be symmetric",
+    )?;
+    ensure(
+      is_psd(&cov, 1e-12),
+      "Covariance matrix must be positive semi-definite",
+    )?;
+
+    let factor = match decomposition {
+      CovarianceDecomposition::Cholesky => cholesky(&cov).ok_or(StochasticError::Numerical(
+        "Covariance matrix is not positive definite, use the PCA decomposition".to_string(),
+      ))?,
+      CovarianceDecomposition::PCA => {
+        let eigen = SymmetricEigen::new(to_dmatrix(&cov));
+        let mut order = (0..cov.nrows()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+        let vectors = from_dmatrix(&eigen.eigenvectors);
+        Array2::from_shape_fn(cov.dim(), |(i, j)| {
+          vectors[[i, order[j]]] * eigen.eigenvalues[order[j]].max(0.0).sqrt()
+        })
+      }
+    };
+
+    Ok(Self {
+      cov,
+      n,
+      t,
+      m,
+      decomposition,
+      factor,
+    })
+  }
+
+  /// Number of components
+  pub fn dim(&self) -> usize {
+    self.cov.nrows()
+  }
+
+  /// Factor A of the covariance matrix (A A^T = cov)
+  pub fn factor(&self) -> &Array2<f64> {
+    &self.factor
+  }
+}
+
+impl SamplingVector<f64> for CGNSD {
+  /// Sample the correlated increments, one component per row
+  fn sample(&self) -> Array2<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let z = Array2::<f64>::random((self.dim(), self.n), StandardNormal);
+
+    self.factor.dot(&z) * dt.sqrt()
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for CGNSD {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CGNSD",
+    title: "Correlated Gaussian noise in d dimensions",
+    path: "stochastic::noise::cgnsd::CGNSD",
+    kind: ModelKind::Noise,
+    description: "Increments dW = A Z sqrt(dt) with A A^T the correlation or covariance matrix",
+    parameters: &[
+      ParameterInfo::array("cov", "Correlation or covariance matrix of the components"),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::choice(
+        "decomposition",
+        "Factorization of the covariance matrix",
+        &["Cholesky", "PCA"],
+      ),
+    ],
+    references: &[],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::{array, Axis};
+
+  use super::*;
+
+  #[test]
+  fn cgnsd_reproduces_covariance() {
+    let cov = array![[1.0, 0.6, -0.3], [0.6, 1.0, 0.2], [-0.3, 0.2, 1.0]];
+
+    for decomposition in [
+      CovarianceDecomposition::Cholesky,
+      CovarianceDecomposition::PCA,
+    ] {
+      let cgnsd = CGNSD::new(cov.clone(), 100_000, Some(100_000.0), None, decomposition);
+      let factor = cgnsd.factor();
+      assert!((factor.dot(&factor.t()) - &cov)
+        .iter()
+        .all(|x| x.abs() < 1e-12));
+
+      let noise = cgnsd.sample();
+      let mean = noise.mean_axis(Axis(1)).unwrap();
+      let centered = &noise - &mean.insert_axis(Axis(1));
+      let sample_cov = centered.dot(&centered.t()) / (cgnsd.n() - 1) as f64;
+
+      assert_eq!(noise.dim(), (3, 100_000));
+      assert!((sample_cov - &cov).iter().all(|x| x.abs() < 0.02));
+    }
+  }
+
+  #[test]
+  fn cgnsd_singular_matrix_needs_pca() {
+    let cov = array![[1.0, 1.0], [1.0, 1.0]];
+
+    assert!(CGNSD::try_new(
+      cov.clone(),
+      10,
+      None,
+      None,
+      CovarianceDecomposition::Cholesky
+    )
+    .is_err());
+    assert!(CGNSD::try_new(cov, 10, None, None, CovarianceDecomposition::PCA).is_ok());
+  }
+}
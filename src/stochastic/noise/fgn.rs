@@ -1,40 +1,160 @@
 use std::sync::{Arc, Mutex};
 
+use nalgebra::{DMatrix, DVector};
 use ndarray::parallel::prelude::*;
-use ndarray::{concatenate, prelude::*};
+use ndarray::{concatenate, prelude::*, Zip};
 use ndarray_rand::rand_distr::StandardNormal;
 use ndarray_rand::RandomExt;
 use ndrustfft::{ndfft, FftHandler};
-use num_complex::{Complex, ComplexDistribution};
+use num_complex::Complex;
+use rand_distr::Uniform;
+use statrs::function::gamma::gamma;
 
-use crate::stochastic::Sampling;
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  math::linalg::{cholesky_psd, from_dmatrix, to_dmatrix},
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, Sampling,
+  },
+};
 
-pub struct FGN {
-  pub hurst: f64,
+/// Algorithm used to generate the fractional Gaussian noise
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FGNMethod {
+  /// Circulant embedding (Davies-Harte).
+  ///
+  /// Exact covariance, O(n log n) with FFTs of size 2n.
+  #[default]
+  DaviesHarte,
+  /// Paxson's spectral synthesis.
+  ///
+  /// Random Fourier coefficients are drawn from an approximation of the fGn spectral
+  /// density and transformed with a single FFT of size n (half the work of
+  /// [`FGNMethod::DaviesHarte`]). The covariance is only approximate: the periodogram
+  /// ignores the zero frequency, so for H > 0.5 the long-range dependence is
+  /// underestimated at lags comparable to n, and the marginal variance deviates by a
+  /// few percent.
+  Paxson,
+  /// Wavelet synthesis with independent Haar coefficients.
+  ///
+  /// The Haar coefficients at every scale are drawn with the exact fGn variance but
+  /// independently of each other, then the inverse Haar transform is applied. O(n)
+  /// without any FFT, the fastest method. The marginal variance is exact, but the
+  /// neglected correlation between coefficients makes the covariance non-stationary
+  /// (it depends on the position within the dyadic blocks) and the covariance error
+  /// is the largest of all methods, growing as H moves away from 0.5.
+  Wavelet,
+}
+
+/// Fractional Gaussian noise generator.
+///
+/// Generic over the floating point type of the samples, the spectral quantities are
+/// always precomputed in double precision and only the per-path work (random numbers
+/// and FFT) runs in `T`.
+#[derive(Clone)]
+pub struct FGN<T: FloatExt = f64> {
+  pub hurst: T,
   pub n: usize,
-  pub t: Option<f64>,
+  pub t: Option<T>,
   pub m: Option<usize>,
   pub offset: usize,
-  pub sqrt_eigenvalues: Arc<Array1<Complex<f64>>>,
-  pub fft_handler: Arc<FftHandler<f64>>,
+  pub method: FGNMethod,
+  /// Square root of the circulant eigenvalues (Davies-Harte)
+  /// or of the spectral density at the Fourier frequencies (Paxson)
+  pub sqrt_eigenvalues: Arc<Array1<Complex<T>>>,
+  pub fft_handler: Arc<FftHandler<T>>,
 }
 
-impl FGN {
+impl<T: FloatExt> FGN<T> {
   #[must_use]
-  pub fn new(hurst: f64, n: usize, t: Option<f64>, m: Option<usize>) -> Self {
-    if !(0.0..=1.0).contains(&hurst) {
-      panic!("Hurst parameter must be between 0 and 1");
-    }
+  pub fn new(hurst: T, n: usize, t: Option<T>, m: Option<usize>) -> Self {
+    Self::with_method(hurst, n, t, m, FGNMethod::DaviesHarte)
+  }
+
+  /// Fallible version of [`FGN::new`]
+  pub fn try_new(hurst: T, n: usize, t: Option<T>, m: Option<usize>) -> StochasticResult<Self> {
+    Self::try_with_method(hurst, n, t, m, FGNMethod::DaviesHarte)
+  }
+
+  /// Create a new FGN generator using the given method
+  #[must_use]
+  pub fn with_method(
+    hurst: T,
+    n: usize,
+    t: Option<T>,
+    m: Option<usize>,
+    method: FGNMethod,
+  ) -> Self {
+    or_panic(Self::try_with_method(hurst, n, t, m, method))
+  }
+
+  /// Fallible version of [`FGN::with_method`]
+  pub fn try_with_method(
+    hurst: T,
+    n: usize,
+    t: Option<T>,
+    m: Option<usize>,
+    method: FGNMethod,
+  ) -> StochasticResult<Self> {
+    let h: f64 = hurst.into();
+    ensure(
+      (0.0..=1.0).contains(&h),
+      "Hurst parameter must be between 0 and 1",
+    )?;
+    ensure(n > 0, "n must be positive")?;
 
     let offset = n.next_power_of_two() - n;
     let n = n.next_power_of_two();
+
+    match method {
+      FGNMethod::DaviesHarte => {}
+      FGNMethod::Paxson => {
+        // At least one non-zero Fourier frequency is needed
+        let offset = offset + n.max(2) - n;
+        let n = n.max(2);
+        let sqrt_density = Array1::from_shape_fn(n / 2 + 1, |k| {
+          let lambda = 2.0 * std::f64::consts::PI * k as f64 / n as f64;
+          match k {
+            0 => Complex::new(T::zero(), T::zero()),
+            _ => Complex::new(
+              T::from_f64_(paxson_spectral_density(h, lambda).sqrt()),
+              T::zero(),
+            ),
+          }
+        });
+
+        return Ok(Self {
+          hurst,
+          n,
+          offset,
+          t,
+          m,
+          method,
+          sqrt_eigenvalues: Arc::new(sqrt_density),
+          fft_handler: Arc::new(FftHandler::new(n)),
+        });
+      }
+      FGNMethod::Wavelet => {
+        return Ok(Self {
+          hurst,
+          n,
+          offset,
+          t,
+          m,
+          method,
+          sqrt_eigenvalues: Arc::new(Array1::zeros(0)),
+          fft_handler: Arc::new(FftHandler::new(1)),
+        });
+      }
+    }
+
     let mut r = Array1::linspace(0.0, n as f64, n + 1);
     r.mapv_inplace(|x| {
       if x == 0.0 {
         1.0
       } else {
-        0.5
-          * ((x + 1.0).powf(2.0 * hurst) - 2.0 * x.powf(2.0 * hurst) + (x - 1.0).powf(2.0 * hurst))
+        0.5 * ((x + 1.0).powf(2.0 * h) - 2.0 * x.powf(2.0 * h) + (x - 1.0).powf(2.0 * h))
       }
     });
     let r = concatenate(
@@ -44,34 +164,143 @@ impl FGN {
     )
     .unwrap();
     let data = r.mapv(|v| Complex::new(v, 0.0));
-    let r_fft = FftHandler::new(r.len());
-    let mut sqrt_eigenvalues = Array1::<Complex<f64>>::zeros(r.len());
-    ndfft(&data, &mut sqrt_eigenvalues, &r_fft, 0);
-    sqrt_eigenvalues.mapv_inplace(|x| Complex::new((x.re / (2.0 * n as f64)).sqrt(), x.im));
+    let r_fft = FftHandler::<f64>::new(r.len());
+    let mut eigenvalues = Array1::<Complex<f64>>::zeros(r.len());
+    ndfft(&data, &mut eigenvalues, &r_fft, 0);
+    let sqrt_eigenvalues = eigenvalues.mapv(|x| {
+      Complex::new(
+        T::from_f64_((x.re / (2.0 * n as f64)).sqrt()),
+        T::from_f64_(x.im),
+      )
+    });
 
-    Self {
+    Ok(Self {
       hurst,
       n,
       offset,
       t,
+      method,
       sqrt_eigenvalues: Arc::new(sqrt_eigenvalues),
       m,
       fft_handler: Arc::new(FftHandler::new(2 * n)),
-    }
+    })
   }
 }
 
-impl Sampling<f64> for FGN {
-  fn sample(&self) -> Array1<f64> {
+impl FGN {
+  /// Sample future increments conditionally on observed past increments.
+  ///
+  /// See [`conditional_fgn`].
+  pub fn sample_conditional(&self, past: &Array1<f64>, horizon: usize) -> Array1<f64> {
+    conditional_fgn(self.hurst, self.dt(), past, horizon)
+  }
+}
+
+/// Autocovariance of the unit step fractional Gaussian noise at lag k
+pub fn fgn_autocovariance(hurst: f64, k: usize) -> f64 {
+  let k = k as f64;
+  0.5
+    * ((k + 1.0).powf(2.0 * hurst) - 2.0 * k.powf(2.0 * hurst) + (k - 1.0).abs().powf(2.0 * hurst))
+}
+
+/// Conditional simulation of fractional Gaussian noise.
+///
+/// The past and future increments are jointly Gaussian, so the future is sampled from
+/// N(C_fp C_pp^{-1} x_p, C_ff - C_fp C_pp^{-1} C_pf) (simple kriging on the fGn covariance).
+///
+/// # Arguments
+/// hurst: f64 - Hurst parameter
+/// dt: f64 - time step of the increments
+/// past: &Array1<f64> - observed increments, oldest first
+/// horizon: usize - number of future increments
+pub fn conditional_fgn(hurst: f64, dt: f64, past: &Array1<f64>, horizon: usize) -> Array1<f64> {
+  assert!(
+    hurst > 0.0 && hurst < 1.0,
+    "Hurst parameter must be in (0, 1)"
+  );
+
+  let p = past.len();
+  let scale = dt.powf(2.0 * hurst);
+  let cov = |i: usize, j: usize| scale * fgn_autocovariance(hurst, i.abs_diff(j));
+
+  let c_pp = DMatrix::from_fn(p, p, &cov);
+  let c_fp = DMatrix::from_fn(horizon, p, |i, j| cov(p + i, j));
+  let c_ff = DMatrix::from_fn(horizon, horizon, |i, j| cov(p + i, p + j));
+
+  // Weights W = C_fp C_pp^{-1}, computed as (C_pp^{-1} C_pf)^T
+  let weights = match p {
+    0 => DMatrix::zeros(horizon, 0),
+    _ => c_pp
+      .cholesky()
+      .expect("Covariance of the past must be positive definite")
+      .solve(&c_fp.transpose())
+      .transpose(),
+  };
+
+  let x_p = DVector::from_iterator(p, past.iter().cloned());
+  let mean = &weights * x_p;
+  let cond_cov = &c_ff - &weights * c_fp.transpose();
+  let chol = to_dmatrix(&cholesky_psd(&from_dmatrix(&cond_cov), 1e-14));
+
+  let z = DVector::from_iterator(horizon, Array1::<f64>::random(horizon, StandardNormal));
+  let future = mean + chol * z;
+
+  Array1::from_iter(future.iter().cloned())
+}
+
+/// Paxson's approximation of the spectral density of unit variance fractional Gaussian noise.
+///
+/// f(lambda) = 2 sin(pi H) Gamma(2H + 1) (1 - cos lambda) (|lambda|^(-2H - 1) + B(lambda, H))
+///
+/// where the infinite sum B is approximated by its first three terms, an integral
+/// correction for the tail and Paxson's empirical bias correction.
+/// Paxson, V. (1997). Fast, approximate synthesis of fractional Gaussian noise for
+/// generating self-similar network traffic.
+pub fn paxson_spectral_density(hurst: f64, lambda: f64) -> f64 {
+  let two_pi = 2.0 * std::f64::consts::PI;
+  let d = -2.0 * hurst - 1.0;
+  let d_ = -2.0 * hurst;
+  let a = |k: f64| two_pi * k + lambda;
+  let b = |k: f64| two_pi * k - lambda;
+
+  let b3 = (1..=3)
+    .map(|k| a(k as f64).powf(d) + b(k as f64).powf(d))
+    .sum::<f64>()
+    + (a(3.0).powf(d_) + b(3.0).powf(d_) + a(4.0).powf(d_) + b(4.0).powf(d_))
+      / (8.0 * hurst * std::f64::consts::PI);
+  let b = (1.0002 - 0.000134 * lambda) * (b3 - 2.0_f64.powf(-7.65 * hurst - 7.4));
+
+  2.0
+    * (std::f64::consts::PI * hurst).sin()
+    * gamma(2.0 * hurst + 1.0)
+    * (1.0 - lambda.cos())
+    * (lambda.abs().powf(d) + b)
+}
+
+impl<T: FloatExt> FGN<T> {
+  /// Time step of the sampled increments, the horizon over the padded length n
+  pub fn dt(&self) -> T {
+    self.t.unwrap_or(T::one()) / T::from_usize_(self.n)
+  }
+
+  /// Hurst parameter and horizon in double precision
+  fn hurst_and_horizon(&self) -> (f64, f64) {
+    (self.hurst.into(), self.t.map_or(1.0, Into::into))
+  }
+
+  fn sample_davies_harte(&self) -> Array1<T> {
     let num_threads = rayon::current_num_threads();
     let chunk_size = (2 * self.n) / num_threads;
-    let rnd = Arc::new(Mutex::new(Array1::<Complex<f64>>::zeros(2 * self.n)));
+    let rnd = Arc::new(Mutex::new(Array1::<Complex<T>>::zeros(2 * self.n)));
 
     (0..num_threads).into_par_iter().for_each(|i| {
-      let chunk = Array1::<Complex<f64>>::random(
-        chunk_size,
-        ComplexDistribution::new(StandardNormal, StandardNormal),
+      let (re, im) = (
+        T::normal_array(chunk_size, T::zero(), T::one()),
+        T::normal_array(chunk_size, T::zero(), T::one()),
       );
+      let chunk = Zip::from(&re)
+        .and(&im)
+        .map_collect(|&re, &im| Complex::new(re, im));
 
       let mut result_lock = rnd.lock().unwrap();
       result_lock
@@ -80,15 +309,78 @@ impl Sampling<f64> for FGN {
     });
 
     let fgn = &*self.sqrt_eigenvalues * &*rnd.lock().unwrap();
-    let mut fgn_fft = Array1::<Complex<f64>>::zeros(2 * self.n);
+    let mut fgn_fft = Array1::<Complex<T>>::zeros(2 * self.n);
     ndfft(&fgn, &mut fgn_fft, &*self.fft_handler, 0);
-    let scale = (self.n as f64).powf(-self.hurst) * self.t.unwrap_or(1.0).powf(self.hurst);
+    let (h, t) = self.hurst_and_horizon();
+    let scale = T::from_f64_((self.n as f64).powf(-h) * t.powf(h));
     let fgn = fgn_fft
       .slice(s![1..self.n - self.offset + 1])
-      .mapv(|x: Complex<f64>| x.re * scale);
+      .mapv(|x: Complex<T>| x.re * scale);
     fgn
   }
 
+  fn sample_paxson(&self) -> Array1<T> {
+    let n = self.n;
+    let half = n / 2;
+    let exp = T::exp1_array(half);
+    let phase = Array1::<T>::random(half, Uniform::new(T::zero(), T::TAU()));
+
+    // Hermitian symmetric coefficients with E|z_k|^2 = f(lambda_k), so the transform is real
+    let mut z = Array1::<Complex<T>>::zeros(n);
+    for k in 1..half {
+      let coeff = Complex::from_polar(self.sqrt_eigenvalues[k].re * exp[k].sqrt(), phase[k]);
+      z[k] = coeff;
+      z[n - k] = coeff.conj();
+    }
+    let nyquist = T::normal_array(1, T::zero(), T::one())[0];
+    z[half] = Complex::new(self.sqrt_eigenvalues[half].re * nyquist, T::zero());
+
+    let mut fgn_fft = Array1::<Complex<T>>::zeros(n);
+    ndfft(&z, &mut fgn_fft, &self.fft_handler, 0);
+    // Var(x_j) = sum_k f(lambda_k) ~ n, hence the additional 1 / sqrt(n)
+    let (h, t) = self.hurst_and_horizon();
+    let scale = T::from_f64_((n as f64).powf(-0.5 - h) * t.powf(h));
+    fgn_fft
+      .slice(s![..n - self.offset])
+      .mapv(|x: Complex<T>| x.re * scale)
+  }
+
+  fn sample_wavelet(&self) -> Array1<T> {
+    let (h, t) = self.hurst_and_horizon();
+    // Haar coefficients of unit fGn at scale L: Var = L^(2H - 1) (2^(2 - 2H) - 1)
+    let detail_factor = (2.0_f64.powf(2.0 - 2.0 * h) - 1.0).sqrt();
+
+    // Start from the scaling coefficient of the whole block and refine with the inverse Haar transform
+    let mut coeffs = T::normal_array(1, T::zero(), T::from_f64_((self.n as f64).powf(h - 0.5)));
+    while coeffs.len() < self.n {
+      let m = coeffs.len();
+      let l = (self.n / m) as f64;
+      let details = T::normal_array(m, T::zero(), T::from_f64_(detail_factor * l.powf(h - 0.5)));
+
+      coeffs = Array1::from_shape_fn(2 * m, |i| {
+        let detail = if i % 2 == 0 {
+          details[i / 2]
+        } else {
+          -details[i / 2]
+        };
+        (coeffs[i / 2] + detail) * T::FRAC_1_SQRT_2()
+      });
+    }
+
+    let scale = T::from_f64_((t / self.n as f64).powf(h));
+    coeffs.slice(s![..self.n - self.offset]).mapv(|x| x * scale)
+  }
+}
+
+impl<T: FloatExt> Sampling<T> for FGN<T> {
+  fn sample(&self) -> Array1<T> {
+    match self.method {
+      FGNMethod::DaviesHarte => self.sample_davies_harte(),
+      FGNMethod::Paxson => self.sample_paxson(),
+      FGNMethod::Wavelet => self.sample_wavelet(),
+    }
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n - self.offset
@@ -100,6 +392,26 @@ impl Sampling<f64> for FGN {
   }
 }
 
+impl<T: FloatExt> ProcessInfo for FGN<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FGN",
+    title: "Fractional Gaussian noise",
+    path: "stochastic::noise::fgn::FGN",
+    kind: ModelKind::Noise,
+    description: "Increments of a fractional Brownian motion with Hurst index H",
+    parameters: &[
+      ParameterInfo::HURST,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Davies, R. B., & Harte, D. S. (1987). Tests for Hurst effect.",
+      "Paxson, V. (1997). Fast, approximate synthesis of fractional Gaussian noise for generating self-similar network traffic.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{plot_1d, stochastic::N};
@@ -124,6 +436,112 @@ mod tests {
     plot_1d!(fbm.sample(), "Fractional Brownian Motion (H = 0.7)");
   }
 
+  #[test]
+  fn fgn_try_new_rejects_invalid_hurst() {
+    assert!(FGN::try_new(1.5, N, Some(1.0), None).is_err());
+    assert!(FGN::try_new(0.7, N, Some(1.0), None)
+      .unwrap()
+      .try_sample_par()
+      .is_err());
+  }
+
+  #[test]
+  fn fgn_conditional_has_the_scale_of_the_samples() {
+    // 513 increments are padded to 1024, the step is 1 / 1024
+    let fgn = FGN::new(0.7_f64, 513, Some(1.0), None);
+    assert_eq!(fgn.dt(), 1.0 / 1024.0);
+    let variance = fgn.dt().powf(1.4);
+
+    let sampled = (0..40)
+      .flat_map(|_| fgn.sample())
+      .map(|x| x.powi(2))
+      .sum::<f64>()
+      / (40 * 513) as f64;
+    let conditional = (0..20_000)
+      .map(|_| fgn.sample_conditional(&Array1::zeros(0), 1)[0].powi(2))
+      .sum::<f64>()
+      / 20_000.0;
+
+    assert!((sampled / variance - 1.0).abs() < 0.1, "{}", sampled);
+    assert!(
+      (conditional / variance - 1.0).abs() < 0.1,
+      "{}",
+      conditional
+    );
+  }
+
+  #[test]
+  fn fgn_f32_has_unit_variance() {
+    for method in [
+      FGNMethod::DaviesHarte,
+      FGNMethod::Paxson,
+      FGNMethod::Wavelet,
+    ] {
+      let n = 1 << 14;
+      let fgn = FGN::<f32>::with_method(0.7, n, Some(n as f32), None, method);
+      let sample = fgn.sample();
+      assert_eq!(sample.len(), n);
+
+      let variance = sample.mapv(|x| x.powi(2)).mean().unwrap();
+      assert!((variance - 1.0).abs() < 0.3, "{:?}: {}", method, variance);
+    }
+  }
+
+  #[test]
+  fn fgn_approximate_methods_have_unit_variance() {
+    for method in [FGNMethod::Paxson, FGNMethod::Wavelet] {
+      let n = 1 << 14;
+      let fgn = FGN::with_method(0.7, n, Some(n as f64), None, method);
+      let sample = fgn.sample();
+      assert_eq!(sample.len(), n);
+
+      let variance = sample.mapv(|x| x.powi(2)).mean().unwrap();
+      assert!((variance - 1.0).abs() < 0.3, "{:?}: {}", method, variance);
+    }
+  }
+
+  #[test]
+  #[ignore = "Benchmark"]
+  fn fgn_methods_speed_vs_covariance_error() {
+    let n = 1 << 16;
+    let paths = 200;
+    let lags = 20;
+
+    for hurst in [0.3, 0.7, 0.9] {
+      for method in [
+        FGNMethod::DaviesHarte,
+        FGNMethod::Paxson,
+        FGNMethod::Wavelet,
+      ] {
+        // t = n gives unit time steps
+        let fgn = FGN::with_method(hurst, n, Some(n as f64), None, method);
+        let mut autocov = Array1::<f64>::zeros(lags + 1);
+
+        let start = std::time::Instant::now();
+        let samples = (0..paths).map(|_| fgn.sample()).collect::<Vec<_>>();
+        let elapsed = start.elapsed();
+
+        for sample in &samples {
+          for k in 0..=lags {
+            autocov[k] +=
+              sample.slice(s![..n - k]).dot(&sample.slice(s![k..])) / ((n - k) * paths) as f64;
+          }
+        }
+
+        let error = (0..=lags)
+          .map(|k| (autocov[k] - fgn_autocovariance(hurst, k)).abs())
+          .fold(0.0, f64::max);
+        println!(
+          "H = {}, {:?}: {:?} per path, max autocovariance error {:.4}",
+          hurst,
+          method,
+          elapsed / paths as u32,
+          error
+        );
+      }
+    }
+  }
+
   #[test]
   #[ignore = "Not implemented"]
   #[cfg(feature = "malliavin")]
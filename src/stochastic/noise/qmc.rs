@@ -0,0 +1,590 @@
+//! Low-discrepancy sequences for quasi-Monte Carlo path generation.
+//!
+//! The Sobol sequence uses the Joe-Kuo direction numbers for the first 21 dimensions
+//! and primitive polynomials of increasing degree with pseudo-random (fixed seed)
+//! initial direction numbers above. Paths are generated with Brownian bridge ordering:
+//! the first coordinates of a point give the terminal value and the coarse midpoints of
+//! the Brownian motion, which carry most of the variance, so the effective dimension of
+//! the integrand stays low even for long paths.
+//!
+//! The points are randomized (random digital shift for Sobol, random shift modulo 1 for
+//! Halton) once per batch, which keeps the estimators unbiased and lets independent
+//! batches be used for error estimation.
+//!
+//! - Sobol, I. M. (1967). On the distribution of points in a cube and the approximate
+//!   evaluation of integrals.
+//! - Joe, S., & Kuo, F. Y. (2008). Constructing Sobol sequences with better two-dimensional
+//!   projections.
+//! - Glasserman, P. (2003). Monte Carlo Methods in Financial Engineering, Section 3.1 and 5.
+//! - Jäckel, P. (2002). Monte Carlo Methods in Finance, Chapter 10.
+
+use ndarray::{Array1, Array2};
+use rand::Rng;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::stochastic::FloatExt;
+
+/// Number of bits of the Sobol points
+const BITS: usize = 32;
+
+/// Initial direction numbers m_1..m_s of the Joe-Kuo table for dimensions 2..=21 (new-joe-kuo-6.21201)
+const JOE_KUO: [&[u32]; 20] = [
+  &[1],
+  &[1, 3],
+  &[1, 3, 1],
+  &[1, 1, 1],
+  &[1, 1, 3, 3],
+  &[1, 3, 5, 13],
+  &[1, 1, 5, 5, 17],
+  &[1, 1, 5, 5, 5],
+  &[1, 1, 7, 11, 19],
+  &[1, 1, 5, 1, 1],
+  &[1, 1, 1, 3, 11],
+  &[1, 3, 5, 5, 31],
+  &[1, 3, 3, 9, 7, 49],
+  &[1, 1, 1, 15, 21, 21],
+  &[1, 3, 1, 13, 27, 49],
+  &[1, 1, 1, 15, 7, 5],
+  &[1, 3, 1, 15, 13, 25],
+  &[1, 1, 5, 5, 19, 61],
+  &[1, 3, 7, 11, 23, 15, 103],
+  &[1, 3, 7, 13, 13, 15, 69],
+];
+
+/// Source of the Gaussian increments driving the paths
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseSource {
+  /// Pseudo-random normals
+  #[default]
+  PseudoRandom,
+  /// Randomized Sobol points with Brownian bridge ordering, best with a power of 2 paths
+  Sobol,
+  /// Randomized Halton points with Brownian bridge ordering
+  Halton,
+}
+
+impl NoiseSource {
+  /// Standard normal increments of m paths, every path as a (drivers, increments) matrix.
+  ///
+  /// The quasi-random points have dimension drivers * increments, coordinate
+  /// j * drivers + k is the j-th Brownian bridge variate of driver k.
+  pub fn standard_normals<T: FloatExt>(
+    &self,
+    m: usize,
+    drivers: usize,
+    increments: usize,
+  ) -> Vec<Array2<T>> {
+    let mut points = match self {
+      NoiseSource::PseudoRandom => {
+        return (0..m)
+          .map(|_| {
+            Array2::from_shape_vec(
+              (drivers, increments),
+              T::normal_array(drivers * increments, T::zero(), T::one()).to_vec(),
+            )
+            .unwrap()
+          })
+          .collect();
+      }
+      NoiseSource::Sobol => LowDiscrepancy::Sobol(Sobol::new(drivers * increments)),
+      NoiseSource::Halton => LowDiscrepancy::Halton(Halton::new(drivers * increments)),
+    };
+
+    let normal = Normal::default();
+    let bridge = BrownianBridge::new(increments);
+    let shift = points.random_shift();
+
+    (0..m)
+      .map(|_| {
+        let u = points.next_shifted(&shift);
+        let mut z = Array2::zeros((drivers, increments));
+
+        for k in 0..drivers {
+          let w = (0..increments)
+            .map(|j| normal.inverse_cdf(u[j * drivers + k]))
+            .collect::<Vec<_>>();
+          for (j, dw) in bridge.increments(&w).into_iter().enumerate() {
+            z[[k, j]] = T::from_f64_(dw);
+          }
+        }
+
+        z
+      })
+      .collect()
+  }
+}
+
+/// Randomized low-discrepancy generator
+enum LowDiscrepancy {
+  Sobol(Sobol),
+  Halton(Halton),
+}
+
+/// Random shift of every coordinate
+enum Shift {
+  Digital(Vec<u32>),
+  Modulo(Vec<f64>),
+}
+
+impl LowDiscrepancy {
+  fn random_shift(&self) -> Shift {
+    let mut rng = rand::thread_rng();
+    match self {
+      LowDiscrepancy::Sobol(sobol) => Shift::Digital((0..sobol.dim()).map(|_| rng.gen()).collect()),
+      LowDiscrepancy::Halton(halton) => {
+        Shift::Modulo((0..halton.dim()).map(|_| rng.gen()).collect())
+      }
+    }
+  }
+
+  /// Next point shifted into the open unit cube
+  fn next_shifted(&mut self, shift: &Shift) -> Vec<f64> {
+    match (self, shift) {
+      (LowDiscrepancy::Sobol(sobol), Shift::Digital(shift)) => {
+        sobol.advance();
+        sobol
+          .state
+          .iter()
+          .zip(shift)
+          .map(|(x, s)| ((x ^ s) as f64 + 0.5) / 2f64.powi(BITS as i32))
+          .collect()
+      }
+      (LowDiscrepancy::Halton(halton), Shift::Modulo(shift)) => halton
+        .next_point()
+        .iter()
+        .zip(shift)
+        .map(|(x, s)| (x + s).fract().max(f64::MIN_POSITIVE))
+        .collect(),
+      _ => unreachable!(),
+    }
+  }
+}
+
+/// Sobol sequence generated in Gray code order, starting at the origin
+pub struct Sobol {
+  /// Direction numbers v_1..v_32 of every dimension
+  directions: Vec<[u32; BITS]>,
+  /// Current point as 32-bit integers
+  state: Vec<u32>,
+  /// Index of the next point
+  index: u64,
+}
+
+impl Sobol {
+  #[must_use]
+  pub fn new(dim: usize) -> Self {
+    assert!(dim > 0, "dimension must be positive");
+
+    let polynomials = primitive_polynomials(dim.saturating_sub(1));
+    let mut rng = SplitMix64(0x5eed_5eed_5eed_5eed);
+    let mut directions = Vec::with_capacity(dim);
+    directions.push(std::array::from_fn(|i| 1u32 << (BITS - 1 - i)));
+
+    for (d, &(s, a)) in polynomials.iter().enumerate() {
+      let s = s as usize;
+      let m = match JOE_KUO.get(d) {
+        Some(m) => m.to_vec(),
+        // Odd m_k < 2^k
+        None => (1..=s)
+          .map(|k| (rng.next() as u32 & ((1u32 << k) - 1)) | 1)
+          .collect(),
+      };
+
+      let mut v = [0u32; BITS];
+      for i in 0..s.min(BITS) {
+        v[i] = m[i] << (BITS - 1 - i);
+      }
+      for i in s..BITS {
+        v[i] = v[i - s] ^ (v[i - s] >> s);
+        for k in 1..s {
+          if (a >> (s - 1 - k)) & 1 == 1 {
+            v[i] ^= v[i - k];
+          }
+        }
+      }
+      directions.push(v);
+    }
+
+    Self {
+      directions,
+      state: vec![0; dim],
+      index: 0,
+    }
+  }
+
+  /// Dimension of the points
+  pub fn dim(&self) -> usize {
+    self.state.len()
+  }
+
+  /// Next point in [0, 1)^dim
+  pub fn next_point(&mut self) -> Array1<f64> {
+    self.advance();
+    self
+      .state
+      .iter()
+      .map(|&x| x as f64 / 2f64.powi(BITS as i32))
+      .collect()
+  }
+
+  /// Move the state to the next point, the first call keeps the origin
+  fn advance(&mut self) {
+    if self.index > 0 {
+      // Gray code: flip the direction number of the rightmost zero bit of index - 1
+      let c = (self.index - 1).trailing_ones() as usize;
+      assert!(c < BITS, "Sobol sequence exhausted");
+      for (x, v) in self.state.iter_mut().zip(&self.directions) {
+        *x ^= v[c];
+      }
+    }
+    self.index += 1;
+  }
+}
+
+/// Halton sequence, starting at index 1
+pub struct Halton {
+  bases: Vec<u64>,
+  index: u64,
+}
+
+impl Halton {
+  #[must_use]
+  pub fn new(dim: usize) -> Self {
+    assert!(dim > 0, "dimension must be positive");
+
+    Self {
+      bases: primes(dim),
+      index: 1,
+    }
+  }
+
+  /// Dimension of the points
+  pub fn dim(&self) -> usize {
+    self.bases.len()
+  }
+
+  /// Next point in (0, 1)^dim
+  pub fn next_point(&mut self) -> Array1<f64> {
+    let point = self
+      .bases
+      .iter()
+      .map(|&b| radical_inverse(self.index, b))
+      .collect();
+    self.index += 1;
+    point
+  }
+}
+
+/// Van der Corput radical inverse of i in base b
+fn radical_inverse(mut i: u64, b: u64) -> f64 {
+  let (mut x, mut f) = (0.0, 1.0 / b as f64);
+  while i > 0 {
+    x += (i % b) as f64 * f;
+    i /= b;
+    f /= b as f64;
+  }
+  x
+}
+
+/// First n primes
+fn primes(n: usize) -> Vec<u64> {
+  let mut primes = Vec::with_capacity(n);
+  let mut candidate = 2u64;
+  while primes.len() < n {
+    if primes
+      .iter()
+      .take_while(|&&p| p * p <= candidate)
+      .all(|&p| !candidate.is_multiple_of(p))
+    {
+      primes.push(candidate);
+    }
+    candidate += 1;
+  }
+  primes
+}
+
+/// First n primitive polynomials over GF(2) ordered by degree, as (degree s, a) where the
+/// bits of a are the inner coefficients of x^s + a_1 x^(s-1) + ... + a_(s-1) x + 1
+fn primitive_polynomials(n: usize) -> Vec<(u32, u32)> {
+  let mut polynomials = Vec::with_capacity(n);
+  let mut s = 1;
+
+  while polynomials.len() < n {
+    let order = (1u64 << s) - 1;
+    let factors = prime_factors(order);
+
+    for a in 0..1u64 << (s - 1) {
+      if polynomials.len() == n {
+        break;
+      }
+
+      let p = (1u64 << s) | (a << 1) | 1;
+      // x generates the multiplicative group of GF(2^s) iff x^order = 1 and no proper divisor works
+      let primitive =
+        pow_mod(2, order, p, s) == 1 && factors.iter().all(|&q| pow_mod(2, order / q, p, s) != 1);
+      if primitive {
+        polynomials.push((s, a as u32));
+      }
+    }
+    s += 1;
+  }
+
+  polynomials
+}
+
+/// Distinct prime factors of n
+fn prime_factors(mut n: u64) -> Vec<u64> {
+  let mut factors = Vec::new();
+  let mut q = 2;
+  while q * q <= n {
+    if n.is_multiple_of(q) {
+      factors.push(q);
+      while n.is_multiple_of(q) {
+        n /= q;
+      }
+    }
+    q += 1;
+  }
+  if n > 1 {
+    factors.push(n);
+  }
+  factors
+}
+
+/// x * y mod p over GF(2), p of degree s
+fn mul_mod(mut x: u64, mut y: u64, p: u64, s: u32) -> u64 {
+  let mut product = 0;
+  while y > 0 {
+    if y & 1 == 1 {
+      product ^= x;
+    }
+    y >>= 1;
+    x <<= 1;
+    if x >> s & 1 == 1 {
+      x ^= p;
+    }
+  }
+  product
+}
+
+/// x^e mod p over GF(2), p of degree s and x of degree at most s
+fn pow_mod(mut x: u64, mut e: u64, p: u64, s: u32) -> u64 {
+  if x >> s & 1 == 1 {
+    x ^= p;
+  }
+  let (mut base, mut result) = (x, 1);
+  while e > 0 {
+    if e & 1 == 1 {
+      result = mul_mod(result, base, p, s);
+    }
+    base = mul_mod(base, base, p, s);
+    e >>= 1;
+  }
+  result
+}
+
+/// Deterministic generator of the initial direction numbers above the Joe-Kuo table
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+  fn next(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+}
+
+/// Brownian bridge construction of a Brownian motion on the grid 1, 2, ..., d.
+///
+/// The first variate gives W(d), then every further variate fills the midpoint of the
+/// largest remaining gap conditionally on its two neighbours.
+pub struct BrownianBridge {
+  /// (point, left neighbour, right neighbour, left weight, right weight, conditional std)
+  steps: Vec<(usize, Option<usize>, usize, f64, f64, f64)>,
+}
+
+impl BrownianBridge {
+  #[must_use]
+  pub fn new(d: usize) -> Self {
+    assert!(d > 0, "number of increments must be positive");
+
+    // Time of grid point i is i + 1
+    let t = |i: usize| (i + 1) as f64;
+    let mut filled = vec![false; d];
+    filled[d - 1] = true;
+    let mut steps = vec![(d - 1, None, d - 1, 0.0, 0.0, t(d - 1).sqrt())];
+
+    let mut j = 0;
+    for _ in 1..d {
+      while filled[j] {
+        j = (j + 1) % d;
+      }
+      let mut k = j;
+      while !filled[k] {
+        k += 1;
+      }
+
+      // j..k are empty, k is filled, j - 1 is filled or the origin
+      let l = j + (k - 1 - j) / 2;
+      filled[l] = true;
+      let left = j.checked_sub(1);
+      let t_left = left.map_or(0.0, t);
+      let (left_weight, right_weight) = (
+        (t(k) - t(l)) / (t(k) - t_left),
+        (t(l) - t_left) / (t(k) - t_left),
+      );
+      let std = ((t(l) - t_left) * (t(k) - t(l)) / (t(k) - t_left)).sqrt();
+      steps.push((l, left, k, left_weight, right_weight, std));
+
+      j = k + 1;
+      if j >= d {
+        j = 0;
+      }
+    }
+
+    Self { steps }
+  }
+
+  /// Standard normal increments W(i + 1) - W(i) from the standard normal variates z
+  pub fn increments(&self, z: &[f64]) -> Vec<f64> {
+    let mut w = vec![0.0; self.steps.len()];
+
+    for (&(l, left, right, left_weight, right_weight, std), &z) in self.steps.iter().zip(z) {
+      let w_left = left.map_or(0.0, |i| w[i]);
+      w[l] = if l == right {
+        std * z
+      } else {
+        left_weight * w_left + right_weight * w[right] + std * z
+      };
+    }
+
+    let mut previous = 0.0;
+    w.iter()
+      .map(|&wi| {
+        let dw = wi - previous;
+        previous = wi;
+        dw
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sobol_first_points() {
+    let mut sobol = Sobol::new(3);
+    let points = (0..5).map(|_| sobol.next_point()).collect::<Vec<_>>();
+
+    let expected = [
+      [0.0, 0.0, 0.0],
+      [0.5, 0.5, 0.5],
+      [0.75, 0.25, 0.25],
+      [0.25, 0.75, 0.75],
+      [0.375, 0.375, 0.625],
+    ];
+    for (point, expected) in points.iter().zip(expected) {
+      assert_eq!(point.to_vec(), expected.to_vec());
+    }
+  }
+
+  #[test]
+  fn sobol_is_stratified_in_every_dimension() {
+    // Every dimension is a (0, 1)-sequence: 2^k consecutive points from index 0 hit every
+    // interval [i / 2^k, (i + 1) / 2^k) exactly once
+    let dim = 300;
+    let mut sobol = Sobol::new(dim);
+    let points = (0..256).map(|_| sobol.next_point()).collect::<Vec<_>>();
+
+    for d in 0..dim {
+      let mut hits = [0; 256];
+      for point in &points {
+        hits[(point[d] * 256.0) as usize] += 1;
+      }
+      assert!(hits.iter().all(|&h| h == 1), "dimension {}", d);
+    }
+  }
+
+  #[test]
+  fn primitive_polynomials_match_joe_kuo() {
+    let expected = [
+      (1, 0),
+      (2, 1),
+      (3, 1),
+      (3, 2),
+      (4, 1),
+      (4, 4),
+      (5, 2),
+      (5, 4),
+      (5, 7),
+      (5, 11),
+      (5, 13),
+      (5, 14),
+      (6, 1),
+      (6, 13),
+      (6, 16),
+      (6, 19),
+      (6, 22),
+      (6, 25),
+      (7, 1),
+      (7, 4),
+    ];
+    assert_eq!(primitive_polynomials(20), expected.to_vec());
+    // Number of primitive polynomials of degree 8 is phi(255) / 8
+    let degree_8 = primitive_polynomials(200)
+      .iter()
+      .filter(|(s, _)| *s == 8)
+      .count();
+    assert_eq!(degree_8, 16);
+  }
+
+  #[test]
+  fn halton_radical_inverse() {
+    let mut halton = Halton::new(2);
+    let points = (0..3).map(|_| halton.next_point()).collect::<Vec<_>>();
+
+    let expected = [[0.5, 1.0 / 3.0], [0.25, 2.0 / 3.0], [0.75, 1.0 / 9.0]];
+    for (point, expected) in points.iter().zip(expected) {
+      assert!((point[0] - expected[0]).abs() < 1e-15);
+      assert!((point[1] - expected[1]).abs() < 1e-15);
+    }
+  }
+
+  #[test]
+  fn brownian_bridge_increments_are_standard() {
+    // The bridge is a linear map L, the increments are i.i.d. standard iff L L^T = I
+    let d = 13;
+    let bridge = BrownianBridge::new(d);
+    let columns = (0..d)
+      .map(|i| {
+        let mut e = vec![0.0; d];
+        e[i] = 1.0;
+        bridge.increments(&e)
+      })
+      .collect::<Vec<_>>();
+
+    for a in 0..d {
+      for b in 0..d {
+        let cov = columns.iter().map(|c| c[a] * c[b]).sum::<f64>();
+        let expected = if a == b { 1.0 } else { 0.0 };
+        assert!((cov - expected).abs() < 1e-12);
+      }
+    }
+  }
+
+  #[test]
+  fn qmc_normals_have_standard_moments() {
+    for source in [NoiseSource::Sobol, NoiseSource::Halton] {
+      let z = source.standard_normals::<f64>(1024, 2, 16);
+      assert_eq!(z[0].dim(), (2, 16));
+
+      let terminal = z.iter().map(|z| z.row(1).sum()).collect::<Array1<f64>>();
+      assert!(terminal.mean().unwrap().abs() < 0.05);
+      assert!((terminal.var(1.0) / 16.0 - 1.0).abs() < 0.05);
+    }
+  }
+}
@@ -0,0 +1,65 @@
+//! The most used samplers, traits and constants in one import.
+//!
+//! `use stochastic_rs::stochastic::prelude::*;` brings the sampling traits, the default
+//! constants and the common processes, noises and wrappers into scope.
+//!
+//! The names follow the acronym of the model in capitals (`FGN`, `GBM`, `CIR`, `G2PP`) or
+//! its author (`Heston`, `Bates`, `Merton`). Renamed types are kept as deprecated aliases in
+//! their modules for one release, e.g. `Bates1996`.
+
+#[allow(deprecated)]
+pub use super::volatility::rbergomi::RoughBergomi;
+pub use super::{
+  catalog::{catalog, ProcessInfo},
+  diffusion::{
+    cev::CEV,
+    cir::{DiscretizationScheme, CIR},
+    custom::{CustomSDE, SDEScheme},
+    fou::FOU,
+    gbm::GBM,
+    jacobi::Jacobi,
+    levy_ou::{GammaOU, LevyOU, IGOU},
+    local_vol::LocalVolProcess,
+    multi_gbm::MultiGBM,
+    ou::OU,
+    regime_switching::RegimeSwitching,
+    scheme::Scheme,
+    wright_fisher::WrightFisher,
+  },
+  initial_state::{InitialDistribution, InitialState, RandomInitial},
+  interest::{
+    cir_plus_plus::CIRPlusPlus, g2pp::G2PP, hull_white::HullWhite,
+    libor_market_model::LiborMarketModel, vasicek::Vasicek,
+  },
+  jump::{
+    bates::Bates,
+    cgmy::{CGMYMethod, CGMY},
+    kou::KOU,
+    merton::Merton,
+    nig::NIG,
+    stable::{StableProcess, TemperedStable},
+    vg::VG,
+    vgsa::VGSA,
+  },
+  noise::{
+    cgns::CGNS,
+    fgn::{FGNMethod, FGN},
+    qmc::NoiseSource,
+  },
+  process::{
+    bm::BM,
+    cbms::CBMS,
+    cbmsd::CBMSD,
+    cpoisson::CompoundPoisson,
+    fbm::FBM,
+    hawkes::{Hawkes, HawkesKernel},
+    poisson::Poisson,
+  },
+  trend::{Trend, TrendComponent},
+  variance_reduction::{GaussianDriven, VarianceReduced, VarianceReduction},
+  volatility::{
+    bergomi::Bergomi, bns::BNS, heston::Heston, rbergomi::RBergomi, rough_heston::RoughHeston,
+    sabr::SABR, HestonPow,
+  },
+  FloatExt, Sampling, Sampling2D, Sampling3D, SamplingND, SamplingVector, K, N, S0, X0,
+};
@@ -1,8 +1,13 @@
 pub mod bm;
 pub mod cbms;
+pub mod cbmsd;
 pub mod ccustom;
 pub mod cfbms;
 pub mod cpoisson;
 pub mod customjt;
 pub mod fbm;
+pub mod fbm_bridge;
+pub mod hawkes;
 pub mod poisson;
+pub mod subordinator;
+pub mod time_changed_bm;
@@ -1,26 +1,28 @@
 use impl_new_derive::ImplNew;
 use ndarray::{s, Array1};
-use ndarray_rand::RandomExt;
-use rand_distr::Normal;
 
-use crate::stochastic::Sampling;
+use crate::stochastic::{
+  catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  FloatExt, Sampling,
+};
 
 #[derive(ImplNew)]
-pub struct BM {
+pub struct BM<T: FloatExt = f64> {
   pub n: usize,
-  pub t: Option<f64>,
+  pub t: Option<T>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for BM {
-  fn sample(&self) -> Array1<f64> {
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
-    let gn = Array1::random(self.n - 1, Normal::new(0.0, dt.sqrt()).unwrap());
-    let mut bm = Array1::<f64>::zeros(self.n);
+impl<T: FloatExt> Sampling<T> for BM<T> {
+  fn sample(&self) -> Array1<T> {
+    let dt = self.t.unwrap_or(T::one()) / T::from_usize_(self.n - 1);
+    let gn = T::normal_array(self.n - 1, T::zero(), dt.sqrt());
+    let mut bm = Array1::<T>::zeros(self.n);
     bm.slice_mut(s![1..]).assign(&gn);
 
     for i in 1..self.n {
-      bm[i] += bm[i - 1];
+      let prev = bm[i - 1];
+      bm[i] += prev;
     }
 
     bm
@@ -36,3 +38,15 @@ impl Sampling<f64> for BM {
     self.m
   }
 }
+
+impl<T: FloatExt> ProcessInfo for BM<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "BM",
+    title: "Brownian motion",
+    path: "stochastic::process::bm::BM",
+    kind: ModelKind::Process,
+    description: "W(0) = 0 with independent N(0, dt) increments",
+    parameters: &[ParameterInfo::N, ParameterInfo::T, ParameterInfo::M],
+    references: &[],
+  };
+}
@@ -1,7 +1,14 @@
 use impl_new_derive::ImplNew;
 use ndarray::{Array1, Array2};
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    noise::cgns::CGNS,
+    Sampling2D,
+  },
+};
 
 #[derive(ImplNew)]
 pub struct CBMS {
@@ -14,10 +21,7 @@ pub struct CBMS {
 
 impl Sampling2D<f64> for CBMS {
   fn sample(&self) -> [Array1<f64>; 2] {
-    assert!(
-      (-1.0..=1.0).contains(&self.rho),
-      "Correlation coefficient must be in [-1, 1]"
-    );
+    or_panic(self.validate());
 
     let mut bms = Array2::<f64>::zeros((2, self.n));
     let [cgn1, cgn2] = self.cgns.sample();
@@ -31,6 +35,14 @@ impl Sampling2D<f64> for CBMS {
     [bms.row(0).into_owned(), bms.row(1).into_owned()]
   }
 
+  /// Check the correlation coefficient
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -41,3 +53,51 @@ impl Sampling2D<f64> for CBMS {
     self.m
   }
 }
+
+impl ProcessInfo for CBMS {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CBMS",
+    title: "Correlated Brownian motions",
+    path: "stochastic::process::cbms::CBMS",
+    kind: ModelKind::Process,
+    description: "Two Brownian motions with correlation rho",
+    parameters: &[
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+    ],
+    references: &[],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::Axis;
+
+  use super::*;
+
+  #[test]
+  fn cbms_sample_par_shapes() {
+    let (rho, n, m) = (0.6, 101, 5000);
+    let cbms = CBMS::new(
+      rho,
+      n,
+      Some(1.0),
+      Some(m),
+      CGNS::new(rho, n - 1, Some(1.0), None),
+    );
+    let [bm1, bm2] = cbms.sample_par();
+
+    assert_eq!(bm1.dim(), (m, n));
+    assert_eq!(bm2.dim(), (m, n));
+    assert!(bm1.column(0).iter().chain(bm2.column(0)).all(|&x| x == 0.0));
+
+    // Every row is an independent path, B1(1) is approximately standard normal
+    assert_ne!(bm1.row(0), bm1.row(1));
+    let terminal = bm1.index_axis(Axis(1), n - 1);
+    assert!(terminal.mean().unwrap().abs() < 0.05);
+    assert!((terminal.var(1.0) - 1.0).abs() < 0.1);
+  }
+}
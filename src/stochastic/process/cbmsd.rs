@@ -0,0 +1,96 @@
+use impl_new_derive::ImplNew;
+use ndarray::Array2;
+
+use crate::stochastic::{
+  catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::cgnsd::CGNSD,
+  SamplingVector,
+};
+
+/// d-dimensional Brownian motion with correlated components, e.g. the drivers of
+/// basket and multi-asset models.
+///
+/// The increments are given by [`CGNSD`], which should have n - 1 steps.
+#[derive(ImplNew)]
+pub struct CBMSD {
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  pub cgnsd: CGNSD,
+}
+
+impl SamplingVector<f64> for CBMSD {
+  /// Sample the paths, one component per row
+  fn sample(&self) -> Array2<f64> {
+    let increments = self.cgnsd.sample();
+    let mut bms = Array2::<f64>::zeros((self.cgnsd.dim(), self.n));
+
+    for i in 1..self.n {
+      for j in 0..self.cgnsd.dim() {
+        bms[[j, i]] = bms[[j, i - 1]] + increments[[j, i - 1]];
+      }
+    }
+
+    bms
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for CBMSD {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CBMSD",
+    title: "Correlated Brownian motions in d dimensions",
+    path: "stochastic::process::cbmsd::CBMSD",
+    kind: ModelKind::Process,
+    description: "d Brownian motions with the given correlation matrix",
+    parameters: &[
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component(
+        "cgnsd",
+        "Correlated Gaussian noise generator with n - 1 steps",
+      ),
+    ],
+    references: &[],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use crate::stochastic::{noise::cgnsd::CovarianceDecomposition, N};
+
+  use super::*;
+
+  #[test]
+  fn correlated_bms_shape_and_start() {
+    let cov = array![[1.0, 0.5, 0.2], [0.5, 1.0, 0.3], [0.2, 0.3, 1.0]];
+    let bms = CBMSD::new(
+      N,
+      Some(1.0),
+      None,
+      CGNSD::new(
+        cov,
+        N - 1,
+        Some(1.0),
+        None,
+        CovarianceDecomposition::Cholesky,
+      ),
+    );
+    let paths = bms.sample();
+
+    assert_eq!(paths.dim(), (3, N));
+    assert!(paths.column(0).iter().all(|&x| x == 0.0));
+  }
+}
@@ -3,7 +3,13 @@ use ndarray::{Array1, Axis};
 use rand::thread_rng;
 use rand_distr::Distribution;
 
-use crate::stochastic::{Sampling, Sampling3D};
+use crate::{
+  error::{or_panic, StochasticError, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    Sampling, Sampling3D,
+  },
+};
 
 use super::customjt::CustomJt;
 
@@ -27,9 +33,7 @@ where
   E: Distribution<f64> + Send + Sync,
 {
   fn sample(&self) -> [Array1<f64>; 3] {
-    if self.n.is_none() && self.t_max.is_none() {
-      panic!("n or t_max must be provided");
-    }
+    or_panic(self.validate());
 
     let p = self.customjt.sample();
     let mut jumps = Array1::<f64>::zeros(self.n.unwrap_or(p.len()));
@@ -43,6 +47,14 @@ where
     [p, cum_jupms, jumps]
   }
 
+  /// Check that either n or t_max is given
+  fn validate(&self) -> StochasticResult<()> {
+    match (self.n, self.t_max) {
+      (None, None) => Err(StochasticError::MissingHorizon),
+      _ => self.customjt.validate(),
+    }
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n.unwrap_or(0)
@@ -53,3 +65,36 @@ where
     self.m
   }
 }
+
+impl<D, E> ProcessInfo for CompoundCustom<D, E>
+where
+  D: Distribution<f64> + Send + Sync,
+  E: Distribution<f64> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "CompoundCustom",
+    title: "Compound renewal process",
+    path: "stochastic::process::ccustom::CompoundCustom",
+    kind: ModelKind::Process,
+    description:
+      "Sum of i.i.d. jumps at the arrival times of a renewal process with custom interarrival times",
+    parameters: &[
+      ParameterInfo::integer(
+        "n",
+        "Number of jumps, t_max is used if None",
+        Interval::COUNT,
+        100.0,
+      )
+      .optional(),
+      ParameterInfo::real("t_max", "Time horizon", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::M,
+      ParameterInfo::component("jumps_distribution", "Distribution of the jump sizes"),
+      ParameterInfo::component(
+        "jump_times_distribution",
+        "Distribution of the interarrival times",
+      ),
+      ParameterInfo::component("customjt", "Renewal process of the jump times"),
+    ],
+    references: &[],
+  };
+}
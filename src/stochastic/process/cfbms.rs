@@ -1,7 +1,22 @@
+use std::f64::consts::FRAC_PI_2;
+
 use impl_new_derive::ImplNew;
+use nalgebra::{DMatrix, SymmetricEigen};
 use ndarray::{Array1, Array2};
+use ndarray_rand::RandomExt;
+use num_complex::Complex64;
+use rand_distr::StandardNormal;
+use statrs::function::gamma::gamma;
 
-use crate::stochastic::{noise::cfgns::CFGNS, Sampling2D};
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  math::linalg::cholesky_psd,
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    noise::cfgns::CFGNS,
+    Sampling2D, SamplingVector,
+  },
+};
 
 #[derive(ImplNew)]
 pub struct CFBMS {
@@ -14,10 +29,7 @@ pub struct CFBMS {
 
 impl Sampling2D<f64> for CFBMS {
   fn sample(&self) -> [Array1<f64>; 2] {
-    assert!(
-      (-1.0..=1.0).contains(&self.rho),
-      "Correlation coefficient must be in [-1, 1]"
-    );
+    or_panic(self.validate());
 
     let mut fbms = Array2::<f64>::zeros((2, self.n));
     let [fgn1, fgn2] = self.cfgns.sample();
@@ -31,6 +43,212 @@ impl Sampling2D<f64> for CFBMS {
     [fbms.row(0).to_owned(), fbms.row(1).to_owned()]
   }
 
+  /// Check the correlation coefficient
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Multivariate fractional Brownian motion (mfBm)
+///
+/// d-dimensional Gaussian process where every component is a fractional Brownian motion
+/// with its own Hurst exponent and the cross-covariance is given by the
+/// Amblard–Coeurjolly parameterization
+///
+/// E[X_i(s) X_j(t)] = sigma_i sigma_j / 2 * (w_ij(-s) + w_ij(t) - w_ij(t - s))
+///
+/// where w_ij(h) = (rho_ij - eta_ij sign(h)) |h|^{H_i + H_j} if H_i + H_j != 1 and
+/// w_ij(h) = rho_ij |h| + eta_ij h log|h| otherwise.
+///
+/// https://arxiv.org/abs/1007.0828
+pub struct MFBM {
+  /// Hurst exponent of each component
+  pub hurst: Array1<f64>,
+  /// Symmetric correlation matrix at time 1 (rho_ii = 1)
+  pub rho: Array2<f64>,
+  /// Antisymmetric time-reversibility matrix (eta = 0 for time-reversible processes)
+  pub eta: Array2<f64>,
+  /// Scale of each component
+  pub sigma: Array1<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  /// Cholesky factor of the joint covariance of the increments
+  chol: Array2<f64>,
+}
+
+impl MFBM {
+  #[must_use]
+  pub fn new(
+    hurst: Array1<f64>,
+    rho: Array2<f64>,
+    eta: Array2<f64>,
+    sigma: Option<Array1<f64>>,
+    n: usize,
+    t: Option<f64>,
+    m: Option<usize>,
+  ) -> Self {
+    or_panic(Self::try_new(hurst, rho, eta, sigma, n, t, m))
+  }
+
+  /// Fallible version of [`MFBM::new`]
+  pub fn try_new(
+    hurst: Array1<f64>,
+    rho: Array2<f64>,
+    eta: Array2<f64>,
+    sigma: Option<Array1<f64>>,
+    n: usize,
+    t: Option<f64>,
+    m: Option<usize>,
+  ) -> StochasticResult<Self> {
+    let d = hurst.len();
+    let sigma = sigma.unwrap_or(Array1::ones(d));
+    Self::check_parameters(&hurst, &rho, &eta)?;
+
+    let dt = t.unwrap_or(1.0) / (n - 1) as f64;
+    let cov = Self::increment_covariance(&hurst, &rho, &eta, &sigma, n - 1, dt);
+    let chol = cholesky_psd(&cov, 1e-12);
+
+    Ok(Self {
+      hurst,
+      rho,
+      eta,
+      sigma,
+      n,
+      t,
+      m,
+      chol,
+    })
+  }
+
+  /// Check the Amblard–Coeurjolly coherence conditions.
+  ///
+  /// The parameters define a valid mfBm iff the Hermitian matrix with entries
+  /// Γ(H_i + H_j + 1) (rho_ij sin(π (H_i + H_j) / 2) - i eta_ij cos(π (H_i + H_j) / 2))
+  /// (or Γ(H_i + H_j + 1) (rho_ij - i π / 2 eta_ij) if H_i + H_j = 1) is positive semi-definite.
+  pub fn check_parameters(
+    hurst: &Array1<f64>,
+    rho: &Array2<f64>,
+    eta: &Array2<f64>,
+  ) -> StochasticResult<()> {
+    let d = hurst.len();
+    ensure(rho.dim() == (d, d), "rho must be a d x d matrix")?;
+    ensure(eta.dim() == (d, d), "eta must be a d x d matrix")?;
+
+    for i in 0..d {
+      ensure(
+        hurst[i] > 0.0 && hurst[i] < 1.0,
+        "Hurst parameter must be in (0, 1)",
+      )?;
+      ensure((rho[[i, i]] - 1.0).abs() < 1e-12, "rho_ii must be 1")?;
+
+      for j in 0..d {
+        ensure(
+          (rho[[i, j]] - rho[[j, i]]).abs() < 1e-12,
+          "rho must be symmetric",
+        )?;
+        ensure(
+          (eta[[i, j]] + eta[[j, i]]).abs() < 1e-12,
+          "eta must be antisymmetric",
+        )?;
+      }
+    }
+
+    let coherence = DMatrix::<Complex64>::from_fn(d, d, |i, j| {
+      let h = hurst[i] + hurst[j];
+      let g = gamma(h + 1.0);
+
+      if (h - 1.0).abs() < 1e-12 {
+        g * Complex64::new(rho[[i, j]], -FRAC_PI_2 * eta[[i, j]])
+      } else {
+        g * Complex64::new(
+          rho[[i, j]] * (FRAC_PI_2 * h).sin(),
+          -eta[[i, j]] * (FRAC_PI_2 * h).cos(),
+        )
+      }
+    });
+
+    let eigenvalues = SymmetricEigen::new(coherence).eigenvalues;
+    ensure(
+      eigenvalues.iter().all(|&l| l >= -1e-10),
+      "Parameters violate the Amblard–Coeurjolly coherence condition",
+    )
+  }
+
+  fn w(rho: f64, eta: f64, hurst: f64, h: f64) -> f64 {
+    if h == 0.0 {
+      return 0.0;
+    }
+
+    if (hurst - 1.0).abs() < 1e-12 {
+      rho * h.abs() + eta * h * h.abs().ln()
+    } else {
+      (rho - eta * h.signum()) * h.abs().powf(hurst)
+    }
+  }
+
+  /// Cross-covariance of the unit lag increments of components i and j at lag h
+  fn gamma_ij(rho: f64, eta: f64, hurst: f64, h: f64) -> f64 {
+    0.5
+      * (Self::w(rho, eta, hurst, h - 1.0) - 2.0 * Self::w(rho, eta, hurst, h)
+        + Self::w(rho, eta, hurst, h + 1.0))
+  }
+
+  /// Joint covariance of the stacked increments (component major order)
+  fn increment_covariance(
+    hurst: &Array1<f64>,
+    rho: &Array2<f64>,
+    eta: &Array2<f64>,
+    sigma: &Array1<f64>,
+    n: usize,
+    dt: f64,
+  ) -> Array2<f64> {
+    let d = hurst.len();
+
+    Array2::from_shape_fn((d * n, d * n), |(a, b)| {
+      let (i, k) = (a / n, a % n);
+      let (j, l) = (b / n, b % n);
+      let h = hurst[i] + hurst[j];
+
+      sigma[i]
+        * sigma[j]
+        * dt.powf(h)
+        * Self::gamma_ij(rho[[i, j]], eta[[i, j]], h, l as f64 - k as f64)
+    })
+  }
+}
+
+impl SamplingVector<f64> for MFBM {
+  /// Sample the mfBm, one component per row
+  fn sample(&self) -> Array2<f64> {
+    let d = self.hurst.len();
+    let n = self.n - 1;
+    let z = Array1::random(d * n, StandardNormal);
+    let increments = self.chol.dot(&z);
+
+    let mut mfbm = Array2::<f64>::zeros((d, self.n));
+    for i in 0..d {
+      for k in 1..self.n {
+        mfbm[[i, k]] = mfbm[[i, k - 1]] + increments[i * n + k - 1];
+      }
+    }
+
+    mfbm
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.n
@@ -41,3 +259,95 @@ impl Sampling2D<f64> for CFBMS {
     self.m
   }
 }
+
+impl ProcessInfo for CFBMS {
+  const INFO: ModelInfo = ModelInfo {
+    name: "CFBMS",
+    title: "Correlated fractional Brownian motions",
+    path: "stochastic::process::cfbms::CFBMS",
+    kind: ModelKind::Process,
+    description: "Two fractional Brownian motions with correlated increments",
+    parameters: &[
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cfgns", "Correlated fractional Gaussian noise generator"),
+    ],
+    references: &[],
+  };
+}
+
+impl ProcessInfo for MFBM {
+  const INFO: ModelInfo = ModelInfo {
+    name: "MFBM",
+    title: "Multivariate fractional Brownian motion",
+    path: "stochastic::process::cfbms::MFBM",
+    kind: ModelKind::Process,
+    description: "E[X_i(s) X_j(t)] = sigma_i sigma_j / 2 (w_ij(-s) + w_ij(t) - w_ij(t - s))",
+    parameters: &[
+      ParameterInfo::array("hurst", "Hurst index of every component"),
+      ParameterInfo::array("rho", "Symmetric correlation matrix at time 1"),
+      ParameterInfo::array("eta", "Antisymmetric time-reversibility matrix"),
+      ParameterInfo::array("sigma", "Scale of every component, 1 if None").optional(),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Amblard, P. O., Coeurjolly, J. F., Lavancier, F., & Philippe, A. (2013). Basic properties of the multivariate fractional Brownian motion.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+
+  #[test]
+  fn mfbm_shape_and_start() {
+    let mfbm = MFBM::new(
+      array![0.3, 0.6, 0.8],
+      array![[1.0, 0.4, 0.2], [0.4, 1.0, 0.3], [0.2, 0.3, 1.0]],
+      Array2::zeros((3, 3)),
+      None,
+      200,
+      Some(1.0),
+      None,
+    );
+
+    let paths = mfbm.sample();
+    assert_eq!(paths.dim(), (3, 200));
+    assert!(paths.column(0).iter().all(|&x| x == 0.0));
+  }
+
+  #[test]
+  fn mfbm_try_new_returns_error() {
+    let mfbm = MFBM::try_new(
+      array![0.3, 1.2],
+      Array2::eye(2),
+      Array2::zeros((2, 2)),
+      None,
+      100,
+      Some(1.0),
+      None,
+    );
+    assert!(mfbm.is_err());
+  }
+
+  #[test]
+  #[should_panic(expected = "coherence")]
+  fn mfbm_rejects_incoherent_parameters() {
+    let _ = MFBM::new(
+      array![0.1, 0.9],
+      array![[1.0, 0.99], [0.99, 1.0]],
+      Array2::zeros((2, 2)),
+      None,
+      100,
+      Some(1.0),
+      None,
+    );
+  }
+}
@@ -3,27 +3,33 @@ use ndarray::{Array1, Axis};
 use rand::thread_rng;
 use rand_distr::Distribution;
 
-use crate::stochastic::{Sampling, Sampling3D};
+use crate::{
+  error::StochasticResult,
+  stochastic::{
+    catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, Sampling, Sampling3D,
+  },
+};
 
 use super::poisson::Poisson;
 
-#[derive(ImplNew)]
-pub struct CompoundPoisson<D>
+#[derive(ImplNew, Clone)]
+pub struct CompoundPoisson<D, T: FloatExt = f64>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
   pub m: Option<usize>,
   pub distribution: D,
-  pub poisson: Poisson,
+  pub poisson: Poisson<T>,
 }
 
-impl<D> Sampling3D<f64> for CompoundPoisson<D>
+impl<D, T: FloatExt> Sampling3D<T> for CompoundPoisson<D, T>
 where
-  D: Distribution<f64> + Send + Sync,
+  D: Distribution<T> + Send + Sync,
 {
-  fn sample(&self) -> [Array1<f64>; 3] {
+  fn sample(&self) -> [Array1<T>; 3] {
     let poisson = self.poisson.sample();
-    let mut jumps = Array1::<f64>::zeros(poisson.len());
+    let mut jumps = Array1::<T>::zeros(poisson.len());
     for i in 1..poisson.len() {
       jumps[i] = self.distribution.sample(&mut thread_rng());
     }
@@ -34,6 +40,11 @@ where
     [poisson, cum_jupms, jumps]
   }
 
+  /// Check the underlying Poisson process
+  fn validate(&self) -> StochasticResult<()> {
+    self.poisson.validate()
+  }
+
   /// Number of time steps
   fn n(&self) -> usize {
     self.poisson.n()
@@ -44,3 +55,50 @@ where
     self.m
   }
 }
+
+impl<D, T: FloatExt> ProcessInfo for CompoundPoisson<D, T>
+where
+  D: Distribution<T> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "CompoundPoisson",
+    title: "Compound Poisson process",
+    path: "stochastic::process::cpoisson::CompoundPoisson",
+    kind: ModelKind::Process,
+    description: "Sum of i.i.d. jumps at the arrival times of a Poisson process",
+    parameters: &[
+      ParameterInfo::M,
+      ParameterInfo::component("distribution", "Distribution of the jump sizes"),
+      ParameterInfo::component("poisson", "Poisson process of the jump times"),
+    ],
+    references: &[],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use rand_distr::Normal;
+
+  use super::*;
+
+  #[test]
+  fn cpoisson_sample_par_shapes() {
+    let cp = CompoundPoisson::new(
+      Some(100),
+      Normal::new(0.0, 1.0).unwrap(),
+      Poisson::new(1.0_f64, Some(50), None, None),
+    );
+    let [poisson, cum_jumps, jumps] = cp.sample_par();
+
+    for xs in [&poisson, &cum_jumps, &jumps] {
+      assert_eq!(xs.dim(), (100, 50));
+    }
+    assert!(poisson.column(49).iter().all(|&t| t > 0.0));
+    let total = jumps.sum_axis(Axis(1));
+    assert!(cum_jumps
+      .column(49)
+      .iter()
+      .zip(total.iter())
+      .all(|(a, b)| (a - b).abs() < 1e-10));
+  }
+}
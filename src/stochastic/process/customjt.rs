@@ -4,7 +4,13 @@ use ndarray_rand::RandomExt;
 use rand::thread_rng;
 use rand_distr::Distribution;
 
-use crate::stochastic::Sampling;
+use crate::{
+  error::{StochasticError, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    Sampling,
+  },
+};
 
 #[derive(ImplNew)]
 pub struct CustomJt<D>
@@ -42,7 +48,15 @@ where
 
       x
     } else {
-      panic!("n or t_max must be provided");
+      panic!("{}", StochasticError::MissingHorizon);
+    }
+  }
+
+  /// Check that either n or t_max is given
+  fn validate(&self) -> StochasticResult<()> {
+    match (self.n, self.t_max) {
+      (None, None) => Err(StochasticError::MissingHorizon),
+      _ => Ok(()),
     }
   }
 
@@ -56,3 +70,29 @@ where
     self.m
   }
 }
+
+impl<D> ProcessInfo for CustomJt<D>
+where
+  D: Distribution<f64> + Send + Sync,
+{
+  const INFO: ModelInfo = ModelInfo {
+    name: "CustomJt",
+    title: "Renewal process",
+    path: "stochastic::process::customjt::CustomJt",
+    kind: ModelKind::Process,
+    description: "Arrival times of a renewal process with custom interarrival times",
+    parameters: &[
+      ParameterInfo::integer(
+        "n",
+        "Number of arrivals, t_max is used if None",
+        Interval::COUNT,
+        100.0,
+      )
+      .optional(),
+      ParameterInfo::real("t_max", "Time horizon", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::M,
+      ParameterInfo::component("distribution", "Distribution of the interarrival times"),
+    ],
+    references: &[],
+  };
+}
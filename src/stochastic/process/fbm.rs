@@ -6,41 +6,69 @@ use ndarray::{s, Array1};
 #[cfg(feature = "malliavin")]
 use statrs::function::gamma;
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
+use crate::stochastic::{
+  catalog::{ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::fgn::{conditional_fgn, FGN},
+  FloatExt, Sampling,
+};
 
 #[derive(ImplNew)]
-pub struct FBM {
-  pub hurst: f64,
+pub struct FBM<T: FloatExt = f64> {
+  pub hurst: T,
   pub n: usize,
-  pub t: Option<f64>,
+  pub t: Option<T>,
   pub m: Option<usize>,
-  pub fgn: FGN,
+  pub fgn: FGN<T>,
   #[cfg(feature = "malliavin")]
   pub calculate_malliavin: Option<bool>,
   #[cfg(feature = "malliavin")]
-  malliavin: Mutex<Option<Array1<f64>>>,
+  malliavin: Mutex<Option<Array1<T>>>,
 }
 
-impl Sampling<f64> for FBM {
-  fn sample(&self) -> Array1<f64> {
+impl FBM {
+  /// Continue an observed path with a conditional simulation.
+  ///
+  /// The increments of the observed path are used to condition the future increments
+  /// on the fractional Gaussian noise covariance. The returned path has `horizon + 1`
+  /// points and starts at the last observed value.
+  pub fn sample_conditional(&self, observed: &Array1<f64>, horizon: usize) -> Array1<f64> {
+    assert!(!observed.is_empty(), "Observed path must not be empty");
+
+    let past = Array1::from_iter(observed.windows(2).into_iter().map(|w| w[1] - w[0]));
+    let increments = conditional_fgn(self.hurst, self.fgn.dt(), &past, horizon);
+
+    let mut fbm = Array1::<f64>::zeros(horizon + 1);
+    fbm[0] = *observed.last().unwrap();
+    for i in 1..=horizon {
+      fbm[i] = fbm[i - 1] + increments[i - 1];
+    }
+
+    fbm
+  }
+}
+
+impl<T: FloatExt> Sampling<T> for FBM<T> {
+  fn sample(&self) -> Array1<T> {
     let fgn = self.fgn.sample();
-    let mut fbm = Array1::<f64>::zeros(self.n);
+    let mut fbm = Array1::<T>::zeros(self.n);
     fbm.slice_mut(s![1..]).assign(&fgn);
 
     for i in 1..self.n {
-      fbm[i] += fbm[i - 1];
+      let prev = fbm[i - 1];
+      fbm[i] += prev;
     }
 
     #[cfg(feature = "malliavin")]
     if self.calculate_malliavin.is_some() && self.calculate_malliavin.unwrap() {
       let mut malliavin = Array1::zeros(self.n);
-      let dt = self.t.unwrap_or(1.0) / (self.n) as f64;
+      let hurst: f64 = self.hurst.into();
+      let dt = self.t.map_or(1.0, Into::into) / (self.n) as f64;
       for i in 0..self.n {
         malliavin[i] =
-          1.0 / (gamma::gamma(self.hurst + 0.5)) * (i as f64 * dt).powf(self.hurst - 0.5);
+          T::from_f64_(1.0 / (gamma::gamma(hurst + 0.5)) * (i as f64 * dt).powf(hurst - 0.5));
       }
 
-      let _ = std::mem::replace(&mut *self.malliavin.lock().unwrap(), Some(malliavin));
+      *self.malliavin.lock().unwrap() = Some(malliavin);
     }
     fbm.slice(s![..self.n()]).to_owned()
   }
@@ -64,11 +92,31 @@ impl Sampling<f64> for FBM {
   /// B^H_t = 1 / Γ(H + 1/2) ∫_0^t (t - s)^{H - 1/2} dW_s
   /// which is a truncated Wiener integral.
   #[cfg(feature = "malliavin")]
-  fn malliavin(&self) -> Array1<f64> {
+  fn malliavin(&self) -> Array1<T> {
     self.malliavin.lock().unwrap().clone().unwrap()
   }
 }
 
+impl<T: FloatExt> ProcessInfo for FBM<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FBM",
+    title: "Fractional Brownian motion",
+    path: "stochastic::process::fbm::FBM",
+    kind: ModelKind::Process,
+    description: "E[B(s) B(t)] = (s^2H + t^2H - |t - s|^2H) / 2",
+    parameters: &[
+      ParameterInfo::HURST,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Mandelbrot, B. B., & Van Ness, J. W. (1968). Fractional Brownian motions, fractional noises and applications.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   #[cfg(feature = "malliavin")]
@@ -122,6 +170,25 @@ mod tests {
     plot_1d!(fbm.sample(), "Fractional Brownian Motion (H = 0.7)");
   }
 
+  #[test]
+  fn fbm_conditional_continues_path() {
+    let fbm = FBM::new(
+      0.8,
+      N,
+      Some(1.0),
+      None,
+      FGN::new(0.8, N - 1, Some(1.0), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+
+    let observed = fbm.sample().slice(s![..200]).to_owned();
+    let future = fbm.sample_conditional(&observed, 100);
+
+    assert_eq!(future.len(), 101);
+    assert_eq!(future[0], observed[199]);
+  }
+
   #[test]
   #[cfg(feature = "malliavin")]
   fn fbm_malliavin() {
@@ -0,0 +1,264 @@
+use impl_new_derive::ImplNew;
+use nalgebra::{DMatrix, DVector};
+use ndarray::Array1;
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    noise::fgn::FGN,
+    Sampling,
+  },
+};
+
+/// Fractional Brownian bridge.
+///
+/// Fractional Brownian motion conditioned on its terminal value B(t) and optionally on
+/// intermediate values B(t_i) at grid points. An unconditioned path is sampled with
+/// the FFT based [`FGN`] generator and corrected with the conditional Gaussian formula
+///
+/// B(s) | B(S) = b ~ B(s) + C(s, S) C(S, S)^{-1} (b - B(S))
+///
+/// where S are the conditioning times and C(s, u) = (s^2H + u^2H - |s - u|^2H) / 2 is the
+/// fBM covariance, which gives an exact sample of the conditional law.
+#[derive(ImplNew)]
+pub struct FBMBridge {
+  pub hurst: f64,
+  pub n: usize,
+  pub t: Option<f64>,
+  /// Terminal value B(t)
+  pub terminal: f64,
+  /// Intermediate conditions as (time index, value) pairs, 0 < index < n - 1
+  pub conditions: Option<Vec<(usize, f64)>>,
+  pub m: Option<usize>,
+  pub fgn: FGN,
+}
+
+impl FBMBridge {
+  fn covariance(&self, s: f64, u: f64) -> f64 {
+    let h2 = 2.0 * self.hurst;
+    0.5 * (s.powf(h2) + u.powf(h2) - (s - u).abs().powf(h2))
+  }
+
+  /// Conditioning grid points and values, the terminal point last
+  fn knots(&self) -> Vec<(usize, f64)> {
+    let mut knots = self.conditions.clone().unwrap_or_default();
+    knots.sort_by_key(|&(idx, _)| idx);
+    knots.push((self.n - 1, self.terminal));
+    knots
+  }
+}
+
+impl Sampling<f64> for FBMBridge {
+  fn sample(&self) -> Array1<f64> {
+    or_panic(self.validate());
+
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    // The FGN increments have the step of its padded grid, rescaled to the one of the bridge
+    let scale = (dt / self.fgn.dt()).powf(self.hurst);
+    let fgn = self.fgn.sample();
+    let mut fbm = Array1::<f64>::zeros(self.n);
+    for i in 1..self.n {
+      fbm[i] = fbm[i - 1] + scale * fgn[i - 1];
+    }
+
+    let knots = self.knots();
+    let k = knots.len();
+    let times = knots
+      .iter()
+      .map(|&(idx, _)| idx as f64 * dt)
+      .collect::<Vec<_>>();
+    let c_kk = DMatrix::from_fn(k, k, |i, j| self.covariance(times[i], times[j]));
+    let residual = DVector::from_iterator(k, knots.iter().map(|&(idx, b)| b - fbm[idx]));
+    let alpha = c_kk
+      .cholesky()
+      .expect("Covariance of the conditioning points must be positive definite")
+      .solve(&residual);
+
+    for i in 1..self.n {
+      let s = i as f64 * dt;
+      fbm[i] += (0..k)
+        .map(|j| self.covariance(s, times[j]) * alpha[j])
+        .sum::<f64>();
+    }
+
+    fbm
+  }
+
+  /// Check the Hurst parameter and the conditioning points
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.hurst > 0.0 && self.hurst < 1.0,
+      "Hurst parameter must be in (0, 1)",
+    )?;
+    ensure(self.n > 1, "n must be at least 2")?;
+    ensure(
+      self.fgn.hurst == self.hurst && self.fgn.n() == self.n - 1,
+      "The FGN must have the Hurst parameter of the bridge and n - 1 steps",
+    )?;
+
+    let mut indices = self
+      .conditions
+      .iter()
+      .flatten()
+      .map(|&(idx, _)| idx)
+      .collect::<Vec<_>>();
+    ensure(
+      indices.iter().all(|&idx| idx > 0 && idx < self.n - 1),
+      "Intermediate conditions must be strictly inside the time grid",
+    )?;
+    indices.sort();
+    indices.dedup();
+    ensure(
+      indices.len() == self.conditions.as_ref().map_or(0, Vec::len),
+      "Intermediate conditions must be at distinct time indices",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for FBMBridge {
+  const INFO: ModelInfo = ModelInfo {
+    name: "FBMBridge",
+    title: "Fractional Brownian bridge",
+    path: "stochastic::process::fbm_bridge::FBMBridge",
+    kind: ModelKind::Process,
+    description: "Fractional Brownian motion conditioned on its terminal value and optional intermediate values",
+    parameters: &[
+      ParameterInfo::HURST,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::real("terminal", "Terminal value B(t)", Interval::REAL, 0.0),
+      ParameterInfo::array("conditions", "Intermediate conditions as (time index, value) pairs").optional(),
+      ParameterInfo::M,
+      ParameterInfo::component("fgn", "Fractional Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Sottinen, T., & Yazigi, A. (2014). Generalized Gaussian bridges.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{plot_1d, stochastic::N};
+
+  use super::*;
+
+  #[test]
+  fn fbm_bridge_hits_conditions() {
+    let bridge = FBMBridge::new(
+      0.7,
+      N,
+      Some(1.0),
+      1.5,
+      Some(vec![(N / 2, -0.5)]),
+      None,
+      FGN::new(0.7, N - 1, Some(1.0), None),
+    );
+    let path = bridge.sample();
+
+    assert_eq!(path.len(), N);
+    assert_eq!(path[0], 0.0);
+    assert!((path[N / 2] + 0.5).abs() < 1e-10);
+    assert!((path[N - 1] - 1.5).abs() < 1e-10);
+  }
+
+  #[test]
+  fn fbm_bridge_conditional_moments() {
+    let (hurst, n, paths) = (0.3, 129, 4000);
+    let bridge = FBMBridge::new(
+      hurst,
+      n,
+      Some(1.0),
+      1.0,
+      None,
+      Some(paths),
+      FGN::new(hurst, n - 1, Some(1.0), None),
+    );
+    let samples = bridge.sample_par();
+    let mid = samples.column(n / 2);
+
+    // B(1/2) | B(1) = 1 ~ N(C(1/2, 1), C(1/2, 1/2) - C(1/2, 1)^2)
+    let c = bridge.covariance(0.5, 1.0);
+    let variance = bridge.covariance(0.5, 0.5) - c.powi(2);
+
+    assert!((mid.mean().unwrap() - c).abs() < 0.05);
+    assert!((mid.var(1.0) / variance - 1.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn fbm_bridge_variance_on_a_padded_grid() {
+    // 999 increments are generated on the padded grid of 1024 steps
+    let (hurst, n, paths) = (0.7, 1000, 4000);
+    let bridge = FBMBridge::new(
+      hurst,
+      n,
+      Some(1.0),
+      0.0,
+      None,
+      Some(paths),
+      FGN::new(hurst, n - 1, Some(1.0), None),
+    );
+    let samples = bridge.sample_par();
+    let s = 500.0 / (n - 1) as f64;
+    let variance = bridge.covariance(s, s) - bridge.covariance(s, 1.0).powi(2);
+
+    assert!((samples.column(500).var(1.0) / variance - 1.0).abs() < 0.08);
+  }
+
+  #[test]
+  fn fbm_bridge_rejects_invalid_conditions() {
+    let bridge = FBMBridge::new(
+      0.7,
+      N,
+      Some(1.0),
+      0.0,
+      Some(vec![(N - 1, 1.0)]),
+      None,
+      FGN::new(0.7, N - 1, Some(1.0), None),
+    );
+
+    assert!(bridge.try_sample().is_err());
+
+    let mismatched = FBMBridge::new(
+      0.7,
+      N,
+      Some(1.0),
+      0.0,
+      None,
+      None,
+      FGN::new(0.6, N - 1, Some(1.0), None),
+    );
+    assert!(mismatched.try_sample().is_err());
+    let mismatched = FBMBridge {
+      fgn: FGN::new(0.7, N, Some(1.0), None),
+      hurst: 0.7,
+      ..mismatched
+    };
+    assert!(mismatched.try_sample().is_err());
+  }
+
+  #[test]
+  fn fbm_bridge_plot() {
+    let bridge = FBMBridge::new(
+      0.7,
+      N,
+      Some(1.0),
+      1.0,
+      Some(vec![(N / 4, 0.5), (3 * N / 4, -0.5)]),
+      None,
+      FGN::new(0.7, N - 1, Some(1.0), None),
+    );
+    plot_1d!(bridge.sample(), "Fractional Brownian Bridge (H = 0.7)");
+  }
+}
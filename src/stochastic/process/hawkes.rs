@@ -0,0 +1,336 @@
+//! Hawkes self-exciting point process.
+//!
+//! Every arrival raises the intensity of the next ones,
+//!
+//! lambda(t) = mu + sum_(t_i < t) phi(t - t_i)
+//!
+//! so the arrivals cluster like the jumps of volatile markets or the orders of a limit
+//! order book. Equivalently each arrival is an immigrant of rate mu or a child of an
+//! earlier arrival, which has on average n = int phi children, the branching ratio. For
+//! n < 1 the process is stationary with mean intensity mu / (1 - n) and clusters of
+//! 1 / (1 - n) arrivals, n close to 1 means most arrivals are triggered by others.
+//!
+//! The arrivals are simulated exactly by the thinning of Ogata (1981), the kernels are
+//! decreasing, so the intensity after the last arrival bounds it until the next one. The
+//! parameters are estimated by maximum likelihood in
+//! [`stats::hawkes`](crate::stats::hawkes).
+//!
+//! - Hawkes, A. G. (1971). Spectra of some self-exciting and mutually exciting point processes.
+//! - Ogata, Y. (1981). On Lewis' simulation method for point processes.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use rand::{thread_rng, Rng};
+use rand_distr::Exp1;
+
+use crate::{
+  error::{ensure, StochasticError, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    Sampling,
+  },
+};
+
+/// Excitation kernel phi(s) of the Hawkes process, alpha is its integral, the branching
+/// ratio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HawkesKernel {
+  /// alpha beta e^(-beta s), the excitation decays at the rate beta
+  Exponential { alpha: f64, beta: f64 },
+  /// alpha p c^p / (c + s)^(1 + p), the Omori law of long memory excitation
+  PowerLaw { alpha: f64, c: f64, p: f64 },
+}
+
+impl HawkesKernel {
+  /// phi(s)
+  pub fn value(&self, s: f64) -> f64 {
+    match *self {
+      Self::Exponential { alpha, beta } => alpha * beta * (-beta * s).exp(),
+      Self::PowerLaw { alpha, c, p } => alpha * p * c.powf(p) / (c + s).powf(1.0 + p),
+    }
+  }
+
+  /// int_0^s phi(u) du
+  pub fn integral(&self, s: f64) -> f64 {
+    match *self {
+      Self::Exponential { alpha, beta } => alpha * (1.0 - (-beta * s).exp()),
+      Self::PowerLaw { alpha, c, p } => alpha * (1.0 - (c / (c + s)).powf(p)),
+    }
+  }
+
+  /// Expected number of children of an arrival
+  pub fn branching_ratio(&self) -> f64 {
+    match *self {
+      Self::Exponential { alpha, .. } | Self::PowerLaw { alpha, .. } => alpha,
+    }
+  }
+
+  /// Check the parameters of the kernel
+  pub fn validate(&self) -> StochasticResult<()> {
+    match *self {
+      Self::Exponential { alpha, beta } => {
+        ensure(alpha >= 0.0, "alpha must be non-negative")?;
+        ensure(beta > 0.0, "beta must be positive")
+      }
+      Self::PowerLaw { alpha, c, p } => {
+        ensure(alpha >= 0.0, "alpha must be non-negative")?;
+        ensure(c > 0.0, "c must be positive")?;
+        ensure(p > 0.0, "p must be positive")
+      }
+    }
+  }
+}
+
+/// Hawkes process with baseline intensity mu
+///
+/// Like [`Poisson`](super::poisson::Poisson), the path starts at 0 and has the first n - 1
+/// arrival times, or the arrival times up to t_max if n is None.
+#[derive(ImplNew, Clone)]
+pub struct Hawkes {
+  pub mu: f64,
+  pub kernel: HawkesKernel,
+  pub n: Option<usize>,
+  pub t_max: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl Hawkes {
+  /// Expected number of children of an arrival
+  pub fn branching_ratio(&self) -> f64 {
+    self.kernel.branching_ratio()
+  }
+
+  /// The branching ratio is below 1
+  pub fn is_stationary(&self) -> bool {
+    self.branching_ratio() < 1.0
+  }
+
+  /// Mean intensity mu / (1 - n) of the stationary process, infinite otherwise
+  pub fn stationary_intensity(&self) -> f64 {
+    self.mu * self.mean_cluster_size()
+  }
+
+  /// Expected number of arrivals 1 / (1 - n) of a cluster, an immigrant and its
+  /// descendants, infinite if the process is not stationary
+  pub fn mean_cluster_size(&self) -> f64 {
+    match self.is_stationary() {
+      true => 1.0 / (1.0 - self.branching_ratio()),
+      false => f64::INFINITY,
+    }
+  }
+
+  /// Intensity lambda(t) after the arrivals before t
+  ///
+  /// The arrival times are increasing, a leading 0 of [`Sampling::sample`] is not an arrival.
+  pub fn intensity(&self, times: &Array1<f64>, t: f64) -> f64 {
+    self.mu
+      + arrivals(times)
+        .filter(|&ti| ti < t)
+        .map(|ti| self.kernel.value(t - ti))
+        .sum::<f64>()
+  }
+
+  /// Compensator Lambda(t) = int_0^t lambda(s) ds, the expected number of arrivals up to t
+  pub fn compensator(&self, times: &Array1<f64>, t: f64) -> f64 {
+    self.mu * t
+      + arrivals(times)
+        .filter(|&ti| ti < t)
+        .map(|ti| self.kernel.integral(t - ti))
+        .sum::<f64>()
+  }
+
+  /// Increments Lambda(t_i) - Lambda(t_(i-1)) of the compensator between the arrivals
+  ///
+  /// By the time change theorem these are i.i.d. Exp(1) if the arrivals follow the model,
+  /// e.g. a mean away from 1 or clustered residuals show a poor fit.
+  pub fn residuals(&self, times: &Array1<f64>) -> Array1<f64> {
+    let compensator = arrivals(times)
+      .map(|t| self.compensator(times, t))
+      .collect::<Vec<_>>();
+
+    Array1::from_iter(
+      compensator
+        .iter()
+        .scan(0.0, |previous, &c| Some(c - std::mem::replace(previous, c))),
+    )
+  }
+}
+
+/// Arrival times without the leading 0 of the paths
+fn arrivals(times: &Array1<f64>) -> impl Iterator<Item = f64> + '_ {
+  times.iter().copied().filter(|&t| t > 0.0)
+}
+
+impl Sampling<f64> for Hawkes {
+  /// Arrival times by Ogata's thinning
+  fn sample(&self) -> Array1<f64> {
+    let mut rng = thread_rng();
+    let mut times = vec![0.0];
+    let mut t = 0.0;
+    // Excitation at t, kept recursively for the exponential kernel
+    let mut excitation = 0.0;
+
+    loop {
+      if self.n.is_some_and(|n| times.len() >= n) {
+        break;
+      }
+
+      let bound = self.mu + excitation;
+      let w = rng.sample::<f64, _>(Exp1) / bound;
+      let next = t + w;
+      if self.n.is_none() && next >= self.t_max.unwrap() {
+        break;
+      }
+
+      excitation = match self.kernel {
+        HawkesKernel::Exponential { beta, .. } => excitation * (-beta * w).exp(),
+        HawkesKernel::PowerLaw { .. } => times[1..]
+          .iter()
+          .map(|&ti| self.kernel.value(next - ti))
+          .sum(),
+      };
+      t = next;
+
+      if rng.gen::<f64>() * bound <= self.mu + excitation {
+        times.push(t);
+        excitation += self.kernel.value(0.0);
+      }
+    }
+
+    Array1::from(times)
+  }
+
+  /// Check the intensities and that either n or t_max is given
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.mu > 0.0, "mu must be positive")?;
+    self.kernel.validate()?;
+    match (self.n, self.t_max) {
+      (None, None) => Err(StochasticError::MissingHorizon),
+      _ => Ok(()),
+    }
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n.unwrap_or(0)
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl ProcessInfo for Hawkes {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Hawkes",
+    title: "Hawkes process",
+    path: "stochastic::process::hawkes::Hawkes",
+    kind: ModelKind::Process,
+    description: "Arrival times of intensity lambda(t) = mu + sum_(t_i < t) phi(t - t_i) with an exponential or power law kernel",
+    parameters: &[
+      ParameterInfo::real("mu", "Baseline intensity", Interval::POSITIVE, 1.0),
+      ParameterInfo::component("kernel", "Exponential or power law excitation kernel"),
+      ParameterInfo::integer(
+        "n",
+        "Number of arrivals, t_max is used if None",
+        Interval::COUNT,
+        100.0,
+      )
+      .optional(),
+      ParameterInfo::real("t_max", "Time horizon", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Hawkes, A. G. (1971). Spectra of some self-exciting and mutually exciting point processes.",
+      "Ogata, Y. (1981). On Lewis' simulation method for point processes.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn hawkes_length_equals_n() {
+    let hawkes = Hawkes::new(
+      0.5,
+      HawkesKernel::Exponential {
+        alpha: 0.6,
+        beta: 2.0,
+      },
+      Some(100),
+      None,
+      Some(8),
+    );
+
+    let times = hawkes.sample();
+    assert_eq!(times.len(), 100);
+    assert_eq!(times[0], 0.0);
+    assert!(times.windows(2).into_iter().all(|w| w[1] > w[0]));
+    assert_eq!(hawkes.sample_par().dim(), (8, 100));
+  }
+
+  #[test]
+  fn hawkes_matches_the_stationary_intensity() {
+    for kernel in [
+      HawkesKernel::Exponential {
+        alpha: 0.6,
+        beta: 2.0,
+      },
+      HawkesKernel::PowerLaw {
+        alpha: 0.5,
+        c: 0.5,
+        p: 1.5,
+      },
+    ] {
+      let t_max = 1000.0;
+      let hawkes = Hawkes::new(0.5, kernel, None, Some(t_max), None);
+      let times = hawkes.sample();
+      assert!(times.iter().all(|&t| t < t_max));
+
+      // The residuals of the time change are Exp(1)
+      let residuals = hawkes.residuals(&times);
+      assert_eq!(residuals.len(), times.len() - 1);
+      let mean = residuals.mean().unwrap();
+      assert!((mean - 1.0).abs() < 0.1, "{:?} {}", kernel, mean);
+
+      let expected = hawkes.compensator(&times, t_max);
+      let count = (times.len() - 1) as f64;
+      assert!((count - expected).abs() < 4.0 * expected.sqrt());
+    }
+  }
+
+  #[test]
+  fn hawkes_branching_diagnostics() {
+    let mut hawkes = Hawkes::new(
+      0.5,
+      HawkesKernel::Exponential {
+        alpha: 0.75,
+        beta: 2.0,
+      },
+      None,
+      Some(10.0),
+      None,
+    );
+    assert!(hawkes.is_stationary());
+    assert!((hawkes.mean_cluster_size() - 4.0).abs() < 1e-12);
+    assert!((hawkes.stationary_intensity() - 2.0).abs() < 1e-12);
+
+    let times = ndarray::array![0.0, 1.0, 2.0];
+    let expected = 0.5 + 0.75 * 2.0 * ((-3.0f64).exp() + (-1.0f64).exp());
+    assert!((hawkes.intensity(&times, 2.5) - expected).abs() < 1e-12);
+
+    hawkes.kernel = HawkesKernel::PowerLaw {
+      alpha: 1.2,
+      c: 1.0,
+      p: 1.0,
+    };
+    assert!(!hawkes.is_stationary());
+    assert!(hawkes.stationary_intensity().is_infinite());
+    assert!(Hawkes::new(0.5, hawkes.kernel, None, None, None)
+      .validate()
+      .is_err());
+  }
+}
@@ -1,37 +1,38 @@
 use impl_new_derive::ImplNew;
 use ndarray::{Array0, Array1, Axis, Dim};
-use ndarray_rand::rand_distr::{Distribution, Exp};
-use ndarray_rand::RandomExt;
-use rand::thread_rng;
 
-use crate::stochastic::Sampling;
+use crate::{
+  error::{StochasticError, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, Sampling,
+  },
+};
 
-#[derive(ImplNew)]
-pub struct Poisson {
-  pub lambda: f64,
+#[derive(ImplNew, Clone)]
+pub struct Poisson<T: FloatExt = f64> {
+  pub lambda: T,
   pub n: Option<usize>,
-  pub t_max: Option<f64>,
+  pub t_max: Option<T>,
   pub m: Option<usize>,
 }
 
-impl Sampling<f64> for Poisson {
-  fn sample(&self) -> Array1<f64> {
+impl<T: FloatExt> Sampling<T> for Poisson<T> {
+  fn sample(&self) -> Array1<T> {
     if let Some(n) = self.n {
-      let exponentials = Array1::random(n, Exp::new(1.0 / self.lambda).unwrap());
-      let mut poisson = Array1::<f64>::zeros(n);
+      let exponentials = T::exp1_array(n) * self.lambda;
+      let mut poisson = Array1::<T>::zeros(n);
       for i in 1..n {
         poisson[i] = poisson[i - 1] + exponentials[i - 1];
       }
 
       poisson
     } else if let Some(t_max) = self.t_max {
-      let mut poisson = Array1::from(vec![0.0]);
-      let mut t = 0.0;
+      let mut poisson = Array1::from(vec![T::zero()]);
+      let mut t = T::zero();
 
       while t < t_max {
-        t += Exp::new(1.0 / self.lambda)
-          .unwrap()
-          .sample(&mut thread_rng());
+        t += T::exp1_array(1)[0] * self.lambda;
 
         if t < t_max {
           poisson
@@ -42,7 +43,15 @@ impl Sampling<f64> for Poisson {
 
       poisson
     } else {
-      panic!("n or t_max must be provided");
+      panic!("{}", StochasticError::MissingHorizon);
+    }
+  }
+
+  /// Check that either n or t_max is given
+  fn validate(&self) -> StochasticResult<()> {
+    match (self.n, self.t_max) {
+      (None, None) => Err(StochasticError::MissingHorizon),
+      _ => Ok(()),
     }
   }
 
@@ -56,3 +65,40 @@ impl Sampling<f64> for Poisson {
     self.m
   }
 }
+
+impl<T: FloatExt> ProcessInfo for Poisson<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Poisson",
+    title: "Poisson process",
+    path: "stochastic::process::poisson::Poisson",
+    kind: ModelKind::Process,
+    description: "Arrival times with i.i.d. exponential interarrival times of mean lambda",
+    parameters: &[
+      ParameterInfo::real("lambda", "Mean interarrival time", Interval::POSITIVE, 1.0),
+      ParameterInfo::integer(
+        "n",
+        "Number of arrivals, t_max is used if None",
+        Interval::COUNT,
+        100.0,
+      )
+      .optional(),
+      ParameterInfo::real("t_max", "Time horizon", Interval::POSITIVE, 1.0).optional(),
+      ParameterInfo::M,
+    ],
+    references: &[],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn poisson_interarrival_times_have_mean_lambda() {
+    let arrivals = Poisson::new(0.25_f64, Some(200_001), None, None).sample();
+
+    // The mean of 200_000 exponential gaps has the standard error 0.25 / 447
+    let mean = arrivals[200_000] / 200_000.0;
+    assert!((mean - 0.25).abs() < 3e-3, "{}", mean);
+  }
+}
@@ -0,0 +1,219 @@
+//! Subordinators, the increasing Lévy processes of the business time.
+//!
+//! A subordinator T(t) is a random clock, its Laplace transform is
+//!
+//! E[e^(-s T(t))] = e^(-t Phi(s))
+//!
+//! with the Laplace exponent Phi. The gamma and the inverse Gaussian subordinators here
+//! have mean t and variance nu t (kappa t), so they run on average like the calendar
+//! time. A Brownian motion with drift evaluated at the clock,
+//! [`TimeChangedBM`](super::time_changed_bm::TimeChangedBM), is the variance gamma process
+//! for the gamma clock and the normal inverse Gaussian process for the inverse Gaussian one.
+//!
+//! - Clark, P. K. (1973). A subordinated stochastic process model with finite variance for speculative prices.
+//! - Cont, R., & Tankov, P. (2004). Financial Modelling with Jump Processes, ch. 4.4.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use ndarray_rand::{
+  rand_distr::{Gamma, InverseGaussian},
+  RandomExt,
+};
+use num_complex::Complex64;
+
+use crate::{
+  error::{ensure, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    FloatExt, Sampling,
+  },
+};
+
+/// Increasing Lévy process from 0 with a known Laplace exponent
+pub trait Subordinator<T: FloatExt = f64>: Sampling<T> {
+  /// Laplace exponent Phi(s), E[e^(-s T(t))] = e^(-t Phi(s)), for complex s with
+  /// Re s >= 0
+  fn laplace_exponent(&self, s: Complex64) -> Complex64;
+}
+
+/// Path of the clock from the increments
+fn cumulate<T: FloatExt>(increments: Array1<T>) -> Array1<T> {
+  let mut clock = Array1::zeros(increments.len() + 1);
+  for (i, &dt) in increments.iter().enumerate() {
+    clock[i + 1] = clock[i] + dt;
+  }
+
+  clock
+}
+
+/// Gamma subordinator with mean t and variance nu t, the increments over dt are
+/// Gamma(dt / nu, nu)
+#[derive(ImplNew, Clone)]
+pub struct GammaSubordinator<T: FloatExt = f64> {
+  /// Variance rate
+  pub nu: T,
+  pub n: usize,
+  pub t: Option<T>,
+  pub m: Option<usize>,
+}
+
+impl<T: FloatExt> Sampling<T> for GammaSubordinator<T> {
+  /// The gamma increments are drawn in double precision
+  fn sample(&self) -> Array1<T> {
+    let nu: f64 = self.nu.into();
+    let dt = self.t.map_or(1.0, Into::into) / (self.n - 1) as f64;
+    cumulate(Array1::random(self.n - 1, Gamma::new(dt / nu, nu).unwrap()).mapv(T::from_f64_))
+  }
+
+  /// Check the variance rate
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.nu > T::zero(), "nu must be positive")?;
+    ensure(self.n >= 2, "n must be at least 2")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<T: FloatExt> Subordinator<T> for GammaSubordinator<T> {
+  /// ln(1 + nu s) / nu
+  fn laplace_exponent(&self, s: Complex64) -> Complex64 {
+    let nu: f64 = self.nu.into();
+    (1.0 + nu * s).ln() / nu
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for GammaSubordinator<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "GammaSubordinator",
+    title: "Gamma subordinator",
+    path: "stochastic::process::subordinator::GammaSubordinator",
+    kind: ModelKind::Process,
+    description: "Increasing gamma process with mean t and variance nu t",
+    parameters: &[
+      ParameterInfo::real("nu", "Variance rate", Interval::POSITIVE, 0.3),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Madan, D. B., Carr, P., & Chang, E. C. (1998). The variance gamma process and option pricing.",
+    ],
+  };
+}
+
+/// Inverse Gaussian subordinator with mean t and variance kappa t, the increments over dt
+/// are inverse Gaussian of mean dt and shape dt^2 / kappa
+#[derive(ImplNew, Clone)]
+pub struct IGSubordinator<T: FloatExt = f64> {
+  /// Variance rate
+  pub kappa: T,
+  pub n: usize,
+  pub t: Option<T>,
+  pub m: Option<usize>,
+}
+
+impl<T: FloatExt> Sampling<T> for IGSubordinator<T> {
+  /// The inverse Gaussian increments are drawn in double precision
+  fn sample(&self) -> Array1<T> {
+    let kappa: f64 = self.kappa.into();
+    let dt = self.t.map_or(1.0, Into::into) / (self.n - 1) as f64;
+    cumulate(
+      Array1::random(
+        self.n - 1,
+        InverseGaussian::new(dt, dt.powi(2) / kappa).unwrap(),
+      )
+      .mapv(T::from_f64_),
+    )
+  }
+
+  /// Check the variance rate
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.kappa > T::zero(), "kappa must be positive")?;
+    ensure(self.n >= 2, "n must be at least 2")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<T: FloatExt> Subordinator<T> for IGSubordinator<T> {
+  /// (sqrt(1 + 2 kappa s) - 1) / kappa
+  fn laplace_exponent(&self, s: Complex64) -> Complex64 {
+    let kappa: f64 = self.kappa.into();
+    ((1.0 + 2.0 * kappa * s).sqrt() - 1.0) / kappa
+  }
+}
+
+impl<T: FloatExt> ProcessInfo for IGSubordinator<T> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "IGSubordinator",
+    title: "Inverse Gaussian subordinator",
+    path: "stochastic::process::subordinator::IGSubordinator",
+    kind: ModelKind::Process,
+    description: "Increasing inverse Gaussian process with mean t and variance kappa t",
+    parameters: &[
+      ParameterInfo::real("kappa", "Variance rate", Interval::POSITIVE, 0.2),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Barndorff-Nielsen, O. E. (1997). Normal inverse Gaussian distributions and stochastic volatility modelling.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::Axis;
+
+  use super::*;
+
+  fn check<S: Subordinator>(subordinator: &S, variance_rate: f64) {
+    let paths = subordinator.sample_par();
+    assert!(paths.column(0).iter().all(|&t| t == 0.0));
+    assert!(paths
+      .axis_iter(Axis(0))
+      .all(|path| path.windows(2).into_iter().all(|w| w[1] >= w[0])));
+
+    // Mean t = 1, variance nu t and the Laplace transform at s = 1
+    let clock = paths.column(subordinator.n() - 1).to_owned();
+    let mean = clock.mean().unwrap();
+    let variance = clock.var(1.0);
+    let laplace = clock.mapv(|t| (-t).exp()).mean().unwrap();
+    let expected = (-subordinator.laplace_exponent(Complex64::new(1.0, 0.0)).re).exp();
+    assert!((mean - 1.0).abs() < 0.03, "{}", mean);
+    assert!((variance - variance_rate).abs() < 0.05, "{}", variance);
+    assert!(
+      (laplace - expected).abs() < 0.01,
+      "{} {}",
+      laplace,
+      expected
+    );
+  }
+
+  #[test]
+  fn gamma_subordinator_moments() {
+    check(&GammaSubordinator::new(0.3, 51, Some(1.0), Some(4000)), 0.3);
+  }
+
+  #[test]
+  fn ig_subordinator_moments() {
+    check(&IGSubordinator::new(0.2, 51, Some(1.0), Some(4000)), 0.2);
+  }
+}
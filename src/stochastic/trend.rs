@@ -0,0 +1,208 @@
+//! Deterministic trends added to sampled paths.
+//!
+//! [`Trend`] wraps a sampler and adds a deterministic function of time m(t) to every
+//! path, X(t) = m(t) + Y(t) with Y the path of the sampler, e.g. a linear or exponential
+//! growth of a mean reverting series or a seasonal level. The trends are linear in their
+//! coefficients (the exponential one in logarithms), so they are calibrated to data by
+//! ordinary least squares in [`stats::trend`](crate::stats::trend), which also detrends
+//! series.
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+
+use super::{FloatExt, Sampling};
+use crate::error::{ensure, StochasticResult};
+
+/// Deterministic function of time m(t)
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrendComponent {
+  /// a + b t
+  Linear { intercept: f64, slope: f64 },
+  /// a e^(b t)
+  Exponential { scale: f64, rate: f64 },
+  /// Cubic spline sum_i c_i B_i(t) of the B-splines of the knots, see [`spline_basis`],
+  /// constant beyond the first and the last knot
+  Spline {
+    knots: Array1<f64>,
+    coefficients: Array1<f64>,
+  },
+}
+
+impl TrendComponent {
+  /// Trend m(t)
+  pub fn value(&self, t: f64) -> f64 {
+    match self {
+      Self::Linear { intercept, slope } => intercept + slope * t,
+      Self::Exponential { scale, rate } => scale * (rate * t).exp(),
+      Self::Spline {
+        knots,
+        coefficients,
+      } => spline_basis(knots, t).dot(coefficients),
+    }
+  }
+
+  /// Trend at the times
+  pub fn values(&self, times: &Array1<f64>) -> Array1<f64> {
+    times.mapv(|t| self.value(t))
+  }
+
+  /// Check the knots and the number of coefficients of the spline
+  pub fn validate(&self) -> StochasticResult<()> {
+    match self {
+      Self::Spline {
+        knots,
+        coefficients,
+      } => {
+        ensure(
+          knots.len() >= 2 && knots.windows(2).into_iter().all(|k| k[1] > k[0]),
+          "spline needs at least 2 increasing knots",
+        )?;
+        ensure(
+          coefficients.len() == knots.len() + 2,
+          "spline needs 2 more coefficients than knots",
+        )
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+/// Cubic B-splines B_0(t), ..., B_(k+1)(t) of the k increasing knots, clamped at the first
+/// and the last knot
+///
+/// The B-splines are non-negative and sum to 1, a cubic polynomial on [k_0, k_(k-1)] is a
+/// combination of them. The times are clamped to the knots, so the spline is constant
+/// beyond them.
+pub fn spline_basis(knots: &Array1<f64>, t: f64) -> Array1<f64> {
+  let (first, last) = (knots[0], knots[knots.len() - 1]);
+  let t = t.clamp(first, last);
+  let tau = std::iter::repeat_n(first, 3)
+    .chain(knots.iter().copied())
+    .chain(std::iter::repeat_n(last, 3))
+    .collect::<Vec<_>>();
+
+  // Degree 0, the last non-empty interval is closed
+  let mut basis = (0..tau.len() - 1)
+    .map(|i| {
+      let inside = tau[i] <= t && t < tau[i + 1];
+      let end = t == last && tau[i] < last && tau[i + 1] == last;
+      if inside || end {
+        1.0
+      } else {
+        0.0
+      }
+    })
+    .collect::<Vec<f64>>();
+
+  // Cox-de Boor recursion
+  for degree in 1..=3 {
+    basis = (0..basis.len() - 1)
+      .map(|i| {
+        let mut value = 0.0;
+        if tau[i + degree] > tau[i] {
+          value += (t - tau[i]) / (tau[i + degree] - tau[i]) * basis[i];
+        }
+        if tau[i + degree + 1] > tau[i + 1] {
+          value += (tau[i + degree + 1] - t) / (tau[i + degree + 1] - tau[i + 1]) * basis[i + 1];
+        }
+        value
+      })
+      .collect();
+  }
+
+  Array1::from(basis)
+}
+
+/// Sampler with a deterministic trend added to its paths
+#[derive(ImplNew)]
+pub struct Trend<S> {
+  pub sampler: S,
+  pub component: TrendComponent,
+  /// Time horizon of the paths of the sampler
+  pub t: Option<f64>,
+}
+
+impl<S> Trend<S> {
+  /// Trend on the n points of the path
+  pub fn trend(&self, n: usize) -> Array1<f64> {
+    self
+      .component
+      .values(&Array1::linspace(0.0, self.t.unwrap_or(1.0), n))
+  }
+}
+
+impl<T: FloatExt, S: Sampling<T>> Sampling<T> for Trend<S> {
+  /// Path of the sampler plus the trend
+  fn sample(&self) -> Array1<T> {
+    let mut path = self.sampler.sample();
+    for (x, m) in path.iter_mut().zip(self.trend(self.sampler.n())) {
+      *x += T::from_f64_(m);
+    }
+
+    path
+  }
+
+  /// Check the trend and the parameters of the sampler
+  fn validate(&self) -> StochasticResult<()> {
+    self.component.validate()?;
+    self.sampler.validate()
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.sampler.n()
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.sampler.m()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+  use crate::stochastic::{diffusion::ou::OU, process::bm::BM};
+
+  #[test]
+  fn spline_basis_is_a_partition_of_unity() {
+    let knots = array![0.0, 0.25, 0.5, 0.75, 1.0];
+    for t in [-0.5, 0.0, 0.1, 0.5, 0.99, 1.0, 1.3] {
+      let basis = spline_basis(&knots, t);
+      assert_eq!(basis.len(), 7);
+      assert!(basis.iter().all(|&b| b >= 0.0));
+      assert!((basis.sum() - 1.0).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn trend_is_added_to_the_paths() {
+    // A constant OU path at 0 shows the trend itself
+    let ou = OU::new(0.0, 0.0, 1.0, 11, Some(0.0), Some(2.0), None, None);
+    let trend = Trend::new(
+      ou,
+      TrendComponent::Exponential {
+        scale: 1.5,
+        rate: 0.1,
+      },
+      Some(2.0),
+    );
+    let path = trend.sample();
+    for (i, x) in path.iter().enumerate() {
+      assert!((x - 1.5 * (0.1 * 0.2 * i as f64).exp()).abs() < 1e-12);
+    }
+
+    let bm = BM::new(101, Some(1.0), Some(4));
+    let invalid = Trend::new(
+      bm,
+      TrendComponent::Spline {
+        knots: array![0.0, 1.0],
+        coefficients: array![1.0, 2.0],
+      },
+      Some(1.0),
+    );
+    assert!(invalid.validate().is_err());
+  }
+}
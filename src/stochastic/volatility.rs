@@ -1,7 +1,9 @@
 pub mod bergomi;
+pub mod bns;
 pub mod fheston;
 pub mod heston;
 pub mod rbergomi;
+pub mod rough_heston;
 pub mod sabr;
 pub mod svcgmy;
 
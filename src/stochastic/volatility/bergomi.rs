@@ -1,9 +1,13 @@
 use impl_new_derive::ImplNew;
 use ndarray::{s, Array1};
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::cgns::CGNS,
+  Sampling2D,
+};
 
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 pub struct Bergomi {
   pub nu: f64,
   pub v0: Option<f64>,
@@ -48,3 +52,26 @@ impl Sampling2D<f64> for Bergomi {
     self.m
   }
 }
+
+impl ProcessInfo for Bergomi {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Bergomi",
+    title: "Bergomi model",
+    path: "stochastic::volatility::bergomi::Bergomi",
+    kind: ModelKind::Volatility,
+    description:
+      "dS(t) = r S(t) dt + sqrt(v(t)) S(t) dW1(t) with a lognormal forward variance driven by W2",
+    parameters: &[
+      ParameterInfo::real("nu", "Volatility of the variance", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("v0", "Initial volatility", Interval::POSITIVE, 0.2).optional(),
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("r", "Risk-free rate", Interval::REAL, 0.05),
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+    ],
+    references: &["Bergomi, L. (2005). Smile dynamics II."],
+  };
+}
@@ -0,0 +1,169 @@
+//! Barndorff-Nielsen & Shephard stochastic volatility model.
+//!
+//! dX(t) = (mu + beta v(t)) dt + sqrt(v(t)) dW(t) + rho dZ(lambda t)
+//! dv(t) = -lambda v(t) dt + dZ(lambda t)
+//!
+//! The log-price X jumps down with the variance for rho < 0, the leverage effect. Over a
+//! step the integrated variance is (v(t) - v(t + dt) + dZ) / lambda exactly, and the
+//! increment of X is conditionally normal given the variance path.
+//!
+//! - Barndorff-Nielsen, O. E., & Shephard, N. (2001). Non-Gaussian Ornstein-Uhlenbeck-based models and some of their uses in financial economics.
+//! - Nicolato, E., & Venardos, E. (2003). Option pricing in stochastic volatility models of the Ornstein-Uhlenbeck type.
+
+use std::ops::Bound;
+
+use impl_new_derive::ImplNew;
+use ndarray::Array1;
+use rand::thread_rng;
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::levy_ou::{GammaOU, LevyOU},
+    Sampling2D,
+  },
+};
+
+/// Barndorff-Nielsen & Shephard model with a gamma-OU or IG-OU variance
+///
+/// The samples are the price s0 e^X and the variance.
+#[derive(ImplNew, Clone)]
+pub struct BNS<V: LevyOU = GammaOU> {
+  /// Drift of the log-price, [`BNS::martingale_drift`] under Q
+  pub mu: f64,
+  /// Volatility risk premium, -1/2 under Q
+  pub beta: f64,
+  /// Leverage, the jump of the log-price per jump of the variance
+  pub rho: f64,
+  /// Variance process, its x0 is the initial variance and its n and t are the ones of the path
+  pub variance: V,
+  pub s0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<V: LevyOU> BNS<V> {
+  /// Drift mu = r - lambda k(rho) making e^(-rt) S(t) a martingale for beta = -1/2, with the
+  /// cumulant k of the BDLP
+  pub fn martingale_drift(&self, r: f64) -> f64 {
+    r - self.variance.lambda() * self.variance.bdlp_cumulant(self.rho)
+  }
+}
+
+impl<V: LevyOU> Sampling2D<f64> for BNS<V> {
+  /// Sample the price and the variance
+  fn sample(&self) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+
+    let mut rng = thread_rng();
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let lambda = self.variance.lambda();
+
+    let mut s = Array1::<f64>::zeros(self.n);
+    let mut v = Array1::<f64>::zeros(self.n);
+    s[0] = self.s0.unwrap_or(100.0);
+    v[0] = self.variance.initial();
+
+    for i in 1..self.n {
+      let (next, dz) = self.variance.step(v[i - 1], dt, &mut rng);
+      let integrated = ((v[i - 1] - next + dz) / lambda).max(0.0);
+      let z: f64 = StandardNormal.sample(&mut rng);
+
+      v[i] = next;
+      s[i] = s[i - 1]
+        * (self.mu * dt + self.beta * integrated + integrated.sqrt() * z + self.rho * dz).exp();
+    }
+
+    [s, v]
+  }
+
+  /// Check the parameters
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(self.n >= 2, "n must be at least 2")?;
+    ensure(self.variance.lambda() > 0.0, "lambda must be positive")?;
+    ensure(self.rho <= 0.0, "rho must be non-positive")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<V: LevyOU> ProcessInfo for BNS<V> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "BNS",
+    title: "Barndorff-Nielsen & Shephard model",
+    path: "stochastic::volatility::bns::BNS",
+    kind: ModelKind::Volatility,
+    description: "dX(t) = (mu + beta v(t)) dt + sqrt(v(t)) dW(t) + rho dZ(lambda t) with a Lévy-driven OU variance",
+    parameters: &[
+      ParameterInfo::real("mu", "Drift of the log-price", Interval::REAL, 0.05),
+      ParameterInfo::real("beta", "Volatility risk premium", Interval::REAL, -0.5),
+      ParameterInfo::real("rho", "Leverage", Interval::new(Bound::Unbounded, Bound::Included(0.0)), -1.0),
+      ParameterInfo::component("variance", "Gamma-OU or IG-OU variance process"),
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Barndorff-Nielsen, O. E., & Shephard, N. (2001). Non-Gaussian Ornstein-Uhlenbeck-based models and some of their uses in financial economics.",
+      "Nicolato, E., & Venardos, E. (2003). Option pricing in stochastic volatility models of the Ornstein-Uhlenbeck type.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stochastic::diffusion::levy_ou::IGOU;
+
+  /// Discounted mean of the terminal price under the martingale drift
+  fn discounted_mean<V: LevyOU>(variance: V, paths: usize) -> f64 {
+    let (r, t) = (0.05, 1.0);
+    let mut bns = BNS::new(0.0, -0.5, -1.0, variance, Some(100.0), 51, Some(t), None);
+    bns.mu = bns.martingale_drift(r);
+
+    (0..paths)
+      .map(|_| *bns.sample()[0].last().unwrap())
+      .sum::<f64>()
+      / paths as f64
+      * (-r * t).exp()
+  }
+
+  #[test]
+  fn bns_discounted_price_is_a_martingale() {
+    // The standard errors are about 0.15
+    let gamma = GammaOU::new(2.0, 1.2, 30.0, 2, Some(0.04), None, None);
+    let mean = discounted_mean(gamma, 20_000);
+    assert!((mean - 100.0).abs() < 0.7, "{}", mean);
+
+    let ig = IGOU::new(2.0, 0.2, 5.0, 2, Some(0.04), None, None);
+    let mean = discounted_mean(ig, 20_000);
+    assert!((mean - 100.0).abs() < 0.7, "{}", mean);
+  }
+
+  #[test]
+  fn bns_leverage_shifts_the_log_returns() {
+    // E[X(t)] = mu t + beta E[int v] + rho E[Z(lambda t)] with E[Z(lambda t)] = nu lambda t / alpha
+    let (lambda, nu, alpha, paths) = (2.0, 1.2, 30.0, 20_000);
+    let gamma = GammaOU::new(lambda, nu, alpha, 2, None, None, None);
+    let bns = BNS::new(0.0, 0.0, -1.0, gamma, None, 51, Some(1.0), None);
+
+    let mean = (0..paths)
+      .map(|_| (bns.sample()[0].last().unwrap() / 100.0).ln())
+      .sum::<f64>()
+      / paths as f64;
+    // The standard error is about 0.0015
+    assert!((mean + nu * lambda / alpha).abs() < 0.008, "{}", mean);
+  }
+}
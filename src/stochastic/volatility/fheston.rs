@@ -1,3 +1,5 @@
+#![allow(deprecated)]
+
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 use ndarray_rand::RandomExt;
@@ -6,6 +8,9 @@ use statrs::function::gamma::gamma;
 
 use crate::stochastic::Sampling;
 
+/// Variance of the rough Heston model sampled from a Markovian approximation, superseded
+/// by [`RoughHeston`](super::rough_heston::RoughHeston)
+#[deprecated(note = "use `rough_heston::RoughHeston`")]
 #[derive(ImplNew)]
 pub struct RoughHeston {
   pub v0: Option<f64>,
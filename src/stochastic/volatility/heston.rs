@@ -2,9 +2,21 @@
 use std::sync::Mutex;
 
 use impl_new_derive::ImplNew;
-use ndarray::Array1;
+use ndarray::{Array1, ArrayView2};
+use num_complex::Complex64;
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    diffusion::cir::{
+      check_sqrt_diffusion, sqrt_diffusion_step, DiscretizationScheme, QeTransition,
+    },
+    noise::cgns::CGNS,
+    variance_reduction::GaussianDriven,
+    Distribution, Measure, MeasureChange, RiskPremia, Sampling2D,
+  },
+};
 
 use super::HestonPow;
 
@@ -35,6 +47,11 @@ pub struct Heston {
   pub pow: HestonPow,
   /// Use the symmetric method for the variance to avoid negative values
   pub use_sym: Option<bool>,
+  /// Discretization scheme of the variance, Euler if None.
+  /// Schemes other than Euler are only available for [`HestonPow::Sqrt`]. With
+  /// [`DiscretizationScheme::Exact`] and [`DiscretizationScheme::QuadraticExponential`] the
+  /// price is sampled conditionally on the variance path.
+  pub scheme: Option<DiscretizationScheme>,
   /// Number of paths for multithreading
   pub m: Option<usize>,
   /// Noise generator
@@ -50,64 +67,70 @@ pub struct Heston {
   malliavin_of_price: Mutex<Option<Array1<f64>>>,
 }
 
+impl Clone for Heston {
+  /// Copy of the parameters with empty Malliavin derivatives
+  fn clone(&self) -> Self {
+    Self {
+      s0: self.s0,
+      v0: self.v0,
+      kappa: self.kappa,
+      theta: self.theta,
+      sigma: self.sigma,
+      rho: self.rho,
+      mu: self.mu,
+      n: self.n,
+      t: self.t,
+      pow: self.pow,
+      use_sym: self.use_sym,
+      scheme: self.scheme,
+      m: self.m,
+      cgns: self.cgns.clone(),
+      #[cfg(feature = "malliavin")]
+      calculate_malliavin: self.calculate_malliavin,
+      #[cfg(feature = "malliavin")]
+      malliavin_of_vol: Mutex::new(None),
+      #[cfg(feature = "malliavin")]
+      malliavin_of_price: Mutex::new(None),
+    }
+  }
+}
+
 impl Sampling2D<f64> for Heston {
   fn sample(&self) -> [Array1<f64>; 2] {
-    let [cgn1, cgn2] = self.cgns.sample();
-    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    or_panic(self.validate());
 
-    let mut s = Array1::<f64>::zeros(self.n);
-    let mut v = Array1::<f64>::zeros(self.n);
-
-    s[0] = self.s0.unwrap_or(0.0);
-    v[0] = self.v0.unwrap_or(0.0);
+    let [cgn1, cgn2] = self.cgns.sample();
+    let [s, v] = self.path_from_noise(&cgn1, &cgn2);
 
-    for i in 1..self.n {
-      s[i] = s[i - 1] + self.mu * s[i - 1] * dt + s[i - 1] * v[i - 1].sqrt() * cgn1[i - 1];
+    #[cfg(feature = "malliavin")]
+    self.store_malliavin(&s, &v, &cgn2);
 
-      let dv = self.kappa * (self.theta - v[i - 1]) * dt
-        + self.sigma
-          * v[i - 1].powf(match self.pow {
-            HestonPow::Sqrt => 0.5,
-            HestonPow::ThreeHalves => 1.5,
-          })
-          * cgn2[i - 1];
-
-      v[i] = match self.use_sym.unwrap_or(false) {
-        true => (v[i - 1] + dv).abs(),
-        false => (v[i - 1] + dv).max(0.0),
-      }
-    }
+    [s, v]
+  }
 
-    #[cfg(feature = "malliavin")]
-    if self.calculate_malliavin.is_some() && self.calculate_malliavin.unwrap() {
-      let mut det_term = Array1::zeros(self.n);
-      let mut malliavin = Array1::zeros(self.n);
-
-      for i in 0..self.n {
-        match self.pow {
-          HestonPow::Sqrt => {
-            det_term[i] = ((-(self.kappa * self.theta / 2.0 - self.sigma.powi(2) / 8.0)
-              * (1.0 / v.last().unwrap())
-              - self.kappa / 2.0)
-              * ((self.n - i) as f64 * dt))
-              .exp();
-            malliavin[i] = (self.sigma * v.last().unwrap().sqrt() / 2.0) * det_term[i];
-          }
-          HestonPow::ThreeHalves => {
-            det_term[i] = ((-(self.kappa * self.theta / 2.0 + 3.0 * self.sigma.powi(2) / 8.0)
-              * v.last().unwrap()
-              - (self.kappa * self.theta) / 2.0)
-              * ((self.n - i) as f64 * dt))
-              .exp();
-            malliavin[i] = (self.sigma * v.last().unwrap().powf(1.5) / 2.0) * det_term[i];
-          }
-        };
-      }
+  /// Check the correlation and the restrictions of the variance scheme
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )?;
 
-      let _ = std::mem::replace(&mut *self.malliavin_of_vol.lock().unwrap(), Some(malliavin));
+    let scheme = self.scheme.unwrap_or_default();
+    if scheme != DiscretizationScheme::Euler {
+      ensure(
+        matches!(self.pow, HestonPow::Sqrt),
+        "Only the Euler scheme is available for the 3/2 model",
+      )?;
+      check_sqrt_diffusion(
+        scheme,
+        self.kappa,
+        self.theta,
+        self.sigma,
+        self.t.unwrap_or(1.0) / (self.n - 1) as f64,
+      )?;
     }
 
-    [s, v]
+    Ok(())
   }
 
   /// Number of time steps
@@ -120,17 +143,17 @@ impl Sampling2D<f64> for Heston {
     self.m
   }
 
-  /// Malliavin derivative of the volatility
-  ///
-  /// The Malliavin derivative of the Heston model is given by
-  /// D_r v_t = \sigma v_t^{1/2} / 2 * exp(-(\kappa \theta / 2 - \sigma^2 / 8) / v_t * dt)
-  ///
-  /// The Malliavin derivative of the 3/2 Heston model is given by
-  /// D_r v_t = \sigma v_t^{3/2} / 2 * exp(-(\kappa \theta / 2 + 3 \sigma^2 / 8) * v_t * dt)
+  /// Malliavin derivatives of the terminal price and variance, see [`Heston::store_malliavin`]
   #[cfg(feature = "malliavin")]
   fn malliavin(&self) -> [Array1<f64>; 2] {
     [
-      Array1::zeros(self.n),
+      self
+        .malliavin_of_price
+        .lock()
+        .unwrap()
+        .as_ref()
+        .unwrap()
+        .clone(),
       self
         .malliavin_of_vol
         .lock()
@@ -142,6 +165,321 @@ impl Sampling2D<f64> for Heston {
   }
 }
 
+impl Heston {
+  /// Malliavin derivatives of the terminal price and variance at the grid times r
+  ///
+  /// D_r S(T) = sqrt(v(r)) S(T) in the direction of the price Brownian motion with the
+  /// variance path held fixed, and D_r v(T) = sigma v(r)^p Y(T) / Y(r) in the direction of
+  /// the variance Brownian motion, where Y = dv / dv(0) is the first variation of the Euler
+  /// dynamics of the variance.
+  #[cfg(feature = "malliavin")]
+  fn store_malliavin(&self, s: &Array1<f64>, v: &Array1<f64>, cgn2: &Array1<f64>) {
+    if !self.calculate_malliavin.unwrap_or(false) {
+      return;
+    }
+
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let p = match self.pow {
+      HestonPow::Sqrt => 0.5,
+      HestonPow::ThreeHalves => 1.5,
+    };
+
+    let mut y = Array1::<f64>::ones(self.n);
+    for i in 1..self.n {
+      let jacobian = match v[i - 1] > 0.0 {
+        true => 1.0 - self.kappa * dt + p * self.sigma * v[i - 1].powf(p - 1.0) * cgn2[i - 1],
+        false => 1.0 - self.kappa * dt,
+      };
+      y[i] = y[i - 1] * jacobian;
+    }
+
+    let (s_t, y_t) = (s[self.n - 1], y[self.n - 1]);
+    let price = v.mapv(|v| v.max(0.0).sqrt() * s_t);
+    let vol = Array1::from_shape_fn(self.n, |i| self.sigma * v[i].max(0.0).powf(p) * y_t / y[i]);
+
+    *self.malliavin_of_price.lock().unwrap() = Some(price);
+    *self.malliavin_of_vol.lock().unwrap() = Some(vol);
+  }
+
+  /// Price and variance paths from the correlated Brownian increments
+  fn path_from_noise(&self, cgn1: &Array1<f64>, cgn2: &Array1<f64>) -> [Array1<f64>; 2] {
+    let scheme = self.scheme.unwrap_or_default();
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+
+    let mut s = Array1::<f64>::zeros(self.n);
+    let mut v = Array1::<f64>::zeros(self.n);
+
+    s[0] = self.s0.unwrap_or(0.0);
+    v[0] = self.v0.unwrap_or(0.0);
+
+    match scheme {
+      DiscretizationScheme::Exact => return self.exact_path(s, v, cgn1, cgn2, dt),
+      DiscretizationScheme::QuadraticExponential => return self.qe_path(s, v, cgn1, cgn2, dt),
+      _ => {}
+    }
+
+    for i in 1..self.n {
+      s[i] = s[i - 1] + self.mu * s[i - 1] * dt + s[i - 1] * v[i - 1].sqrt() * cgn1[i - 1];
+
+      v[i] = match (scheme, self.pow) {
+        (DiscretizationScheme::Euler, HestonPow::ThreeHalves) => {
+          let dv = self.kappa * (self.theta - v[i - 1]) * dt
+            + self.sigma * v[i - 1].powf(1.5) * cgn2[i - 1];
+
+          match self.use_sym.unwrap_or(false) {
+            true => (v[i - 1] + dv).abs(),
+            false => (v[i - 1] + dv).max(0.0),
+          }
+        }
+        _ => sqrt_diffusion_step(
+          scheme,
+          self.kappa,
+          self.theta,
+          self.sigma,
+          v[i - 1],
+          dt,
+          cgn2[i - 1],
+          self.use_sym.unwrap_or(false),
+        ),
+      }
+    }
+
+    [s, v]
+  }
+
+  /// Broadie-Kaya scheme: exact variance transition and the log price conditional on it
+  ///
+  /// Integrating the variance SDE gives int sigma sqrt(v) dW2 = v(n+1) - v(n) - kappa theta dt
+  /// + kappa int v dt, so with dW1 = rho dW2 + sqrt(1 - rho^2) dW⊥
+  ///
+  /// ln S(n+1) = ln S(n) + mu dt - int v dt / 2 + rho / sigma (v(n+1) - v(n) - kappa theta dt
+  /// + kappa int v dt) + sqrt(1 - rho^2) sqrt(int v dt) dW⊥ / sqrt(dt)
+  ///
+  /// The integrated variance is approximated by the trapezoidal rule instead of inverting its
+  /// conditional characteristic function, and sqrt(1 - rho^2) dW⊥ = dW1 - rho dW2.
+  fn exact_path(
+    &self,
+    mut s: Array1<f64>,
+    mut v: Array1<f64>,
+    cgn1: &Array1<f64>,
+    cgn2: &Array1<f64>,
+    dt: f64,
+  ) -> [Array1<f64>; 2] {
+    let (kappa, theta, sigma, rho) = (self.kappa, self.theta, self.sigma, self.rho);
+    let mut log_s = s[0].ln();
+
+    for i in 1..self.n {
+      v[i] = sqrt_diffusion_step(
+        DiscretizationScheme::Exact,
+        kappa,
+        theta,
+        sigma,
+        v[i - 1],
+        dt,
+        cgn2[i - 1],
+        false,
+      );
+
+      let integrated = 0.5 * (v[i - 1] + v[i]) * dt;
+      log_s += self.mu * dt - 0.5 * integrated
+        + rho / sigma * (v[i] - v[i - 1] - kappa * theta * dt + kappa * integrated)
+        + (integrated / dt).sqrt() * (cgn1[i - 1] - rho * cgn2[i - 1]);
+      s[i] = log_s.exp();
+    }
+
+    [s, v]
+  }
+
+  /// Quadratic-Exponential scheme of Andersen (2008) with the martingale correction
+  ///
+  /// The variance follows the QE transition and, with the trapezoidal weights
+  /// gamma1 = gamma2 = 1 / 2,
+  ///
+  /// ln S(n+1) = ln S(n) + mu dt + K0 + K1 v(n) + K2 v(n+1) + sqrt(K3 v(n) + K4 v(n+1)) Z
+  ///
+  /// K1 = gamma1 dt (kappa rho / sigma - 1 / 2) - rho / sigma,
+  /// K2 = gamma2 dt (kappa rho / sigma - 1 / 2) + rho / sigma,
+  /// K3 = gamma1 dt (1 - rho^2), K4 = gamma2 dt (1 - rho^2).
+  ///
+  /// K0 = -ln E[exp(A v(n+1)) | v(n)] - (K1 + K3 / 2) v(n) with A = K2 + K4 / 2, so that
+  /// e^(-mu t) S(t) is a discrete time martingale. Where the moment generating function
+  /// does not exist the uncorrected K0 = -rho kappa theta dt / sigma is used.
+  fn qe_path(
+    &self,
+    mut s: Array1<f64>,
+    mut v: Array1<f64>,
+    cgn1: &Array1<f64>,
+    cgn2: &Array1<f64>,
+    dt: f64,
+  ) -> [Array1<f64>; 2] {
+    let (kappa, theta, sigma, rho) = (self.kappa, self.theta, self.sigma, self.rho);
+    let gamma = 0.5;
+    let k1 = gamma * dt * (kappa * rho / sigma - 0.5) - rho / sigma;
+    let k2 = gamma * dt * (kappa * rho / sigma - 0.5) + rho / sigma;
+    let k3 = gamma * dt * (1.0 - rho.powi(2));
+    let a = k2 + 0.5 * k3;
+    let mut log_s = s[0].ln();
+
+    for i in 1..self.n {
+      let transition = QeTransition::new(kappa, theta, sigma, v[i - 1], dt);
+      v[i] = transition.sample(cgn2[i - 1] / dt.sqrt());
+
+      let k0 = match transition.mgf(a) {
+        Some(mgf) => -mgf.ln() - (k1 + 0.5 * k3) * v[i - 1],
+        None => -rho * kappa * theta * dt / sigma,
+      };
+      // sqrt(1 - rho^2) Z sqrt(dt) = dW1 - rho dW2, and K3 = K4
+      log_s += self.mu * dt
+        + k0
+        + k1 * v[i - 1]
+        + k2 * v[i]
+        + (gamma * (v[i - 1] + v[i])).sqrt() * (cgn1[i - 1] - rho * cgn2[i - 1]);
+      s[i] = log_s.exp();
+    }
+
+    [s, v]
+  }
+
+  /// Characteristic function of the log return ln(S(t) / S(0)) at a complex argument.
+  ///
+  /// Uses the formulation of Albrecher et al. (2007) which avoids the branch cut of the
+  /// complex logarithm. Only valid for [`HestonPow::Sqrt`].
+  pub fn log_return_cf(&self, w: Complex64) -> Complex64 {
+    let t = self.t.unwrap_or(1.0);
+    let i = Complex64::i();
+    let (kappa, sigma, rho) = (self.kappa, self.sigma, self.rho);
+
+    let beta = kappa - rho * sigma * i * w;
+    let d = (beta.powi(2) + sigma.powi(2) * (i * w + w.powi(2))).sqrt();
+    let g = (beta - d) / (beta + d);
+    let e = (-d * t).exp();
+
+    let c = kappa * self.theta / sigma.powi(2)
+      * ((beta - d) * t - 2.0 * ((1.0 - g * e) / (1.0 - g)).ln());
+    let dd = (beta - d) / sigma.powi(2) * (1.0 - e) / (1.0 - g * e);
+
+    (i * w * self.mu * t + c + dd * self.v0.unwrap_or(0.0)).exp()
+  }
+}
+
+impl GaussianDriven<f64> for Heston {
+  type Path = [Array1<f64>; 2];
+
+  fn drivers(&self) -> usize {
+    2
+  }
+
+  fn increments(&self) -> usize {
+    self.n - 1
+  }
+
+  /// Price and variance paths, the increments of the price and the variance Brownian
+  /// motions are sqrt(dt) z_0 and sqrt(dt) (rho z_0 + sqrt(1 - rho^2) z_1)
+  fn sample_from_increments(&self, z: ArrayView2<f64>) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+
+    let sqrt_dt = (self.t.unwrap_or(1.0) / (self.n - 1) as f64).sqrt();
+    let cgn1 = z.row(0).mapv(|z| sqrt_dt * z);
+    let cgn2 = &cgn1 * self.rho
+      + z
+        .row(1)
+        .mapv(|z| (1.0 - self.rho.powi(2)).sqrt() * sqrt_dt * z);
+
+    let [s, v] = self.path_from_noise(&cgn1, &cgn2);
+    #[cfg(feature = "malliavin")]
+    self.store_malliavin(&s, &v, &cgn2);
+
+    [s, v]
+  }
+}
+
+impl Distribution for Heston {
+  /// Characteristic function of the log return ln(S(t) / S(0))
+  fn characteristic_function(&self, u: f64) -> Complex64 {
+    self.log_return_cf(Complex64::new(u, 0.0))
+  }
+
+  /// Moment generating function of the log return, E[e^(uX)] = phi(-iu)
+  fn moment_generating_function(&self, u: f64) -> f64 {
+    let mgf = self.log_return_cf(Complex64::new(0.0, -u));
+
+    match mgf.re.is_finite() && mgf.re > 0.0 {
+      true => mgf.re,
+      false => f64::INFINITY,
+    }
+  }
+}
+
+impl MeasureChange for Heston {
+  /// (mu - (r - q)) s, the equity risk premium of the price
+  fn drift_adjustment(&self, x: f64, premia: &RiskPremia) -> f64 {
+    (self.mu - (premia.r - premia.q)) * x
+  }
+
+  /// Under Q the price drift is r - q and the variance drift kappa (theta - v) - lambda v,
+  /// i.e. kappa* = kappa + lambda and theta* = kappa theta / (kappa + lambda)
+  fn under_measure(&self, measure: Measure, premia: &RiskPremia) -> Self {
+    let (mu, kappa, theta) = match measure {
+      Measure::RealWorld => (self.mu, self.kappa, self.theta),
+      Measure::RiskNeutral => (
+        premia.r - premia.q,
+        self.kappa + premia.lambda,
+        self.kappa * self.theta / (self.kappa + premia.lambda),
+      ),
+    };
+
+    Self::new(
+      self.s0,
+      self.v0,
+      kappa,
+      theta,
+      self.sigma,
+      self.rho,
+      mu,
+      self.n,
+      self.t,
+      self.pow,
+      self.use_sym,
+      self.scheme,
+      self.m,
+      CGNS::new(self.cgns.rho, self.cgns.n, self.cgns.t, self.cgns.m),
+      #[cfg(feature = "malliavin")]
+      self.calculate_malliavin,
+    )
+  }
+}
+
+impl ProcessInfo for Heston {
+  const INFO: ModelInfo = ModelInfo {
+    name: "Heston",
+    title: "Heston stochastic volatility model",
+    path: "stochastic::volatility::heston::Heston",
+    kind: ModelKind::Volatility,
+    description: "dS(t) = mu S(t) dt + sqrt(v(t)) S(t) dW1(t), dv(t) = kappa (theta - v(t)) dt + sigma v(t)^p dW2(t)",
+    parameters: &[
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("v0", "Initial variance", Interval::NON_NEGATIVE, 0.04).optional(),
+      ParameterInfo::real("kappa", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("theta", "Long-run variance", Interval::POSITIVE, 0.04),
+      ParameterInfo::real("sigma", "Volatility of the variance", Interval::POSITIVE, 0.3),
+      ParameterInfo::RHO,
+      ParameterInfo::real("mu", "Drift of the price", Interval::REAL, 0.05),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::choice("pow", "Power p of the variance in its diffusion", &["Sqrt", "ThreeHalves"]),
+      ParameterInfo::boolean("use_sym", "Reflect instead of truncate negative variance").optional(),
+      ParameterInfo::choice("scheme", "Discretization scheme of the variance", &["Euler", "Milstein", "Alfonsi", "Lamperti", "Exact", "QuadraticExponential"]).optional(),
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator with n - 1 steps"),
+    ],
+    references: &[
+      "Heston, S. L. (1993). A closed-form solution for options with stochastic volatility with applications to bond and currency options.",
+      "Broadie, M., & Kaya, Ö. (2006). Exact simulation of stochastic volatility and other affine jump diffusion processes.",
+      "Andersen, L. (2008). Simple and efficient simulation of the Heston stochastic volatility model.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   #[cfg(feature = "malliavin")]
@@ -150,9 +488,205 @@ mod tests {
     stochastic::{N, S0, X0},
   };
 
-  #[cfg(feature = "malliavin")]
   use super::*;
 
+  #[test]
+  fn heston_risk_neutral_parameters() {
+    let heston = Heston::new(
+      Some(100.0),
+      Some(0.04),
+      2.0,
+      0.04,
+      0.3,
+      -0.7,
+      0.08,
+      100,
+      Some(1.0),
+      HestonPow::Sqrt,
+      None,
+      None,
+      None,
+      CGNS::new(-0.7, 99, Some(1.0), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let premia = RiskPremia {
+      r: 0.03,
+      q: 0.0,
+      lambda: 0.5,
+    };
+    let q = heston.under_measure(Measure::RiskNeutral, &premia);
+
+    assert_eq!(q.mu, 0.03);
+    assert_eq!(q.kappa, 2.5);
+    // The long-run variance drift kappa theta is unchanged
+    assert!((q.kappa * q.theta - heston.kappa * heston.theta).abs() < 1e-12);
+    assert_eq!(heston.under_measure(Measure::RealWorld, &premia).kappa, 2.0);
+  }
+
+  #[test]
+  fn heston_alfonsi_variance_is_unbiased() {
+    let (kappa, theta, sigma, v0, n) = (1.5, 0.04, 0.3, 0.04, 11);
+    let paths = 10_000;
+
+    let heston = Heston::new(
+      Some(100.0),
+      Some(v0),
+      kappa,
+      theta,
+      sigma,
+      -0.7,
+      0.0,
+      n,
+      Some(1.0),
+      HestonPow::Sqrt,
+      None,
+      Some(DiscretizationScheme::Alfonsi),
+      None,
+      CGNS::new(-0.7, n - 1, Some(1.0), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+
+    let mut mean = 0.0;
+    for _ in 0..paths {
+      let [_, v] = heston.sample();
+      assert!(v.iter().all(|v| *v >= 0.0));
+      mean += v[n - 1] / paths as f64;
+    }
+
+    // E[v_T] = theta + (v0 - theta) e^{-kappa T} = theta
+    assert!((mean - theta).abs() < 2e-3, "{}", mean);
+  }
+
+  #[test]
+  fn heston_exact_scheme_prices_call() {
+    use crate::quant::{pricing::heston::HestonPricer, r#trait::Pricer};
+
+    // Feller ratio 2 kappa theta / sigma^2 = 0.16
+    let (kappa, theta, sigma, rho, v0, r, n) = (1.0, 0.04, 1.0, -0.7, 0.04, 0.03, 21);
+    let paths = 40_000;
+
+    let heston = Heston::new(
+      Some(100.0),
+      Some(v0),
+      kappa,
+      theta,
+      sigma,
+      rho,
+      r,
+      n,
+      Some(1.0),
+      HestonPow::Sqrt,
+      None,
+      Some(DiscretizationScheme::Exact),
+      None,
+      CGNS::new(rho, n - 1, Some(1.0), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let payoffs = Array1::from_shape_fn(paths, |_| {
+      let [s, v] = heston.sample();
+      assert!(v.iter().all(|v| *v >= 0.0));
+      (-r).exp() * (s[n - 1] - 100.0).max(0.0)
+    });
+
+    let (call, _) = HestonPricer::new(
+      100.0,
+      v0,
+      100.0,
+      r,
+      None,
+      rho,
+      kappa,
+      theta,
+      sigma,
+      Some(0.0),
+      Some(1.0),
+      None,
+      None,
+    )
+    .calculate_call_put();
+    let std_error = (payoffs.var(1.0) / paths as f64).sqrt();
+
+    assert!(
+      (payoffs.mean().unwrap() - call).abs() < 4.0 * std_error + 0.05,
+      "{} vs {}",
+      payoffs.mean().unwrap(),
+      call
+    );
+  }
+
+  #[test]
+  fn heston_qe_scheme_prices_call_at_coarse_steps() {
+    use crate::quant::{pricing::heston::HestonPricer, r#trait::Pricer};
+
+    // Feller ratio 0.16 and quarterly steps
+    let (kappa, theta, sigma, rho, v0, r, n) = (1.0, 0.04, 1.0, -0.7, 0.04, 0.03, 5);
+    let paths = 40_000;
+
+    let heston = Heston::new(
+      Some(100.0),
+      Some(v0),
+      kappa,
+      theta,
+      sigma,
+      rho,
+      r,
+      n,
+      Some(1.0),
+      HestonPow::Sqrt,
+      None,
+      Some(DiscretizationScheme::QuadraticExponential),
+      None,
+      CGNS::new(rho, n - 1, Some(1.0), None),
+      #[cfg(feature = "malliavin")]
+      None,
+    );
+    let (mut terminal, mut payoffs) = (Array1::zeros(paths), Array1::zeros(paths));
+    for i in 0..paths {
+      let [s, v] = heston.sample();
+      assert!(v.iter().all(|v| *v >= 0.0));
+      terminal[i] = s[n - 1];
+      payoffs[i] = (-r).exp() * (s[n - 1] - 100.0).max(0.0);
+    }
+
+    // The martingale correction keeps the discounted price a martingale
+    let forward = 100.0 * r.exp();
+    let forward_error = (terminal.var(1.0) / paths as f64).sqrt();
+    assert!(
+      (terminal.mean().unwrap() - forward).abs() < 4.0 * forward_error,
+      "{} vs {}",
+      terminal.mean().unwrap(),
+      forward
+    );
+
+    let (call, _) = HestonPricer::new(
+      100.0,
+      v0,
+      100.0,
+      r,
+      None,
+      rho,
+      kappa,
+      theta,
+      sigma,
+      Some(0.0),
+      Some(1.0),
+      None,
+      None,
+    )
+    .calculate_call_put();
+    let std_error = (payoffs.var(1.0) / paths as f64).sqrt();
+
+    assert!(
+      (payoffs.mean().unwrap() - call).abs() < 4.0 * std_error + 0.05,
+      "{} vs {}",
+      payoffs.mean().unwrap(),
+      call
+    );
+  }
+
   #[test]
   #[cfg(feature = "malliavin")]
   fn heston_malliavin() {
@@ -169,6 +703,7 @@ mod tests {
       HestonPow::Sqrt,
       None,
       None,
+      None,
       CGNS::new(0.7, N, None, None),
       Some(true),
     );
@@ -1,9 +1,21 @@
+#![allow(deprecated)]
+
 use impl_new_derive::ImplNew;
-use ndarray::{s, Array1};
+use ndarray::{s, Array1, Array2, ArrayView2};
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    noise::cgns::CGNS,
+    variance_reduction::GaussianDriven,
+    FloatExt, Sampling2D,
+  },
+};
 
-#[derive(ImplNew)]
+/// Rough Bergomi sampler driven by correlated Gaussian noise, superseded by [`RBergomi`]
+#[deprecated(note = "use `RBergomi`")]
+#[derive(ImplNew, Clone)]
 pub struct RoughBergomi {
   pub hurst: f64,
   pub nu: f64,
@@ -27,7 +39,7 @@ impl Sampling2D<f64> for RoughBergomi {
     s[0] = self.s0.unwrap_or(100.0);
     v2[0] = self.v0.unwrap_or(1.0).powi(2);
 
-    for i in 1..=self.n {
+    for i in 1..self.n {
       s[i] = s[i - 1] + self.r * s[i - 1] * dt + v2[i - 1].sqrt() * s[i - 1] * cgn1[i - 1];
 
       let sum_z = z.slice(s![..i]).sum();
@@ -51,3 +63,254 @@ impl Sampling2D<f64> for RoughBergomi {
     self.m
   }
 }
+
+impl ProcessInfo for RoughBergomi {
+  const INFO: ModelInfo = ModelInfo {
+    name: "RoughBergomi",
+    title: "Rough Bergomi model",
+    path: "stochastic::volatility::rbergomi::RoughBergomi",
+    kind: ModelKind::Volatility,
+    description: "dS(t) = r S(t) dt + sqrt(v(t)) S(t) dW1(t), v(t) = v0^2 exp(nu sqrt(2H) int_0^t (t - s)^(H - 1/2) dW2(s) - nu^2 t^2H / 2)",
+    parameters: &[
+      ParameterInfo::HURST.example(0.1),
+      ParameterInfo::real("nu", "Volatility of the variance", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("v0", "Initial volatility", Interval::POSITIVE, 0.2).optional(),
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("r", "Risk-free rate", Interval::REAL, 0.05),
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+    ],
+    references: &[
+      "Bayer, C., Friz, P., & Gatheral, J. (2016). Pricing under rough volatility.",
+    ],
+  };
+}
+
+/// Rough Bergomi model (Bayer, Friz & Gatheral, 2016)
+///
+/// dS(t) = r S(t) dt + sqrt(v(t)) S(t) (rho dW(t) + sqrt(1 - rho^2) dW⊥(t))
+///
+/// v(t) = xi0(t) exp(eta Y(t) - eta^2 Var(Y(t)) / 2), Y(t) = sqrt(2H) int_0^t (t - s)^(H - 1/2) dW(s)
+///
+/// The Riemann-Liouville process Y is simulated with the hybrid scheme of Bennedsen, Lunde &
+/// Pakkanen (2017) with kappa = 1: the kernel is integrated exactly over the last step and
+/// approximated at the optimal points b_k of the earlier steps. The variance of Y in the
+/// compensator is the one of the scheme, so E[v(t)] = xi0(t) on the grid.
+#[derive(ImplNew, Clone)]
+pub struct RBergomi<X = fn(f64) -> f64> {
+  /// Volatility of the variance
+  pub eta: f64,
+  /// Hurst index of the volatility, H < 1/2 for rough volatility
+  pub hurst: f64,
+  /// Correlation between the price and the variance
+  pub rho: f64,
+  /// Initial forward variance curve, e.g. one built from an option chain with
+  /// [`ForwardVarianceCurve`](crate::quant::volatility::forward_variance::ForwardVarianceCurve)
+  pub xi0: X,
+  /// Initial price
+  pub s0: Option<f64>,
+  /// Drift of the price
+  pub r: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Time to maturity
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl<X: Fn(f64) -> f64 + Send + Sync> RBergomi<X> {
+  /// Weights (b_k dt)^(H - 1/2) of the increments dW(t(i - k + 1)) in Y(t(i)), k >= 2, with
+  /// b_k = ((k^(H + 1/2) - (k - 1)^(H + 1/2)) / (H + 1/2))^(1 / (H - 1/2))
+  fn kernel_weights(&self, dt: f64) -> Array1<f64> {
+    let alpha = self.hurst - 0.5;
+
+    Array1::from_shape_fn(self.n, |k| match k {
+      0 | 1 => 0.0,
+      _ => {
+        let k = k as f64;
+        let b =
+          ((k.powf(alpha + 1.0) - (k - 1.0).powf(alpha + 1.0)) / (alpha + 1.0)).powf(1.0 / alpha);
+        (b * dt).powf(alpha)
+      }
+    })
+  }
+
+  /// Price and variance paths from standard normals z of shape (3, n - 1), the rows drive
+  /// dW, the exactly integrated kernel over the last step and dW⊥
+  fn path_from_normals(&self, z: ArrayView2<f64>) -> [Array1<f64>; 2] {
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let alpha = self.hurst - 0.5;
+    let scale = (2.0 * self.hurst).sqrt();
+    let weights = self.kernel_weights(dt);
+
+    // (dW, int_{t(i-1)}^{t(i)} (t(i) - s)^alpha dW(s)) is Gaussian with
+    // Cov = dt^(alpha + 1) / (alpha + 1) and Var = dt^(2 alpha + 1) / (2 alpha + 1)
+    let dw = z.row(0).mapv(|z| dt.sqrt() * z);
+    let c1 = dt.powf(alpha + 0.5) / (alpha + 1.0);
+    let c2 =
+      dt.powf(alpha + 0.5) * (1.0 / (2.0 * alpha + 1.0) - 1.0 / (alpha + 1.0).powi(2)).sqrt();
+    let last_step = &z.row(0) * c1 + &z.row(1) * c2;
+
+    let mut s = Array1::<f64>::zeros(self.n);
+    let mut v = Array1::<f64>::zeros(self.n);
+    s[0] = self.s0.unwrap_or(100.0);
+    v[0] = (self.xi0)(0.0);
+
+    let mut log_s = s[0].ln();
+    // Variance of the scheme, dt^(2 alpha + 1) / (2 alpha + 1) + sum of the squared weights dt
+    let mut variance = dt.powf(2.0 * alpha + 1.0) / (2.0 * alpha + 1.0);
+
+    for i in 1..self.n {
+      let db = self.rho * dw[i - 1] + (1.0 - self.rho.powi(2)).sqrt() * dt.sqrt() * z[[2, i - 1]];
+      log_s += (self.r - 0.5 * v[i - 1]) * dt + v[i - 1].sqrt() * db;
+      s[i] = log_s.exp();
+
+      let mut y = last_step[i - 1];
+      for k in 2..=i {
+        y += weights[k] * dw[i - k];
+      }
+      if i > 1 {
+        variance += weights[i].powi(2) * dt;
+      }
+
+      v[i] = (self.xi0)(i as f64 * dt)
+        * (self.eta * scale * y - 0.5 * self.eta.powi(2) * scale.powi(2) * variance).exp();
+    }
+
+    [s, v]
+  }
+}
+
+impl<X: Fn(f64) -> f64 + Send + Sync> Sampling2D<f64> for RBergomi<X> {
+  fn sample(&self) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+
+    let z = Array2::from_shape_vec(
+      (3, self.n - 1),
+      f64::normal_array(3 * (self.n - 1), 0.0, 1.0).to_vec(),
+    )
+    .unwrap();
+
+    self.path_from_normals(z.view())
+  }
+
+  /// Check the Hurst index and the correlation
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.hurst > 0.0 && self.hurst < 1.0 && self.hurst != 0.5,
+      "Hurst index must be in (0, 1/2) or (1/2, 1)",
+    )?;
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<X: Fn(f64) -> f64 + Send + Sync> GaussianDriven<f64> for RBergomi<X> {
+  type Path = [Array1<f64>; 2];
+
+  fn drivers(&self) -> usize {
+    3
+  }
+
+  fn increments(&self) -> usize {
+    self.n - 1
+  }
+
+  fn sample_from_increments(&self, z: ArrayView2<f64>) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+    self.path_from_normals(z)
+  }
+}
+
+impl<X> ProcessInfo for RBergomi<X> {
+  const INFO: ModelInfo = ModelInfo {
+    name: "RBergomi",
+    title: "Rough Bergomi model with the hybrid scheme",
+    path: "stochastic::volatility::rbergomi::RBergomi",
+    kind: ModelKind::Volatility,
+    description: "dS(t) = r S(t) dt + sqrt(v(t)) S(t) dB(t), v(t) = xi0(t) exp(eta sqrt(2H) int_0^t (t - s)^(H - 1/2) dW(s) - eta^2 t^2H / 2), d<B, W>(t) = rho dt",
+    parameters: &[
+      ParameterInfo::real("eta", "Volatility of the variance", Interval::POSITIVE, 1.9),
+      ParameterInfo::HURST.example(0.1),
+      ParameterInfo::RHO.example(-0.9),
+      ParameterInfo::function("xi0", "Initial forward variance curve xi0(t)"),
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("r", "Risk-free rate", Interval::REAL, 0.05),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Bayer, C., Friz, P., & Gatheral, J. (2016). Pricing under rough volatility.",
+      "Bennedsen, M., Lunde, A., & Pakkanen, M. S. (2017). Hybrid scheme for Brownian semistationary processes.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rbergomi(n: usize) -> RBergomi {
+    RBergomi::new(
+      1.9,
+      0.1,
+      -0.9,
+      |t| 0.04 + 0.02 * t,
+      Some(100.0),
+      0.03,
+      n,
+      Some(1.0),
+      None,
+    )
+  }
+
+  #[test]
+  fn rbergomi_hybrid_variance_matches_kernel() {
+    // Var(Y(t)) of the scheme converges to t^2H
+    let process = rbergomi(257);
+    let dt: f64 = 1.0 / 256.0;
+    let alpha = process.hurst - 0.5;
+    let variance = 2.0
+      * process.hurst
+      * (dt.powf(2.0 * alpha + 1.0) / (2.0 * alpha + 1.0)
+        + process.kernel_weights(dt).mapv(|w| w * w).sum() * dt);
+
+    assert!((variance - 1.0).abs() < 0.01, "{}", variance);
+  }
+
+  #[test]
+  fn rbergomi_moments() {
+    let process = rbergomi(101);
+    let paths = 20_000;
+    let (mut s, mut v) = (Array1::zeros(paths), Array1::zeros(paths));
+    for i in 0..paths {
+      let [sp, vp] = process.sample();
+      assert!(vp.iter().all(|v| *v > 0.0));
+      s[i] = sp[100];
+      v[i] = vp[100];
+    }
+
+    // E[v(T)] = xi0(T) and e^(-rT) S is a martingale
+    let v_error = (v.var(1.0) / paths as f64).sqrt();
+    assert!((v.mean().unwrap() - 0.06).abs() < 4.0 * v_error);
+    let s_error = (s.var(1.0) / paths as f64).sqrt();
+    assert!((s.mean().unwrap() - 100.0 * 0.03f64.exp()).abs() < 4.0 * s_error);
+  }
+}
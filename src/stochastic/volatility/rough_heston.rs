@@ -0,0 +1,247 @@
+use impl_new_derive::ImplNew;
+use ndarray::{Array1, Array2, ArrayView2};
+use statrs::function::gamma::gamma;
+
+use crate::{
+  error::{ensure, or_panic, StochasticResult},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    variance_reduction::GaussianDriven,
+    FloatExt, Sampling2D,
+  },
+};
+
+/// Rough Heston model (El Euch & Rosenbaum, 2019)
+///
+/// dS(t) = r S(t) dt + sqrt(v(t)) S(t) (rho dW(t) + sqrt(1 - rho^2) dW⊥(t))
+///
+/// v(t) = v0 + int_0^t K(t - s) (kappa (theta - v(s)) ds + nu sqrt(v(s)) dW(s)), K(t) = t^(H - 1/2) / Gamma(H + 1/2)
+///
+/// The Volterra equation is discretized with the coefficients frozen over every step: the
+/// drift is weighted by the integral of the kernel over the step and the Brownian increment
+/// by the L2 norm of the kernel over the step, so the scheme is the Euler scheme of the
+/// Heston model for H = 1/2. The coefficients use the positive part of the variance. The
+/// characteristic function of the price is computed by
+/// [`RoughHestonPricer`](crate::quant::pricing::rough_heston::RoughHestonPricer).
+#[derive(ImplNew, Clone)]
+pub struct RoughHeston {
+  /// Hurst index of the variance, in (0, 1/2]
+  pub hurst: f64,
+  /// Initial variance
+  pub v0: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of the variance
+  pub nu: f64,
+  /// Correlation between the price and the variance
+  pub rho: f64,
+  /// Initial price
+  pub s0: Option<f64>,
+  /// Drift of the price
+  pub r: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Time to maturity
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl RoughHeston {
+  /// Weights of the drift and of the Brownian increments k steps back, k >= 1
+  ///
+  /// int_{t(k-1)}^{t(k)} K(s) ds and (int_{t(k-1)}^{t(k)} K(s)^2 ds / dt)^(1/2)
+  fn kernel_weights(&self, dt: f64) -> (Array1<f64>, Array1<f64>) {
+    let alpha = self.hurst + 0.5;
+    let drift = Array1::from_shape_fn(self.n, |k| match k {
+      0 => 0.0,
+      _ => {
+        let k = k as f64;
+        dt.powf(alpha) * (k.powf(alpha) - (k - 1.0).powf(alpha)) / gamma(alpha + 1.0)
+      }
+    });
+    let diffusion = Array1::from_shape_fn(self.n, |k| match k {
+      0 => 0.0,
+      _ => {
+        let (k, h2) = (k as f64, 2.0 * self.hurst);
+        (dt.powf(h2 - 1.0) * (k.powf(h2) - (k - 1.0).powf(h2)) / h2).sqrt() / gamma(alpha)
+      }
+    });
+
+    (drift, diffusion)
+  }
+
+  /// Price and variance paths from standard normals z of shape (2, n - 1), the rows drive
+  /// dW and dW⊥
+  fn path_from_normals(&self, z: ArrayView2<f64>) -> [Array1<f64>; 2] {
+    let dt = self.t.unwrap_or(1.0) / (self.n - 1) as f64;
+    let (drift, diffusion) = self.kernel_weights(dt);
+
+    let mut s = Array1::<f64>::zeros(self.n);
+    let mut v = Array1::<f64>::zeros(self.n);
+    s[0] = self.s0.unwrap_or(100.0);
+    v[0] = self.v0;
+
+    // Increments kappa (theta - v) and nu sqrt(v) dW of the earlier steps
+    let mut drifts = Vec::with_capacity(self.n - 1);
+    let mut shocks = Vec::with_capacity(self.n - 1);
+    let mut log_s = s[0].ln();
+
+    for i in 1..self.n {
+      let dw = dt.sqrt() * z[[0, i - 1]];
+      let db = self.rho * dw + (1.0 - self.rho.powi(2)).sqrt() * dt.sqrt() * z[[1, i - 1]];
+      log_s += (self.r - 0.5 * v[i - 1]) * dt + v[i - 1].sqrt() * db;
+      s[i] = log_s.exp();
+
+      drifts.push(self.kappa * (self.theta - v[i - 1]));
+      shocks.push(self.nu * v[i - 1].sqrt() * z[[0, i - 1]]);
+
+      let mut vi = self.v0;
+      for j in 0..i {
+        vi += drift[i - j] * drifts[j] + diffusion[i - j] * dt.sqrt() * shocks[j];
+      }
+      v[i] = vi.max(0.0);
+    }
+
+    [s, v]
+  }
+}
+
+impl Sampling2D<f64> for RoughHeston {
+  fn sample(&self) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+
+    let z = Array2::from_shape_vec(
+      (2, self.n - 1),
+      f64::normal_array(2 * (self.n - 1), 0.0, 1.0).to_vec(),
+    )
+    .unwrap();
+
+    self.path_from_normals(z.view())
+  }
+
+  /// Check the Hurst index, the correlation and the initial variance
+  fn validate(&self) -> StochasticResult<()> {
+    ensure(
+      self.hurst > 0.0 && self.hurst <= 0.5,
+      "Hurst index must be in (0, 1/2]",
+    )?;
+    ensure(
+      (-1.0..=1.0).contains(&self.rho),
+      "Correlation coefficient must be in [-1, 1]",
+    )?;
+    ensure(self.v0 >= 0.0, "v0 must be non-negative")
+  }
+
+  /// Number of time steps
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Number of samples for parallel sampling
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl GaussianDriven<f64> for RoughHeston {
+  type Path = [Array1<f64>; 2];
+
+  fn drivers(&self) -> usize {
+    2
+  }
+
+  fn increments(&self) -> usize {
+    self.n - 1
+  }
+
+  fn sample_from_increments(&self, z: ArrayView2<f64>) -> [Array1<f64>; 2] {
+    or_panic(self.validate());
+    self.path_from_normals(z)
+  }
+}
+
+impl ProcessInfo for RoughHeston {
+  const INFO: ModelInfo = ModelInfo {
+    name: "RoughHeston",
+    title: "Rough Heston model",
+    path: "stochastic::volatility::rough_heston::RoughHeston",
+    kind: ModelKind::Volatility,
+    description: "dS(t) = r S(t) dt + sqrt(v(t)) S(t) dB(t), v(t) = v0 + int_0^t (t - s)^(H - 1/2) / Gamma(H + 1/2) (kappa (theta - v(s)) ds + nu sqrt(v(s)) dW(s)), d<B, W>(t) = rho dt",
+    parameters: &[
+      ParameterInfo::HURST.example(0.1),
+      ParameterInfo::real("v0", "Initial variance", Interval::NON_NEGATIVE, 0.04),
+      ParameterInfo::real("kappa", "Mean reversion rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("theta", "Long-run variance", Interval::POSITIVE, 0.04),
+      ParameterInfo::real("nu", "Volatility of the variance", Interval::POSITIVE, 0.3),
+      ParameterInfo::RHO.example(-0.7),
+      ParameterInfo::real("s0", "Initial price", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("r", "Risk-free rate", Interval::REAL, 0.05),
+      ParameterInfo::N,
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "El Euch, O., & Rosenbaum, M. (2019). The characteristic function of rough Heston models.",
+    ],
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rough_heston_kernel_weights_are_heston_at_half() {
+    let process = RoughHeston::new(
+      0.5,
+      0.04,
+      2.0,
+      0.04,
+      0.3,
+      -0.7,
+      None,
+      0.0,
+      11,
+      Some(1.0),
+      None,
+    );
+    let (drift, diffusion) = process.kernel_weights(0.1);
+
+    assert!(drift.iter().skip(1).all(|w| (w - 0.1).abs() < 1e-12));
+    assert!(diffusion.iter().skip(1).all(|w| (w - 1.0).abs() < 1e-12));
+  }
+
+  #[test]
+  fn rough_heston_moments() {
+    let process = RoughHeston::new(
+      0.1,
+      0.04,
+      1.0,
+      0.06,
+      0.2,
+      -0.7,
+      Some(100.0),
+      0.03,
+      101,
+      Some(1.0),
+      None,
+    );
+    let paths = 10_000;
+    let (mut s, mut v) = (Array1::zeros(paths), Array1::zeros(paths));
+    for i in 0..paths {
+      let [sp, vp] = process.sample();
+      s[i] = sp[100];
+      v[i] = vp[100];
+    }
+
+    // E[v(t)] = theta + (v0 - theta) E_a(-kappa t^a) solves the mean equation, bounded by
+    // v0 and theta, and e^(-rT) S is a martingale
+    let v_mean = v.mean().unwrap();
+    assert!(v_mean > 0.04 && v_mean < 0.06, "{}", v_mean);
+    let s_error = (s.var(1.0) / paths as f64).sqrt();
+    assert!((s.mean().unwrap() - 100.0 * 0.03f64.exp()).abs() < 4.0 * s_error);
+  }
+}
@@ -4,7 +4,11 @@ use std::sync::Mutex;
 use impl_new_derive::ImplNew;
 use ndarray::Array1;
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use crate::stochastic::{
+  catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+  noise::cgns::CGNS,
+  Sampling2D,
+};
 
 #[derive(ImplNew)]
 
@@ -26,6 +30,29 @@ pub struct SABR {
   malliavin_of_price: Mutex<Option<Array1<f64>>>,
 }
 
+impl Clone for SABR {
+  /// Copy of the parameters with empty Malliavin derivatives
+  fn clone(&self) -> Self {
+    Self {
+      alpha: self.alpha,
+      beta: self.beta,
+      rho: self.rho,
+      n: self.n,
+      f0: self.f0,
+      v0: self.v0,
+      t: self.t,
+      m: self.m,
+      cgns: self.cgns.clone(),
+      #[cfg(feature = "malliavin")]
+      calculate_malliavin: self.calculate_malliavin,
+      #[cfg(feature = "malliavin")]
+      malliavin_of_vol: Mutex::new(None),
+      #[cfg(feature = "malliavin")]
+      malliavin_of_price: Mutex::new(None),
+    }
+  }
+}
+
 impl Sampling2D<f64> for SABR {
   fn sample(&self) -> [Array1<f64>; 2] {
     let [cgn1, cgn2] = self.cgns.sample();
@@ -50,10 +77,7 @@ impl Sampling2D<f64> for SABR {
         malliavin_of_vol[i] = self.alpha * v.last().unwrap();
       }
 
-      let _ = std::mem::replace(
-        &mut *self.malliavin_of_vol.lock().unwrap(),
-        Some(malliavin_of_vol),
-      );
+      *self.malliavin_of_vol.lock().unwrap() = Some(malliavin_of_vol);
     }
 
     [f, v]
@@ -88,8 +112,36 @@ impl Sampling2D<f64> for SABR {
   }
 }
 
-#[cfg(test)]
+impl ProcessInfo for SABR {
+  const INFO: ModelInfo = ModelInfo {
+    name: "SABR",
+    title: "SABR model",
+    path: "stochastic::volatility::sabr::SABR",
+    kind: ModelKind::Volatility,
+    description: "dF(t) = v(t) F(t)^beta dW1(t), dv(t) = alpha v(t) dW2(t)",
+    parameters: &[
+      ParameterInfo::real(
+        "alpha",
+        "Volatility of the volatility",
+        Interval::POSITIVE,
+        0.4,
+      ),
+      ParameterInfo::real("beta", "Elasticity", Interval::closed(0.0, 1.0), 0.5),
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::real("f0", "Initial forward", Interval::POSITIVE, 100.0).optional(),
+      ParameterInfo::real("v0", "Initial volatility", Interval::POSITIVE, 0.2).optional(),
+      ParameterInfo::T,
+      ParameterInfo::M,
+      ParameterInfo::component("cgns", "Correlated Gaussian noise generator"),
+    ],
+    references: &[
+      "Hagan, P. S., Kumar, D., Lesniewski, A. S., & Woodward, D. E. (2002). Managing smile risk.",
+    ],
+  };
+}
 
+#[cfg(test)]
 mod tests {
   #[cfg(feature = "malliavin")]
   use crate::{plot_2d, stochastic::N};
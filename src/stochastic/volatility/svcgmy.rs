@@ -7,13 +7,17 @@ use scilib::math::basic::gamma;
 
 use crate::{
   stats::non_central_chi_squared,
-  stochastic::{process::poisson::Poisson, Sampling},
+  stochastic::{
+    catalog::{Interval, ModelInfo, ModelKind, ParameterInfo, ProcessInfo},
+    process::poisson::Poisson,
+    Sampling,
+  },
 };
 
 /// CGMY Stochastic Volatility process
 ///
 /// https://www.econstor.eu/bitstream/10419/239493/1/175133161X.pdf
-#[derive(ImplNew)]
+#[derive(ImplNew, Clone)]
 pub struct SVCGMY {
   /// Positive jump rate lambda_plus (corresponds to G)
   pub lambda_plus: f64, // G
@@ -27,7 +31,7 @@ pub struct SVCGMY {
   pub eta: f64,
   /// Volatility of volatility
   pub zeta: f64,
-  ///
+  /// Leverage, the weight of the variance in the log-price
   pub rho: f64,
   /// Number of time steps
   pub n: usize,
@@ -39,12 +43,28 @@ pub struct SVCGMY {
   pub v0: Option<f64>,
   /// Total time horizon
   pub t: Option<f64>,
-  /// Number of samples for parallel sampling (not used in this implementation)
+  /// Number of samples for parallel sampling
   pub m: Option<usize>,
 }
 
 impl Sampling<f64> for SVCGMY {
   fn sample(&self) -> Array1<f64> {
+    let [x, _, _] = crate::stochastic::SamplingND::sample(self);
+    x
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Log price x together with the auxiliary variance v and pure jump y components
+impl crate::stochastic::SamplingND<f64, 3> for SVCGMY {
+  fn sample(&self) -> [Array1<f64>; 3] {
     let mut rng = rand::thread_rng();
 
     let t_max = self.t.unwrap_or(1.0);
@@ -118,7 +138,7 @@ impl Sampling<f64> for SVCGMY {
       x[i] = y[i] + self.rho * v[i];
     }
 
-    x
+    [x, v, y]
   }
 
   fn n(&self) -> usize {
@@ -130,6 +150,35 @@ impl Sampling<f64> for SVCGMY {
   }
 }
 
+impl ProcessInfo for SVCGMY {
+  const INFO: ModelInfo = ModelInfo {
+    name: "SVCGMY",
+    title: "CGMY process with stochastic volatility",
+    path: "stochastic::volatility::svcgmy::SVCGMY",
+    kind: ModelKind::Volatility,
+    description: "CGMY process time changed by the integrated CIR activity rate dv(t) = kappa (eta - v(t)) dt + zeta sqrt(v(t)) dW(t)",
+    parameters: &[
+      ParameterInfo::real("lambda_plus", "Tempering of the negative jumps (G)", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("lambda_minus", "Tempering of the positive jumps (M)", Interval::POSITIVE, 5.0),
+      ParameterInfo::real("alpha", "Stability index (Y)", Interval::open(0.0, 2.0), 0.7),
+      ParameterInfo::real("kappa", "Mean reversion rate of the activity rate", Interval::POSITIVE, 2.0),
+      ParameterInfo::real("eta", "Long-run activity rate", Interval::POSITIVE, 1.0),
+      ParameterInfo::real("zeta", "Volatility of the activity rate", Interval::POSITIVE, 0.3),
+      ParameterInfo::RHO,
+      ParameterInfo::N,
+      ParameterInfo::integer("j", "Number of terms of the series representation", Interval::COUNT, 1000.0),
+      ParameterInfo::X0,
+      ParameterInfo::real("v0", "Initial activity rate", Interval::NON_NEGATIVE, 1.0).optional(),
+      ParameterInfo::T,
+      ParameterInfo::M,
+    ],
+    references: &[
+      "Carr, P., Geman, H., Madan, D. B., & Yor, M. (2003). Stochastic volatility for Levy processes.",
+      "Poirot, J., & Tankov, P. (2006). Monte Carlo option pricing for tempered stable (CGMY) processes.",
+    ],
+  };
+}
+
 #[cfg(test)]
 mod tests {
   use ndarray::Axis;
@@ -216,4 +265,33 @@ mod tests {
     );
     plot_nd!(svcgmy.sample_par(), "SVCGMY Process");
   }
+
+  #[test]
+  fn svcgmy_sample_par_components() {
+    let svcgmy = SVCGMY::new(
+      25.46,
+      4.604,
+      0.52,
+      1.003,
+      0.0711,
+      0.3443,
+      -2.0280,
+      N,
+      1024,
+      None,
+      Some(0.0064),
+      Some(1.0),
+      Some(10),
+    );
+    let [x, v, y] = crate::stochastic::SamplingND::sample_par(&svcgmy);
+
+    assert_eq!(x.dim(), (10, N));
+    assert!(v.iter().all(|&v| v >= 0.0));
+    let rebuilt = &y.slice(ndarray::s![.., 1..]) + &(svcgmy.rho * &v.slice(ndarray::s![.., 1..]));
+    assert!(x
+      .slice(ndarray::s![.., 1..])
+      .iter()
+      .zip(rebuilt.iter())
+      .all(|(a, b)| (a - b).abs() < 1e-12));
+  }
 }
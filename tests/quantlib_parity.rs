@@ -0,0 +1,212 @@
+//! Parity tests against QuantLib reference values.
+//!
+//! The reference prices and Greeks are the ones used by the QuantLib test suite
+//! (`europeanoption.cpp`, `hestonmodel.cpp`, `barrieroption.cpp`) which in turn are taken
+//! from
+//!
+//! - Haug, E. G. (2007). The Complete Guide to Option Pricing Formulas.
+//! - Beaglehole, D. R., Dybvig, P. H., & Zhou, G. (1997). Going to extremes: correcting
+//!   simulation bias in exotic option valuation.
+//! - Fang, F., & Oosterlee, C. W. (2008). A novel pricing method for European options based on
+//!   Fourier-cosine series expansions.
+//! - Longstaff, F. A., & Schwartz, E. S. (2001). Valuing American options by simulation.
+//!
+//! Run with `cargo test --features quantlib-parity --test quantlib_parity`.
+
+#![cfg(feature = "quantlib-parity")]
+
+use stochastic_rs::{
+  quant::{
+    pricing::{
+      bsm::{BSMCoc, BSMPricer},
+      exotic::{BarrierKind, BarrierOption, ExoticMCPricer},
+      finitie_difference::{FiniteDifferenceMethod, FiniteDifferencePricer},
+      heston::HestonPricer,
+    },
+    r#trait::Pricer,
+    OptionStyle, OptionType,
+  },
+  stochastic::diffusion::{gbm::GBM, scheme::Scheme},
+};
+
+/// Generalized Black-Scholes pricer with cost of carry r - q
+fn bsm(s: f64, k: f64, q: f64, r: f64, tau: f64, v: f64, option_type: OptionType) -> BSMPricer {
+  BSMPricer::new(
+    s,
+    v,
+    k,
+    r,
+    None,
+    None,
+    Some(q),
+    Some(tau),
+    None,
+    None,
+    option_type,
+    BSMCoc::MERTON1973,
+  )
+}
+
+fn assert_close(name: &str, value: f64, expected: f64, tolerance: f64) {
+  assert!(
+    (value - expected).abs() <= tolerance,
+    "{}: calculated {}, QuantLib {}, tolerance {}",
+    name,
+    value,
+    expected,
+    tolerance
+  );
+}
+
+#[test]
+fn bsm_european_values() {
+  // (type, strike, spot, q, r, tau, vol, value)
+  let cases = [
+    (OptionType::Call, 65.0, 60.0, 0.00, 0.08, 0.25, 0.30, 2.1334),
+    (OptionType::Put, 95.0, 100.0, 0.05, 0.10, 0.50, 0.20, 2.4648),
+    (OptionType::Put, 19.0, 19.0, 0.10, 0.10, 0.75, 0.28, 1.7011),
+    (OptionType::Call, 19.0, 19.0, 0.10, 0.10, 0.75, 0.28, 1.7011),
+    (OptionType::Call, 1.60, 1.56, 0.08, 0.06, 0.50, 0.12, 0.0291),
+  ];
+
+  for (option_type, k, s, q, r, tau, v, expected) in cases {
+    let (call, put) = bsm(s, k, q, r, tau, v, option_type).calculate_call_put();
+    let value = match option_type {
+      OptionType::Call => call,
+      OptionType::Put => put,
+    };
+    assert_close("bsm value", value, expected, 1e-4);
+  }
+}
+
+#[test]
+fn bsm_european_greeks() {
+  let delta_call = bsm(105.0, 100.0, 0.10, 0.10, 0.5, 0.36, OptionType::Call).delta();
+  let delta_put = bsm(105.0, 100.0, 0.10, 0.10, 0.5, 0.36, OptionType::Put).delta();
+  let gamma = bsm(55.0, 60.0, 0.0, 0.10, 0.75, 0.30, OptionType::Call).gamma();
+  let theta = bsm(430.0, 405.0, 0.05, 0.07, 1.0 / 12.0, 0.20, OptionType::Put).theta();
+  let rho = bsm(72.0, 75.0, 0.0, 0.09, 1.0, 0.19, OptionType::Call).rho();
+
+  assert_close("delta call", delta_call, 0.5946, 1e-4);
+  assert_close("delta put", delta_put, -0.3566, 1e-4);
+  assert_close("gamma", gamma, 0.0278, 1e-4);
+  assert_close("theta", theta, -31.1924, 1e-4);
+  assert_close("rho", rho, 38.7325, 1e-4);
+}
+
+#[test]
+fn bsm_put_call_parity() {
+  let pricer = bsm(100.0, 95.0, 0.05, 0.10, 0.5, 0.20, OptionType::Call);
+  let (call, put) = pricer.calculate_call_put();
+
+  let forward = 100.0 * (-0.05_f64 * 0.5).exp() - 95.0 * (-0.10_f64 * 0.5).exp();
+  assert_close("put-call parity", call - put, forward, 1e-12);
+}
+
+#[test]
+fn heston_european_value() {
+  // Fang-Oosterlee reference case, AnalyticHestonEngine gives 5.785155450
+  let pricer = HestonPricer::new(
+    100.0,
+    0.0175,
+    100.0,
+    0.0,
+    None,
+    -0.5711,
+    1.5768,
+    0.0398,
+    0.5751,
+    Some(0.0),
+    Some(1.0),
+    None,
+    None,
+  );
+  let (call, put) = pricer.calculate_call_put();
+
+  assert_close("heston call", call, 5.785155450, 1e-6);
+  assert_close("heston put", put, 5.785155450, 1e-6);
+}
+
+#[test]
+fn american_put_values() {
+  // (spot, vol, tau, value) with strike 40 and r = 6%, FdBlackScholesVanillaEngine values
+  let cases = [
+    (36.0, 0.2, 1.0, 4.478),
+    (36.0, 0.4, 1.0, 7.101),
+    (40.0, 0.2, 1.0, 2.314),
+    (44.0, 0.2, 1.0, 1.110),
+  ];
+
+  for (s, v, tau, expected) in cases {
+    let pricer = FiniteDifferencePricer::new(
+      s,
+      v,
+      40.0,
+      0.06,
+      1000,
+      600,
+      Some(tau),
+      None,
+      None,
+      OptionStyle::American,
+      OptionType::Put,
+      FiniteDifferenceMethod::CrankNicolson,
+    );
+
+    assert_close("american put", pricer.calculate_price(), expected, 1e-2);
+  }
+}
+
+#[test]
+fn barrier_option_values() {
+  // Beaglehole-Dybvig-Zhou case of barrieroption.cpp: continuously monitored down-and-out
+  // call with spot and strike 50, barrier 45, r = ln(1.1), q = 0, vol 0.5 and one year
+  let (r, v) = (1.1_f64.ln(), 0.5);
+  let pricer = ExoticMCPricer::new(
+    GBM::new(
+      r,
+      v,
+      101,
+      Some(50.0),
+      Some(1.0),
+      Some(Scheme::Milstein),
+      None,
+      None,
+      #[cfg(feature = "malliavin")]
+      None,
+    ),
+    r,
+    1.0,
+    200_000,
+  );
+  let out = BarrierOption::new(
+    BarrierKind::DownAndOut,
+    45.0,
+    50.0,
+    OptionType::Call,
+    Some(v),
+  );
+  let into = BarrierOption::new(
+    BarrierKind::DownAndIn,
+    45.0,
+    50.0,
+    OptionType::Call,
+    Some(v),
+  );
+  let prices = pricer.price_all(&[&out, &into]);
+
+  assert_close(
+    "down-and-out call",
+    prices[0].mean,
+    5.477,
+    4.0 * prices[0].std_error + 1e-2,
+  );
+  // The in-out parity against the vanilla of the same parameters
+  let (call, _) = bsm(50.0, 50.0, 0.0, r, 1.0, v, OptionType::Call).calculate_call_put();
+  assert_close(
+    "in-out parity",
+    prices[0].mean + prices[1].mean,
+    call,
+    4.0 * (prices[0].std_error + prices[1].std_error) + 1e-2,
+  );
+}